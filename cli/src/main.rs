@@ -2,9 +2,14 @@
 
 use anyhow::{Context, Result};
 use clap::{Parser as ClapParser, Subcommand};
-use parser::{parse, read};
+use codegen::diagnostic::Phase;
+use parser::options::{ParseOptions, Warning};
+use parser::read;
+use parser::source::SpecSource;
 use serde_json::Value;
-use std::path::PathBuf;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
 
 #[derive(ClapParser, Debug)]
 #[command(name = "oas-gen")]
@@ -18,45 +23,103 @@ struct Cli {
 enum Commands {
     /// Generate SDK or server code from an OpenAPI specification
     Generate {
+        #[command(flatten)]
+        args: GenerateArgs,
+
+        /// Print a unified diff of what would change in the output directory instead of writing it
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Fail if the output directory differs from the generated code, without writing it
+        #[arg(long, conflicts_with = "dry_run")]
+        check: bool,
+
+        /// Compile the written output with the generator's checker (`tsc --noEmit`,
+        /// `cargo check`) and fail on errors, or when the checker isn't installed
+        #[arg(long)]
+        verify: bool,
+
+        /// Print every generated file to stdout after a `==> path <==` line instead of writing
+        /// the output directory
+        #[arg(long, conflicts_with_all = ["dry_run", "check", "verify"])]
+        stdout: bool,
+
+        /// Print the output merged into one source file to stdout, for generators that
+        /// support it (rust-axum)
+        #[arg(long, conflicts_with_all = ["dry_run", "check", "verify", "stdout"])]
+        single_file: bool,
+    },
+
+    /// Show what `generate` would change in the output directory, as a unified diff
+    Diff {
+        #[command(flatten)]
+        args: GenerateArgs,
+    },
+
+    /// List the generators that can be passed to `--lang`
+    ListGenerators {
+        /// Print name, version, description and options as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Also list each generator's options
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
+    /// Resolve external $ref references and output a single combined spec
+    Resolve {
         /// Path to the OpenAPI specification file (JSON or YAML)
         #[arg(value_name = "SPEC")]
         spec: PathBuf,
 
-        /// Template to use for code generation (e.g., "typescript", "rust-axum")
-        #[arg(short, long, value_name = "TEMPLATE")]
-        template: String,
-
-        /// Output directory for generated code
-        #[arg(short, long, value_name = "DIR")]
+        /// Output file for resolved spec (defaults to stdout)
+        #[arg(short, long, value_name = "FILE")]
         output: Option<PathBuf>,
 
-        /// Service organization style
-        #[arg(long, value_enum, default_value = "per-service")]
-        service_style: ServiceStyleArg,
+        /// Verbose output
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
+    /// Bundle a multi-file specification into one fully dereferenced document
+    Bundle {
+        /// Path to the root OpenAPI specification file (JSON or YAML)
+        #[arg(value_name = "SPEC")]
+        spec: PathBuf,
 
-        /// Don't include documentation comments
-        #[arg(long)]
-        no_docs: bool,
+        /// Output file (defaults to stdout)
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
 
-        /// Resolve external references before generating
-        #[arg(short = 'r', long)]
-        resolve: bool,
+        /// Output format (defaults to the extension of the output file, or of SPEC)
+        #[arg(long, value_enum)]
+        format: Option<SpecFormatArg>,
 
         /// Verbose output
         #[arg(short, long)]
         verbose: bool,
     },
 
-    /// Resolve external $ref references and output a single combined spec
-    Resolve {
+    /// Rewrite a specification with canonical key ordering
+    Fmt {
         /// Path to the OpenAPI specification file (JSON or YAML)
         #[arg(value_name = "SPEC")]
         spec: PathBuf,
 
-        /// Output file for resolved spec (defaults to stdout)
+        /// Output file (defaults to rewriting SPEC in place)
         #[arg(short, long, value_name = "FILE")]
         output: Option<PathBuf>,
 
+        /// Don't write anything; exit with an error if SPEC is not formatted
+        #[arg(long)]
+        check: bool,
+
+        /// Rewrite SPEC in place even if it has YAML comments or anchors, which
+        /// formatting drops
+        #[arg(long, conflicts_with_all = ["output", "check"])]
+        write: bool,
+
         /// Verbose output
         #[arg(short, long)]
         verbose: bool,
@@ -68,7 +131,7 @@ enum Commands {
         #[arg(value_name = "SPEC")]
         spec: PathBuf,
 
-        /// Comma-separated list of rulesets to enable (e.g., "recommended,strict")
+        /// Comma-separated list of rulesets to enable (e.g., "recommended,strict" or "core,compat"). Defaults to "core"
         #[arg(long, value_name = "RULESETS")]
         rulesets: Option<String>,
 
@@ -79,6 +142,48 @@ enum Commands {
         /// Filter by severity levels (e.g., "error,warning"). Available: error, warning, info, hint
         #[arg(long, value_name = "SEVERITY")]
         severity: Option<String>,
+
+        /// Only run rules in these categories (e.g., "security,correctness"). Available: correctness, security, style, documentation, compatibility
+        #[arg(long, value_name = "CATEGORIES")]
+        only: Option<String>,
+
+        /// Override severity per category (e.g., "security:error,style:off")
+        #[arg(long, value_name = "OVERRIDES")]
+        category_severity: Option<String>,
+
+        /// Also run the rules of a Spectral ruleset (e.g., ".spectral.yaml")
+        #[arg(long, value_name = "FILE")]
+        spectral: Option<PathBuf>,
+
+        /// Lint config file. Defaults to the nearest oas-lint.toml above the spec
+        #[arg(long, value_name = "FILE")]
+        config: Option<PathBuf>,
+
+        /// Print the effective lint configuration as TOML instead of linting
+        #[arg(long)]
+        print_config: bool,
+
+        /// List the mechanical fixes (missing operationIds, default tags,
+        /// status code keys) that apply to the spec
+        #[arg(long)]
+        fix: bool,
+
+        /// With --fix, rewrite the spec file in place before reporting what's
+        /// left; only the fixed nodes change
+        #[arg(long, requires = "fix")]
+        write: bool,
+
+        /// Previous version of the spec; also report breaking changes since it
+        #[arg(long, value_name = "FILE")]
+        baseline: Option<PathBuf>,
+
+        /// Print how many findings each rule had suppressed by x-lint-ignore
+        #[arg(long)]
+        report_suppressed: bool,
+
+        /// Output format: human-readable diagnostics, JSON, or SARIF 2.1.0 for code-scanning UIs
+        #[arg(long, value_enum, default_value = "pretty")]
+        format: LintFormatArg,
     },
 
     /// Deep merge multiple JSON or YAML files
@@ -97,6 +202,253 @@ enum Commands {
     },
 }
 
+/// Where to read the spec, how to generate and where to write, shared by
+/// `generate` and `diff`
+#[derive(Debug, Clone, clap::Args)]
+struct GenerateArgs {
+    /// Path to the OpenAPI specification file (JSON or YAML), `-` for stdin,
+    /// an http(s) URL, or a directory of spec snapshots to generate one
+    /// versioned package per file. Without one, the `input` of the project file
+    #[arg(value_name = "SPEC", conflicts_with = "spec_flag")]
+    spec: Option<PathBuf>,
+
+    /// The specification, as a flag instead of an argument
+    #[arg(long = "spec", value_name = "SPEC")]
+    spec_flag: Option<PathBuf>,
+
+    /// Template to use for code generation (e.g., "typescript", "rust-axum", or an alias
+    /// like "ts"). Without one, generates the `targets` of the oas-gen.toml in the current
+    /// directory or its closest ancestor
+    #[arg(short, long, visible_alias = "lang", value_name = "TEMPLATE")]
+    template: Option<String>,
+
+    /// Output directory for generated code
+    #[arg(short, long, visible_alias = "out", value_name = "DIR")]
+    output: Option<PathBuf>,
+
+    /// Profile of the project file to generate (e.g. `internal`), instead of
+    /// the one its `profile` key names
+    #[arg(long, value_name = "NAME", conflicts_with = "template")]
+    profile: Option<String>,
+
+    /// Service organization style (per-service unless the project file sets one)
+    #[arg(long, value_enum)]
+    service_style: Option<ServiceStyleArg>,
+
+    /// Don't include documentation comments
+    #[arg(long)]
+    no_docs: bool,
+
+    /// Write docs in this locale (e.g. `de`, `pt-BR`) where the spec has
+    /// `x-descriptions` translations
+    #[arg(long, value_name = "LOCALE")]
+    locale: Option<String>,
+
+    /// Also write nullability-report.md, listing fields whose nullable and
+    /// required settings are ambiguous
+    #[arg(long)]
+    nullability_report: bool,
+
+    /// Start each generated file with a do-not-edit comment naming the generator
+    /// version and the spec hash
+    #[arg(long)]
+    header: bool,
+
+    /// Directory of templates replacing the generator's built-in ones of the same name
+    #[arg(long, value_name = "DIR")]
+    templates: Option<PathBuf>,
+
+    /// Apply this OpenAPI Overlay (YAML or JSON) to the spec first; repeat to apply several in order
+    #[arg(long = "overlay", value_name = "FILE")]
+    overlays: Vec<PathBuf>,
+
+    #[command(flatten)]
+    refs: RefArgs,
+
+    #[command(flatten)]
+    filter: FilterArgs,
+
+    /// Fail when the spec breaks the OpenAPI schema (unknown fields, missing required fields, ...)
+    /// or when a schema can only be generated as an untyped value
+    #[arg(long)]
+    strict: bool,
+
+    /// Repair common mistakes (missing responses, `required: "true"`, numeric versions, ...) with a warning instead of failing
+    #[arg(long, conflicts_with = "strict")]
+    lenient: bool,
+
+    /// Lint the spec first (with the nearest oas-lint.toml, or the core rules) and stop on findings at or above this severity
+    #[arg(long, value_enum, value_name = "LEVEL")]
+    lint: Option<LintThresholdArg>,
+
+    #[command(flatten)]
+    timing: TimingArgs,
+
+    #[command(flatten)]
+    post_write: PostWriteArgs,
+
+    /// Generate even when the spec, generator and options are unchanged since the last run
+    #[arg(long)]
+    force: bool,
+
+    /// Verbose output
+    #[arg(short, long)]
+    verbose: bool,
+}
+
+impl GenerateArgs {
+    fn run(mut self, output_check: OutputCheck, verify: bool) -> Result<()> {
+        let spec = self.spec_flag.take().or(self.spec.take());
+        let Some(template) = self.template.take() else {
+            if self.output.is_some() {
+                anyhow::bail!(
+                    "--output needs --template; set the outputs of project targets in {}",
+                    codegen::ProjectConfig::FILE_NAME
+                );
+            }
+            return handle_generate_project(self, spec, output_check, verify);
+        };
+        let spec = spec.context("No spec given; pass it as an argument or with --spec")?;
+        handle_generate(self, spec, template, output_check, verify)
+    }
+}
+
+/// How post-write hooks run when a spec directory generates several packages
+#[derive(Debug, Clone, clap::Args)]
+struct PostWriteArgs {
+    /// Run the post-write hooks (npm install, formatters, ...) of at most N packages at once;
+    /// defaults to 1
+    #[arg(long, value_name = "N")]
+    jobs: Option<std::num::NonZeroUsize>,
+
+    /// Keep running the post-write hooks of the other packages after one fails
+    #[arg(long)]
+    keep_going: bool,
+}
+
+impl PostWriteArgs {
+    fn options(&self) -> generate::AfterWriteOptions {
+        let defaults = generate::AfterWriteOptions::default();
+        generate::AfterWriteOptions {
+            concurrency: self.jobs.map_or(defaults.concurrency, |jobs| jobs.get()),
+            continue_on_error: self.keep_going,
+        }
+    }
+}
+
+/// Where to report how long each phase took
+#[derive(Debug, Clone, clap::Args)]
+struct TimingArgs {
+    /// Print how long each phase (parse, build_ir, validate, generate, write, ...) took
+    #[arg(long)]
+    timings: bool,
+
+    /// Write the phase timings and counts to FILE as JSON, e.g. for CI
+    #[arg(long, value_name = "FILE")]
+    report: Option<PathBuf>,
+}
+
+impl TimingArgs {
+    /// Print and write `report` as asked.
+    fn finish(&self, report: &generate::GenerationReport) -> Result<()> {
+        if self.timings {
+            eprintln!("\n⏱️  Timings:\n{}", report);
+        }
+        if let Some(path) = &self.report {
+            std::fs::write(path, serde_json::to_string_pretty(report)?)
+                .with_context(|| format!("Failed to write report to {}", path.display()))?;
+        }
+        Ok(())
+    }
+}
+
+/// How `$ref`s to other files and URLs are loaded
+#[derive(Debug, Clone, clap::Args)]
+struct RefArgs {
+    /// Resolve external references before generating
+    #[arg(short = 'r', long)]
+    resolve: bool,
+
+    /// Fetch remote $refs under this URL prefix (repeatable). Remote $refs
+    /// are only loaded when a prefix is given or with --offline, except those
+    /// on the host of a spec given as a URL
+    #[arg(long, value_name = "URL_PREFIX")]
+    allow_remote: Vec<String>,
+
+    /// Never fetch remote $refs; serve them from the ref cache only
+    #[arg(long)]
+    offline: bool,
+
+    /// Directory caching fetched remote $refs
+    #[arg(long, value_name = "DIR")]
+    ref_cache: Option<PathBuf>,
+
+    /// Refetch cached remote $refs older than this many seconds
+    #[arg(long, value_name = "SECONDS", default_value_t = parser::fetch::DEFAULT_CACHE_TTL.as_secs())]
+    ref_cache_ttl: u64,
+}
+
+impl RefArgs {
+    /// The remote ref policy, or `None` when remote fetching wasn't asked for.
+    fn remote_refs(&self) -> Option<parser::fetch::RemoteRefs> {
+        if !self.offline && self.allow_remote.is_empty() {
+            return None;
+        }
+        Some(self.remote_refs_allowing(None))
+    }
+
+    /// The remote ref policy of a spec fetched from `url`. Its relative refs
+    /// resolve against it, so its host is allowed as well.
+    fn remote_refs_for(&self, url: &str) -> parser::fetch::RemoteRefs {
+        let host: String = url.split_inclusive('/').take(3).collect();
+        self.remote_refs_allowing(Some(&host))
+    }
+
+    fn remote_refs_allowing(&self, host: Option<&str>) -> parser::fetch::RemoteRefs {
+        let cache_dir = self.ref_cache.clone().unwrap_or_else(default_ref_cache_dir);
+        if self.offline {
+            return parser::fetch::RemoteRefs::offline(cache_dir);
+        }
+        let remote = parser::fetch::RemoteRefs::new(parser::fetch::HttpFetcher::new())
+            .with_cache_dir(cache_dir)
+            .with_cache_ttl(std::time::Duration::from_secs(self.ref_cache_ttl));
+        host.into_iter()
+            .chain(self.allow_remote.iter().map(String::as_str))
+            .fold(remote, |remote, prefix| remote.allow(prefix))
+    }
+}
+
+/// Which operations to generate
+#[derive(Debug, Clone, clap::Args)]
+struct FilterArgs {
+    /// Only generate the operations of this tag (repeatable)
+    #[arg(long = "include-tag", value_name = "TAG")]
+    include_tags: Vec<String>,
+
+    /// Don't generate the operations of this tag (repeatable)
+    #[arg(long = "exclude-tag", value_name = "TAG")]
+    exclude_tags: Vec<String>,
+
+    /// Only generate operations whose operationId matches this glob, e.g. `list*` (repeatable)
+    #[arg(long = "include-operation", value_name = "GLOB")]
+    include_operations: Vec<String>,
+
+    /// Don't generate operations whose operationId matches this glob (repeatable)
+    #[arg(long = "exclude-operation", value_name = "GLOB")]
+    exclude_operations: Vec<String>,
+}
+
+/// `$XDG_CACHE_HOME/oas-gen/refs`, falling back to `~/.cache` and then the
+/// temp directory.
+fn default_ref_cache_dir() -> PathBuf {
+    std::env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+        .unwrap_or_else(std::env::temp_dir)
+        .join("oas-gen")
+        .join("refs")
+}
+
 #[derive(Debug, Clone, Copy, clap::ValueEnum)]
 enum ServiceStyleArg {
     PerService,
@@ -104,6 +456,47 @@ enum ServiceStyleArg {
     ByTag,
 }
 
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum LintThresholdArg {
+    Error,
+    Warning,
+    Info,
+    Hint,
+}
+
+impl From<LintThresholdArg> for codegen::LintThreshold {
+    fn from(level: LintThresholdArg) -> Self {
+        match level {
+            LintThresholdArg::Error => codegen::LintThreshold::Error,
+            LintThresholdArg::Warning => codegen::LintThreshold::Warning,
+            LintThresholdArg::Info => codegen::LintThreshold::Info,
+            LintThresholdArg::Hint => codegen::LintThreshold::Hint,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum LintFormatArg {
+    Pretty,
+    Json,
+    Sarif,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum SpecFormatArg {
+    Json,
+    Yaml,
+}
+
+impl From<SpecFormatArg> for parser::fmt::SpecFormat {
+    fn from(format: SpecFormatArg) -> Self {
+        match format {
+            SpecFormatArg::Json => parser::fmt::SpecFormat::Json,
+            SpecFormatArg::Yaml => parser::fmt::SpecFormat::Yaml,
+        }
+    }
+}
+
 impl From<ServiceStyleArg> for codegen::ServiceStyle {
     fn from(style: ServiceStyleArg) -> Self {
         match style {
@@ -119,33 +512,80 @@ fn main() -> Result<()> {
 
     match cli.command {
         Commands::Generate {
+            args,
+            dry_run,
+            check,
+            verify,
+            stdout,
+            single_file,
+        } => args.run(
+            OutputCheck {
+                dry_run,
+                check,
+                stdout,
+                single_file,
+            },
+            verify,
+        ),
+        Commands::Diff { args } => args.run(
+            OutputCheck {
+                dry_run: true,
+                check: false,
+                stdout: false,
+                single_file: false,
+            },
+            false,
+        ),
+        Commands::ListGenerators { json, verbose } => handle_list_generators(json, verbose),
+        Commands::Resolve {
             spec,
-            template,
             output,
-            service_style,
-            no_docs,
-            resolve,
             verbose,
-        } => handle_generate(
+        } => handle_resolve(spec, output, verbose),
+        Commands::Bundle {
             spec,
-            template,
             output,
-            service_style,
-            no_docs,
-            resolve,
+            format,
             verbose,
-        ),
-        Commands::Resolve {
+        } => handle_bundle(spec, output, format, verbose),
+        Commands::Fmt {
             spec,
             output,
+            check,
+            write,
             verbose,
-        } => handle_resolve(spec, output, verbose),
+        } => handle_fmt(spec, output, check, write, verbose),
         Commands::Lint {
             spec,
             rulesets,
             rules,
             severity,
-        } => handle_lint(spec, rulesets, rules, severity),
+            only,
+            category_severity,
+            spectral,
+            config,
+            print_config,
+            fix,
+            write,
+            baseline,
+            report_suppressed,
+            format,
+        } => handle_lint(
+            spec,
+            rulesets,
+            rules,
+            severity,
+            only,
+            category_severity,
+            spectral,
+            config,
+            print_config,
+            fix,
+            write,
+            baseline,
+            report_suppressed,
+            format,
+        ),
         Commands::Merge {
             files,
             output,
@@ -187,57 +627,183 @@ fn handle_resolve(spec: PathBuf, output: Option<PathBuf>, verbose: bool) -> Resu
     Ok(())
 }
 
-fn handle_generate(
+fn handle_bundle(
     spec: PathBuf,
-    template: String,
     output: Option<PathBuf>,
-    service_style: ServiceStyleArg,
-    no_docs: bool,
-    resolve: bool,
+    format: Option<SpecFormatArg>,
     verbose: bool,
 ) -> Result<()> {
+    let format = format.map(Into::into).unwrap_or_else(|| {
+        let path = output.as_ref().unwrap_or(&spec);
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => parser::fmt::SpecFormat::Json,
+            _ => parser::fmt::SpecFormat::Yaml,
+        }
+    });
+
     if verbose {
-        eprintln!("🔍 Reading OpenAPI specification: {}", spec.display());
+        eprintln!("📦 Bundling OpenAPI specification: {}", spec.display());
     }
 
-    // Read the OpenAPI specification
-    let spec_content = if resolve {
+    let bundled = parser::bundle_to_string(&parser::source::FileSource::new(&spec), format)
+        .with_context(|| format!("Failed to bundle spec at {}", spec.display()))?;
+
+    if let Some(output_path) = output {
         if verbose {
-            eprintln!("🔗 Resolving external references...");
+            eprintln!("💾 Writing bundled spec to: {}", output_path.display());
         }
-        parser::resolve::resolve(&spec)
-            .with_context(|| format!("Failed to resolve spec at {}", spec.display()))?
+        std::fs::write(&output_path, &bundled)
+            .with_context(|| format!("Failed to write to {}", output_path.display()))?;
+        println!("✅ Bundled spec written to {}", output_path.display());
     } else {
-        read(&spec)?
-    };
+        print!("{}", bundled);
+    }
+
+    Ok(())
+}
 
+fn handle_fmt(
+    spec: PathBuf,
+    output: Option<PathBuf>,
+    check: bool,
+    write: bool,
+    verbose: bool,
+) -> Result<()> {
     if verbose {
-        eprintln!("📄 Parsing OpenAPI specification...");
+        eprintln!("🔍 Reading OpenAPI specification: {}", spec.display());
     }
 
-    // Parse the specification
-    let oas = parse(&spec_content)?;
+    let content = read(&spec).with_context(|| format!("Failed to read {}", spec.display()))?;
+    let formatted = parser::fmt::format(&content)
+        .with_context(|| format!("Failed to format spec at {}", spec.display()))?;
 
-    if verbose {
-        eprintln!("🏗️  Building intermediate representation...");
+    if check {
+        if formatted != content {
+            anyhow::bail!(
+                "{} is not formatted. Run `oas-gen fmt {}` to fix it",
+                spec.display(),
+                spec.display()
+            );
+        }
+        if verbose {
+            eprintln!("✅ {} is formatted", spec.display());
+        }
+        return Ok(());
     }
 
-    // Build the GenIR
-    let gen_ir: codegen::GenIr = oas.into();
+    // Comments and anchors can't be carried over, so only drop them from
+    // the spec itself when asked to
+    let dropped = parser::fmt::dropped_by_format(&content);
+    if !dropped.is_empty() {
+        if output.is_none() && !write {
+            anyhow::bail!(
+                "{} has YAML {}, which formatting drops. Pass --write to rewrite it anyway, or --output to write the formatted spec elsewhere",
+                spec.display(),
+                dropped.join(" and ")
+            );
+        }
+        eprintln!(
+            "⚠️  Dropped the YAML {} of {}",
+            dropped.join(" and "),
+            spec.display()
+        );
+    }
 
+    let output_path = output.unwrap_or(spec);
     if verbose {
+        eprintln!("💾 Writing formatted spec to: {}", output_path.display());
+    }
+    std::fs::write(&output_path, &formatted)
+        .with_context(|| format!("Failed to write to {}", output_path.display()))?;
+    println!("✅ Formatted spec written to {}", output_path.display());
+
+    Ok(())
+}
+
+/// The built-in generators plus the plugins in `OAS_GEN_PLUGIN_PATH`.
+/// Plugins that fail to load are reported and skipped, and plugins that
+/// replace a generator are reported.
+fn registry() -> generate::GeneratorRegistry {
+    let mut registry = generate::GeneratorRegistry::with_defaults();
+    let load = registry.load_plugins_from_env();
+    for (path, error) in &load.errors {
+        eprintln!("⚠️  Skipping plugin {}: {}", path.display(), error);
+    }
+    for (language, path) in &load.replaced {
         eprintln!(
-            "📦 Found {} types, {} services",
-            gen_ir.types.len(),
-            gen_ir.services.len()
+            "⚠️  Plugin {} replaces the '{}' generator",
+            path.display(),
+            language
+        );
+    }
+    registry
+}
+
+/// `list-generators`: the registered generators, one per line
+fn handle_list_generators(json: bool, verbose: bool) -> Result<()> {
+    let generators = registry().list();
+    if json {
+        println!("{}", serde_json::to_string_pretty(&generators)?);
+        return Ok(());
+    }
+    let width = generators.iter().map(|g| g.name.len()).max().unwrap_or(0);
+    for generator in &generators {
+        println!(
+            "{:width$}  {:8}  {}",
+            generator.name, generator.version, generator.description
         );
+        if verbose {
+            for option in &generator.options {
+                let default = option
+                    .default
+                    .as_ref()
+                    .map(|value| format!(" [default: {}]", value))
+                    .unwrap_or_default();
+                println!("    {}: {}{}", option.name, option.description, default);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn handle_generate(
+    args: GenerateArgs,
+    spec: PathBuf,
+    template: String,
+    output_check: OutputCheck,
+    verify: bool,
+) -> Result<()> {
+    if spec.is_dir() {
+        if args.refs.resolve {
+            anyhow::bail!("--resolve is not supported when generating from a directory");
+        }
+        if !args.overlays.is_empty() {
+            anyhow::bail!("--overlay is not supported when generating from a directory");
+        }
+        if output_check.single_file {
+            anyhow::bail!("--single-file is not supported when generating from a directory");
+        }
+        return handle_generate_versions(args, spec, template, output_check, verify);
     }
 
+    let verbose = args.verbose;
+    let mut timings = generate::GenerationReport::new();
+    let options = LoadOptions {
+        refs: &args.refs,
+        overlays: args.overlays,
+        strict: args.strict,
+        lenient: args.lenient,
+        lint: args.lint.map(codegen::LintThreshold::from),
+        verbose,
+    };
+    let gen_ir = load_ir(&spec, options, &mut timings)?;
+
     // Determine output directory
-    let output_dir = output.unwrap_or_else(|| {
+    let output_dir = args.output.unwrap_or_else(|| {
         let spec_name = spec
             .file_stem()
             .and_then(|s| s.to_str())
+            .filter(|stem| *stem != "-")
             .unwrap_or("output");
         PathBuf::from(format!("{}-{}", spec_name, template))
     });
@@ -248,64 +814,885 @@ fn handle_generate(
 
     // Create configuration
     let config = codegen::Config {
-        service_style: service_style.into(),
-        include_docs: !no_docs,
-        lang_options: std::collections::BTreeMap::new(),
+        service_style: args
+            .service_style
+            .unwrap_or(ServiceStyleArg::PerService)
+            .into(),
+        include_docs: !args.no_docs,
+        naming: codegen::NamingPolicy::default(),
+        time_formats: codegen::TimeFormats::default(),
+        formats: codegen::formats::FormatRegistry::default(),
+        locale: args.locale,
+        package_name: None,
+        base_url: None,
+        include_tags: args.filter.include_tags,
+        exclude_tags: args.filter.exclude_tags,
+        include_operations: args.filter.include_operations,
+        exclude_operations: args.filter.exclude_operations,
+        strict: args.strict,
+        // Linted above
+        lint_threshold: None,
+        file_header: args.header.then(codegen::FileHeader::default),
+        template_dir: args.templates,
+        // Overlays were applied to the spec above
+        passes: codegen::passes::Passes::default(),
+        transforms: codegen::transform::Transforms::default(),
+        shared_types: None,
+        lang_options: report_options(args.nullability_report),
+    };
+
+    let registry = registry();
+    let Some((vfs, generated)) = generate_or_reuse(
+        &registry,
+        &template,
+        &gen_ir,
+        &config,
+        &output_dir,
+        WriteOptions {
+            output_check,
+            force: args.force,
+            verbose,
+        },
+        &mut timings,
+    )?
+    else {
+        return args.timing.finish(&timings);
     };
 
     if verbose {
-        eprintln!("🔨 Generating code using '{}' template...", template);
+        eprintln!("🪝 Running post-write hooks...");
     }
 
-    // Generate code
-    let registry = generate::GeneratorRegistry::with_defaults();
-    let vfs = registry
-        .generate(&template, &gen_ir, &config)
-        .with_context(|| format!("Failed to generate code for template '{}'", template))?;
-
-    if verbose {
-        eprintln!("💾 Writing {} files to disk...", vfs.len());
+    // Run post-processors and the after_write_to_disk hook, also for
+    // reused output, whose last run may not have finished them
+    let targets = [generate::WriteTarget {
+        language: &template,
+        output_dir: &output_dir,
+        vfs: &vfs,
+    }];
+    let reports = registry.after_write_all(&targets, &args.post_write.options());
+    report_after_write(&reports, &mut timings, verbose)?;
+
+    if verify {
+        let started = Instant::now();
+        verify_output(&registry, &template, &output_dir, verbose)?;
+        timings.record(Phase::Verify, Some(template.as_str()), started, None);
     }
 
-    // Write to disk
-    vfs.write_to_disk(&output_dir)
-        .with_context(|| format!("Failed to write files to {}", output_dir.display()))?;
+    if generated {
+        println!(
+            "✅ Successfully generated {} files in {}",
+            vfs.len(),
+            output_dir.display()
+        );
 
-    if verbose {
-        eprintln!("🪝 Running post-write hooks...");
+        // List generated files
+        if verbose {
+            println!("\nGenerated files:");
+            for (path, _) in vfs.files() {
+                println!("  📄 {}", path.display());
+            }
+        }
     }
 
-    // Call after_write_to_disk hook
-    registry
-        .after_write_to_disk(&template, &output_dir, &vfs)
-        .with_context(|| "Failed to run after_write_to_disk hook")?;
+    args.timing.finish(&timings)
+}
 
-    println!(
-        "✅ Successfully generated {} files in {}",
-        vfs.len(),
-        output_dir.display()
-    );
+/// How [`load_ir`] reads a spec: the generate flags, merged with the
+/// project file's settings when there is one
+struct LoadOptions<'a> {
+    refs: &'a RefArgs,
+    overlays: Vec<PathBuf>,
+    strict: bool,
+    lenient: bool,
+    lint: Option<codegen::LintThreshold>,
+    verbose: bool,
+}
 
-    // List generated files
+/// Read `spec` (a file, `-` for stdin or a URL), lint it at `lint`, parse
+/// it as `--strict` and `--lenient` ask, apply `overlays` and build the IR
+fn load_ir(
+    spec: &Path,
+    options: LoadOptions,
+    timings: &mut generate::GenerationReport,
+) -> Result<codegen::GenIr> {
+    let LoadOptions {
+        refs,
+        overlays,
+        strict,
+        lenient,
+        lint,
+        verbose,
+    } = options;
+    let started = Instant::now();
     if verbose {
-        println!("\nGenerated files:");
-        for (path, _) in vfs.files() {
-            println!("  📄 {}", path.display());
-        }
+        eprintln!("🔍 Reading OpenAPI specification: {}", spec.display());
     }
 
-    Ok(())
-}
+    // Stdin and URLs are read once and parsed from memory
+    let location = spec.to_string_lossy().into_owned();
+    let kind = parser::input::Location::parse(&location);
+    let input = match kind {
+        parser::input::Location::File(_) => None,
+        _ => Some(
+            parser::read_source(&location)
+                .with_context(|| format!("Failed to read {}", location))?,
+        ),
+    };
 
-fn handle_lint(
-    spec: PathBuf,
-    rulesets: Option<String>,
-    rules: Option<String>,
-    severity: Option<String>,
-) -> Result<()> {
-    // Read the spec file
-    let spec_content = std::fs::read_to_string(&spec)
-        .with_context(|| format!("Failed to read spec file: {}", spec.display()))?;
+    // Reject Swagger 2.0 and unknown versions before resolving any refs
+    let content = match &input {
+        Some(input) => input.content().to_string(),
+        None => read(spec).with_context(|| format!("Failed to read {}", spec.display()))?,
+    };
+    parser::detect(&content)
+        .and_then(|info| info.version.supported())
+        .with_context(|| format!("Failed to load spec at {}", location))?;
+    let mut parse_time = started.elapsed();
+
+    // Lint before resolving refs so broken specs fail fast
+    if let Some(threshold) = lint {
+        if verbose {
+            eprintln!("🔎 Linting specification...");
+        }
+        let started = Instant::now();
+        let lint_config = codegen::Config {
+            lint_threshold: Some(threshold),
+            ..Default::default()
+        };
+        let path = input.is_none().then_some(spec);
+        generate::lint_spec(&content, path, &lint_config)
+            .with_context(|| format!("Lint failed for {}", location))?;
+        timings.record(Phase::Lint, None, started, None);
+    }
+    let started = Instant::now();
+
+    // Without --resolve, local file and remote refs are bundled by the parser
+    let remote = match &kind {
+        parser::input::Location::Url(url) => Some(refs.remote_refs_for(url)),
+        _ => refs.remote_refs(),
+    };
+    let fetcher = parser::fetch::HttpFetcher::new();
+    let source: Box<dyn SpecSource + '_> = if let Some(input) = input {
+        if refs.resolve {
+            anyhow::bail!("--resolve is not supported when reading from stdin or a URL");
+        }
+        match &kind {
+            // Refs resolve against the URL, as with the facade's generate_from_url
+            parser::input::Location::Url(url) => {
+                let mut source = parser::source::UrlSource::new(url.as_str(), &fetcher)
+                    .with_content(input.content())
+                    .with_format(input.format());
+                if let Some(remote) = &remote {
+                    source = source.with_remote_refs(remote);
+                }
+                Box::new(source)
+            }
+            _ => {
+                if remote.is_some() {
+                    anyhow::bail!(
+                        "--allow-remote and --offline are not supported when reading from stdin"
+                    );
+                }
+                Box::new(input)
+            }
+        }
+    } else if refs.resolve {
+        if verbose {
+            eprintln!("🔗 Resolving external references...");
+        }
+        Box::new(parser::source::FileSource::new(spec).with_legacy_resolver())
+    } else {
+        if verbose {
+            eprintln!("📄 Parsing OpenAPI specification...");
+        }
+        // Bundle from the content already read above
+        let source = parser::source::FileSource::new(spec).with_content(content);
+        match &remote {
+            Some(remote) => Box::new(source.with_remote_refs(remote)),
+            None => Box::new(source),
+        }
+    };
+
+    if verbose {
+        eprintln!("🏗️  Building intermediate representation...");
+    }
+
+    // Errors name the source themselves
+    let parsed = source
+        .load_with_options(&parse_options(strict, lenient, verbose))
+        .context("Failed to load spec")?;
+    let overlaid = codegen::Config::default().with_passes(codegen::passes::Passes::from(
+        overlays
+            .into_iter()
+            .map(|path| codegen::passes::PassConfig::Overlay { path })
+            .collect::<Vec<_>>(),
+    ));
+    let spec_document = generate::apply_overlays(parsed.spec, &overlaid)?;
+    parse_time += started.elapsed();
+    timings.record_duration(Phase::Parse, None, parse_time, None);
+    let started = Instant::now();
+    let gen_ir = codegen::GenIr::from(spec_document);
+    timings.record(Phase::BuildIr, None, started, Some(gen_ir.types.len()));
+    print_parse_warnings(&parsed.warnings, verbose);
+
+    if verbose {
+        eprintln!(
+            "📦 Found {} types, {} services",
+            gen_ir.types.len(),
+            gen_ir.services.len()
+        );
+    }
+    Ok(gen_ir)
+}
+
+/// How generated output is written: the output checks, `--force` and
+/// `--verbose`
+#[derive(Debug, Clone, Copy)]
+struct WriteOptions {
+    output_check: OutputCheck,
+    force: bool,
+    verbose: bool,
+}
+
+/// Generate `template` into `output_dir`, or reuse the files there when the
+/// manifest records the same spec, generator and options. `None` when
+/// `output_check` printed or compared the output instead; otherwise the
+/// files and whether they were generated
+fn generate_or_reuse(
+    registry: &generate::GeneratorRegistry,
+    template: &str,
+    gen_ir: &codegen::GenIr,
+    config: &codegen::Config,
+    output_dir: &Path,
+    write: WriteOptions,
+    timings: &mut generate::GenerationReport,
+) -> Result<Option<(codegen::VirtualFS, bool)>> {
+    let cached = if write.force || write.output_check.is_active() {
+        None
+    } else {
+        registry
+            .cached_output(template, gen_ir, config, output_dir)
+            .with_context(|| format!("Failed to read {}", output_dir.display()))?
+    };
+    if let Some(vfs) = cached {
+        println!(
+            "✅ {} is up to date ({} files; spec, generator and options unchanged)",
+            output_dir.display(),
+            vfs.len()
+        );
+        return Ok(Some((vfs, false)));
+    }
+
+    if write.verbose {
+        eprintln!("🔨 Generating code using '{}' template...", template);
+    }
+    let vfs = generate_and_write(
+        registry, template, gen_ir, config, output_dir, write, timings,
+    )?;
+    Ok(vfs.map(|vfs| (vfs, true)))
+}
+
+/// Generate `template` and write it to `output_dir`. `None` when
+/// `output_check` printed or compared the output instead.
+fn generate_and_write(
+    registry: &generate::GeneratorRegistry,
+    template: &str,
+    gen_ir: &codegen::GenIr,
+    config: &codegen::Config,
+    output_dir: &Path,
+    write: WriteOptions,
+    timings: &mut generate::GenerationReport,
+) -> Result<Option<codegen::VirtualFS>> {
+    let WriteOptions {
+        output_check,
+        verbose,
+        ..
+    } = write;
+    if output_check.single_file {
+        let merged = registry
+            .generate_single_file_with_report(template, gen_ir, config, timings)
+            .with_context(|| {
+                format!(
+                    "Failed to generate one file for template '{}' (--stdout prints every file)",
+                    template
+                )
+            })?;
+        print!("{}", merged);
+        return Ok(None);
+    }
+
+    // Generate code
+    let vfs = registry
+        .generate_with_report(template, gen_ir, config, timings)
+        .with_context(|| format!("Failed to generate code for template '{}'", template))?;
+
+    if output_check.print(&vfs)? || output_check.compare(&vfs, output_dir)? {
+        return Ok(None);
+    }
+
+    if verbose {
+        eprintln!("💾 Writing {} files to disk...", vfs.len());
+    }
+
+    warn_hand_edited(output_dir, &vfs);
+
+    // Write to disk
+    let started = Instant::now();
+    vfs.write_to_disk(output_dir)
+        .with_context(|| format!("Failed to write files to {}", output_dir.display()))?;
+    timings.record(Phase::Write, None, started, Some(vfs.len()));
+    Ok(Some(vfs))
+}
+
+/// `generate --dry-run` and `--check`: compare with the output directory
+/// instead of writing to it. `--stdout` and `--single-file`: print the
+/// output instead.
+#[derive(Debug, Clone, Copy)]
+struct OutputCheck {
+    dry_run: bool,
+    check: bool,
+    stdout: bool,
+    single_file: bool,
+}
+
+impl OutputCheck {
+    /// Whether the output is compared or printed instead of written.
+    fn is_active(self) -> bool {
+        self.dry_run || self.check || self.stdout || self.single_file
+    }
+
+    /// Print `vfs` to stdout if asked to. Returns whether it did, in which
+    /// case nothing must be written. `--single-file` output is generated
+    /// with [`generate::GeneratorRegistry::generate_single_file`] instead.
+    fn print(self, vfs: &codegen::VirtualFS) -> Result<bool> {
+        if self.stdout {
+            let stdout = std::io::stdout().lock();
+            vfs.write_concatenated(stdout)
+                .context("Failed to write to stdout")?;
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    /// Compare `vfs` with `output_dir` if asked to. Returns whether it did,
+    /// in which case nothing must be written.
+    fn compare(self, vfs: &codegen::VirtualFS, output_dir: &Path) -> Result<bool> {
+        if !self.dry_run && !self.check {
+            return Ok(false);
+        }
+        let diff = vfs
+            .write_to_disk_with(output_dir, codegen::WriteMode::DryRun)
+            .with_context(|| format!("Failed to compare with {}", output_dir.display()))?;
+        if self.check {
+            if !diff.is_empty() {
+                for change in &diff.changes {
+                    eprintln!("  {} {}", change.kind.as_str(), change.path.display());
+                }
+                anyhow::bail!(
+                    "Generated code in {} is out of date ({}). Run `oas-gen generate` without --check to update it",
+                    output_dir.display(),
+                    diff.summary()
+                );
+            }
+            println!(
+                "✅ Generated code in {} is up to date",
+                output_dir.display()
+            );
+        } else {
+            print!("{}", diff);
+            println!("🔍 Dry run: {} in {}", diff.summary(), output_dir.display());
+        }
+        Ok(true)
+    }
+}
+
+/// List the post-processors that were skipped, e.g. formatters that aren't
+/// installed.
+fn report_post_processing(
+    outcomes: &[(String, codegen::postprocess::PostProcessOutcome)],
+    verbose: bool,
+) {
+    for (name, outcome) in outcomes {
+        match outcome {
+            codegen::postprocess::PostProcessOutcome::Skipped(reason) => {
+                eprintln!("⏭️  Skipped {}: {}", name, reason)
+            }
+            codegen::postprocess::PostProcessOutcome::Ran if verbose => {
+                eprintln!("🧹 Ran {}", name)
+            }
+            codegen::postprocess::PostProcessOutcome::Ran => {}
+        }
+    }
+}
+
+/// `generate --verify`: compile the output in `output_dir`, printing the
+/// compiler's warnings and failing on its errors. Fails as well when the
+/// output couldn't be verified, since verifying was asked for.
+fn verify_output(
+    registry: &generate::GeneratorRegistry,
+    template: &str,
+    output_dir: &Path,
+    verbose: bool,
+) -> Result<()> {
+    if verbose {
+        eprintln!("🔎 Verifying {}...", output_dir.display());
+    }
+    let Some(report) = registry
+        .verify(template, output_dir)
+        .with_context(|| "Failed to verify generated code")?
+    else {
+        anyhow::bail!("Can't verify: '{}' has no verifier", template);
+    };
+    match &report.outcome {
+        codegen::verify::VerifyOutcome::Skipped(reason) => {
+            anyhow::bail!("Can't verify with {}: {}", report.verifier, reason);
+        }
+        codegen::verify::VerifyOutcome::Passed | codegen::verify::VerifyOutcome::Failed => {}
+    }
+    for diagnostic in &report.diagnostics {
+        if diagnostic.severity() == codegen::diagnostic::Severity::Warning {
+            eprintln!("  ⚠️  {}", diagnostic);
+        }
+    }
+    let report = report.into_result()?;
+    println!("✅ {} passed", report.verifier);
+    Ok(())
+}
+
+/// Warn about generated files in `output_dir` that were edited by hand
+/// since the last run (see [`codegen::Manifest::edited`]): writing `vfs`
+/// overwrites them, or keeps them if they are no longer generated.
+fn warn_hand_edited(output_dir: &Path, vfs: &codegen::VirtualFS) {
+    let Ok(Some(manifest)) = codegen::Manifest::read(output_dir) else {
+        return;
+    };
+    for path in manifest.edited(output_dir).unwrap_or_default() {
+        if vfs.contains(&path) {
+            eprintln!(
+                "⚠️  {} was edited by hand; the changes are overwritten (use a protected region to keep them)",
+                path.display()
+            );
+        } else {
+            eprintln!(
+                "⚠️  {} is no longer generated but was edited by hand; kept it",
+                path.display()
+            );
+        }
+    }
+}
+
+/// Lang options shared by every generator, from generate flags.
+fn report_options(nullability_report: bool) -> std::collections::BTreeMap<String, Value> {
+    let mut options = std::collections::BTreeMap::new();
+    if nullability_report {
+        options.insert(
+            codegen::Config::NULLABILITY_REPORT.to_string(),
+            Value::Bool(true),
+        );
+    }
+    options
+}
+
+/// How `--strict`, `--lenient` and `--verbose` parse a spec
+fn parse_options(strict: bool, lenient: bool, verbose: bool) -> ParseOptions {
+    if strict {
+        ParseOptions::strict()
+    } else {
+        ParseOptions {
+            repair: lenient,
+            // Violations are only listed with --verbose
+            validate: verbose,
+            ..ParseOptions::default()
+        }
+    }
+}
+
+/// Print parse warnings; meta-schema violations are common in real specs, so
+/// they are only listed when asked
+fn print_parse_warnings(warnings: &[Warning], verbose: bool) {
+    for warning in warnings {
+        if verbose || matches!(warning, Warning::Repair(_)) {
+            eprintln!("⚠️  {}", warning);
+        }
+    }
+}
+
+/// `generate` from a directory of spec snapshots: one package per version,
+/// plus the types they share
+fn handle_generate_versions(
+    args: GenerateArgs,
+    dir: PathBuf,
+    template: String,
+    output_check: OutputCheck,
+    verify: bool,
+) -> Result<()> {
+    let verbose = args.verbose;
+    if verbose {
+        eprintln!("🔍 Reading spec snapshots from: {}", dir.display());
+    }
+
+    let mut timings = generate::GenerationReport::new();
+    let started = Instant::now();
+    let snapshots =
+        generate::load_snapshots_with(&dir, &parse_options(args.strict, args.lenient, verbose))
+            .with_context(|| format!("Failed to load spec snapshots from {}", dir.display()))?;
+    timings.record(Phase::Parse, None, started, None);
+    for snapshot in &snapshots {
+        print_parse_warnings(&snapshot.warnings, verbose);
+    }
+    if snapshots.is_empty() {
+        anyhow::bail!("No .json, .yaml or .yml specs found in {}", dir.display());
+    }
+
+    if verbose {
+        let versions: Vec<&str> = snapshots.iter().map(|s| s.version.as_str()).collect();
+        eprintln!("📚 Found versions: {}", versions.join(", "));
+    }
+
+    let output_dir = args.output.unwrap_or_else(|| {
+        let dir_name = dir.file_name().and_then(|s| s.to_str()).unwrap_or("output");
+        PathBuf::from(format!("{}-{}", dir_name, template))
+    });
+
+    let config = codegen::Config {
+        service_style: args
+            .service_style
+            .unwrap_or(ServiceStyleArg::PerService)
+            .into(),
+        include_docs: !args.no_docs,
+        naming: codegen::NamingPolicy::default(),
+        time_formats: codegen::TimeFormats::default(),
+        formats: codegen::formats::FormatRegistry::default(),
+        locale: args.locale,
+        package_name: None,
+        base_url: None,
+        include_tags: args.filter.include_tags,
+        exclude_tags: args.filter.exclude_tags,
+        include_operations: args.filter.include_operations,
+        exclude_operations: args.filter.exclude_operations,
+        strict: args.strict,
+        // Each snapshot file is linted by generate_versions
+        lint_threshold: args.lint.map(codegen::LintThreshold::from),
+        file_header: args.header.then(codegen::FileHeader::default),
+        template_dir: args.templates,
+        passes: codegen::passes::Passes::default(),
+        transforms: codegen::transform::Transforms::default(),
+        shared_types: None,
+        lang_options: report_options(args.nullability_report),
+    };
+
+    if verbose {
+        eprintln!("🔨 Generating code using '{}' template...", template);
+    }
+
+    let registry = registry();
+    let vfs = registry
+        .generate_versions_with_report(&template, &snapshots, &config, &mut timings)
+        .with_context(|| format!("Failed to generate code for template '{}'", template))?;
+
+    if output_check.print(&vfs)? || output_check.compare(&vfs, &output_dir)? {
+        return args.timing.finish(&timings);
+    }
+
+    warn_hand_edited(&output_dir, &vfs);
+    let started = Instant::now();
+    vfs.write_to_disk(&output_dir)
+        .with_context(|| format!("Failed to write files to {}", output_dir.display()))?;
+    timings.record(Phase::Write, None, started, Some(vfs.len()));
+
+    if verbose {
+        eprintln!("🪝 Running post-write hooks...");
+    }
+
+    // Each package's hooks see only its own files, relative to it
+    let packages: Vec<(PathBuf, codegen::VirtualFS)> =
+        std::iter::once(generate::versions::SHARED_DIR)
+            .chain(snapshots.iter().map(|snapshot| snapshot.version.as_str()))
+            .map(|package| (output_dir.join(package), vfs.subtree(package)))
+            .filter(|(_, package_vfs)| !package_vfs.is_empty())
+            .collect();
+    let targets: Vec<generate::WriteTarget> = packages
+        .iter()
+        .map(|(package_dir, package_vfs)| generate::WriteTarget {
+            language: &template,
+            output_dir: package_dir,
+            vfs: package_vfs,
+        })
+        .collect();
+    let reports = registry.after_write_all(&targets, &args.post_write.options());
+    // The manifest is written for the whole output, not per package
+    if reports.iter().any(generate::TargetReport::rewrote_files) {
+        generate::rehash_manifest(&output_dir).with_context(|| {
+            format!("Failed to update the manifest in {}", output_dir.display())
+        })?;
+    }
+    report_after_write(&reports, &mut timings, verbose)?;
+
+    if verify {
+        for (package_dir, _) in &packages {
+            let started = Instant::now();
+            verify_output(&registry, &template, package_dir, verbose)?;
+            timings.record(Phase::Verify, Some(template.as_str()), started, None);
+        }
+    }
+
+    println!(
+        "✅ Successfully generated {} versions ({} files) in {}",
+        snapshots.len(),
+        vfs.len(),
+        output_dir.display()
+    );
+
+    args.timing.finish(&timings)
+}
+
+/// `generate` without a template: every target of the project file found
+/// from the current directory, each written to its own output directory.
+/// Flags given on the command line replace the project's options
+fn handle_generate_project(
+    args: GenerateArgs,
+    spec: Option<PathBuf>,
+    output_check: OutputCheck,
+    verify: bool,
+) -> Result<()> {
+    let verbose = args.verbose;
+    let cwd = std::env::current_dir().context("Failed to read the current directory")?;
+    let path = codegen::ProjectConfig::discover(&cwd).with_context(|| {
+        format!(
+            "No template given; pass --template or add `targets` to {}",
+            codegen::ProjectConfig::FILE_NAME
+        )
+    })?;
+    if verbose {
+        eprintln!("📋 Using project file: {}", path.display());
+    }
+    let mut project = codegen::ProjectConfig::from_file(&path)
+        .and_then(|project| project.select_profile(args.profile.as_deref()))
+        .with_context(|| format!("Failed to load {}", path.display()))?;
+    if verbose && let Some(profile) = args.profile.or_else(|| project.profile.clone()) {
+        eprintln!("📋 Using profile: {}", profile);
+    }
+    let spec = spec.or_else(|| project.input.clone()).with_context(|| {
+        format!(
+            "No spec given; pass it as an argument or set `input` in {}",
+            path.display()
+        )
+    })?;
+    if spec.is_dir() {
+        anyhow::bail!("Generating from a directory of spec snapshots needs --template");
+    }
+
+    let config = &mut project.config;
+    if let Some(service_style) = args.service_style {
+        config.service_style = service_style.into();
+    }
+    if args.no_docs {
+        config.include_docs = false;
+    }
+    if args.locale.is_some() {
+        config.locale = args.locale;
+    }
+    if args.header && config.file_header.is_none() {
+        config.file_header = Some(codegen::FileHeader::default());
+    }
+    if args.templates.is_some() {
+        config.template_dir = args.templates;
+    }
+    let filter = args.filter;
+    for (option, flags) in [
+        (&mut config.include_tags, filter.include_tags),
+        (&mut config.exclude_tags, filter.exclude_tags),
+        (&mut config.include_operations, filter.include_operations),
+        (&mut config.exclude_operations, filter.exclude_operations),
+    ] {
+        if !flags.is_empty() {
+            *option = flags;
+        }
+    }
+    config.strict |= args.strict;
+    // Linted while loading the spec
+    let project_lint = config.lint_threshold.take();
+    let lint = args.lint.map(codegen::LintThreshold::from).or(project_lint);
+    // The project's overlays first, then those of --overlay
+    let overlays: Vec<PathBuf> = config
+        .passes
+        .overlays()
+        .with_context(|| format!("Failed to load {}", path.display()))?
+        .into_iter()
+        .map(Path::to_path_buf)
+        .chain(args.overlays)
+        .collect();
+    config.passes = config.passes.without_overlays();
+
+    let options = LoadOptions {
+        refs: &args.refs,
+        overlays,
+        strict: config.strict,
+        lenient: args.lenient,
+        lint,
+        verbose,
+    };
+    let mut timings = generate::GenerationReport::new();
+    let gen_ir = load_ir(&spec, options, &mut timings)?;
+
+    // Check every target has somewhere to go before generating any
+    let registry = registry().with_project(project.clone());
+    let default_targets = registry
+        .default_targets()
+        .with_context(|| format!("Failed to read the targets of {}", path.display()))?;
+    if output_check.single_file && default_targets.len() > 1 {
+        anyhow::bail!(
+            "--single-file needs one target, but {} has {}; pass --template",
+            path.display(),
+            default_targets.len()
+        );
+    }
+    let mut plans = Vec::new();
+    for target in &default_targets {
+        let language = registry.resolve(&target.generator).to_string();
+        let output_dir = project.output_for(target, &language).with_context(|| {
+            format!(
+                "No output directory for '{}'; set `output` in {}",
+                language,
+                path.display()
+            )
+        })?;
+        let mut config = registry.target_config(target);
+        config
+            .lang_options
+            .extend(report_options(args.nullability_report));
+        plans.push((language, output_dir, config));
+    }
+
+    let write = WriteOptions {
+        output_check,
+        force: args.force,
+        verbose,
+    };
+    let mut outputs = Vec::new();
+    for (language, output_dir, config) in plans {
+        if let Some((vfs, generated)) = generate_or_reuse(
+            &registry,
+            &language,
+            &gen_ir,
+            &config,
+            &output_dir,
+            write,
+            &mut timings,
+        )? {
+            outputs.push((language, output_dir, vfs, generated));
+        }
+    }
+    if outputs.is_empty() {
+        return args.timing.finish(&timings);
+    }
+
+    if verbose {
+        eprintln!("🪝 Running post-write hooks...");
+    }
+    let targets: Vec<generate::WriteTarget> = outputs
+        .iter()
+        .map(|(language, output_dir, vfs, _)| generate::WriteTarget {
+            language,
+            output_dir,
+            vfs,
+        })
+        .collect();
+    let reports = registry.after_write_all(&targets, &args.post_write.options());
+    report_after_write(&reports, &mut timings, verbose)?;
+
+    for (language, output_dir, vfs, generated) in &outputs {
+        if verify {
+            let started = Instant::now();
+            verify_output(&registry, language, output_dir, verbose)?;
+            timings.record(Phase::Verify, Some(language.as_str()), started, None);
+        }
+        if *generated {
+            println!(
+                "✅ Successfully generated {} ({} files) in {}",
+                language,
+                vfs.len(),
+                output_dir.display()
+            );
+        }
+    }
+
+    args.timing.finish(&timings)
+}
+
+/// Print what the post-write hooks of each package did and add their
+/// timings to `timings`, failing if any of them failed.
+fn report_after_write(
+    reports: &[generate::TargetReport],
+    timings: &mut generate::GenerationReport,
+    verbose: bool,
+) -> Result<()> {
+    let mut failed = 0;
+    for report in reports {
+        timings.extend(&report.timings);
+        let package_dir = report.output_dir.display();
+        match &report.outcome {
+            generate::TargetOutcome::Done(outcomes) => {
+                if verbose && !report.output.is_empty() {
+                    eprintln!("🪝 {}:\n{}", package_dir, report.output.trim_end());
+                }
+                report_post_processing(outcomes, verbose);
+            }
+            generate::TargetOutcome::Failed(e) => {
+                failed += 1;
+                eprintln!("❌ Post-write hooks failed in {}: {}", package_dir, e);
+                if !report.output.is_empty() {
+                    eprintln!("{}", report.output.trim_end());
+                }
+            }
+            generate::TargetOutcome::Cancelled => {
+                eprintln!(
+                    "⏭️  Skipped post-write hooks in {}: another package failed",
+                    package_dir
+                );
+            }
+        }
+    }
+    if failed > 0 {
+        anyhow::bail!(
+            "Post-write hooks failed in {} of {} packages",
+            failed,
+            reports.len()
+        );
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn handle_lint(
+    spec: PathBuf,
+    rulesets: Option<String>,
+    rules: Option<String>,
+    severity: Option<String>,
+    only: Option<String>,
+    category_severity: Option<String>,
+    spectral: Option<PathBuf>,
+    config: Option<PathBuf>,
+    print_config: bool,
+    fix: bool,
+    write: bool,
+    baseline: Option<PathBuf>,
+    report_suppressed: bool,
+    format: LintFormatArg,
+) -> Result<()> {
+    // Read the spec file
+    let mut spec_content = std::fs::read_to_string(&spec)
+        .with_context(|| format!("Failed to read spec file: {}", spec.display()))?;
+
+    // Load oas-lint.toml; command-line options override it
+    let config_path = config
+        .or_else(|| lint::LintConfig::discover(spec.parent().unwrap_or(std::path::Path::new(""))));
+    let lint_config = match config_path {
+        Some(path) => lint::LintConfig::from_path(&path)
+            .with_context(|| format!("Failed to load lint config: {}", path.display()))?,
+        None => lint::LintConfig::default(),
+    };
 
     // Build the ruleset based on provided options
     let rule_set = if let Some(ruleset_str) = rulesets {
@@ -315,8 +1702,19 @@ fn handle_lint(
         let rule_names: Vec<&str> = rules_str.split(',').map(|s| s.trim()).collect();
         lint::RuleSet::from_slice(&rule_names)
     } else {
-        // Default to all rules
-        lint::RuleSet::all()
+        build_ruleset_from_names(lint_config.ruleset.as_deref().unwrap_or("core"))?
+    };
+
+    // Narrow to the requested categories
+    let rule_set = if let Some(only_str) = only {
+        rule_set.intersect(parse_categories(&only_str)?)
+    } else {
+        rule_set
+    };
+
+    let category_severity = match category_severity {
+        Some(overrides) => lint::CategorySeverity::parse(&overrides).map_err(anyhow::Error::msg)?,
+        None => lint::CategorySeverity::default(),
     };
 
     // Parse severity filter
@@ -326,18 +1724,116 @@ fn handle_lint(
         None
     };
 
+    let mut linter = lint::Linter::from_config(&lint_config)?
+        .with_rule_set(rule_set)
+        .with_category_severity(category_severity);
+    if let Some(path) = spectral {
+        let ruleset = lint::SpectralRuleset::from_path(&path)
+            .with_context(|| format!("Failed to load Spectral ruleset: {}", path.display()))?;
+        linter = linter.with_rules(ruleset.into_rules());
+    }
+
+    if print_config {
+        print!("{}", linter.effective_config().to_toml());
+        return Ok(());
+    }
+
+    if fix {
+        let fixed = linter
+            .apply_fixes(&spec_content)
+            .context("Failed to fix specification")?;
+        if write {
+            if fixed.changed() {
+                std::fs::write(&spec, &fixed.spec)
+                    .with_context(|| format!("Failed to write spec file: {}", spec.display()))?;
+                spec_content = fixed.spec;
+            }
+            eprintln!(
+                "🔧 Applied {} fix(es) to {}",
+                fixed.applied.len(),
+                spec.display()
+            );
+        } else {
+            for diagnostic in &fixed.applied {
+                let description = diagnostic
+                    .autofix
+                    .as_ref()
+                    .map_or(diagnostic.message.as_str(), |fix| fix.description.as_str());
+                eprintln!(
+                    "🔧 {}:{}: {}",
+                    spec.display(),
+                    diagnostic.range.start.line + 1,
+                    description
+                );
+            }
+            if fixed.changed() {
+                eprintln!(
+                    "   Rerun with --write to apply {} fix(es) to {}",
+                    fixed.applied.len(),
+                    spec.display()
+                );
+            }
+        }
+    }
+
     // Run the linter
-    let result =
-        lint::lint_with_ruleset(&spec_content, rule_set).context("Failed to lint specification")?;
+    let mut result = linter
+        .lint(&spec_content)
+        .context("Failed to lint specification")?;
+
+    // Breaking changes against the previous version
+    if let Some(baseline) = baseline {
+        let old_content = std::fs::read_to_string(&baseline)
+            .with_context(|| format!("Failed to read baseline spec: {}", baseline.display()))?;
+        let breaking = lint::compare(&old_content, &spec_content)
+            .context("Failed to compare against baseline")?;
+        result.diagnostics.extend(breaking.diagnostics);
+        result.diagnostics.sort_by_key(|d| d.byte_span.start);
+        for (rule, count) in breaking.suppressed {
+            *result.suppressed.entry(rule).or_insert(0) += count;
+        }
+    }
+
+    if report_suppressed {
+        eprintln!(
+            "🔇 {} finding(s) suppressed by x-lint-ignore",
+            result.suppressed_count()
+        );
+        for (rule, count) in &result.suppressed {
+            eprintln!("   {}: {}", rule, count);
+        }
+    }
 
-    // Print diagnostics in rustc-style format
+    let filtered = lint::Validation {
+        diagnostics: result
+            .diagnostics
+            .iter()
+            .filter(|d| {
+                severity_filter
+                    .as_ref()
+                    .is_none_or(|f| f.contains(&d.severity))
+            })
+            .cloned()
+            .collect(),
+        parsed_ok: result.parsed_ok,
+        suppressed: result.suppressed.clone(),
+    };
+    let reporter: Box<dyn lint::Reporter> = match format {
+        LintFormatArg::Pretty => {
+            Box::new(lint::PrettyReporter::new().with_color(std::io::stdout().is_terminal()))
+        }
+        LintFormatArg::Json => lint::ReportFormat::Json.reporter(),
+        LintFormatArg::Sarif => lint::ReportFormat::Sarif.reporter(),
+    };
     let spec_display = spec.display().to_string();
-    print_diagnostics(
-        &result,
-        &spec_display,
-        &spec_content,
-        severity_filter.as_ref(),
-    );
+    reporter.report(
+        &filtered,
+        lint::SpecFile {
+            path: &spec_display,
+            content: &spec_content,
+        },
+        &mut std::io::stdout().lock(),
+    )?;
 
     // Exit with error code if there are errors (only if we're showing errors)
     if result.has_errors()
@@ -374,198 +1870,32 @@ fn parse_severity_filter(severity_str: &str) -> Result<Vec<lint::Severity>> {
     Ok(severities)
 }
 
-fn build_ruleset_from_names(ruleset_str: &str) -> Result<lint::RuleSet> {
-    let mut rule_set = lint::RuleSet::new();
+fn parse_categories(categories_str: &str) -> Result<lint::RuleSet> {
+    let mut categories = Vec::new();
 
-    for name in ruleset_str.split(',').map(|s| s.trim()) {
-        match name {
-            "all" => {
-                rule_set = lint::RuleSet::all();
-            }
-            "recommended" => {
-                // Add recommended rules for good API design
-                for rule in &[
-                    "openapi-version-31",
-                    "info-title-required",
-                    "info-version-required",
-                    "info-description-present",
-                    "paths-not-empty",
-                    "operation-id-required",
-                    "operation-id-unique",
-                    "responses-exist",
-                    "response-description-required",
-                    "path-params-declared",
-                    "path-params-required",
-                ] {
-                    rule_set.enable(lint::RuleId::parse(rule).unwrap());
-                }
-            }
-            "strict" => {
-                // Add all error-level rules
-                for rule in lint::available_rules() {
-                    if matches!(rule.default_severity(), lint::Severity::Error) {
-                        rule_set.enable(*rule);
-                    }
-                }
-            }
-            "security" => {
-                // Security-focused rules
-                for rule in &[
-                    "servers-https-required",
-                    "security-schemes-defined",
-                    "security-declared",
-                    "security-no-api-key-in-query",
-                    "security-oauth2-complete",
-                ] {
-                    if let Some(rule_id) = lint::RuleId::parse(rule) {
-                        rule_set.enable(rule_id);
-                    }
-                }
-            }
-            "style" => {
-                // Style and consistency rules
-                for rule in &[
-                    "path-style-normalized",
-                    "path-no-verbs",
-                    "param-naming-consistent",
-                    "schema-naming-conventions",
-                    "operation-summary-required",
-                ] {
-                    if let Some(rule_id) = lint::RuleId::parse(rule) {
-                        rule_set.enable(rule_id);
-                    }
-                }
-            }
-            other => {
+    for name in categories_str.split(',').map(|s| s.trim()) {
+        match lint::RuleCategory::parse(name) {
+            Some(category) => categories.push(category),
+            None => {
                 anyhow::bail!(
-                    "Unknown ruleset: '{}'. Available rulesets: all, recommended, strict, security, style",
-                    other
+                    "Unknown rule category: '{}'. Available categories: correctness, security, style, documentation, compatibility",
+                    name
                 );
             }
         }
     }
 
-    Ok(rule_set)
+    Ok(lint::RuleSet::from_categories(&categories))
 }
 
-fn print_diagnostics(
-    result: &lint::Validation,
-    spec_path: &str,
-    spec_content: &str,
-    severity_filter: Option<&Vec<lint::Severity>>,
-) {
-    // Filter diagnostics by severity if specified
-    let filtered_diagnostics: Vec<_> = result
-        .diagnostics
-        .iter()
-        .filter(|d| {
-            if let Some(filter) = severity_filter {
-                filter.contains(&d.severity)
-            } else {
-                true
-            }
-        })
-        .collect();
-
-    if filtered_diagnostics.is_empty() {
-        println!("✅ No issues found");
-        return;
-    }
-
-    let lines: Vec<&str> = spec_content.lines().collect();
-    let error_count = filtered_diagnostics
-        .iter()
-        .filter(|d| matches!(d.severity, lint::Severity::Error))
-        .count();
-    let warning_count = filtered_diagnostics
-        .iter()
-        .filter(|d| matches!(d.severity, lint::Severity::Warning))
-        .count();
-
-    for diag in &filtered_diagnostics {
-        let severity_str = match diag.severity {
-            lint::Severity::Error => "\x1b[1;31merror\x1b[0m",
-            lint::Severity::Warning => "\x1b[1;33mwarning\x1b[0m",
-            lint::Severity::Info => "\x1b[1;36minfo\x1b[0m",
-            lint::Severity::Hint => "\x1b[1;34mhint\x1b[0m",
-        };
-
-        // Print the main diagnostic line
-        println!(
-            "{}: {}\x1b[1m[{}]\x1b[0m",
-            severity_str,
-            diag.message,
-            diag.rule.as_str()
-        );
-
-        // Print location
-        let line_num = diag.range.start.line + 1;
-        let col_num = diag.range.start.col + 1;
-        println!(
-            "  \x1b[1;36m-->\x1b[0m {}:{}:{}",
-            spec_path, line_num, col_num
-        );
-
-        // Print the source line with context
-        if let Some(line_text) = lines.get(diag.range.start.line as usize) {
-            // Calculate gutter width based on line number
-            let gutter_width = line_num.to_string().len();
-            let gutter_padding = " ".repeat(gutter_width);
-
-            println!("{} \x1b[1;36m|\x1b[0m", gutter_padding);
-            println!(
-                "\x1b[1;36m{:>width$} |\x1b[0m {}",
-                line_num,
-                line_text,
-                width = gutter_width
-            );
-
-            // Print the caret indicator
-            let spaces = " ".repeat(diag.range.start.col as usize);
-            let carets = if diag.range.end.line == diag.range.start.line {
-                let len = (diag.range.end.col - diag.range.start.col).max(1);
-                "^".repeat(len as usize)
-            } else {
-                "^".to_string()
-            };
-
-            let caret_color = match diag.severity {
-                lint::Severity::Error => "\x1b[1;31m",
-                lint::Severity::Warning => "\x1b[1;33m",
-                lint::Severity::Info => "\x1b[1;36m",
-                lint::Severity::Hint => "\x1b[1;34m",
-            };
-
-            println!(
-                "{} \x1b[1;36m|\x1b[0m {}{}{}\x1b[0m",
-                gutter_padding, spaces, caret_color, carets
-            );
-            println!("{} \x1b[1;36m|\x1b[0m", gutter_padding);
-        }
-
-        println!();
-    }
-
-    // Print summary
-    let mut summary_parts = Vec::new();
-    if error_count > 0 {
-        summary_parts.push(format!(
-            "\x1b[1;31m{} error{}\x1b[0m",
-            error_count,
-            if error_count == 1 { "" } else { "s" }
-        ));
-    }
-    if warning_count > 0 {
-        summary_parts.push(format!(
-            "\x1b[1;33m{} warning{}\x1b[0m",
-            warning_count,
-            if warning_count == 1 { "" } else { "s" }
-        ));
-    }
-
-    if !summary_parts.is_empty() {
-        println!("{}", summary_parts.join(", "));
-    }
+fn build_ruleset_from_names(ruleset_str: &str) -> Result<lint::RuleSet> {
+    lint::RuleSet::named(ruleset_str).with_context(|| {
+        format!(
+            "Unknown ruleset in '{}'. Available rulesets: {}, or a category: correctness, security, style, documentation, compatibility",
+            ruleset_str,
+            lint::RuleSet::PRESETS.join(", ")
+        )
+    })
 }
 
 fn handle_merge(files: Vec<PathBuf>, output: Option<PathBuf>, verbose: bool) -> Result<()> {
@@ -614,13 +1944,7 @@ fn handle_merge(files: Vec<PathBuf>, output: Option<PathBuf>, verbose: bool) ->
             serde_json::from_str(&content)
                 .with_context(|| format!("Failed to parse JSON: {}", file.display()))?
         } else {
-            // Use strict_booleans to avoid YAML 1.1 quirks where y/n/yes/no/on/off
-            // are interpreted as booleans instead of strings
-            let options = serde_saphyr::Options {
-                strict_booleans: true,
-                ..Default::default()
-            };
-            serde_saphyr::from_str_with_options(&content, options)
+            parser::yaml::from_str(&content)
                 .with_context(|| format!("Failed to parse YAML: {}", file.display()))?
         };
 
@@ -0,0 +1,206 @@
+//! Exporting a [`VirtualFS`] as a zip or tar archive, or as one stream of
+//! delimited files.
+//!
+//! Services that generate SDKs on demand can hand the output out as a
+//! download without touching the filesystem. The zip and tar writers need
+//! the `archive` feature:
+//!
+//! ```no_run
+//! # #[cfg(feature = "archive")] {
+//! # let vfs = codegen::VirtualFS::new();
+//! let zip = vfs.write_zip(std::io::Cursor::new(Vec::new()))?.into_inner();
+//! # }
+//! # Ok::<(), std::io::Error>(())
+//! ```
+//!
+//! Entries are written in path order with a fixed timestamp, so the same
+//! VFS always produces the same bytes. Wrap the writer of
+//! [`write_tar`](VirtualFS::write_tar) in a gzip encoder for a `.tar.gz`.
+//! [`write_concatenated`](VirtualFS::write_concatenated) is for readers of
+//! plain text, such as code playgrounds and review bots.
+
+#[cfg(feature = "archive")]
+use std::io::Seek;
+use std::io::{self, Write};
+use std::path::{Component, Path};
+
+use crate::VirtualFS;
+
+impl VirtualFS {
+    /// Write every file into a zip archive on `writer`, deflate-compressed,
+    /// and return the writer. Files keep their [`mode`](Self::mode).
+    #[cfg(feature = "archive")]
+    pub fn write_zip<W: Write + Seek>(&self, writer: W) -> io::Result<W> {
+        let mut zip = zip::ZipWriter::new(writer);
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated)
+            .last_modified_time(zip::DateTime::default());
+        for (path, content) in self.files() {
+            let options = options.unix_permissions(self.mode(path));
+            zip.start_file(archive_path(path)?, options)
+                .map_err(io::Error::other)?;
+            zip.write_all(content)?;
+        }
+        zip.finish().map_err(io::Error::other)
+    }
+
+    /// Write every file into a tar archive on `writer` and return the
+    /// writer. Files keep their [`mode`](Self::mode).
+    #[cfg(feature = "archive")]
+    pub fn write_tar<W: Write>(&self, writer: W) -> io::Result<W> {
+        let mut tar = tar::Builder::new(writer);
+        for (path, content) in self.files() {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(self.mode(path));
+            header.set_mtime(0);
+            tar.append_data(&mut header, archive_path(path)?, content)?;
+        }
+        tar.into_inner()
+    }
+
+    /// Write every file to `writer` in path order, each after a
+    /// `==> path <==` line (as `head` prints several files), and return the
+    /// writer. A newline is added to files that don't end with one, so every
+    /// delimiter starts a line.
+    pub fn write_concatenated<W: Write>(&self, mut writer: W) -> io::Result<W> {
+        for (path, content) in self.files() {
+            writeln!(writer, "==> {} <==", archive_path(path)?)?;
+            writer.write_all(content)?;
+            if !content.is_empty() && !content.ends_with(b"\n") {
+                writer.write_all(b"\n")?;
+            }
+        }
+        Ok(writer)
+    }
+}
+
+/// `path` with `/` separators, as archives store it. Paths that would
+/// escape the archive root are rejected.
+fn archive_path(path: &Path) -> io::Result<String> {
+    let mut parts = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_string_lossy()),
+            Component::CurDir => {}
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("{} can't be archived outside the root", path.display()),
+                ));
+            }
+        }
+    }
+    Ok(parts.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "archive")]
+    use std::io::{Cursor, Read};
+
+    #[cfg(feature = "archive")]
+    fn sample() -> VirtualFS {
+        let mut vfs = VirtualFS::new();
+        vfs.add_file("src/index.ts", "export * from './types';\n");
+        vfs.add_file("package.json", "{}\n");
+        vfs.add_binary("assets/logo.png", vec![0x89, b'P', b'N', b'G']);
+        vfs.add_file("scripts/build.sh", "#!/bin/sh\nnpm run build\n");
+        vfs.set_executable("scripts/build.sh");
+        vfs
+    }
+
+    #[test]
+    #[cfg(feature = "archive")]
+    fn test_write_zip() {
+        let vfs = sample();
+        let bytes = vfs.write_zip(Cursor::new(Vec::new())).unwrap().into_inner();
+        assert_eq!(
+            vfs.write_zip(Cursor::new(Vec::new())).unwrap().into_inner(),
+            bytes
+        );
+
+        let mut zip = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+        assert_eq!(
+            zip.file_names().collect::<std::collections::BTreeSet<_>>(),
+            [
+                "assets/logo.png",
+                "package.json",
+                "scripts/build.sh",
+                "src/index.ts"
+            ]
+            .into_iter()
+            .collect()
+        );
+        let mut content = String::new();
+        zip.by_name("src/index.ts")
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        assert_eq!(content, "export * from './types';\n");
+        assert_eq!(
+            zip.by_name("scripts/build.sh")
+                .unwrap()
+                .unix_mode()
+                .map(|mode| mode & 0o777),
+            Some(0o755)
+        );
+        assert_eq!(
+            zip.by_name("package.json")
+                .unwrap()
+                .unix_mode()
+                .map(|mode| mode & 0o777),
+            Some(0o644)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "archive")]
+    fn test_write_tar() {
+        let vfs = sample();
+        let bytes = vfs.write_tar(Vec::new()).unwrap();
+
+        let mut archive = tar::Archive::new(bytes.as_slice());
+        let mut files = Vec::new();
+        for entry in archive.entries().unwrap() {
+            let mut entry = entry.unwrap();
+            let path = entry.path().unwrap().to_string_lossy().into_owned();
+            let mode = entry.header().mode().unwrap();
+            assert_eq!(mode, vfs.mode(Path::new(&path)));
+            let mut content = Vec::new();
+            entry.read_to_end(&mut content).unwrap();
+            files.push((path, content));
+        }
+        assert_eq!(
+            files,
+            vfs.files()
+                .map(|(path, content)| (archive_path(path).unwrap(), content.to_vec()))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_write_concatenated() {
+        let mut vfs = VirtualFS::new();
+        vfs.add_file("src/index.ts", "export * from './types';\n");
+        vfs.add_file("package.json", "{}");
+        vfs.add_file(".gitkeep", "");
+        let stream = vfs.write_concatenated(Vec::new()).unwrap();
+        assert_eq!(
+            String::from_utf8(stream).unwrap(),
+            "==> .gitkeep <==\n\
+             ==> package.json <==\n{}\n\
+             ==> src/index.ts <==\nexport * from './types';\n"
+        );
+    }
+
+    #[test]
+    fn test_rejects_escaping_paths() {
+        let mut vfs = VirtualFS::new();
+        vfs.add_file("../outside.txt", "");
+        let err = vfs.write_concatenated(Vec::new()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+}
@@ -0,0 +1,346 @@
+//! Errors traced back to the IR and the spec.
+//!
+//! A bare "template error" doesn't say which schema broke the template.
+//! [`Diagnostic`] wraps an [`Error`] with what was being generated: the
+//! [`Phase`], the type or operation ([`Subject`]) and a JSON pointer into
+//! the spec. Generators attach the context with [`ResultExt`]:
+//!
+//! ```ignore
+//! use codegen::diagnostic::ResultExt;
+//!
+//! for decl in ir.types.values() {
+//!     let rendered = self.render_type(decl).in_type(decl)?;
+//! }
+//! ```
+//!
+//! Once the registry adds the phase, a failure there reads:
+//!
+//! ```text
+//! generate: type `Pet` (#/components/schemas/Pet): Template error: ...
+//! ```
+//!
+//! Context is added from the inside out and the innermost wins, so a
+//! generator's subject isn't replaced by a caller's.
+//!
+//! Compiler messages about the generated code
+//! ([`verify`](crate::verify)) are diagnostics too, with an
+//! [`Error::Compiler`] and the [`Location`] in the output.
+
+use std::fmt;
+use std::path::PathBuf;
+
+use ir::gen_ir::{Operation, StableId, TypeDecl};
+
+use crate::{Error, Result};
+
+/// Step of generation an error happened in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Linting the spec, when a lint threshold is set.
+    Lint,
+    /// Reading the spec and resolving its references.
+    Parse,
+    /// Building the [`GenIr`](crate::GenIr) from the spec.
+    BuildIr,
+    /// [`Generator::validate`](crate::Generator::validate).
+    Validate,
+    /// [`Generator::before_generate`](crate::Generator::before_generate).
+    BeforeGenerate,
+    /// Rendering files.
+    Generate,
+    /// [`Generator::after_generate`](crate::Generator::after_generate).
+    AfterGenerate,
+    /// Writing files to disk.
+    Write,
+    /// [`Generator::post_processors`](crate::Generator::post_processors),
+    /// such as formatters.
+    Format,
+    /// [`Generator::after_write_to_disk`](crate::Generator::after_write_to_disk).
+    AfterWrite,
+    /// [`Generator::verifier`](crate::Generator::verifier).
+    Verify,
+}
+
+impl Phase {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Phase::Lint => "lint",
+            Phase::Parse => "parse",
+            Phase::BuildIr => "build_ir",
+            Phase::Validate => "validate",
+            Phase::BeforeGenerate => "before_generate",
+            Phase::Generate => "generate",
+            Phase::AfterGenerate => "after_generate",
+            Phase::Write => "write",
+            Phase::Format => "format",
+            Phase::AfterWrite => "after_write",
+            Phase::Verify => "verify",
+        }
+    }
+}
+
+impl serde::Serialize for Phase {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// The part of the IR an error is about.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Subject {
+    /// A type declaration.
+    Type(StableId),
+    /// An operation, with its method and path template for display.
+    Operation {
+        id: StableId,
+        method: String,
+        path: String,
+    },
+}
+
+impl fmt::Display for Subject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Subject::Type(id) => write!(f, "type `{}`", display_id(id)),
+            Subject::Operation { id, method, path } => {
+                write!(f, "operation `{}` ({} {})", display_id(id), method, path)
+            }
+        }
+    }
+}
+
+/// How bad a [`Diagnostic`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        }
+    }
+}
+
+/// A place in the generated output, e.g. where a compiler error is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    /// File relative to the output directory.
+    pub path: PathBuf,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path.display())?;
+        if let Some(line) = self.line {
+            write!(f, ":{}", line)?;
+            if let Some(column) = self.column {
+                write!(f, ":{}", column)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An [`Error`] with what was being generated when it happened.
+#[derive(Debug)]
+pub struct Diagnostic {
+    pub error: Error,
+    pub phase: Option<Phase>,
+    pub subject: Option<Subject>,
+    /// JSON pointer to the spec location, e.g. `#/components/schemas/Pet`.
+    pub pointer: Option<String>,
+    /// Where in the generated output, for compiler messages.
+    pub location: Option<Location>,
+}
+
+impl Diagnostic {
+    pub fn new(error: Error) -> Self {
+        Self {
+            error,
+            phase: None,
+            subject: None,
+            pointer: None,
+            location: None,
+        }
+    }
+
+    /// Warning for compiler warnings, error for everything else.
+    pub fn severity(&self) -> Severity {
+        match &self.error {
+            Error::Compiler { severity, .. } => *severity,
+            _ => Severity::Error,
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(phase) = self.phase {
+            write!(f, "{}: ", phase.as_str())?;
+        }
+        if let Some(subject) = &self.subject {
+            write!(f, "{}", subject)?;
+            if let Some(pointer) = &self.pointer {
+                write!(f, " ({})", pointer)?;
+            }
+            write!(f, ": ")?;
+        } else if let Some(pointer) = &self.pointer {
+            write!(f, "{}: ", pointer)?;
+        }
+        if let Some(location) = &self.location {
+            write!(f, "{}: ", location)?;
+        }
+        write!(f, "{}", self.error)
+    }
+}
+
+impl Error {
+    /// The context of this error, if any was attached.
+    pub fn diagnostic(&self) -> Option<&Diagnostic> {
+        match self {
+            Error::Diagnostic(diagnostic) => Some(diagnostic),
+            _ => None,
+        }
+    }
+
+    /// This error, in the context of `phase` unless it already has one.
+    pub fn in_phase(self, phase: Phase) -> Error {
+        self.with(|diagnostic| {
+            diagnostic.phase.get_or_insert(phase);
+        })
+    }
+
+    /// This error, about the type `decl` unless it's already about a type
+    /// or operation. Points at the schema the type came from.
+    pub fn in_type(self, decl: &TypeDecl) -> Error {
+        self.with(|diagnostic| {
+            if diagnostic.subject.is_none() {
+                diagnostic.subject = Some(Subject::Type(decl.id.clone()));
+                diagnostic.pointer = diagnostic.pointer.take().or(decl.origin.clone());
+            }
+        })
+    }
+
+    /// This error, about the operation `op` unless it's already about a
+    /// type or operation. Points at the operation in the spec's `paths`.
+    pub fn in_operation(self, op: &Operation) -> Error {
+        self.with(|diagnostic| {
+            if diagnostic.subject.is_none() {
+                let method = format!("{:?}", op.http.method).to_uppercase();
+                diagnostic.pointer = diagnostic.pointer.take().or_else(|| {
+                    Some(format!(
+                        "#/paths/{}/{}",
+                        escape_pointer(&op.http.path_template),
+                        method.to_lowercase()
+                    ))
+                });
+                diagnostic.subject = Some(Subject::Operation {
+                    id: op.id.clone(),
+                    method,
+                    path: op.http.path_template.clone(),
+                });
+            }
+        })
+    }
+
+    fn with(self, f: impl FnOnce(&mut Diagnostic)) -> Error {
+        let mut diagnostic = match self {
+            Error::Diagnostic(diagnostic) => diagnostic,
+            error => Box::new(Diagnostic::new(error)),
+        };
+        f(&mut diagnostic);
+        Error::Diagnostic(diagnostic)
+    }
+}
+
+/// Attaching [`Diagnostic`] context to results.
+pub trait ResultExt<T> {
+    /// See [`Error::in_phase`].
+    fn in_phase(self, phase: Phase) -> Result<T>;
+    /// See [`Error::in_type`].
+    fn in_type(self, decl: &TypeDecl) -> Result<T>;
+    /// See [`Error::in_operation`].
+    fn in_operation(self, op: &Operation) -> Result<T>;
+}
+
+impl<T> ResultExt<T> for Result<T> {
+    fn in_phase(self, phase: Phase) -> Result<T> {
+        self.map_err(|e| e.in_phase(phase))
+    }
+
+    fn in_type(self, decl: &TypeDecl) -> Result<T> {
+        self.map_err(|e| e.in_type(decl))
+    }
+
+    fn in_operation(self, op: &Operation) -> Result<T> {
+        self.map_err(|e| e.in_operation(op))
+    }
+}
+
+/// `Pet` for named types instead of their debug form.
+fn display_id(id: &StableId) -> String {
+    match id {
+        StableId::Named(name) => name.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// `path` as a JSON pointer token (RFC 6901).
+fn escape_pointer(path: &str) -> String {
+    path.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_innermost_context_wins() {
+        let decl = TypeDecl {
+            id: StableId::new("Pet"),
+            name: ir::gen_ir::CanonicalName::from_string("Pet"),
+            docs: Default::default(),
+            kind: ir::gen_ir::TypeKind::Struct {
+                fields: vec![],
+                additional: ir::gen_ir::Additional::Forbidden,
+                discriminator: None,
+            },
+            origin: Some("#/components/schemas/Pet".to_string()),
+            tags: Default::default(),
+        };
+        let other = TypeDecl {
+            id: StableId::new("Owner"),
+            origin: None,
+            ..decl.clone()
+        };
+
+        let err: Result<()> = Err(Error::Custom("missing template".to_string()));
+        let err = err
+            .in_type(&decl)
+            .in_type(&other)
+            .in_phase(Phase::Generate)
+            .in_phase(Phase::Write)
+            .unwrap_err();
+        let diagnostic = err.diagnostic().unwrap();
+        assert_eq!(
+            diagnostic.subject,
+            Some(Subject::Type(StableId::new("Pet")))
+        );
+        assert_eq!(diagnostic.phase, Some(Phase::Generate));
+        assert_eq!(
+            err.to_string(),
+            "generate: type `Pet` (#/components/schemas/Pet): missing template"
+        );
+        assert!(matches!(diagnostic.error, Error::Custom(_)));
+        assert_eq!(escape_pointer("/pets/{id}"), "~1pets~1{id}");
+    }
+}
@@ -0,0 +1,317 @@
+//! Comparing generated output with an output directory.
+//!
+//! [`VirtualFS::diff`] reports which files writing the VFS would add,
+//! change or delete, with a unified diff for each text file. CI can use it
+//! to check that checked-in generated code is up to date:
+//!
+//! ```no_run
+//! # use codegen::VirtualFS;
+//! # use std::path::Path;
+//! # let vfs = VirtualFS::new();
+//! let diff = vfs.diff(Path::new("sdk"))?;
+//! if !diff.is_empty() {
+//!     eprint!("{}", diff);
+//!     std::process::exit(1);
+//! }
+//! # Ok::<(), codegen::Error>(())
+//! ```
+
+use std::borrow::Cow;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use similar::TextDiff;
+
+use crate::{Manifest, Result, VirtualFS, protected};
+
+/// Directories of an output directory that are never generated: VCS
+/// metadata, installed dependencies, build output and the manifest.
+pub const IGNORED_DIRS: &[&str] = &[".git", "node_modules", "target", "dist", ".oas-gen"];
+
+/// Lines of unchanged context around each hunk.
+const CONTEXT_LINES: usize = 3;
+
+/// How [`VirtualFS::write_to_disk_with`] treats the output directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WriteMode {
+    /// Write every file.
+    #[default]
+    Write,
+    /// Only compute what would change; nothing is written.
+    DryRun,
+}
+
+/// What writing a file would do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ChangeKind {
+    /// The file doesn't exist on disk yet.
+    Added,
+    /// The file exists with different content.
+    Changed,
+    /// The file exists on disk but isn't generated any more.
+    Deleted,
+}
+
+impl ChangeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChangeKind::Added => "added",
+            ChangeKind::Changed => "changed",
+            ChangeKind::Deleted => "deleted",
+        }
+    }
+}
+
+/// One file that differs between the VFS and the output directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileChange {
+    /// Path relative to the output directory.
+    pub path: PathBuf,
+    pub kind: ChangeKind,
+    /// Unified diff from the file on disk to the generated one, or `None`
+    /// when either side isn't UTF-8.
+    pub diff: Option<String>,
+}
+
+/// Files that differ between a [`VirtualFS`] and an output directory, in
+/// path order. Displays as one unified diff.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VfsDiff {
+    pub changes: Vec<FileChange>,
+}
+
+impl VfsDiff {
+    /// Whether the output directory already matches the VFS.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Changes of one kind.
+    pub fn of_kind(&self, kind: ChangeKind) -> impl Iterator<Item = &FileChange> {
+        self.changes
+            .iter()
+            .filter(move |change| change.kind == kind)
+    }
+
+    /// `2 added, 1 changed, 0 deleted`.
+    pub fn summary(&self) -> String {
+        [ChangeKind::Added, ChangeKind::Changed, ChangeKind::Deleted]
+            .iter()
+            .map(|kind| format!("{} {}", self.of_kind(*kind).count(), kind.as_str()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+impl fmt::Display for VfsDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            let path = change.path.display();
+            match &change.diff {
+                Some(diff) => write!(f, "{}", diff)?,
+                None => writeln!(f, "Binary files a/{} and b/{} differ", path, path)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl VirtualFS {
+    /// Compare the VFS with the files under `base_path`. Files listed in
+    /// its [`Manifest`] that the VFS doesn't contain are reported as
+    /// deleted, unless they were edited by hand since (writing keeps those).
+    /// Without a manifest nothing is: the other files on disk may be the
+    /// user's own. A missing `base_path` means every file is added.
+    pub fn diff(&self, base_path: &Path) -> Result<VfsDiff> {
+        let generated = match Manifest::read(base_path)? {
+            Some(manifest) => {
+                let edited = manifest.edited(base_path)?;
+                manifest
+                    .paths()
+                    .filter(|path| !edited.iter().any(|edited| edited == path))
+                    .map(Path::to_path_buf)
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+        self.diff_against(base_path, generated, true)
+    }
+
+    /// Compare the VFS with a directory that should hold exactly its files,
+    /// such as golden files: every other file under `dir` is reported as
+    /// deleted, except inside [`IGNORED_DIRS`], and files are compared byte
+    /// for byte, without splicing in protected regions.
+    pub fn diff_exact(&self, dir: &Path) -> Result<VfsDiff> {
+        let mut on_disk = Vec::new();
+        if dir.is_dir() {
+            collect_files(dir, Path::new(""), &mut on_disk)?;
+        }
+        self.diff_against(dir, on_disk, false)
+    }
+
+    /// Compare with `base_path`, reporting the files of `generated` the VFS
+    /// doesn't contain as deleted. `keep_regions` compares each file with
+    /// the protected regions on disk spliced in, as writing would.
+    fn diff_against(
+        &self,
+        base_path: &Path,
+        generated: Vec<PathBuf>,
+        keep_regions: bool,
+    ) -> Result<VfsDiff> {
+        let mut changes = Vec::new();
+        for (path, content) in self.files() {
+            let full_path = base_path.join(path);
+            if !full_path.is_file() {
+                changes.push(file_change(path, ChangeKind::Added, None, content));
+                continue;
+            }
+            let existing = std::fs::read(&full_path)?;
+            let content = if keep_regions {
+                protected::keep_regions(path, content, Some(existing.as_slice()))?
+            } else {
+                Cow::Borrowed(content)
+            };
+            if existing != *content {
+                changes.push(file_change(
+                    path,
+                    ChangeKind::Changed,
+                    Some(existing.as_slice()),
+                    &content,
+                ));
+            }
+        }
+
+        for path in generated {
+            if !self.contains(&path) && base_path.join(&path).is_file() {
+                let existing = std::fs::read(base_path.join(&path))?;
+                changes.push(file_change(
+                    &path,
+                    ChangeKind::Deleted,
+                    Some(existing.as_slice()),
+                    &[],
+                ));
+            }
+        }
+
+        changes.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(VfsDiff { changes })
+    }
+
+    /// Write the VFS under `base_path` according to `mode`, returning what
+    /// changed (or, for [`WriteMode::DryRun`], what would have).
+    pub fn write_to_disk_with(&self, base_path: &Path, mode: WriteMode) -> Result<VfsDiff> {
+        let diff = self.diff(base_path)?;
+        if mode == WriteMode::Write {
+            self.write_to_disk(base_path)?;
+        }
+        Ok(diff)
+    }
+}
+
+fn file_change(path: &Path, kind: ChangeKind, old: Option<&[u8]>, new: &[u8]) -> FileChange {
+    let old_text = std::str::from_utf8(old.unwrap_or_default());
+    let new_text = std::str::from_utf8(new);
+    let diff = match (old_text, new_text) {
+        (Ok(old_text), Ok(new_text)) => {
+            let path = path.display();
+            let old_header = match kind {
+                ChangeKind::Added => "/dev/null".to_string(),
+                _ => format!("a/{}", path),
+            };
+            let new_header = match kind {
+                ChangeKind::Deleted => "/dev/null".to_string(),
+                _ => format!("b/{}", path),
+            };
+            Some(
+                TextDiff::from_lines(old_text, new_text)
+                    .unified_diff()
+                    .context_radius(CONTEXT_LINES)
+                    .header(&old_header, &new_header)
+                    .to_string(),
+            )
+        }
+        _ => None,
+    };
+    FileChange {
+        path: path.to_path_buf(),
+        kind,
+        diff,
+    }
+}
+
+/// Relative paths of the files under `dir`, skipping [`IGNORED_DIRS`].
+fn collect_files(root: &Path, dir: &Path, out: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in std::fs::read_dir(root.join(dir))? {
+        let entry = entry?;
+        let path = dir.join(entry.file_name());
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            if !IGNORED_DIRS
+                .iter()
+                .any(|ignored| entry.file_name() == *ignored)
+            {
+                collect_files(root, &path, out)?;
+            }
+        } else if file_type.is_file() {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_against_output_dir() {
+        let dir = std::env::temp_dir().join(format!("oas-gen-diff-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let mut vfs = VirtualFS::new();
+        vfs.add_file("src/index.ts", "export * from './types';\n");
+        vfs.add_file("src/types.ts", "export type Id = string;\n");
+        let diff = vfs.diff(&dir).unwrap();
+        assert_eq!(diff.summary(), "2 added, 0 changed, 0 deleted");
+
+        // Without a manifest other files may be the user's own
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(dir.join("src/old.ts"), "export {};\n").unwrap();
+        std::fs::create_dir_all(dir.join("node_modules/pkg")).unwrap();
+        std::fs::write(dir.join("node_modules/pkg/index.js"), "").unwrap();
+        let diff = vfs.diff(&dir).unwrap();
+        assert_eq!(diff.summary(), "2 added, 0 changed, 0 deleted");
+
+        // ... unless the directory should hold exactly the generated files
+        let diff = vfs.diff_exact(&dir).unwrap();
+        assert_eq!(diff.summary(), "2 added, 0 changed, 1 deleted");
+        assert_eq!(
+            diff.changes[1].diff.as_deref(),
+            Some("--- a/src/old.ts\n+++ /dev/null\n@@ -1 +0,0 @@\n-export {};\n")
+        );
+
+        vfs.write_to_disk(&dir).unwrap();
+        vfs.add_file("src/types.ts", "export type Id = number;\n");
+        let diff = vfs.write_to_disk_with(&dir, WriteMode::DryRun).unwrap();
+        let found: Vec<(&Path, ChangeKind)> = diff
+            .changes
+            .iter()
+            .map(|change| (change.path.as_path(), change.kind))
+            .collect();
+        assert_eq!(found, [(Path::new("src/types.ts"), ChangeKind::Changed)]);
+        assert_eq!(
+            diff.to_string(),
+            "--- a/src/types.ts\n+++ b/src/types.ts\n@@ -1 +1 @@\n-export type Id = string;\n+export type Id = number;\n"
+        );
+        // Dry runs leave the directory alone
+        assert_eq!(
+            std::fs::read_to_string(dir.join("src/types.ts")).unwrap(),
+            "export type Id = string;\n"
+        );
+
+        vfs.write_to_disk_with(&dir, WriteMode::Write).unwrap();
+        assert!(vfs.diff(&dir).unwrap().is_empty());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
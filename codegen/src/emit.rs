@@ -0,0 +1,331 @@
+//! Building blocks for generators that emit code programmatically.
+//!
+//! Templates suit files with a fixed shape; code whose shape follows the
+//! spec is easier to build in Rust. [`CodeBuilder`] keeps track of
+//! indentation so nested blocks don't need hand-counted spaces,
+//! [`identifier`] turns a spec name into a valid identifier of the target
+//! language and [`PrimitiveTypes`] maps the IR's primitives to its types:
+//!
+//! ```
+//! use codegen::emit::{CodeBuilder, PrimitiveTypes, identifier};
+//! use ir::gen_ir::Primitive;
+//! use ir::naming::NamingPolicy;
+//!
+//! let mut code = CodeBuilder::rust();
+//! code.braces("pub struct Pet", |code| {
+//!     code.line(format!(
+//!         "pub {}: {},",
+//!         identifier("type", "rust", &NamingPolicy::default()),
+//!         PrimitiveTypes::RUST.get(Primitive::String)
+//!     ));
+//! });
+//! assert_eq!(code.finish(), "pub struct Pet {\n    pub r#type: String,\n}\n");
+//! ```
+
+use std::fmt;
+
+use ir::gen_ir::Primitive;
+use ir::naming::NamingPolicy;
+
+/// Text of generated code, built line by line at the current indentation.
+#[derive(Debug, Clone)]
+pub struct CodeBuilder {
+    out: String,
+    unit: String,
+    level: usize,
+}
+
+impl CodeBuilder {
+    /// A builder indenting each level with `unit`, e.g. a tab.
+    pub fn new(unit: impl Into<String>) -> Self {
+        Self {
+            out: String::new(),
+            unit: unit.into(),
+            level: 0,
+        }
+    }
+
+    /// A builder indenting with four spaces, as rustfmt does.
+    pub fn rust() -> Self {
+        Self::new("    ")
+    }
+
+    /// A builder indenting with two spaces, as Prettier does.
+    pub fn typescript() -> Self {
+        Self::new("  ")
+    }
+
+    /// Add a line at the current indentation. Empty lines aren't indented.
+    pub fn line(&mut self, line: impl AsRef<str>) -> &mut Self {
+        let line = line.as_ref();
+        if !line.is_empty() {
+            for _ in 0..self.level {
+                self.out.push_str(&self.unit);
+            }
+            self.out.push_str(line);
+        }
+        self.out.push('\n');
+        self
+    }
+
+    /// Add each line of `text` at the current indentation, e.g. a doc
+    /// comment or a pre-rendered fragment.
+    pub fn lines(&mut self, text: &str) -> &mut Self {
+        for line in text.lines() {
+            self.line(line);
+        }
+        self
+    }
+
+    /// Add an empty line.
+    pub fn blank(&mut self) -> &mut Self {
+        self.line("")
+    }
+
+    /// Indent the following lines one more level.
+    pub fn indent(&mut self) -> &mut Self {
+        self.level += 1;
+        self
+    }
+
+    /// Indent the following lines one level less.
+    pub fn dedent(&mut self) -> &mut Self {
+        self.level = self.level.saturating_sub(1);
+        self
+    }
+
+    /// Add the lines of `body` one level deeper.
+    pub fn indented(&mut self, body: impl FnOnce(&mut Self)) -> &mut Self {
+        self.indent();
+        body(self);
+        self.dedent()
+    }
+
+    /// Add `open`, the lines of `body` one level deeper, then `close`.
+    pub fn block(
+        &mut self,
+        open: impl AsRef<str>,
+        close: impl AsRef<str>,
+        body: impl FnOnce(&mut Self),
+    ) -> &mut Self {
+        self.line(open);
+        self.indented(body);
+        self.line(close)
+    }
+
+    /// A block in braces: `head {`, `body`, `}`.
+    pub fn braces(&mut self, head: impl AsRef<str>, body: impl FnOnce(&mut Self)) -> &mut Self {
+        self.block(format!("{} {{", head.as_ref()), "}", body)
+    }
+
+    /// Whether nothing was added yet.
+    pub fn is_empty(&self) -> bool {
+        self.out.is_empty()
+    }
+
+    /// The code, ending with a newline.
+    pub fn finish(self) -> String {
+        self.out
+    }
+
+    /// The code without its final newline, for joining with other
+    /// fragments.
+    pub fn into_fragment(mut self) -> String {
+        if self.out.ends_with('\n') {
+            self.out.pop();
+        }
+        self.out
+    }
+}
+
+impl Default for CodeBuilder {
+    fn default() -> Self {
+        Self::rust()
+    }
+}
+
+impl fmt::Display for CodeBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.out)
+    }
+}
+
+/// Whether `name` can be used as an identifier in `language` as it is:
+/// made of letters, digits and `_` (and `$` in TypeScript), not starting
+/// with a digit, and not a word `naming` reserves.
+pub fn is_identifier(name: &str, language: &str, naming: &NamingPolicy) -> bool {
+    let mut chars = name.chars();
+    let Some(first) = chars.next() else {
+        return false;
+    };
+    let valid = |c: char| c.is_alphanumeric() || c == '_' || (c == '$' && language == "typescript");
+    valid(first)
+        && !first.is_ascii_digit()
+        && chars.all(valid)
+        && !naming.is_reserved(name, language)
+}
+
+/// `name` as a valid identifier in `language`: other characters become
+/// `_`, a leading digit gets a `_` prefix, and reserved words are escaped
+/// with [`NamingPolicy::escape`] (`r#type` in Rust, `default_` in
+/// TypeScript, `__` for a bare `_` in Rust).
+pub fn identifier(name: &str, language: &str, naming: &NamingPolicy) -> String {
+    let mut ident: String = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '_' || (c == '$' && language == "typescript") {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+        ident.insert(0, '_');
+    }
+    naming.escape(&ident, language)
+}
+
+/// Names of the IR's [`Primitive`]s in a target language.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrimitiveTypes {
+    pub any: &'static str,
+    pub bool: &'static str,
+    pub i32: &'static str,
+    pub i64: &'static str,
+    pub f32: &'static str,
+    pub f64: &'static str,
+    pub string: &'static str,
+    pub date: &'static str,
+    pub date_time: &'static str,
+    pub uuid: &'static str,
+    pub bytes: &'static str,
+    pub decimal: &'static str,
+}
+
+impl PrimitiveTypes {
+    /// Rust, with dates from `jiff` and decimals from `rust_decimal`.
+    pub const RUST: Self = Self {
+        any: "serde_json::Value",
+        bool: "bool",
+        i32: "i32",
+        i64: "i64",
+        f32: "f32",
+        f64: "f64",
+        string: "String",
+        date: "jiff::civil::Date",
+        date_time: "jiff::Timestamp",
+        uuid: "uuid::Uuid",
+        bytes: "bytes::Bytes",
+        decimal: "rust_decimal::Decimal",
+    };
+
+    /// TypeScript, where every number is a `number`.
+    pub const TYPESCRIPT: Self = Self {
+        any: "any",
+        bool: "boolean",
+        i32: "number",
+        i64: "number",
+        f32: "number",
+        f64: "number",
+        string: "string",
+        date: "Date",
+        date_time: "Date",
+        uuid: "string",
+        bytes: "Uint8Array",
+        decimal: "number",
+    };
+
+    /// The built-in table of `language`, if there is one.
+    pub fn for_language(language: &str) -> Option<Self> {
+        match language {
+            "rust" => Some(Self::RUST),
+            "typescript" => Some(Self::TYPESCRIPT),
+            _ => None,
+        }
+    }
+
+    /// The type `primitive` is generated as.
+    pub fn get(&self, primitive: Primitive) -> &'static str {
+        match primitive {
+            Primitive::Any => self.any,
+            Primitive::Bool => self.bool,
+            Primitive::I32 => self.i32,
+            Primitive::I64 => self.i64,
+            Primitive::F32 => self.f32,
+            Primitive::F64 => self.f64,
+            Primitive::String => self.string,
+            Primitive::Date => self.date,
+            Primitive::DateTime => self.date_time,
+            Primitive::Uuid => self.uuid,
+            Primitive::Bytes => self.bytes,
+            Primitive::Decimal => self.decimal,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_code_builder() {
+        let mut code = CodeBuilder::typescript();
+        code.line("/** A pet. */")
+            .braces("export interface Pet", |code| {
+                code.line("name: string;");
+                code.lines("/** Age in years. */\nage?: number;");
+            })
+            .blank()
+            .block("export const PETS = [", "];", |code| {
+                code.line("'cat',");
+            });
+        assert_eq!(
+            code.to_string(),
+            "/** A pet. */\nexport interface Pet {\n  name: string;\n  /** Age in years. */\n  age?: number;\n}\n\nexport const PETS = [\n  'cat',\n];\n"
+        );
+        assert_eq!(code.into_fragment().lines().last(), Some("];"));
+    }
+
+    #[test]
+    fn test_identifier() {
+        let naming = NamingPolicy::default();
+        assert_eq!(identifier("type", "rust", &naming), "r#type");
+        assert_eq!(identifier("self", "rust", &naming), "self_");
+        assert_eq!(identifier("default", "typescript", &naming), "default_");
+        assert_eq!(identifier("content-type", "rust", &naming), "content_type");
+        assert_eq!(identifier("2fa", "typescript", &naming), "_2fa");
+        assert_eq!(identifier("$ref", "typescript", &naming), "$ref");
+        assert_eq!(identifier("$ref", "rust", &naming), "_ref");
+        assert_eq!(identifier("", "rust", &naming), "__");
+        assert_eq!(identifier("-", "typescript", &naming), "_");
+
+        assert!(is_identifier("pet_id", "rust", &naming));
+        assert!(!is_identifier("type", "rust", &naming));
+        assert!(!is_identifier("2fa", "rust", &naming));
+        assert!(!is_identifier("_", "rust", &naming));
+        assert!(!is_identifier("pet-id", "typescript", &naming));
+
+        let naming = NamingPolicy {
+            reserved_words: ["model".to_string()].into(),
+            escape_suffix: "Value".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(identifier("model", "rust", &naming), "modelValue");
+        assert!(!is_identifier("model", "typescript", &naming));
+    }
+
+    #[test]
+    fn test_primitive_types() {
+        assert_eq!(
+            PrimitiveTypes::RUST.get(Primitive::DateTime),
+            "jiff::Timestamp"
+        );
+        assert_eq!(PrimitiveTypes::TYPESCRIPT.get(Primitive::I64), "number");
+        assert_eq!(
+            PrimitiveTypes::for_language("typescript"),
+            Some(PrimitiveTypes::TYPESCRIPT)
+        );
+        assert_eq!(PrimitiveTypes::for_language("cobol"), None);
+    }
+}
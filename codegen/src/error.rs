@@ -26,6 +26,39 @@ pub enum Error {
     /// Validation error.
     ValidationError(String),
 
+    /// Invalid project file ([`ProjectConfig`](crate::ProjectConfig)) or
+    /// `lang_options` ([`options`](crate::options)).
+    ConfigError(String),
+
+    /// Schemas that would be generated as "any" values in strict mode.
+    UntypedSchemas(Vec<ir::gen_ir::Fallback>),
+
+    /// Lint findings at or above [`Config::lint_threshold`](crate::Config::lint_threshold),
+    /// formatted as `line:col: severity [rule] message (pointer)`.
+    LintFailed(Vec<String>),
+
+    /// The generated code failed its
+    /// [`Generator::verifier`](crate::Generator::verifier), e.g. didn't
+    /// compile.
+    VerificationFailed {
+        verifier: String,
+        diagnostics: Vec<crate::diagnostic::Diagnostic>,
+    },
+
+    /// A compiler message about the generated code, e.g. `TS2322` from
+    /// tsc. Its [`Diagnostic`](crate::diagnostic::Diagnostic) has the
+    /// location.
+    Compiler {
+        severity: crate::diagnostic::Severity,
+        /// The compiler's code for the message, e.g. `TS2322` or `E0308`.
+        code: Option<String>,
+        message: String,
+    },
+
+    /// Another error with the phase, type or operation and spec location
+    /// it came from (see [`diagnostic`](crate::diagnostic)).
+    Diagnostic(Box<crate::diagnostic::Diagnostic>),
+
     /// Custom error for generator-specific issues.
     Custom(String),
 }
@@ -41,6 +74,56 @@ impl fmt::Display for Error {
             Error::Utf8Error(e) => write!(f, "UTF-8 error: {}", e),
             Error::IoError(e) => write!(f, "I/O error: {}", e),
             Error::ValidationError(e) => write!(f, "Validation error: {}", e),
+            Error::ConfigError(e) => write!(f, "Config error: {}", e),
+            Error::UntypedSchemas(fallbacks) => {
+                write!(
+                    f,
+                    "{} schema(s) would be generated as untyped values:",
+                    fallbacks.len()
+                )?;
+                for fallback in fallbacks {
+                    write!(
+                        f,
+                        "\n  {}: {}",
+                        fallback.pointer,
+                        fallback.reason.describe()
+                    )?;
+                }
+                Ok(())
+            }
+            Error::LintFailed(findings) => {
+                write!(f, "{} lint finding(s) block generation:", findings.len())?;
+                for finding in findings {
+                    write!(f, "\n  {}", finding)?;
+                }
+                Ok(())
+            }
+            Error::VerificationFailed {
+                verifier,
+                diagnostics,
+            } => {
+                let errors: Vec<_> = diagnostics
+                    .iter()
+                    .filter(|d| d.severity() == crate::diagnostic::Severity::Error)
+                    .collect();
+                write!(f, "{} found {} error(s):", verifier, errors.len())?;
+                for error in errors {
+                    write!(f, "\n  {}", error)?;
+                }
+                Ok(())
+            }
+            Error::Compiler {
+                severity,
+                code,
+                message,
+            } => {
+                write!(f, "{}", severity.as_str())?;
+                if let Some(code) = code {
+                    write!(f, " {}", code)?;
+                }
+                write!(f, ": {}", message)
+            }
+            Error::Diagnostic(diagnostic) => write!(f, "{}", diagnostic),
             Error::Custom(e) => write!(f, "{}", e),
         }
     }
@@ -52,6 +135,7 @@ impl std::error::Error for Error {
             Error::TemplateError(e) => Some(e.as_ref()),
             Error::Utf8Error(e) => Some(e),
             Error::IoError(e) => Some(e),
+            Error::Diagnostic(diagnostic) => diagnostic.error.source(),
             _ => None,
         }
     }
@@ -0,0 +1,121 @@
+//! String formats generators can validate.
+//!
+//! `format` values with their own primitive (`uuid`, `date`, `date-time`,
+//! `byte`, `binary`) become typed fields. Other formats stay plain strings;
+//! those listed in a [`FormatRegistry`] also get a validation check in the
+//! generated code (a TypeScript `validate<Type>` function, a Rust
+//! `validate` method). Formats missing from the registry are not checked.
+//!
+//! The built-in formats are `email`, `idn-email`, `hostname`, `ipv4`, `ipv6`,
+//! `uri`, `url` and `uri-reference`. Register more on [`crate::Config`]:
+//!
+//! ```
+//! use codegen::formats::FormatRule;
+//!
+//! let mut config = codegen::Config::default();
+//! config
+//!     .formats
+//!     .register("slug", FormatRule::new("^[a-z0-9]+(-[a-z0-9]+)*$", "a slug"));
+//! ```
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Result};
+
+/// How values of one format are checked.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FormatRule {
+    /// Regular expression a value must match. It is used by JavaScript and
+    /// by the Rust `regex` crate, so stick to their common syntax (no
+    /// lookaround or backreferences).
+    pub pattern: String,
+    /// What a valid value is, for messages: "expected {description}".
+    pub description: String,
+}
+
+impl FormatRule {
+    pub fn new(pattern: impl Into<String>, description: impl Into<String>) -> Self {
+        Self {
+            pattern: pattern.into(),
+            description: description.into(),
+        }
+    }
+
+    /// Fail with [`Error::ConfigError`] if the `regex` crate rejects
+    /// [`pattern`](Self::pattern), which generated Rust code compiles.
+    pub fn check(&self, format: &str) -> Result<()> {
+        regex::Regex::new(&self.pattern).map(drop).map_err(|e| {
+            Error::ConfigError(format!("invalid pattern for format `{}`: {}", format, e))
+        })
+    }
+}
+
+const EMAIL: &str = r"^[^@\s]+@[^@\s]+\.[^@\s]+$";
+const HOSTNAME: &str = r"^[A-Za-z0-9]([A-Za-z0-9-]{0,61}[A-Za-z0-9])?(\.[A-Za-z0-9]([A-Za-z0-9-]{0,61}[A-Za-z0-9])?)*$";
+const IPV4: &str = r"^((25[0-5]|2[0-4][0-9]|1[0-9]{2}|[1-9]?[0-9])\.){3}(25[0-5]|2[0-4][0-9]|1[0-9]{2}|[1-9]?[0-9])$";
+/// Loose: hex groups, colons and an optional embedded IPv4 address.
+const IPV6: &str = r"^[0-9A-Fa-f]{0,4}(:[0-9A-Fa-f]{0,4}){2,7}(:(\d{1,3}\.){3}\d{1,3})?$";
+const URI: &str = r"^[A-Za-z][A-Za-z0-9+.-]*:[^\s]*$";
+const URI_REFERENCE: &str = r"^[^\s]*$";
+
+/// Formats with a validation rule, by `format` name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FormatRegistry {
+    rules: BTreeMap<String, FormatRule>,
+}
+
+impl FormatRegistry {
+    /// A registry without any formats.
+    pub fn empty() -> Self {
+        Self {
+            rules: BTreeMap::new(),
+        }
+    }
+
+    /// Add a format, replacing any rule it already has.
+    pub fn register(&mut self, format: impl Into<String>, rule: FormatRule) {
+        self.rules.insert(format.into(), rule);
+    }
+
+    pub fn get(&self, format: &str) -> Option<&FormatRule> {
+        self.rules.get(format)
+    }
+
+    /// Check every rule's pattern, see [`FormatRule::check`].
+    pub fn check(&self) -> Result<()> {
+        self.rules
+            .iter()
+            .try_for_each(|(format, rule)| rule.check(format))
+    }
+
+    /// Rule checking `field`, if it is a single string with a registered
+    /// format.
+    pub fn for_field(&self, field: &ir::gen_ir::Field) -> Option<&FormatRule> {
+        if !field.ty.modifiers.is_empty() {
+            return None;
+        }
+        self.get(field.format.as_deref()?)
+    }
+}
+
+impl Default for FormatRegistry {
+    /// The built-in formats.
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry.register("email", FormatRule::new(EMAIL, "an email address"));
+        registry.register("idn-email", FormatRule::new(EMAIL, "an email address"));
+        registry.register("hostname", FormatRule::new(HOSTNAME, "a hostname"));
+        registry.register("ipv4", FormatRule::new(IPV4, "an IPv4 address"));
+        registry.register("ipv6", FormatRule::new(IPV6, "an IPv6 address"));
+        registry.register("uri", FormatRule::new(URI, "a URI"));
+        registry.register("url", FormatRule::new(URI, "a URL"));
+        registry.register(
+            "uri-reference",
+            FormatRule::new(URI_REFERENCE, "a URI reference"),
+        );
+        registry
+    }
+}
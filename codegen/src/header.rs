@@ -0,0 +1,305 @@
+//! Headers marking files as generated.
+//!
+//! Set [`Config::file_header`](crate::Config::file_header) and the registry
+//! prepends a comment to every text file it generates, in the comment
+//! syntax of the file's extension:
+//!
+//! ```text
+//! // Code generated by oas-gen. DO NOT EDIT.
+//! // Generator: oas-gen 0.1.0
+//! // Spec hash: 3f1c9a0b7d2e4f68
+//! ```
+//!
+//! Files without a known comment syntax (JSON, lock files, binaries) are
+//! left alone. The timestamp is off by default: it changes every file on
+//! every run, defeating incremental writes and `--check`.
+
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{FileSink, GENERATOR_VERSION, GenIr, Result, VirtualFS};
+
+/// What goes in the header of generated files.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FileHeader {
+    /// First line, telling readers not to edit the file.
+    pub notice: Option<String>,
+    /// Add the generator name and version ([`GENERATOR_VERSION`]).
+    pub version: bool,
+    /// Add the hash of the spec the file was generated from
+    /// ([`ApiMeta::spec_hash`](ir::gen_ir::ApiMeta::spec_hash)).
+    pub spec_hash: bool,
+    /// Add the UTC time of generation.
+    pub timestamp: bool,
+}
+
+impl Default for FileHeader {
+    fn default() -> Self {
+        Self {
+            notice: Some(Self::DEFAULT_NOTICE.to_string()),
+            version: true,
+            spec_hash: true,
+            timestamp: false,
+        }
+    }
+}
+
+impl FileHeader {
+    /// The default [`notice`](Self::notice), in the form tools such as
+    /// GitHub's linguist and `go vet` recognise.
+    pub const DEFAULT_NOTICE: &'static str = "Code generated by oas-gen. DO NOT EDIT.";
+
+    /// The header's lines for output generated from `ir`, without comment
+    /// syntax.
+    pub fn lines(&self, ir: &GenIr) -> Vec<String> {
+        let mut lines = Vec::new();
+        if let Some(notice) = &self.notice {
+            lines.extend(notice.lines().map(str::to_string));
+        }
+        if self.version {
+            lines.push(format!("Generator: {}", GENERATOR_VERSION));
+        }
+        if self.spec_hash {
+            lines.push(format!("Spec hash: {}", ir.api.spec_hash));
+        }
+        if self.timestamp {
+            lines.push(format!("Generated at: {}", utc_now()));
+        }
+        lines
+    }
+}
+
+/// How a file format writes comments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommentStyle {
+    /// A prefix on each line, e.g. `//` or `#`.
+    Line(&'static str),
+    /// Delimiters around the whole comment, e.g. `<!--` and `-->`.
+    Block(&'static str, &'static str),
+}
+
+impl CommentStyle {
+    /// The comment syntax of `path`, from its extension or, for files such
+    /// as `.gitignore`, its name.
+    pub fn for_path(path: &Path) -> Option<CommentStyle> {
+        let name = path.file_name()?.to_str()?;
+        if matches!(
+            name,
+            ".gitignore"
+                | ".npmignore"
+                | ".dockerignore"
+                | "CODEOWNERS"
+                | "Dockerfile"
+                | "Makefile"
+        ) {
+            return Some(CommentStyle::Line("#"));
+        }
+        let style = match path.extension()?.to_str()? {
+            "ts" | "tsx" | "mts" | "cts" | "js" | "jsx" | "mjs" | "cjs" | "rs" | "go" | "java"
+            | "kt" | "kts" | "swift" | "c" | "h" | "cc" | "cpp" | "hpp" | "cs" | "scala"
+            | "dart" | "proto" => CommentStyle::Line("//"),
+            "py" | "pyi" | "rb" | "sh" | "bash" | "toml" | "yaml" | "yml" | "r" | "pl" | "tf"
+            | "graphql" => CommentStyle::Line("#"),
+            "sql" | "lua" | "hs" => CommentStyle::Line("--"),
+            "md" | "html" | "htm" | "xml" | "svg" | "vue" => CommentStyle::Block("<!--", "-->"),
+            "css" => CommentStyle::Block("/*", "*/"),
+            _ => return None,
+        };
+        Some(style)
+    }
+
+    /// `lines` as a comment, ending with a newline.
+    pub fn comment(&self, lines: &[String]) -> String {
+        match self {
+            CommentStyle::Line(prefix) => lines
+                .iter()
+                .map(|line| format!("{} {}\n", prefix, line).replace(" \n", "\n"))
+                .collect(),
+            CommentStyle::Block(open, close) => {
+                let mut comment = format!("{}\n", open);
+                for line in lines {
+                    comment.push_str(&format!("  {}\n", line).replace("  \n", "\n"));
+                }
+                comment.push_str(close);
+                comment.push('\n');
+                comment
+            }
+        }
+    }
+}
+
+/// `content` with the header `lines` prepended, or `None` if `path` has no
+/// known comment syntax or isn't UTF-8 text. Shebangs and XML declarations
+/// stay on the first line.
+pub fn prepend_header(path: &Path, content: &[u8], lines: &[String]) -> Option<Vec<u8>> {
+    if lines.is_empty() {
+        return None;
+    }
+    let style = CommentStyle::for_path(path)?;
+    let text = std::str::from_utf8(content).ok()?;
+    let shebang = text.starts_with("#!") && !text.starts_with("#![");
+    let (first, rest) = if shebang || text.starts_with("<?xml") {
+        text.split_at(text.find('\n').map_or(text.len(), |end| end + 1))
+    } else {
+        ("", text)
+    };
+    let mut out = String::with_capacity(content.len() + 128);
+    out.push_str(first);
+    if !first.is_empty() && !first.ends_with('\n') {
+        out.push('\n');
+    }
+    out.push_str(&style.comment(lines));
+    if !rest.is_empty() {
+        out.push('\n');
+        out.push_str(rest);
+    }
+    Some(out.into_bytes())
+}
+
+impl VirtualFS {
+    /// Prepend `header` to every file with a known comment syntax.
+    pub fn add_headers(&mut self, header: &FileHeader, ir: &GenIr) {
+        let lines = header.lines(ir);
+        for (path, content) in self.files.iter_mut() {
+            if let Some(with_header) = prepend_header(path, content, &lines) {
+                *content = with_header;
+            }
+        }
+    }
+}
+
+/// Prepends a header to each file before passing it on, for streamed
+/// generation.
+pub struct HeaderSink<'a> {
+    inner: &'a mut dyn FileSink,
+    lines: Vec<String>,
+}
+
+impl<'a> HeaderSink<'a> {
+    pub fn new(inner: &'a mut dyn FileSink, header: &FileHeader, ir: &GenIr) -> Self {
+        Self {
+            inner,
+            lines: header.lines(ir),
+        }
+    }
+}
+
+impl FileSink for HeaderSink<'_> {
+    fn write_file(&mut self, path: &Path, content: &[u8]) -> Result<()> {
+        match prepend_header(path, content, &self.lines) {
+            Some(with_header) => self.inner.write_file(path, &with_header),
+            None => self.inner.write_file(path, content),
+        }
+    }
+
+    fn write_file_with_mode(&mut self, path: &Path, content: &[u8], mode: u32) -> Result<()> {
+        match prepend_header(path, content, &self.lines) {
+            Some(with_header) => self.inner.write_file_with_mode(path, &with_header, mode),
+            None => self.inner.write_file_with_mode(path, content, mode),
+        }
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.inner.finish()
+    }
+}
+
+/// The current UTC time as `YYYY-MM-DDTHH:MM:SSZ`.
+fn utc_now() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    let (days, secs_of_day) = ((secs / 86_400) as i64, secs % 86_400);
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        secs_of_day / 3_600,
+        secs_of_day % 3_600 / 60,
+        secs_of_day % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prepend_header() {
+        let lines = vec![
+            FileHeader::DEFAULT_NOTICE.to_string(),
+            "Spec hash: 0123456789abcdef".to_string(),
+        ];
+        let with_header = |path: &str, content: &str| {
+            prepend_header(Path::new(path), content.as_bytes(), &lines)
+                .map(|bytes| String::from_utf8(bytes).unwrap())
+        };
+
+        assert_eq!(
+            with_header("src/index.ts", "export {};\n").unwrap(),
+            "// Code generated by oas-gen. DO NOT EDIT.\n// Spec hash: 0123456789abcdef\n\nexport {};\n"
+        );
+        assert_eq!(
+            with_header("bin/run.sh", "#!/bin/sh\necho hi\n").unwrap(),
+            "#!/bin/sh\n# Code generated by oas-gen. DO NOT EDIT.\n# Spec hash: 0123456789abcdef\n\necho hi\n"
+        );
+        assert_eq!(
+            with_header("README.md", "# Pets\n").unwrap(),
+            "<!--\n  Code generated by oas-gen. DO NOT EDIT.\n  Spec hash: 0123456789abcdef\n-->\n\n# Pets\n"
+        );
+        assert!(with_header(".gitignore", "node_modules\n").is_some());
+        assert_eq!(with_header("package.json", "{}\n"), None);
+        assert_eq!(
+            prepend_header(Path::new("logo.svg"), &[0xff, 0xfe], &lines),
+            None
+        );
+    }
+
+    #[test]
+    fn test_header_lines() {
+        let ir = GenIr {
+            api: ir::gen_ir::ApiMeta {
+                title: "Pets".to_string(),
+                version: "1.0.0".to_string(),
+                package_name: ir::gen_ir::CanonicalName::from_string("pets"),
+                docs: ir::gen_ir::Docs::default(),
+                spec_hash: String::new(),
+            },
+            types: Default::default(),
+            services: vec![],
+            auth_schemes: vec![],
+            errors: vec![],
+            server_sets: vec![],
+            webhooks: vec![],
+            fallbacks: vec![],
+        };
+        let header = FileHeader {
+            timestamp: true,
+            ..FileHeader::default()
+        };
+        let lines = header.lines(&ir);
+        assert_eq!(lines[0], FileHeader::DEFAULT_NOTICE);
+        assert_eq!(lines[1], format!("Generator: {}", GENERATOR_VERSION));
+        assert_eq!(lines[2], format!("Spec hash: {}", ir.api.spec_hash));
+        // YYYY-MM-DDTHH:MM:SSZ
+        let timestamp = lines[3].strip_prefix("Generated at: ").unwrap();
+        assert_eq!(timestamp.len(), 20);
+        assert!(timestamp.ends_with('Z'));
+    }
+}
@@ -0,0 +1,242 @@
+//! Where generated files go.
+//!
+//! A [`Layout`] maps the parts of an SDK (types, the client runtime, one
+//! module per service) to paths. Generators ask it for every path and
+//! compute imports between them, so output can match a repository's
+//! conventions without forking templates. The built-in layouts are picked
+//! with the `layout` lang option ([`crate::Config::LAYOUT`]):
+//!
+//! | `layout`             | types               | client and runtime | service `pets`           |
+//! |----------------------|---------------------|--------------------|--------------------------|
+//! | `standard` (default) | `src/types/index`   | `src/services/`    | `src/services/pets`      |
+//! | `flat`               | `src/types`         | `src/`             | `src/pets.service`       |
+//! | `by_tag`             | `src/types/index`   | `src/core/`        | `src/pets/index`         |
+//! | `domain`             | `src/types/index`   | `src/core/`        | `src/pets/service`       |
+//!
+//! Paths are modules without a file extension. With `domain`, each service
+//! directory also gets an `index` re-exporting the service and the types it
+//! uses. Supporting type modules (errors, date helpers) go next to the types.
+//! With `by_tag` and `domain`, services named `types` or `core` get a
+//! `_service` suffix (`src/types_service/`), so they don't share a
+//! directory with the types or the runtime.
+
+use std::path::PathBuf;
+
+use ir::gen_ir::Service;
+
+use crate::{Config, Error, Result};
+
+/// Paths of the parts of a generated SDK, relative to the output root and
+/// without a file extension.
+pub trait Layout: Send + Sync {
+    /// Module declaring the API types.
+    fn types_module(&self) -> PathBuf;
+
+    /// Directory of the modules supporting the types (errors, date and
+    /// number helpers).
+    fn types_dir(&self) -> PathBuf;
+
+    /// Directory of the client and its runtime helpers.
+    fn runtime_dir(&self) -> PathBuf;
+
+    /// Module of `service`.
+    fn service_module(&self, service: &Service) -> PathBuf;
+
+    /// Module re-exporting `service` and the types it uses, for layouts
+    /// grouping code by domain.
+    fn service_index(&self, _service: &Service) -> Option<PathBuf> {
+        None
+    }
+}
+
+/// `src/types/`, with every service and the client in `src/services/`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StandardLayout;
+
+impl Layout for StandardLayout {
+    fn types_module(&self) -> PathBuf {
+        self.types_dir().join("index")
+    }
+
+    fn types_dir(&self) -> PathBuf {
+        PathBuf::from("src").join("types")
+    }
+
+    fn runtime_dir(&self) -> PathBuf {
+        PathBuf::from("src").join("services")
+    }
+
+    fn service_module(&self, service: &Service) -> PathBuf {
+        self.runtime_dir().join(&service.name.snake)
+    }
+}
+
+/// Everything directly in `src/`; services are `<name>.service`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FlatLayout;
+
+impl Layout for FlatLayout {
+    fn types_module(&self) -> PathBuf {
+        PathBuf::from("src").join("types")
+    }
+
+    fn types_dir(&self) -> PathBuf {
+        PathBuf::from("src")
+    }
+
+    fn runtime_dir(&self) -> PathBuf {
+        PathBuf::from("src")
+    }
+
+    fn service_module(&self, service: &Service) -> PathBuf {
+        PathBuf::from("src").join(format!("{}.service", service.name.snake))
+    }
+}
+
+/// Directories under `src/` that layouts with a directory per service use
+/// for the types and the runtime.
+const RESERVED_DIRS: &[&str] = &["types", "core"];
+
+/// `src/<service>`, suffixed with `_service` when the name is reserved.
+fn service_dir(service: &Service) -> PathBuf {
+    let name = &service.name.snake;
+    if RESERVED_DIRS.contains(&name.as_str()) {
+        PathBuf::from("src").join(format!("{}_service", name))
+    } else {
+        PathBuf::from("src").join(name)
+    }
+}
+
+/// A directory per service (tag), with the client in `src/core/`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ByTagLayout;
+
+impl Layout for ByTagLayout {
+    fn types_module(&self) -> PathBuf {
+        StandardLayout.types_module()
+    }
+
+    fn types_dir(&self) -> PathBuf {
+        StandardLayout.types_dir()
+    }
+
+    fn runtime_dir(&self) -> PathBuf {
+        PathBuf::from("src").join("core")
+    }
+
+    fn service_module(&self, service: &Service) -> PathBuf {
+        service_dir(service).join("index")
+    }
+}
+
+/// Like [`ByTagLayout`], with the service in `service` and an `index`
+/// exposing the service with its types as one domain module.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DomainLayout;
+
+impl Layout for DomainLayout {
+    fn types_module(&self) -> PathBuf {
+        StandardLayout.types_module()
+    }
+
+    fn types_dir(&self) -> PathBuf {
+        StandardLayout.types_dir()
+    }
+
+    fn runtime_dir(&self) -> PathBuf {
+        ByTagLayout.runtime_dir()
+    }
+
+    fn service_module(&self, service: &Service) -> PathBuf {
+        service_dir(service).join("service")
+    }
+
+    fn service_index(&self, service: &Service) -> Option<PathBuf> {
+        Some(service_dir(service).join("index"))
+    }
+}
+
+impl Config {
+    /// `lang_options` key naming the output [`layout`](crate::layout):
+    /// `standard`, `flat`, `by_tag` or `domain`.
+    pub const LAYOUT: &'static str = "layout";
+
+    /// Name of the layout chosen in `lang_options` (`standard` if unset).
+    pub fn layout_name(&self) -> Result<&str> {
+        match self.lang_options.get(Self::LAYOUT) {
+            None => Ok("standard"),
+            Some(serde_json::Value::String(name)) => Ok(name),
+            Some(other) => Err(Error::Custom(format!(
+                "lang option `{}` must be a string, got {}",
+                Self::LAYOUT,
+                other
+            ))),
+        }
+    }
+
+    /// The built-in layout chosen in `lang_options`.
+    pub fn layout(&self) -> Result<Box<dyn Layout>> {
+        match self.layout_name()? {
+            "standard" => Ok(Box::new(StandardLayout)),
+            "flat" => Ok(Box::new(FlatLayout)),
+            "by_tag" => Ok(Box::new(ByTagLayout)),
+            "domain" => Ok(Box::new(DomainLayout)),
+            other => Err(Error::Custom(format!(
+                "unknown layout `{}`; expected standard, flat, by_tag or domain",
+                other
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ir::gen_ir::{CanonicalName, Docs, StableId};
+
+    #[test]
+    fn test_layout_from_config() {
+        let service = Service {
+            id: StableId::new("pets"),
+            name: CanonicalName::from_string("pets"),
+            docs: Docs::default(),
+            server_set: None,
+            operations: vec![],
+            owners: vec![],
+        };
+        let mut config = Config::default();
+        assert_eq!(
+            config.layout().unwrap().service_module(&service),
+            PathBuf::from("src/services/pets")
+        );
+
+        config
+            .lang_options
+            .insert(Config::LAYOUT.to_string(), "domain".into());
+        let layout = config.layout().unwrap();
+        assert_eq!(
+            layout.service_module(&service),
+            PathBuf::from("src/pets/service")
+        );
+        assert_eq!(
+            layout.service_index(&service),
+            Some(PathBuf::from("src/pets/index"))
+        );
+
+        // Services don't take over the types or runtime directories
+        for name in ["types", "core"] {
+            let service = Service {
+                name: CanonicalName::from_string(name),
+                ..service.clone()
+            };
+            let dir = PathBuf::from("src").join(format!("{}_service", name));
+            assert_eq!(layout.service_module(&service), dir.join("service"));
+            assert_eq!(layout.service_index(&service), Some(dir.join("index")));
+        }
+
+        config
+            .lang_options
+            .insert(Config::LAYOUT.to_string(), "nested".into());
+        assert!(config.layout().is_err());
+    }
+}
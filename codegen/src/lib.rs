@@ -3,20 +3,70 @@
 //! This crate provides the foundational types and traits needed by both
 //! the generation engine and individual language generators (templates).
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::{Path, PathBuf};
 
-pub use ir::gen_ir::GenIr;
-use serde::Serialize;
+pub use ir::filter::OperationFilter;
+pub use ir::gen_ir::{GenIr, TimeFormat};
+pub use ir::naming::NamingPolicy;
+use serde::{Deserialize, Serialize};
 
 mod error;
 pub use error::{Error, Result};
 
+mod sink;
+pub use sink::{CallbackSink, DiskSink, FileSink};
+
+mod owners;
+pub use owners::codeowners;
+
+mod project;
+pub use project::{Profile, ProjectConfig, Target};
+
+mod header;
+pub use header::{CommentStyle, FileHeader, HeaderSink, prepend_header};
+
+mod manifest;
+pub use manifest::{MANIFEST_PATH, Manifest, ManifestEntry, content_hash};
+
+mod archive;
+mod diff;
+pub use diff::{ChangeKind, FileChange, IGNORED_DIRS, VfsDiff, WriteMode};
+
+pub mod diagnostic;
+pub mod emit;
+pub mod formats;
+pub mod layout;
+pub mod options;
+pub mod overrides;
+pub mod passes;
+pub mod plugin;
+pub mod postprocess;
+pub mod protected;
+pub mod testing;
+pub mod transform;
+pub mod verify;
+
+/// Name and version of the generator, recorded in generated SDK metadata.
+pub const GENERATOR_VERSION: &str = concat!("oas-gen ", env!("CARGO_PKG_VERSION"));
+
+/// Unix permissions of generated files that don't set their own.
+pub const DEFAULT_FILE_MODE: u32 = 0o644;
+
+/// Unix permissions of generated files marked executable.
+pub const EXECUTABLE_FILE_MODE: u32 = 0o755;
+
 /// Virtual file system representing the generated output.
 /// Maps file paths to their content before writing to disk.
 #[derive(Debug, Clone, Default)]
 pub struct VirtualFS {
     files: BTreeMap<PathBuf, Vec<u8>>,
+    /// Unix permissions of the files that don't use [`DEFAULT_FILE_MODE`].
+    modes: BTreeMap<PathBuf, u32>,
+    spec_hash: Option<String>,
+    cache_key: Option<String>,
+    /// Where the generator's output directory ended up; see [`Self::root`].
+    root: PathBuf,
 }
 
 impl VirtualFS {
@@ -47,11 +97,93 @@ impl VirtualFS {
             .map(|v| std::str::from_utf8(v).map_err(Error::Utf8Error))
     }
 
+    /// Set the Unix permissions `path` is written with, e.g. `0o600`.
+    pub fn set_mode(&mut self, path: impl Into<PathBuf>, mode: u32) {
+        self.modes.insert(path.into(), mode);
+    }
+
+    /// Make `path` executable ([`EXECUTABLE_FILE_MODE`]), e.g. a shell
+    /// script or git hook.
+    pub fn set_executable(&mut self, path: impl Into<PathBuf>) {
+        self.set_mode(path, EXECUTABLE_FILE_MODE);
+    }
+
+    /// Unix permissions of `path`, [`DEFAULT_FILE_MODE`] unless set.
+    pub fn mode(&self, path: &Path) -> u32 {
+        self.modes.get(path).copied().unwrap_or(DEFAULT_FILE_MODE)
+    }
+
+    /// Whether `path` is written executable.
+    pub fn is_executable(&self, path: &Path) -> bool {
+        self.mode(path) & 0o111 != 0
+    }
+
+    /// The directory, relative to the output root, that the generator's
+    /// output was moved to by [`transform`](Self::transform), e.g. with a
+    /// [`PathPrefix`](transform::PathPrefix). Empty unless moved.
+    /// Post-processors run there.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Record the hash of the spec the files were generated from, written
+    /// to the [`Manifest`].
+    pub fn set_spec_hash(&mut self, spec_hash: impl Into<String>) {
+        self.spec_hash = Some(spec_hash.into());
+    }
+
+    /// Hash of the spec the files were generated from, if recorded.
+    pub fn spec_hash(&self) -> Option<&str> {
+        self.spec_hash.as_deref()
+    }
+
+    /// Record the hash of everything the files were generated from, written
+    /// to the [`Manifest`] so an unchanged generation can be skipped.
+    pub fn set_cache_key(&mut self, cache_key: impl Into<String>) {
+        self.cache_key = Some(cache_key.into());
+    }
+
+    /// Hash of everything the files were generated from, if recorded.
+    pub fn cache_key(&self) -> Option<&str> {
+        self.cache_key.as_deref()
+    }
+
     /// Iterate over all files in the virtual file system.
     pub fn files(&self) -> impl Iterator<Item = (&Path, &[u8])> {
         self.files.iter().map(|(p, c)| (p.as_path(), c.as_slice()))
     }
 
+    /// Move every file of `other` into this file system under `prefix`.
+    pub fn extend_under(&mut self, prefix: impl AsRef<Path>, other: VirtualFS) {
+        let prefix = prefix.as_ref();
+        for (path, content) in other.files {
+            self.files.insert(prefix.join(path), content);
+        }
+        for (path, mode) in other.modes {
+            self.modes.insert(prefix.join(path), mode);
+        }
+    }
+
+    /// The files under `prefix`, with `prefix` stripped: what
+    /// [`extend_under`](Self::extend_under) put there.
+    pub fn subtree(&self, prefix: impl AsRef<Path>) -> VirtualFS {
+        let prefix = prefix.as_ref();
+        let under = |path: &PathBuf| path.strip_prefix(prefix).ok().map(Path::to_path_buf);
+        VirtualFS {
+            files: self
+                .files
+                .iter()
+                .filter_map(|(path, content)| Some((under(path)?, content.clone())))
+                .collect(),
+            modes: self
+                .modes
+                .iter()
+                .filter_map(|(path, mode)| Some((under(path)?, *mode)))
+                .collect(),
+            ..VirtualFS::default()
+        }
+    }
+
     /// Get the number of files.
     pub fn len(&self) -> usize {
         self.files.len()
@@ -68,18 +200,53 @@ impl VirtualFS {
     }
 
     /// Write all files to the actual file system.
+    ///
+    /// Files whose content is unchanged aren't rewritten, so incremental
+    /// compilers and file watchers only see real changes. Files the previous
+    /// write generated (see [`Manifest`]) that the VFS no longer contains are
+    /// deleted. Files already on disk keep their [`protected`] regions.
+    /// Files with a [`mode`](Self::mode) set get those permissions on Unix;
+    /// the others keep the permissions they have.
     pub fn write_to_disk(&self, base_path: &Path) -> std::io::Result<()> {
+        let previous = Manifest::read(base_path)?;
         for (path, content) in &self.files {
             let full_path = base_path.join(path);
-            if let Some(parent) = full_path.parent() {
-                std::fs::create_dir_all(parent)?;
+            let existing = std::fs::read(&full_path).ok();
+            let content = protected::keep_regions(path, content, existing.as_deref())?;
+            if existing.as_deref() != Some(&*content) {
+                if let Some(parent) = full_path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(&full_path, content)?;
+            }
+            if let Some(mode) = self.modes.get(path) {
+                set_mode(&full_path, *mode)?;
             }
-            std::fs::write(full_path, content)?;
         }
-        Ok(())
+        let manifest = Manifest::of(self);
+        if let Some(previous) = previous {
+            previous.remove_stale(base_path, &manifest)?;
+        }
+        manifest.write(base_path)
     }
 }
 
+/// Give the file at `path` the Unix permissions `mode`, unless it has them.
+#[cfg(unix)]
+pub(crate) fn set_mode(path: &Path, mode: u32) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    if std::fs::metadata(path)?.permissions().mode() & 0o7777 != mode {
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))?;
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub(crate) fn set_mode(_path: &Path, _mode: u32) -> std::io::Result<()> {
+    Ok(())
+}
+
 /// Configuration for code generation.
 #[derive(Debug, Clone, Serialize)]
 pub struct Config {
@@ -87,6 +254,60 @@ pub struct Config {
     pub service_style: ServiceStyle,
     /// Whether to generate documentation comments.
     pub include_docs: bool,
+    /// Type name prefixes/suffixes and reserved-word escaping.
+    pub naming: NamingPolicy,
+    /// Default wire formats of date and date-time fields.
+    pub time_formats: TimeFormats,
+    /// String formats checked by generated validation code.
+    pub formats: formats::FormatRegistry,
+    /// Locale of generated docs (e.g. `de`, `pt-BR`), picked from the
+    /// `x-descriptions` translations; the spec's own text when `None` or
+    /// untranslated. See [`ir::i18n`].
+    pub locale: Option<String>,
+    /// Package name to generate instead of the one derived from the spec's
+    /// `info.title`, e.g. `acme-internal`.
+    pub package_name: Option<String>,
+    /// Base URL generated clients default to, ahead of the spec's
+    /// `servers`.
+    pub base_url: Option<String>,
+    /// Only generate the operations of these tags; all when empty.
+    pub include_tags: Vec<String>,
+    /// Don't generate the operations of these tags.
+    pub exclude_tags: Vec<String>,
+    /// Only generate operations whose `operationId` matches one of these
+    /// globs (`admin*`, `get?et`); all when empty.
+    pub include_operations: Vec<String>,
+    /// Don't generate operations whose `operationId` matches one of these
+    /// globs.
+    pub exclude_operations: Vec<String>,
+    /// Fail generation when any schema could only be generated as an "any"
+    /// value ([`GenIr::fallbacks`](ir::gen_ir::GenIr::fallbacks)), listing
+    /// every one with its spec pointer.
+    pub strict: bool,
+    /// Lint the spec before generating and fail on findings at or above
+    /// this severity, with the nearest `oas-lint.toml`. Entry points that
+    /// have the spec text (e.g. `GeneratorRegistry::generate_spec`) lint
+    /// it; those that take an already-built [`GenIr`] fail with
+    /// [`Error::ConfigError`] instead of skipping the lint, since the IR
+    /// can't be linted.
+    pub lint_threshold: Option<LintThreshold>,
+    /// Header prepended to every generated text file, marking it as
+    /// generated; none by default.
+    pub file_header: Option<FileHeader>,
+    /// Directory of templates replacing the generator's built-in ones of
+    /// the same name (see [`overrides`]); none by default.
+    pub template_dir: Option<PathBuf>,
+    /// Rewrites of the IR run before the generator sees it, e.g. renaming
+    /// a type or applying an overlay (see [`passes`]); none by default.
+    pub passes: passes::Passes,
+    /// Rewrites applied to every generated file, e.g. a path prefix or a
+    /// license banner; none by default.
+    #[serde(skip_serializing)]
+    pub transforms: transform::Transforms,
+    /// Types another package of the same run provides, imported from it
+    /// instead of declared again (multi-version generation); none by
+    /// default.
+    pub shared_types: Option<SharedTypes>,
     /// Language-specific options.
     #[serde(skip_serializing)]
     pub lang_options: BTreeMap<String, serde_json::Value>,
@@ -97,13 +318,242 @@ impl Default for Config {
         Self {
             service_style: ServiceStyle::PerService,
             include_docs: true,
+            naming: NamingPolicy::default(),
+            time_formats: TimeFormats::default(),
+            formats: formats::FormatRegistry::default(),
+            locale: None,
+            package_name: None,
+            base_url: None,
+            include_tags: Vec::new(),
+            exclude_tags: Vec::new(),
+            include_operations: Vec::new(),
+            exclude_operations: Vec::new(),
+            strict: false,
+            lint_threshold: None,
+            file_header: None,
+            template_dir: None,
+            passes: passes::Passes::default(),
+            transforms: transform::Transforms::default(),
+            shared_types: None,
             lang_options: BTreeMap::new(),
         }
     }
 }
 
+/// Declared types provided by another generated package, see
+/// [`Config::shared_types`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SharedTypes {
+    /// Path of the providing package relative to the root of the package
+    /// being generated, e.g. `../shared`.
+    pub package: String,
+    /// Canonical names of the provided types.
+    pub types: BTreeSet<String>,
+}
+
+impl SharedTypes {
+    /// Whether `type_decl` is provided by the shared package.
+    pub fn contains(&self, type_decl: &ir::gen_ir::TypeDecl) -> bool {
+        self.types.contains(&type_decl.name.canonical)
+    }
+}
+
+impl Config {
+    /// `lang_options` key: when `true`, every numeric field accepts numbers
+    /// sent as strings, not just those marked `x-coerce-number`.
+    pub const COERCE_NUMBERS: &'static str = "coerce_numbers";
+
+    /// `lang_options` key: when `true`, the registry adds
+    /// [`NULLABILITY_REPORT_PATH`](Self::NULLABILITY_REPORT_PATH) listing
+    /// fields with ambiguous nullability (see [`ir::audit`]).
+    pub const NULLABILITY_REPORT: &'static str = "nullability_report";
+
+    /// Where the nullability report is written, relative to the output root.
+    pub const NULLABILITY_REPORT_PATH: &'static str = "nullability-report.md";
+
+    // Builders, for callers outside the workspace: fields are added in minor
+    // versions, so struct literals aren't covered by semver.
+
+    /// How to organize services.
+    pub fn with_service_style(mut self, service_style: ServiceStyle) -> Self {
+        self.service_style = service_style;
+        self
+    }
+
+    /// Whether to generate documentation comments.
+    pub fn with_include_docs(mut self, include_docs: bool) -> Self {
+        self.include_docs = include_docs;
+        self
+    }
+
+    /// Type name prefixes/suffixes and reserved-word escaping.
+    pub fn with_naming(mut self, naming: NamingPolicy) -> Self {
+        self.naming = naming;
+        self
+    }
+
+    /// Default wire formats of date and date-time fields.
+    pub fn with_time_formats(mut self, time_formats: TimeFormats) -> Self {
+        self.time_formats = time_formats;
+        self
+    }
+
+    /// String formats checked by generated validation code.
+    pub fn with_formats(mut self, formats: formats::FormatRegistry) -> Self {
+        self.formats = formats;
+        self
+    }
+
+    /// Locale of generated docs, see [`Config::locale`](Self::locale).
+    pub fn with_locale(mut self, locale: impl Into<String>) -> Self {
+        self.locale = Some(locale.into());
+        self
+    }
+
+    /// Package name to generate instead of the one derived from the spec.
+    pub fn with_package_name(mut self, package_name: impl Into<String>) -> Self {
+        self.package_name = Some(package_name.into());
+        self
+    }
+
+    /// Base URL generated clients default to.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    /// Only generate the operations of these tags.
+    pub fn with_include_tags(mut self, tags: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.include_tags = tags.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Don't generate the operations of these tags.
+    pub fn with_exclude_tags(mut self, tags: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.exclude_tags = tags.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Only generate operations whose `operationId` matches one of these globs.
+    pub fn with_include_operations(
+        mut self,
+        operations: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.include_operations = operations.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Don't generate operations whose `operationId` matches one of these globs.
+    pub fn with_exclude_operations(
+        mut self,
+        operations: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.exclude_operations = operations.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Fail generation when any schema could only be generated as an "any" value.
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Lint the spec first and fail on findings at or above this severity.
+    pub fn with_lint_threshold(mut self, lint_threshold: LintThreshold) -> Self {
+        self.lint_threshold = Some(lint_threshold);
+        self
+    }
+
+    /// Prepend `file_header` to every generated text file.
+    pub fn with_file_header(mut self, file_header: FileHeader) -> Self {
+        self.file_header = Some(file_header);
+        self
+    }
+
+    /// Replace built-in templates with those of the same name in this directory.
+    pub fn with_template_dir(mut self, template_dir: impl Into<PathBuf>) -> Self {
+        self.template_dir = Some(template_dir.into());
+        self
+    }
+
+    /// Rewrites of the IR run before the generator sees it.
+    pub fn with_passes(mut self, passes: passes::Passes) -> Self {
+        self.passes = passes;
+        self
+    }
+
+    /// Rewrites applied to every generated file.
+    pub fn with_transforms(mut self, transforms: transform::Transforms) -> Self {
+        self.transforms = transforms;
+        self
+    }
+
+    /// Set the language-specific option `key`.
+    pub fn with_lang_option(mut self, key: impl Into<String>, value: serde_json::Value) -> Self {
+        self.lang_options.insert(key.into(), value);
+        self
+    }
+
+    /// The operations to generate, see [`ir::filter`].
+    pub fn operation_filter(&self) -> OperationFilter {
+        OperationFilter {
+            include_tags: self.include_tags.clone(),
+            exclude_tags: self.exclude_tags.clone(),
+            include_operations: self.include_operations.clone(),
+            exclude_operations: self.exclude_operations.clone(),
+        }
+    }
+
+    /// Whether the nullability report was asked for.
+    pub fn nullability_report(&self) -> bool {
+        self.lang_options.get(Self::NULLABILITY_REPORT) == Some(&serde_json::Value::Bool(true))
+    }
+
+    /// Whether `field` is a single number that should also be accepted as a
+    /// string (`"42.5"`) when deserializing.
+    pub fn coerces_number(&self, field: &ir::gen_ir::Field) -> bool {
+        use ir::gen_ir::{Primitive, StableId};
+
+        let is_number = matches!(
+            field.ty.target,
+            StableId::Primitive(Primitive::I32 | Primitive::I64 | Primitive::F32 | Primitive::F64)
+        );
+        is_number
+            && field.ty.modifiers.is_empty()
+            && (field.coerce_number
+                || self.lang_options.get(Self::COERCE_NUMBERS)
+                    == Some(&serde_json::Value::Bool(true)))
+    }
+}
+
+/// Wire formats for date and date-time fields that don't set their own with
+/// `x-time-format` or `x-format-pattern`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TimeFormats {
+    pub date: TimeFormat,
+    pub date_time: TimeFormat,
+}
+
+impl TimeFormats {
+    /// Wire format of `field`, or `None` if it isn't a single date or
+    /// date-time value.
+    pub fn for_field<'a>(&'a self, field: &'a ir::gen_ir::Field) -> Option<&'a TimeFormat> {
+        use ir::gen_ir::{Primitive, StableId};
+
+        if !field.ty.modifiers.is_empty() {
+            return None;
+        }
+        let default = match field.ty.target {
+            StableId::Primitive(Primitive::Date) => &self.date,
+            StableId::Primitive(Primitive::DateTime) => &self.date_time,
+            _ => return None,
+        };
+        Some(field.time_format.as_ref().unwrap_or(default))
+    }
+}
+
 /// How to organize service/operation code.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum ServiceStyle {
     /// One file per service.
@@ -114,6 +564,105 @@ pub enum ServiceStyle {
     ByTag,
 }
 
+/// Lowest lint severity that blocks generation, see
+/// [`Config::lint_threshold`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LintThreshold {
+    /// Only errors block generation.
+    Error,
+    /// Errors and warnings block generation.
+    Warning,
+    /// Errors, warnings and infos block generation.
+    Info,
+    /// Errors, warnings, infos and hints block generation.
+    Hint,
+}
+
+impl LintThreshold {
+    /// The name used in config files and on the command line, e.g. `warning`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LintThreshold::Error => "error",
+            LintThreshold::Warning => "warning",
+            LintThreshold::Info => "info",
+            LintThreshold::Hint => "hint",
+        }
+    }
+
+    /// The threshold named `s`, see [`as_str`](Self::as_str).
+    pub fn parse(s: &str) -> Option<LintThreshold> {
+        match s {
+            "error" => Some(LintThreshold::Error),
+            "warning" => Some(LintThreshold::Warning),
+            "info" => Some(LintThreshold::Info),
+            "hint" => Some(LintThreshold::Hint),
+            _ => None,
+        }
+    }
+}
+
+/// What a [`Generator`] is, for listings such as
+/// `GeneratorRegistry::list`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GeneratorInfo {
+    /// The language name generators are looked up by.
+    pub name: String,
+    pub version: String,
+    pub description: String,
+    /// The `lang_options` the generator understands.
+    pub options: Vec<GeneratorOption>,
+    /// JSON Schema of the options, see [`Generator::options_schema`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub options_schema: Option<serde_json::Value>,
+}
+
+/// A `lang_options` key of a generator.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GeneratorOption {
+    pub name: String,
+    pub description: String,
+    /// The value used when the option isn't set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<serde_json::Value>,
+}
+
+impl GeneratorInfo {
+    /// Info with the options read from the `properties` of `options_schema`.
+    pub fn new(
+        name: impl Into<String>,
+        version: impl Into<String>,
+        description: impl Into<String>,
+        options_schema: Option<serde_json::Value>,
+    ) -> Self {
+        let options = options_schema
+            .as_ref()
+            .and_then(|schema| schema.get("properties")?.as_object())
+            .map(|properties| {
+                properties
+                    .iter()
+                    .map(|(name, property)| GeneratorOption {
+                        name: name.clone(),
+                        description: property
+                            .get("description")
+                            .and_then(|d| d.as_str())
+                            .unwrap_or_default()
+                            .to_string(),
+                        default: property.get("default").cloned(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self {
+            name: name.into(),
+            version: version.into(),
+            description: description.into(),
+            options,
+            options_schema,
+        }
+    }
+}
+
 /// Main trait for language-specific code generators.
 ///
 /// Implement this trait to create a custom generator for a language.
@@ -123,14 +672,82 @@ pub trait Generator: Send + Sync {
     /// Generate code from the IR into a virtual file system.
     fn generate(&self, ir: &GenIr, config: &Config) -> Result<VirtualFS>;
 
+    /// Stream generated files into `sink` instead of building a VirtualFS.
+    ///
+    /// The default implementation generates the whole VirtualFS and replays it
+    /// in path order. Generators with large outputs should override this to
+    /// emit each file as soon as it is rendered (in a deterministic order) and
+    /// implement `generate` on top of it.
+    fn generate_streaming(
+        &self,
+        ir: &GenIr,
+        config: &Config,
+        sink: &mut dyn FileSink,
+    ) -> Result<()> {
+        let vfs = self.generate(ir, config)?;
+        for (path, content) in vfs.files() {
+            sink.write_file_with_mode(path, content, vfs.mode(path))?;
+        }
+        Ok(())
+    }
+
     /// The language identifier (e.g., "typescript", "python", "rust").
     fn language(&self) -> &str;
 
+    /// Semver version of the generator, usually its crate's
+    /// `CARGO_PKG_VERSION`.
+    fn version(&self) -> &str {
+        "0.0.0"
+    }
+
+    /// One-line description, e.g. for `oas-gen list-generators`.
+    fn description(&self) -> &str {
+        ""
+    }
+
+    /// The generator's name, version, description and options.
+    fn info(&self) -> GeneratorInfo {
+        GeneratorInfo::new(
+            self.language(),
+            self.version(),
+            self.description(),
+            self.options_schema(),
+        )
+    }
+
     /// Optional: validate the IR before generation.
     fn validate(&self, _ir: &GenIr) -> Result<()> {
         Ok(())
     }
 
+    /// JSON Schema of the `lang_options` this generator understands. The
+    /// registry checks options against it before generating (see
+    /// [`options`]); `None`, the default, accepts anything.
+    fn options_schema(&self) -> Option<serde_json::Value> {
+        None
+    }
+
+    /// Tools run over the output after it is written, in order, e.g.
+    /// formatters or `npm install` (see [`postprocess`]).
+    fn post_processors(&self) -> Vec<Box<dyn postprocess::PostProcessor>> {
+        Vec::new()
+    }
+
+    /// Compiler checking the written output, e.g. `tsc --noEmit`, run by
+    /// the registry when verification is asked for (see [`verify`]).
+    /// `None`, the default, can't verify.
+    fn verifier(&self) -> Option<Box<dyn verify::Verifier>> {
+        None
+    }
+
+    /// The generated files merged into one source file, for consumers that
+    /// want a single file rather than a tree. `None`, the default, means the
+    /// language's files can't be merged; they can still be concatenated with
+    /// [`VirtualFS::write_concatenated`].
+    fn merge_files(&self, _vfs: &VirtualFS) -> Result<Option<String>> {
+        Ok(None)
+    }
+
     // === Hooks ===
     // These methods allow generators to perform actions before/after key steps.
     // All have default implementations that do nothing, making them optional.
@@ -147,9 +764,9 @@ pub trait Generator: Send + Sync {
         Ok(())
     }
 
-    /// Called after files have been written to disk.
-    /// Can be used to run post-generation commands like `npm install`,
-    /// formatting, or build steps.
+    /// Called after files have been written to disk and the
+    /// [`post_processors`](Self::post_processors) ran. Prefer declaring
+    /// external commands as post-processors over running them here.
     fn after_write_to_disk(&self, _output_dir: &Path, _vfs: &VirtualFS) -> Result<()> {
         Ok(())
     }
@@ -235,6 +852,10 @@ mod tests {
                         deprecated: false,
                         const_value: Some(Literal::String("pet".to_string())),
                         wire_name: "type".to_string(),
+                        sensitive: false,
+                        time_format: None,
+                        coerce_number: false,
+                        format: None,
                     },
                     Field {
                         name: CanonicalName::from_string("name"),
@@ -250,6 +871,10 @@ mod tests {
                         deprecated: false,
                         const_value: None,
                         wire_name: "name".to_string(),
+                        sensitive: false,
+                        time_format: None,
+                        coerce_number: false,
+                        format: None,
                     },
                     Field {
                         name: CanonicalName::from_string("active"),
@@ -265,6 +890,10 @@ mod tests {
                         deprecated: false,
                         const_value: Some(Literal::Bool(true)),
                         wire_name: "active".to_string(),
+                        sensitive: false,
+                        time_format: None,
+                        coerce_number: false,
+                        format: None,
                     },
                 ],
                 additional: Additional::Forbidden,
@@ -282,12 +911,15 @@ mod tests {
                 version: "1.0.0".to_string(),
                 package_name: CanonicalName::from_string("test-api"),
                 docs: Docs::default(),
+                spec_hash: String::new(),
             },
             types,
             services: vec![],
             auth_schemes: vec![],
             errors: vec![],
             server_sets: vec![],
+            webhooks: vec![],
+            fallbacks: Vec::new(),
         };
 
         let generator = MockGenerator;
@@ -303,4 +935,51 @@ mod tests {
         assert!(content.contains("name: String\n")); // No const value
         assert!(content.contains("active: Boolean = const Bool(true)"));
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_modes() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("oas-gen-modes-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mode = |path: &str| {
+            std::fs::metadata(dir.join(path))
+                .unwrap()
+                .permissions()
+                .mode()
+                & 0o777
+        };
+
+        let mut vfs = VirtualFS::new();
+        vfs.add_file("scripts/release.sh", "#!/bin/sh\nnpm publish\n");
+        vfs.set_executable("scripts/release.sh");
+        vfs.add_file("README.md", "# Pets\n");
+        assert!(vfs.is_executable(Path::new("scripts/release.sh")));
+        assert_eq!(vfs.mode(Path::new("README.md")), DEFAULT_FILE_MODE);
+        vfs.write_to_disk(&dir).unwrap();
+        assert_eq!(mode("scripts/release.sh"), EXECUTABLE_FILE_MODE);
+
+        // Unchanged files still get their mode back
+        std::fs::set_permissions(
+            dir.join("scripts/release.sh"),
+            std::fs::Permissions::from_mode(0o644),
+        )
+        .unwrap();
+        vfs.write_to_disk(&dir).unwrap();
+        assert_eq!(mode("scripts/release.sh"), EXECUTABLE_FILE_MODE);
+
+        // Streaming keeps modes too
+        std::fs::remove_dir_all(&dir).unwrap();
+        let mut sink = DiskSink::new(&dir);
+        for (path, content) in vfs.files() {
+            sink.write_file_with_mode(path, content, vfs.mode(path))
+                .unwrap();
+        }
+        sink.finish().unwrap();
+        assert_eq!(mode("scripts/release.sh"), EXECUTABLE_FILE_MODE);
+        assert_eq!(mode("README.md") & 0o111, 0);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -0,0 +1,372 @@
+//! The record of what was generated into an output directory.
+//!
+//! Writing a [`VirtualFS`] leaves [`MANIFEST_PATH`] listing the generated
+//! files with a hash of each, the generator version and the hash of the
+//! spec they came from. The next write deletes the files it lists that are
+//! no longer generated, so removed operations don't leave dead modules
+//! behind, and [`VirtualFS::diff`] reports only those as deleted. Files
+//! edited by hand since are kept.
+//! [`Manifest::edited`] finds generated files that were changed by hand
+//! since, which the next write would overwrite.
+//!
+//! The manifest also records the cache key of the generation: a hash of the
+//! spec, the generator with its version and the config. When the next run
+//! has the same key, [`Manifest::read_files`] gives back the files on disk
+//! instead of generating them again.
+
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{GENERATOR_VERSION, VirtualFS, protected};
+
+/// Where the manifest is written, relative to the output directory.
+pub const MANIFEST_PATH: &str = ".oas-gen/manifest.json";
+
+/// Files generated into an output directory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    /// Name and version of the generator that wrote the files
+    /// ([`GENERATOR_VERSION`]).
+    pub generator: String,
+    /// Hash of the spec document the files were generated from
+    /// ([`ApiMeta::spec_hash`](ir::gen_ir::ApiMeta::spec_hash)), when known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spec_hash: Option<String>,
+    /// Hash of the spec, generator and config the files were generated
+    /// from ([`VirtualFS::cache_key`]), when known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_key: Option<String>,
+    /// The generated files, sorted by path.
+    pub files: Vec<ManifestEntry>,
+}
+
+/// One generated file of a [`Manifest`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// Path relative to the output directory.
+    pub path: PathBuf,
+    /// [`content_hash`] of the file as generated.
+    pub hash: String,
+}
+
+impl Default for Manifest {
+    fn default() -> Self {
+        Self {
+            generator: GENERATOR_VERSION.to_string(),
+            spec_hash: None,
+            cache_key: None,
+            files: Vec::new(),
+        }
+    }
+}
+
+/// Hash of a generated file's content, ignoring the bodies of its
+/// [`protected`] regions so that code written there doesn't count as an
+/// edit. Hashed with [`ir::gen_ir::spec_hash`], which is stable across Rust
+/// releases.
+pub fn content_hash(content: &[u8]) -> String {
+    let text = std::str::from_utf8(content).ok().map(protected::generated_part);
+    ir::gen_ir::spec_hash(text.as_deref().map_or(content, str::as_bytes))
+}
+
+impl Manifest {
+    /// The manifest of writing `vfs`.
+    pub fn of(vfs: &VirtualFS) -> Self {
+        let mut manifest = Self::from_files(vfs.files());
+        manifest.spec_hash = vfs.spec_hash().map(str::to_string);
+        manifest.cache_key = vfs.cache_key().map(str::to_string);
+        manifest
+    }
+
+    /// The manifest of generating `files`, each a path relative to the
+    /// output directory and its content.
+    pub fn from_files<'a>(files: impl IntoIterator<Item = (&'a Path, &'a [u8])>) -> Self {
+        Self::from_entries(files.into_iter().map(|(path, content)| ManifestEntry {
+            path: path.to_path_buf(),
+            hash: content_hash(content),
+        }))
+    }
+
+    /// The manifest listing `entries`; for a path listed twice, the last
+    /// entry wins, as the last write of a file does.
+    pub fn from_entries(entries: impl IntoIterator<Item = ManifestEntry>) -> Self {
+        let mut files: Vec<ManifestEntry> = entries.into_iter().collect();
+        files.reverse();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        files.dedup_by(|a, b| a.path == b.path);
+        Self {
+            files,
+            ..Self::default()
+        }
+    }
+
+    /// Set the hash of the spec the files were generated from.
+    pub fn with_spec_hash(mut self, spec_hash: impl Into<String>) -> Self {
+        self.spec_hash = Some(spec_hash.into());
+        self
+    }
+
+    /// Paths of the listed files.
+    pub fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.files.iter().map(|entry| entry.path.as_path())
+    }
+
+    /// Whether `path` is listed.
+    pub fn contains(&self, path: &Path) -> bool {
+        self.files.iter().any(|entry| entry.path == path)
+    }
+
+    /// Read the manifest of `base_path`, `None` if nothing was generated
+    /// there yet.
+    pub fn read(base_path: &Path) -> io::Result<Option<Self>> {
+        let content = match std::fs::read(base_path.join(MANIFEST_PATH)) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        serde_json::from_slice(&content).map(Some).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "invalid {} (delete it to regenerate everything): {}",
+                    base_path.join(MANIFEST_PATH).display(),
+                    e
+                ),
+            )
+        })
+    }
+
+    /// Write the manifest into `base_path`.
+    pub fn write(&self, base_path: &Path) -> io::Result<()> {
+        let path = base_path.join(MANIFEST_PATH);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut content = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        content.push('\n');
+        std::fs::write(path, content)
+    }
+
+    /// The listed files as they are under `base_path`, with the recorded
+    /// spec hash and cache key. `None` if any of them was deleted or edited
+    /// by hand, so the output has to be generated again.
+    pub fn read_files(&self, base_path: &Path) -> io::Result<Option<VirtualFS>> {
+        let mut vfs = VirtualFS::new();
+        for entry in &self.files {
+            let content = match std::fs::read(base_path.join(&entry.path)) {
+                Ok(content) => content,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+                Err(e) => return Err(e),
+            };
+            if content_hash(&content) != entry.hash {
+                return Ok(None);
+            }
+            vfs.add_binary(entry.path.clone(), content);
+        }
+        if let Some(spec_hash) = &self.spec_hash {
+            vfs.set_spec_hash(spec_hash);
+        }
+        if let Some(cache_key) = &self.cache_key {
+            vfs.set_cache_key(cache_key);
+        }
+        Ok(Some(vfs))
+    }
+
+    /// Record the hashes of the listed files as they are now under
+    /// `base_path`, e.g. after a formatter rewrote them, so they don't count
+    /// as edited. Deleted files keep their hash.
+    pub fn rehash(&mut self, base_path: &Path) -> io::Result<()> {
+        for entry in &mut self.files {
+            match std::fs::read(base_path.join(&entry.path)) {
+                Ok(content) => entry.hash = content_hash(&content),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    /// Listed files that `current` no longer generates.
+    pub fn stale<'a>(&'a self, current: &'a Manifest) -> impl Iterator<Item = &'a Path> {
+        self.paths().filter(|path| !current.contains(path))
+    }
+
+    /// Listed files under `base_path` whose content no longer matches their
+    /// hash, i.e. that were edited by hand since they were generated.
+    /// Deleted files don't count.
+    pub fn edited(&self, base_path: &Path) -> io::Result<Vec<PathBuf>> {
+        let mut edited = Vec::new();
+        for entry in &self.files {
+            match std::fs::read(base_path.join(&entry.path)) {
+                Ok(content) if content_hash(&content) != entry.hash => {
+                    edited.push(entry.path.clone());
+                }
+                Ok(_) => {}
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(edited)
+    }
+
+    /// Delete the files `current` no longer generates, and the directories
+    /// left empty by that. Files edited by hand since they were generated
+    /// are kept, and returned; `current` doesn't list them, so from then on
+    /// they are the user's own.
+    pub fn remove_stale(&self, base_path: &Path, current: &Manifest) -> io::Result<Vec<PathBuf>> {
+        let mut kept = Vec::new();
+        for entry in &self.files {
+            let path = entry.path.as_path();
+            if current.contains(path) {
+                continue;
+            }
+            // A hand-edited manifest mustn't delete anything outside the output
+            if !path
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+            {
+                continue;
+            }
+            let full_path = base_path.join(path);
+            match std::fs::read(&full_path) {
+                Ok(content) if content_hash(&content) != entry.hash => {
+                    kept.push(path.to_path_buf());
+                    continue;
+                }
+                Ok(_) => {}
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            }
+            std::fs::remove_file(full_path)?;
+            for dir in path.ancestors().skip(1) {
+                if dir.as_os_str().is_empty() || std::fs::remove_dir(base_path.join(dir)).is_err() {
+                    break;
+                }
+            }
+        }
+        Ok(kept)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_incremental_write() {
+        let dir = std::env::temp_dir().join(format!("oas-gen-manifest-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let mut vfs = VirtualFS::new();
+        vfs.add_file("src/index.ts", "export * from './services/pets';\n");
+        vfs.add_file("src/services/pets.ts", "export class PetsService {}\n");
+        vfs.add_file("src/services/stores.ts", "export class StoresService {}\n");
+        vfs.write_to_disk(&dir).unwrap();
+        assert_eq!(Manifest::read(&dir).unwrap(), Some(Manifest::of(&vfs)));
+        std::fs::write(dir.join("README.local.md"), "notes\n").unwrap();
+        std::fs::write(
+            dir.join("src/services/stores.ts"),
+            "export class Stores {}\n",
+        )
+        .unwrap();
+
+        let mut next = VirtualFS::new();
+        next.add_file("src/index.ts", "export * from './models';\n");
+        next.add_file("src/models/pet.ts", "export interface Pet {}\n");
+        // Only files the previous run generated count as deleted
+        let diff = next.diff(&dir).unwrap();
+        assert_eq!(diff.summary(), "1 added, 1 changed, 1 deleted");
+
+        next.write_to_disk(&dir).unwrap();
+        // Edited by hand, so kept, and no longer tracked
+        assert!(!dir.join("src/services/pets.ts").exists());
+        assert!(dir.join("src/services/stores.ts").is_file());
+        assert!(
+            !Manifest::read(&dir)
+                .unwrap()
+                .unwrap()
+                .contains(Path::new("src/services/stores.ts"))
+        );
+        std::fs::remove_file(dir.join("src/services/stores.ts")).unwrap();
+        assert!(dir.join("src/models/pet.ts").is_file());
+        assert!(dir.join("README.local.md").is_file());
+        assert!(next.diff(&dir).unwrap().is_empty());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_hashes_and_edits() {
+        let dir = std::env::temp_dir().join(format!("oas-gen-edited-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let mut vfs = VirtualFS::new();
+        vfs.set_spec_hash("0123456789abcdef");
+        vfs.add_file("src/index.ts", "export * from './pets';\n");
+        vfs.add_file(
+            "src/pets.ts",
+            "// oas-gen:begin keep\nthrow new Error('todo');\n// oas-gen:end keep\n",
+        );
+        vfs.write_to_disk(&dir).unwrap();
+
+        let manifest = Manifest::read(&dir).unwrap().unwrap();
+        assert_eq!(manifest.generator, GENERATOR_VERSION);
+        assert_eq!(manifest.spec_hash.as_deref(), Some("0123456789abcdef"));
+        assert_eq!(
+            manifest.files[0].hash,
+            content_hash(b"export * from './pets';\n")
+        );
+        assert!(manifest.edited(&dir).unwrap().is_empty());
+
+        // Code in protected regions isn't an edit
+        std::fs::write(
+            dir.join("src/pets.ts"),
+            "// oas-gen:begin keep\nreturn [];\n// oas-gen:end keep\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("src/index.ts"), "export {};\n").unwrap();
+        assert_eq!(
+            manifest.edited(&dir).unwrap(),
+            vec![PathBuf::from("src/index.ts")]
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_read_files() {
+        let dir = std::env::temp_dir().join(format!("oas-gen-read-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let mut vfs = VirtualFS::new();
+        vfs.set_spec_hash("0123456789abcdef");
+        vfs.set_cache_key("fedcba9876543210");
+        vfs.add_file("src/index.ts", "export * from './pets';\n");
+        vfs.add_file("src/pets.ts", "export class PetsService {}\n");
+        vfs.write_to_disk(&dir).unwrap();
+
+        let manifest = Manifest::read(&dir).unwrap().unwrap();
+        assert_eq!(manifest.cache_key.as_deref(), Some("fedcba9876543210"));
+        let cached = manifest.read_files(&dir).unwrap().unwrap();
+        assert_eq!(
+            cached.files().collect::<Vec<_>>(),
+            vfs.files().collect::<Vec<_>>()
+        );
+        assert_eq!(cached.cache_key(), Some("fedcba9876543210"));
+
+        std::fs::write(dir.join("src/pets.ts"), "export {};\n").unwrap();
+        assert!(manifest.read_files(&dir).unwrap().is_none());
+        std::fs::remove_file(dir.join("src/pets.ts")).unwrap();
+        assert!(manifest.read_files(&dir).unwrap().is_none());
+
+        // A rewrite that is recorded, as after a formatter, isn't an edit
+        std::fs::write(dir.join("src/pets.ts"), "export class PetsService {}\n\n").unwrap();
+        let mut manifest = manifest;
+        manifest.rehash(&dir).unwrap();
+        assert!(manifest.read_files(&dir).unwrap().is_some());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,266 @@
+//! Typed, validated `lang_options`.
+//!
+//! A generator declares the options it understands in two forms: a serde
+//! struct it reads them into with [`Config::lang_options_as`], and a JSON
+//! Schema returned from [`Generator::options_schema`](crate::Generator::options_schema).
+//! The registry checks `lang_options` against the schema before generating,
+//! so a typo fails with the known keys instead of being silently ignored:
+//!
+//! ```
+//! use codegen::{Config, options};
+//! use serde_json::json;
+//!
+//! let schema = json!({
+//!     "type": "object",
+//!     "additionalProperties": false,
+//!     "properties": { "cache": { "type": "boolean" } }
+//! });
+//! let mut config = Config::default();
+//! config.lang_options.insert("cahce".to_string(), json!(false));
+//! let err = options::validate("typescript", &schema, &config.lang_options).unwrap_err();
+//! assert!(err.to_string().contains("did you mean `cache`?"));
+//! ```
+//!
+//! Only `type` (a name or a list), `enum`, `items`, `properties` and
+//! `additionalProperties: false` are checked; other keywords are
+//! documentation.
+
+use std::collections::BTreeMap;
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::{Config, Error, Result};
+
+/// Options the registry handles itself, accepted whatever the schema says.
+pub const REGISTRY_OPTIONS: &[&str] = &[Config::NULLABILITY_REPORT];
+
+/// Check `options` against a generator's options `schema`, reporting every
+/// problem at once.
+pub fn validate(language: &str, schema: &Value, options: &BTreeMap<String, Value>) -> Result<()> {
+    let mut problems = Vec::new();
+    let options: serde_json::Map<String, Value> = options
+        .iter()
+        .filter(|(key, _)| !REGISTRY_OPTIONS.contains(&key.as_str()))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    check(schema, &Value::Object(options), "", &mut problems);
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::ConfigError(format!(
+            "invalid lang_options for {}: {}",
+            language,
+            problems.join("; ")
+        )))
+    }
+}
+
+impl Config {
+    /// Read `lang_options` into a generator's options struct. Registry
+    /// options are left out, so the struct can deny unknown fields.
+    pub fn lang_options_as<T: DeserializeOwned>(&self) -> Result<T> {
+        let options: serde_json::Map<String, Value> = self
+            .lang_options
+            .iter()
+            .filter(|(key, _)| !REGISTRY_OPTIONS.contains(&key.as_str()))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        serde_json::from_value(Value::Object(options))
+            .map_err(|e| Error::ConfigError(format!("invalid lang_options: {}", e)))
+    }
+}
+
+fn check(schema: &Value, value: &Value, path: &str, problems: &mut Vec<String>) {
+    let name = if path.is_empty() { "options" } else { path };
+
+    if let Some(types) = schema.get("type") {
+        let allowed: Vec<&str> = match types {
+            Value::String(ty) => vec![ty.as_str()],
+            Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !allowed.is_empty() && !allowed.iter().any(|ty| has_type(value, ty)) {
+            problems.push(format!(
+                "`{}` must be {}, got {}",
+                name,
+                allowed.join(" or "),
+                value
+            ));
+            return;
+        }
+    }
+
+    if let Some(Value::Array(values)) = schema.get("enum")
+        && !values.contains(value)
+    {
+        let values: Vec<String> = values.iter().map(Value::to_string).collect();
+        problems.push(format!(
+            "`{}` must be one of {}, got {}",
+            name,
+            values.join(", "),
+            value
+        ));
+    }
+
+    if let Value::Object(object) = value {
+        let properties = schema.get("properties").and_then(Value::as_object);
+        let closed = schema.get("additionalProperties") == Some(&Value::Bool(false));
+        for (key, field) in object {
+            let field_path = if path.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", path, key)
+            };
+            match properties.and_then(|properties| properties.get(key)) {
+                Some(field_schema) => check(field_schema, field, &field_path, problems),
+                None if closed => {
+                    let known: Vec<&str> = properties
+                        .map(|properties| properties.keys().map(String::as_str).collect())
+                        .unwrap_or_default();
+                    problems.push(unknown_key(&field_path, key, &known));
+                }
+                None => {}
+            }
+        }
+    }
+
+    if let (Value::Array(items), Some(item_schema)) = (value, schema.get("items")) {
+        for (i, item) in items.iter().enumerate() {
+            check(item_schema, item, &format!("{}[{}]", name, i), problems);
+        }
+    }
+}
+
+fn unknown_key(path: &str, key: &str, known: &[&str]) -> String {
+    let closest = known
+        .iter()
+        .map(|candidate| (edit_distance(key, candidate), *candidate))
+        .filter(|(distance, _)| *distance <= 2)
+        .min();
+    match closest {
+        Some((_, candidate)) => format!("unknown option `{}`, did you mean `{}`?", path, candidate),
+        None if known.is_empty() => format!("unknown option `{}`", path),
+        None => format!(
+            "unknown option `{}` (expected one of {})",
+            path,
+            known.join(", ")
+        ),
+    }
+}
+
+fn has_type(value: &Value, ty: &str) -> bool {
+    match ty {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        _ => true,
+    }
+}
+
+/// Levenshtein distance, for suggesting the option a typo meant.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                diagonal
+            } else {
+                1 + diagonal.min(above).min(row[j])
+            };
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "layout": { "type": "string", "enum": ["standard", "flat"] },
+                "cache": { "type": "boolean" },
+                "shared_types": { "type": "array", "items": { "type": "string" } }
+            }
+        })
+    }
+
+    fn options(value: Value) -> BTreeMap<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_validate_options() {
+        let valid = options(json!({
+            "layout": "flat",
+            "shared_types": ["Pet"],
+            "nullability_report": true
+        }));
+        assert!(validate("typescript", &schema(), &valid).is_ok());
+
+        let invalid = options(json!({
+            "layuot": "flat",
+            "cache": "yes",
+            "shared_types": [1],
+            "colour": true
+        }));
+        let message = validate("typescript", &schema(), &invalid)
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            message,
+            "Config error: invalid lang_options for typescript: \
+             `cache` must be boolean, got \"yes\"; \
+             unknown option `colour` (expected one of cache, layout, shared_types); \
+             unknown option `layuot`, did you mean `layout`?; \
+             `shared_types[0]` must be string, got 1"
+        );
+
+        let wrong_enum = options(json!({ "layout": "nested" }));
+        assert!(
+            validate("typescript", &schema(), &wrong_enum)
+                .unwrap_err()
+                .to_string()
+                .contains("`layout` must be one of \"standard\", \"flat\", got \"nested\"")
+        );
+    }
+
+    #[test]
+    fn test_lang_options_as() {
+        #[derive(Debug, Default, Deserialize, PartialEq)]
+        #[serde(default, deny_unknown_fields)]
+        struct Options {
+            cache: bool,
+        }
+
+        let mut config = Config {
+            lang_options: options(json!({ "cache": true, "nullability_report": true })),
+            ..Config::default()
+        };
+        assert_eq!(
+            config.lang_options_as::<Options>().unwrap(),
+            Options { cache: true }
+        );
+
+        config.lang_options.insert("cahce".to_string(), json!(true));
+        assert!(matches!(
+            config.lang_options_as::<Options>(),
+            Err(Error::ConfigError(_))
+        ));
+    }
+}
@@ -0,0 +1,539 @@
+//! User-supplied replacements for a generator's built-in templates.
+//!
+//! Generators compile their templates in. Setting
+//! [`Config::template_dir`](crate::Config::template_dir) lets a team change
+//! the emitted code without rebuilding them: a file in that directory with
+//! the name of a built-in template (e.g. `interface.ts.jinja`) is rendered
+//! instead, at runtime, with [minijinja](https://docs.rs/minijinja).
+//! Templates without an override keep using the built-in one.
+//!
+//! An override receives the same data as the template it replaces,
+//! serialized, so `{{ name.pascal }}` and `{% for field in fields %}` work
+//! as they do in the built-in template. Overrides can `{% include %}` and
+//! `{% extends %}` other files of the directory. Undefined variables are
+//! errors, so a typo fails generation instead of silently emitting nothing.
+//!
+//! A copy of a built-in template is a valid override: the Askama syntax
+//! the built-in templates use is translated when an override is loaded.
+//! `if let`, `else if`, `let`, `match`/`when`, `!`, `&&` and `||` become
+//! their Jinja equivalents, enum variants compare against their serialized
+//! form, and the methods `is_empty()`, `is_some()`, `is_none()`, `len()`,
+//! `first()` and `replace(from, to)` are available. As in the built-in
+//! templates, the final newline of a file is dropped. Generators register
+//! the filters of their templates with [`TemplateOverrides::add_filter`].
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use minijinja::value::{Rest, ValueKind};
+use minijinja::{AutoEscape, Environment, ErrorKind, State, UndefinedBehavior, Value};
+use serde::Serialize;
+
+use crate::{Config, Error, Result};
+
+/// Templates loaded from a directory; none by default.
+#[derive(Debug, Default)]
+pub struct TemplateOverrides {
+    source: Option<Source>,
+}
+
+/// The directory of the overrides, the templates in it and the environment
+/// loading them.
+#[derive(Debug)]
+struct Source {
+    dir: PathBuf,
+    /// Paths of the files in `dir`, relative and `/`-separated.
+    names: BTreeSet<String>,
+    env: Environment<'static>,
+}
+
+impl TemplateOverrides {
+    /// Override templates with the files in `dir`.
+    pub fn load(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        if !dir.is_dir() {
+            return Err(Error::ConfigError(format!(
+                "template directory {} does not exist",
+                dir.display()
+            )));
+        }
+        let mut names = BTreeSet::new();
+        list_templates(&dir, "", &mut names)?;
+
+        let mut env = Environment::new();
+        let (root, known) = (dir.clone(), names.clone());
+        env.set_loader(move |name| {
+            if !known.contains(name) {
+                return Ok(None);
+            }
+            std::fs::read_to_string(root.join(name))
+                .map(|source| Some(translate(&source)))
+                .map_err(|e| {
+                    minijinja::Error::new(ErrorKind::InvalidOperation, "could not read template")
+                        .with_source(e)
+                })
+        });
+        env.set_auto_escape_callback(|_| AutoEscape::None);
+        env.set_undefined_behavior(UndefinedBehavior::Strict);
+        env.set_unknown_method_callback(call_method);
+        env.add_filter("fmt", fmt);
+        Ok(Self {
+            source: Some(Source { dir, names, env }),
+        })
+    }
+
+    /// The overrides of [`Config::template_dir`], if it is set.
+    pub fn from_config(config: &Config) -> Result<Self> {
+        match &config.template_dir {
+            Some(dir) => Self::load(dir),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Whether `name` is overridden.
+    pub fn contains(&self, name: &str) -> bool {
+        self.source
+            .as_ref()
+            .is_some_and(|source| source.names.contains(name))
+    }
+
+    /// Make the filter `name` available to overrides. `filter` receives the
+    /// filtered value and the filter's arguments, e.g. a generator's
+    /// `{{ field.name|escape_rust_keyword(naming) }}`.
+    pub fn add_filter<F>(&mut self, name: &'static str, filter: F)
+    where
+        F: Fn(&Value, &[Value]) -> std::result::Result<Value, minijinja::Error>
+            + Send
+            + Sync
+            + 'static,
+    {
+        if let Some(source) = &mut self.source {
+            source
+                .env
+                .add_filter(name, move |value: Value, args: Rest<Value>| {
+                    filter(&value, &args)
+                });
+        }
+    }
+
+    /// Render the override of `name` with `data`, or `None` if `name`
+    /// isn't overridden.
+    pub fn render(&self, name: &str, data: &impl Serialize) -> Option<Result<String>> {
+        let source = self.source.as_ref()?;
+        if !source.names.contains(name) {
+            return None;
+        }
+        let rendered = source
+            .env
+            .get_template(name)
+            .and_then(|template| template.render(Value::from_serialize(data)))
+            .map_err(|e| {
+                Error::TemplateError(format!("{}: {:#}", source.dir.join(name).display(), e).into())
+            });
+        Some(rendered)
+    }
+}
+
+/// Render `data` with the override of its template `name` if there is one,
+/// else with the built-in template.
+pub fn render<T: askama::Template + Serialize>(
+    templates: &TemplateOverrides,
+    name: &str,
+    data: &T,
+) -> Result<String> {
+    match templates.render(name, data) {
+        Some(rendered) => rendered,
+        None => data.render().map_err(|e| Error::TemplateError(Box::new(e))),
+    }
+}
+
+/// Add the files below `dir` to `names`, as `prefix`-relative template names.
+fn list_templates(dir: &Path, prefix: &str, names: &mut BTreeSet<String>) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = format!("{}{}", prefix, entry.file_name().to_string_lossy());
+        if entry.file_type()?.is_dir() {
+            list_templates(&entry.path(), &format!("{}/", name), names)?;
+        } else {
+            names.insert(name);
+        }
+    }
+    Ok(())
+}
+
+/// The methods built-in templates call on their data.
+fn call_method(
+    _: &State,
+    value: &Value,
+    method: &str,
+    args: &[Value],
+) -> std::result::Result<Value, minijinja::Error> {
+    match (method, args) {
+        ("is_empty", []) => Ok(Value::from(
+            value.len().map_or(value.is_none(), |len| len == 0),
+        )),
+        ("is_some", []) => Ok(Value::from(!value.is_none())),
+        ("is_none", []) => Ok(Value::from(value.is_none())),
+        ("len", []) => value.len().map(Value::from).ok_or_else(|| {
+            minijinja::Error::new(ErrorKind::InvalidOperation, "value has no length")
+        }),
+        ("first", []) => Ok(value.try_iter()?.next().unwrap_or(Value::from(()))),
+        ("replace", [from, to]) => match (value.as_str(), from.as_str(), to.as_str()) {
+            (Some(value), Some(from), Some(to)) => Ok(Value::from(value.replace(from, to))),
+            _ => Err(minijinja::Error::new(
+                ErrorKind::InvalidOperation,
+                "replace() takes strings",
+            )),
+        },
+        _ => Err(minijinja::Error::new(
+            ErrorKind::UnknownMethod,
+            format!("no method `{}`", method),
+        )),
+    }
+}
+
+/// Askama's `fmt` filter for the formats the built-in templates use: `{}`,
+/// and `{:?}` of enums, which renders variants with data as `Variant(data)`.
+fn fmt(value: Value, format: &str) -> std::result::Result<String, minijinja::Error> {
+    match format {
+        "{}" => Ok(value.to_string()),
+        "{:?}" if value.kind() == ValueKind::Map && value.len() == Some(1) => {
+            let variant = value.try_iter()?.next().unwrap_or_default();
+            let data = value.get_item(&variant)?;
+            match data.as_str() {
+                Some(data) => Ok(format!("{}({:?})", variant, data)),
+                None => Ok(format!("{}({})", variant, data)),
+            }
+        }
+        "{:?}" => Ok(value.to_string()),
+        _ => Err(minijinja::Error::new(
+            ErrorKind::InvalidOperation,
+            format!("unsupported format `{}`", format),
+        )),
+    }
+}
+
+/// Translate the Askama-specific syntax in `source` to Jinja.
+fn translate(source: &str) -> String {
+    let mut out = String::with_capacity(source.len());
+    // The variables holding the value of each open `match`, and whether its
+    // first `when` is still to come.
+    let mut matches: Vec<(String, bool)> = Vec::new();
+    let mut count = 0;
+    let mut rest = source;
+    while let Some(start) = rest.find('{') {
+        let (text, tail) = rest.split_at(start);
+        let close = match tail.get(..2) {
+            Some("{%") => "%}",
+            Some("{{") => "}}",
+            Some("{#") => "#}",
+            _ => {
+                out.push_str(&rest[..=start]);
+                rest = &rest[start + 1..];
+                continue;
+            }
+        };
+        // Askama drops the text between `match` and its first `when`
+        if !matches.last().is_some_and(|(_, first)| *first) {
+            out.push_str(text);
+        }
+        let Some(end) = tail[2..].find(close) else {
+            out.push_str(tail);
+            return out;
+        };
+        let inner = &tail[2..2 + end];
+        rest = &tail[2 + end + 2..];
+        match close {
+            "%}" => out.push_str(&translate_tag(inner, &mut matches, &mut count)),
+            "}}" => {
+                let (left, body, right) = markers(inner);
+                out.push_str(&format!("{{{{{} {} {}}}}}", left, expression(body), right));
+            }
+            _ => out.push_str(&tail[..2 + end + 2]),
+        }
+    }
+    if !matches.last().is_some_and(|(_, first)| *first) {
+        out.push_str(rest);
+    }
+    out
+}
+
+/// Split the whitespace-control markers off a tag's content. Askama's `~`
+/// becomes `-`.
+fn markers(inner: &str) -> (&str, &str, &str) {
+    let marker = |c: Option<char>| match c {
+        Some('-' | '~') => "-",
+        Some('+') => "+",
+        _ => "",
+    };
+    let left = marker(inner.chars().next());
+    let right = if inner.len() > left.len() {
+        marker(inner.chars().last())
+    } else {
+        ""
+    };
+    let body = &inner[left.len()..inner.len() - right.len()];
+    (left, body.trim(), right)
+}
+
+/// Translate the block tag with content `inner`.
+fn translate_tag(inner: &str, matches: &mut Vec<(String, bool)>, count: &mut usize) -> String {
+    let (left, body, right) = markers(inner);
+    let tag = |body: &str| format!("{{%{} {} {}%}}", left, body, right);
+    let (keyword, args) = body.split_once(char::is_whitespace).unwrap_or((body, ""));
+    let args = args.trim();
+    match keyword {
+        "if" | "else" => {
+            let (keyword, args) = match (keyword, args.split_once(char::is_whitespace)) {
+                ("else", Some(("if", args))) => ("elif", args.trim()),
+                ("else", _) => return tag("else"),
+                _ => ("if", args),
+            };
+            match args
+                .strip_prefix("let ")
+                .and_then(|args| args.split_once('='))
+            {
+                Some((pattern, subject)) => conditional(
+                    keyword,
+                    pattern.trim(),
+                    &format!("({})", expression(subject.trim())),
+                    left,
+                    right,
+                ),
+                None => tag(&format!("{} {}", keyword, expression(args))),
+            }
+        }
+        "let" => tag(&format!("set {}", expression(args))),
+        "match" => {
+            *count += 1;
+            let subject = format!("oas_match_{}", count);
+            let set = tag(&format!("set {} = {}", subject, expression(args)));
+            matches.push((subject, true));
+            set
+        }
+        "when" => {
+            let Some((subject, first)) = matches.last_mut() else {
+                return tag(body);
+            };
+            let keyword = if std::mem::replace(first, false) {
+                "if"
+            } else {
+                "elif"
+            };
+            if args == "_" {
+                return tag(if keyword == "if" { "if true" } else { "else" });
+            }
+            conditional(keyword, args, &subject.clone(), left, right)
+        }
+        "endmatch" => {
+            matches.pop();
+            tag("endif")
+        }
+        _ => tag(&format!("{} {}", keyword, expression(args))),
+    }
+}
+
+/// `{% <keyword> %}` testing `subject` against the Askama `pattern`, binding
+/// the variable the pattern names.
+fn conditional(keyword: &str, pattern: &str, subject: &str, left: &str, right: &str) -> String {
+    let (variant, binding) = match pattern.split_once(" with ") {
+        Some((variant, binding)) => (variant.trim(), Some(binding)),
+        None => match pattern.split_once('(') {
+            Some((variant, binding)) => (variant.trim(), Some(binding)),
+            None => (pattern, None),
+        },
+    };
+    let has_data = binding.is_some();
+    let binding = binding
+        .map(|binding| {
+            binding
+                .trim()
+                .trim_start_matches('(')
+                .trim_end_matches(')')
+                .trim()
+        })
+        .filter(|binding| *binding != "_");
+    let name = variant.rsplit("::").next().unwrap_or(variant);
+    let (condition, value) = match name {
+        "Some" => (format!("{} is not none", subject), subject.to_string()),
+        "None" => (format!("{} is none", subject), subject.to_string()),
+        _ if name
+            .starts_with(|c: char| c == '"' || c == '\'' || c.is_ascii_digit() || c == '-') =>
+        {
+            (format!("{} == {}", subject, name), subject.to_string())
+        }
+        // Variants with data serialize as `{"Variant": data}`, unit
+        // variants as `"Variant"`
+        _ if has_data => (
+            format!("{} is mapping and \"{}\" in {}", subject, name, subject),
+            format!("{}[\"{}\"]", subject, name),
+        ),
+        _ => (format!("{} == \"{}\"", subject, name), subject.to_string()),
+    };
+    match binding {
+        Some(binding) => format!(
+            "{{%{} {} {} %}}{{% set {} = {} {}%}}",
+            left, keyword, condition, binding, value, right
+        ),
+        None => format!("{{%{} {} {} {}%}}", left, keyword, condition, right),
+    }
+}
+
+/// Translate Askama's `!`, `&&` and `||` in an expression.
+fn expression(expr: &str) -> String {
+    let mut out = String::with_capacity(expr.len());
+    let mut quote = None;
+    let mut chars = expr.chars().peekable();
+    while let Some(c) = chars.next() {
+        if let Some(q) = quote {
+            out.push(c);
+            if c == '\\' {
+                out.extend(chars.next());
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"' | '\'', _) => {
+                quote = Some(c);
+                out.push(c);
+            }
+            ('!', Some('=')) => out.push(c),
+            ('!', _) => out.push_str(" not "),
+            ('&', Some('&')) | ('|', Some('|')) => {
+                chars.next();
+                out.push_str(if c == '&' { " and " } else { " or " });
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Alias<'a> {
+        name: &'a str,
+        target: &'a str,
+    }
+
+    #[test]
+    fn test_overrides() {
+        let dir = std::env::temp_dir().join(format!("oas-gen-templates-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("type_alias.ts.jinja"),
+            "{% include 'banner.jinja' %}\nexport type {{ name }} = {{ target }};\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("banner.jinja"), "/* <custom> */").unwrap();
+        std::fs::write(dir.join("enum.ts.jinja"), "{{ nmae }}").unwrap();
+
+        let overrides = TemplateOverrides::load(&dir).unwrap();
+        let data = Alias {
+            name: "PetId",
+            target: "string",
+        };
+        assert_eq!(
+            overrides
+                .render("type_alias.ts.jinja", &data)
+                .unwrap()
+                .unwrap(),
+            "/* <custom> */\nexport type PetId = string;"
+        );
+        assert!(overrides.render("interface.ts.jinja", &data).is_none());
+        let err = overrides
+            .render("enum.ts.jinja", &data)
+            .unwrap()
+            .unwrap_err();
+        assert!(err.to_string().contains("enum.ts.jinja"));
+
+        assert!(
+            TemplateOverrides::default()
+                .render("type_alias.ts.jinja", &data)
+                .is_none()
+        );
+        assert!(TemplateOverrides::load(dir.join("missing")).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[derive(Serialize)]
+    enum Status {
+        Code(u16),
+        Default,
+    }
+
+    #[derive(Serialize)]
+    struct Operation<'a> {
+        name: &'a str,
+        summary: Option<&'a str>,
+        tags: Vec<&'a str>,
+        status: Status,
+    }
+
+    #[test]
+    fn test_askama_syntax() {
+        let dir = std::env::temp_dir().join(format!("oas-gen-askama-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("partials")).unwrap();
+        std::fs::write(
+            dir.join("partials/op.jinja"),
+            concat!(
+                "{% if let Some(summary) = summary %}// {{ summary }}\n{% endif %}",
+                "{% let tag = tags.first() %}",
+                "{% if !tags.is_empty() && tag.is_some() %}{{ tag.replace(\"-\", \"_\") }} ",
+                "{% else if tags.len() == 0 || name == \"!\" %}untagged {% endif %}",
+                "{% match status %}",
+                "ignored",
+                "{% when Status::Code with (code) %}{{ name }} -> {{ code }}",
+                "{% when Status::Default %}{{ name }} -> default",
+                "{% endmatch %} {{ status|fmt(\"{:?}\") }}\n",
+            ),
+        )
+        .unwrap();
+        std::fs::write(dir.join("shout.jinja"), "{{ name|shout }}").unwrap();
+
+        let mut overrides = TemplateOverrides::load(&dir).unwrap();
+        assert!(overrides.contains("partials/op.jinja"));
+        assert!(!overrides.contains("op.jinja"));
+        overrides.add_filter("shout", |value, _| {
+            Ok(Value::from(value.to_string().to_uppercase()))
+        });
+
+        let op = Operation {
+            name: "getPet",
+            summary: Some("Get a pet"),
+            tags: vec!["pet-store"],
+            status: Status::Code(200),
+        };
+        assert_eq!(
+            overrides.render("partials/op.jinja", &op).unwrap().unwrap(),
+            "// Get a pet\npet_store getPet -> 200 Code(200)"
+        );
+        let op = Operation {
+            name: "ping",
+            summary: None,
+            tags: vec![],
+            status: Status::Default,
+        };
+        assert_eq!(
+            overrides.render("partials/op.jinja", &op).unwrap().unwrap(),
+            "untagged ping -> default Default"
+        );
+        assert_eq!(
+            overrides.render("shout.jinja", &op).unwrap().unwrap(),
+            "PING"
+        );
+        // Without the filter
+        let overrides = TemplateOverrides::load(&dir).unwrap();
+        assert!(overrides.render("shout.jinja", &op).unwrap().is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
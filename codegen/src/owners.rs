@@ -0,0 +1,50 @@
+//! CODEOWNERS output for generated files.
+
+use std::path::PathBuf;
+
+/// Render a CODEOWNERS fragment assigning generated files to their owners.
+///
+/// Paths are written relative to the output directory, so the fragment must
+/// be prefixed with that directory when merged into a repository's
+/// CODEOWNERS file. Files without owners are skipped; returns `None` when no
+/// file has an owner.
+pub fn codeowners(entries: &[(PathBuf, Vec<String>)]) -> Option<String> {
+    let lines: Vec<String> = entries
+        .iter()
+        .filter(|(_, owners)| !owners.is_empty())
+        .map(|(path, owners)| {
+            let pattern = path.to_string_lossy().replace('\\', "/");
+            format!("/{} {}", pattern, owners.join(" "))
+        })
+        .collect();
+
+    if lines.is_empty() {
+        return None;
+    }
+
+    Some(format!(
+        "# Generated code owners. Paths are relative to the generated output directory.\n{}\n",
+        lines.join("\n")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codeowners_skips_unowned_files() {
+        let entries = vec![
+            (
+                PathBuf::from("src/services/pets.ts"),
+                vec!["@acme/pets".to_string(), "ops@acme.dev".to_string()],
+            ),
+            (PathBuf::from("src/services/users.ts"), vec![]),
+        ];
+
+        let fragment = codeowners(&entries).unwrap();
+        assert!(fragment.ends_with("/src/services/pets.ts @acme/pets ops@acme.dev\n"));
+        assert!(!fragment.contains("users.ts"));
+        assert!(codeowners(&entries[1..]).is_none());
+    }
+}
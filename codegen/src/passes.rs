@@ -0,0 +1,608 @@
+//! Rewriting the IR before a generator sees it.
+//!
+//! [`Config::passes`](crate::Config::passes) runs the [`GenIr`] through a
+//! list of [`IrPass`]es before generating, so teams can adapt the output
+//! without writing a generator of their own: rename a type, drop
+//! operations, add a shared error type to every operation. The built-in
+//! passes are declared in config as [`PassConfig`]s, e.g. in an
+//! `oas-gen.toml`:
+//!
+//! ```toml
+//! [[options.passes]]
+//! pass = "overlay"
+//! path = "overlays/internal.yaml"
+//!
+//! [[options.passes]]
+//! pass = "rename_type"
+//! from = "Pet"
+//! to = "Animal"
+//!
+//! [[options.passes]]
+//! pass = "filter"
+//! exclude_tags = ["admin"]
+//!
+//! [[options.passes]]
+//! pass = "common_error"
+//! type = "Problem"
+//! status = 500
+//! ```
+//!
+//! Custom passes are added in code:
+//!
+//! ```
+//! use codegen::GenIr;
+//! use codegen::passes::{PassConfig, Passes};
+//!
+//! let mut passes = Passes::new();
+//! passes.declare(PassConfig::RenameType {
+//!     from: "Pet".to_string(),
+//!     to: "Animal".to_string(),
+//! });
+//! passes.push(|ir: &mut GenIr| -> codegen::Result<()> {
+//!     ir.webhooks.clear();
+//!     Ok(())
+//! });
+//! ```
+//!
+//! Passes run in order on the IR as built from the spec, before the
+//! config's own operation filter and naming policy, so they name types and
+//! tags as the spec does. A `filter` pass drops operations at its place in
+//! the list, so the passes after it only see the operations it keeps; the
+//! config's own filter always runs after every pass. Overlays rewrite the spec, not the IR: they are
+//! applied where the spec is parsed, so they must be declared before any
+//! other pass, and the IR of an overlaid spec is generated with
+//! [`Passes::without_overlays`]. Running an overlay on an IR fails.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use ir::gen_ir::{
+    CanonicalName, Docs, ErrorDecl, ErrorUse, ErrorVariant, StableId, StatusSpec, TypeRef,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{Error, GenIr, OperationFilter, Result};
+
+/// Rewrites the IR.
+pub trait IrPass: Send + Sync {
+    fn apply(&self, ir: &mut GenIr) -> Result<()>;
+}
+
+impl<F> IrPass for F
+where
+    F: Fn(&mut GenIr) -> Result<()> + Send + Sync,
+{
+    fn apply(&self, ir: &mut GenIr) -> Result<()> {
+        self(ir)
+    }
+}
+
+/// A built-in pass, as declared in config.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "pass", rename_all = "snake_case")]
+pub enum PassConfig {
+    /// Apply the [OpenAPI Overlay](https://spec.openapis.org/overlay/v1.0.0.html)
+    /// at `path` (YAML or JSON) to the spec.
+    Overlay { path: PathBuf },
+    /// Rename the schema `from` to `to`, updating every reference to it.
+    RenameType { from: String, to: String },
+    /// Drop operations with the same keys as the config's own
+    /// [`operation_filter`](crate::Config::operation_filter), but at this
+    /// point in the list of passes.
+    Filter {
+        #[serde(default)]
+        include_tags: Vec<String>,
+        #[serde(default)]
+        exclude_tags: Vec<String>,
+        #[serde(default)]
+        include_operations: Vec<String>,
+        #[serde(default)]
+        exclude_operations: Vec<String>,
+    },
+    /// Add an error variant of the schema `type` for the HTTP `status` to
+    /// every operation that doesn't declare that status, e.g. a shared
+    /// `Problem` for 500.
+    CommonError {
+        #[serde(rename = "type")]
+        type_name: String,
+        status: u16,
+    },
+}
+
+impl PassConfig {
+    /// The name the pass is declared with, e.g. `rename_type`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            PassConfig::Overlay { .. } => "overlay",
+            PassConfig::RenameType { .. } => "rename_type",
+            PassConfig::Filter { .. } => "filter",
+            PassConfig::CommonError { .. } => "common_error",
+        }
+    }
+
+    /// Resolve a relative overlay path against `dir`.
+    pub(crate) fn resolve_path(&mut self, dir: &Path) {
+        if let PassConfig::Overlay { path } = self {
+            *path = dir.join(&*path);
+        }
+    }
+}
+
+impl IrPass for PassConfig {
+    /// Overlays apply to the spec, so they fail here rather than being
+    /// skipped.
+    fn apply(&self, ir: &mut GenIr) -> Result<()> {
+        let error =
+            |message: String| Error::ConfigError(format!("{} pass: {}", self.name(), message));
+        match self {
+            PassConfig::Overlay { path } => Err(error(format!(
+                "{} rewrites the spec, not the IR; apply it with generate::apply_overlays \
+                 and generate with generate::overlaid(config), or use generate_spec",
+                path.display()
+            ))),
+            PassConfig::RenameType { from, to } => {
+                let renamed = StableId::new(CanonicalName::from_string(to).pascal);
+                if ir.types.contains_key(&renamed) {
+                    return Err(error(format!("there already is a type `{}`", renamed)));
+                }
+                if !ir.rename_type(from, to) {
+                    return Err(error(format!("no type `{}`", from)));
+                }
+                Ok(())
+            }
+            PassConfig::Filter {
+                include_tags,
+                exclude_tags,
+                include_operations,
+                exclude_operations,
+            } => {
+                let filter = OperationFilter {
+                    include_tags: include_tags.clone(),
+                    exclude_tags: exclude_tags.clone(),
+                    include_operations: include_operations.clone(),
+                    exclude_operations: exclude_operations.clone(),
+                };
+                if let Some(tag) = filter.unknown_tags(ir).first() {
+                    return Err(error(format!("unknown tag `{}`", tag)));
+                }
+                ir.filter_operations(&filter);
+                Ok(())
+            }
+            PassConfig::CommonError { type_name, status } => {
+                if !(400..600).contains(status) {
+                    return Err(error(format!("{} is not an error status", status)));
+                }
+                let target = StableId::new(type_name.as_str());
+                if !ir.types.contains_key(&target) {
+                    return Err(error(format!("no type `{}`", type_name)));
+                }
+                add_error_variant(
+                    ir,
+                    ErrorVariant {
+                        name: CanonicalName::from_string(ir::error_variant_name(*status)),
+                        status: StatusSpec::Code(*status),
+                        content_type: Some("application/json".to_string()),
+                        ty: Some(TypeRef {
+                            target,
+                            optional: false,
+                            nullable: false,
+                            by_ref: false,
+                            modifiers: Vec::new(),
+                        }),
+                        docs: Docs::default(),
+                    },
+                );
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Add `variant` to the errors of every operation without a variant of its
+/// status, including the shared error declarations operations use.
+/// Webhooks are left alone: their errors are the receiver's.
+fn add_error_variant(ir: &mut GenIr, variant: ErrorVariant) {
+    let mut shared = Vec::new();
+    let operations = ir
+        .services
+        .iter_mut()
+        .flat_map(|service| service.operations.iter_mut());
+    for op in operations {
+        match &mut op.errors {
+            ErrorUse::Inline(decl) => add_missing_variant(decl, &variant),
+            ErrorUse::Shared(id) => shared.push(id.clone()),
+            ErrorUse::None => {
+                let error_name = format!("{}Error", op.name.pascal);
+                op.errors = ErrorUse::Inline(Box::new(ErrorDecl {
+                    id: StableId::new(&error_name),
+                    name: CanonicalName::from_string(&error_name),
+                    docs: Docs::default(),
+                    variants: vec![variant.clone()],
+                }));
+            }
+        }
+    }
+    for decl in ir
+        .errors
+        .iter_mut()
+        .filter(|decl| shared.contains(&decl.id))
+    {
+        add_missing_variant(decl, &variant);
+    }
+}
+
+/// Add `variant` to `decl` unless it already has a variant of that status.
+fn add_missing_variant(decl: &mut ErrorDecl, variant: &ErrorVariant) {
+    let StatusSpec::Code(status) = variant.status else {
+        return;
+    };
+    let declared = decl
+        .variants
+        .iter()
+        .any(|v| matches!(v.status, StatusSpec::Code(code) if code == status));
+    if !declared {
+        decl.variants.push(variant.clone());
+    }
+}
+
+#[derive(Clone)]
+enum Pass {
+    Declared(PassConfig),
+    Custom(Arc<dyn IrPass>),
+}
+
+/// An ordered list of [`IrPass`]es, empty by default. Serializes as the
+/// list of its [`PassConfig`]s; custom passes can't be serialized and are
+/// left out.
+#[derive(Clone, Default)]
+pub struct Passes(Vec<Pass>);
+
+impl Passes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a built-in pass, run after the ones already added.
+    pub fn declare(&mut self, pass: PassConfig) {
+        self.0.push(Pass::Declared(pass));
+    }
+
+    /// Add a custom pass, run after the ones already added.
+    pub fn push(&mut self, pass: impl IrPass + 'static) {
+        self.0.push(Pass::Custom(Arc::new(pass)));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether every pass is a [`PassConfig`], so serializing the passes
+    /// loses nothing.
+    pub fn is_declarative(&self) -> bool {
+        self.0.iter().all(|pass| matches!(pass, Pass::Declared(_)))
+    }
+
+    /// Whether any pass is an overlay.
+    pub fn has_overlays(&self) -> bool {
+        self.0
+            .iter()
+            .any(|pass| matches!(pass, Pass::Declared(PassConfig::Overlay { .. })))
+    }
+
+    /// Paths of the overlays to apply to the spec, in order. Fails with
+    /// [`Error::ConfigError`] if an overlay is declared after another pass:
+    /// overlays rewrite the spec, which is gone once the other passes run,
+    /// so it couldn't run in the declared order.
+    pub fn overlays(&self) -> Result<Vec<&Path>> {
+        let mut overlays = Vec::new();
+        let mut after = None;
+        for pass in &self.0 {
+            match pass {
+                Pass::Declared(PassConfig::Overlay { path }) => match after {
+                    Some(name) => {
+                        return Err(Error::ConfigError(format!(
+                            "overlay {} is declared after the {} pass; overlays rewrite the \
+                             spec, so they must come before every other pass",
+                            path.display(),
+                            name
+                        )));
+                    }
+                    None => overlays.push(path.as_path()),
+                },
+                Pass::Declared(pass) => after = after.or(Some(pass.name())),
+                Pass::Custom(_) => after = after.or(Some("custom")),
+            }
+        }
+        Ok(overlays)
+    }
+
+    /// The passes without the overlays, for the IR of a spec they were
+    /// applied to.
+    pub fn without_overlays(&self) -> Self {
+        Self(
+            self.0
+                .iter()
+                .filter(|pass| !matches!(pass, Pass::Declared(PassConfig::Overlay { .. })))
+                .cloned()
+                .collect(),
+        )
+    }
+
+    /// Run `ir` through every pass, in order.
+    pub fn apply(&self, ir: &mut GenIr) -> Result<()> {
+        for pass in &self.0 {
+            match pass {
+                Pass::Declared(pass) => pass.apply(ir)?,
+                Pass::Custom(pass) => pass.apply(ir)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Resolve relative overlay paths against `dir`.
+    pub(crate) fn resolve_paths(&mut self, dir: &Path) {
+        for pass in &mut self.0 {
+            if let Pass::Declared(pass) = pass {
+                pass.resolve_path(dir);
+            }
+        }
+    }
+}
+
+impl From<Vec<PassConfig>> for Passes {
+    fn from(passes: Vec<PassConfig>) -> Self {
+        Self(passes.into_iter().map(Pass::Declared).collect())
+    }
+}
+
+impl fmt::Debug for Passes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.0.iter().map(|pass| match pass {
+                Pass::Declared(pass) => pass.name(),
+                Pass::Custom(_) => "custom",
+            }))
+            .finish()
+    }
+}
+
+impl Serialize for Passes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.iter().filter_map(|pass| match pass {
+            Pass::Declared(pass) => Some(pass),
+            Pass::Custom(_) => None,
+        }))
+    }
+}
+
+impl<'de> Deserialize<'de> for Passes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        Vec::<PassConfig>::deserialize(deserializer).map(Passes::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: &str = r##"{
+        "openapi": "3.0.0",
+        "info": { "title": "Pets", "version": "1" },
+        "paths": {
+            "/pets": {
+                "get": {
+                    "operationId": "listPets",
+                    "tags": ["pets"],
+                    "responses": {
+                        "200": {
+                            "description": "ok",
+                            "content": { "application/json": { "schema": { "$ref": "#/components/schemas/Pet" } } }
+                        },
+                        "404": {
+                            "description": "missing",
+                            "content": { "application/json": { "schema": { "$ref": "#/components/schemas/Problem" } } }
+                        }
+                    }
+                }
+            },
+            "/admin": {
+                "get": {
+                    "operationId": "audit",
+                    "tags": ["admin"],
+                    "responses": { "204": { "description": "ok" } }
+                }
+            }
+        },
+        "components": {
+            "schemas": {
+                "Pet": { "type": "object", "properties": { "name": { "type": "string" } } },
+                "Problem": { "type": "object", "properties": { "title": { "type": "string" } } }
+            }
+        }
+    }"##;
+
+    fn ir() -> GenIr {
+        GenIr::from(parser::parse(SPEC).unwrap())
+    }
+
+    #[test]
+    fn test_declared_passes() {
+        #[derive(Deserialize)]
+        struct Options {
+            passes: Passes,
+        }
+
+        let Options { passes } = toml::from_str(
+            r#"
+            [[passes]]
+            pass = "rename_type"
+            from = "Pet"
+            to = "Animal"
+
+            [[passes]]
+            pass = "common_error"
+            type = "Problem"
+            status = 500
+            "#,
+        )
+        .unwrap();
+        assert!(passes.is_declarative());
+        let mut ir = ir();
+        passes.apply(&mut ir).unwrap();
+
+        assert!(ir.types.contains_key(&StableId::new("Animal")));
+        assert!(!ir.types.contains_key(&StableId::new("Pet")));
+        let variants = |operation_id: &str| -> Vec<String> {
+            let op = ir
+                .services
+                .iter()
+                .flat_map(|service| &service.operations)
+                .find(|op| op.name.canonical == operation_id)
+                .unwrap();
+            let ErrorUse::Inline(errors) = &op.errors else {
+                panic!("expected inline errors");
+            };
+            assert_eq!(
+                errors.variants.last().unwrap().ty.as_ref().unwrap().target,
+                StableId::new("Problem")
+            );
+            errors
+                .variants
+                .iter()
+                .map(|v| v.name.pascal.clone())
+                .collect()
+        };
+        assert_eq!(
+            variants("listPets"),
+            vec!["NotFound", "InternalServerError"]
+        );
+        // Operations without errors get them
+        assert_eq!(variants("audit"), vec!["InternalServerError"]);
+
+        let json = serde_json::to_value(&passes).unwrap();
+        assert_eq!(json[0]["pass"], "rename_type");
+        assert_eq!(json[1]["type"], "Problem");
+    }
+
+    #[test]
+    fn test_filter_pass_runs_in_order() {
+        let passes: Passes = serde_json::from_str(
+            r#"[
+                { "pass": "filter", "exclude_tags": ["admin"] },
+                { "pass": "common_error", "type": "Problem", "status": 500 }
+            ]"#,
+        )
+        .unwrap();
+        let mut ir = ir();
+        passes.apply(&mut ir).unwrap();
+
+        let operations: Vec<&str> = ir
+            .services
+            .iter()
+            .flat_map(|service| &service.operations)
+            .map(|op| op.name.canonical.as_str())
+            .collect();
+        assert_eq!(operations, vec!["listPets"]);
+        assert_eq!(serde_json::to_value(&passes).unwrap()[0]["pass"], "filter");
+
+        let unknown = PassConfig::Filter {
+            include_tags: vec!["stores".to_string()],
+            exclude_tags: Vec::new(),
+            include_operations: Vec::new(),
+            exclude_operations: Vec::new(),
+        };
+        let Err(Error::ConfigError(message)) = unknown.apply(&mut ir) else {
+            panic!("expected a config error");
+        };
+        assert_eq!(message, "filter pass: unknown tag `stores`");
+    }
+
+    #[test]
+    fn test_common_error_extends_shared_errors() {
+        let mut ir = ir();
+        let op = ir
+            .services
+            .iter_mut()
+            .flat_map(|service| &mut service.operations)
+            .find(|op| op.name.canonical == "listPets")
+            .unwrap();
+        let ErrorUse::Inline(decl) = std::mem::replace(&mut op.errors, ErrorUse::None) else {
+            panic!("expected inline errors");
+        };
+        op.errors = ErrorUse::Shared(decl.id.clone());
+        ir.errors.push(*decl);
+
+        let common_error = PassConfig::CommonError {
+            type_name: "Problem".to_string(),
+            status: 500,
+        };
+        common_error.apply(&mut ir).unwrap();
+        common_error.apply(&mut ir).unwrap();
+        let statuses: Vec<String> = ir.errors[0]
+            .variants
+            .iter()
+            .map(|v| v.name.pascal.clone())
+            .collect();
+        assert_eq!(statuses, vec!["NotFound", "InternalServerError"]);
+    }
+
+    #[test]
+    fn test_pass_errors() {
+        let rename = PassConfig::RenameType {
+            from: "Owner".to_string(),
+            to: "Person".to_string(),
+        };
+        assert_eq!(
+            rename.apply(&mut ir()).unwrap_err().to_string(),
+            "Config error: rename_type pass: no type `Owner`"
+        );
+        let common_error = PassConfig::CommonError {
+            type_name: "Problem".to_string(),
+            status: 200,
+        };
+        assert!(common_error.apply(&mut ir()).is_err());
+    }
+
+    #[test]
+    fn test_custom_passes() {
+        let mut passes = Passes::new();
+        passes.declare(PassConfig::Overlay {
+            path: PathBuf::from("overlay.yaml"),
+        });
+        passes.push(|ir: &mut GenIr| -> Result<()> {
+            ir.services.clear();
+            Ok(())
+        });
+        assert!(!passes.is_declarative());
+        assert_eq!(passes.overlays().unwrap(), vec![Path::new("overlay.yaml")]);
+        assert_eq!(format!("{:?}", passes), r#"["overlay", "custom"]"#);
+
+        // Overlays rewrite the spec, so running one on the IR fails
+        assert!(matches!(
+            passes.apply(&mut ir()),
+            Err(Error::ConfigError(message)) if message.contains("overlay.yaml")
+        ));
+        let mut ir = ir();
+        passes.without_overlays().apply(&mut ir).unwrap();
+        assert!(ir.services.is_empty());
+        assert_eq!(
+            serde_json::to_value(&passes)
+                .unwrap()
+                .as_array()
+                .unwrap()
+                .len(),
+            1
+        );
+
+        // An overlay after another pass can't run in the declared order
+        passes.declare(PassConfig::Overlay {
+            path: PathBuf::from("late.yaml"),
+        });
+        assert!(passes.overlays().is_err());
+    }
+}
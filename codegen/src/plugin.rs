@@ -0,0 +1,207 @@
+//! The interface between the registry and generators built as plugins.
+//!
+//! Rust has no stable ABI, so a plugin can't hand the host a
+//! `Box<dyn Generator>`: the vtable layout differs between compiler
+//! versions. Plugins instead exchange JSON through a `#[repr(C)]`
+//! [`PluginVTable`] of `extern "C"` functions, versioned by
+//! [`PLUGIN_ABI_VERSION`]. A shared library plugin exports two symbols:
+//!
+//! ```ignore
+//! #[unsafe(no_mangle)]
+//! pub extern "C" fn oas_gen_plugin_abi_version() -> u32 {
+//!     codegen::plugin::PLUGIN_ABI_VERSION
+//! }
+//!
+//! #[unsafe(no_mangle)]
+//! pub extern "C" fn oas_gen_plugin_vtable() -> *const PluginVTable {
+//!     static VTABLE: PluginVTable = PluginVTable {
+//!         abi_version: PLUGIN_ABI_VERSION,
+//!         language,
+//!         generate,
+//!         free,
+//!     };
+//!     &VTABLE
+//! }
+//! ```
+//!
+//! The host checks the version before touching the vtable, so a plugin
+//! built for another version fails to load with an error instead of
+//! crashing. [`encode_input`] and [`decode_output`] are the JSON messages,
+//! shared with WASM plugins:
+//!
+//! - input: `{"abi_version": 1, "ir": <GenIr>, "config": <Config>, "options": <lang_options>}`
+//! - output: `{"files": [{"path": "src/index.ts", "content": "...", "mode": 420}]}`
+//!   (`mode` optional), or `{"error": "message"}`
+//!
+//! Output paths must be relative, `/`-separated and stay inside the
+//! output directory.
+
+use std::path::{Component, Path};
+
+use serde::Deserialize;
+
+use crate::{Config, Error, GenIr, Result, VirtualFS};
+
+/// Version of [`PluginVTable`] and the JSON messages. Bumped on any
+/// incompatible change.
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+
+/// Symbol of `extern "C" fn() -> u32` returning the plugin's
+/// [`PLUGIN_ABI_VERSION`].
+pub const ABI_VERSION_SYMBOL: &[u8] = b"oas_gen_plugin_abi_version";
+
+/// Symbol of `extern "C" fn() -> *const PluginVTable`.
+pub const VTABLE_SYMBOL: &[u8] = b"oas_gen_plugin_vtable";
+
+/// Bytes allocated by the plugin, handed to the host and given back to
+/// [`PluginVTable::free`].
+#[repr(C)]
+#[derive(Debug)]
+pub struct PluginBuffer {
+    pub ptr: *mut u8,
+    pub len: usize,
+    pub capacity: usize,
+}
+
+impl PluginBuffer {
+    /// Hand `bytes` over to the host. For plugins written in Rust.
+    pub fn from_vec(bytes: Vec<u8>) -> Self {
+        let mut bytes = std::mem::ManuallyDrop::new(bytes);
+        Self {
+            ptr: bytes.as_mut_ptr(),
+            len: bytes.len(),
+            capacity: bytes.capacity(),
+        }
+    }
+
+    /// Take back a buffer made by [`from_vec`](Self::from_vec), e.g. in
+    /// the plugin's [`free`](PluginVTable::free).
+    ///
+    /// # Safety
+    ///
+    /// `self` must come from [`from_vec`](Self::from_vec) in the same
+    /// binary and not have been freed.
+    pub unsafe fn into_vec(self) -> Vec<u8> {
+        unsafe { Vec::from_raw_parts(self.ptr, self.len, self.capacity) }
+    }
+
+    /// The bytes, without taking ownership.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to `len` readable bytes until the buffer is freed.
+    pub unsafe fn as_slice(&self) -> &[u8] {
+        if self.ptr.is_null() {
+            return &[];
+        }
+        unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+    }
+}
+
+/// Functions of a plugin. Every function may be called from any thread.
+#[repr(C)]
+pub struct PluginVTable {
+    /// [`PLUGIN_ABI_VERSION`] the plugin was built for.
+    pub abi_version: u32,
+    /// The language name, UTF-8.
+    pub language: unsafe extern "C" fn() -> PluginBuffer,
+    /// Generate from the JSON input of `len` bytes at `input`, returning
+    /// the JSON output.
+    pub generate: unsafe extern "C" fn(input: *const u8, len: usize) -> PluginBuffer,
+    /// Free a buffer returned by `language` or `generate`.
+    pub free: unsafe extern "C" fn(buffer: PluginBuffer),
+}
+
+/// The JSON input of a plugin.
+pub fn encode_input(ir: &GenIr, config: &Config) -> Result<Vec<u8>> {
+    serde_json::to_vec(&serde_json::json!({
+        "abi_version": PLUGIN_ABI_VERSION,
+        "ir": ir,
+        "config": config,
+        "options": config.lang_options,
+    }))
+    .map_err(|e| Error::Custom(format!("Failed to serialize plugin input: {}", e)))
+}
+
+/// The files of a plugin's JSON output, or the error it reported. Errors
+/// name the plugin by `language`. Absolute paths, `..` and `\` separators
+/// (`..\` escapes on Windows) are rejected.
+pub fn decode_output(language: &str, output: &[u8]) -> Result<VirtualFS> {
+    let error = |message: String| Error::Custom(format!("{} plugin: {}", language, message));
+    let output: Output = serde_json::from_slice(output)
+        .map_err(|e| error(format!("invalid plugin output: {}", e)))?;
+    if let Some(message) = output.error {
+        return Err(error(message));
+    }
+    let mut vfs = VirtualFS::new();
+    for file in output.files {
+        let inside = Path::new(&file.path)
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+        if !inside || file.path.is_empty() || file.path.contains('\\') {
+            return Err(error(format!(
+                "output path `{}` is not inside the output directory",
+                file.path
+            )));
+        }
+        if let Some(mode) = file.mode {
+            vfs.set_mode(&file.path, mode);
+        }
+        vfs.add_file(file.path, file.content);
+    }
+    Ok(vfs)
+}
+
+#[derive(Deserialize)]
+struct Output {
+    #[serde(default)]
+    files: Vec<OutputFile>,
+    error: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct OutputFile {
+    path: String,
+    content: String,
+    mode: Option<u32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    #[test]
+    fn test_protocol() {
+        let vfs = decode_output(
+            "echo",
+            br##"{"files":[{"path":"bin/run","content":"#!/bin/sh\n","mode":493}]}"##,
+        )
+        .unwrap();
+        assert_eq!(
+            vfs.get_file(Path::new("bin/run")),
+            Some("#!/bin/sh\n".as_bytes())
+        );
+        assert!(vfs.is_executable(Path::new("bin/run")));
+
+        let err = decode_output("echo", br#"{"error":"no services"}"#).unwrap_err();
+        assert_eq!(err.to_string(), "echo plugin: no services");
+        assert!(decode_output("echo", b"not json").is_err());
+        for path in [
+            "/etc/passwd",
+            "../outside",
+            "src/../../outside",
+            "",
+            "..\\outside",
+        ] {
+            let output = serde_json::json!({ "files": [{ "path": path, "content": "" }] });
+            let err = decode_output("echo", output.to_string().as_bytes()).unwrap_err();
+            assert!(err.to_string().contains("not inside the output directory"));
+        }
+
+        let buffer = PluginBuffer::from_vec(b"echo".to_vec());
+        assert_eq!(unsafe { buffer.as_slice() }, b"echo");
+        assert_eq!(unsafe { buffer.into_vec() }, b"echo".to_vec());
+    }
+}
@@ -0,0 +1,419 @@
+//! External tools run over generated output: formatters such as prettier,
+//! rustfmt or black, or build steps such as `npm install`.
+//!
+//! A generator lists its tools in
+//! [`Generator::post_processors`](crate::Generator::post_processors) and the
+//! registry runs them after the files are written, instead of the generator
+//! spawning processes in a hook:
+//!
+//! ```
+//! use codegen::postprocess::{CommandProcessor, IfMissing};
+//! use std::time::Duration;
+//!
+//! let prettier = CommandProcessor::new("npx")
+//!     .with_args(["prettier", "--write"])
+//!     .with_extensions(["ts", "js"])
+//!     .with_timeout(Duration::from_secs(120))
+//!     .with_if_missing(IfMissing::Skip);
+//! ```
+//!
+//! [`VirtualFS::post_process`] runs a processor over the VFS instead, so
+//! formatted content can be diffed or archived without writing it out.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crate::{Error, Result, VirtualFS};
+
+/// A tool run over generated files.
+pub trait PostProcessor: Send + Sync {
+    /// Name used in messages, e.g. `prettier`.
+    fn name(&self) -> &str;
+
+    /// Whether `path`, relative to the output directory, is processed.
+    fn matches(&self, path: &Path) -> bool;
+
+    /// Process `files` (the matching generated files, relative to
+    /// `output_dir`) after they were written.
+    fn run(&self, output_dir: &Path, files: &[PathBuf]) -> Result<PostProcessOutcome>;
+
+    /// [`run`](Self::run), also returning what the tool printed. Tools
+    /// that don't print keep the default, which returns no output.
+    fn run_captured(
+        &self,
+        output_dir: &Path,
+        files: &[PathBuf],
+    ) -> Result<(PostProcessOutcome, String)> {
+        Ok((self.run(output_dir, files)?, String::new()))
+    }
+}
+
+/// What running a [`PostProcessor`] did.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PostProcessOutcome {
+    Ran,
+    /// Not run, e.g. because the tool isn't installed or no file matched.
+    Skipped(String),
+}
+
+/// What to do when a [`CommandProcessor`]'s program isn't installed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IfMissing {
+    /// Fail generation.
+    #[default]
+    Fail,
+    /// Skip the tool; the files are kept as generated.
+    Skip,
+}
+
+/// Runs a program in the output directory, with the matching files as
+/// trailing arguments.
+#[derive(Debug, Clone)]
+pub struct CommandProcessor {
+    name: String,
+    program: String,
+    args: Vec<String>,
+    extensions: Vec<String>,
+    pass_files: bool,
+    timeout: Duration,
+    if_missing: IfMissing,
+}
+
+impl CommandProcessor {
+    /// Time a program may run before it is killed, unless set with
+    /// [`with_timeout`](Self::with_timeout).
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(300);
+
+    /// Run `program` over every generated file.
+    pub fn new(program: impl Into<String>) -> Self {
+        let program = program.into();
+        Self {
+            name: program.clone(),
+            program,
+            args: Vec::new(),
+            extensions: Vec::new(),
+            pass_files: true,
+            timeout: Self::DEFAULT_TIMEOUT,
+            if_missing: IfMissing::Fail,
+        }
+    }
+
+    /// Name used in messages; the program by default.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Arguments before the file paths.
+    pub fn with_args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    /// Only process files with these extensions (without the dot).
+    pub fn with_extensions<I, S>(mut self, extensions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.extensions
+            .extend(extensions.into_iter().map(Into::into));
+        self
+    }
+
+    /// Run once for the whole output directory without passing files, as
+    /// for `npm install`.
+    pub fn without_files(mut self) -> Self {
+        self.pass_files = false;
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn with_if_missing(mut self, if_missing: IfMissing) -> Self {
+        self.if_missing = if_missing;
+        self
+    }
+}
+
+impl PostProcessor for CommandProcessor {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn matches(&self, path: &Path) -> bool {
+        self.pass_files
+            && (self.extensions.is_empty()
+                || path
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| self.extensions.iter().any(|e| e == ext)))
+    }
+
+    fn run(&self, output_dir: &Path, files: &[PathBuf]) -> Result<PostProcessOutcome> {
+        self.run_captured(output_dir, files)
+            .map(|(outcome, _)| outcome)
+    }
+
+    /// The output is what the program printed to stdout, then to stderr.
+    fn run_captured(
+        &self,
+        output_dir: &Path,
+        files: &[PathBuf],
+    ) -> Result<(PostProcessOutcome, String)> {
+        let skipped = |reason: String| Ok((PostProcessOutcome::Skipped(reason), String::new()));
+        if self.pass_files && files.is_empty() {
+            return skipped("no matching files".to_string());
+        }
+        let mut command = Command::new(&self.program);
+        command
+            .args(&self.args)
+            .current_dir(output_dir)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        if self.pass_files {
+            command.args(files);
+        }
+
+        let child = match command.spawn() {
+            Ok(child) => child,
+            Err(e) if e.kind() == io::ErrorKind::NotFound && self.if_missing == IfMissing::Skip => {
+                return skipped(format!("`{}` is not installed", self.program));
+            }
+            Err(e) => {
+                return Err(Error::Custom(format!("Failed to run {}: {}", self.name, e)));
+            }
+        };
+
+        let output = wait_with_timeout(child, &self.name, self.timeout)?;
+        if !output.status.success() {
+            return Err(Error::Custom(format!(
+                "{} failed ({}): {}",
+                self.name,
+                output.status,
+                output.stderr.trim()
+            )));
+        }
+        Ok((PostProcessOutcome::Ran, output.stdout + &output.stderr))
+    }
+}
+
+/// What a finished program printed.
+pub(crate) struct CommandOutput {
+    pub status: ExitStatus,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// Wait for `child`, killing it after `timeout`, and collect the output of
+/// its piped streams.
+pub(crate) fn wait_with_timeout(
+    mut child: Child,
+    name: &str,
+    timeout: Duration,
+) -> Result<CommandOutput> {
+    // Drained while waiting, so a chatty tool can't fill the pipe and stall
+    fn drain(pipe: Option<impl io::Read + Send + 'static>) -> Option<JoinHandle<String>> {
+        pipe.map(|mut pipe| {
+            std::thread::spawn(move || {
+                let mut text = String::new();
+                let _ = pipe.read_to_string(&mut text);
+                text
+            })
+        })
+    }
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(Error::Custom(format!(
+                "{} timed out after {:?}",
+                name, timeout
+            )));
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    };
+
+    let collect = |reader: Option<JoinHandle<String>>| {
+        reader
+            .and_then(|reader| reader.join().ok())
+            .unwrap_or_default()
+    };
+    Ok(CommandOutput {
+        status,
+        stdout: collect(stdout),
+        stderr: collect(stderr),
+    })
+}
+
+/// Run `processor` over the generated files under `output_dir`, in the
+/// VFS's [`root`](VirtualFS::root) there and with paths relative to it.
+/// Files of `vfs` that aren't there (e.g. paths relative to another root)
+/// are left out.
+pub fn run_on_disk(
+    processor: &dyn PostProcessor,
+    output_dir: &Path,
+    vfs: &VirtualFS,
+) -> Result<PostProcessOutcome> {
+    let root = output_dir.join(vfs.root());
+    processor.run(&root, &files_on_disk(processor, &root, vfs))
+}
+
+/// [`run_on_disk`], also returning what the tool printed (see
+/// [`PostProcessor::run_captured`]).
+pub fn run_on_disk_captured(
+    processor: &dyn PostProcessor,
+    output_dir: &Path,
+    vfs: &VirtualFS,
+) -> Result<(PostProcessOutcome, String)> {
+    let root = output_dir.join(vfs.root());
+    processor.run_captured(&root, &files_on_disk(processor, &root, vfs))
+}
+
+/// The files of `vfs` under its root, relative to it.
+fn rooted_files(vfs: &VirtualFS) -> impl Iterator<Item = &Path> {
+    vfs.files()
+        .filter_map(|(path, _)| path.strip_prefix(vfs.root()).ok())
+}
+
+fn files_on_disk(processor: &dyn PostProcessor, root: &Path, vfs: &VirtualFS) -> Vec<PathBuf> {
+    rooted_files(vfs)
+        .filter(|path| processor.matches(path) && root.join(path).is_file())
+        .map(Path::to_path_buf)
+        .collect()
+}
+
+impl VirtualFS {
+    /// Run `processor` over the matching files of the VFS, replacing their
+    /// content with the processed files. The files are processed in a
+    /// temporary directory.
+    pub fn post_process(&mut self, processor: &dyn PostProcessor) -> Result<PostProcessOutcome> {
+        let files: Vec<PathBuf> = rooted_files(self)
+            .filter(|path| processor.matches(path))
+            .map(Path::to_path_buf)
+            .collect();
+        let root = self.root.clone();
+        if files.is_empty() {
+            return Ok(PostProcessOutcome::Skipped("no matching files".to_string()));
+        }
+
+        static RUNS: AtomicUsize = AtomicUsize::new(0);
+        let dir = std::env::temp_dir().join(format!(
+            "oas-gen-{}-{}-{}",
+            processor.name(),
+            std::process::id(),
+            RUNS.fetch_add(1, Ordering::Relaxed)
+        ));
+        let result = (|| -> Result<PostProcessOutcome> {
+            for path in &files {
+                let full_path = dir.join(path);
+                if let Some(parent) = full_path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(&full_path, self.files[&root.join(path)].as_slice())?;
+            }
+            let outcome = processor.run(&dir, &files)?;
+            if outcome == PostProcessOutcome::Ran {
+                for path in &files {
+                    let content = std::fs::read(dir.join(path))?;
+                    self.files.insert(root.join(path), content);
+                }
+            }
+            Ok(outcome)
+        })();
+        let _ = std::fs::remove_dir_all(&dir);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_processor() {
+        let missing = CommandProcessor::new("oas-gen-no-such-formatter")
+            .with_extensions(["ts"])
+            .with_if_missing(IfMissing::Skip);
+        assert!(missing.matches(Path::new("src/index.ts")));
+        assert!(!missing.matches(Path::new("package.json")));
+
+        let mut vfs = VirtualFS::new();
+        vfs.add_file("src/index.ts", "export {};\n");
+        assert!(matches!(
+            vfs.post_process(&missing).unwrap(),
+            PostProcessOutcome::Skipped(reason) if reason.contains("not installed")
+        ));
+        let failing = missing.clone().with_if_missing(IfMissing::Fail);
+        assert!(vfs.post_process(&failing).is_err());
+
+        let unmatched = CommandProcessor::new("true").with_extensions(["py"]);
+        assert_eq!(
+            vfs.post_process(&unmatched).unwrap(),
+            PostProcessOutcome::Skipped("no matching files".to_string())
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_post_process_vfs() {
+        // Appends a line to every file it is given
+        let appender = CommandProcessor::new("sh")
+            .with_name("appender")
+            .with_args([
+                "-c",
+                "for f in \"$@\"; do echo '// formatted' >> \"$f\"; done",
+                "sh",
+            ])
+            .with_extensions(["ts"]);
+        let mut vfs = VirtualFS::new();
+        vfs.add_file("src/index.ts", "export {};\n");
+        vfs.add_file("package.json", "{}\n");
+        assert_eq!(
+            vfs.post_process(&appender).unwrap(),
+            PostProcessOutcome::Ran
+        );
+        assert_eq!(
+            vfs.get_file(Path::new("src/index.ts")),
+            Some("export {};\n// formatted\n".as_bytes())
+        );
+        assert_eq!(
+            vfs.get_file(Path::new("package.json")),
+            Some("{}\n".as_bytes())
+        );
+
+        let slow = CommandProcessor::new("sleep")
+            .with_args(["5"])
+            .without_files()
+            .with_timeout(Duration::from_millis(100));
+        let err = slow.run(&std::env::temp_dir(), &[]).unwrap_err();
+        assert!(err.to_string().contains("sleep timed out"));
+
+        let echo = CommandProcessor::new("sh")
+            .with_args(["-c", "echo formatted; echo warning >&2"])
+            .without_files();
+        assert_eq!(
+            echo.run_captured(&std::env::temp_dir(), &[]).unwrap(),
+            (PostProcessOutcome::Ran, "formatted\nwarning\n".to_string())
+        );
+    }
+}
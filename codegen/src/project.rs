@@ -0,0 +1,733 @@
+//! Project files: generation settings checked in next to a spec.
+//!
+//! An `oas-gen.toml` (or `.yaml`) names the input spec and output
+//! directory, sets the global [`Config`] options and gives each generator
+//! its own `lang_options`:
+//!
+//! ```toml
+//! input = "openapi.yaml"
+//! output = "sdk"
+//! targets = ["ts"]
+//! profile = "public"
+//!
+//! [aliases]
+//! js = "typescript"
+//!
+//! [options]
+//! service_style = "by_tag"
+//! include_docs = true
+//! locale = "de"
+//! strict = false
+//! lint_threshold = "warning"
+//! template_dir = "templates"
+//!
+//! [options.file_header]
+//! notice = "Generated from openapi.yaml. DO NOT EDIT."
+//! timestamp = false
+//!
+//! [options.naming]
+//! type_suffix = "Dto"
+//! reserved_words = ["Record"]
+//!
+//! [options.time_formats]
+//! date_time = "unix_millis"          # rfc3339, unix_seconds, unix_millis
+//! date = { pattern = "%d.%m.%Y" }
+//!
+//! [options.formats.sku]
+//! pattern = "^[A-Z]{3}-[0-9]+$"
+//! description = "a SKU like ABC-123"
+//!
+//! [[options.passes]]
+//! pass = "rename_type"
+//! from = "Pet"
+//! to = "Animal"
+//!
+//! [generators.typescript]
+//! layout = "domain"
+//! coerce_numbers = true
+//!
+//! [profiles.internal]
+//! output = "sdk-internal"
+//!
+//! [profiles.internal.options]
+//! package_name = "acme-internal"
+//! base_url = "https://api.internal.acme.dev"
+//!
+//! [profiles.internal.generators.typescript]
+//! raw_request = true
+//!
+//! [profiles.public.options]
+//! exclude_tags = ["admin"]
+//! exclude_operations = ["internal*"]
+//! ```
+//!
+//! `targets` names the generators to run when none is asked for (see
+//! `GeneratorRegistry::generate_default` in the `generate` crate). Give
+//! each its own output directory with a table instead:
+//!
+//! ```toml
+//! [targets.typescript]
+//! output = "sdk/ts"
+//!
+//! [targets.rust-axum]
+//! output = "server"
+//! ```
+//!
+//! `aliases` adds names for generators, on top of built-in ones such as
+//! `ts`.
+//!
+//! A profile is a named flavor of the SDK, picked at generate time with
+//! [`ProjectConfig::with_profile`]: its `output`, options and generator
+//! options replace the top-level ones they name, the rest are kept. The
+//! `profile` key names the one to use when none is asked for, see
+//! [`ProjectConfig::select_profile`].
+//!
+//! Every key is optional; unknown keys are errors.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use ir::gen_ir::TimeFormat;
+use serde::Deserialize;
+
+use crate::formats::FormatRule;
+use crate::passes::PassConfig;
+use crate::{Config, Error, FileHeader, LintThreshold, Result, ServiceStyle};
+
+/// Generation settings read from a project file.
+#[derive(Debug, Clone, Default)]
+pub struct ProjectConfig {
+    /// Spec to generate from. [`from_file`](Self::from_file) resolves
+    /// relative paths against the file's directory.
+    pub input: Option<PathBuf>,
+    /// Directory to write generated code to, resolved like `input`, as are
+    /// the `template_dir` option and the paths of overlay passes.
+    pub output: Option<PathBuf>,
+    /// The `[options]` section, with empty `lang_options`.
+    pub config: Config,
+    /// `lang_options` by generator language.
+    pub generators: BTreeMap<String, BTreeMap<String, serde_json::Value>>,
+    /// The `[profiles]` sections, by name.
+    pub profiles: BTreeMap<String, Profile>,
+    /// The `profile` key: the profile to use when none is asked for.
+    pub profile: Option<String>,
+    /// Generators to run by default: in the order listed, or by name when
+    /// given as tables.
+    pub targets: Vec<Target>,
+    /// Generator names by alias, e.g. `js` → `typescript`.
+    pub aliases: BTreeMap<String, String>,
+}
+
+/// A generator a project runs by default.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Target {
+    /// Name or alias of the generator.
+    pub generator: String,
+    /// Where its output goes; see [`ProjectConfig::output_for`].
+    pub output: Option<PathBuf>,
+}
+
+/// Overrides of a project file's settings, applied by
+/// [`ProjectConfig::with_profile`].
+#[derive(Debug, Clone, Default)]
+pub struct Profile {
+    /// Replaces [`ProjectConfig::output`].
+    pub output: Option<PathBuf>,
+    /// The options the profile sets.
+    options: RawOptions,
+    /// `lang_options` by generator language, each replacing the top-level
+    /// option of the same name.
+    pub generators: BTreeMap<String, BTreeMap<String, serde_json::Value>>,
+}
+
+impl ProjectConfig {
+    /// Conventional name of a TOML project file.
+    pub const FILE_NAME: &'static str = "oas-gen.toml";
+
+    /// Read a project file: YAML for `.yaml`, `.yml` and `.json`
+    /// extensions, TOML otherwise.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let input = std::fs::read_to_string(path)?;
+        let is_yaml = matches!(
+            path.extension().and_then(|ext| ext.to_str()),
+            Some("yaml" | "yml" | "json")
+        );
+        let parsed = if is_yaml {
+            Self::from_yaml(&input)
+        } else {
+            Self::from_toml(&input)
+        };
+        let mut project = parsed.map_err(|e| match e {
+            Error::ConfigError(message) => {
+                Error::ConfigError(format!("{}: {}", path.display(), message))
+            }
+            e => e,
+        })?;
+
+        let dir = path.parent().unwrap_or(Path::new(""));
+        project.input = project.input.map(|input| dir.join(input));
+        project.output = project.output.map(|output| dir.join(output));
+        project.config.template_dir = project
+            .config
+            .template_dir
+            .map(|template_dir| dir.join(template_dir));
+        project.config.passes.resolve_paths(dir);
+        for target in &mut project.targets {
+            target.output = target.output.take().map(|output| dir.join(output));
+        }
+        for profile in project.profiles.values_mut() {
+            profile.output = profile.output.take().map(|output| dir.join(output));
+            profile.options.template_dir = profile
+                .options
+                .template_dir
+                .take()
+                .map(|template_dir| dir.join(template_dir));
+            for pass in profile.options.passes.iter_mut().flatten() {
+                pass.resolve_path(dir);
+            }
+        }
+        Ok(project)
+    }
+
+    pub fn from_toml(input: &str) -> Result<Self> {
+        let raw: RawProject =
+            toml::from_str(input).map_err(|e| Error::ConfigError(e.to_string()))?;
+        raw.check()?;
+        Ok(raw.into())
+    }
+
+    pub fn from_yaml(input: &str) -> Result<Self> {
+        let raw: RawProject =
+            serde_saphyr::from_str(input).map_err(|e| Error::ConfigError(e.to_string()))?;
+        raw.check()?;
+        Ok(raw.into())
+    }
+
+    /// Find [`FILE_NAME`](Self::FILE_NAME) in `dir` or its closest
+    /// ancestor that has one.
+    pub fn discover(dir: impl AsRef<Path>) -> Option<PathBuf> {
+        dir.as_ref()
+            .ancestors()
+            .map(|dir| dir.join(Self::FILE_NAME))
+            .find(|path| path.is_file())
+    }
+
+    /// These settings with the profile `name` applied. Fails with
+    /// [`Error::ConfigError`] if there is no such profile.
+    pub fn with_profile(mut self, name: &str) -> Result<Self> {
+        let Some(profile) = self.profiles.get(name).cloned() else {
+            let known: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+            return Err(Error::ConfigError(if known.is_empty() {
+                format!("unknown profile `{}`, no profiles are defined", name)
+            } else {
+                format!(
+                    "unknown profile `{}`, expected one of: {}",
+                    name,
+                    known.join(", ")
+                )
+            }));
+        };
+        if profile.output.is_some() {
+            self.output = profile.output;
+        }
+        profile.options.apply(&mut self.config);
+        for (language, options) in profile.generators {
+            self.generators.entry(language).or_default().extend(options);
+        }
+        Ok(self)
+    }
+
+    /// These settings with the profile `name` applied, or the file's
+    /// [`profile`](Self::profile) if `name` is `None`. Unchanged when
+    /// neither names one.
+    pub fn select_profile(self, name: Option<&str>) -> Result<Self> {
+        match name.map(str::to_string).or_else(|| self.profile.clone()) {
+            Some(name) => self.with_profile(&name),
+            None => Ok(self),
+        }
+    }
+
+    /// Where `target`'s output goes: its own `output`, else the project's.
+    /// With several targets, each gets a directory named after its
+    /// `language`, the generator its name or alias resolves to, under the
+    /// project's `output`.
+    pub fn output_for(&self, target: &Target, language: &str) -> Option<PathBuf> {
+        if target.output.is_some() {
+            return target.output.clone();
+        }
+        let output = self.output.as_ref()?;
+        Some(if self.targets.len() > 1 {
+            output.join(language)
+        } else {
+            output.clone()
+        })
+    }
+
+    /// The configuration to generate `language` with: the global options
+    /// plus that generator's `lang_options`.
+    pub fn config_for(&self, language: &str) -> Config {
+        Config {
+            lang_options: self.generators.get(language).cloned().unwrap_or_default(),
+            ..self.config.clone()
+        }
+    }
+}
+
+impl Config {
+    /// Read the configuration for `language` from a project file, see
+    /// [`ProjectConfig::from_file`]. Use [`ProjectConfig`] directly for the
+    /// file's input and output paths.
+    pub fn from_file(path: impl AsRef<Path>, language: &str) -> Result<Config> {
+        Ok(ProjectConfig::from_file(path)?.config_for(language))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawProject {
+    input: Option<PathBuf>,
+    output: Option<PathBuf>,
+    #[serde(default)]
+    options: RawOptions,
+    #[serde(default)]
+    generators: BTreeMap<String, BTreeMap<String, serde_json::Value>>,
+    #[serde(default)]
+    profiles: BTreeMap<String, RawProfile>,
+    profile: Option<String>,
+    #[serde(default)]
+    targets: RawTargets,
+    #[serde(default)]
+    aliases: BTreeMap<String, String>,
+}
+
+/// `targets = ["typescript"]` or `[targets.typescript]` tables.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawTargets {
+    Names(Vec<String>),
+    Tables(BTreeMap<String, RawTarget>),
+}
+
+impl Default for RawTargets {
+    fn default() -> Self {
+        RawTargets::Names(Vec::new())
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawTarget {
+    output: Option<PathBuf>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawProfile {
+    output: Option<PathBuf>,
+    #[serde(default)]
+    options: RawOptions,
+    #[serde(default)]
+    generators: BTreeMap<String, BTreeMap<String, serde_json::Value>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawOptions {
+    service_style: Option<ServiceStyle>,
+    include_docs: Option<bool>,
+    locale: Option<String>,
+    package_name: Option<String>,
+    base_url: Option<String>,
+    include_tags: Option<Vec<String>>,
+    exclude_tags: Option<Vec<String>>,
+    include_operations: Option<Vec<String>>,
+    exclude_operations: Option<Vec<String>>,
+    strict: Option<bool>,
+    lint_threshold: Option<LintThreshold>,
+    file_header: Option<FileHeader>,
+    template_dir: Option<PathBuf>,
+    passes: Option<Vec<PassConfig>>,
+    naming: Option<RawNaming>,
+    time_formats: Option<RawTimeFormats>,
+    /// Added to the built-in formats, replacing any of the same name.
+    formats: Option<BTreeMap<String, FormatRule>>,
+}
+
+/// `[options.naming]`: the [`NamingPolicy`](crate::NamingPolicy) fields it sets.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawNaming {
+    type_prefix: Option<String>,
+    type_suffix: Option<String>,
+    reserved_words: Option<BTreeSet<String>>,
+    escape_suffix: Option<String>,
+}
+
+/// `[options.time_formats]`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawTimeFormats {
+    date: Option<TimeFormat>,
+    date_time: Option<TimeFormat>,
+}
+
+impl RawOptions {
+    /// Set the options given in the file on `config`.
+    fn apply(self, config: &mut Config) {
+        if let Some(service_style) = self.service_style {
+            config.service_style = service_style;
+        }
+        if let Some(include_docs) = self.include_docs {
+            config.include_docs = include_docs;
+        }
+        if let Some(strict) = self.strict {
+            config.strict = strict;
+        }
+        if let Some(include_tags) = self.include_tags {
+            config.include_tags = include_tags;
+        }
+        if let Some(exclude_tags) = self.exclude_tags {
+            config.exclude_tags = exclude_tags;
+        }
+        if let Some(include_operations) = self.include_operations {
+            config.include_operations = include_operations;
+        }
+        if let Some(exclude_operations) = self.exclude_operations {
+            config.exclude_operations = exclude_operations;
+        }
+        config.locale = self.locale.or(config.locale.take());
+        config.package_name = self.package_name.or(config.package_name.take());
+        config.base_url = self.base_url.or(config.base_url.take());
+        config.lint_threshold = self.lint_threshold.or(config.lint_threshold);
+        config.file_header = self.file_header.or(config.file_header.take());
+        config.template_dir = self.template_dir.or(config.template_dir.take());
+        if let Some(passes) = self.passes {
+            config.passes = passes.into();
+        }
+        if let Some(naming) = self.naming {
+            if let Some(type_prefix) = naming.type_prefix {
+                config.naming.type_prefix = type_prefix;
+            }
+            if let Some(type_suffix) = naming.type_suffix {
+                config.naming.type_suffix = type_suffix;
+            }
+            if let Some(reserved_words) = naming.reserved_words {
+                config.naming.reserved_words = reserved_words;
+            }
+            if let Some(escape_suffix) = naming.escape_suffix {
+                config.naming.escape_suffix = escape_suffix;
+            }
+        }
+        if let Some(time_formats) = self.time_formats {
+            if let Some(date) = time_formats.date {
+                config.time_formats.date = date;
+            }
+            if let Some(date_time) = time_formats.date_time {
+                config.time_formats.date_time = date_time;
+            }
+        }
+        for (format, rule) in self.formats.into_iter().flatten() {
+            config.formats.register(format, rule);
+        }
+    }
+}
+
+impl RawProject {
+    /// Check the format patterns of the options and every profile, so a bad
+    /// one fails here rather than in the generated code.
+    fn check(&self) -> Result<()> {
+        let options =
+            std::iter::once(&self.options).chain(self.profiles.values().map(|p| &p.options));
+        for options in options {
+            for (format, rule) in options.formats.iter().flatten() {
+                rule.check(format)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl From<RawProject> for ProjectConfig {
+    fn from(raw: RawProject) -> Self {
+        let mut config = Config::default();
+        raw.options.apply(&mut config);
+        let targets = match raw.targets {
+            RawTargets::Names(names) => names
+                .into_iter()
+                .map(|generator| Target {
+                    generator,
+                    output: None,
+                })
+                .collect(),
+            RawTargets::Tables(tables) => tables
+                .into_iter()
+                .map(|(generator, target)| Target {
+                    generator,
+                    output: target.output,
+                })
+                .collect(),
+        };
+        ProjectConfig {
+            input: raw.input,
+            output: raw.output,
+            config,
+            generators: raw.generators,
+            targets,
+            aliases: raw.aliases,
+            profile: raw.profile,
+            profiles: raw
+                .profiles
+                .into_iter()
+                .map(|(name, profile)| {
+                    let profile = Profile {
+                        output: profile.output,
+                        options: profile.options,
+                        generators: profile.generators,
+                    };
+                    (name, profile)
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_config() {
+        let project = ProjectConfig::from_toml(
+            r#"
+input = "openapi.yaml"
+
+[options]
+service_style = "by_tag"
+lint_threshold = "warning"
+
+[[options.passes]]
+pass = "common_error"
+type = "Problem"
+status = 500
+
+[generators.typescript]
+layout = "domain"
+"#,
+        )
+        .unwrap();
+        assert_eq!(project.input, Some(PathBuf::from("openapi.yaml")));
+        assert_eq!(project.output, None);
+
+        let config = project.config_for("typescript");
+        assert_eq!(config.service_style, ServiceStyle::ByTag);
+        assert_eq!(config.lint_threshold, Some(LintThreshold::Warning));
+        assert!(config.include_docs);
+        assert_eq!(config.layout_name().unwrap(), "domain");
+        assert_eq!(config.passes.len(), 1);
+        assert!(project.config_for("rust-axum").lang_options.is_empty());
+
+        let yaml = ProjectConfig::from_yaml(
+            "output: sdk\noptions:\n  include_docs: false\ngenerators:\n  rust-axum:\n    coerce_numbers: true\n",
+        )
+        .unwrap();
+        assert_eq!(yaml.output, Some(PathBuf::from("sdk")));
+        assert!(!yaml.config.include_docs);
+        assert_eq!(
+            yaml.config_for("rust-axum").lang_options[Config::COERCE_NUMBERS],
+            serde_json::Value::Bool(true)
+        );
+
+        assert!(matches!(
+            ProjectConfig::from_toml("[options]\nservice_style = \"nested\""),
+            Err(Error::ConfigError(_))
+        ));
+        assert!(ProjectConfig::from_toml("outptu = \"sdk\"").is_err());
+    }
+
+    #[test]
+    fn test_naming_and_formats() {
+        let project = ProjectConfig::from_toml(
+            r#"
+[options.naming]
+type_suffix = "Dto"
+reserved_words = ["Record"]
+
+[options.time_formats]
+date_time = "unix_millis"
+date = { pattern = "%d.%m.%Y" }
+
+[options.formats.sku]
+pattern = "^[A-Z]{3}-[0-9]+$"
+description = "a SKU"
+"#,
+        )
+        .unwrap();
+        let config = project.config;
+        assert_eq!(config.naming.type_suffix, "Dto");
+        assert_eq!(config.naming.type_prefix, "");
+        assert_eq!(config.naming.escape_suffix, "_");
+        assert!(config.naming.reserved_words.contains("Record"));
+        assert_eq!(config.time_formats.date_time, TimeFormat::UnixMillis);
+        assert_eq!(
+            config.time_formats.date,
+            TimeFormat::Pattern("%d.%m.%Y".into())
+        );
+        assert_eq!(config.formats.get("sku").unwrap().description, "a SKU");
+        assert!(config.formats.get("email").is_some());
+
+        assert!(ProjectConfig::from_toml("[options.time_formats]\ndate = \"iso\"").is_err());
+        assert!(ProjectConfig::from_toml("[options.naming]\nsuffix = \"Dto\"").is_err());
+
+        let lookahead = r#"
+[profiles.ci.options.formats.sku]
+pattern = "^(?=[A-Z])"
+description = "a SKU"
+"#;
+        let Err(Error::ConfigError(message)) = ProjectConfig::from_toml(lookahead) else {
+            panic!("expected a config error");
+        };
+        assert!(message.contains("invalid pattern for format `sku`"));
+    }
+
+    #[test]
+    fn test_profiles() {
+        let project = ProjectConfig::from_toml(
+            r#"
+output = "sdk"
+
+[options]
+service_style = "by_tag"
+locale = "de"
+
+[generators.typescript]
+layout = "domain"
+coerce_numbers = true
+
+[profiles.internal]
+output = "sdk-internal"
+
+[profiles.internal.options]
+package_name = "acme-internal"
+base_url = "https://api.internal.acme.dev"
+include_docs = false
+
+[profiles.internal.generators.typescript]
+coerce_numbers = false
+
+[profiles.public.options]
+exclude_tags = ["admin"]
+"#,
+        )
+        .unwrap();
+        assert_eq!(project.profiles.len(), 2);
+        assert_eq!(project.config.package_name, None);
+
+        let internal = project.clone().with_profile("internal").unwrap();
+        assert_eq!(internal.output, Some(PathBuf::from("sdk-internal")));
+        let config = internal.config_for("typescript");
+        assert_eq!(config.package_name.as_deref(), Some("acme-internal"));
+        assert_eq!(
+            config.base_url.as_deref(),
+            Some("https://api.internal.acme.dev")
+        );
+        assert!(!config.include_docs);
+        // Options the profile doesn't set are kept
+        assert_eq!(config.service_style, ServiceStyle::ByTag);
+        assert_eq!(config.locale.as_deref(), Some("de"));
+        assert_eq!(config.layout_name().unwrap(), "domain");
+        assert_eq!(
+            config.lang_options[Config::COERCE_NUMBERS],
+            serde_json::Value::Bool(false)
+        );
+
+        let public = project.clone().with_profile("public").unwrap();
+        assert_eq!(public.output, Some(PathBuf::from("sdk")));
+        assert!(public.config_for("typescript").include_docs);
+        assert_eq!(public.config.exclude_tags, vec!["admin".to_string()]);
+        assert!(internal.config.exclude_tags.is_empty());
+
+        // The `profile` key, unless another one is asked for
+        let project = ProjectConfig {
+            profile: Some("internal".to_string()),
+            ..project
+        };
+        let selected = project.clone().select_profile(None).unwrap();
+        assert_eq!(selected.output, Some(PathBuf::from("sdk-internal")));
+        let selected = project.clone().select_profile(Some("public")).unwrap();
+        assert_eq!(selected.output, Some(PathBuf::from("sdk")));
+        assert!(
+            ProjectConfig::from_toml("profile = \"internal\"")
+                .unwrap()
+                .select_profile(None)
+                .is_err()
+        );
+
+        let err = project.with_profile("partner").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            Error::ConfigError(
+                "unknown profile `partner`, expected one of: internal, public".to_string()
+            )
+            .to_string()
+        );
+        assert!(
+            ProjectConfig::from_toml(
+                "[profiles.internal]
+putput = \"sdk\""
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_targets() {
+        let project = ProjectConfig::from_toml(
+            r#"
+output = "sdk"
+targets = ["ts"]
+
+[aliases]
+js = "typescript"
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            project.targets,
+            vec![Target {
+                generator: "ts".to_string(),
+                output: None,
+            }]
+        );
+        assert_eq!(project.aliases["js"], "typescript");
+        assert_eq!(
+            project.output_for(&project.targets[0], "typescript"),
+            Some(PathBuf::from("sdk"))
+        );
+
+        let project = ProjectConfig::from_toml(
+            r#"
+output = "sdk"
+
+[targets.typescript]
+
+[targets.rust-axum]
+output = "server"
+"#,
+        )
+        .unwrap();
+        let outputs: Vec<_> = project
+            .targets
+            .iter()
+            .map(|target| project.output_for(target, &target.generator))
+            .collect();
+        assert_eq!(
+            outputs,
+            vec![
+                Some(PathBuf::from("server")),
+                Some(PathBuf::from("sdk/typescript"))
+            ]
+        );
+        assert!(ProjectConfig::from_toml("[targets.typescript]\nouptut = \"sdk\"").is_err());
+    }
+}
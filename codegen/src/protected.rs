@@ -0,0 +1,285 @@
+//! Protected regions: hand-written code that survives regeneration.
+//!
+//! A generator marks a region with comment lines starting with
+//! [`BEGIN_KEEP`] and [`END_KEEP`], in whatever comment syntax the file
+//! uses. When the file
+//! already exists on disk, writing it keeps the lines between the markers
+//! from the file on disk instead of the generated ones:
+//!
+//! ```
+//! let generated = "fn list_pets() {\n    // oas-gen:begin keep list_pets\n    todo!()\n    // oas-gen:end keep\n}\n";
+//! let on_disk = "fn list_pets() {\n    // oas-gen:begin keep list_pets\n    vec![]\n    // oas-gen:end keep\n}\n";
+//! assert_eq!(codegen::protected::splice(generated, on_disk).unwrap(), on_disk);
+//! ```
+//!
+//! The text after `begin keep` names a region; unnamed regions are matched
+//! by their position among the unnamed regions of the file. A region the
+//! new content no longer has is an error unless its body is blank, so
+//! hand-written code is never dropped silently.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+
+use crate::{Error, Result};
+
+/// Starts a protected region; the rest of the line names it.
+pub const BEGIN_KEEP: &str = "oas-gen:begin keep";
+
+/// Ends the open protected region. Regions don't nest.
+pub const END_KEEP: &str = "oas-gen:end keep";
+
+/// Comment openers a marker may follow at the start of a line.
+const COMMENT_OPENERS: &[&str] = &["//", "/*", "<!--", "#", "--", "*"];
+
+/// `generated`, with the body of each protected region replaced by the body
+/// of the same region in `existing`. Fails if `existing` has a region with
+/// code in it that `generated` doesn't have.
+pub fn splice(generated: &str, existing: &str) -> Result<String> {
+    let mut kept: BTreeMap<String, String> = regions(existing)
+        .map_err(|e| Error::ValidationError(format!("existing file: {}", e)))?
+        .into_iter()
+        .collect();
+    regions(generated).map_err(|e| Error::ValidationError(format!("generated file: {}", e)))?;
+
+    let mut out = String::with_capacity(generated.len());
+    let mut unnamed = 0;
+    // Inside a region whose existing body was written instead
+    let mut skipping = false;
+    for line in generated.split_inclusive('\n') {
+        if let Some(name) = marker(line, BEGIN_KEEP) {
+            out.push_str(line);
+            let key = region_key(name, &mut unnamed);
+            if let Some(body) = kept.remove(&key) {
+                out.push_str(&body);
+                skipping = true;
+            }
+        } else if marker(line, END_KEEP).is_some() {
+            out.push_str(line);
+            skipping = false;
+        } else if !skipping {
+            out.push_str(line);
+        }
+    }
+    if let Some((key, _)) = kept.iter().find(|(_, body)| !body.trim().is_empty()) {
+        return Err(Error::ValidationError(format!(
+            "protected region `{}` is no longer generated; move its code out of the region or delete it",
+            key
+        )));
+    }
+    Ok(out)
+}
+
+/// `text` without the bodies of its protected regions: the part of the
+/// file that regeneration owns.
+pub fn generated_part(text: &str) -> Cow<'_, str> {
+    if !text.contains(BEGIN_KEEP) {
+        return Cow::Borrowed(text);
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut inside = false;
+    for line in text.split_inclusive('\n') {
+        if marker(line, BEGIN_KEEP).is_some() {
+            out.push_str(line);
+            inside = true;
+        } else if marker(line, END_KEEP).is_some() {
+            out.push_str(line);
+            inside = false;
+        } else if !inside {
+            out.push_str(line);
+        }
+    }
+    Cow::Owned(out)
+}
+
+/// What to write over `existing` for `content`: `content` itself, or with
+/// the protected regions of `existing` spliced in. `path` names the file in
+/// errors.
+pub(crate) fn keep_regions<'a>(
+    path: &Path,
+    content: &'a [u8],
+    existing: Option<&[u8]>,
+) -> io::Result<Cow<'a, [u8]>> {
+    let (Ok(generated), Some(Ok(existing))) = (
+        std::str::from_utf8(content),
+        existing.map(std::str::from_utf8),
+    ) else {
+        return Ok(Cow::Borrowed(content));
+    };
+    if !generated.contains(BEGIN_KEEP) {
+        return Ok(Cow::Borrowed(content));
+    }
+    splice(generated, existing)
+        .map(|spliced| Cow::Owned(spliced.into_bytes()))
+        .map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {}", path.display(), e),
+            )
+        })
+}
+
+/// The regions of `text` by key, with their bodies.
+fn regions(text: &str) -> std::result::Result<Vec<(String, String)>, String> {
+    let mut regions: Vec<(String, String)> = Vec::new();
+    let mut open: Option<(String, String, usize)> = None;
+    let mut unnamed = 0;
+    for (i, line) in text.split_inclusive('\n').enumerate() {
+        let line_number = i + 1;
+        if let Some(name) = marker(line, BEGIN_KEEP) {
+            if let Some((key, _, start)) = &open {
+                return Err(format!(
+                    "line {}: protected region `{}` from line {} isn't closed",
+                    line_number, key, start
+                ));
+            }
+            let key = region_key(name, &mut unnamed);
+            if regions.iter().any(|(existing, _)| *existing == key) {
+                return Err(format!(
+                    "line {}: duplicate protected region `{}`",
+                    line_number, key
+                ));
+            }
+            open = Some((key, String::new(), line_number));
+        } else if marker(line, END_KEEP).is_some() {
+            let Some((key, body, _)) = open.take() else {
+                return Err(format!(
+                    "line {}: `{}` without a matching `{}`",
+                    line_number, END_KEEP, BEGIN_KEEP
+                ));
+            };
+            regions.push((key, body));
+        } else if let Some((_, body, _)) = &mut open {
+            body.push_str(line);
+        }
+    }
+    match open {
+        Some((key, _, start)) => Err(format!(
+            "protected region `{}` from line {} isn't closed",
+            key, start
+        )),
+        None => Ok(regions),
+    }
+}
+
+/// The text after `marker` on `line`, if the line is a comment starting
+/// with it. A marker elsewhere, e.g. in a string, doesn't count.
+fn marker<'a>(line: &'a str, marker: &str) -> Option<&'a str> {
+    let line = line.trim_start();
+    let comment = COMMENT_OPENERS
+        .iter()
+        .find_map(|opener| line.strip_prefix(opener))?;
+    let name = comment.trim_start().strip_prefix(marker)?.trim();
+    // Closing comment delimiters aren't part of the name
+    Some(name.trim_end_matches("*/").trim_end_matches("-->").trim())
+}
+
+/// A region's name, or `#<n>` for the n-th unnamed region.
+fn region_key(name: &str, unnamed: &mut usize) -> String {
+    if name.is_empty() {
+        *unnamed += 1;
+        format!("#{}", unnamed)
+    } else {
+        name.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GENERATED: &str = "\
+import { Client } from './client';
+// oas-gen:begin keep imports
+// oas-gen:end keep
+
+export async function listPets(client: Client) {
+  // oas-gen:begin keep list_pets
+  throw new Error('not implemented');
+  // oas-gen:end keep
+}
+
+export async function createPet(client: Client) {
+  // oas-gen:begin keep create_pet
+  throw new Error('not implemented');
+  // oas-gen:end keep
+}
+";
+
+    #[test]
+    fn test_splice_protected_regions() {
+        let existing = "\
+import { Client } from './client';
+// oas-gen:begin keep imports
+import { db } from '../db';
+// oas-gen:end keep
+
+export async function listPets(client: Client) {
+  // oas-gen:begin keep list_pets
+  return db.pets.all();
+  // oas-gen:end keep
+}
+
+export async function deletePet(client: Client) {
+  // oas-gen:begin keep delete_pet
+  return db.pets.delete();
+  // oas-gen:end keep
+}
+";
+        let err = splice(GENERATED, existing).unwrap_err().to_string();
+        assert!(err.contains("protected region `delete_pet` is no longer generated"));
+
+        // Once its code is moved out, the removed region goes with its function
+        let existing = existing.replace("  return db.pets.delete();\n", "");
+        let spliced = splice(GENERATED, &existing).unwrap();
+        assert!(spliced.contains("// oas-gen:begin keep imports\nimport { db } from '../db';\n"));
+        assert!(spliced.contains("  // oas-gen:begin keep list_pets\n  return db.pets.all();\n"));
+        // New regions keep their generated body
+        assert!(spliced.contains(
+            "  // oas-gen:begin keep create_pet\n  throw new Error('not implemented');\n"
+        ));
+        assert!(!spliced.contains("deletePet"));
+        assert_eq!(splice(GENERATED, GENERATED).unwrap(), GENERATED);
+        assert_eq!(generated_part(&spliced), generated_part(GENERATED));
+        assert!(!generated_part(GENERATED).contains("not implemented"));
+
+        // Only comments starting with a marker are markers
+        let quoted = "const marker = '// oas-gen:begin keep';\n";
+        assert_eq!(splice(quoted, "").unwrap(), quoted);
+        assert_eq!(marker("  /* oas-gen:end keep */\n", END_KEEP), Some(""));
+        assert_eq!(
+            marker("<!-- oas-gen:begin keep docs -->\n", BEGIN_KEEP),
+            Some("docs")
+        );
+
+        let broken = "// oas-gen:begin keep list_pets\nreturn [];\n";
+        let err = splice(GENERATED, broken).unwrap_err().to_string();
+        assert!(err.contains("protected region `list_pets` from line 1 isn't closed"));
+    }
+
+    #[test]
+    fn test_regions_survive_write_to_disk() {
+        let dir = std::env::temp_dir().join(format!("oas-gen-keep-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let mut vfs = crate::VirtualFS::new();
+        vfs.add_file("src/pets.ts", GENERATED);
+        vfs.write_to_disk(&dir).unwrap();
+        let edited = GENERATED.replace(
+            "  // oas-gen:begin keep list_pets\n  throw new Error('not implemented');",
+            "  // oas-gen:begin keep list_pets\n  return [];",
+        );
+        std::fs::write(dir.join("src/pets.ts"), &edited).unwrap();
+
+        // Regenerating neither reports nor overwrites the hand-written body
+        assert!(vfs.diff(&dir).unwrap().is_empty());
+        vfs.write_to_disk(&dir).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.join("src/pets.ts")).unwrap(),
+            edited
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
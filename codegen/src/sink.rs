@@ -0,0 +1,159 @@
+//! Destinations for generated files.
+//!
+//! [`VirtualFS`] collects every file in memory, which is the default. For very
+//! large outputs a generator can stream files into a [`FileSink`] as they are
+//! rendered instead, so only one file needs to be held at a time.
+
+use std::path::{Path, PathBuf};
+
+use crate::{
+    DEFAULT_FILE_MODE, Manifest, ManifestEntry, Result, VirtualFS, content_hash, protected,
+    set_mode,
+};
+
+/// Receives generated files one at a time.
+///
+/// Generators must emit files in a deterministic order (e.g. the order of
+/// services in the IR) so that streamed output is reproducible.
+pub trait FileSink {
+    /// Accept one generated file. `path` is relative to the output root.
+    fn write_file(&mut self, path: &Path, content: &[u8]) -> Result<()>;
+
+    /// Accept one generated file written with the Unix permissions `mode`
+    /// (see [`VirtualFS::set_mode`]). Sinks that don't create files keep
+    /// the default, which drops the mode.
+    fn write_file_with_mode(&mut self, path: &Path, content: &[u8], _mode: u32) -> Result<()> {
+        self.write_file(path, content)
+    }
+
+    /// Accept one generated text file.
+    fn write_text(&mut self, path: &Path, content: &str) -> Result<()> {
+        self.write_file(path, content.as_bytes())
+    }
+
+    /// Called once after the generator has emitted its last file.
+    fn finish(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl FileSink for VirtualFS {
+    fn write_file(&mut self, path: &Path, content: &[u8]) -> Result<()> {
+        self.add_binary(path, content.to_vec());
+        Ok(())
+    }
+
+    fn write_file_with_mode(&mut self, path: &Path, content: &[u8], mode: u32) -> Result<()> {
+        if mode != DEFAULT_FILE_MODE {
+            self.set_mode(path, mode);
+        }
+        self.write_file(path, content)
+    }
+}
+
+/// Writes each file straight to disk under a base directory. On
+/// [`finish`](FileSink::finish) it deletes the files a previous run generated
+/// that weren't written this time and records the new [`Manifest`]. Files
+/// already on disk keep their [`protected`] regions, and files with a mode
+/// get it on Unix, as with [`VirtualFS::write_to_disk`].
+#[derive(Debug, Clone)]
+pub struct DiskSink {
+    base_path: PathBuf,
+    written: Vec<PathBuf>,
+    hashes: Vec<ManifestEntry>,
+    bytes_written: u64,
+    spec_hash: Option<String>,
+}
+
+impl DiskSink {
+    /// Write files under `base_path`, creating directories as needed.
+    pub fn new(base_path: impl Into<PathBuf>) -> Self {
+        Self {
+            base_path: base_path.into(),
+            written: Vec::new(),
+            hashes: Vec::new(),
+            bytes_written: 0,
+            spec_hash: None,
+        }
+    }
+
+    /// Record the hash of the spec the files are generated from in the
+    /// [`Manifest`].
+    pub fn with_spec_hash(mut self, spec_hash: impl Into<String>) -> Self {
+        self.spec_hash = Some(spec_hash.into());
+        self
+    }
+
+    /// Relative paths of the files written so far, in emission order.
+    pub fn written(&self) -> &[PathBuf] {
+        &self.written
+    }
+
+    /// Total bytes written so far.
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written
+    }
+}
+
+impl FileSink for DiskSink {
+    fn write_file(&mut self, path: &Path, content: &[u8]) -> Result<()> {
+        self.hashes.push(ManifestEntry {
+            path: path.to_path_buf(),
+            hash: content_hash(content),
+        });
+        let full_path = self.base_path.join(path);
+        let existing = std::fs::read(&full_path).ok();
+        let content = protected::keep_regions(path, content, existing.as_deref())?;
+        if let Some(parent) = full_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&full_path, &content)?;
+        self.written.push(path.to_path_buf());
+        self.bytes_written += content.len() as u64;
+        Ok(())
+    }
+
+    fn write_file_with_mode(&mut self, path: &Path, content: &[u8], mode: u32) -> Result<()> {
+        self.write_file(path, content)?;
+        if mode != DEFAULT_FILE_MODE {
+            set_mode(&self.base_path.join(path), mode)?;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        let mut manifest = Manifest::from_entries(self.hashes.drain(..));
+        manifest.spec_hash = self.spec_hash.clone();
+        if let Some(previous) = Manifest::read(&self.base_path)? {
+            previous.remove_stale(&self.base_path, &manifest)?;
+        }
+        manifest.write(&self.base_path)?;
+        Ok(())
+    }
+}
+
+/// Forwards each file to a callback, e.g. to upload it or add it to an archive.
+pub struct CallbackSink<F>
+where
+    F: FnMut(&Path, &[u8]) -> Result<()>,
+{
+    callback: F,
+}
+
+impl<F> CallbackSink<F>
+where
+    F: FnMut(&Path, &[u8]) -> Result<()>,
+{
+    pub fn new(callback: F) -> Self {
+        Self { callback }
+    }
+}
+
+impl<F> FileSink for CallbackSink<F>
+where
+    F: FnMut(&Path, &[u8]) -> Result<()>,
+{
+    fn write_file(&mut self, path: &Path, content: &[u8]) -> Result<()> {
+        (self.callback)(path, content)
+    }
+}
@@ -0,0 +1,130 @@
+//! Snapshot tests for generators: compare a [`VirtualFS`] with a directory
+//! of golden files.
+//!
+//! ```no_run
+//! # fn generate() -> codegen::VirtualFS { codegen::VirtualFS::new() }
+//! let vfs = generate();
+//! codegen::testing::assert_snapshot(
+//!     &vfs,
+//!     concat!(env!("CARGO_MANIFEST_DIR"), "/tests/snapshots/petstore"),
+//! );
+//! ```
+//!
+//! A mismatch fails with a unified line diff of every differing file, made
+//! with `similar`. Files are compared byte for byte: protected regions in
+//! golden files are not spliced in as they are when writing. Run the
+//! tests with `OAS_GEN_UPDATE_SNAPSHOTS=1` to (re)write the golden files
+//! from the generated output instead, then review the change in git.
+
+use std::path::Path;
+
+use crate::VirtualFS;
+
+/// Environment variable that switches snapshot assertions to update mode.
+pub const UPDATE_ENV: &str = "OAS_GEN_UPDATE_SNAPSHOTS";
+
+/// Panic with a diff unless the files under `dir` are exactly the files of
+/// `vfs`. In update mode ([`UPDATE_ENV`] set to anything but `0` or empty),
+/// replace `dir` with the files of `vfs` instead.
+#[track_caller]
+pub fn assert_snapshot(vfs: &VirtualFS, dir: impl AsRef<Path>) {
+    let dir = dir.as_ref();
+    if update_mode() {
+        if let Err(e) = update_snapshot(vfs, dir) {
+            panic!("failed to update snapshot {}: {}", dir.display(), e);
+        }
+        return;
+    }
+    if let Err(message) = check_snapshot(vfs, dir) {
+        panic!("{}", message);
+    }
+}
+
+/// Compare `vfs` with the golden files under `dir`, describing every
+/// difference on failure.
+pub fn check_snapshot(vfs: &VirtualFS, dir: &Path) -> Result<(), String> {
+    if !dir.is_dir() {
+        return Err(format!(
+            "snapshot {} does not exist; run with {}=1 to create it",
+            dir.display(),
+            UPDATE_ENV
+        ));
+    }
+    let diff = vfs
+        .diff_exact(dir)
+        .map_err(|e| format!("failed to read snapshot {}: {}", dir.display(), e))?;
+    if diff.is_empty() {
+        return Ok(());
+    }
+    Err(format!(
+        "generated files differ from snapshot {} ({}):\n{}\nrun with {}=1 to update it",
+        dir.display(),
+        diff.summary(),
+        diff,
+        UPDATE_ENV
+    ))
+}
+
+/// Replace the contents of `dir` with the files of `vfs`.
+pub fn update_snapshot(vfs: &VirtualFS, dir: &Path) -> std::io::Result<()> {
+    if dir.exists() {
+        std::fs::remove_dir_all(dir)?;
+    }
+    for (path, content) in vfs.files() {
+        let full_path = dir.join(path);
+        if let Some(parent) = full_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(full_path, content)?;
+    }
+    Ok(())
+}
+
+fn update_mode() -> bool {
+    std::env::var_os(UPDATE_ENV).is_some_and(|value| !value.is_empty() && value != "0")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_snapshot() {
+        let dir = std::env::temp_dir().join(format!("oas-gen-snapshot-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let mut vfs = VirtualFS::new();
+        vfs.add_file("src/index.ts", "export * from './types';\n");
+        vfs.add_file("src/types.ts", "export type Id = string;\n");
+        assert!(check_snapshot(&vfs, &dir).unwrap_err().contains("does not exist"));
+
+        update_snapshot(&vfs, &dir).unwrap();
+        assert_eq!(check_snapshot(&vfs, &dir), Ok(()));
+        // No manifest or other bookkeeping ends up in the golden files
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+        vfs.add_file("src/types.ts", "export type Id = number;\n");
+        let message = check_snapshot(&vfs, &dir).unwrap_err();
+        assert!(message.contains("(0 added, 1 changed, 0 deleted)"));
+        assert!(message.contains("-export type Id = string;\n+export type Id = number;\n"));
+        assert!(message.ends_with(&format!("run with {}=1 to update it", UPDATE_ENV)));
+
+        update_snapshot(&vfs, &dir).unwrap();
+        assert_eq!(check_snapshot(&vfs, &dir), Ok(()));
+
+        // The bodies of protected regions are part of the snapshot too
+        vfs.add_file(
+            "src/api.ts",
+            "// oas-gen:begin keep\nthrow 1;\n// oas-gen:end keep\n",
+        );
+        update_snapshot(&vfs, &dir).unwrap();
+        vfs.add_file(
+            "src/api.ts",
+            "// oas-gen:begin keep\nthrow 2;\n// oas-gen:end keep\n",
+        );
+        let message = check_snapshot(&vfs, &dir).unwrap_err();
+        assert!(message.contains(" // oas-gen:begin keep\n-throw 1;\n+throw 2;\n"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
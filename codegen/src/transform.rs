@@ -0,0 +1,302 @@
+//! Rewriting generated files on their way out.
+//!
+//! [`Config::transforms`](crate::Config::transforms) runs each generated
+//! file through a list of [`FileTransform`]s after the generator produced
+//! it, so output can be adapted without forking the generator: move it
+//! under a prefix, add a license banner, or drop files.
+//!
+//! ```
+//! use codegen::transform::{Banner, OutputFile, PathPrefix, Skip, Transforms};
+//!
+//! let mut transforms = Transforms::new();
+//! transforms.push(Skip::new(|path| path.ends_with("README.md")));
+//! transforms.push(PathPrefix::new("packages/sdk")?);
+//! transforms.push(Banner::new("SPDX-License-Identifier: MIT"));
+//! // Closures work too
+//! transforms.push(|mut file: OutputFile| -> codegen::Result<Option<OutputFile>> {
+//!     file.content.extend_from_slice(b"\n");
+//!     Ok(Some(file))
+//! });
+//! # Ok::<(), codegen::Error>(())
+//! ```
+//!
+//! Transforms run in order, after the [`FileHeader`](crate::FileHeader),
+//! and see the path the previous transform produced. Transforms that move
+//! the whole output also move its [`root`](VirtualFS::root), where
+//! post-processors run.
+
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+use crate::{Error, FileSink, Result, VirtualFS, prepend_header};
+
+/// A generated file about to be written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputFile {
+    /// Path relative to the output root.
+    pub path: PathBuf,
+    pub content: Vec<u8>,
+}
+
+/// Rewrites or drops generated files.
+pub trait FileTransform: Send + Sync {
+    /// The file to write instead of `file`, or `None` to skip it.
+    fn apply(&self, file: OutputFile) -> Result<Option<OutputFile>>;
+
+    /// Where the generator's output directory `root` ends up. Only
+    /// transforms that move every file, like [`PathPrefix`], change it.
+    fn root(&self, root: PathBuf) -> PathBuf {
+        root
+    }
+}
+
+impl<F> FileTransform for F
+where
+    F: Fn(OutputFile) -> Result<Option<OutputFile>> + Send + Sync,
+{
+    fn apply(&self, file: OutputFile) -> Result<Option<OutputFile>> {
+        self(file)
+    }
+}
+
+/// Moves every file under a directory, e.g. `packages/sdk` in a monorepo.
+#[derive(Debug, Clone)]
+pub struct PathPrefix(PathBuf);
+
+impl PathPrefix {
+    /// Fails with [`Error::ConfigError`] unless `prefix` is a relative path
+    /// inside the output directory: no `..`, root or drive.
+    pub fn new(prefix: impl Into<PathBuf>) -> Result<Self> {
+        let prefix = prefix.into();
+        if !prefix
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+        {
+            return Err(Error::ConfigError(format!(
+                "path prefix {} must stay inside the output directory",
+                prefix.display()
+            )));
+        }
+        Ok(Self(prefix))
+    }
+}
+
+impl FileTransform for PathPrefix {
+    fn apply(&self, mut file: OutputFile) -> Result<Option<OutputFile>> {
+        file.path = self.0.join(&file.path);
+        Ok(Some(file))
+    }
+
+    fn root(&self, root: PathBuf) -> PathBuf {
+        self.0.join(root)
+    }
+}
+
+/// Skips the files whose path matches a predicate.
+pub struct Skip<F>(F);
+
+impl<F> Skip<F>
+where
+    F: Fn(&Path) -> bool + Send + Sync,
+{
+    pub fn new(matches: F) -> Self {
+        Self(matches)
+    }
+}
+
+impl<F> FileTransform for Skip<F>
+where
+    F: Fn(&Path) -> bool + Send + Sync,
+{
+    fn apply(&self, file: OutputFile) -> Result<Option<OutputFile>> {
+        Ok((!(self.0)(&file.path)).then_some(file))
+    }
+}
+
+/// Prepends a comment, e.g. a license notice, to every file with a known
+/// comment syntax (see [`CommentStyle`](crate::CommentStyle)).
+#[derive(Debug, Clone)]
+pub struct Banner(Vec<String>);
+
+impl Banner {
+    /// A banner of the lines of `text`.
+    pub fn new(text: &str) -> Self {
+        Self(text.lines().map(str::to_string).collect())
+    }
+}
+
+impl FileTransform for Banner {
+    fn apply(&self, mut file: OutputFile) -> Result<Option<OutputFile>> {
+        if let Some(with_banner) = prepend_header(&file.path, &file.content, &self.0) {
+            file.content = with_banner;
+        }
+        Ok(Some(file))
+    }
+}
+
+/// An ordered list of [`FileTransform`]s, empty by default.
+#[derive(Clone, Default)]
+pub struct Transforms(Vec<Arc<dyn FileTransform>>);
+
+impl Transforms {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a transform, run after the ones already added.
+    pub fn push(&mut self, transform: impl FileTransform + 'static) {
+        self.0.push(Arc::new(transform));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Where the output directory `root` ends up after every transform.
+    pub fn root(&self, root: PathBuf) -> PathBuf {
+        self.0
+            .iter()
+            .fold(root, |root, transform| transform.root(root))
+    }
+
+    /// Run `file` through every transform, stopping when one skips it.
+    pub fn apply(&self, file: OutputFile) -> Result<Option<OutputFile>> {
+        let mut file = file;
+        for transform in &self.0 {
+            match transform.apply(file)? {
+                Some(next) => file = next,
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(file))
+    }
+}
+
+impl fmt::Debug for Transforms {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Transforms({} transforms)", self.0.len())
+    }
+}
+
+impl VirtualFS {
+    /// Run every file through `transforms`. Files keep their
+    /// [`mode`](Self::mode) when renamed; files a transform renames onto
+    /// the same path overwrite each other in path order.
+    pub fn transform(self, transforms: &Transforms) -> Result<VirtualFS> {
+        if transforms.is_empty() {
+            return Ok(self);
+        }
+        let mut out = VirtualFS {
+            spec_hash: self.spec_hash,
+            root: transforms.root(self.root),
+            ..VirtualFS::default()
+        };
+        for (path, content) in self.files {
+            let mode = self.modes.get(&path).copied();
+            if let Some(file) = transforms.apply(OutputFile { path, content })? {
+                if let Some(mode) = mode {
+                    out.set_mode(file.path.clone(), mode);
+                }
+                out.add_binary(file.path, file.content);
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// Runs each file through [`Transforms`] before passing it on, for
+/// streamed generation.
+pub struct TransformSink<'a> {
+    inner: &'a mut dyn FileSink,
+    transforms: &'a Transforms,
+}
+
+impl<'a> TransformSink<'a> {
+    pub fn new(inner: &'a mut dyn FileSink, transforms: &'a Transforms) -> Self {
+        Self { inner, transforms }
+    }
+}
+
+impl FileSink for TransformSink<'_> {
+    fn write_file(&mut self, path: &Path, content: &[u8]) -> Result<()> {
+        let file = OutputFile {
+            path: path.to_path_buf(),
+            content: content.to_vec(),
+        };
+        match self.transforms.apply(file)? {
+            Some(file) => self.inner.write_file(&file.path, &file.content),
+            None => Ok(()),
+        }
+    }
+
+    fn write_file_with_mode(&mut self, path: &Path, content: &[u8], mode: u32) -> Result<()> {
+        let file = OutputFile {
+            path: path.to_path_buf(),
+            content: content.to_vec(),
+        };
+        match self.transforms.apply(file)? {
+            Some(file) => self
+                .inner
+                .write_file_with_mode(&file.path, &file.content, mode),
+            None => Ok(()),
+        }
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.inner.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transforms() {
+        let mut vfs = VirtualFS::new();
+        vfs.set_spec_hash("0123456789abcdef");
+        vfs.add_file("src/index.ts", "export {};\n");
+        vfs.add_file("README.md", "# Pets\n");
+        vfs.add_file("package.json", "{}\n");
+
+        let mut transforms = Transforms::new();
+        transforms.push(Skip::new(|path| path.ends_with("README.md")));
+        transforms.push(PathPrefix::new("packages/sdk").unwrap());
+        transforms.push(Banner::new("Copyright Pets Inc.\nSPDX-License-Identifier: MIT"));
+        let vfs = vfs.transform(&transforms).unwrap();
+
+        assert_eq!(vfs.len(), 2);
+        assert_eq!(vfs.root(), Path::new("packages/sdk"));
+        assert_eq!(vfs.spec_hash(), Some("0123456789abcdef"));
+        assert_eq!(
+            vfs.get_file_str(Path::new("packages/sdk/src/index.ts"))
+                .unwrap()
+                .unwrap(),
+            "// Copyright Pets Inc.\n// SPDX-License-Identifier: MIT\n\nexport {};\n"
+        );
+        // JSON has no comments, so it's only moved
+        assert_eq!(
+            vfs.get_file(Path::new("packages/sdk/package.json")),
+            Some(&b"{}\n"[..])
+        );
+
+        let mut streamed = VirtualFS::new();
+        let mut sink = TransformSink::new(&mut streamed, &transforms);
+        sink.write_text(Path::new("README.md"), "# Pets\n").unwrap();
+        sink.write_text(Path::new("package.json"), "{}\n").unwrap();
+        assert!(streamed.contains(Path::new("packages/sdk/package.json")));
+        assert_eq!(streamed.len(), 1);
+
+        for escaping in ["../sdk", "/srv/sdk", "packages/../../sdk"] {
+            assert!(matches!(
+                PathPrefix::new(escaping),
+                Err(Error::ConfigError(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,379 @@
+//! Checking that generated code compiles.
+//!
+//! A generator names the compiler that checks its output in
+//! [`Generator::verifier`](crate::Generator::verifier), e.g. `tsc --noEmit`
+//! or `cargo check`, and the registry runs it over the written output when
+//! asked to. Broken output is then caught by the pipeline instead of by
+//! whoever consumes the SDK, and the compiler's messages come back as
+//! [`Diagnostic`]s with a [`Location`] rather than raw text:
+//!
+//! ```
+//! use codegen::verify::CommandVerifier;
+//!
+//! let tsc = CommandVerifier::new("npx")
+//!     .with_name("tsc")
+//!     .with_args(["tsc", "--noEmit", "--pretty", "false"]);
+//! ```
+//!
+//! Both `file(line,col): error CODE: message` (tsc) and
+//! `file:line:col: error[CODE]: message` (rustc, `cargo --message-format
+//! short`, gcc and most other compilers) lines are understood.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use crate::diagnostic::{Diagnostic, Location, Severity};
+use crate::postprocess::{IfMissing, wait_with_timeout};
+use crate::{Error, Result};
+
+/// Checks generated output, typically by compiling it.
+pub trait Verifier: Send + Sync {
+    /// Name used in messages, e.g. `tsc`.
+    fn name(&self) -> &str;
+
+    /// Check the output written to `output_dir`. Errors are for failing to
+    /// run the check; code that doesn't compile is a
+    /// [`VerifyOutcome::Failed`] report.
+    fn verify(&self, output_dir: &Path) -> Result<VerifyReport>;
+}
+
+/// Whether the output passed a [`Verifier`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerifyOutcome {
+    Passed,
+    Failed,
+    /// Not run, e.g. because the compiler isn't installed.
+    Skipped(String),
+}
+
+/// What running a [`Verifier`] found.
+#[derive(Debug)]
+pub struct VerifyReport {
+    /// Name of the verifier.
+    pub verifier: String,
+    pub outcome: VerifyOutcome,
+    /// Errors and warnings, in the order the compiler reported them.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl VerifyReport {
+    /// The errors among the diagnostics.
+    pub fn errors(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics
+            .iter()
+            .filter(|d| d.severity() == Severity::Error)
+    }
+
+    /// This report, or [`Error::VerificationFailed`] if the output failed.
+    pub fn into_result(self) -> Result<Self> {
+        if self.outcome == VerifyOutcome::Failed {
+            return Err(Error::VerificationFailed {
+                verifier: self.verifier,
+                diagnostics: self.diagnostics,
+            });
+        }
+        Ok(self)
+    }
+}
+
+/// Parse a line of compiler output into a diagnostic with an
+/// [`Error::Compiler`]; `None` if it isn't an error or warning with a
+/// location.
+pub fn parse_diagnostic(line: &str) -> Option<Diagnostic> {
+    let line = line.trim_end();
+    let (location, severity, code, message) =
+        parse_parenthesized(line).or_else(|| parse_colon_separated(line))?;
+    let mut diagnostic = Diagnostic::new(Error::Compiler {
+        severity,
+        code: code.map(str::to_string),
+        message: message.to_string(),
+    });
+    diagnostic.location = Some(location);
+    Some(diagnostic)
+}
+
+type Parsed<'a> = (Location, Severity, Option<&'a str>, &'a str);
+
+/// `src/index.ts(12,5): error TS2322: message`
+fn parse_parenthesized(line: &str) -> Option<Parsed<'_>> {
+    let (location, rest) = line.split_once("): ")?;
+    let (path, position) = location.rsplit_once('(')?;
+    let (line_no, column) = position.split_once(',')?;
+    let (head, message) = rest.split_once(": ")?;
+    let (severity, code) = match head.split_once(' ') {
+        Some((severity, code)) => (severity, Some(code)),
+        None => (head, None),
+    };
+    let location = Location {
+        path: PathBuf::from(path),
+        line: Some(line_no.trim().parse().ok()?),
+        column: Some(column.trim().parse().ok()?),
+    };
+    Some((location, parse_severity(severity)?, code, message))
+}
+
+/// `src/lib.rs:12:5: error[E0308]: message`. The path ends at the first
+/// `:line:col:`, so it may contain colons itself, e.g. `C:\src\lib.rs`.
+fn parse_colon_separated(line: &str) -> Option<Parsed<'_>> {
+    line.match_indices(':').find_map(|(at, _)| {
+        let path = &line[..at];
+        let mut parts = line[at + 1..].splitn(3, ':');
+        let line_no: u32 = parts.next()?.trim().parse().ok()?;
+        let column: u32 = parts.next()?.trim().parse().ok()?;
+        let (head, message) = parts.next()?.trim_start().split_once(": ")?;
+        let (severity, code) = match head.split_once('[') {
+            Some((severity, code)) => (severity, Some(code.strip_suffix(']')?)),
+            None => (head, None),
+        };
+        let location = Location {
+            path: PathBuf::from(path),
+            line: Some(line_no),
+            column: Some(column),
+        };
+        (!path.is_empty()).then_some((location, parse_severity(severity)?, code, message))
+    })
+}
+
+fn parse_severity(word: &str) -> Option<Severity> {
+    match word.trim() {
+        "error" => Some(Severity::Error),
+        "warning" => Some(Severity::Warning),
+        _ => None,
+    }
+}
+
+/// Runs a compiler in the output directory and parses its messages. The
+/// output fails when the program exits with an error.
+#[derive(Debug, Clone)]
+pub struct CommandVerifier {
+    name: String,
+    program: String,
+    args: Vec<String>,
+    timeout: Duration,
+    if_missing: IfMissing,
+    missing_markers: Vec<String>,
+}
+
+impl CommandVerifier {
+    /// Time a compiler may run before it is killed, unless set with
+    /// [`with_timeout`](Self::with_timeout).
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(600);
+
+    pub fn new(program: impl Into<String>) -> Self {
+        let program = program.into();
+        Self {
+            name: program.clone(),
+            program,
+            args: Vec::new(),
+            timeout: Self::DEFAULT_TIMEOUT,
+            if_missing: IfMissing::Skip,
+            missing_markers: Vec::new(),
+        }
+    }
+
+    /// Name used in messages; the program by default.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    pub fn with_args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// What to do when the program isn't installed; skip by default, since
+    /// verifying is optional.
+    pub fn with_if_missing(mut self, if_missing: IfMissing) -> Self {
+        self.if_missing = if_missing;
+        self
+    }
+
+    /// Output by which a failing program says the compiler isn't
+    /// installed, for launchers like `npx --no` that run but can't find it.
+    /// Treated like a missing program.
+    pub fn with_missing_marker(mut self, marker: impl Into<String>) -> Self {
+        self.missing_markers.push(marker.into());
+        self
+    }
+}
+
+impl Verifier for CommandVerifier {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn verify(&self, output_dir: &Path) -> Result<VerifyReport> {
+        let report = |outcome, diagnostics| VerifyReport {
+            verifier: self.name.clone(),
+            outcome,
+            diagnostics,
+        };
+        let child = Command::new(&self.program)
+            .args(&self.args)
+            .current_dir(output_dir)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn();
+        let child = match child {
+            Ok(child) => child,
+            Err(e) if e.kind() == io::ErrorKind::NotFound && self.if_missing == IfMissing::Skip => {
+                let reason = format!("`{}` is not installed", self.program);
+                return Ok(report(VerifyOutcome::Skipped(reason), Vec::new()));
+            }
+            Err(e) => {
+                return Err(Error::Custom(format!("Failed to run {}: {}", self.name, e)));
+            }
+        };
+
+        let output = wait_with_timeout(child, &self.name, self.timeout)?;
+        let mut diagnostics: Vec<Diagnostic> = output
+            .stdout
+            .lines()
+            .chain(output.stderr.lines())
+            .filter_map(parse_diagnostic)
+            .collect();
+        if output.status.success() {
+            return Ok(report(VerifyOutcome::Passed, diagnostics));
+        }
+        let text = format!("{}\n{}", output.stdout.trim(), output.stderr.trim());
+        if self.if_missing == IfMissing::Skip
+            && self
+                .missing_markers
+                .iter()
+                .any(|marker| text.contains(marker.as_str()))
+        {
+            let reason = format!("`{}` is not installed", self.name);
+            return Ok(report(VerifyOutcome::Skipped(reason), Vec::new()));
+        }
+        // Keep what the compiler said when none of it could be parsed
+        if !diagnostics.iter().any(|d| d.severity() == Severity::Error) {
+            diagnostics.push(Diagnostic::new(Error::Compiler {
+                severity: Severity::Error,
+                code: None,
+                message: format!("{} failed ({}): {}", self.name, output.status, text.trim()),
+            }));
+        }
+        Ok(report(VerifyOutcome::Failed, diagnostics))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parts(diagnostic: &Diagnostic) -> (&Location, Severity, Option<&str>, &str) {
+        match &diagnostic.error {
+            Error::Compiler {
+                severity,
+                code,
+                message,
+            } => (
+                diagnostic.location.as_ref().unwrap(),
+                *severity,
+                code.as_deref(),
+                message,
+            ),
+            other => panic!("not a compiler message: {other}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_diagnostics() {
+        let tsc = parse_diagnostic(
+            "src/types/Pet.ts(12,5): error TS2322: Type 'string' is not assignable to type 'number'.",
+        )
+        .unwrap();
+        let (location, _, code, _) = parts(&tsc);
+        assert_eq!(location.path, PathBuf::from("src/types/Pet.ts"));
+        assert_eq!((location.line, location.column), (Some(12), Some(5)));
+        assert_eq!(code, Some("TS2322"));
+        assert_eq!(
+            tsc.to_string(),
+            "src/types/Pet.ts:12:5: error TS2322: Type 'string' is not assignable to type 'number'."
+        );
+
+        let rustc =
+            parse_diagnostic("src/types.rs:3:17: error[E0412]: cannot find type `Pet`").unwrap();
+        let (_, severity, code, message) = parts(&rustc);
+        assert_eq!(severity, Severity::Error);
+        assert_eq!(code, Some("E0412"));
+        assert_eq!(message, "cannot find type `Pet`");
+
+        let warning =
+            parse_diagnostic("src/lib.rs:1:5: warning: unused import: `std::fmt`").unwrap();
+        assert_eq!(warning.severity(), Severity::Warning);
+        assert_eq!(parts(&warning).2, None);
+
+        let windows =
+            parse_diagnostic(r"C:\out\src\lib.rs:3:17: error[E0412]: cannot find type `Pet`")
+                .unwrap();
+        let (location, _, code, _) = parts(&windows);
+        assert_eq!(location.path, PathBuf::from(r"C:\out\src\lib.rs"));
+        assert_eq!((location.line, location.column), (Some(3), Some(17)));
+        assert_eq!(code, Some("E0412"));
+
+        assert!(
+            parse_diagnostic("error: could not compile `pets` due to 1 previous error").is_none()
+        );
+        assert!(parse_diagnostic("src/lib.rs:1:5: note: here").is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_command_verifier() {
+        let dir = std::env::temp_dir();
+        let failing = CommandVerifier::new("sh").with_name("checker").with_args([
+            "-c",
+            "echo 'src/a.ts(1,2): error TS1005: expected' ; echo 'src/b.rs:3:4: warning: unused' >&2; exit 2",
+        ]);
+        let report = failing.verify(&dir).unwrap();
+        assert_eq!(report.outcome, VerifyOutcome::Failed);
+        assert_eq!(report.diagnostics.len(), 2);
+        assert_eq!(report.errors().count(), 1);
+        let err = report.into_result().unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("src/a.ts:1:2: error TS1005: expected")
+        );
+
+        let unparsed = CommandVerifier::new("sh").with_args(["-c", "echo boom >&2; exit 1"]);
+        let report = unparsed.verify(&dir).unwrap();
+        assert_eq!(report.outcome, VerifyOutcome::Failed);
+        assert!(report.diagnostics[0].message.contains("boom"));
+
+        let passing = CommandVerifier::new("true");
+        assert_eq!(passing.verify(&dir).unwrap().outcome, VerifyOutcome::Passed);
+
+        let missing = CommandVerifier::new("oas-gen-no-such-compiler");
+        assert!(matches!(
+            missing.verify(&dir).unwrap().outcome,
+            VerifyOutcome::Skipped(reason) if reason.contains("not installed")
+        ));
+
+        // A launcher that runs but can't find the compiler, like `npx --no tsc`
+        let launcher = CommandVerifier::new("sh")
+            .with_name("tsc")
+            .with_args([
+                "-c",
+                "echo 'npm error could not determine executable to run' >&2; exit 1",
+            ])
+            .with_missing_marker("could not determine executable to run");
+        assert!(matches!(
+            launcher.verify(&dir).unwrap().outcome,
+            VerifyOutcome::Skipped(reason) if reason == "`tsc` is not installed"
+        ));
+    }
+}
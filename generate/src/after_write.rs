@@ -0,0 +1,285 @@
+//! Running post-write hooks over several outputs at once.
+//!
+//! A run that writes several targets (the versions of a spec, or the
+//! outputs of several generators) hands them all to
+//! [`GeneratorRegistry::after_write_all`] instead of calling
+//! [`after_write_to_disk`](GeneratorRegistry::after_write_to_disk) in a
+//! loop. Targets run in parallel, up to
+//! [`AfterWriteOptions::concurrency`] at a time, each in its own output
+//! directory. What their post-processors print is captured per target
+//! instead of interleaving on the terminal. A target that fails or panics
+//! doesn't affect the others that are running. By default it cancels the
+//! targets that haven't started yet; with
+//! [`continue_on_error`](AfterWriteOptions::continue_on_error) they all
+//! run.
+
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use codegen::postprocess::PostProcessOutcome;
+use codegen::{Error, VirtualFS};
+
+use crate::{GenerationReport, GeneratorRegistry};
+
+/// Generated output written to disk, to post-process.
+#[derive(Debug, Clone, Copy)]
+pub struct WriteTarget<'a> {
+    /// The generator that produced it.
+    pub language: &'a str,
+    pub output_dir: &'a Path,
+    pub vfs: &'a VirtualFS,
+}
+
+/// How [`GeneratorRegistry::after_write_all`] runs targets.
+#[derive(Debug, Clone)]
+pub struct AfterWriteOptions {
+    /// Targets post-processed at the same time; `1`, one after the
+    /// other, by default.
+    pub concurrency: usize,
+    /// Run every target even after one failed, instead of cancelling the
+    /// ones that haven't started.
+    pub continue_on_error: bool,
+}
+
+impl Default for AfterWriteOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: 1,
+            continue_on_error: false,
+        }
+    }
+}
+
+/// What post-processing a target did.
+#[derive(Debug)]
+pub enum TargetOutcome {
+    /// Every hook ran; what each post-processor did, by name.
+    Done(Vec<(String, PostProcessOutcome)>),
+    Failed(Error),
+    /// Not run because an earlier target failed.
+    Cancelled,
+}
+
+/// Post-processing of one [`WriteTarget`].
+#[derive(Debug)]
+pub struct TargetReport {
+    pub language: String,
+    pub output_dir: PathBuf,
+    pub outcome: TargetOutcome,
+    /// What the post-processors printed, in the order they ran.
+    pub output: String,
+    pub duration: Duration,
+    /// How long the `format` and `after_write` phases took, as far as they
+    /// ran.
+    pub timings: GenerationReport,
+}
+
+impl TargetReport {
+    pub fn is_failed(&self) -> bool {
+        matches!(self.outcome, TargetOutcome::Failed(_))
+    }
+
+    /// Whether a post-processor ran, and so may have rewritten files.
+    pub fn rewrote_files(&self) -> bool {
+        match &self.outcome {
+            TargetOutcome::Done(outcomes) => outcomes
+                .iter()
+                .any(|(_, outcome)| *outcome == PostProcessOutcome::Ran),
+            _ => false,
+        }
+    }
+}
+
+impl GeneratorRegistry {
+    /// Run the post-processors and
+    /// [`after_write_to_disk`](codegen::Generator::after_write_to_disk)
+    /// hook of every target, as configured by `options`. Returns a report
+    /// per target, in the order of `targets`; failures are reported there
+    /// rather than returned.
+    pub fn after_write_all(
+        &self,
+        targets: &[WriteTarget<'_>],
+        options: &AfterWriteOptions,
+    ) -> Vec<TargetReport> {
+        let next = AtomicUsize::new(0);
+        let failed = AtomicBool::new(false);
+        let reports: Mutex<Vec<Option<TargetReport>>> =
+            Mutex::new(targets.iter().map(|_| None).collect());
+        let workers = options.concurrency.clamp(1, targets.len().max(1));
+
+        std::thread::scope(|scope| {
+            for _ in 0..workers {
+                scope.spawn(|| {
+                    loop {
+                        let index = next.fetch_add(1, Ordering::SeqCst);
+                        let Some(target) = targets.get(index) else {
+                            break;
+                        };
+                        let report = if !options.continue_on_error && failed.load(Ordering::SeqCst)
+                        {
+                            TargetReport {
+                                language: target.language.to_string(),
+                                output_dir: target.output_dir.to_path_buf(),
+                                outcome: TargetOutcome::Cancelled,
+                                output: String::new(),
+                                duration: Duration::ZERO,
+                                timings: GenerationReport::new(),
+                            }
+                        } else {
+                            self.run_target(target)
+                        };
+                        if report.is_failed() {
+                            failed.store(true, Ordering::SeqCst);
+                        }
+                        reports.lock().unwrap()[index] = Some(report);
+                    }
+                });
+            }
+        });
+
+        reports
+            .into_inner()
+            .unwrap()
+            .into_iter()
+            .map(|report| report.expect("every target is reported"))
+            .collect()
+    }
+
+    fn run_target(&self, target: &WriteTarget<'_>) -> TargetReport {
+        let started = Instant::now();
+        let mut output = String::new();
+        let mut timings = GenerationReport::new();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            self.after_write(
+                target.language,
+                target.output_dir,
+                target.vfs,
+                &mut output,
+                &mut timings,
+            )
+        }));
+        let outcome = match result {
+            Ok(Ok(outcomes)) => TargetOutcome::Done(outcomes),
+            Ok(Err(e)) => TargetOutcome::Failed(e),
+            Err(panic) => {
+                let message = panic
+                    .downcast_ref::<&str>()
+                    .map(|s| s.to_string())
+                    .or_else(|| panic.downcast_ref::<String>().cloned())
+                    .unwrap_or_default();
+                TargetOutcome::Failed(Error::Custom(format!(
+                    "post-write hooks panicked: {}",
+                    message
+                )))
+            }
+        };
+        TargetReport {
+            language: target.language.to_string(),
+            output_dir: target.output_dir.to_path_buf(),
+            outcome,
+            output,
+            duration: started.elapsed(),
+            timings,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use codegen::postprocess::PostProcessor;
+    use codegen::{Config, GenIr, Generator, Result};
+
+    use super::*;
+
+    /// Prints the name of the output directory, failing on `broken`.
+    struct Echo;
+
+    impl PostProcessor for Echo {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        fn matches(&self, _path: &Path) -> bool {
+            false
+        }
+
+        fn run(&self, output_dir: &Path, files: &[PathBuf]) -> Result<PostProcessOutcome> {
+            self.run_captured(output_dir, files)
+                .map(|(outcome, _)| outcome)
+        }
+
+        fn run_captured(
+            &self,
+            output_dir: &Path,
+            _files: &[PathBuf],
+        ) -> Result<(PostProcessOutcome, String)> {
+            let name = output_dir.file_name().unwrap().to_string_lossy();
+            if name == "broken" {
+                return Err(Error::Custom("echo failed".to_string()));
+            }
+            Ok((PostProcessOutcome::Ran, format!("{}\n", name)))
+        }
+    }
+
+    struct EchoGenerator;
+
+    impl Generator for EchoGenerator {
+        fn generate(&self, _ir: &GenIr, _config: &Config) -> Result<VirtualFS> {
+            Ok(VirtualFS::new())
+        }
+
+        fn language(&self) -> &str {
+            "echo"
+        }
+
+        fn post_processors(&self) -> Vec<Box<dyn PostProcessor>> {
+            vec![Box::new(Echo)]
+        }
+    }
+
+    #[test]
+    fn test_after_write_all() {
+        let mut registry = GeneratorRegistry::new();
+        registry.register(Box::new(EchoGenerator));
+        let vfs = VirtualFS::new();
+        let target = |output_dir: &'static str| WriteTarget {
+            language: "echo",
+            output_dir: Path::new(output_dir),
+            vfs: &vfs,
+        };
+        let targets = [target("v1"), target("broken"), target("v2")];
+
+        let options = AfterWriteOptions {
+            concurrency: 1,
+            continue_on_error: false,
+        };
+        let reports = registry.after_write_all(&targets, &options);
+        assert_eq!(reports.len(), 3);
+        assert!(matches!(reports[0].outcome, TargetOutcome::Done(_)));
+        assert_eq!(reports[0].output, "v1\n");
+        assert!(reports[1].is_failed());
+        assert!(matches!(reports[2].outcome, TargetOutcome::Cancelled));
+
+        let options = AfterWriteOptions {
+            concurrency: 4,
+            continue_on_error: true,
+        };
+        let reports = registry.after_write_all(&targets, &options);
+        assert!(reports[1].is_failed());
+        assert_eq!(reports[2].output_dir, Path::new("v2"));
+        assert_eq!(reports[2].output, "v2\n");
+
+        let unknown = [WriteTarget {
+            language: "cobol",
+            ..targets[0]
+        }];
+        let reports = registry.after_write_all(&unknown, &AfterWriteOptions::default());
+        assert!(matches!(
+            reports[0].outcome,
+            TargetOutcome::Failed(Error::GeneratorNotFound(_))
+        ));
+    }
+}
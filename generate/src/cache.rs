@@ -0,0 +1,382 @@
+//! Cache of parsed specs for long-lived generation services.
+//!
+//! Parsing a spec and building its [`GenIr`] dominates the cost of a
+//! generation request. [`SpecCache`] keeps built IRs keyed by a hash of the
+//! spec source so repeated requests for the same spec skip both steps.
+//! Entries expire after an optional TTL and the least recently used entries
+//! are evicted once the configured memory budget is exceeded.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use codegen::{Error, GenIr, Result};
+use sha2::{Digest, Sha256};
+
+/// Why an entry left the cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvictionReason {
+    /// The entry outlived the cache TTL.
+    Expired,
+    /// The cache exceeded its memory budget.
+    Capacity,
+}
+
+/// Hooks for exporting cache metrics (hit rates, evictions) to a metrics system.
+///
+/// All methods default to no-ops.
+pub trait CacheMetrics: Send + Sync {
+    /// A lookup found a live entry.
+    fn on_hit(&self, _key: &str) {}
+
+    /// A lookup had to parse the spec and build the IR.
+    fn on_miss(&self, _key: &str, _build_time: Duration) {}
+
+    /// An entry was removed.
+    fn on_evict(&self, _key: &str, _reason: EvictionReason) {}
+}
+
+/// Point-in-time counters of a [`SpecCache`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    pub entries: usize,
+    /// Approximate memory held by cached entries, in bytes.
+    pub bytes: usize,
+}
+
+impl CacheStats {
+    /// Fraction of lookups served from the cache (0.0 when there were none).
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
+}
+
+struct Entry {
+    ir: Arc<GenIr>,
+    inserted: Instant,
+    last_used: Instant,
+    size: usize,
+}
+
+#[derive(Default)]
+struct State {
+    entries: HashMap<String, Entry>,
+    bytes: usize,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+}
+
+/// Thread-safe cache of built IRs keyed by spec content hash.
+pub struct SpecCache {
+    state: Mutex<State>,
+    ttl: Option<Duration>,
+    max_bytes: Option<usize>,
+    metrics: Option<Arc<dyn CacheMetrics>>,
+}
+
+impl SpecCache {
+    /// Create an unbounded cache without expiry.
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(State::default()),
+            ttl: None,
+            max_bytes: None,
+            metrics: None,
+        }
+    }
+
+    /// Expire entries this long after they were built.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Evict least recently used entries once the cache holds more than
+    /// `max_bytes`. Entry size is approximated from the spec source size.
+    pub fn with_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Report hits, misses and evictions to `metrics`.
+    pub fn with_metrics(mut self, metrics: Arc<dyn CacheMetrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Cache key for a spec source: the hex SHA-256 of its content, so
+    /// distinct specs never share an entry.
+    pub fn key(content: &str) -> String {
+        sha256_hex(content.as_bytes())
+    }
+
+    /// Return the IR for `content`, parsing and building it on a miss.
+    pub fn get_or_parse(&self, content: &str) -> Result<Arc<GenIr>> {
+        let key = Self::key(content);
+        if let Some(ir) = self.lookup(&key) {
+            return Ok(ir);
+        }
+
+        // Build outside the lock so concurrent requests for other specs aren't blocked
+        let started = Instant::now();
+        let spec = parser::parse(content)
+            .map_err(|e| Error::Custom(format!("Failed to parse spec: {}", e)))?;
+        let ir = Arc::new(GenIr::from(spec));
+        let build_time = started.elapsed();
+
+        if let Some(metrics) = &self.metrics {
+            metrics.on_miss(&key, build_time);
+        }
+        self.insert(key, content.len(), ir.clone());
+        Ok(ir)
+    }
+
+    /// Return the cached IR for `content` without building it.
+    pub fn get(&self, content: &str) -> Option<Arc<GenIr>> {
+        self.lookup(&Self::key(content))
+    }
+
+    /// Drop the entry for `content`, if any.
+    pub fn invalidate(&self, content: &str) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(entry) = state.entries.remove(&Self::key(content)) {
+            state.bytes -= entry.size;
+        }
+    }
+
+    /// Drop all entries. Counters are kept.
+    pub fn clear(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.entries.clear();
+        state.bytes = 0;
+    }
+
+    /// Current counters.
+    pub fn stats(&self) -> CacheStats {
+        let state = self.state.lock().unwrap();
+        CacheStats {
+            hits: state.hits,
+            misses: state.misses,
+            evictions: state.evictions,
+            entries: state.entries.len(),
+            bytes: state.bytes,
+        }
+    }
+
+    fn lookup(&self, key: &str) -> Option<Arc<GenIr>> {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+
+        let expired = state
+            .entries
+            .get(key)
+            .is_some_and(|entry| self.is_expired(entry, now));
+        if expired {
+            self.evict(&mut state, key, EvictionReason::Expired);
+        }
+
+        match state.entries.get_mut(key) {
+            Some(entry) => {
+                entry.last_used = now;
+                let ir = entry.ir.clone();
+                state.hits += 1;
+                if let Some(metrics) = &self.metrics {
+                    metrics.on_hit(key);
+                }
+                Some(ir)
+            }
+            None => {
+                state.misses += 1;
+                None
+            }
+        }
+    }
+
+    fn insert(&self, key: String, source_len: usize, ir: Arc<GenIr>) {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+
+        // Another request may have built the same spec concurrently
+        if let Some(previous) = state.entries.remove(&key) {
+            state.bytes -= previous.size;
+        }
+
+        let size = estimate_size(source_len);
+        state.entries.insert(
+            key.clone(),
+            Entry {
+                ir,
+                inserted: now,
+                last_used: now,
+                size,
+            },
+        );
+        state.bytes += size;
+
+        let expired: Vec<String> = state
+            .entries
+            .iter()
+            .filter(|(_, entry)| self.is_expired(entry, now))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in &expired {
+            self.evict(&mut state, key, EvictionReason::Expired);
+        }
+
+        if let Some(max_bytes) = self.max_bytes {
+            while state.bytes > max_bytes && state.entries.len() > 1 {
+                let Some(oldest) = state
+                    .entries
+                    .iter()
+                    .filter(|(candidate, _)| **candidate != key)
+                    .min_by_key(|(_, entry)| entry.last_used)
+                    .map(|(key, _)| key.clone())
+                else {
+                    break;
+                };
+                self.evict(&mut state, &oldest, EvictionReason::Capacity);
+            }
+        }
+    }
+
+    fn is_expired(&self, entry: &Entry, now: Instant) -> bool {
+        self.ttl
+            .is_some_and(|ttl| now.duration_since(entry.inserted) >= ttl)
+    }
+
+    fn evict(&self, state: &mut State, key: &str, reason: EvictionReason) {
+        if let Some(entry) = state.entries.remove(key) {
+            state.bytes -= entry.size;
+            state.evictions += 1;
+            if let Some(metrics) = &self.metrics {
+                metrics.on_evict(key, reason);
+            }
+        }
+    }
+}
+
+impl Default for SpecCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Rough in-memory size of an IR built from a spec of `source_len` bytes.
+/// The IR holds several canonical name variants per identifier, so it is
+/// noticeably larger than the source.
+fn estimate_size(source_len: usize) -> usize {
+    source_len.saturating_mul(4)
+}
+
+/// Hex SHA-256 of `bytes`.
+pub(crate) fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    fn spec(title: &str) -> String {
+        format!(
+            r#"{{"openapi": "3.0.0", "info": {{"title": "{}", "version": "1.0.0"}}, "paths": {{}}}}"#,
+            title
+        )
+    }
+
+    #[derive(Default)]
+    struct Counting {
+        hits: AtomicUsize,
+        misses: AtomicUsize,
+        evictions: AtomicUsize,
+    }
+
+    impl CacheMetrics for Counting {
+        fn on_hit(&self, _key: &str) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn on_miss(&self, _key: &str, _build_time: Duration) {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn on_evict(&self, _key: &str, _reason: EvictionReason) {
+            self.evictions.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn test_cache_hits_reuse_ir() {
+        let metrics = Arc::new(Counting::default());
+        let cache = SpecCache::new().with_metrics(metrics.clone());
+
+        let first = cache.get_or_parse(&spec("A")).unwrap();
+        let second = cache.get_or_parse(&spec("A")).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        cache.get_or_parse(&spec("B")).unwrap();
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (1, 2, 2));
+        assert!((stats.hit_rate() - 1.0 / 3.0).abs() < f64::EPSILON);
+        assert_eq!(metrics.hits.load(Ordering::Relaxed), 1);
+        assert_eq!(metrics.misses.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn test_cache_evicts_least_recently_used() {
+        let a = spec("A");
+        let b = spec("B");
+        let c = spec("C");
+        let budget = estimate_size(a.len()) * 2;
+        let cache = SpecCache::new().with_max_bytes(budget);
+
+        cache.get_or_parse(&a).unwrap();
+        cache.get_or_parse(&b).unwrap();
+        // Touch A so B becomes the least recently used entry
+        cache.get_or_parse(&a).unwrap();
+        cache.get_or_parse(&c).unwrap();
+
+        assert!(cache.get(&a).is_some());
+        assert!(cache.get(&b).is_none());
+        assert!(cache.get(&c).is_some());
+        assert_eq!(cache.stats().evictions, 1);
+    }
+
+    #[test]
+    fn test_cache_expires_entries() {
+        let cache = SpecCache::new().with_ttl(Duration::ZERO);
+        cache.get_or_parse(&spec("A")).unwrap();
+        assert!(cache.get(&spec("A")).is_none());
+        assert_eq!(cache.stats().entries, 0);
+    }
+
+    #[test]
+    fn test_cache_key_is_content_hash() {
+        assert_eq!(
+            SpecCache::key("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_ne!(SpecCache::key(&spec("A")), SpecCache::key(&spec("B")));
+    }
+
+    #[test]
+    fn test_cache_reports_parse_errors() {
+        let cache = SpecCache::new();
+        assert!(cache.get_or_parse("not: [valid").is_err());
+        assert_eq!(cache.stats().entries, 0);
+    }
+}
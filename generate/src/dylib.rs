@@ -0,0 +1,101 @@
+//! Generators loaded from shared libraries over the versioned C ABI of
+//! [`codegen::plugin`]. Requires the `dynamic-plugins` feature.
+
+use std::path::Path;
+
+use codegen::plugin::{
+    ABI_VERSION_SYMBOL, PLUGIN_ABI_VERSION, PluginBuffer, PluginVTable, VTABLE_SYMBOL,
+    decode_output, encode_input,
+};
+use codegen::{Config, Error, GenIr, Generator, Result, VirtualFS};
+use libloading::Library;
+
+/// A generator in a shared library.
+pub struct DylibGenerator {
+    vtable: &'static PluginVTable,
+    language: String,
+    // Last, so the vtable's functions outlive every use
+    _library: Library,
+}
+
+// The ABI requires plugin functions to be callable from any thread
+unsafe impl Send for DylibGenerator {}
+unsafe impl Sync for DylibGenerator {}
+
+impl DylibGenerator {
+    /// Load the plugin at `path`, checking that it was built for
+    /// [`PLUGIN_ABI_VERSION`] before calling anything else.
+    pub fn load(path: &Path) -> Result<Self> {
+        let error =
+            |message: String| Error::PluginLoadError(format!("{}: {}", path.display(), message));
+        // Loading runs the library's initializers; plugins are trusted code
+        let library = unsafe { Library::new(path) }.map_err(|e| error(e.to_string()))?;
+
+        let version = unsafe {
+            let abi_version = library
+                .get::<unsafe extern "C" fn() -> u32>(ABI_VERSION_SYMBOL)
+                .map_err(|_| {
+                    error(
+                        "not an oas-gen plugin, or built for the unversioned interface: it doesn't export `oas_gen_plugin_abi_version`"
+                            .to_string(),
+                    )
+                })?;
+            abi_version()
+        };
+        if version != PLUGIN_ABI_VERSION {
+            return Err(error(format!(
+                "plugin uses ABI version {}, expected {}",
+                version, PLUGIN_ABI_VERSION
+            )));
+        }
+
+        // 'static as far as `self` is concerned: the library stays loaded
+        // as long as `self` does
+        let vtable: &'static PluginVTable = unsafe {
+            let vtable = library
+                .get::<unsafe extern "C" fn() -> *const PluginVTable>(VTABLE_SYMBOL)
+                .map_err(|e| error(e.to_string()))?;
+            vtable().as_ref()
+        }
+        .ok_or_else(|| error("plugin returned no vtable".to_string()))?;
+        if vtable.abi_version != PLUGIN_ABI_VERSION {
+            return Err(error(format!(
+                "plugin vtable has ABI version {}, expected {}",
+                vtable.abi_version, PLUGIN_ABI_VERSION
+            )));
+        }
+
+        let language = unsafe { take(vtable, (vtable.language)()) };
+        let language = String::from_utf8(language)
+            .map_err(|_| error("language name is not UTF-8".to_string()))?;
+        Ok(Self {
+            vtable,
+            language,
+            _library: library,
+        })
+    }
+}
+
+impl Generator for DylibGenerator {
+    fn generate(&self, ir: &GenIr, config: &Config) -> Result<VirtualFS> {
+        let input = encode_input(ir, config)?;
+        let output = unsafe {
+            take(
+                self.vtable,
+                (self.vtable.generate)(input.as_ptr(), input.len()),
+            )
+        };
+        decode_output(&self.language, &output)
+    }
+
+    fn language(&self) -> &str {
+        &self.language
+    }
+}
+
+/// Copy a buffer the plugin returned, then give it back to be freed.
+unsafe fn take(vtable: &PluginVTable, buffer: PluginBuffer) -> Vec<u8> {
+    let bytes = unsafe { buffer.as_slice() }.to_vec();
+    unsafe { (vtable.free)(buffer) };
+    bytes
+}
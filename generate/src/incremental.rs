@@ -0,0 +1,95 @@
+//! Skipping generation when nothing it depends on changed.
+//!
+//! The registry records a cache key in every [`VirtualFS`] it generates:
+//! a hash of the spec, the generator with its version and the config. The
+//! key is written to the output's [`Manifest`], so the next run can compare
+//! keys with [`GeneratorRegistry::cached_output`] and reuse the files on
+//! disk. That makes running the generator in every CI job cheap.
+
+use std::path::Path;
+
+use codegen::{Config, Error, GenIr, Generator, Manifest, Result, VirtualFS};
+
+use crate::GeneratorRegistry;
+use crate::cache::sha256_hex;
+
+impl GeneratorRegistry {
+    /// Hash of everything generating `language` from `ir` with `config`
+    /// depends on: the spec, the generator's name and version, oas-gen's
+    /// version, the config with its `lang_options` and the templates in
+    /// `template_dir`. `None` when the config has
+    /// [`transforms`](Config::transforms) or custom
+    /// [`passes`](Config::passes), which can't be hashed, so their output is
+    /// never reused.
+    pub fn cache_key(&self, language: &str, ir: &GenIr, config: &Config) -> Result<Option<String>> {
+        let generator = self
+            .get(language)
+            .ok_or_else(|| Error::GeneratorNotFound(language.to_string()))?;
+        cache_key(generator, ir, config)
+    }
+
+    /// The output of generating `language` from `ir` with `config`, read
+    /// back from `output_dir` if its manifest records the same
+    /// [`cache_key`](Self::cache_key) and none of the generated files was
+    /// edited or deleted since. `None` means it has to be generated.
+    pub fn cached_output(
+        &self,
+        language: &str,
+        ir: &GenIr,
+        config: &Config,
+        output_dir: &Path,
+    ) -> Result<Option<VirtualFS>> {
+        let Some(key) = self.cache_key(language, ir, config)? else {
+            return Ok(None);
+        };
+        match Manifest::read(output_dir)? {
+            Some(manifest) if manifest.cache_key.as_deref() == Some(key.as_str()) => {
+                Ok(manifest.read_files(output_dir)?)
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+pub(crate) fn cache_key(
+    generator: &dyn Generator,
+    ir: &GenIr,
+    config: &Config,
+) -> Result<Option<String>> {
+    if !config.transforms.is_empty() || !config.passes.is_declarative() {
+        return Ok(None);
+    }
+    let mut templates = Vec::new();
+    if let Some(dir) = &config.template_dir {
+        template_hashes(dir, Path::new(""), &mut templates)?;
+    }
+    let inputs = serde_json::json!({
+        "spec": ir.checksum(),
+        "generator": generator.language(),
+        "version": generator.version(),
+        "oas_gen": codegen::GENERATOR_VERSION,
+        "config": config,
+        "lang_options": config.lang_options,
+        "templates": templates,
+    });
+    let inputs = serde_json::to_vec(&inputs)
+        .map_err(|e| Error::Custom(format!("Failed to serialize the config: {}", e)))?;
+    Ok(Some(sha256_hex(&inputs)))
+}
+
+/// Paths and hashes of the files under `dir`, in path order. Hashed whole:
+/// unlike generated files, a template's protected regions are part of it.
+fn template_hashes(dir: &Path, prefix: &Path, hashes: &mut Vec<(String, String)>) -> Result<()> {
+    let mut entries = std::fs::read_dir(dir.join(prefix))?.collect::<std::io::Result<Vec<_>>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let path = prefix.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            template_hashes(dir, &path, hashes)?;
+        } else {
+            let content = std::fs::read(entry.path())?;
+            hashes.push((path.to_string_lossy().into_owned(), sha256_hex(&content)));
+        }
+    }
+    Ok(())
+}
@@ -5,16 +5,47 @@
 //! - **Generator Strategy** (Rust code): decides file structure and logic
 //! - **Template Fragments** (Handlebars): renders code snippets
 
+use std::borrow::Cow;
 use std::collections::BTreeMap;
 use std::path::Path;
+use std::time::Instant;
 
-use codegen::{Config, Error, GenIr, Generator, Result, VirtualFS};
+use codegen::diagnostic::{Phase, ResultExt};
+use codegen::postprocess::{self, PostProcessOutcome};
+use codegen::transform::TransformSink;
+use codegen::verify::VerifyReport;
+use codegen::{
+    Config, Error, FileSink, GenIr, Generator, GeneratorInfo, HeaderSink, LintThreshold, Manifest,
+    ProjectConfig, Result, SharedTypes, VirtualFS,
+};
+use ir::gen_ir::StableId;
+
+pub mod after_write;
+pub mod cache;
+#[cfg(feature = "dynamic-plugins")]
+pub mod dylib;
+mod incremental;
+pub mod plugins;
+pub mod project;
+pub mod report;
+pub mod versions;
+#[cfg(feature = "wasm-plugins")]
+pub mod wasm;
+
+pub use after_write::{AfterWriteOptions, TargetOutcome, TargetReport, WriteTarget};
+pub use cache::{CacheMetrics, CacheStats, EvictionReason, SpecCache};
+pub use plugins::{PLUGIN_PATH_ENV, PluginLoad};
+pub use project::TargetOutput;
+pub use report::{GenerationReport, PhaseTiming};
+pub use versions::{SpecSnapshot, load_snapshots, load_snapshots_with};
 
 /// Registry for managing multiple generators.
 ///
 /// This allows for built-in generators and dynamic plugin loading.
 pub struct GeneratorRegistry {
     generators: BTreeMap<String, Box<dyn Generator>>,
+    aliases: BTreeMap<String, String>,
+    project: Option<ProjectConfig>,
 }
 
 impl GeneratorRegistry {
@@ -22,6 +53,8 @@ impl GeneratorRegistry {
     pub fn new() -> Self {
         Self {
             generators: BTreeMap::new(),
+            aliases: BTreeMap::new(),
+            project: None,
         }
     }
 
@@ -31,10 +64,16 @@ impl GeneratorRegistry {
 
         // Register built-in generators based on enabled features
         #[cfg(feature = "typescript")]
-        registry.register(Box::new(typescript::TypeScriptGenerator::new()));
+        {
+            registry.register(Box::new(typescript::TypeScriptGenerator::new()));
+            registry.alias("ts", "typescript");
+        }
 
         #[cfg(feature = "rust-axum")]
-        registry.register(Box::new(rust_axum::RustAxumGenerator::new()));
+        {
+            registry.register(Box::new(rust_axum::RustAxumGenerator::new()));
+            registry.alias("axum", "rust-axum");
+        }
 
         registry
     }
@@ -45,9 +84,32 @@ impl GeneratorRegistry {
             .insert(generator.language().to_string(), generator);
     }
 
-    /// Get a generator by language name.
+    /// Make `alias` another name for the generator `language`, e.g. `ts`
+    /// for `typescript`. A generator registered as `alias` takes
+    /// precedence.
+    pub fn alias(&mut self, alias: impl Into<String>, language: impl Into<String>) {
+        self.aliases.insert(alias.into(), language.into());
+    }
+
+    /// Aliases and the generator each names, by alias.
+    pub fn aliases(&self) -> &BTreeMap<String, String> {
+        &self.aliases
+    }
+
+    /// The language `name` refers to: itself, or the generator it is an
+    /// alias of.
+    pub fn resolve<'a>(&'a self, name: &'a str) -> &'a str {
+        if self.generators.contains_key(name) {
+            return name;
+        }
+        self.aliases.get(name).map_or(name, String::as_str)
+    }
+
+    /// Get a generator by language name or alias.
     pub fn get(&self, language: &str) -> Option<&dyn Generator> {
-        self.generators.get(language).map(|g| g.as_ref())
+        self.generators
+            .get(self.resolve(language))
+            .map(|g| g.as_ref())
     }
 
     /// List all available generator languages.
@@ -55,66 +117,602 @@ impl GeneratorRegistry {
         self.generators.keys().map(|s| s.as_str()).collect()
     }
 
+    /// Name, version, description and options of every generator, by name.
+    pub fn list(&self) -> Vec<GeneratorInfo> {
+        self.generators.values().map(|g| g.info()).collect()
+    }
+
     /// Generate code using the specified language generator.
     /// This calls the generator's hooks at appropriate times.
+    ///
+    /// Fails with [`Error::ConfigError`] if `lang_options` don't match the
+    /// generator's [`options_schema`](Generator::options_schema), or if
+    /// [`Config::lint_threshold`] is set: an IR can't be linted, so lint the
+    /// spec with [`lint_spec`] first or use [`generate_spec`](Self::generate_spec).
     pub fn generate(&self, language: &str, ir: &GenIr, config: &Config) -> Result<VirtualFS> {
+        self.generate_with_report(language, ir, config, &mut GenerationReport::new())
+    }
+
+    /// [`generate`](Self::generate), recording how long each of the
+    /// generator's phases took in `report`.
+    pub fn generate_with_report(
+        &self,
+        language: &str,
+        ir: &GenIr,
+        config: &Config,
+        report: &mut GenerationReport,
+    ) -> Result<VirtualFS> {
+        unlintable(config)?;
+        let generator = self.checked(language, config)?;
+        Self::run(generator, ir, config, report)
+    }
+
+    /// Look up the generator for `language`, validate `config`'s
+    /// `lang_options` against its schema and check its format patterns.
+    fn checked(&self, language: &str, config: &Config) -> Result<&dyn Generator> {
         let generator = self
             .get(language)
             .ok_or_else(|| Error::GeneratorNotFound(language.to_string()))?;
+        if let Some(schema) = generator.options_schema() {
+            codegen::options::validate(language, &schema, &config.lang_options)?;
+        }
+        config.formats.check()?;
+        Ok(generator)
+    }
 
-        generator.validate(ir)?;
+    fn run(
+        generator: &dyn Generator,
+        ir: &GenIr,
+        config: &Config,
+        report: &mut GenerationReport,
+    ) -> Result<VirtualFS> {
+        let language = Some(generator.language());
+        let cache_key = incremental::cache_key(generator, ir, config)?;
+        let ir = prepare(ir, config)?;
+        let ir = ir.as_ref();
+
+        let started = Instant::now();
+        generator.validate(ir).in_phase(Phase::Validate)?;
+        report.record(Phase::Validate, language, started, Some(ir.types.len()));
 
         // Call before_generate hook
-        generator.before_generate(ir, config)?;
+        let started = Instant::now();
+        generator
+            .before_generate(ir, config)
+            .in_phase(Phase::BeforeGenerate)?;
+        report.record(Phase::BeforeGenerate, language, started, None);
 
         // Generate the code
-        let vfs = generator.generate(ir, config)?;
+        let started = Instant::now();
+        let mut vfs = generator.generate(ir, config).in_phase(Phase::Generate)?;
+        if config.nullability_report() {
+            vfs.add_file(Config::NULLABILITY_REPORT_PATH, nullability_report(ir));
+        }
+        if let Some(header) = &config.file_header {
+            vfs.add_headers(header, ir);
+        }
+        let mut vfs = vfs.transform(&config.transforms)?;
+        // The spec document's hash, as in file headers, not the IR's
+        if !ir.api.spec_hash.is_empty() {
+            vfs.set_spec_hash(&ir.api.spec_hash);
+        }
+        if let Some(cache_key) = cache_key {
+            vfs.set_cache_key(cache_key);
+        }
+        report.record(Phase::Generate, language, started, Some(vfs.len()));
 
         // Call after_generate hook
-        generator.after_generate(&vfs, ir, config)?;
+        let started = Instant::now();
+        generator
+            .after_generate(&vfs, ir, config)
+            .in_phase(Phase::AfterGenerate)?;
+        report.record(Phase::AfterGenerate, language, started, None);
 
         Ok(vfs)
     }
 
-    /// Call the after_write_to_disk hook for the specified generator.
-    /// This should be called after writing files to disk.
+    /// Generate code and merge it into one source file with the generator's
+    /// [`merge_files`](Generator::merge_files). Fails with
+    /// [`Error::ConfigError`] for generators that can't merge their output.
+    pub fn generate_single_file(
+        &self,
+        language: &str,
+        ir: &GenIr,
+        config: &Config,
+    ) -> Result<String> {
+        self.generate_single_file_with_report(language, ir, config, &mut GenerationReport::new())
+    }
+
+    /// [`generate_single_file`](Self::generate_single_file), recording how
+    /// long each of the generator's phases took in `report`.
+    pub fn generate_single_file_with_report(
+        &self,
+        language: &str,
+        ir: &GenIr,
+        config: &Config,
+        report: &mut GenerationReport,
+    ) -> Result<String> {
+        unlintable(config)?;
+        let generator = self.checked(language, config)?;
+        let vfs = Self::run(generator, ir, config, report)?;
+        generator
+            .merge_files(&vfs)
+            .in_phase(Phase::Generate)?
+            .ok_or_else(|| {
+                Error::ConfigError(format!(
+                    "the {} generator can't merge its output into one file",
+                    language
+                ))
+            })
+    }
+
+    /// Lint, parse and generate a spec held in memory. Unlike
+    /// [`generate`](Self::generate), this honors
+    /// [`Config::lint_threshold`], since the spec text is at hand.
+    pub fn generate_spec(&self, language: &str, spec: &str, config: &Config) -> Result<VirtualFS> {
+        lint_spec(spec, None, config)?;
+        let config: &Config = &linted(config);
+        self.generate(language, &parse_spec(spec, config)?, &overlaid(config))
+    }
+
+    /// Generate code by streaming files into `sink` as they are produced,
+    /// without holding the whole output in memory.
+    ///
+    /// The `after_generate` hook is not called because no VirtualFS is built.
+    pub fn generate_to_sink(
+        &self,
+        language: &str,
+        ir: &GenIr,
+        config: &Config,
+        sink: &mut dyn FileSink,
+    ) -> Result<()> {
+        unlintable(config)?;
+        let generator = self.checked(language, config)?;
+
+        let ir = prepare(ir, config)?;
+        let ir = ir.as_ref();
+
+        generator.validate(ir).in_phase(Phase::Validate)?;
+        generator
+            .before_generate(ir, config)
+            .in_phase(Phase::BeforeGenerate)?;
+        let mut transformed;
+        let sink: &mut dyn FileSink = if config.transforms.is_empty() {
+            sink
+        } else {
+            transformed = TransformSink::new(sink, &config.transforms);
+            &mut transformed
+        };
+        let mut with_header;
+        let sink: &mut dyn FileSink = match &config.file_header {
+            Some(header) => {
+                with_header = HeaderSink::new(sink, header, ir);
+                &mut with_header
+            }
+            None => sink,
+        };
+        generator
+            .generate_streaming(ir, config, sink)
+            .in_phase(Phase::Generate)?;
+        if config.nullability_report() {
+            sink.write_text(
+                Path::new(Config::NULLABILITY_REPORT_PATH),
+                &nullability_report(ir),
+            )
+            .in_phase(Phase::Write)?;
+        }
+        sink.finish().in_phase(Phase::Write)
+    }
+
+    /// Generate code from spec source, reusing a cached IR when the same spec
+    /// was seen before. Intended for long-lived services that keep one
+    /// registry and cache around across requests. Honors
+    /// [`Config::lint_threshold`].
+    pub fn generate_cached(
+        &self,
+        cache: &SpecCache,
+        language: &str,
+        spec: &str,
+        config: &Config,
+    ) -> Result<VirtualFS> {
+        lint_spec(spec, None, config)?;
+        let config: &Config = &linted(config);
+        // The cache holds specs as written, before overlays
+        if config.passes.has_overlays() {
+            return self.generate(language, &parse_spec(spec, config)?, &overlaid(config));
+        }
+        let ir = cache.get_or_parse(spec)?;
+        self.generate(language, &ir, config)
+    }
+
+    /// Generate several versions of a spec into one output.
+    ///
+    /// Each snapshot is generated under a directory named after its version.
+    /// Types that are identical in every snapshot are generated once under
+    /// [`versions::SHARED_DIR`], and each version's config lists them in
+    /// [`Config::shared_types`] so generators that support it import them
+    /// instead of redeclaring them.
+    ///
+    /// With [`Config::lint_threshold`] set, each snapshot's
+    /// [`path`](SpecSnapshot::path) is linted first; snapshots without one
+    /// fail with [`Error::ConfigError`].
+    pub fn generate_versions(
+        &self,
+        language: &str,
+        snapshots: &[SpecSnapshot],
+        config: &Config,
+    ) -> Result<VirtualFS> {
+        self.generate_versions_with_report(
+            language,
+            snapshots,
+            config,
+            &mut GenerationReport::new(),
+        )
+    }
+
+    /// [`generate_versions`](Self::generate_versions), recording the lint
+    /// and the phases of every version's generation in `report`.
+    pub fn generate_versions_with_report(
+        &self,
+        language: &str,
+        snapshots: &[SpecSnapshot],
+        config: &Config,
+        report: &mut GenerationReport,
+    ) -> Result<VirtualFS> {
+        if config.lint_threshold.is_some() {
+            let started = Instant::now();
+            for snapshot in snapshots {
+                let path = snapshot.path.as_deref().ok_or_else(|| {
+                    Error::ConfigError(format!(
+                        "lint_threshold needs the spec text, but snapshot {} wasn't loaded from a file",
+                        snapshot.version
+                    ))
+                })?;
+                lint_spec(&std::fs::read_to_string(path)?, Some(path), config)?;
+            }
+            report.record(Phase::Lint, None, started, Some(snapshots.len()));
+        }
+        let config: &Config = &linted(config);
+        let generator = self.checked(language, config)?;
+        let mut output = VirtualFS::new();
+
+        let shared = versions::shared_types(snapshots);
+        let mut version_config = config.clone();
+        if let Some(first) = snapshots.first()
+            && !shared.is_empty()
+        {
+            let shared_ir = versions::shared_ir(first, &shared);
+            let vfs = Self::run(generator, &shared_ir, config, report)?;
+            output.extend_under(versions::SHARED_DIR, vfs);
+
+            // Named as the versions will see them, after the naming policy
+            let types = shared
+                .iter()
+                .filter_map(|id| first.ir.types.get(id))
+                .map(|decl| match &decl.id {
+                    StableId::Named(name) if config.naming.renames_types() => {
+                        config.naming.type_name(name).canonical
+                    }
+                    _ => decl.name.canonical.clone(),
+                })
+                .collect();
+            version_config.shared_types = Some(SharedTypes {
+                package: format!("../{}", versions::SHARED_DIR),
+                types,
+            });
+        }
+
+        for snapshot in snapshots {
+            let vfs = Self::run(generator, &snapshot.ir, &version_config, report)?;
+            output.extend_under(&snapshot.version, vfs);
+        }
+
+        Ok(output)
+    }
+
+    /// Run the generator's post-processors over the written files, then its
+    /// after_write_to_disk hook. This should be called after writing files
+    /// to disk. Returns what each post-processor did, by name.
     pub fn after_write_to_disk(
         &self,
         language: &str,
         output_dir: &Path,
         vfs: &VirtualFS,
-    ) -> Result<()> {
+    ) -> Result<Vec<(String, PostProcessOutcome)>> {
+        self.after_write_to_disk_with_report(
+            language,
+            output_dir,
+            vfs,
+            &mut GenerationReport::new(),
+        )
+    }
+
+    /// [`after_write_to_disk`](Self::after_write_to_disk), recording how
+    /// long the post-processors (the `format` phase) and the hook took in
+    /// `report`.
+    pub fn after_write_to_disk_with_report(
+        &self,
+        language: &str,
+        output_dir: &Path,
+        vfs: &VirtualFS,
+        report: &mut GenerationReport,
+    ) -> Result<Vec<(String, PostProcessOutcome)>> {
+        self.after_write(language, output_dir, vfs, &mut String::new(), report)
+    }
+
+    /// [`after_write_to_disk_with_report`](Self::after_write_to_disk_with_report),
+    /// appending what the post-processors printed to `output`.
+    fn after_write(
+        &self,
+        language: &str,
+        output_dir: &Path,
+        vfs: &VirtualFS,
+        output: &mut String,
+        report: &mut GenerationReport,
+    ) -> Result<Vec<(String, PostProcessOutcome)>> {
         let generator = self
             .get(language)
             .ok_or_else(|| Error::GeneratorNotFound(language.to_string()))?;
 
-        generator.after_write_to_disk(output_dir, vfs)
+        let started = Instant::now();
+        let mut outcomes = Vec::new();
+        for processor in generator.post_processors() {
+            let (outcome, printed) =
+                postprocess::run_on_disk_captured(processor.as_ref(), output_dir, vfs)
+                    .in_phase(Phase::Format)?;
+            output.push_str(&printed);
+            outcomes.push((processor.name().to_string(), outcome));
+        }
+        if outcomes
+            .iter()
+            .any(|(_, outcome)| *outcome == PostProcessOutcome::Ran)
+        {
+            rehash_manifest(output_dir)?;
+        }
+        report.record(Phase::Format, Some(language), started, Some(outcomes.len()));
+
+        let started = Instant::now();
+        generator
+            .after_write_to_disk(output_dir, vfs)
+            .in_phase(Phase::AfterWrite)?;
+        report.record(Phase::AfterWrite, Some(language), started, None);
+        Ok(outcomes)
+    }
+
+    /// Check the output written to `output_dir` with the generator's
+    /// [`verifier`](Generator::verifier), e.g. `tsc --noEmit`. Returns
+    /// `None` if the generator has none; a report whose outcome is
+    /// `Failed` lists the compiler's errors (see
+    /// [`VerifyReport::into_result`](codegen::verify::VerifyReport::into_result)).
+    pub fn verify(&self, language: &str, output_dir: &Path) -> Result<Option<VerifyReport>> {
+        let generator = self
+            .get(language)
+            .ok_or_else(|| Error::GeneratorNotFound(language.to_string()))?;
+        generator
+            .verifier()
+            .map(|verifier| verifier.verify(output_dir))
+            .transpose()
+            .in_phase(Phase::Verify)
     }
 
-    /// Load a dynamic plugin from a shared library.
+    /// Load a generator from a shared library exporting the versioned C
+    /// ABI of [`codegen::plugin`]. Fails with [`Error::PluginLoadError`]
+    /// if the plugin was built for another ABI version.
     /// Requires the "dynamic-plugins" feature.
     #[cfg(feature = "dynamic-plugins")]
     pub fn load_plugin(&mut self, path: &Path) -> Result<()> {
-        use libloading::{Library, Symbol};
+        let generator = dylib::DylibGenerator::load(path)?;
+        self.register(Box::new(generator));
+        Ok(())
+    }
 
-        let lib = unsafe { Library::new(path).map_err(|e| Error::PluginLoadError(e.to_string()))? };
+    /// Load a sandboxed generator from a `.wasm` file (see [`wasm`]).
+    /// Requires the "wasm-plugins" feature.
+    #[cfg(feature = "wasm-plugins")]
+    pub fn load_wasm_plugin(&mut self, path: &Path) -> Result<()> {
+        let generator = wasm::WasmGenerator::load(path)?;
+        self.register(Box::new(generator));
+        Ok(())
+    }
+}
+
+/// Lint `spec`, the text of the file at `path` if it has one, and fail with
+/// [`Error::LintFailed`] if any finding reaches [`Config::lint_threshold`].
+/// Rules and levels come from the nearest `oas-lint.toml` above `path`, or
+/// above the current directory for specs without a file, and are the core
+/// rules when there is none. Findings point into `spec` as written. Does
+/// nothing when no threshold is set.
+pub fn lint_spec(spec: &str, path: Option<&Path>, config: &Config) -> Result<()> {
+    let Some(threshold) = config.lint_threshold else {
+        return Ok(());
+    };
+    let validation = project_linter(path)?
+        .lint(spec)
+        .map_err(|e| Error::ValidationError(format!("Failed to lint spec: {}", e)))?;
+    let location = path.map(|path| format!("{}:", path.display()));
+    let findings: Vec<String> = validation
+        .diagnostics
+        .iter()
+        .filter(|d| blocks(d.severity, threshold))
+        .map(|d| {
+            format!(
+                "{}{}:{}: {} [{}] {} ({})",
+                location.as_deref().unwrap_or(""),
+                d.range.start.line + 1,
+                d.range.start.col + 1,
+                d.severity.as_str(),
+                d.rule.as_str(),
+                d.message,
+                d.pointer
+            )
+        })
+        .collect();
+    if findings.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::LintFailed(findings))
+    }
+}
+
+/// The linter configured by the `oas-lint.toml` nearest to `path`'s
+/// directory, or to the current directory.
+fn project_linter(path: Option<&Path>) -> Result<lint::Linter> {
+    let dir = match path.and_then(Path::parent) {
+        Some(dir) => dir.to_path_buf(),
+        None => std::env::current_dir()?,
+    };
+    let Some(file) = lint::LintConfig::discover(&dir) else {
+        return Ok(lint::Linter::default());
+    };
+    lint::LintConfig::from_path(&file)
+        .and_then(|config| lint::Linter::from_config(&config))
+        .map_err(|e| Error::ConfigError(format!("lint config {}: {}", file.display(), e)))
+}
+
+/// `config` without its lint threshold, for handing an already linted spec's
+/// IR to the entry points that refuse to skip linting.
+pub fn linted(config: &Config) -> Cow<'_, Config> {
+    match config.lint_threshold {
+        Some(_) => Cow::Owned(Config {
+            lint_threshold: None,
+            ..config.clone()
+        }),
+        None => Cow::Borrowed(config),
+    }
+}
+
+/// Fail when `config` asks for linting an IR, which has no spec text left.
+fn unlintable(config: &Config) -> Result<()> {
+    match config.lint_threshold {
+        Some(_) => Err(Error::ConfigError(
+            "lint_threshold needs the spec text: lint it with lint_spec and generate with \
+             generate::linted(config), or use generate_spec"
+                .to_string(),
+        )),
+        None => Ok(()),
+    }
+}
 
-        // Plugin must export: extern "C" fn create_generator() -> Box<dyn Generator>
-        let constructor: Symbol<unsafe extern "C" fn() -> *mut dyn Generator> = unsafe {
-            lib.get(b"create_generator")
-                .map_err(|e| Error::PluginLoadError(e.to_string()))?
+/// Apply the overlay [`passes`](codegen::passes) of `config` to `spec`, in
+/// order. Generate the resulting IR with [`overlaid`]`(config)`. Fails with
+/// [`Error::ConfigError`] if an overlay can't be read or applied, or is
+/// declared after another pass.
+pub fn apply_overlays(spec: oas3::Spec, config: &Config) -> Result<oas3::Spec> {
+    let mut spec = spec;
+    for path in config.passes.overlays()? {
+        let error = |message: String| {
+            Error::ConfigError(format!("overlay {}: {}", path.display(), message))
         };
+        let text = std::fs::read_to_string(path).map_err(|e| error(e.to_string()))?;
+        // YAML is a superset of JSON
+        let overlay: overlay::Overlay =
+            serde_saphyr::from_str(&text).map_err(|e| error(e.to_string()))?;
+        spec = overlay::apply(&spec, &overlay).map_err(|e| error(e.to_string()))?;
+    }
+    Ok(spec)
+}
 
-        let generator_ptr = unsafe { constructor() };
-        let generator = unsafe { Box::from_raw(generator_ptr) };
+/// Rehash the files the [`Manifest`] in `output_dir` records, after
+/// post-processors rewrote them, so they aren't taken for hand edits and
+/// the output stays up to date for
+/// [`cached_output`](GeneratorRegistry::cached_output). Does nothing
+/// without a manifest.
+pub fn rehash_manifest(output_dir: &Path) -> Result<()> {
+    if let Some(mut manifest) = Manifest::read(output_dir)? {
+        manifest.rehash(output_dir)?;
+        manifest.write(output_dir)?;
+    }
+    Ok(())
+}
+
+/// `config` without its overlay passes, for generating the IR of a spec
+/// [`apply_overlays`] rewrote. The registry's entry points that take an IR
+/// fail on overlays instead of skipping them.
+pub fn overlaid(config: &Config) -> Cow<'_, Config> {
+    if config.passes.has_overlays() {
+        Cow::Owned(Config {
+            passes: config.passes.without_overlays(),
+            ..config.clone()
+        })
+    } else {
+        Cow::Borrowed(config)
+    }
+}
 
-        self.register(generator);
+/// Parse `spec`, apply the config's overlays and build the IR.
+fn parse_spec(spec: &str, config: &Config) -> Result<GenIr> {
+    let parsed =
+        parser::parse(spec).map_err(|e| Error::Custom(format!("Failed to parse spec: {}", e)))?;
+    Ok(GenIr::from(apply_overlays(parsed, config)?))
+}
 
-        // Keep the library loaded
-        std::mem::forget(lib);
+fn blocks(severity: lint::Severity, threshold: LintThreshold) -> bool {
+    let rank = match severity {
+        lint::Severity::Error => LintThreshold::Error,
+        lint::Severity::Warning => LintThreshold::Warning,
+        lint::Severity::Info => LintThreshold::Info,
+        lint::Severity::Hint => LintThreshold::Hint,
+    };
+    rank <= threshold
+}
 
-        Ok(())
+/// Run the configured passes, then apply the operation filter, naming
+/// policy, locale, package name and base URL before any generator sees the
+/// IR, copying it only when one of them changes something. In strict mode,
+/// fails if any schema fell back to an untyped value. Fails with
+/// [`Error::ConfigError`] if the filter names a tag the spec doesn't have or
+/// the naming policy gives two types the same name.
+fn prepare<'a>(ir: &'a GenIr, config: &Config) -> Result<Cow<'a, GenIr>> {
+    let ir = if config.passes.is_empty() {
+        Cow::Borrowed(ir)
+    } else {
+        let mut owned = ir.clone();
+        config.passes.apply(&mut owned)?;
+        Cow::Owned(owned)
+    };
+    if config.strict && !ir.fallbacks.is_empty() {
+        return Err(Error::UntypedSchemas(ir.fallbacks.clone()));
+    }
+    let filter = config.operation_filter();
+    let unknown = filter.unknown_tags(&ir);
+    if !unknown.is_empty() {
+        return Err(Error::ConfigError(format!(
+            "unknown tag{} {} in the operation filter",
+            if unknown.len() == 1 { "" } else { "s" },
+            unknown
+                .iter()
+                .map(|tag| format!("`{}`", tag))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )));
     }
+    if filter.is_empty()
+        && !config.naming.renames_types()
+        && config.locale.is_none()
+        && config.package_name.is_none()
+        && config.base_url.is_none()
+    {
+        return Ok(ir);
+    }
+    let mut owned = ir.into_owned();
+    owned.filter_operations(&filter);
+    owned
+        .apply_naming(&config.naming)
+        .map_err(|e| Error::ConfigError(format!("naming policy: {}", e)))?;
+    if let Some(locale) = &config.locale {
+        owned.localize(locale);
+    }
+    if let Some(package_name) = &config.package_name {
+        owned.rename_package(package_name);
+    }
+    if let Some(base_url) = &config.base_url {
+        owned.set_base_url(base_url);
+    }
+    Ok(Cow::Owned(owned))
+}
+
+fn nullability_report(ir: &GenIr) -> String {
+    ir::audit::render_report(&ir.nullability_audit())
 }
 
 impl Default for GeneratorRegistry {
@@ -135,6 +733,15 @@ mod tests {
         {
             assert!(!registry.languages().is_empty());
             assert!(registry.languages().contains(&"typescript"));
+
+            let typescript = registry
+                .list()
+                .into_iter()
+                .find(|info| info.name == "typescript")
+                .unwrap();
+            assert_eq!(typescript.version, "0.1.0");
+            assert!(!typescript.description.is_empty());
+            assert!(typescript.options.iter().any(|o| o.name == "layout"));
         }
     }
 }
@@ -0,0 +1,178 @@
+//! Finding plugins on disk.
+//!
+//! Operators add generators by dropping plugin files into a directory:
+//! shared libraries (`.so`, `.dylib`, `.dll`; feature `dynamic-plugins`)
+//! and `.wasm` modules (feature `wasm-plugins`). Other files are ignored.
+//! [`PLUGIN_PATH_ENV`] lists such directories. A plugin that fails to load
+//! doesn't stop the others; its error is reported in the [`PluginLoad`],
+//! as are plugins that replace a generator registered before them, so a
+//! stray file can't silently take over a built-in language.
+
+use std::path::{Path, PathBuf};
+
+use codegen::{Error, Generator, Result};
+
+use crate::GeneratorRegistry;
+
+/// Environment variable listing plugin directories, separated like `PATH`
+/// (`:` on Unix, `;` on Windows).
+pub const PLUGIN_PATH_ENV: &str = "OAS_GEN_PLUGIN_PATH";
+
+/// What loading plugins from directories did.
+#[derive(Debug, Default)]
+pub struct PluginLoad {
+    /// Languages of the generators registered, with the file each came from.
+    pub loaded: Vec<(String, PathBuf)>,
+    /// Those of [`loaded`](Self::loaded) that replaced a generator (or
+    /// alias) already registered for the language, e.g. a built-in one.
+    pub replaced: Vec<(String, PathBuf)>,
+    /// Plugins (or directories) that failed to load.
+    pub errors: Vec<(PathBuf, Error)>,
+}
+
+impl PluginLoad {
+    fn extend(&mut self, other: PluginLoad) {
+        self.loaded.extend(other.loaded);
+        self.replaced.extend(other.replaced);
+        self.errors.extend(other.errors);
+    }
+}
+
+impl GeneratorRegistry {
+    /// Register every plugin in `dir`, in file name order. Fails only if
+    /// `dir` can't be read; plugins that fail to load are listed in
+    /// [`PluginLoad::errors`]. A plugin replaces an already registered
+    /// generator of the same language, and is then listed in
+    /// [`PluginLoad::replaced`].
+    pub fn load_plugins_from_dir(&mut self, dir: &Path) -> Result<PluginLoad> {
+        let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+            .map_err(|e| Error::PluginLoadError(format!("{}: {}", dir.display(), e)))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_file() && plugin_kind(path).is_some())
+            .collect();
+        paths.sort();
+
+        let mut load = PluginLoad::default();
+        for path in paths {
+            match self.load_plugin_file(&path) {
+                Ok((language, replaced)) => {
+                    if replaced {
+                        load.replaced.push((language.clone(), path.clone()));
+                    }
+                    load.loaded.push((language, path));
+                }
+                Err(e) => load.errors.push((path, e)),
+            }
+        }
+        Ok(load)
+    }
+
+    /// Register the plugins of every directory in [`PLUGIN_PATH_ENV`], in
+    /// order, so later directories override earlier ones. Directories that
+    /// can't be read are listed in [`PluginLoad::errors`].
+    pub fn load_plugins_from_env(&mut self) -> PluginLoad {
+        let mut load = PluginLoad::default();
+        let Some(dirs) = std::env::var_os(PLUGIN_PATH_ENV) else {
+            return load;
+        };
+        for dir in std::env::split_paths(&dirs).filter(|dir| !dir.as_os_str().is_empty()) {
+            match self.load_plugins_from_dir(&dir) {
+                Ok(dir_load) => load.extend(dir_load),
+                Err(e) => load.errors.push((dir, e)),
+            }
+        }
+        load
+    }
+
+    /// Register the plugin at `path`, returning its language and whether it
+    /// replaced a registered generator.
+    fn load_plugin_file(&mut self, path: &Path) -> Result<(String, bool)> {
+        let generator: Result<Box<dyn Generator>> =
+            match plugin_kind(path) {
+                #[cfg(feature = "dynamic-plugins")]
+                Some(PluginKind::Dylib) => crate::dylib::DylibGenerator::load(path)
+                    .map(|g| Box::new(g) as Box<dyn Generator>),
+                #[cfg(feature = "wasm-plugins")]
+                Some(PluginKind::Wasm) => crate::wasm::WasmGenerator::load(path)
+                    .map(|g| Box::new(g) as Box<dyn Generator>),
+                _ => Err(Error::PluginLoadError(format!(
+                    "{}: not a plugin this build can load",
+                    path.display()
+                ))),
+            };
+        let generator = generator?;
+        let language = generator.language().to_string();
+        let replaced = self.get(&language).is_some();
+        self.register(generator);
+        Ok((language, replaced))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PluginKind {
+    #[cfg_attr(not(feature = "dynamic-plugins"), allow(dead_code))]
+    Dylib,
+    #[cfg_attr(not(feature = "wasm-plugins"), allow(dead_code))]
+    Wasm,
+}
+
+/// The kind of plugin `path` is, if this build loads that kind.
+fn plugin_kind(path: &Path) -> Option<PluginKind> {
+    let extension = path.extension()?.to_str()?;
+    if cfg!(feature = "dynamic-plugins") && extension == std::env::consts::DLL_EXTENSION {
+        return Some(PluginKind::Dylib);
+    }
+    if cfg!(feature = "wasm-plugins") && extension == "wasm" {
+        return Some(PluginKind::Wasm);
+    }
+    None
+}
+
+#[cfg(all(test, feature = "wasm-plugins"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_plugins_from_dir() {
+        let dir = std::env::temp_dir().join(format!("oas-gen-plugins-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        // Module::from_file accepts the text format too
+        std::fs::write(
+            dir.join("a-echo.wasm"),
+            r#"(module
+                (memory (export "memory") 1)
+                (data (i32.const 0) "echo")
+                (func (export "oas_gen_abi_version") (result i32) (i32.const 1))
+                (func (export "oas_gen_alloc") (param i32) (result i32) (i32.const 0))
+                (func (export "oas_gen_language") (result i64) (i64.const 4))
+                (func (export "oas_gen_generate") (param i32 i32) (result i64) (i64.const 0)))"#,
+        )
+        .unwrap();
+        std::fs::write(dir.join("b-broken.wasm"), "not wasm").unwrap();
+        std::fs::write(dir.join("README.md"), "# Plugins").unwrap();
+
+        let mut registry = GeneratorRegistry::new();
+        let load = registry.load_plugins_from_dir(&dir).unwrap();
+        assert_eq!(
+            load.loaded,
+            vec![("echo".to_string(), dir.join("a-echo.wasm"))]
+        );
+        assert_eq!(load.errors.len(), 1);
+        assert_eq!(load.errors[0].0, dir.join("b-broken.wasm"));
+        assert!(registry.get("echo").is_some());
+        assert!(load.replaced.is_empty());
+
+        // Loading again replaces the plugin's own earlier registration
+        let load = registry.load_plugins_from_dir(&dir).unwrap();
+        assert_eq!(load.replaced, load.loaded);
+
+        assert!(
+            registry
+                .load_plugins_from_dir(&dir.join("missing"))
+                .is_err()
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,108 @@
+//! Generating a project's default targets.
+//!
+//! A registry given a [`ProjectConfig`] with
+//! [`set_project`](GeneratorRegistry::set_project) knows what to generate
+//! when nobody names a generator: the project's `targets`, each with its
+//! own output directory, options and `lang_options`.
+//! [`generate_default`](GeneratorRegistry::generate_default) generates
+//! them all. The project's `aliases` are added to the registry's, so
+//! targets and callers can name generators either way.
+
+use std::path::PathBuf;
+
+use codegen::{Config, Error, GenIr, ProjectConfig, Result, Target, VirtualFS};
+
+use crate::GeneratorRegistry;
+
+/// The output of one default target.
+#[derive(Debug)]
+pub struct TargetOutput {
+    /// The generator's language, aliases resolved.
+    pub language: String,
+    /// Where the project puts it, see [`ProjectConfig::output_for`].
+    pub output_dir: Option<PathBuf>,
+    pub vfs: VirtualFS,
+}
+
+impl GeneratorRegistry {
+    /// Take default targets, their outputs and options, and aliases from
+    /// `project`, replacing any project set before.
+    pub fn set_project(&mut self, project: ProjectConfig) {
+        for (alias, language) in &project.aliases {
+            self.alias(alias.clone(), language.clone());
+        }
+        self.project = Some(project);
+    }
+
+    /// This registry with `project` set, see
+    /// [`set_project`](Self::set_project).
+    pub fn with_project(mut self, project: ProjectConfig) -> Self {
+        self.set_project(project);
+        self
+    }
+
+    pub fn project(&self) -> Option<&ProjectConfig> {
+        self.project.as_ref()
+    }
+
+    /// What [`generate_default`](Self::generate_default) generates: the
+    /// project's targets or, without any, the only registered generator.
+    /// Fails with [`Error::ConfigError`] if that doesn't name one target.
+    pub fn default_targets(&self) -> Result<Vec<Target>> {
+        if let Some(project) = &self.project
+            && !project.targets.is_empty()
+        {
+            return Ok(project.targets.clone());
+        }
+        match self.languages().as_slice() {
+            [language] => Ok(vec![Target {
+                generator: language.to_string(),
+                output: None,
+            }]),
+            languages => Err(Error::ConfigError(format!(
+                "no default target; set `targets` in {} to one or more of: {}",
+                ProjectConfig::FILE_NAME,
+                languages.join(", ")
+            ))),
+        }
+    }
+
+    /// The configuration to generate `target` with: the project's options
+    /// plus the `lang_options` given under any name of its generator.
+    pub fn target_config(&self, target: &Target) -> Config {
+        let language = self.resolve(&target.generator);
+        let Some(project) = &self.project else {
+            return Config::default();
+        };
+        let mut config = project.config_for(language);
+        for (name, options) in &project.generators {
+            if name != language && self.resolve(name) == language {
+                config.lang_options.extend(options.clone());
+            }
+        }
+        config
+    }
+
+    /// Generate every [`default_targets`](Self::default_targets) target
+    /// from `ir`, in order, stopping at the first failure. Like
+    /// [`generate`](Self::generate), fails if the project sets a
+    /// `lint_threshold`: lint the spec with [`lint_spec`](crate::lint_spec)
+    /// and clear it first.
+    pub fn generate_default(&self, ir: &GenIr) -> Result<Vec<TargetOutput>> {
+        self.default_targets()?
+            .iter()
+            .map(|target| {
+                let vfs = self.generate(&target.generator, ir, &self.target_config(target))?;
+                let language = self.resolve(&target.generator);
+                Ok(TargetOutput {
+                    language: language.to_string(),
+                    output_dir: self
+                        .project
+                        .as_ref()
+                        .and_then(|project| project.output_for(target, language)),
+                    vfs,
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,178 @@
+//! How long each phase of a generation took.
+//!
+//! [`GeneratorRegistry::generate_with_report`](crate::GeneratorRegistry::generate_with_report)
+//! records the phases the registry runs for a generator. Callers add the
+//! ones around it (parsing the spec, writing files, post-processing) with
+//! [`GenerationReport::record`], so one report covers a whole run. The
+//! report prints as a table and serializes to JSON for CI:
+//!
+//! ```json
+//! {"total_ms": 41.2, "phases": [{"phase": "generate", "generator": "typescript", "duration_ms": 30.5, "count": 14}]}
+//! ```
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use codegen::diagnostic::Phase;
+use serde::{Serialize, Serializer};
+
+/// Timing of the phases of a generation, in the order they ran.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GenerationReport {
+    #[serde(rename = "total_ms", serialize_with = "millis")]
+    total: Duration,
+    phases: Vec<PhaseTiming>,
+}
+
+/// One run of a phase.
+#[derive(Debug, Clone, Serialize)]
+pub struct PhaseTiming {
+    pub phase: Phase,
+    /// The generator that ran the phase, if it ran one.
+    pub generator: Option<String>,
+    #[serde(rename = "duration_ms", serialize_with = "millis")]
+    pub duration: Duration,
+    /// What the phase went through: types for `build_ir` and `validate`,
+    /// files for `generate` and `write`, post-processors for `format`, specs
+    /// for `lint`.
+    pub count: Option<usize>,
+}
+
+impl GenerationReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record that `phase` ran from `started` until now.
+    pub fn record(
+        &mut self,
+        phase: Phase,
+        generator: Option<&str>,
+        started: Instant,
+        count: Option<usize>,
+    ) {
+        self.record_duration(phase, generator, started.elapsed(), count);
+    }
+
+    /// Record that `phase` took `duration`, for phases that ran in parts,
+    /// e.g. around another phase.
+    pub fn record_duration(
+        &mut self,
+        phase: Phase,
+        generator: Option<&str>,
+        duration: Duration,
+        count: Option<usize>,
+    ) {
+        self.total += duration;
+        self.phases.push(PhaseTiming {
+            phase,
+            generator: generator.map(str::to_string),
+            duration,
+            count,
+        });
+    }
+
+    /// The phases, in the order they ran.
+    pub fn phases(&self) -> &[PhaseTiming] {
+        &self.phases
+    }
+
+    /// Time spent in all phases.
+    pub fn total(&self) -> Duration {
+        self.total
+    }
+
+    /// Add the phases of `other` after these, e.g. those of a target
+    /// post-processed in parallel.
+    pub fn extend(&mut self, other: &GenerationReport) {
+        self.total += other.total;
+        self.phases.extend(other.phases.iter().cloned());
+    }
+
+    /// Time spent in `generator`'s phases.
+    pub fn generator_total(&self, generator: &str) -> Duration {
+        self.phases
+            .iter()
+            .filter(|timing| timing.generator.as_deref() == Some(generator))
+            .map(|timing| timing.duration)
+            .sum()
+    }
+}
+
+impl fmt::Display for GenerationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .phases
+            .iter()
+            .filter_map(|timing| timing.generator.as_ref())
+            .map(String::len)
+            .max()
+            .unwrap_or(0)
+            .max("generator".len());
+        writeln!(
+            f,
+            "{:16}{:width$}  {:>7}  {:>10}",
+            "phase", "generator", "count", "time"
+        )?;
+        for timing in &self.phases {
+            let count = timing.count.map(|c| c.to_string()).unwrap_or_default();
+            writeln!(
+                f,
+                "{:16}{:width$}  {:>7}  {:>10}",
+                timing.phase.as_str(),
+                timing.generator.as_deref().unwrap_or(""),
+                count,
+                format_duration(timing.duration)
+            )?;
+        }
+        write!(
+            f,
+            "{:16}{:width$}  {:>7}  {:>10}",
+            "total",
+            "",
+            "",
+            format_duration(self.total)
+        )
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.1}ms", duration.as_secs_f64() * 1000.0)
+}
+
+fn millis<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_secs_f64() * 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report() {
+        let mut report = GenerationReport::new();
+        let started = Instant::now();
+        report.record(Phase::Parse, None, started, None);
+        report.record(Phase::Generate, Some("typescript"), started, Some(3));
+        assert_eq!(report.phases().len(), 2);
+        assert_eq!(
+            report.total(),
+            report.phases()[0].duration + report.phases()[1].duration
+        );
+        assert_eq!(
+            report.generator_total("typescript"),
+            report.phases()[1].duration
+        );
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["phases"][1]["phase"], "generate");
+        assert_eq!(json["phases"][1]["generator"], "typescript");
+        assert_eq!(json["phases"][1]["count"], 3);
+        assert!(json["total_ms"].is_f64());
+
+        let table = report.to_string();
+        assert!(table.starts_with("phase"));
+        assert!(table.contains("typescript"));
+        assert!(table.lines().last().unwrap().starts_with("total"));
+    }
+}
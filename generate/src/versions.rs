@@ -0,0 +1,213 @@
+//! Generation of several historical versions of a spec in one run.
+//!
+//! Each [`SpecSnapshot`] is generated into its own directory (`v2024_01/`,
+//! `v2024_06/`, ...). Types whose declaration is identical in every snapshot
+//! are generated once into a `shared/` package. Generators that support it
+//! import them from there instead of redeclaring them; see
+//! [`Config::shared_types`](codegen::Config::shared_types).
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use codegen::{Error, GenIr, Result};
+use ir::gen_ir::{StableId, TypeDecl};
+use parser::options::{ParseOptions, Warning};
+use parser::source::{FileSource, SpecSource};
+
+/// Directory the shared package is generated into.
+pub const SHARED_DIR: &str = "shared";
+
+/// One version of a spec, already built into an IR.
+#[derive(Debug, Clone)]
+pub struct SpecSnapshot {
+    /// Namespace/package label, e.g. `v2024_01`.
+    pub version: String,
+    pub ir: GenIr,
+    /// Warnings from parsing the snapshot's spec (repairs, schema violations).
+    pub warnings: Vec<Warning>,
+    /// File the snapshot was loaded from, linted by
+    /// [`generate_versions`](crate::GeneratorRegistry::generate_versions)
+    /// when [`Config::lint_threshold`](codegen::Config::lint_threshold) is set.
+    pub path: Option<PathBuf>,
+}
+
+impl SpecSnapshot {
+    pub fn new(version: impl Into<String>, ir: GenIr) -> Self {
+        Self {
+            version: version.into(),
+            ir,
+            warnings: Vec::new(),
+            path: None,
+        }
+    }
+}
+
+/// Turn a snapshot file stem such as `2024-01` into a label usable as a
+/// namespace or package name (`v2024_01`).
+pub fn version_label(stem: &str) -> String {
+    let mut label: String = stem
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if !label.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        label.insert(0, 'v');
+    }
+    label
+}
+
+/// Load every `.json`, `.yaml` and `.yml` spec in `dir`, ordered by file name.
+pub fn load_snapshots(dir: &Path) -> Result<Vec<SpecSnapshot>> {
+    load_snapshots_with(dir, &ParseOptions::default())
+}
+
+/// [`load_snapshots`], parsing each spec with `options`.
+pub fn load_snapshots_with(dir: &Path, options: &ParseOptions) -> Result<Vec<SpecSnapshot>> {
+    let mut paths: Vec<_> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.is_file()
+                && matches!(
+                    path.extension().and_then(|ext| ext.to_str()),
+                    Some("json" | "yaml" | "yml")
+                )
+        })
+        .collect();
+    paths.sort();
+
+    paths
+        .iter()
+        .map(|path| {
+            // Errors name the file themselves
+            let parsed = FileSource::new(path)
+                .load_with_options(options)
+                .map_err(|e| Error::Custom(format!("Failed to parse {}", e)))?;
+            let stem = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .unwrap_or("version");
+            let mut snapshot = SpecSnapshot::new(version_label(stem), GenIr::from(parsed.spec));
+            snapshot.warnings = parsed.warnings;
+            snapshot.path = Some(path.clone());
+            Ok(snapshot)
+        })
+        .collect()
+}
+
+/// Types declared identically in every snapshot whose dependencies are
+/// shared as well. Empty unless there are at least two snapshots.
+pub fn shared_types(snapshots: &[SpecSnapshot]) -> BTreeSet<StableId> {
+    let [first, rest @ ..] = snapshots else {
+        return BTreeSet::new();
+    };
+    if rest.is_empty() {
+        return BTreeSet::new();
+    }
+
+    let mut shared: BTreeSet<StableId> = first
+        .ir
+        .types
+        .iter()
+        .filter(|(id, decl)| {
+            let shape = declaration_shape(decl);
+            rest.iter().all(|snapshot| {
+                snapshot
+                    .ir
+                    .types
+                    .get(*id)
+                    .is_some_and(|other| declaration_shape(other) == shape)
+            })
+        })
+        .map(|(id, _)| id.clone())
+        .collect();
+
+    // A shared type can't reference a version-specific one
+    let graph = first.ir.type_graph();
+    loop {
+        let dangling: Vec<StableId> = shared
+            .iter()
+            .filter(|id| graph.dependencies(id).any(|dep| !shared.contains(dep)))
+            .cloned()
+            .collect();
+        if dangling.is_empty() {
+            return shared;
+        }
+        for id in dangling {
+            shared.remove(&id);
+        }
+    }
+}
+
+/// Everything about a declaration that ends up in generated code. Origin and
+/// tags are ignored because they change with unrelated edits to the spec.
+fn declaration_shape(decl: &TypeDecl) -> String {
+    serde_json::to_string(&(&decl.name, &decl.docs, &decl.kind)).unwrap_or_default()
+}
+
+/// IR holding only the shared types, used to generate the shared package.
+pub(crate) fn shared_ir(snapshot: &SpecSnapshot, shared: &BTreeSet<StableId>) -> GenIr {
+    let mut ir = snapshot.ir.clone();
+    ir.types.retain(|id, _| shared.contains(id));
+    ir.services.clear();
+    ir.webhooks.clear();
+    ir
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(version: &str, pet_properties: &str) -> SpecSnapshot {
+        let json = format!(
+            r##"{{
+                "openapi": "3.0.0",
+                "info": {{ "title": "Pets", "version": "1.0.0" }},
+                "paths": {{}},
+                "components": {{
+                    "schemas": {{
+                        "Status": {{ "type": "string", "enum": ["active", "inactive"] }},
+                        "Owner": {{
+                            "type": "object",
+                            "properties": {{ "status": {{ "$ref": "#/components/schemas/Status" }} }}
+                        }},
+                        "Pet": {{
+                            "type": "object",
+                            "properties": {pet_properties}
+                        }},
+                        "Listing": {{
+                            "type": "object",
+                            "properties": {{ "pet": {{ "$ref": "#/components/schemas/Pet" }} }}
+                        }}
+                    }}
+                }}
+            }}"##
+        );
+        SpecSnapshot::new(version, GenIr::from(parser::parse(&json).unwrap()))
+    }
+
+    #[test]
+    fn test_version_label() {
+        assert_eq!(version_label("2024-01"), "v2024_01");
+        assert_eq!(version_label("v2"), "v2");
+        assert_eq!(version_label("stable.1"), "stable_1");
+    }
+
+    #[test]
+    fn test_shared_types_exclude_changed_and_dependent_types() {
+        let snapshots = vec![
+            snapshot("v2024_01", r#"{ "name": { "type": "string" } }"#),
+            snapshot(
+                "v2024_06",
+                r#"{ "name": { "type": "string" }, "age": { "type": "integer" } }"#,
+            ),
+        ];
+
+        let shared = shared_types(&snapshots);
+        assert!(shared.contains(&StableId::new("Status")));
+        assert!(shared.contains(&StableId::new("Owner")));
+        // Pet changed, and Listing references Pet
+        assert!(!shared.contains(&StableId::new("Pet")));
+        assert!(!shared.contains(&StableId::new("Listing")));
+
+        assert!(shared_types(&snapshots[..1]).is_empty());
+    }
+}
@@ -0,0 +1,350 @@
+//! Generators compiled to WebAssembly.
+//!
+//! Shared-library plugins ([`GeneratorRegistry::load_plugin`]) run with the
+//! host's privileges and must be built for its platform. A WASM plugin is a
+//! single `.wasm` file that runs sandboxed in [wasmtime]: it can't import
+//! anything, so it has no file system, network or clock, and its memory and
+//! running time are capped. Requires the `wasm-plugins` feature.
+//!
+//! # Guest interface
+//!
+//! Data crosses the boundary as JSON in the guest's memory. A guest
+//! exports:
+//!
+//! - `memory`
+//! - `oas_gen_abi_version() -> i32`, returning [`WASM_ABI_VERSION`]
+//! - `oas_gen_alloc(len: i32) -> i32`, returning `len` writable bytes for
+//!   the host's input
+//! - `oas_gen_language() -> i64`, the language name as UTF-8
+//! - `oas_gen_generate(ptr: i32, len: i32) -> i64`, generating from the
+//!   input at `ptr` and returning the output
+//!
+//! Strings the guest returns are packed into an `i64` as
+//! `(ptr << 32) | len`. The input and output are the JSON messages of
+//! [`codegen::plugin`], shared with shared-library plugins.
+//!
+//! Each generation runs in a fresh instance, so no state leaks between
+//! runs. Calls into the guest that outlast the timeout trap, using
+//! wasmtime's epoch interruption: while a guest runs, a thread bumps the
+//! engine's epoch every [`TICK`](WasmGenerator::TICK) and the store checks
+//! the clock on each bump.
+//!
+//! [`GeneratorRegistry::load_plugin`]: crate::GeneratorRegistry::load_plugin
+//! [wasmtime]: https://wasmtime.dev
+
+use std::path::Path;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use codegen::plugin::{decode_output, encode_input};
+use codegen::{Config, Error, GenIr, Generator, Result, VirtualFS};
+use wasmtime::{
+    Engine, Instance, Memory, Module, ResourceLimiter, Store, StoreLimits, StoreLimitsBuilder,
+    UpdateDeadline,
+};
+
+/// Version of the guest interface. Plugins built for another version are
+/// rejected when loaded.
+pub const WASM_ABI_VERSION: i32 = 1;
+
+/// A generator running in a WASM sandbox.
+pub struct WasmGenerator {
+    engine: Engine,
+    module: Module,
+    language: String,
+    max_memory: usize,
+    timeout: Duration,
+}
+
+impl WasmGenerator {
+    /// Memory a plugin may use, unless set with
+    /// [`with_max_memory`](Self::with_max_memory).
+    pub const DEFAULT_MAX_MEMORY: usize = 512 * 1024 * 1024;
+
+    /// Time a call into the plugin may take, unless set with
+    /// [`with_timeout`](Self::with_timeout).
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+    /// How often a running plugin checks whether it timed out.
+    pub const TICK: Duration = Duration::from_millis(10);
+
+    /// Load the plugin at `path`, a `.wasm` (or `.wat`) file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let engine = engine()?;
+        let module =
+            Module::from_file(&engine, path).map_err(|e| plugin_error(path.display(), e))?;
+        Self::from_module(engine, module).map_err(|e| match e {
+            Error::PluginLoadError(message) => {
+                Error::PluginLoadError(format!("{}: {}", path.display(), message))
+            }
+            e => e,
+        })
+    }
+
+    /// Load a plugin from the bytes of a `.wasm` (or `.wat`) file.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let engine = engine()?;
+        let module = Module::new(&engine, bytes).map_err(|e| plugin_error("module", e))?;
+        Self::from_module(engine, module)
+    }
+
+    fn from_module(engine: Engine, module: Module) -> Result<Self> {
+        let imports: Vec<String> = module
+            .imports()
+            .map(|import| format!("{}::{}", import.module(), import.name()))
+            .collect();
+        if !imports.is_empty() {
+            return Err(Error::PluginLoadError(format!(
+                "plugins can't import anything, but this one imports {}",
+                imports.join(", ")
+            )));
+        }
+
+        let mut generator = Self {
+            engine,
+            module,
+            language: String::new(),
+            max_memory: Self::DEFAULT_MAX_MEMORY,
+            timeout: Self::DEFAULT_TIMEOUT,
+        };
+        let mut guest = generator.instantiate()?;
+        let version = guest.call::<(), i32>("oas_gen_abi_version", ())?;
+        if version != WASM_ABI_VERSION {
+            return Err(Error::PluginLoadError(format!(
+                "plugin uses ABI version {}, expected {}",
+                version, WASM_ABI_VERSION
+            )));
+        }
+        let packed = guest.call::<(), i64>("oas_gen_language", ())?;
+        let language = guest.read(packed)?;
+        generator.language = String::from_utf8(language)
+            .map_err(|_| Error::PluginLoadError("plugin language name is not UTF-8".to_string()))?;
+        Ok(generator)
+    }
+
+    /// Cap the memory the plugin may use, in bytes.
+    pub fn with_max_memory(mut self, bytes: usize) -> Self {
+        self.max_memory = bytes;
+        self
+    }
+
+    /// Cap the time each call into the plugin may take.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    fn instantiate(&self) -> Result<Guest> {
+        let limits = StoreLimitsBuilder::new()
+            .memory_size(self.max_memory)
+            .build();
+        let mut store = Store::new(&self.engine, limits);
+        store.limiter(|limits| limits as &mut dyn ResourceLimiter);
+        // Every epoch bump checks the clock; other guests' tickers only
+        // make the checks more frequent
+        let timeout = self.timeout;
+        let deadline = Instant::now() + timeout;
+        store.set_epoch_deadline(1);
+        store.epoch_deadline_callback(move |_| {
+            if Instant::now() >= deadline {
+                Err(wasmtime::Error::msg(format!(
+                    "plugin ran longer than {:?}",
+                    timeout
+                )))
+            } else {
+                Ok(UpdateDeadline::Continue(1))
+            }
+        });
+        let ticker = Ticker::start(self.engine.clone());
+        let instance = Instance::new(&mut store, &self.module, &[])
+            .map_err(|e| plugin_error("instantiation", e))?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or_else(|| Error::PluginLoadError("plugin exports no `memory`".to_string()))?;
+        Ok(Guest {
+            store,
+            instance,
+            memory,
+            _ticker: ticker,
+        })
+    }
+}
+
+impl Generator for WasmGenerator {
+    fn generate(&self, ir: &GenIr, config: &Config) -> Result<VirtualFS> {
+        let input = encode_input(ir, config)?;
+        let len = i32::try_from(input.len())
+            .map_err(|_| Error::Custom("plugin input exceeds 2 GiB".to_string()))?;
+
+        let mut guest = self.instantiate()?;
+        let ptr = guest.call::<i32, i32>("oas_gen_alloc", len)?;
+        guest
+            .memory
+            .write(&mut guest.store, ptr as u32 as usize, &input)
+            .map_err(|e| {
+                Error::Custom(format!(
+                    "{} plugin allocated out of bounds: {}",
+                    self.language, e
+                ))
+            })?;
+        let packed = guest.call::<(i32, i32), i64>("oas_gen_generate", (ptr, len))?;
+        let output = guest.read(packed)?;
+
+        decode_output(&self.language, &output)
+    }
+
+    fn language(&self) -> &str {
+        &self.language
+    }
+}
+
+/// An instance of a plugin, with its store.
+struct Guest {
+    store: Store<StoreLimits>,
+    instance: Instance,
+    memory: Memory,
+    _ticker: Ticker,
+}
+
+impl Guest {
+    fn call<P, R>(&mut self, name: &str, params: P) -> Result<R>
+    where
+        P: wasmtime::WasmParams,
+        R: wasmtime::WasmResults,
+    {
+        let func = self
+            .instance
+            .get_typed_func::<P, R>(&mut self.store, name)
+            .map_err(|e| plugin_error(name, e))?;
+        func.call(&mut self.store, params)
+            .map_err(|e| Error::Custom(format!("plugin trapped in {}: {:#}", name, e)))
+    }
+
+    /// The bytes of a packed `(ptr << 32) | len` string.
+    fn read(&mut self, packed: i64) -> Result<Vec<u8>> {
+        let packed = packed as u64;
+        let (ptr, len) = ((packed >> 32) as usize, (packed & 0xffff_ffff) as usize);
+        let mut bytes = vec![0; len];
+        self.memory
+            .read(&self.store, ptr, &mut bytes)
+            .map_err(|e| Error::Custom(format!("plugin returned out of bounds: {}", e)))?;
+        Ok(bytes)
+    }
+}
+
+/// Bumps an engine's epoch every [`WasmGenerator::TICK`] until dropped.
+struct Ticker {
+    stop: mpsc::Sender<()>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Ticker {
+    fn start(engine: Engine) -> Self {
+        let (stop, stopped) = mpsc::channel();
+        let thread = thread::spawn(move || {
+            while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(WasmGenerator::TICK) {
+                engine.increment_epoch();
+            }
+        });
+        Self {
+            stop,
+            thread: Some(thread),
+        }
+    }
+}
+
+impl Drop for Ticker {
+    fn drop(&mut self) {
+        let _ = self.stop.send(());
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// An engine whose stores can be interrupted when a plugin times out.
+fn engine() -> Result<Engine> {
+    let mut config = wasmtime::Config::new();
+    config.epoch_interruption(true);
+    Engine::new(&config).map_err(|e| plugin_error("engine", e))
+}
+
+fn plugin_error(context: impl std::fmt::Display, e: impl std::fmt::Display) -> Error {
+    Error::PluginLoadError(format!("{}: {:#}", context, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A plugin named `echo` that returns `output` whatever the input.
+    fn guest(abi_version: i32, output: &str) -> String {
+        format!(
+            r#"(module
+                (memory (export "memory") 1)
+                (data (i32.const 0) "echo")
+                (data (i32.const 16) "{}")
+                (func (export "oas_gen_abi_version") (result i32) (i32.const {}))
+                (func (export "oas_gen_alloc") (param i32) (result i32)
+                    (drop (memory.grow (i32.const 16)))
+                    (i32.const 65536))
+                (func (export "oas_gen_language") (result i64) (i64.const 4))
+                (func (export "oas_gen_generate") (param i32 i32) (result i64)
+                    (i64.const {})))"#,
+            output.replace('"', "\\\""),
+            abi_version,
+            (16i64 << 32) | output.len() as i64,
+        )
+    }
+
+    #[test]
+    fn test_wasm_generator() {
+        let ir = GenIr::from(
+            parser::parse(r#"{"openapi":"3.0.0","info":{"title":"T","version":"1"},"paths":{}}"#)
+                .unwrap(),
+        );
+
+        let output = r#"{"files":[{"path":"hello.txt","content":"hi","mode":384}]}"#;
+        let generator =
+            WasmGenerator::from_bytes(guest(WASM_ABI_VERSION, output).as_bytes()).unwrap();
+        assert_eq!(generator.language(), "echo");
+        let vfs = generator.generate(&ir, &Config::default()).unwrap();
+        assert_eq!(vfs.get_file(Path::new("hello.txt")), Some("hi".as_bytes()));
+        assert_eq!(vfs.mode(Path::new("hello.txt")), 0o600);
+
+        let failing = WasmGenerator::from_bytes(
+            guest(WASM_ABI_VERSION, r#"{"error":"no services"}"#).as_bytes(),
+        )
+        .unwrap();
+        let err = failing.generate(&ir, &Config::default()).unwrap_err();
+        assert_eq!(err.to_string(), "echo plugin: no services");
+
+        let err = WasmGenerator::from_bytes(guest(WASM_ABI_VERSION + 1, output).as_bytes())
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("ABI version 2, expected 1"));
+
+        let looping = r#"(module
+            (memory (export "memory") 1)
+            (data (i32.const 0) "echo")
+            (func (export "oas_gen_abi_version") (result i32) (i32.const 1))
+            (func (export "oas_gen_alloc") (param i32) (result i32) (i32.const 1024))
+            (func (export "oas_gen_language") (result i64) (i64.const 4))
+            (func (export "oas_gen_generate") (param i32 i32) (result i64)
+                (loop (br 0))
+                (i64.const 0)))"#;
+        let err = WasmGenerator::from_bytes(looping.as_bytes())
+            .unwrap()
+            .with_timeout(Duration::from_millis(100))
+            .generate(&ir, &Config::default())
+            .unwrap_err();
+        assert!(err.to_string().contains("ran longer than 100ms"));
+
+        let importing = r#"(module (import "wasi_snapshot_preview1" "fd_write" (func)))"#;
+        let err = WasmGenerator::from_bytes(importing.as_bytes())
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("wasi_snapshot_preview1::fd_write"));
+    }
+}
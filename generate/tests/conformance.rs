@@ -0,0 +1,269 @@
+//! Conformance tests against real-world specs.
+//!
+//! Every spec in the corpus must parse, convert to GenIR and generate with
+//! every registered generator without panicking. Its measurements are
+//! compared with the ones recorded in `tests/conformance.lock.json`:
+//!
+//! - lint warnings must match the recorded count exactly, so a change that
+//!   removes warnings also lowers the budget;
+//! - each generator's output may grow at most [`SIZE_SLACK_PERCENT`] over
+//!   its recorded size;
+//! - a downloaded spec must have the recorded SHA-256, so the remote corpus
+//!   can't change under the tests even when the upstream tag moves.
+//!
+//! Run with `OAS_GEN_UPDATE_SNAPSHOTS=1` to record the measurements instead,
+//! then review the lock file change in git. Specs without a recorded entry
+//! fail until they are recorded.
+//!
+//! Built only with the `conformance` feature:
+//!
+//! ```bash
+//! # Specs in examples/
+//! cargo test -p generate --features conformance --test conformance
+//!
+//! # Also the pinned remote corpus (downloads once into the target dir)
+//! cargo test -p generate --features conformance --test conformance -- --ignored
+//! ```
+
+#![cfg(feature = "conformance")]
+
+use std::collections::BTreeMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use codegen::testing::UPDATE_ENV;
+use codegen::{Config, GenIr};
+use generate::{GeneratorRegistry, SpecCache};
+use parser::fetch::{HttpFetcher, RemoteRefs};
+use serde::{Deserialize, Serialize};
+
+/// How much a generator's output may grow over its recorded size.
+const SIZE_SLACK_PERCENT: usize = 10;
+
+struct Case {
+    /// Key in the lock file
+    name: &'static str,
+    /// Path under `examples/`, or a URL whose content is pinned by the
+    /// SHA-256 in the lock file.
+    location: &'static str,
+}
+
+/// What one spec measured, as recorded in the lock file.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+struct Measurement {
+    /// Hex SHA-256 of the spec
+    sha256: String,
+    /// Lint warnings
+    warnings: usize,
+    /// Bytes generated by each generator
+    bytes: BTreeMap<String, usize>,
+}
+
+type Lock = BTreeMap<String, Measurement>;
+
+/// Serializes reading and recording the lock file across tests.
+static LOCK: Mutex<()> = Mutex::new(());
+
+fn lock_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/conformance.lock.json")
+}
+
+fn update_mode() -> bool {
+    std::env::var_os(UPDATE_ENV).is_some_and(|value| !value.is_empty() && value != "0")
+}
+
+const LOCAL_CORPUS: &[Case] = &[
+    Case {
+        name: "petstore",
+        location: "petstore.json",
+    },
+    Case {
+        name: "edge-cases",
+        location: "edge-cases.yaml",
+    },
+    Case {
+        name: "openrouter",
+        location: "openrouter.yml",
+    },
+    Case {
+        name: "unkey",
+        location: "unkey-fixed.yaml",
+    },
+    Case {
+        name: "stripe",
+        location: "stripe.json",
+    },
+];
+
+const REMOTE_CORPUS: &[Case] = &[
+    Case {
+        name: "oai-petstore",
+        location: "https://raw.githubusercontent.com/OAI/OpenAPI-Specification/3.1.0/examples/v3.0/petstore.yaml",
+    },
+    Case {
+        name: "github",
+        location: "https://raw.githubusercontent.com/github/rest-api-description/v2.1.0/descriptions/api.github.com/api.github.com.yaml",
+    },
+    Case {
+        name: "stripe-openapi",
+        location: "https://raw.githubusercontent.com/stripe/openapi/v1000/openapi/spec3.json",
+    },
+];
+
+/// Run the pipeline on one spec, returning what it measured and a
+/// description of each failure.
+fn measure(content: &str) -> (Measurement, Vec<String>) {
+    let mut measurement = Measurement {
+        sha256: SpecCache::key(content),
+        ..Measurement::default()
+    };
+    let mut failures = Vec::new();
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let spec = match parser::parse(content) {
+            Ok(spec) => spec,
+            Err(err) => {
+                failures.push(format!("parse failed: {}", err));
+                return;
+            }
+        };
+        let ir = GenIr::from(spec);
+        if ir.operation_count() == 0 {
+            failures.push("no operations in GenIR".to_string());
+        }
+
+        let registry = GeneratorRegistry::with_defaults();
+        for language in registry.languages() {
+            match registry.generate(language, &ir, &Config::default()) {
+                Ok(vfs) if vfs.is_empty() => {
+                    failures.push(format!("{} generated no files", language))
+                }
+                Ok(vfs) => {
+                    let bytes = vfs.files().map(|(_, content)| content.len()).sum();
+                    measurement.bytes.insert(language.to_string(), bytes);
+                }
+                Err(err) => failures.push(format!("{} failed: {}", language, err)),
+            }
+        }
+
+        match lint::lint_all(content) {
+            Ok(validation) => measurement.warnings = validation.warnings().count(),
+            Err(err) => failures.push(format!("lint failed: {}", err)),
+        }
+    }));
+    if let Err(payload) = result {
+        let message = payload
+            .downcast_ref::<String>()
+            .map(String::as_str)
+            .or_else(|| payload.downcast_ref::<&str>().copied())
+            .unwrap_or("unknown panic");
+        failures.push(format!("panicked: {}", message));
+    }
+
+    (measurement, failures)
+}
+
+/// Describe how `measured` breaks the budgets `recorded` sets.
+fn compare(measured: &Measurement, recorded: &Measurement) -> Vec<String> {
+    let mut failures = Vec::new();
+    if measured.sha256 != recorded.sha256 {
+        failures.push(format!(
+            "content changed: SHA-256 is {}, pinned {}",
+            measured.sha256, recorded.sha256
+        ));
+    }
+    if measured.warnings > recorded.warnings {
+        failures.push(format!(
+            "{} lint warnings, budget is {}",
+            measured.warnings, recorded.warnings
+        ));
+    } else if measured.warnings < recorded.warnings {
+        failures.push(format!(
+            "lint warnings dropped from {} to {}; record the lower budget",
+            recorded.warnings, measured.warnings
+        ));
+    }
+    for (language, &bytes) in &measured.bytes {
+        match recorded.bytes.get(language) {
+            Some(&budget) if bytes * 100 > budget * (100 + SIZE_SLACK_PERCENT) => {
+                failures.push(format!(
+                    "{} generated {} bytes, more than {}% over the recorded {}",
+                    language, bytes, SIZE_SLACK_PERCENT, budget
+                ))
+            }
+            Some(_) => {}
+            None => failures.push(format!("no recorded size for {}", language)),
+        }
+    }
+    failures
+}
+
+/// Measure every spec `load` returns and check it against the lock file,
+/// or record it in update mode. Returns the failures.
+fn run(corpus: &[Case], load: impl Fn(&Case) -> Result<String, String>) -> Vec<String> {
+    let _guard = LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let mut lock: Lock = match std::fs::read_to_string(lock_path()) {
+        Ok(content) => serde_json::from_str(&content).expect("conformance.lock.json is valid"),
+        Err(_) => Lock::new(),
+    };
+
+    let mut failures = Vec::new();
+    for case in corpus {
+        let content = match load(case) {
+            Ok(content) => content,
+            Err(err) => {
+                failures.push(format!("{}: {}", case.name, err));
+                continue;
+            }
+        };
+        let (measured, errors) = measure(&content);
+        let errors = if !errors.is_empty() {
+            errors
+        } else if update_mode() {
+            lock.insert(case.name.to_string(), measured);
+            Vec::new()
+        } else {
+            match lock.get(case.name) {
+                Some(recorded) => compare(&measured, recorded),
+                None => vec![format!("not recorded; run with {}=1", UPDATE_ENV)],
+            }
+        };
+        failures.extend(errors.into_iter().map(|e| format!("{}: {}", case.name, e)));
+    }
+
+    if update_mode() {
+        let json = serde_json::to_string_pretty(&lock).expect("the lock serializes");
+        std::fs::write(lock_path(), json + "\n").expect("conformance.lock.json is writable");
+    }
+    failures
+}
+
+fn assert_conforms(failures: Vec<String>) {
+    assert!(
+        failures.is_empty(),
+        "conformance failures:\n{}",
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn test_local_corpus_conforms() {
+    let examples = Path::new(env!("CARGO_MANIFEST_DIR")).join("../examples");
+    assert_conforms(run(LOCAL_CORPUS, |case| {
+        std::fs::read_to_string(examples.join(case.location)).map_err(|err| err.to_string())
+    }));
+}
+
+#[test]
+#[ignore = "downloads the pinned corpus"]
+fn test_remote_corpus_conforms() {
+    let remote = RemoteRefs::new(HttpFetcher::new())
+        .allow_any()
+        .with_cache_dir(Path::new(env!("CARGO_TARGET_TMPDIR")).join("conformance-corpus"));
+    assert_conforms(run(REMOTE_CORPUS, |case| {
+        remote
+            .load(case.location)
+            .map_err(|err| format!("download failed: {}", err))
+    }));
+}
@@ -1,6 +1,7 @@
 //! Integration tests for the code generation pipeline.
 
-use codegen::{Config, ServiceStyle, VirtualFS};
+use codegen::passes::PassConfig;
+use codegen::{CallbackSink, Config, Error, FileHeader, LintThreshold, ServiceStyle, VirtualFS};
 use generate::GeneratorRegistry;
 use ir::gen_ir::*;
 use std::collections::{BTreeMap, BTreeSet};
@@ -28,7 +29,11 @@ fn create_test_ir() -> GenIr {
             since: None,
             examples: vec![],
             external_urls: vec![],
+            sunset: None,
+            replacement: None,
+            translations: BTreeMap::new(),
         },
+        spec_hash: "0123456789abcdef".to_string(),
     };
 
     let mut types = BTreeMap::new();
@@ -52,6 +57,9 @@ fn create_test_ir() -> GenIr {
             since: None,
             examples: vec![],
             external_urls: vec![],
+            sunset: None,
+            replacement: None,
+            translations: BTreeMap::new(),
         },
         kind: TypeKind::Struct {
             fields: vec![
@@ -80,6 +88,10 @@ fn create_test_ir() -> GenIr {
                     deprecated: false,
                     const_value: None,
                     wire_name: "id".to_string(),
+                    sensitive: false,
+                    time_format: None,
+                    coerce_number: false,
+                    format: None,
                 },
                 Field {
                     name: CanonicalName {
@@ -106,6 +118,10 @@ fn create_test_ir() -> GenIr {
                     deprecated: false,
                     const_value: None,
                     wire_name: "name".to_string(),
+                    sensitive: false,
+                    time_format: None,
+                    coerce_number: false,
+                    format: None,
                 },
                 Field {
                     name: CanonicalName {
@@ -132,6 +148,10 @@ fn create_test_ir() -> GenIr {
                     deprecated: false,
                     const_value: None,
                     wire_name: "email".to_string(),
+                    sensitive: false,
+                    time_format: None,
+                    coerce_number: false,
+                    format: None,
                 },
             ],
             additional: Additional::Forbidden,
@@ -248,7 +268,13 @@ fn create_test_ir() -> GenIr {
             pagination: None,
             idempotent: true,
             retryable_statuses: BTreeSet::new(),
+            owners: vec![],
+            feature_flag: None,
+            cache: None,
+            renamed_from: vec![],
+            tags: vec![],
         }],
+        owners: vec![],
     };
 
     GenIr {
@@ -258,9 +284,25 @@ fn create_test_ir() -> GenIr {
         auth_schemes: vec![],
         errors: vec![],
         server_sets: vec![],
+        webhooks: vec![],
+        fallbacks: Vec::new(),
     }
 }
 
+/// Generate `language` output for `ir` with the default registry.
+fn generate(language: &str, ir: &GenIr, config: &Config) -> VirtualFS {
+    GeneratorRegistry::with_defaults()
+        .generate(language, ir, config)
+        .unwrap_or_else(|e| panic!("{} generation failed: {}", language, e))
+}
+
+/// Content of the generated file at `path`.
+fn file<'a>(vfs: &'a VirtualFS, path: &str) -> &'a str {
+    vfs.get_file_str(Path::new(path))
+        .unwrap_or_else(|| panic!("{} was not generated", path))
+        .unwrap()
+}
+
 #[test]
 fn test_virtual_fs_operations() {
     let mut vfs = VirtualFS::new();
@@ -302,15 +344,7 @@ fn test_registry_default_generators() {
 
 #[test]
 fn test_typescript_generation() {
-    let test_ir = create_test_ir();
-    let config = Config::default();
-    let mut registry = GeneratorRegistry::with_defaults();
-    registry.register(Box::new(TypeScriptGenerator::new()));
-
-    let result = registry.generate("typescript", &test_ir, &config);
-    assert!(result.is_ok(), "Generation failed: {:?}", result.err());
-
-    let vfs = result.unwrap();
+    let vfs = generate("typescript", &create_test_ir(), &Config::default());
 
     // Check that expected files are generated
     assert!(vfs.contains(Path::new("package.json")));
@@ -319,18 +353,12 @@ fn test_typescript_generation() {
     assert!(vfs.contains(Path::new("src/index.ts")));
 
     // Check package.json content
-    let package_json = vfs
-        .get_file_str(Path::new("package.json"))
-        .unwrap()
-        .unwrap();
+    let package_json = file(&vfs, "package.json");
     assert!(package_json.contains("test-api"));
     assert!(package_json.contains("1.0.0"));
 
     // Check types are generated
-    let types_content = vfs
-        .get_file_str(Path::new("src/types/index.ts"))
-        .unwrap()
-        .unwrap();
+    let types_content = file(&vfs, "src/types/index.ts");
     assert!(types_content.contains("interface User"));
     assert!(types_content.contains("enum Status"));
     assert!(types_content.contains("id: "));
@@ -347,18 +375,12 @@ fn test_typescript_per_service_style() {
         service_style: ServiceStyle::PerService,
         ..Default::default()
     };
-    let mut registry = GeneratorRegistry::with_defaults();
-    registry.register(Box::new(TypeScriptGenerator::new()));
-
-    let vfs = registry.generate("typescript", &test_ir, &config).unwrap();
+    let vfs = generate("typescript", &test_ir, &config);
 
     // Check that service file is generated per service
     assert!(vfs.contains(Path::new("src/services/user_service.ts")));
 
-    let service_content = vfs
-        .get_file_str(Path::new("src/services/user_service.ts"))
-        .unwrap()
-        .unwrap();
+    let service_content = file(&vfs, "src/services/user_service.ts");
     assert!(service_content.contains("class UserServiceService"));
     assert!(service_content.contains("async getUser"));
 }
@@ -370,20 +392,998 @@ fn test_typescript_single_client_style() {
         service_style: ServiceStyle::SingleClient,
         ..Default::default()
     };
+    let vfs = generate("typescript", &test_ir, &config);
+
+    // Check that single client file is generated
+    assert!(vfs.contains(Path::new("src/services/client.ts")));
+
+    let client_content = file(&vfs, "src/services/client.ts");
+    assert!(client_content.contains("class TestApiSDK"));
+    assert!(client_content.contains("get userService"));
+}
+
+#[test]
+fn test_typescript_feature_flags() {
+    let mut test_ir = create_test_ir();
+    test_ir.services[0].operations[0].feature_flag = Some("newUsers".to_string());
+    let vfs = generate("typescript", &test_ir, &Config::default());
+
+    let service_content = file(&vfs, "src/services/user_service.ts");
+    assert!(service_content.contains("private featureFlags: FeatureFlagProvider"));
+    assert!(service_content.contains("if (!this.featureFlags('newUsers'))"));
+    assert!(service_content.contains("throw new FeatureDisabledError('newUsers', 'getUser')"));
+
+    let client_content = file(&vfs, "src/services/client.ts");
+    assert!(client_content.contains("export type FeatureFlagProvider"));
+    assert!(client_content.contains("private featureFlags: FeatureFlagProvider = () => false"));
+}
+
+#[test]
+fn test_renamed_operation_alias() {
+    let mut test_ir = create_test_ir();
+    test_ir.services[0].operations[0].renamed_from = vec![
+        CanonicalName::from_string("fetchUser"),
+        CanonicalName::from_string("getUser"),
+        // Spelled differently, same method name
+        CanonicalName::from_string("fetch_user"),
+    ];
+    // Another operation claiming the same former name doesn't get it
+    let mut other = test_ir.services[0].operations[0].clone();
+    other.id = StableId::new("loadUser");
+    other.name = CanonicalName::from_string("loadUser");
+    other.renamed_from = vec![CanonicalName::from_string("fetchUser")];
+    test_ir.services[0].operations.push(other);
+    let vfs = generate("typescript", &test_ir, &Config::default());
+    let service_content = file(&vfs, "src/services/user_service.ts");
+    assert!(service_content.contains("@deprecated Renamed to {@link getUser}"));
+    assert!(service_content.contains(
+        "fetchUser(...args: Parameters<UserServiceService['getUser']>): ReturnType<UserServiceService['getUser']> {"
+    ));
+    assert_eq!(service_content.matches("  fetchUser(...args:").count(), 1);
+    assert!(service_content.contains("return this.getUser(...args);"));
+    // The current name is not aliased to itself
+    assert!(!service_content.contains("  getUser(...args:"));
+}
+
+#[test]
+fn test_operation_filters() {
+    let mut test_ir = create_test_ir();
+    let mut admin = test_ir.services[0].clone();
+    admin.id = StableId::new("Admin");
+    admin.name = CanonicalName::from_string("Admin");
+    admin.operations[0].name = CanonicalName::from_string("AdminGetUser");
+    let mut delete = test_ir.services[0].operations[0].clone();
+    delete.name = CanonicalName::from_string("DeleteUser");
+    delete.http.method = HttpMethod::Delete;
+    test_ir.services[0].operations.push(delete);
+    test_ir.services.push(admin);
+    let registry = GeneratorRegistry::with_defaults();
+
+    let config = Config {
+        exclude_tags: vec!["Admin".to_string()],
+        exclude_operations: vec!["Delete*".to_string()],
+        ..Config::default()
+    };
+    let vfs = generate("typescript", &test_ir, &config);
+    assert!(!vfs.contains(Path::new("src/services/admin.ts")));
+    let service_content = file(&vfs, "src/services/user_service.ts");
+    assert!(service_content.contains("getUser("));
+    assert!(!service_content.contains("deleteUser("));
+
+    let config = Config {
+        include_tags: vec!["Admin".to_string()],
+        ..Config::default()
+    };
+    let vfs = generate("typescript", &test_ir, &config);
+    assert!(vfs.contains(Path::new("src/services/admin.ts")));
+    assert!(!vfs.contains(Path::new("src/services/user_service.ts")));
+
+    let config = Config {
+        exclude_tags: vec!["Admni".to_string()],
+        ..Config::default()
+    };
+    let err = registry
+        .generate("typescript", &test_ir, &config)
+        .unwrap_err();
+    assert!(err.to_string().contains("unknown tag `Admni`"));
+}
+
+#[test]
+fn test_strict_rejects_fallbacks() {
+    let mut test_ir = create_test_ir();
+    let registry = GeneratorRegistry::with_defaults();
+    let config = Config {
+        strict: true,
+        ..Default::default()
+    };
+    assert!(registry.generate("typescript", &test_ir, &config).is_ok());
+
+    test_ir.fallbacks = vec![
+        Fallback {
+            pointer: "#/components/schemas/User/properties/meta".to_string(),
+            reason: FallbackReason::Untyped,
+        },
+        Fallback {
+            pointer: "#/components/schemas/User/properties/owner".to_string(),
+            reason: FallbackReason::UnresolvedRef,
+        },
+    ];
+    assert!(
+        registry
+            .generate("typescript", &test_ir, &Config::default())
+            .is_ok()
+    );
+
+    let message = registry
+        .generate("typescript", &test_ir, &config)
+        .unwrap_err()
+        .to_string();
+    assert!(message.starts_with("2 schema(s) would be generated as untyped values:"));
+    assert!(message.contains(
+        "#/components/schemas/User/properties/owner: `$ref` does not point at a declared schema."
+    ));
+}
+
+#[test]
+fn test_lint_threshold() {
+    // No operationId (warning) and a malformed status code (error)
+    let spec = r#"
+openapi: 3.1.0
+info: { title: Pets, version: "1" }
+paths:
+  /pets:
+    get:
+      responses:
+        "20x":
+          description: OK
+"#;
+    let registry = GeneratorRegistry::with_defaults();
+    assert!(generate::lint_spec(spec, None, &Config::default()).is_ok());
+
+    let config = Config {
+        lint_threshold: Some(LintThreshold::Error),
+        ..Default::default()
+    };
+    let Err(Error::LintFailed(findings)) = registry.generate_spec("typescript", spec, &config)
+    else {
+        panic!("expected lint failure");
+    };
+    assert_eq!(findings.len(), 1);
+    assert!(findings[0].contains("error [response-status-code-valid]"));
+
+    let config = Config {
+        lint_threshold: Some(LintThreshold::Warning),
+        ..Default::default()
+    };
+    let message = registry
+        .generate_spec("typescript", spec, &config)
+        .unwrap_err()
+        .to_string();
+    assert!(message.contains("lint finding(s) block generation:"));
+    assert!(message.contains("warning [operation-id-required]"));
+
+    // An IR can't be linted, so the threshold isn't silently skipped
+    let ir = GenIr::from(parser::parse(spec).unwrap());
+    let Err(Error::ConfigError(_)) = registry.generate("typescript", &ir, &config) else {
+        panic!("expected the threshold to be refused");
+    };
+    assert!(
+        registry
+            .generate("typescript", &ir, &generate::linted(&config))
+            .is_ok()
+    );
+
+    // The nearest oas-lint.toml configures the rules; findings name the
+    // file and point into it as written
+    let dir = std::env::temp_dir().join(format!("oas-gen-lint-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("specs")).unwrap();
+    let path = dir.join("specs/openapi.yaml");
+    std::fs::write(&path, spec).unwrap();
+    let Err(Error::LintFailed(findings)) = generate::lint_spec(spec, Some(&path), &config) else {
+        panic!("expected lint failure");
+    };
+    assert!(
+        findings
+            .iter()
+            .any(|f| f.starts_with(&format!("{}:8:", path.display())))
+    );
+    std::fs::write(
+        dir.join("oas-lint.toml"),
+        "[rules]\noperation-id-required = \"off\"\nresponse-status-code-valid = \"off\"\n",
+    )
+    .unwrap();
+    assert!(generate::lint_spec(spec, Some(&path), &config).is_ok());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_nullability_report() {
+    let mut test_ir = create_test_ir();
+    let user = test_ir.types.get_mut(&StableId::new("User")).unwrap();
+    user.origin = Some("#/components/schemas/User".to_string());
+    if let TypeKind::Struct { fields, .. } = &mut user.kind {
+        fields[1].ty.nullable = true;
+    }
+    let vfs = generate("typescript", &test_ir, &Config::default());
+    assert!(!vfs.contains(Path::new(Config::NULLABILITY_REPORT_PATH)));
+
+    let mut config = Config::default();
+    config
+        .lang_options
+        .insert(Config::NULLABILITY_REPORT.to_string(), true.into());
+    let vfs = generate("typescript", &test_ir, &config);
+    let report = file(&vfs, Config::NULLABILITY_REPORT_PATH);
+    assert!(report.contains("1 field(s)"));
+    assert!(report.contains(
+        "| `User.name` | `#/components/schemas/User/properties/name` | Required and nullable"
+    ));
+}
+
+#[test]
+fn test_success_union() {
+    let mut test_ir = create_test_ir();
+    let operation = &mut test_ir.services[0].operations[0];
+    operation.success = Some(Payload {
+        status: StatusSpec::Code(200),
+        content_type: Some("application/json".to_string()),
+        ty: Some(TypeRef {
+            target: StableId::new("User"),
+            optional: false,
+            nullable: false,
+            by_ref: false,
+            modifiers: vec![],
+        }),
+        headers: vec![],
+        docs: Docs::default(),
+    });
+    operation.alt_success = vec![Payload {
+        status: StatusSpec::Code(204),
+        content_type: None,
+        ty: None,
+        headers: vec![],
+        docs: Docs::default(),
+    }];
+    let vfs = generate("typescript", &test_ir, &Config::default());
+    let service_content = file(&vfs, "src/services/user_service.ts");
+    assert!(
+        service_content
+            .contains("Promise<{ status: 200; data: User } | { status: 204; data: undefined }>")
+    );
+    assert!(service_content.contains(
+        "const data = [204].includes(response.status) ? undefined : await response.json();"
+    ));
+
+    let vfs = generate("rust-axum", &test_ir, &Config::default());
+    let module = file(&vfs, "src/services/user_service.rs");
+    assert!(module.contains("pub enum GetUserResponse {"));
+    assert!(module.contains("Ok(crate::types::User),"));
+    assert!(module.contains("NoContent,"));
+    assert!(module.contains("pub type GetUserResult = Result<GetUserResponse, GetUserError>;"));
+
+    // Errors name the operation they came from
+    test_ir.services[0].operations[0].alt_success[0].status = StatusSpec::Code(0);
+    let err = GeneratorRegistry::with_defaults()
+        .generate("rust-axum", &test_ir, &Config::default())
+        .unwrap_err();
+    let diagnostic = err.diagnostic().unwrap();
+    assert!(matches!(
+        &diagnostic.subject,
+        Some(codegen::diagnostic::Subject::Operation { .. })
+    ));
+    assert_eq!(diagnostic.phase, Some(codegen::diagnostic::Phase::Generate));
+}
+
+#[test]
+fn test_typescript_result_cache() {
+    let mut test_ir = create_test_ir();
+    let operation = &mut test_ir.services[0].operations[0];
+    operation.success = Some(Payload {
+        status: StatusSpec::Code(200),
+        content_type: Some("application/json".to_string()),
+        ty: Some(TypeRef {
+            target: StableId::new("User"),
+            optional: false,
+            nullable: false,
+            by_ref: false,
+            modifiers: vec![],
+        }),
+        headers: vec![],
+        docs: Docs::default(),
+    });
+    operation.cache = Some(CacheHint { max_age: Some(30) });
+    let vfs = generate("typescript", &test_ir, &Config::default());
+    // Callers get copies, never the stored result
+    let cache = file(&vfs, "src/services/cache.ts");
+    assert!(cache.contains("return structuredClone(entry.value) as T;"));
+    assert!(cache.contains("value: structuredClone(value)"));
+
+    let service_content = file(&vfs, "src/services/user_service.ts");
+    assert!(service_content.contains("private cache?: ResponseCache"));
+    assert!(service_content.contains("const cacheKey = `UserService.getUser ${path}?`;"));
+    assert!(service_content.contains("this.cache?.set(cacheKey, result, 30);"));
+    assert!(service_content.contains("invalidateGetUser(): void"));
+    assert!(service_content.contains("this.cache?.invalidate('UserService.');"));
+
+    let client_content = file(&vfs, "src/services/client.ts");
+    assert!(client_content.contains("export type { CacheOptions } from './cache';"));
+    assert!(client_content.contains("cacheOptions?: CacheOptions"));
+
+    let mut config = Config::default();
+    config
+        .lang_options
+        .insert("cache".to_string(), serde_json::Value::Bool(false));
+    let vfs = generate("typescript", &test_ir, &config);
+    assert!(!vfs.contains(Path::new("src/services/cache.ts")));
+    let service_content = file(&vfs, "src/services/user_service.ts");
+    assert!(!service_content.contains("cacheKey"));
+}
+
+#[test]
+fn test_typescript_offline_queue() {
+    let mut test_ir = create_test_ir();
+    let mut config = Config::default();
+    config
+        .lang_options
+        .insert("offline_queue".to_string(), serde_json::Value::Bool(true));
+    // GET operations are never queued
+    let vfs = generate("typescript", &test_ir, &config);
+    assert!(!vfs.contains(Path::new("src/services/offline.ts")));
+
+    test_ir.services[0].operations[0].http.method = HttpMethod::Put;
+    // The queue is opt-in
+    let vfs = generate("typescript", &test_ir, &Config::default());
+    assert!(!vfs.contains(Path::new("src/services/offline.ts")));
+
+    let vfs = generate("typescript", &test_ir, &config);
+    assert!(vfs.contains(Path::new("src/services/offline.ts")));
+
+    let service_content = file(&vfs, "src/services/user_service.ts");
+    assert!(service_content.contains("private offlineQueue?: OfflineQueue"));
+    assert!(
+        service_content
+            .contains("const requestId = await this.offlineQueue.enqueue('getUser', url, init);")
+    );
+    assert!(service_content.contains("throw new RequestQueuedError('getUser', requestId, error);"));
+
+    let client_content = file(&vfs, "src/services/client.ts");
+    assert!(client_content.contains("readonly offlineQueue?: OfflineQueue"));
+    assert!(client_content.contains("export * from './offline';"));
+
+    // Replays retry server errors instead of dropping them
+    let offline_content = file(&vfs, "src/services/offline.ts");
+    assert!(offline_content.contains(
+        "if (response.status >= 500 || response.status === 408 || response.status === 429) {"
+    ));
+
+    // Credentials are left out of the stored request and supplied on replay
+    test_ir.auth_schemes = vec![AuthScheme {
+        id: StableId::new("Token"),
+        name: CanonicalName::from_string("Token"),
+        kind: AuthKind::Http {
+            scheme: "bearer".to_string(),
+            bearer_format: None,
+        },
+        docs: Docs::default(),
+    }];
+    test_ir.services[0].operations[0].auth = vec![AuthUse {
+        scheme: StableId::new("Token"),
+        scopes: vec![],
+        optional: false,
+    }];
+    let vfs = generate("typescript", &test_ir, &config);
+    let service_content = file(&vfs, "src/services/user_service.ts");
+    assert!(service_content.contains(
+        "const requestId = await this.offlineQueue.enqueue('getUser', url, init, ['Authorization']);"
+    ));
+    let client_content = file(&vfs, "src/services/client.ts");
+    assert!(client_content.contains("offlineQueue?.authorize(() => this.credentialHeaders());"));
+}
+
+#[test]
+fn test_typescript_request_dedupe() {
+    let test_ir = create_test_ir();
+    // Coalescing is opt-in
+    let vfs = generate("typescript", &test_ir, &Config::default());
+    assert!(!vfs.contains(Path::new("src/services/inflight.ts")));
+    let service_content = file(&vfs, "src/services/user_service.ts");
+    assert!(service_content.contains("async getUser(): Promise<void>"));
+
+    let mut config = Config::default();
+    config
+        .lang_options
+        .insert("dedupe_requests".to_string(), serde_json::Value::Bool(true));
+    let vfs = generate("typescript", &test_ir, &config);
+    assert!(vfs.contains(Path::new("src/services/inflight.ts")));
+
+    let service_content = file(&vfs, "src/services/user_service.ts");
+    assert!(service_content.contains("async getUser(options: CallOptions = {}): Promise<void>"));
+    assert!(service_content.contains("await this.inFlight.fetch(`GET ${url}`, send)"));
+
+    let client_content = file(&vfs, "src/services/client.ts");
+    assert!(client_content.contains("private inFlight = new InFlightRequests();"));
+    assert!(client_content.contains("dedupe?: boolean;"));
+    // The body is sent and is part of the key
+    assert!(client_content.contains("const send = () => fetch(url, { method, headers, body });"));
+    assert!(client_content.contains("`GET ${url} ${JSON.stringify(headers)} ${body ?? ''}`"));
+}
+
+#[test]
+fn test_typescript_meta() {
+    let test_ir = create_test_ir();
+    let vfs = generate("typescript", &test_ir, &Config::default());
+    let meta_content = file(&vfs, "src/meta.ts");
+    assert!(meta_content.contains("title: \"Test API\","));
+    assert!(meta_content.contains("version: \"1.0.0\","));
+    assert!(meta_content.contains("checksum: \"0123456789abcdef\","));
+    assert!(meta_content.contains(&format!("generator: \"{}\",", codegen::GENERATOR_VERSION)));
+    assert!(meta_content.contains("operationCount: 1,"));
+
+    // The checksum is the spec's, not the IR's
+    let mut renamed = create_test_ir();
+    renamed.rename_package("Other");
+    let vfs = generate("typescript", &renamed, &Config::default());
+    assert!(file(&vfs, "src/meta.ts").contains("checksum: \"0123456789abcdef\","));
+    // ... as is the one recorded in the manifest
+    assert_eq!(vfs.spec_hash(), Some("0123456789abcdef"));
+
+    let index_content = file(&vfs, "src/index.ts");
+    assert!(index_content.contains("export { meta } from './meta';"));
+    assert!(index_content.contains("// oas-gen:begin keep exports\n// oas-gen:end keep\n"));
+}
+
+#[test]
+fn test_typescript_format_validation() {
+    let mut test_ir = create_test_ir();
+    for type_decl in test_ir.types.values_mut() {
+        if let TypeKind::Struct { fields, .. } = &mut type_decl.kind {
+            for field in fields.iter_mut() {
+                if field.wire_name == "email" {
+                    field.format = Some("email".to_string());
+                }
+            }
+        }
+    }
+    let vfs = generate("typescript", &test_ir, &Config::default());
+    let types_content = file(&vfs, "src/types/index.ts");
+    assert!(types_content.contains("export function validateUser(value: User): string[]"));
+    assert!(types_content.contains("errors.push('email: expected an email address');"));
+
+    // Formats missing from the registry are not checked
+    let mut config = Config::default();
+    config.formats = codegen::formats::FormatRegistry::empty();
+    let vfs = generate("typescript", &test_ir, &config);
+    let types_content = file(&vfs, "src/types/index.ts");
+    assert!(!types_content.contains("validateUser"));
+}
+
+#[test]
+fn test_typescript_layouts() {
+    let mut test_ir = create_test_ir();
+    test_ir.services[0].operations[0].success = Some(Payload {
+        status: StatusSpec::Code(200),
+        content_type: Some("application/json".to_string()),
+        ty: Some(TypeRef {
+            target: StableId::new("User"),
+            optional: false,
+            nullable: false,
+            by_ref: false,
+            modifiers: vec![],
+        }),
+        headers: vec![],
+        docs: Docs::default(),
+    });
+
+    let mut config = Config::default();
+    config
+        .lang_options
+        .insert(Config::LAYOUT.to_string(), "flat".into());
+    let vfs = generate("typescript", &test_ir, &config);
+    let service = file(&vfs, "src/user_service.service.ts");
+    assert!(service.contains("from './types';"));
+    assert!(service.contains("from './errors';"));
+    let index = file(&vfs, "src/index.ts");
+    assert!(index.contains("export * from './client';"));
+    assert!(index.contains("export { UserServiceService } from './user_service.service';"));
+
+    config
+        .lang_options
+        .insert(Config::LAYOUT.to_string(), "domain".into());
+    config
+        .lang_options
+        .insert("dedupe_requests".to_string(), serde_json::Value::Bool(true));
+    let vfs = generate("typescript", &test_ir, &config);
+    let service = file(&vfs, "src/user_service/service.ts");
+    assert!(service.contains("from '../types';"));
+    assert!(service.contains("from '../core/inflight';"));
+    let domain = file(&vfs, "src/user_service/index.ts");
+    assert!(domain.contains("export { UserServiceService } from './service';"));
+    assert!(domain.contains("export type { User } from '../types';"));
+    let client = file(&vfs, "src/core/client.ts");
+    assert!(client.contains("from '../user_service/service';"));
+
+    // Rust module paths are fixed
+    assert!(
+        GeneratorRegistry::with_defaults()
+            .generate("rust-axum", &test_ir, &config)
+            .is_err()
+    );
+}
+
+#[test]
+fn test_typescript_raw_request() {
+    let test_ir = create_test_ir();
+    let vfs = generate("typescript", &test_ir, &Config::default());
+    let client_content = file(&vfs, "src/services/client.ts");
+    assert!(client_content.contains("export interface RequestOptions"));
+    assert!(client_content.contains(
+        "async request<T = unknown>(method: string, path: string, options: RequestOptions = {}): Promise<T>"
+    ));
+
+    // Every configured credential is applied, wherever it goes
+    let mut secured = test_ir.clone();
+    let scheme = |name: &str, kind: AuthKind| AuthScheme {
+        id: StableId::new(name),
+        name: CanonicalName::from_string(name),
+        kind,
+        docs: Docs::default(),
+    };
+    let api_key = |location: ApiKeyLocation, param_name: &str| AuthKind::ApiKey {
+        location,
+        param_name: param_name.to_string(),
+    };
+    secured.auth_schemes = vec![
+        scheme(
+            "Basic",
+            AuthKind::Http {
+                scheme: "Basic".to_string(),
+                bearer_format: None,
+            },
+        ),
+        scheme("QueryKey", api_key(ApiKeyLocation::Query, "api_key")),
+        scheme("CookieKey", api_key(ApiKeyLocation::Cookie, "session")),
+    ];
+    let vfs = generate("typescript", &secured, &Config::default());
+    let client_content = file(&vfs, "src/services/client.ts");
+    assert!(
+        client_content.contains("headers['Authorization'] = `Basic ${btoa(this.security.basic)}`;")
+    );
+    assert!(client_content.contains("queryParams.set('api_key', this.security.queryKey);"));
+    assert!(
+        client_content
+            .contains("const cookie = `session=${encodeURIComponent(this.security.cookieKey)}`;")
+    );
+
+    let mut config = Config::default();
+    config
+        .lang_options
+        .insert("raw_request".to_string(), serde_json::Value::Bool(false));
+    let vfs = generate("typescript", &test_ir, &config);
+    let client_content = file(&vfs, "src/services/client.ts");
+    assert!(!client_content.contains("RequestOptions"));
+    assert!(!client_content.contains("UnexpectedError"));
+}
+
+#[test]
+fn test_typescript_streaming_matches_vfs() {
+    let test_ir = create_test_ir();
+    let config = Config::default();
     let mut registry = GeneratorRegistry::with_defaults();
     registry.register(Box::new(TypeScriptGenerator::new()));
 
     let vfs = registry.generate("typescript", &test_ir, &config).unwrap();
 
-    // Check that single client file is generated
-    assert!(vfs.contains(Path::new("src/services/client.ts")));
+    let mut streamed = Vec::new();
+    let mut sink = CallbackSink::new(|path: &Path, content: &[u8]| {
+        streamed.push((path.to_path_buf(), content.to_vec()));
+        Ok(())
+    });
+    registry
+        .generate_to_sink("typescript", &test_ir, &config, &mut sink)
+        .unwrap();
+
+    // Files are emitted as they are rendered: types first, index last
+    assert_eq!(streamed.len(), vfs.len());
+    assert_eq!(streamed[0].0, Path::new("src/types/index.ts"));
+    assert_eq!(streamed.last().unwrap().0, Path::new("src/index.ts"));
+    for (path, content) in &streamed {
+        assert_eq!(vfs.get_file(path), Some(content.as_slice()));
+    }
+}
+
+#[test]
+fn test_file_headers() {
+    let test_ir = create_test_ir();
+    let config = Config {
+        file_header: Some(FileHeader::default()),
+        ..Config::default()
+    };
+    let registry = GeneratorRegistry::with_defaults();
+    let vfs = registry.generate("typescript", &test_ir, &config).unwrap();
+
+    let index = file(&vfs, "src/index.ts");
+    assert!(index.starts_with(&format!(
+        "// {}\n// Generator: {}\n// Spec hash: {}\n\n",
+        FileHeader::DEFAULT_NOTICE,
+        codegen::GENERATOR_VERSION,
+        test_ir.api.spec_hash
+    )));
+    let gitignore = file(&vfs, ".gitignore");
+    assert!(gitignore.starts_with("# Code generated by oas-gen. DO NOT EDIT.\n"));
+    // JSON has no comments
+    let package = file(&vfs, "package.json");
+    assert!(package.starts_with('{'));
+
+    // Streamed files get the same headers
+    let mut streamed = VirtualFS::new();
+    registry
+        .generate_to_sink("typescript", &test_ir, &config, &mut streamed)
+        .unwrap();
+    assert_eq!(
+        streamed.get_file(Path::new("src/index.ts")),
+        vfs.get_file(Path::new("src/index.ts"))
+    );
+}
 
-    let client_content = vfs
-        .get_file_str(Path::new("src/services/client.ts"))
+#[test]
+fn test_file_transforms() {
+    use codegen::transform::{PathPrefix, Skip, Transforms};
+
+    let test_ir = create_test_ir();
+    let mut transforms = Transforms::new();
+    transforms.push(Skip::new(|path| path.ends_with(".gitignore")));
+    transforms.push(PathPrefix::new("packages/sdk").unwrap());
+    let config = Config {
+        transforms,
+        ..Config::default()
+    };
+    let registry = GeneratorRegistry::with_defaults();
+    let vfs = registry.generate("typescript", &test_ir, &config).unwrap();
+
+    assert!(vfs.contains(Path::new("packages/sdk/src/index.ts")));
+    assert!(!vfs.contains(Path::new("packages/sdk/.gitignore")));
+    // Post-processors like `npm install` run next to package.json
+    assert_eq!(vfs.root(), Path::new("packages/sdk"));
+    assert!(
+        vfs.files()
+            .all(|(path, _)| path.starts_with("packages/sdk"))
+    );
+
+    let mut streamed = VirtualFS::new();
+    registry
+        .generate_to_sink("typescript", &test_ir, &config, &mut streamed)
+        .unwrap();
+    assert_eq!(
+        streamed.files().map(|(path, _)| path).collect::<Vec<_>>(),
+        vfs.files().map(|(path, _)| path).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_package_name_and_base_url() {
+    let test_ir = create_test_ir();
+    let config = Config {
+        package_name: Some("acme-internal".to_string()),
+        base_url: Some("https://api.internal.acme.dev".to_string()),
+        ..Config::default()
+    };
+    let vfs = generate("typescript", &test_ir, &config);
+
+    let package_json: serde_json::Value =
+        serde_json::from_slice(vfs.get_file(Path::new("package.json")).unwrap()).unwrap();
+    assert_eq!(package_json["name"], "acme-internal");
+    let client = file(&vfs, "src/services/client.ts");
+    assert!(client.contains("private baseUrl: string = 'https://api.internal.acme.dev'"));
+}
+
+#[test]
+fn test_template_overrides() {
+    let dir = std::env::temp_dir().join(format!("oas-gen-overrides-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("meta.ts.jinja"),
+        "export const TITLE = {{ title }};\nexport const OPERATIONS = {{ operation_count }};\n",
+    )
+    .unwrap();
+
+    let test_ir = create_test_ir();
+    let config = Config {
+        template_dir: Some(dir.clone()),
+        ..Config::default()
+    };
+    let registry = GeneratorRegistry::with_defaults();
+    let vfs = registry.generate("typescript", &test_ir, &config).unwrap();
+    let meta = file(&vfs, "src/meta.ts");
+    assert_eq!(
+        meta,
+        format!(
+            "export const TITLE = \"{}\";\nexport const OPERATIONS = {};\n",
+            test_ir.api.title,
+            test_ir.operation_count()
+        )
+    );
+    // Templates without an override are the built-in ones
+    let default = registry
+        .generate("typescript", &test_ir, &Config::default())
+        .unwrap();
+    assert_eq!(
+        vfs.get_file(Path::new("src/index.ts")),
+        default.get_file(Path::new("src/index.ts"))
+    );
+
+    let missing = Config {
+        template_dir: Some(dir.join("missing")),
+        ..Config::default()
+    };
+    assert!(registry.generate("typescript", &test_ir, &missing).is_err());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_builtin_templates_as_overrides() {
+    // Multipart fields of every kind, which the service module handles
+    // one by one
+    let spec = r##"
+openapi: 3.1.0
+info: { title: Uploads, version: "1" }
+paths:
+  /uploads:
+    post:
+      operationId: upload
+      tags: [files]
+      requestBody:
+        content:
+          multipart/form-data:
+            schema:
+              type: object
+              properties:
+                file: { type: string, format: binary }
+                name: { type: string }
+                count: { type: integer }
+                meta: { $ref: "#/components/schemas/Meta" }
+      responses:
+        "201":
+          description: Created
+          content:
+            application/json:
+              schema: { $ref: "#/components/schemas/Meta" }
+components:
+  schemas:
+    Meta:
+      type: object
+      properties:
+        id: { type: string }
+"##;
+    let irs = [create_test_ir(), GenIr::from(parser::parse(spec).unwrap())];
+    let templates = Path::new(env!("CARGO_MANIFEST_DIR")).join("../templates");
+    for language in ["typescript", "rust-axum"] {
+        let config = Config {
+            template_dir: Some(templates.join(language).join("templates")),
+            ..Config::default()
+        };
+        for ir in &irs {
+            let builtin = generate(language, ir, &Config::default());
+            let overridden = generate(language, ir, &config);
+            assert_eq!(
+                builtin.files().collect::<Vec<_>>(),
+                overridden.files().collect::<Vec<_>>(),
+                "{} output changed when its templates were copied as overrides",
+                language
+            );
+        }
+    }
+}
+
+/// Generates one file, checked by a script that rejects `broken` content.
+#[cfg(unix)]
+struct CheckedGenerator {
+    content: &'static str,
+}
+
+#[cfg(unix)]
+impl codegen::Generator for CheckedGenerator {
+    fn generate(&self, _ir: &GenIr, _config: &Config) -> codegen::Result<VirtualFS> {
+        let mut vfs = VirtualFS::new();
+        vfs.add_file("src/main.txt", self.content);
+        Ok(vfs)
+    }
+
+    fn language(&self) -> &str {
+        "checked"
+    }
+
+    fn verifier(&self) -> Option<Box<dyn codegen::verify::Verifier>> {
+        Some(Box::new(
+            codegen::verify::CommandVerifier::new("sh")
+                .with_name("checker")
+                .with_args([
+                    "-c",
+                    "if grep -q broken src/main.txt; then echo 'src/main.txt:1:1: error[C1]: broken code'; exit 1; fi",
+                ]),
+        ))
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_verify() {
+    use codegen::verify::VerifyOutcome;
+
+    let test_ir = create_test_ir();
+    let config = Config::default();
+    let dir = std::env::temp_dir().join(format!("oas-gen-verify-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let mut registry = GeneratorRegistry::new();
+    registry.register(Box::new(CheckedGenerator { content: "ok\n" }));
+    let vfs = registry.generate("checked", &test_ir, &config).unwrap();
+    vfs.write_to_disk(&dir).unwrap();
+    let report = registry.verify("checked", &dir).unwrap().unwrap();
+    assert_eq!(report.outcome, VerifyOutcome::Passed);
+
+    registry.register(Box::new(CheckedGenerator {
+        content: "broken\n",
+    }));
+    let vfs = registry.generate("checked", &test_ir, &config).unwrap();
+    vfs.write_to_disk(&dir).unwrap();
+    let report = registry.verify("checked", &dir).unwrap().unwrap();
+    assert_eq!(report.outcome, VerifyOutcome::Failed);
+    let error = report.errors().next().unwrap();
+    assert_eq!(
+        error
+            .location
+            .as_ref()
+            .map(|location| location.path.as_path()),
+        Some(Path::new("src/main.txt"))
+    );
+    assert!(matches!(
+        &error.error,
+        Error::Compiler { code: Some(code), .. } if code == "C1"
+    ));
+    assert!(matches!(
+        report.into_result(),
+        Err(Error::VerificationFailed { .. })
+    ));
+
+    assert!(registry.verify("nonexistent", &dir).is_err());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_cached_output() {
+    let test_ir = create_test_ir();
+    let config = Config::default();
+    let registry = GeneratorRegistry::with_defaults();
+    let dir = std::env::temp_dir().join(format!("oas-gen-cached-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    assert!(
+        registry
+            .cached_output("typescript", &test_ir, &config, &dir)
+            .unwrap()
+            .is_none()
+    );
+    let vfs = registry.generate("typescript", &test_ir, &config).unwrap();
+    let key = registry.cache_key("typescript", &test_ir, &config).unwrap();
+    assert_eq!(vfs.cache_key(), key.as_deref());
+    vfs.write_to_disk(&dir).unwrap();
+
+    let cached = registry
+        .cached_output("typescript", &test_ir, &config, &dir)
         .unwrap()
         .unwrap();
-    assert!(client_content.contains("class TestApiSDK"));
-    assert!(client_content.contains("get userService"));
+    assert_eq!(
+        cached.files().collect::<Vec<_>>(),
+        vfs.files().collect::<Vec<_>>()
+    );
+
+    // Other options, or another generator, need a new generation
+    let without_docs = Config {
+        include_docs: false,
+        ..Config::default()
+    };
+    assert!(
+        registry
+            .cached_output("typescript", &test_ir, &without_docs, &dir)
+            .unwrap()
+            .is_none()
+    );
+    assert_ne!(
+        registry.cache_key("rust-axum", &test_ir, &config).unwrap(),
+        key
+    );
+
+    // Code in a protected region isn't a hand edit
+    let index = dir.join("src/index.ts");
+    let content = std::fs::read_to_string(&index).unwrap();
+    let content = content.replace(
+        "// oas-gen:begin keep exports\n",
+        "// oas-gen:begin keep exports\nexport * from './extra';\n",
+    );
+    assert!(content.contains("./extra"));
+    std::fs::write(&index, content).unwrap();
+    assert!(
+        registry
+            .cached_output("typescript", &test_ir, &config, &dir)
+            .unwrap()
+            .is_some()
+    );
+
+    // A hand edit elsewhere is
+    let (path, _) = vfs.files().next().unwrap();
+    std::fs::write(dir.join(path), "edited\n").unwrap();
+    assert!(
+        registry
+            .cached_output("typescript", &test_ir, &config, &dir)
+            .unwrap()
+            .is_none()
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_generation_report() {
+    use codegen::diagnostic::Phase;
+
+    let test_ir = create_test_ir();
+    let registry = GeneratorRegistry::with_defaults();
+    let mut report = generate::GenerationReport::new();
+    let vfs = registry
+        .generate_with_report("typescript", &test_ir, &Config::default(), &mut report)
+        .unwrap();
+
+    let phases: Vec<Phase> = report.phases().iter().map(|t| t.phase).collect();
+    assert_eq!(
+        phases,
+        [
+            Phase::Validate,
+            Phase::BeforeGenerate,
+            Phase::Generate,
+            Phase::AfterGenerate
+        ]
+    );
+    assert!(
+        report
+            .phases()
+            .iter()
+            .all(|t| t.generator.as_deref() == Some("typescript"))
+    );
+    assert_eq!(report.phases()[0].count, Some(test_ir.types.len()));
+    assert_eq!(report.phases()[2].count, Some(vfs.len()));
+    assert_eq!(report.generator_total("typescript"), report.total());
+}
+
+#[test]
+fn test_single_file_output() {
+    let test_ir = create_test_ir();
+    let config = Config::default();
+    let registry = GeneratorRegistry::with_defaults();
+
+    let merged = registry
+        .generate_single_file("rust-axum", &test_ir, &config)
+        .unwrap();
+    assert!(merged.starts_with("// Cargo.toml of the generated crate"));
+    assert!(merged.contains("\n// [dependencies]\n"));
+    assert!(merged.contains("\n//! Generated Axum API\n"));
+    assert!(merged.contains("pub mod types {\n"));
+    // Usable as a module of another crate
+    assert!(!merged.contains("crate::"));
+    assert!(merged.contains("use super::super::shared::RequestContext;"));
+    assert!(merged.contains("pub mod services {\n"));
+    assert!(!merged.contains("pub mod types;"));
+
+    assert!(matches!(
+        registry.generate_single_file("typescript", &test_ir, &config),
+        Err(Error::ConfigError(_))
+    ));
+
+    let vfs = registry.generate("typescript", &test_ir, &config).unwrap();
+    let stream = String::from_utf8(vfs.write_concatenated(Vec::new()).unwrap()).unwrap();
+    assert_eq!(stream.matches("\n==> ").count() + 1, vfs.len());
+    assert!(stream.contains("==> package.json <==\n"));
 }
 
 #[test]
@@ -402,12 +1402,15 @@ fn test_validation_empty_ir() {
                 lower: "empty".to_string(),
             },
             docs: Docs::default(),
+            spec_hash: String::new(),
         },
         types: BTreeMap::new(),
         services: vec![],
         auth_schemes: vec![],
         errors: vec![],
         server_sets: vec![],
+        webhooks: vec![],
+        fallbacks: Vec::new(),
     };
 
     let config = Config::default();
@@ -433,6 +1436,23 @@ fn test_config_options() {
     let config1 = Config {
         service_style: ServiceStyle::SingleClient,
         include_docs: false,
+        naming: Default::default(),
+        time_formats: Default::default(),
+        formats: Default::default(),
+        locale: None,
+        package_name: None,
+        base_url: None,
+        include_tags: Vec::new(),
+        exclude_tags: Vec::new(),
+        include_operations: Vec::new(),
+        exclude_operations: Vec::new(),
+        strict: false,
+        lint_threshold: None,
+        file_header: None,
+        template_dir: None,
+        passes: Default::default(),
+        transforms: Default::default(),
+        shared_types: None,
         lang_options: BTreeMap::new(),
     };
 
@@ -443,3 +1463,459 @@ fn test_config_options() {
     assert_eq!(config2.service_style, ServiceStyle::PerService);
     assert!(config2.include_docs);
 }
+
+#[test]
+fn test_naming_policy_reserved_words() {
+    let test_ir = create_test_ir();
+    let config = Config {
+        naming: codegen::NamingPolicy {
+            reserved_words: ["User", "email", "get_user"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let vfs = generate("typescript", &test_ir, &config);
+    let types = file(&vfs, "src/types/index.ts");
+    assert!(types.contains("interface User_ "));
+    assert!(!types.contains("interface User "));
+
+    let vfs = generate("rust-axum", &test_ir, &config);
+    let types = file(&vfs, "src/types.rs");
+    assert!(types.contains("#[serde(rename = \"email\")]\n    pub email_: "));
+    let service = file(&vfs, "src/services/user_service.rs");
+    assert!(service.contains("fn get_user_("));
+}
+
+#[test]
+fn test_generate_versions() {
+    let snapshot = |version: &str, pet_properties: &str| {
+        let json = format!(
+            r##"{{
+                "openapi": "3.0.0",
+                "info": {{ "title": "Pets", "version": "1.0.0" }},
+                "paths": {{}},
+                "components": {{
+                    "schemas": {{
+                        "Owner": {{
+                            "type": "object",
+                            "properties": {{ "name": {{ "type": "string" }} }}
+                        }},
+                        "Pet": {{ "type": "object", "properties": {pet_properties} }}
+                    }}
+                }}
+            }}"##
+        );
+        generate::SpecSnapshot::new(version, GenIr::from(parser::parse(&json).unwrap()))
+    };
+    let snapshots = vec![
+        snapshot("v1", r#"{ "name": { "type": "string" } }"#),
+        snapshot(
+            "v2",
+            r#"{ "name": { "type": "string" }, "age": { "type": "integer" } }"#,
+        ),
+    ];
+    let config = Config {
+        naming: codegen::NamingPolicy {
+            type_prefix: "Acme".to_string(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let registry = GeneratorRegistry::with_defaults();
+
+    let mut report = generate::GenerationReport::new();
+    let vfs = registry
+        .generate_versions_with_report("typescript", &snapshots, &config, &mut report)
+        .unwrap();
+    // The shared package and each version are timed
+    let generated = report
+        .phases()
+        .iter()
+        .filter(|t| t.phase == codegen::diagnostic::Phase::Generate)
+        .count();
+    assert_eq!(generated, 3);
+    let shared = file(&vfs, "shared/src/types/index.ts");
+    assert!(shared.contains("export interface AcmeOwner "));
+    assert!(!shared.contains("AcmePet"));
+
+    for version in ["v1", "v2"] {
+        let types = vfs
+            .get_file_str(&Path::new(version).join("src/types/index.ts"))
+            .unwrap()
+            .unwrap();
+        // The prefixed shared type is imported, not declared again
+        assert!(types.contains("import { AcmeOwner } from '../../../shared/src/types';"));
+        assert!(!types.contains("export interface AcmeOwner "));
+        assert!(types.contains("export interface AcmePet "));
+    }
+
+    // A package's hooks get its own files, relative to its directory
+    let v1 = vfs.subtree("v1");
+    assert!(v1.contains(Path::new("src/types/index.ts")));
+    assert!(
+        v1.files()
+            .all(|(path, _)| vfs.contains(&Path::new("v1").join(path)))
+    );
+    assert_eq!(
+        v1.len(),
+        vfs.files()
+            .filter(|(path, _)| path.starts_with("v1"))
+            .count()
+    );
+}
+
+#[test]
+fn test_passes() {
+    let spec = r##"
+openapi: 3.1.0
+info: { title: Zoo, version: "1" }
+paths:
+  /animals:
+    get:
+      operationId: listAnimals
+      responses:
+        "200":
+          description: OK
+          content:
+            application/json:
+              schema: { $ref: "#/components/schemas/Pet" }
+components:
+  schemas:
+    Pet:
+      type: object
+      properties:
+        name: { type: string }
+"##;
+    let dir = std::env::temp_dir().join(format!("oas-gen-passes-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("overlay.yaml"),
+        r#"
+overlay: 1.0.0
+info: { title: Tags, version: "1" }
+actions:
+  - target: $.components.schemas.Pet.properties
+    update:
+      nickname: { type: string }
+"#,
+    )
+    .unwrap();
+
+    let mut config = Config::default();
+    config.passes.declare(PassConfig::Overlay {
+        path: dir.join("overlay.yaml"),
+    });
+    config.passes.declare(PassConfig::RenameType {
+        from: "Pet".to_string(),
+        to: "Animal".to_string(),
+    });
+    let registry = GeneratorRegistry::with_defaults();
+    let vfs = registry.generate_spec("typescript", spec, &config).unwrap();
+    let output = String::from_utf8(vfs.write_concatenated(Vec::new()).unwrap()).unwrap();
+    assert!(output.contains("Animal"));
+    assert!(output.contains("nickname"));
+    assert!(!output.contains("Pet"));
+    // Declared passes don't keep the output from being reused
+    assert!(vfs.cache_key().is_some());
+    // Overlays rewrite the spec, so generating from an IR refuses them
+    let ir = GenIr::from(parser::parse(spec).unwrap());
+    assert!(matches!(
+        registry.generate("typescript", &ir, &config),
+        Err(Error::ConfigError(_))
+    ));
+    let overlaid = generate::apply_overlays(parser::parse(spec).unwrap(), &config).unwrap();
+    let vfs = registry
+        .generate(
+            "typescript",
+            &GenIr::from(overlaid),
+            &generate::overlaid(&config),
+        )
+        .unwrap();
+    let output = String::from_utf8(vfs.write_concatenated(Vec::new()).unwrap()).unwrap();
+    assert!(output.contains("nickname"));
+    assert!(!output.contains("Pet"));
+
+    config.passes.declare(PassConfig::RenameType {
+        from: "Owner".to_string(),
+        to: "Person".to_string(),
+    });
+    assert!(matches!(
+        registry.generate_spec("typescript", spec, &config),
+        Err(Error::ConfigError(_))
+    ));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_typescript_type_per_file() {
+    let spec = r##"
+openapi: 3.1.0
+info: { title: Zoo, version: "1" }
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      responses:
+        "200":
+          description: OK
+          content:
+            application/json:
+              schema: { $ref: "#/components/schemas/Pet" }
+        "400":
+          description: Bad request
+          content:
+            application/json:
+              schema: { $ref: "#/components/schemas/Errors" }
+components:
+  schemas:
+    Pet:
+      type: object
+      properties:
+        name: { type: string }
+        category: { $ref: "#/components/schemas/Category" }
+    Category:
+      type: object
+      properties:
+        parent: { $ref: "#/components/schemas/Category" }
+    Errors:
+      type: object
+      properties:
+        messages: { type: array, items: { type: string } }
+    ErrorsType:
+      type: object
+      properties:
+        code: { type: integer }
+    PetDto:
+      type: object
+      properties:
+        name: { type: string }
+    PetDTO:
+      type: object
+      properties:
+        id: { type: string }
+"##;
+    let registry = GeneratorRegistry::with_defaults();
+    let mut config = Config::default();
+    config
+        .lang_options
+        .insert("type_files".to_string(), serde_json::json!("per_type"));
+    let vfs = registry.generate_spec("typescript", spec, &config).unwrap();
+
+    let pet = file(&vfs, "src/types/pet.ts");
+    assert!(pet.contains("import type { Category } from './category';"));
+    assert!(pet.contains("export interface Pet {"));
+    // References to itself need no import
+    let category = vfs
+        .get_file_str(Path::new("src/types/category.ts"))
+        .unwrap()
+        .unwrap();
+    assert!(!category.contains("import"));
+    // Named to keep clear of the error classes
+    assert!(vfs.contains(Path::new("src/types/errors-type.ts")));
+    assert!(vfs.contains(Path::new("src/types/errors.ts")));
+    // and of each other, rather than overwriting one another's file
+    let modules: BTreeSet<_> = vfs
+        .files()
+        .map(|(path, _)| path.to_string_lossy().into_owned())
+        .filter(|path| {
+            path.starts_with("src/types/errors-type") || path.starts_with("src/types/pet-dto")
+        })
+        .collect();
+    assert_eq!(modules.len(), 4, "{:?}", modules);
+
+    let index = file(&vfs, "src/types/index.ts");
+    assert!(index.contains("export * from './pet';"));
+    assert!(index.contains("export * from './errors-type';"));
+    assert!(!index.contains("export interface"));
+
+    config
+        .lang_options
+        .insert("layout".to_string(), serde_json::json!("flat"));
+    let vfs = registry.generate_spec("typescript", spec, &config).unwrap();
+    assert!(vfs.contains(Path::new("src/types/pet.ts")));
+    let index = file(&vfs, "src/types.ts");
+    assert!(index.contains("export * from './types/pet';"));
+}
+
+#[test]
+fn test_generate_default() {
+    let ir = create_test_ir();
+    let registry = GeneratorRegistry::with_defaults();
+    assert_eq!(registry.resolve("ts"), "typescript");
+    assert_eq!(registry.get("axum").unwrap().language(), "rust-axum");
+    // Several generators and no project: nothing to pick
+    assert!(matches!(
+        registry.generate_default(&ir),
+        Err(Error::ConfigError(_))
+    ));
+
+    let project = codegen::ProjectConfig::from_toml(
+        r#"
+output = "sdk"
+targets = ["js", "axum"]
+
+[aliases]
+js = "typescript"
+
+[generators.js]
+layout = "flat"
+"#,
+    )
+    .unwrap();
+    let registry = registry.with_project(project);
+    let targets = registry.default_targets().unwrap();
+    assert_eq!(
+        registry.target_config(&targets[0]).lang_options["layout"],
+        "flat"
+    );
+
+    let outputs = registry.generate_default(&ir).unwrap();
+    assert_eq!(outputs.len(), 2);
+    assert_eq!(outputs[0].language, "typescript");
+    // Named after the generator, not the alias the target uses
+    assert_eq!(
+        outputs[0].output_dir,
+        Some(Path::new("sdk/typescript").to_path_buf())
+    );
+    assert_eq!(outputs[1].language, "rust-axum");
+    assert!(!outputs[0].vfs.is_empty());
+
+    // A registry with a single generator defaults to it
+    let mut registry = GeneratorRegistry::new();
+    registry.register(Box::new(TypeScriptGenerator::new()));
+    let outputs = registry.generate_default(&ir).unwrap();
+    assert_eq!(outputs[0].language, "typescript");
+    assert_eq!(outputs[0].output_dir, None);
+    // and writes it to the project's output
+    let project = codegen::ProjectConfig::from_toml("output = \"sdk\"").unwrap();
+    let outputs = registry
+        .with_project(project)
+        .generate_default(&ir)
+        .unwrap();
+    assert_eq!(outputs[0].output_dir, Some(Path::new("sdk").to_path_buf()));
+}
+
+/// Parse every generated Rust file, failing on the first syntax error.
+fn assert_rust_parses(vfs: &VirtualFS) {
+    for (path, content) in vfs.files() {
+        if path.extension().is_some_and(|ext| ext == "rs") {
+            let content = std::str::from_utf8(content).unwrap();
+            if let Err(e) = syn::parse_file(content) {
+                panic!("{} doesn't parse: {}\n{}", path.display(), e, content);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_time_formats() {
+    let spec = r##"
+openapi: 3.1.0
+info: { title: Events, version: "1" }
+paths:
+  /events:
+    post:
+      operationId: createEvents
+      requestBody:
+        required: true
+        content:
+          application/json:
+            schema: { $ref: "#/components/schemas/Event" }
+      responses:
+        "200":
+          description: Created
+          content:
+            application/json:
+              schema:
+                type: array
+                items: { $ref: "#/components/schemas/Event" }
+components:
+  schemas:
+    Event:
+      type: object
+      required: [at]
+      properties:
+        at: { type: string, format: date-time }
+        createdAt: { type: integer, x-time-format: unix }
+        day: { type: string, format: date, x-format-pattern: "%d/%m/%Y" }
+        logged: { type: string, format: date-time, x-format-pattern: "%Y-%m-%d %H:%M" }
+        attendees:
+          type: array
+          items: { $ref: "#/components/schemas/Attendee" }
+    Attendee:
+      type: object
+      properties:
+        joinedAt: { type: string, format: date-time }
+"##;
+    let registry = GeneratorRegistry::with_defaults();
+    let config = Config::default();
+
+    let vfs = registry.generate_spec("typescript", spec, &config).unwrap();
+    let types = file(&vfs, "src/types/index.ts");
+    assert!(types.contains("  at: Date;"));
+    // Epoch integers are dates too
+    assert!(types.contains("  createdAt?: Date;"));
+    assert!(types.contains("parseTime(json.createdAt, 'unix')"));
+    assert!(types.contains(
+        "attendees: json.attendees == null ? json.attendees : json.attendees.map(attendeeFromJSON),"
+    ));
+    assert!(types.contains(
+        "attendees: value.attendees == null ? value.attendees : value.attendees.map(attendeeToJSON),"
+    ));
+
+    // Services send and return what the codecs convert
+    let service = file(&vfs, "src/services/default.ts");
+    assert!(service.contains("import { eventFromJSON, eventToJSON } from '../types';"));
+    assert!(service.contains("body: JSON.stringify(eventToJSON(params.body)),"));
+    assert!(service.contains("return (await response.json()).map(eventFromJSON);"));
+
+    let vfs = registry.generate_spec("rust-axum", spec, &config).unwrap();
+    assert_rust_parses(&vfs);
+    let types = file(&vfs, "src/types.rs");
+    assert!(
+        types.contains(
+            "#[serde(default, with = \"jiff::fmt::serde::timestamp::second::optional\")]"
+        )
+    );
+    // Patterns without an offset are read as UTC
+    assert!(types.contains("fn parse(value: &str) -> Result<jiff::Timestamp, jiff::Error> {"));
+    assert!(types.contains("to_zoned(jiff::tz::TimeZone::UTC)"));
+}
+
+#[test]
+fn test_coerced_numbers_are_decoded() {
+    let spec = r##"
+openapi: 3.1.0
+info: { title: Quotes, version: "1" }
+paths:
+  /quote:
+    get:
+      operationId: getQuote
+      responses:
+        "200":
+          description: OK
+          content:
+            application/json:
+              schema: { $ref: "#/components/schemas/Quote" }
+components:
+  schemas:
+    Quote:
+      type: object
+      properties:
+        price: { type: number, x-coerce-number: true }
+"##;
+    let vfs = GeneratorRegistry::with_defaults()
+        .generate_spec("typescript", spec, &Config::default())
+        .unwrap();
+    let types = file(&vfs, "src/types/index.ts");
+    assert!(types.contains("import { coerceNumber } from './number';"));
+    assert!(types.contains("coerceNumber(json.price)"));
+    // The service runs responses through the codec, so `coerceNumber` is reached
+    let service = file(&vfs, "src/services/default.ts");
+    assert!(service.contains("import { quoteFromJSON } from '../types';"));
+    assert!(service.contains("return quoteFromJSON(await response.json());"));
+}
@@ -0,0 +1,170 @@
+//! Nullability vs optionality audit.
+//!
+//! OpenAPI has two independent ways to say "there may be no value": leaving
+//! a property out of `required`, and allowing `null`. Generated clients have
+//! to pick a representation for each combination, and specs rarely mean
+//! what that choice implies. [`GenIr::nullability_audit`] lists the fields
+//! where the spec is ambiguous so they can be fixed or confirmed:
+//!
+//! - nullable but not required: absent and `null` may or may not mean the same
+//! - required and nullable: the key must be sent even when there is no value
+//! - required with a `default`: the default can never apply
+
+use std::fmt::Write;
+
+use serde::Serialize;
+
+use crate::gen_ir::{GenIr, TypeKind};
+
+/// An ambiguous combination of `required`, `nullable` and `default`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum Ambiguity {
+    NullableOptional,
+    NullableRequired,
+    DefaultRequired,
+}
+
+impl Ambiguity {
+    /// Human-readable explanation for reports.
+    pub fn describe(self) -> &'static str {
+        match self {
+            Ambiguity::NullableOptional => {
+                "Nullable but not required: clients can't tell whether a missing value and `null` mean the same."
+            }
+            Ambiguity::NullableRequired => {
+                "Required and nullable: the key must always be sent, even without a value."
+            }
+            Ambiguity::DefaultRequired => "Required with a `default`: the default never applies.",
+        }
+    }
+}
+
+/// One ambiguous field.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NullabilityIssue {
+    /// Declared type name, e.g. `Pet`
+    pub type_name: String,
+    /// Wire name of the field, e.g. `owner_id`
+    pub field: String,
+    /// JSON pointer to the property when the type's origin is known
+    pub pointer: Option<String>,
+    pub ambiguity: Ambiguity,
+}
+
+impl GenIr {
+    /// Fields of declared types whose nullability and optionality are
+    /// ambiguous, in declaration order.
+    pub fn nullability_audit(&self) -> Vec<NullabilityIssue> {
+        let mut issues = Vec::new();
+        for decl in self.types.values() {
+            let TypeKind::Struct { fields, .. } = &decl.kind else {
+                continue;
+            };
+            for field in fields {
+                let ambiguities = [
+                    (
+                        field.ty.nullable && field.ty.optional,
+                        Ambiguity::NullableOptional,
+                    ),
+                    (
+                        field.ty.nullable && !field.ty.optional,
+                        Ambiguity::NullableRequired,
+                    ),
+                    (
+                        field.default.is_some() && !field.ty.optional,
+                        Ambiguity::DefaultRequired,
+                    ),
+                ];
+                for (_, ambiguity) in ambiguities.into_iter().filter(|(found, _)| *found) {
+                    issues.push(NullabilityIssue {
+                        type_name: decl.name.canonical.clone(),
+                        field: field.wire_name.clone(),
+                        pointer: decl.origin.as_ref().map(|origin| {
+                            format!(
+                                "{}/properties/{}",
+                                origin,
+                                field.wire_name.replace('~', "~0").replace('/', "~1")
+                            )
+                        }),
+                        ambiguity,
+                    });
+                }
+            }
+        }
+        issues
+    }
+}
+
+/// Render `issues` as a Markdown report.
+pub fn render_report(issues: &[NullabilityIssue]) -> String {
+    let mut report = String::from("# Nullability report\n\n");
+    if issues.is_empty() {
+        report.push_str("No fields with ambiguous nullability or optionality.\n");
+        return report;
+    }
+
+    let _ = writeln!(
+        report,
+        "{} field(s) with ambiguous nullability or optionality.\n",
+        issues.len()
+    );
+    report.push_str("| Field | Location | Issue |\n|---|---|---|\n");
+    for issue in issues {
+        let _ = writeln!(
+            report,
+            "| `{}.{}` | `{}` | {} |",
+            issue.type_name,
+            issue.field,
+            issue.pointer.as_deref().unwrap_or("-"),
+            issue.ambiguity.describe()
+        );
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nullability_audit() {
+        let spec = r#"
+openapi: 3.1.0
+info: { title: Pets, version: "1" }
+paths: {}
+components:
+  schemas:
+    Pet:
+      type: object
+      required: [id, owner, kind]
+      properties:
+        id: { type: string }
+        owner: { type: [string, "null"] }
+        nickname: { type: [string, "null"] }
+        kind: { type: string, default: dog }
+        age: { type: integer }
+"#;
+        let ir = GenIr::from(parser::parse(spec).unwrap());
+        let issues = ir.nullability_audit();
+        let found: Vec<(&str, Ambiguity)> = issues
+            .iter()
+            .map(|issue| (issue.field.as_str(), issue.ambiguity))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("kind", Ambiguity::DefaultRequired),
+                ("nickname", Ambiguity::NullableOptional),
+                ("owner", Ambiguity::NullableRequired),
+            ]
+        );
+        assert_eq!(
+            issues[2].pointer.as_deref(),
+            Some("#/components/schemas/Pet/properties/owner")
+        );
+
+        let report = render_report(&issues);
+        assert!(report.contains("3 field(s)"));
+        assert!(report.contains("| `Pet.kind` | `#/components/schemas/Pet/properties/kind` |"));
+    }
+}
@@ -0,0 +1,304 @@
+//! Generating part of an API.
+//!
+//! An [`OperationFilter`] keeps the operations with some tags and/or whose `operationId` matches a glob, so a partial SDK can be
+//! generated without editing the spec, e.g. one without the `admin`
+//! endpoints. [`GenIr::filter_operations`] applies it.
+
+use std::collections::BTreeSet;
+
+use crate::gen_ir::{GenIr, Operation, Service, StableId};
+use crate::operation_type_ids;
+use crate::type_graph::TypeGraph;
+
+/// Which operations to generate. Empty lists don't filter.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OperationFilter {
+    /// Only generate these tags.
+    pub include_tags: Vec<String>,
+    /// Don't generate these tags.
+    pub exclude_tags: Vec<String>,
+    /// Only generate operations whose id matches one of these globs, where
+    /// `*` matches any run of characters and `?` a single one.
+    pub include_operations: Vec<String>,
+    /// Don't generate operations whose id matches one of these globs.
+    pub exclude_operations: Vec<String>,
+}
+
+impl OperationFilter {
+    /// Whether the filter keeps every operation.
+    pub fn is_empty(&self) -> bool {
+        self.include_tags.is_empty()
+            && self.exclude_tags.is_empty()
+            && self.include_operations.is_empty()
+            && self.exclude_operations.is_empty()
+    }
+
+    /// Whether the operation `op` of `service` is generated. Tags match any
+    /// of the operation's tags, or the service name for untagged operations.
+    pub fn keeps(&self, service: &Service, op: &Operation) -> bool {
+        let service_tag = [service.name.canonical.clone()];
+        let tags = if op.tags.is_empty() {
+            &service_tag[..]
+        } else {
+            &op.tags[..]
+        };
+        let id = op.name.canonical.as_str();
+        (self.include_tags.is_empty() || self.include_tags.iter().any(|t| tags.contains(t)))
+            && !self.exclude_tags.iter().any(|t| tags.contains(t))
+            && (self.include_operations.is_empty()
+                || self.include_operations.iter().any(|p| glob_matches(p, id)))
+            && !self.exclude_operations.iter().any(|p| glob_matches(p, id))
+    }
+
+    /// Tags named by the filter that `ir` doesn't have, most likely typos.
+    pub fn unknown_tags<'a>(&'a self, ir: &GenIr) -> Vec<&'a str> {
+        self.include_tags
+            .iter()
+            .chain(&self.exclude_tags)
+            .map(String::as_str)
+            .filter(|tag| {
+                !ir.services.iter().any(|service| {
+                    service.name.canonical == *tag
+                        || service
+                            .operations
+                            .iter()
+                            .any(|op| op.tags.iter().any(|t| t == tag))
+                })
+            })
+            .collect()
+    }
+}
+
+impl GenIr {
+    /// Drop the operations `filter` doesn't keep, the services left without
+    /// operations and the types only those operations used. Types no
+    /// operation uses are kept, as are webhooks and the types they use.
+    pub fn filter_operations(&mut self, filter: &OperationFilter) {
+        if filter.is_empty() {
+            return;
+        }
+        let graph = self.type_graph();
+        let used = reachable(&graph, self.services.iter().flat_map(|s| &s.operations));
+        for service in &mut self.services {
+            let kept: Vec<Operation> = std::mem::take(&mut service.operations)
+                .into_iter()
+                .filter(|op| filter.keeps(service, op))
+                .collect();
+            service.operations = kept;
+        }
+        self.services
+            .retain(|service| !service.operations.is_empty());
+
+        let kept = reachable(
+            &graph,
+            self.services
+                .iter()
+                .flat_map(|s| &s.operations)
+                .chain(&self.webhooks),
+        );
+        self.types
+            .retain(|id, _| kept.contains(id) || !used.contains(id));
+    }
+}
+
+/// The types `operations` use, directly or through other types.
+fn reachable<'a>(
+    graph: &TypeGraph,
+    operations: impl Iterator<Item = &'a Operation>,
+) -> BTreeSet<StableId> {
+    let mut reached = BTreeSet::new();
+    let mut pending: Vec<StableId> = operations.flat_map(operation_type_ids).collect();
+    while let Some(id) = pending.pop() {
+        if reached.insert(id.clone()) {
+            pending.extend(graph.dependencies(&id).cloned());
+        }
+    }
+    reached
+}
+
+/// Whether `text` matches `pattern`, where `*` matches any run of
+/// characters and `?` any single one.
+pub fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Where the last `*` was, and the text position it currently matches up to
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_p, star_t)) => {
+                    p = star_p + 1;
+                    t = star_t + 1;
+                    star = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::parse;
+
+    use super::*;
+
+    const SPEC: &str = r##"{
+        "openapi": "3.1.0",
+        "info": { "title": "Test API", "version": "1.0.0" },
+        "paths": {
+            "/pets/{id}": {
+                "get": {
+                    "tags": ["pets"],
+                    "operationId": "getPet",
+                    "responses": {
+                        "200": {
+                            "description": "A pet",
+                            "content": { "application/json": { "schema": { "$ref": "#/components/schemas/Pet" } } }
+                        }
+                    }
+                },
+                "delete": {
+                    "tags": ["pets"],
+                    "operationId": "deletePet",
+                    "responses": { "204": { "description": "Deleted" } }
+                }
+            },
+            "/pets/{id}/owner": {
+                "get": {
+                    "tags": ["pets", "admin"],
+                    "operationId": "getPetOwner",
+                    "responses": {
+                        "200": {
+                            "description": "The owner",
+                            "content": { "application/json": { "schema": { "$ref": "#/components/schemas/User" } } }
+                        }
+                    }
+                }
+            },
+            "/admin/events": {
+                "get": {
+                    "tags": ["admin"],
+                    "operationId": "adminListEvents",
+                    "responses": {
+                        "200": {
+                            "description": "Events",
+                            "content": { "application/json": { "schema": { "$ref": "#/components/schemas/Event" } } }
+                        }
+                    }
+                }
+            },
+            "/admin/users/{id}": {
+                "get": {
+                    "tags": ["admin"],
+                    "operationId": "adminGetUser",
+                    "responses": {
+                        "200": {
+                            "description": "A user",
+                            "content": { "application/json": { "schema": { "$ref": "#/components/schemas/User" } } }
+                        }
+                    }
+                }
+            }
+        },
+        "webhooks": {
+            "eventCreated": {
+                "post": {
+                    "requestBody": {
+                        "content": { "application/json": { "schema": { "$ref": "#/components/schemas/Event" } } }
+                    },
+                    "responses": { "200": { "description": "Received" } }
+                }
+            }
+        },
+        "components": {
+            "schemas": {
+                "Pet": { "type": "object", "properties": { "name": { "type": "string" } } },
+                "User": {
+                    "type": "object",
+                    "properties": { "address": { "$ref": "#/components/schemas/Address" } }
+                },
+                "Address": { "type": "object", "properties": { "city": { "type": "string" } } },
+                "Event": { "type": "object", "properties": { "kind": { "type": "string" } } },
+                "Health": { "type": "object", "properties": { "ok": { "type": "boolean" } } }
+            }
+        }
+    }"##;
+
+    fn operation_ids(ir: &GenIr) -> Vec<&str> {
+        ir.services
+            .iter()
+            .flat_map(|service| &service.operations)
+            .map(|op| op.name.canonical.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_filter_operations() {
+        let ir = GenIr::from(parse(SPEC).unwrap());
+
+        let mut without_admin = ir.clone();
+        let filter = OperationFilter {
+            exclude_tags: vec!["admin".to_string()],
+            ..Default::default()
+        };
+        without_admin.filter_operations(&filter);
+        assert_eq!(operation_ids(&without_admin), vec!["getPet", "deletePet"]);
+        // Types only the admin endpoints used are gone, unused ones and those
+        // of webhooks stay
+        let types: Vec<_> = without_admin.types.keys().cloned().collect();
+        assert_eq!(
+            types,
+            vec![
+                StableId::new("Event"),
+                StableId::new("Health"),
+                StableId::new("Pet")
+            ]
+        );
+
+        let mut reads = ir.clone();
+        reads.filter_operations(&OperationFilter {
+            include_operations: vec!["*Get*".to_string(), "get*".to_string()],
+            exclude_operations: vec!["admin*".to_string()],
+            ..Default::default()
+        });
+        assert_eq!(operation_ids(&reads), vec!["getPet", "getPetOwner"]);
+
+        // Every tag of an operation counts, not only the one naming its service
+        let mut admin = ir.clone();
+        admin.filter_operations(&OperationFilter {
+            include_tags: vec!["admin".to_string()],
+            ..Default::default()
+        });
+        let mut ids = operation_ids(&admin);
+        ids.sort();
+        assert_eq!(ids, vec!["adminGetUser", "adminListEvents", "getPetOwner"]);
+
+        let typo = OperationFilter {
+            include_tags: vec!["pets".to_string(), "admni".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(typo.unknown_tags(&ir), vec!["admni"]);
+    }
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("admin*", "adminListUsers"));
+        assert!(glob_matches("*Pet*", "listPets"));
+        assert!(glob_matches("get?et", "getPet"));
+        assert!(glob_matches("*", ""));
+        assert!(!glob_matches("admin*", "listAdmins"));
+        assert!(!glob_matches("get?et", "getPets"));
+        assert!(!glob_matches("", "getPet"));
+    }
+}
@@ -1,7 +1,7 @@
 //! Codegen-oriented, language-agnostic IR produced from OpenAPI v3.x.
 //! This IR is resolved, stable, and designed for templating.
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, BTreeSet};
 
 pub type Map<K, V> = BTreeMap<K, V>;
@@ -47,6 +47,20 @@ pub struct Docs {
     pub since: Option<String>,     // e.g., version tag if derivable
     pub examples: Vec<DocExample>, // pre-renderable code/doc examples
     pub external_urls: Vec<String>,
+    /// Date or version after which a deprecated item is removed (`x-sunset`).
+    pub sunset: Option<String>,
+    /// Operation to use instead of a deprecated one (`x-replacement-operation`).
+    pub replacement: Option<CanonicalName>,
+    /// Translations of `summary`/`description` by locale (`x-descriptions`),
+    /// swapped in by [`GenIr::localize`].
+    pub translations: BTreeMap<String, LocalizedDocs>,
+}
+
+/// Summary and description in one locale.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct LocalizedDocs {
+    pub summary: Option<String>,
+    pub description: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -67,6 +81,8 @@ pub struct GenIr {
     pub auth_schemes: Vec<AuthScheme>,  // normalized from securitySchemes
     pub errors: Vec<ErrorDecl>,         // shared error union(s) if you build them
     pub server_sets: Vec<ServerSet>,    // base URLs and variables
+    pub webhooks: Vec<Operation>,       // OpenAPI 3.1 webhooks, path_template = name
+    pub fallbacks: Vec<Fallback>,       // schemas that could not be typed and became `any`
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -75,6 +91,7 @@ pub struct ApiMeta {
     pub version: String,
     pub package_name: CanonicalName, // root package/module name for codegen
     pub docs: Docs,
+    pub spec_hash: String, // `spec_hash` of the spec document the IR was built from
 }
 
 /// Server groups (global and/or per-tag overrides), already expanded for templating.
@@ -156,6 +173,64 @@ pub enum TypeKind {
     Alias {
         aliased: AliasTarget,
     },
+    // Schema that cannot be expressed as a type (`not`, boolean schemas).
+    // Generators should fall back to their "any value" type and document why.
+    Opaque {
+        reason: OpaqueReason,
+        /// The original schema JSON.
+        schema: serde_json::Value,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+pub enum OpaqueReason {
+    Not,           // uses the `not` keyword
+    BooleanSchema, // is, or combines, `true`/`false` schemas
+}
+
+impl OpaqueReason {
+    /// Human-readable explanation for doc comments.
+    pub fn describe(self) -> &'static str {
+        match self {
+            OpaqueReason::Not => "Uses `not`, which cannot be expressed as a type.",
+            OpaqueReason::BooleanSchema => {
+                "Uses boolean schemas, which cannot be expressed as a type."
+            }
+        }
+    }
+}
+
+/// A schema the IR builder could not type, so generators emit their "any
+/// value" type for it (or, for an unresolved `$ref`, a type that doesn't exist).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Fallback {
+    /// JSON pointer to the schema, e.g. `#/components/schemas/Pet/properties/tags`
+    pub pointer: String,
+    pub reason: FallbackReason,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+pub enum FallbackReason {
+    UnresolvedRef,             // `$ref` to a schema that isn't declared
+    Untyped,                   // no `type`, `{}`, `true` or a free-form object
+    MixedTypes,                // several types without `oneOf`/`anyOf`
+    MissingItems,              // array without `items`
+    Unsupported(OpaqueReason), // construct that can't be expressed as a type
+}
+
+impl FallbackReason {
+    /// Human-readable explanation for error messages.
+    pub fn describe(self) -> &'static str {
+        match self {
+            FallbackReason::UnresolvedRef => "`$ref` does not point at a declared schema.",
+            FallbackReason::Untyped => "Does not say what type its values have.",
+            FallbackReason::MixedTypes => {
+                "Lists several types without `oneOf`/`anyOf` to tell them apart."
+            }
+            FallbackReason::MissingItems => "Is an array without `items`.",
+            FallbackReason::Unsupported(reason) => reason.describe(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, PartialEq)]
@@ -251,6 +326,32 @@ pub struct Field {
     pub const_value: Option<Literal>,
     /// Hints for serde/jackson/etc. (e.g., wire name differs; explode styles are gone at this layer)
     pub wire_name: String,
+    /// Secret or PII (`format: password` or `x-sensitive: true`); generators
+    /// keep its value out of debug/log output.
+    pub sensitive: bool,
+    /// Wire format from `x-time-format`/`x-format-pattern`. `None` uses the
+    /// configured default for the field's type.
+    pub time_format: Option<TimeFormat>,
+    /// Accept numbers sent as strings (`"42.5"`) (`x-coerce-number: true`).
+    pub coerce_number: bool,
+    /// `format` of a plain string field (`email`, `hostname`, ...); formats
+    /// with their own primitive (`uuid`, `date-time`, ...) are not repeated here.
+    pub format: Option<String>,
+}
+
+/// Wire format of a date or date-time value.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeFormat {
+    /// RFC 3339 (`2024-01-31T12:00:00Z`, or `2024-01-31` for dates).
+    #[default]
+    Rfc3339,
+    /// Seconds since the Unix epoch, as a number. Date-times only.
+    UnixSeconds,
+    /// Milliseconds since the Unix epoch, as a number. Date-times only.
+    UnixMillis,
+    /// strftime-style pattern, e.g. `%Y/%m/%d %H:%M:%S`.
+    Pattern(String),
 }
 
 /// #### Services & Operations #################################################
@@ -262,6 +363,21 @@ pub struct Service {
     pub docs: Docs,
     pub server_set: Option<StableId>, // reference to ServerSet if overridden
     pub operations: Vec<Operation>,
+    pub owners: Vec<String>, // from `x-owner` on the tag, e.g. ["@acme/pets"]
+}
+
+impl Service {
+    /// Owners of the service and of each of its operations, without duplicates.
+    pub fn all_owners(&self) -> Vec<String> {
+        let mut owners: Vec<String> = Vec::new();
+        let operation_owners = self.operations.iter().flat_map(|op| &op.owners);
+        for owner in self.owners.iter().chain(operation_owners) {
+            if !owners.contains(owner) {
+                owners.push(owner.clone());
+            }
+        }
+        owners
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
@@ -300,6 +416,21 @@ pub struct Operation {
     // Idempotency & retry info:
     pub idempotent: bool,
     pub retryable_statuses: BTreeSet<u16>,
+
+    // Code ownership from `x-owner` on the operation:
+    pub owners: Vec<String>,
+
+    // Runtime feature flag from `x-feature-flag`, checked before each call:
+    pub feature_flag: Option<String>,
+
+    // Client-side result caching (GET only), from `x-cacheable` or a documented `Cache-Control`:
+    pub cache: Option<CacheHint>,
+
+    // Former names from `x-renamed-from`, kept as deprecated aliases in client SDKs:
+    pub renamed_from: Vec<CanonicalName>,
+
+    // Tags of the operation in the spec; the first one names its service:
+    pub tags: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -339,6 +470,9 @@ pub struct HeaderParam {
     pub ty: TypeRef,
     pub required: bool,
     pub default: Option<Literal>,
+    /// Media type of `content`-style parameters (e.g. "application/json" for
+    /// JSON-encoded values). None for plain `schema` parameters.
+    pub content_type: Option<String>,
 }
 #[derive(Debug, Clone, Serialize)]
 pub struct CookieParam {
@@ -348,6 +482,9 @@ pub struct CookieParam {
     pub ty: TypeRef,
     pub required: bool,
     pub default: Option<Literal>,
+    /// Media type of `content`-style parameters (e.g. "application/json" for
+    /// JSON-encoded values). None for plain `schema` parameters.
+    pub content_type: Option<String>,
 }
 #[derive(Debug, Clone, Serialize)]
 pub struct PathParam {
@@ -526,6 +663,14 @@ pub struct PagingHint {
     pub docs: Docs,
 }
 
+/// A GET operation whose results clients may reuse.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CacheHint {
+    /// Seconds a result stays fresh: `max-age`, or `x-cacheable: <seconds>`.
+    /// `None` leaves the TTL to the client configuration.
+    pub max_age: Option<u64>,
+}
+
 /// ############### Helper: Building two “views” from one type #################
 /// For convenience, keep track of per-IO (input/output) mirrors when needed.
 
@@ -691,6 +836,12 @@ fn to_kebab_case(s: &str) -> String {
     to_snake_case(s).replace('_', "-")
 }
 
+/// Stable hash of `bytes` as 16 hex digits: FNV-1a, which unlike
+/// `DefaultHasher` is the same across Rust releases.
+pub fn spec_hash(bytes: &[u8]) -> String {
+    format!("{:016x}", parser::fetch::fnv1a(bytes))
+}
+
 impl From<oas3::spec::Info> for ApiMeta {
     fn from(info: oas3::spec::Info) -> Self {
         let title = info.title.clone();
@@ -703,6 +854,9 @@ impl From<oas3::spec::Info> for ApiMeta {
             since: None,
             examples: Vec::new(),
             external_urls: Vec::new(),
+            sunset: None,
+            replacement: None,
+            translations: crate::i18n::translations(&info.extensions),
         };
 
         Self {
@@ -710,6 +864,193 @@ impl From<oas3::spec::Info> for ApiMeta {
             version: info.version,
             package_name,
             docs,
+            spec_hash: String::new(),
+        }
+    }
+}
+
+impl GenIr {
+    /// An IR with no types, services or servers, to fill in field by field.
+    /// Usually built from a spec with `GenIr::from(spec)` instead.
+    pub fn new(api: ApiMeta) -> Self {
+        Self {
+            api,
+            types: Map::new(),
+            services: Vec::new(),
+            auth_schemes: Vec::new(),
+            errors: Vec::new(),
+            server_sets: Vec::new(),
+            webhooks: Vec::new(),
+            fallbacks: Vec::new(),
+        }
+    }
+
+    /// Distinct `x-feature-flag` names used by operations, sorted.
+    pub fn feature_flags(&self) -> BTreeSet<&str> {
+        self.services
+            .iter()
+            .flat_map(|service| &service.operations)
+            .chain(&self.webhooks)
+            .filter_map(|op| op.feature_flag.as_deref())
+            .collect()
+    }
+
+    /// Number of operations across all services (webhooks excluded).
+    pub fn operation_count(&self) -> usize {
+        self.services
+            .iter()
+            .map(|service| service.operations.len())
+            .sum()
+    }
+
+    /// Stable hash of the whole IR as 16 hex digits. It changes whenever the
+    /// generated code may, including after passes; use
+    /// [`ApiMeta::spec_hash`] to identify the contract itself.
+    pub fn checksum(&self) -> String {
+        spec_hash(&serde_json::to_vec(self).expect("the IR serializes to JSON"))
+    }
+
+    /// Generate the package as `name` instead of the name derived from
+    /// `info.title`.
+    pub fn rename_package(&mut self, name: &str) {
+        self.api.package_name = CanonicalName::from_string(name);
+    }
+
+    /// Make `url` the default base URL, ahead of the spec's `servers`,
+    /// which stay available as alternatives.
+    pub fn set_base_url(&mut self, url: &str) {
+        if self.server_sets.is_empty() {
+            self.server_sets.push(ServerSet {
+                name: CanonicalName::from_string("default"),
+                urls: Vec::new(),
+            });
+        }
+        let urls = &mut self.server_sets[0].urls;
+        urls.retain(|server| server.template != url);
+        urls.insert(
+            0,
+            ServerUrl {
+                template: url.to_string(),
+                resolved_preview: url.to_string(),
+                variables: Map::new(),
+            },
+        );
+    }
+
+    /// Whether any operation has a [`CacheHint`].
+    pub fn has_cacheable_operations(&self) -> bool {
+        self.services
+            .iter()
+            .flat_map(|service| &service.operations)
+            .any(|op| op.cache.is_some())
+    }
+
+    /// Visit every `TypeRef` held by the IR (types, operations, errors), including
+    /// nested map value refs. Useful for passes that rename or retarget types.
+    pub fn visit_type_refs_mut(&mut self, mut f: impl FnMut(&mut TypeRef)) {
+        fn visit(ty: &mut TypeRef, f: &mut dyn FnMut(&mut TypeRef)) {
+            f(ty);
+            for modifier in &mut ty.modifiers {
+                if let TypeMod::Map(value) = modifier {
+                    visit(value, f);
+                }
+            }
+        }
+
+        fn visit_error(decl: &mut ErrorDecl, f: &mut dyn FnMut(&mut TypeRef)) {
+            for variant in &mut decl.variants {
+                if let Some(ty) = &mut variant.ty {
+                    visit(ty, f);
+                }
+            }
+        }
+
+        fn visit_payload(payload: &mut Payload, f: &mut dyn FnMut(&mut TypeRef)) {
+            if let Some(ty) = &mut payload.ty {
+                visit(ty, f);
+            }
+            for header in &mut payload.headers {
+                visit(&mut header.ty, f);
+            }
+        }
+
+        let f: &mut dyn FnMut(&mut TypeRef) = &mut f;
+
+        for decl in self.types.values_mut() {
+            match &mut decl.kind {
+                TypeKind::Struct {
+                    fields, additional, ..
+                } => {
+                    for field in fields {
+                        visit(&mut field.ty, f);
+                    }
+                    if let Additional::Typed(ty) = additional {
+                        visit(ty, f);
+                    }
+                }
+                TypeKind::Union { variants, .. } => {
+                    for variant in variants {
+                        visit(&mut variant.ty, f);
+                    }
+                }
+                TypeKind::Enum { .. } | TypeKind::Opaque { .. } => {}
+                TypeKind::Alias { aliased } => match aliased {
+                    AliasTarget::Primitive(_) => {}
+                    AliasTarget::Reference(ty) => visit(ty, f),
+                    AliasTarget::Composite(Composite::List(ty)) => visit(ty, f),
+                    AliasTarget::Composite(Composite::Map { value, .. }) => visit(value, f),
+                    AliasTarget::Composite(Composite::Tuple(items)) => {
+                        for ty in items {
+                            visit(ty, f);
+                        }
+                    }
+                },
+            }
+        }
+
+        let operations = self
+            .services
+            .iter_mut()
+            .flat_map(|service| service.operations.iter_mut())
+            .chain(self.webhooks.iter_mut());
+        for op in operations {
+            let http = &mut op.http;
+            for param in &mut http.path_params {
+                visit(&mut param.ty, f);
+            }
+            for param in &mut http.query {
+                visit(&mut param.ty, f);
+            }
+            for param in &mut http.headers {
+                visit(&mut param.ty, f);
+            }
+            for param in &mut http.cookies {
+                visit(&mut param.ty, f);
+            }
+            if let Some(body) = &mut http.body {
+                for variant in &mut body.variants {
+                    visit(&mut variant.ty, f);
+                    for part in &mut variant.encoding {
+                        visit(&mut part.ty, f);
+                        for header in &mut part.headers {
+                            visit(&mut header.ty, f);
+                        }
+                    }
+                }
+            }
+            if let Some(success) = &mut op.success {
+                visit_payload(success, f);
+            }
+            for payload in &mut op.alt_success {
+                visit_payload(payload, f);
+            }
+            if let ErrorUse::Inline(decl) = &mut op.errors {
+                visit_error(decl, f);
+            }
+        }
+
+        for decl in &mut self.errors {
+            visit_error(decl, f);
         }
     }
 }
@@ -0,0 +1,222 @@
+//! Translated documentation.
+//!
+//! A schema, property, operation, parameter or the `info` object can carry
+//! translations of its summary and description under `x-descriptions`,
+//! keyed by locale. A value is either the translated description or an
+//! object with `summary` and/or `description`:
+//!
+//! ```yaml
+//! description: A pet in the store.
+//! x-descriptions:
+//!   de: Ein Haustier im Laden.
+//!   fr: { summary: Animal, description: Un animal du magasin. }
+//! ```
+//!
+//! They are kept in [`Docs::translations`]; [`GenIr::localize`] swaps them
+//! in, so generators render whichever language the IR holds.
+
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use crate::gen_ir::{Docs, ErrorDecl, ErrorUse, GenIr, LocalizedDocs, Payload, TypeKind};
+
+/// Read `x-descriptions` from extensions (stored without the `x-` prefix).
+pub(crate) fn translations(
+    extensions: &BTreeMap<String, Value>,
+) -> BTreeMap<String, LocalizedDocs> {
+    let Some(Value::Object(locales)) = extensions.get("descriptions") else {
+        return BTreeMap::new();
+    };
+    let text = |value: Option<&Value>| value.and_then(Value::as_str).map(str::to_string);
+    locales
+        .iter()
+        .filter_map(|(locale, value)| {
+            let docs = match value {
+                Value::String(description) => LocalizedDocs {
+                    summary: None,
+                    description: Some(description.clone()),
+                },
+                Value::Object(object) => LocalizedDocs {
+                    summary: text(object.get("summary")),
+                    description: text(object.get("description")),
+                },
+                _ => return None,
+            };
+            Some((locale.clone(), docs))
+        })
+        .collect()
+}
+
+impl Docs {
+    /// The translation for `locale`: an exact match (ignoring case and `_`
+    /// vs `-`), else one for its language (`pt` for `pt-BR`).
+    pub fn translation(&self, locale: &str) -> Option<&LocalizedDocs> {
+        let normalize = |locale: &str| locale.replace('_', "-").to_ascii_lowercase();
+        let wanted = normalize(locale);
+        let language = wanted.split('-').next().unwrap_or(&wanted).to_string();
+        self.translations
+            .iter()
+            .find(|(key, _)| normalize(key) == wanted)
+            .or_else(|| {
+                self.translations
+                    .iter()
+                    .find(|(key, _)| normalize(key) == language)
+            })
+            .map(|(_, docs)| docs)
+    }
+
+    /// Replace the summary and description with their `locale` translation.
+    /// Parts without one are kept.
+    pub fn localize(&mut self, locale: &str) {
+        let Some(translation) = self.translation(locale).cloned() else {
+            return;
+        };
+        if translation.summary.is_some() {
+            self.summary = translation.summary;
+        }
+        if translation.description.is_some() {
+            self.description = translation.description;
+        }
+    }
+}
+
+impl GenIr {
+    /// Use the `locale` translation of every documented item, where there is
+    /// one. Generators then emit comments and docs in that language.
+    pub fn localize(&mut self, locale: &str) {
+        self.visit_docs_mut(|docs| docs.localize(locale));
+    }
+
+    /// Call `f` with the documentation of every item in the IR.
+    pub fn visit_docs_mut(&mut self, mut f: impl FnMut(&mut Docs)) {
+        fn visit_errors(decl: &mut ErrorDecl, f: &mut dyn FnMut(&mut Docs)) {
+            f(&mut decl.docs);
+            for variant in &mut decl.variants {
+                f(&mut variant.docs);
+            }
+        }
+
+        fn visit_payload(payload: &mut Payload, f: &mut dyn FnMut(&mut Docs)) {
+            f(&mut payload.docs);
+            for header in &mut payload.headers {
+                f(&mut header.docs);
+            }
+        }
+
+        let f: &mut dyn FnMut(&mut Docs) = &mut f;
+
+        f(&mut self.api.docs);
+        for decl in self.types.values_mut() {
+            f(&mut decl.docs);
+            match &mut decl.kind {
+                TypeKind::Struct { fields, .. } => fields.iter_mut().for_each(|x| f(&mut x.docs)),
+                TypeKind::Union { variants, .. } => {
+                    variants.iter_mut().for_each(|x| f(&mut x.docs))
+                }
+                TypeKind::Enum { values, .. } => values.iter_mut().for_each(|x| f(&mut x.docs)),
+                TypeKind::Alias { .. } | TypeKind::Opaque { .. } => {}
+            }
+        }
+        for decl in &mut self.errors {
+            visit_errors(decl, f);
+        }
+        for scheme in &mut self.auth_schemes {
+            f(&mut scheme.docs);
+        }
+
+        for service in &mut self.services {
+            f(&mut service.docs);
+        }
+        let operations = self
+            .services
+            .iter_mut()
+            .flat_map(|service| service.operations.iter_mut())
+            .chain(self.webhooks.iter_mut());
+        for op in operations {
+            f(&mut op.docs);
+            let http = &mut op.http;
+            http.path_params.iter_mut().for_each(|x| f(&mut x.docs));
+            http.query.iter_mut().for_each(|x| f(&mut x.docs));
+            http.headers.iter_mut().for_each(|x| f(&mut x.docs));
+            http.cookies.iter_mut().for_each(|x| f(&mut x.docs));
+            if let Some(body) = &mut http.body {
+                body.variants.iter_mut().for_each(|x| f(&mut x.docs));
+            }
+            for payload in op.success.iter_mut().chain(&mut op.alt_success) {
+                visit_payload(payload, f);
+            }
+            if let ErrorUse::Inline(decl) = &mut op.errors {
+                visit_errors(decl, f);
+            }
+            if let Some(pagination) = &mut op.pagination {
+                f(&mut pagination.docs);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_localize_from_extensions() {
+        let extensions = BTreeMap::from([(
+            "descriptions".to_string(),
+            json!({
+                "de": "Ein Haustier.",
+                "pt_BR": { "summary": "Animal", "description": "Um animal." }
+            }),
+        )]);
+        let docs = Docs {
+            summary: Some("Pet".to_string()),
+            description: Some("A pet.".to_string()),
+            translations: translations(&extensions),
+            ..Docs::default()
+        };
+
+        let mut german = docs.clone();
+        german.localize("de-AT");
+        assert_eq!(german.summary.as_deref(), Some("Pet"));
+        assert_eq!(german.description.as_deref(), Some("Ein Haustier."));
+
+        let mut portuguese = docs.clone();
+        portuguese.localize("pt-br");
+        assert_eq!(portuguese.summary.as_deref(), Some("Animal"));
+
+        let mut french = docs;
+        french.localize("fr");
+        assert_eq!(french.description.as_deref(), Some("A pet."));
+    }
+
+    #[test]
+    fn test_localize_spec() {
+        let spec = r#"
+openapi: 3.1.0
+info:
+  title: Pets
+  version: "1"
+  description: The pet store.
+  x-descriptions: { de: Die Tierhandlung. }
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      summary: List pets
+      x-descriptions:
+        de: { summary: Tiere auflisten }
+      responses:
+        "200": { description: ok }
+"#;
+        let mut ir = GenIr::from(parser::parse(spec).unwrap());
+        ir.localize("de");
+        assert_eq!(
+            ir.api.docs.description.as_deref(),
+            Some("Die Tierhandlung.")
+        );
+        let op = &ir.services[0].operations[0];
+        assert_eq!(op.docs.summary.as_deref(), Some("Tiere auflisten"));
+    }
+}
@@ -2,7 +2,14 @@ use serde_json::Value as JsonValue;
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 
+pub mod audit;
+pub mod filter;
 pub mod gen_ir;
+pub mod i18n;
+mod merge;
+pub mod naming;
+pub mod postman;
+pub mod type_graph;
 
 use gen_ir::*;
 
@@ -11,11 +18,12 @@ struct BuildContext<'a> {
     types: BTreeMap<StableId, TypeDecl>,
     inline_schema_cache: HashMap<u64, StableId>, // Schema hash -> StableId (for inline deduplication)
     type_structure_cache: HashMap<u64, StableId>, // TypeKind structural hash -> StableId (for type deduplication)
-    type_counter: usize,
+    pointer: Vec<(String, bool)>, // JSON pointer segments of the current location, flagged if they contribute to inline names
     used_type_names: HashSet<String>, // Track used names for collision detection
     spec: &'a oas3::spec::Spec,
     current_operation_id: Option<String>, // Track current operation for naming
     current_operation_tag: Option<String>, // Track current operation's primary tag for naming fallback
+    fallbacks: Vec<Fallback>, // Schemas typed as `any` because nothing better was possible
 }
 
 impl<'a> BuildContext<'a> {
@@ -24,18 +32,61 @@ impl<'a> BuildContext<'a> {
             types: BTreeMap::new(),
             inline_schema_cache: HashMap::new(),
             type_structure_cache: HashMap::new(),
-            type_counter: 0,
+            pointer: Vec::new(),
             used_type_names: HashSet::new(),
             spec,
             current_operation_id: None,
             current_operation_tag: None,
+            fallbacks: Vec::new(),
         }
     }
 
-    #[allow(dead_code)]
-    fn next_type_id(&mut self, base: &str) -> StableId {
-        self.type_counter += 1;
-        StableId::new(format!("{}{}", base, self.type_counter))
+    /// Record that the schema at the current location is typed as `any`.
+    fn fallback(&mut self, reason: FallbackReason) {
+        let pointer = self.pointer();
+        self.fallbacks.push(Fallback { pointer, reason });
+    }
+
+    /// Descend into `segments` of the spec. Returns the depth to pass to `leave`.
+    fn enter(&mut self, segments: &[&str]) -> usize {
+        let depth = self.pointer.len();
+        self.pointer
+            .extend(segments.iter().map(|segment| (segment.to_string(), true)));
+        depth
+    }
+
+    /// Like `enter`, but the segments are structural (e.g. `content/application~1json/schema`)
+    /// and are left out of location-derived names.
+    fn enter_structural(&mut self, segments: &[&str]) -> usize {
+        let depth = self.pointer.len();
+        self.pointer
+            .extend(segments.iter().map(|segment| (segment.to_string(), false)));
+        depth
+    }
+
+    fn leave(&mut self, depth: usize) {
+        self.pointer.truncate(depth);
+    }
+
+    /// Current location as a JSON pointer, e.g. `#/components/schemas/Pet/properties/owner`
+    fn pointer(&self) -> String {
+        let mut pointer = String::from("#");
+        for (segment, _) in &self.pointer {
+            pointer.push('/');
+            pointer.push_str(&segment.replace('~', "~0").replace('/', "~1"));
+        }
+        pointer
+    }
+
+    /// Type name derived from the current location, e.g. `ResponseOneOf0` for
+    /// `#/components/schemas/Response/oneOf/0`. Only depends on where the schema
+    /// lives, so it does not shift when unrelated schemas are added or reordered.
+    fn pointer_type_name(&self) -> String {
+        self.pointer
+            .iter()
+            .filter(|(_, named)| *named)
+            .map(|(segment, _)| to_pascal_case(segment))
+            .collect()
     }
 
     fn add_type(&mut self, decl: TypeDecl) -> StableId {
@@ -83,9 +134,11 @@ fn to_pascal_case(s: &str) -> String {
 
 /// Generate a unique name for an inline schema
 ///
-/// When there's a name collision, tries to prefix with the operation's tag first
-/// (e.g., "DogsListResponse" instead of "ListResponse2") before falling back
-/// to numeric suffixes.
+/// Names never depend on how many inline schemas were seen before. Without an
+/// operation or hint the name is derived from the schema's location. On a name
+/// collision, tries to prefix with the operation's tag first (e.g.,
+/// "DogsListResponse" instead of "ListResponse2"), then the location-derived
+/// name, and only falls back to numeric suffixes as a last resort.
 fn generate_inline_type_name(
     ctx: &BuildContext,
     operation_id: Option<&str>,
@@ -100,7 +153,14 @@ fn generate_inline_type_name(
         (Some(op_id), None, "Response") => format!("{}Response", to_pascal_case(op_id)),
         (Some(op_id), None, _) => format!("{}{}", to_pascal_case(op_id), context),
         (None, Some(hint_str), _) => format!("{}{}", to_pascal_case(hint_str), context),
-        (None, None, _) => format!("InlineType{}", context),
+        (None, None, _) => {
+            let located = ctx.pointer_type_name();
+            if located.is_empty() {
+                format!("InlineType{}", context)
+            } else {
+                located
+            }
+        }
     };
 
     // If base name is unique, use it
@@ -116,6 +176,12 @@ fn generate_inline_type_name(
         }
     }
 
+    // Then the name derived from the schema's location in the spec
+    let located = ctx.pointer_type_name();
+    if !located.is_empty() && !ctx.used_type_names.contains(&located) {
+        return located;
+    }
+
     // Fall back to numeric suffix
     let mut candidate = base_name.clone();
     let mut suffix = 2;
@@ -162,7 +228,7 @@ fn hash_type_kind_with_context(kind: &TypeKind, context_name: &str) -> u64 {
 /// Check if a schema is an inline object that should be hoisted
 fn should_hoist_schema(schema: &oas3::spec::ObjectSchema) -> bool {
     // Hoist if it's an object type with properties or additional constraints
-    if let Some(schema_type) = &schema.schema_type
+    if let Some(schema_type) = effective_type_set(schema)
         && let oas3::spec::SchemaTypeSet::Single(oas3::spec::SchemaType::Object) = schema_type
     {
         // Hoist if it has properties, or if it's explicitly an object
@@ -243,56 +309,61 @@ fn associate_tags_with_types(types: &mut BTreeMap<StableId, TypeDecl>, services:
         let tag = service.name.canonical.clone();
 
         for operation in &service.operations {
-            // Collect all type references from this operation
-            let mut type_ids = HashSet::new();
-
-            // From path parameters
-            for param in &operation.http.path_params {
-                collect_type_ids_from_type_ref(&param.ty, &mut type_ids);
+            // Add the tag to all types the operation uses
+            for type_id in operation_type_ids(operation) {
+                add_tag_recursively(types, &type_id, &tag);
             }
+        }
+    }
+}
 
-            // From query parameters
-            for param in &operation.http.query {
-                collect_type_ids_from_type_ref(&param.ty, &mut type_ids);
-            }
+/// Named types an operation refers to directly, in its parameters, request
+/// body and success responses
+pub(crate) fn operation_type_ids(operation: &Operation) -> HashSet<StableId> {
+    let mut type_ids = HashSet::new();
 
-            // From header parameters
-            for param in &operation.http.headers {
-                collect_type_ids_from_type_ref(&param.ty, &mut type_ids);
-            }
+    // From path parameters
+    for param in &operation.http.path_params {
+        collect_type_ids_from_type_ref(&param.ty, &mut type_ids);
+    }
 
-            // From cookie parameters
-            for param in &operation.http.cookies {
-                collect_type_ids_from_type_ref(&param.ty, &mut type_ids);
-            }
+    // From query parameters
+    for param in &operation.http.query {
+        collect_type_ids_from_type_ref(&param.ty, &mut type_ids);
+    }
 
-            // From request body
-            if let Some(body) = &operation.http.body {
-                for variant in &body.variants {
-                    collect_type_ids_from_type_ref(&variant.ty, &mut type_ids);
-                }
-            }
+    // From header parameters
+    for param in &operation.http.headers {
+        collect_type_ids_from_type_ref(&param.ty, &mut type_ids);
+    }
 
-            // From success response
-            if let Some(success) = &operation.success
-                && let Some(ty) = &success.ty
-            {
-                collect_type_ids_from_type_ref(ty, &mut type_ids);
-            }
+    // From cookie parameters
+    for param in &operation.http.cookies {
+        collect_type_ids_from_type_ref(&param.ty, &mut type_ids);
+    }
 
-            // From alt success responses
-            for alt_success in &operation.alt_success {
-                if let Some(ty) = &alt_success.ty {
-                    collect_type_ids_from_type_ref(ty, &mut type_ids);
-                }
-            }
+    // From request body
+    if let Some(body) = &operation.http.body {
+        for variant in &body.variants {
+            collect_type_ids_from_type_ref(&variant.ty, &mut type_ids);
+        }
+    }
 
-            // Add the tag to all collected types
-            for type_id in type_ids {
-                add_tag_recursively(types, &type_id, &tag);
-            }
+    // From success response
+    if let Some(success) = &operation.success
+        && let Some(ty) = &success.ty
+    {
+        collect_type_ids_from_type_ref(ty, &mut type_ids);
+    }
+
+    // From alt success responses
+    for alt_success in &operation.alt_success {
+        if let Some(ty) = &alt_success.ty {
+            collect_type_ids_from_type_ref(ty, &mut type_ids);
         }
     }
+
+    type_ids
 }
 
 /// Recursively add a tag to a type and all its nested types
@@ -373,7 +444,9 @@ impl From<oas3::spec::Spec> for GenIr {
         let mut ctx = BuildContext::new(&spec);
 
         // Convert API metadata
-        let api = ApiMeta::from(spec.info.clone());
+        let mut api = ApiMeta::from(spec.info.clone());
+        api.spec_hash =
+            gen_ir::spec_hash(&serde_json::to_vec(&spec).expect("the spec serializes to JSON"));
 
         // Convert schemas to types
         if let Some(components) = &spec.components {
@@ -394,10 +467,14 @@ impl From<oas3::spec::Spec> for GenIr {
         let services = convert_paths(
             &mut ctx,
             &spec.paths,
+            &spec.tags,
             &spec.security,
             spec.components.as_ref(),
         );
 
+        // Convert OpenAPI 3.1 webhooks
+        let webhooks = convert_webhooks(&mut ctx, &spec.webhooks, &spec.security);
+
         // Associate tags with types based on operation usage
         associate_tags_with_types(&mut ctx.types, &services);
 
@@ -408,16 +485,51 @@ impl From<oas3::spec::Spec> for GenIr {
             auth_schemes,
             errors: Vec::new(),
             server_sets,
+            webhooks,
+            fallbacks: ctx.fallbacks,
         }
     }
 }
 
+impl GenIr {
+    /// Load a spec from `source` and build the IR from it. This is the one
+    /// path from a spec on disk (or elsewhere) to the IR.
+    pub fn from_source(
+        source: &dyn parser::source::SpecSource,
+    ) -> Result<Self, parser::error::ParserError> {
+        Ok(GenIr::from(source.load()?))
+    }
+
+    /// Like [`from_source`](Self::from_source), loading in strict or lenient
+    /// mode and returning the warnings lenient mode collected.
+    pub fn from_source_with(
+        source: &dyn parser::source::SpecSource,
+        options: &parser::options::ParseOptions,
+    ) -> Result<(Self, Vec<parser::options::Warning>), parser::error::ParserError> {
+        let parsed = source.load_with_options(options)?;
+        Ok((GenIr::from(parsed.spec), parsed.warnings))
+    }
+
+    /// Build the IR from a spec and apply a [`naming::NamingPolicy`] to declared type names.
+    pub fn from_spec_with_naming(
+        spec: oas3::spec::Spec,
+        policy: &naming::NamingPolicy,
+    ) -> Result<Self, naming::NameCollision> {
+        let mut ir = GenIr::from(spec);
+        ir.apply_naming(policy)?;
+        Ok(ir)
+    }
+}
+
 /// Convert OpenAPI components/schemas to TypeDecl
 fn convert_schemas(ctx: &mut BuildContext, components: &oas3::spec::Components) {
     for (name, schema_ref) in &components.schemas {
         // Resolve the schema reference
         if let Ok(schema) = schema_ref.resolve(ctx.spec) {
+            let depth = ctx.enter_structural(&["components", "schemas"]);
+            ctx.enter(&[name]);
             let type_decl = convert_schema_to_type(ctx, name, &schema);
+            ctx.leave(depth);
             if let Some(decl) = type_decl {
                 let _ = ctx.add_type(decl);
             }
@@ -425,6 +537,32 @@ fn convert_schemas(ctx: &mut BuildContext, components: &oas3::spec::Components)
     }
 }
 
+/// Read deprecation extensions into `docs`:
+/// `x-deprecated-since` -> `since`, `x-sunset` -> `sunset`,
+/// `x-replacement-operation` -> `replacement`. Any of them implies `deprecated`.
+fn apply_deprecation_extensions(docs: &mut Docs, extensions: &BTreeMap<String, JsonValue>) {
+    // oas3 strips the "x-" prefix from extension keys
+    let text = |key: &str| {
+        extensions.get(key).map(|value| match value {
+            JsonValue::String(s) => s.clone(),
+            other => other.to_string(),
+        })
+    };
+
+    if let Some(since) = text("deprecated-since") {
+        docs.since = Some(since);
+        docs.deprecated = true;
+    }
+    if let Some(sunset) = text("sunset") {
+        docs.sunset = Some(sunset);
+        docs.deprecated = true;
+    }
+    if let Some(replacement) = text("replacement-operation") {
+        docs.replacement = Some(CanonicalName::from_string(replacement));
+        docs.deprecated = true;
+    }
+}
+
 /// Convert a single schema to TypeDecl
 fn convert_schema_to_type(
     ctx: &mut BuildContext,
@@ -434,14 +572,18 @@ fn convert_schema_to_type(
     let id = StableId::new(name);
     let canonical_name = CanonicalName::from_string(name);
 
-    let docs = Docs {
+    let mut docs = Docs {
         summary: schema.title.clone(),
         description: schema.description.clone(),
         deprecated: schema.deprecated.unwrap_or(false),
         since: None,
         examples: Vec::new(),
         external_urls: Vec::new(),
+        sunset: None,
+        replacement: None,
+        translations: i18n::translations(&schema.extensions),
     };
+    apply_deprecation_extensions(&mut docs, &schema.extensions);
 
     // Determine the type kind based on schema properties
     let kind = infer_type_kind(ctx, schema);
@@ -451,13 +593,64 @@ fn convert_schema_to_type(
         name: canonical_name,
         docs,
         kind,
-        origin: None,
+        origin: Some(ctx.pointer()),
         tags: BTreeSet::new(),
     })
 }
 
+/// Why a schema can't be modelled as a type, if it can't.
+/// A lone `true` schema accepts anything and is modelled as `Any` instead.
+fn opaque_reason(schema: &oas3::spec::ObjectSchema) -> Option<OpaqueReason> {
+    use parser::compat::{BOOLEAN_SCHEMA_EXTENSION, NOT_EXTENSION};
+
+    if schema.extensions.contains_key(NOT_EXTENSION) {
+        return Some(OpaqueReason::Not);
+    }
+    match schema.extensions.get(BOOLEAN_SCHEMA_EXTENSION) {
+        Some(JsonValue::Bool(true)) => return None,
+        Some(_) => return Some(OpaqueReason::BooleanSchema),
+        None => {}
+    }
+
+    let has_boolean_member = schema
+        .any_of
+        .iter()
+        .chain(&schema.one_of)
+        .chain(&schema.all_of)
+        .any(|member| {
+            matches!(member, oas3::spec::ObjectOrReference::Object(member)
+                if member.extensions.contains_key(BOOLEAN_SCHEMA_EXTENSION))
+        });
+    has_boolean_member.then_some(OpaqueReason::BooleanSchema)
+}
+
+/// The schema as written in the spec, for [`TypeKind::Opaque`].
+fn original_schema_json(schema: &oas3::spec::ObjectSchema) -> JsonValue {
+    let mut value = serde_json::to_value(schema).unwrap_or(JsonValue::Null);
+    parser::compat::restore(&mut value);
+    value
+}
+
 /// Infer the TypeKind from a schema
 fn infer_type_kind(ctx: &mut BuildContext, schema: &oas3::spec::ObjectSchema) -> TypeKind {
+    if let Some(reason) = opaque_reason(schema) {
+        ctx.fallback(FallbackReason::Unsupported(reason));
+        return TypeKind::Opaque {
+            reason,
+            schema: original_schema_json(schema),
+        };
+    }
+    if schema
+        .extensions
+        .contains_key(parser::compat::BOOLEAN_SCHEMA_EXTENSION)
+    {
+        // `true` schema accepts anything
+        ctx.fallback(FallbackReason::Untyped);
+        return TypeKind::Alias {
+            aliased: AliasTarget::Primitive(Primitive::Any),
+        };
+    }
+
     // Check for enum values
     if !schema.enum_values.is_empty() {
         let base = infer_primitive_from_schema(schema);
@@ -477,7 +670,7 @@ fn infer_type_kind(ctx: &mut BuildContext, schema: &oas3::spec::ObjectSchema) ->
     }
 
     // Check schema type
-    match &schema.schema_type {
+    match effective_type_set(schema) {
         Some(oas3::spec::SchemaTypeSet::Single(oas3::spec::SchemaType::Object)) => {
             // Object/Struct type
             let fields = convert_properties(ctx, &schema.properties, &schema.required);
@@ -516,11 +709,14 @@ fn infer_type_kind(ctx: &mut BuildContext, schema: &oas3::spec::ObjectSchema) ->
         Some(oas3::spec::SchemaTypeSet::Single(oas3::spec::SchemaType::Array)) => {
             // Array type - create as alias to list
             if let Some(items) = &schema.items {
+                let depth = ctx.enter(&["items"]);
                 let item_type_ref = convert_schema_to_type_ref(ctx, items);
+                ctx.leave(depth);
                 return TypeKind::Alias {
                     aliased: AliasTarget::Composite(Composite::List(Box::new(item_type_ref))),
                 };
             }
+            ctx.fallback(FallbackReason::MissingItems);
             TypeKind::Alias {
                 aliased: AliasTarget::Primitive(Primitive::Any),
             }
@@ -530,8 +726,12 @@ fn infer_type_kind(ctx: &mut BuildContext, schema: &oas3::spec::ObjectSchema) ->
             additional: Additional::Forbidden,
             discriminator: None,
         },
-        _ => {
+        other => {
             // Default to struct for None or Multiple types
+            ctx.fallback(match other {
+                Some(oas3::spec::SchemaTypeSet::Multiple(_)) => FallbackReason::MixedTypes,
+                _ => FallbackReason::Untyped,
+            });
             TypeKind::Struct {
                 fields: Vec::new(),
                 additional: Additional::Any,
@@ -569,7 +769,9 @@ fn convert_any_of_to_union(
                 };
 
             // For anyOf, we need to convert each schema to a TypeRef
+            let depth = ctx.enter(&["anyOf", &idx.to_string()]);
             let ty = convert_schema_ref_to_type_ref(ctx, schema_ref);
+            ctx.leave(depth);
 
             Some(Variant {
                 name: CanonicalName::from_string(&variant_name),
@@ -580,6 +782,9 @@ fn convert_any_of_to_union(
                     since: None,
                     examples: Vec::new(),
                     external_urls: Vec::new(),
+                    sunset: None,
+                    replacement: None,
+                    translations: BTreeMap::new(),
                 },
                 ty,
                 tag_value: None,
@@ -620,7 +825,9 @@ fn convert_one_of_to_union(
                         .unwrap_or_else(|| format!("Variant{}", idx + 1))
                 };
 
+            let depth = ctx.enter(&["oneOf", &idx.to_string()]);
             let ty = convert_schema_ref_to_type_ref(ctx, schema_ref);
+            ctx.leave(depth);
 
             Some(Variant {
                 name: CanonicalName::from_string(&variant_name),
@@ -631,6 +838,9 @@ fn convert_one_of_to_union(
                     since: None,
                     examples: Vec::new(),
                     external_urls: Vec::new(),
+                    sunset: None,
+                    replacement: None,
+                    translations: BTreeMap::new(),
                 },
                 ty,
                 tag_value: None,
@@ -654,17 +864,21 @@ fn convert_all_of_to_type(
     let mut fields_map: BTreeMap<String, Field> = BTreeMap::new();
     let mut additional = Additional::Any;
 
-    for schema_ref in schemas {
+    for (idx, schema_ref) in schemas.iter().enumerate() {
+        let idx = idx.to_string();
         if let Ok(schema) = schema_ref.resolve(ctx.spec) {
             // IMPORTANT: If this schema itself contains an allOf, we need to recursively
             // merge those fields first. This handles nested allOf (e.g., Final -> Middle -> Base)
             if !schema.all_of.is_empty() {
                 // Recursively process the nested allOf
+                let depth = ctx.enter(&["allOf", &idx]);
+                let nested = convert_all_of_to_type(ctx, &schema.all_of);
+                ctx.leave(depth);
                 if let TypeKind::Struct {
                     fields: nested_fields,
                     additional: nested_additional,
                     ..
-                } = convert_all_of_to_type(ctx, &schema.all_of)
+                } = nested
                 {
                     // Merge the nested fields into our fields_map
                     for nested_field in nested_fields {
@@ -713,7 +927,9 @@ fn convert_all_of_to_type(
                 let is_required = required_set.contains(prop_name);
 
                 if let Ok(prop_schema) = prop_schema_ref.resolve(ctx.spec) {
+                    let depth = ctx.enter(&["allOf", &idx, "properties", prop_name]);
                     let ty = convert_schema_ref_to_type_ref(ctx, prop_schema_ref);
+                    ctx.leave(depth);
                     let is_nullable = prop_schema.is_nullable().unwrap_or(false);
 
                     // Check if this field has a const value
@@ -722,6 +938,7 @@ fn convert_all_of_to_type(
                         .as_ref()
                         .map(convert_json_value_to_literal);
 
+                    let time_format = time_format(&prop_schema);
                     let new_field = Field {
                         name: CanonicalName::from_string(prop_name),
                         docs: Docs {
@@ -731,18 +948,28 @@ fn convert_all_of_to_type(
                             since: None,
                             examples: Vec::new(),
                             external_urls: Vec::new(),
+                            sunset: None,
+                            replacement: None,
+                            translations: BTreeMap::new(),
                         },
                         ty: TypeRef {
-                            target: ty.target.clone(),
+                            target: time_target(ty.target.clone(), &ty.modifiers, &time_format),
                             optional: !is_required,
                             nullable: is_nullable,
                             by_ref: false,
                             modifiers: ty.modifiers.clone(),
                         },
-                        default: None,
+                        default: prop_schema
+                            .default
+                            .as_ref()
+                            .map(convert_json_value_to_literal),
                         deprecated: prop_schema.deprecated.unwrap_or(false),
                         const_value,
                         wire_name: prop_name.clone(),
+                        sensitive: is_sensitive(&prop_schema),
+                        time_format,
+                        coerce_number: is_coerced_number(&prop_schema),
+                        format: string_format(&prop_schema),
                     };
 
                     // Merge with existing field if present
@@ -820,13 +1047,15 @@ fn convert_properties(
             let is_required = required_fields.contains(prop_name);
 
             // Check if this is an inline schema that should be hoisted
-            let (ty, is_nullable) = match prop_schema_ref {
+            let depth = ctx.enter(&["properties", prop_name]);
+            let converted = match prop_schema_ref {
                 oas3::spec::ObjectOrReference::Ref { .. } => {
                     // Reference - use ref conversion which preserves type identity
                     let ty = convert_schema_ref_to_type_ref(ctx, prop_schema_ref);
-                    let prop_schema = prop_schema_ref.resolve(ctx.spec).ok()?;
-                    let is_nullable = prop_schema.is_nullable().unwrap_or(false);
-                    (ty, is_nullable)
+                    prop_schema_ref.resolve(ctx.spec).ok().map(|prop_schema| {
+                        let is_nullable = prop_schema.is_nullable().unwrap_or(false);
+                        (ty, is_nullable)
+                    })
                 }
                 oas3::spec::ObjectOrReference::Object(inline_schema) => {
                     // Inline schema - check if we should hoist it
@@ -848,7 +1077,7 @@ fn convert_properties(
                             by_ref: false,
                             modifiers: Vec::new(),
                         };
-                        (ty, is_nullable)
+                        Some((ty, is_nullable))
                     } else {
                         // Simple inline schema - use normal conversion with hint
                         let ty = convert_object_schema_to_type_ref_with_hint(
@@ -856,10 +1085,12 @@ fn convert_properties(
                             inline_schema,
                             Some(prop_name),
                         );
-                        (ty, is_nullable)
+                        Some((ty, is_nullable))
                     }
                 }
             };
+            ctx.leave(depth);
+            let (ty, is_nullable) = converted?;
 
             let prop_schema = prop_schema_ref.resolve(ctx.spec).ok()?;
 
@@ -869,32 +1100,105 @@ fn convert_properties(
                 .as_ref()
                 .map(convert_json_value_to_literal);
 
+            let mut docs = Docs {
+                summary: prop_schema.title.clone(),
+                description: prop_schema.description.clone(),
+                deprecated: prop_schema.deprecated.unwrap_or(false),
+                since: None,
+                examples: Vec::new(),
+                external_urls: Vec::new(),
+                sunset: None,
+                replacement: None,
+                translations: i18n::translations(&prop_schema.extensions),
+            };
+            apply_deprecation_extensions(&mut docs, &prop_schema.extensions);
+            let deprecated = docs.deprecated;
+            let time_format = time_format(&prop_schema);
+
             Some(Field {
                 name: CanonicalName::from_string(prop_name),
-                docs: Docs {
-                    summary: prop_schema.title.clone(),
-                    description: prop_schema.description.clone(),
-                    deprecated: prop_schema.deprecated.unwrap_or(false),
-                    since: None,
-                    examples: Vec::new(),
-                    external_urls: Vec::new(),
-                },
+                docs,
                 ty: TypeRef {
-                    target: ty.target,
+                    target: time_target(ty.target, &ty.modifiers, &time_format),
                     optional: !is_required,
                     nullable: is_nullable,
                     by_ref: false,
                     modifiers: ty.modifiers,
                 },
-                default: None, // TODO: parse default values
-                deprecated: prop_schema.deprecated.unwrap_or(false),
+                default: prop_schema
+                    .default
+                    .as_ref()
+                    .map(convert_json_value_to_literal),
+                deprecated,
                 const_value,
                 wire_name: prop_name.clone(),
+                sensitive: is_sensitive(&prop_schema),
+                time_format,
+                coerce_number: is_coerced_number(&prop_schema),
+                format: string_format(&prop_schema),
             })
         })
         .collect()
 }
 
+/// Whether a property holds a secret or PII: `format: password` or
+/// `x-sensitive: true`.
+fn is_sensitive(schema: &oas3::spec::ObjectSchema) -> bool {
+    schema.format.as_deref() == Some("password")
+        || schema.extensions.get("sensitive") == Some(&JsonValue::Bool(true))
+}
+
+/// `format` of a string property that no primitive stands for (`email`,
+/// `hostname`, ...). Generators look it up in `codegen::formats`.
+fn string_format(schema: &oas3::spec::ObjectSchema) -> Option<String> {
+    let format = schema.format.as_deref()?;
+    (infer_primitive_from_schema(schema) == Primitive::String && format != "password")
+        .then(|| format.to_string())
+}
+
+/// Whether a numeric property may arrive as a string: `x-coerce-number: true`.
+fn is_coerced_number(schema: &oas3::spec::ObjectSchema) -> bool {
+    schema.extensions.get("coerce-number") == Some(&JsonValue::Bool(true))
+}
+
+/// Read `x-format-pattern` (a strftime pattern) or `x-time-format`
+/// (`rfc3339`, `unix`, `unix-ms`).
+fn time_format(schema: &oas3::spec::ObjectSchema) -> Option<TimeFormat> {
+    if let Some(pattern) = schema
+        .extensions
+        .get("format-pattern")
+        .and_then(JsonValue::as_str)
+    {
+        return Some(TimeFormat::Pattern(pattern.to_string()));
+    }
+    match schema
+        .extensions
+        .get("time-format")
+        .and_then(JsonValue::as_str)?
+    {
+        "rfc3339" => Some(TimeFormat::Rfc3339),
+        "unix" | "unix-seconds" => Some(TimeFormat::UnixSeconds),
+        "unix-ms" | "unix-millis" => Some(TimeFormat::UnixMillis),
+        _ => None,
+    }
+}
+
+/// Epoch numbers (`type: integer, x-time-format: unix`) are date-times.
+fn time_target(target: StableId, modifiers: &[TypeMod], format: &Option<TimeFormat>) -> StableId {
+    let is_number = matches!(
+        target,
+        StableId::Primitive(Primitive::I32 | Primitive::I64 | Primitive::F32 | Primitive::F64)
+    );
+    match format {
+        Some(TimeFormat::UnixSeconds | TimeFormat::UnixMillis)
+            if is_number && modifiers.is_empty() =>
+        {
+            StableId::primitive(Primitive::DateTime)
+        }
+        _ => target,
+    }
+}
+
 /// Convert a JSON value to a Literal
 fn convert_json_value_to_literal(value: &JsonValue) -> Literal {
     match value {
@@ -963,14 +1267,6 @@ fn convert_enum_values(enum_values: &[JsonValue], _base: Primitive) -> Vec<EnumV
         .collect()
 }
 
-/// Convert an ObjectSchema to a TypeRef
-fn convert_object_schema_to_type_ref(
-    ctx: &mut BuildContext,
-    schema: &oas3::spec::ObjectSchema,
-) -> TypeRef {
-    convert_object_schema_to_type_ref_with_hint(ctx, schema, None)
-}
-
 /// Check if a schema represents the Stripe "expandable" pattern:
 /// anyOf/oneOf with exactly 2 variants where one is a simple type and one is a reference
 fn is_expandable_pattern(schema: &oas3::spec::ObjectSchema) -> bool {
@@ -1002,7 +1298,7 @@ fn is_expandable_pattern(schema: &oas3::spec::ObjectSchema) -> bool {
                     | oas3::spec::SchemaType::Integer
                     | oas3::spec::SchemaType::Number
                     | oas3::spec::SchemaType::Boolean,
-                )) = &s.schema_type
+                )) = effective_type_set(s)
                 {
                     has_simple = true;
                 }
@@ -1021,6 +1317,21 @@ fn convert_object_schema_to_type_ref_with_hint(
 ) -> TypeRef {
     let nullable = schema.is_nullable().unwrap_or(false);
 
+    // Schemas we can't type are hoisted so the original JSON and the reason survive
+    if opaque_reason(schema).is_some() {
+        let type_name =
+            generate_inline_type_name(ctx, ctx.current_operation_id.as_deref(), "Value", hint);
+        let type_id = hoist_inline_schema(ctx, type_name, schema);
+
+        return TypeRef {
+            target: type_id,
+            optional: false,
+            nullable,
+            by_ref: false,
+            modifiers: Vec::new(),
+        };
+    }
+
     // Check for anyOf/oneOf/allOf - these need to be hoisted to named types
     if !schema.any_of.is_empty() || !schema.one_of.is_empty() || !schema.all_of.is_empty() {
         // Special case: anyOf/oneOf with a single reference - just return the reference
@@ -1116,7 +1427,7 @@ fn convert_object_schema_to_type_ref_with_hint(
     }
 
     // Check schema type to determine the type
-    if let Some(schema_type) = &schema.schema_type {
+    if let Some(schema_type) = effective_type_set(schema) {
         match schema_type {
             oas3::spec::SchemaTypeSet::Single(oas3::spec::SchemaType::String)
             | oas3::spec::SchemaTypeSet::Single(oas3::spec::SchemaType::Integer)
@@ -1136,11 +1447,13 @@ fn convert_object_schema_to_type_ref_with_hint(
                 if let Some(items) = &schema.items {
                     // Generate hint for array items: "Input" -> "InputItem"
                     let item_hint = hint.map(|h| format!("{}Item", h));
+                    let depth = ctx.enter(&["items"]);
                     let inner_ref = convert_schema_to_type_ref_with_hint_internal(
                         ctx,
                         items,
                         item_hint.as_deref(),
                     );
+                    ctx.leave(depth);
                     // Preserve inner modifiers and add List modifier
                     let mut modifiers = inner_ref.modifiers.clone();
                     modifiers.push(TypeMod::List);
@@ -1157,6 +1470,13 @@ fn convert_object_schema_to_type_ref_with_hint(
         }
     }
 
+    ctx.fallback(match effective_type_set(schema) {
+        Some(oas3::spec::SchemaTypeSet::Multiple(_)) => FallbackReason::MixedTypes,
+        Some(oas3::spec::SchemaTypeSet::Single(oas3::spec::SchemaType::Array)) => {
+            FallbackReason::MissingItems
+        }
+        _ => FallbackReason::Untyped,
+    });
     TypeRef {
         target: StableId::Primitive(Primitive::Any),
         optional: false,
@@ -1180,6 +1500,7 @@ fn convert_schema_to_type_ref_with_hint_internal(
     match schema {
         oas3::spec::Schema::Boolean(oas3::spec::BooleanSchema(true)) => {
             // true schema accepts anything
+            ctx.fallback(FallbackReason::Untyped);
             TypeRef {
                 target: StableId::Primitive(Primitive::Any),
                 optional: false,
@@ -1227,6 +1548,18 @@ fn convert_schema_ref_to_type_ref_with_hint(
             .unwrap_or("Unknown")
             .to_string();
 
+        let declared = ctx
+            .spec
+            .components
+            .as_ref()
+            .is_some_and(|components| components.schemas.contains_key(&type_name));
+        let unresolved = ref_path
+            .strip_prefix("#/components/schemas/")
+            .is_some_and(|name| !name.contains('/') && !declared);
+        if unresolved {
+            ctx.fallback(FallbackReason::UnresolvedRef);
+        }
+
         return TypeRef {
             target: StableId::new(&type_name),
             optional: false,
@@ -1240,6 +1573,7 @@ fn convert_schema_ref_to_type_ref_with_hint(
     if let Ok(schema) = schema_ref.resolve(ctx.spec) {
         convert_object_schema_to_type_ref_with_hint(ctx, &schema, hint)
     } else {
+        ctx.fallback(FallbackReason::UnresolvedRef);
         TypeRef {
             target: StableId::Primitive(Primitive::Any),
             optional: false,
@@ -1250,9 +1584,32 @@ fn convert_schema_ref_to_type_ref_with_hint(
     }
 }
 
+/// The schema's type set with `null` dropped from OpenAPI 3.1 type arrays, so
+/// `type: [string, "null"]` is handled like `type: string`. Nullability is read
+/// separately through `is_nullable`.
+fn effective_type_set(schema: &oas3::spec::ObjectSchema) -> Option<oas3::spec::SchemaTypeSet> {
+    use oas3::spec::{SchemaType, SchemaTypeSet};
+
+    match schema.schema_type.as_ref()? {
+        SchemaTypeSet::Multiple(types) => {
+            let non_null: Vec<SchemaType> = types
+                .iter()
+                .copied()
+                .filter(|ty| *ty != SchemaType::Null)
+                .collect();
+            match non_null.as_slice() {
+                [] => Some(SchemaTypeSet::Single(SchemaType::Null)),
+                [single] => Some(SchemaTypeSet::Single(*single)),
+                _ => Some(SchemaTypeSet::Multiple(non_null)),
+            }
+        }
+        single => Some(single.clone()),
+    }
+}
+
 /// Infer primitive type from schema
 fn infer_primitive_from_schema(schema: &oas3::spec::ObjectSchema) -> Primitive {
-    if let Some(schema_type) = &schema.schema_type {
+    if let Some(schema_type) = effective_type_set(schema) {
         match schema_type {
             oas3::spec::SchemaTypeSet::Single(oas3::spec::SchemaType::String) => {
                 match schema.format.as_deref() {
@@ -1392,6 +1749,9 @@ fn convert_security_schemes(components: &oas3::spec::Components) -> Vec<AuthSche
                     since: None,
                     examples: Vec::new(),
                     external_urls: Vec::new(),
+                    sunset: None,
+                    replacement: None,
+                    translations: BTreeMap::new(),
                 },
             })
         })
@@ -1466,6 +1826,7 @@ fn convert_oauth_scopes(scopes: &std::collections::BTreeMap<String, String>) ->
 fn convert_paths(
     ctx: &mut BuildContext,
     paths: &Option<BTreeMap<String, oas3::spec::PathItem>>,
+    tags: &[oas3::spec::Tag],
     security: &[oas3::spec::SecurityRequirement],
     _components: Option<&oas3::spec::Components>,
 ) -> Vec<Service> {
@@ -1491,6 +1852,11 @@ fn convert_paths(
         .map(|(tag, operations)| {
             let id = StableId::new(&tag);
             let name = CanonicalName::from_string(&tag);
+            let owners = tags
+                .iter()
+                .find(|t| t.name == tag)
+                .map(|t| owners_from_extensions(&t.extensions))
+                .unwrap_or_default();
 
             Service {
                 id,
@@ -1498,6 +1864,7 @@ fn convert_paths(
                 docs: Docs::default(),
                 server_set: None,
                 operations,
+                owners,
             }
         })
         .collect()
@@ -1511,7 +1878,41 @@ fn convert_path_item(
     services_map: &mut BTreeMap<String, Vec<Operation>>,
     global_security: Option<&[oas3::spec::SecurityRequirement]>,
 ) {
-    let methods = [
+    for (method_name, operation) in path_item_operations(path_item) {
+        // Extract tag first so it can be used for collision resolution in naming
+        let tag = operation
+            .tags
+            .first()
+            .cloned()
+            .unwrap_or_else(|| "default".to_string());
+
+        // Set current tag in context for schema naming fallback
+        ctx.current_operation_tag = Some(tag.clone());
+
+        let depth = ctx.enter_structural(&["paths"]);
+        ctx.enter(&[path, method_name]);
+        let op = convert_operation(
+            ctx,
+            path,
+            method_name,
+            operation,
+            &path_item.parameters,
+            global_security,
+        );
+        ctx.leave(depth);
+
+        // Clear tag after operation is converted
+        ctx.current_operation_tag = None;
+
+        services_map.entry(tag).or_default().push(op);
+    }
+}
+
+/// Operations of a path item with their lowercase method names, in a fixed order.
+fn path_item_operations(
+    path_item: &oas3::spec::PathItem,
+) -> impl Iterator<Item = (&'static str, &oas3::spec::Operation)> {
+    [
         ("get", path_item.get.as_ref()),
         ("post", path_item.post.as_ref()),
         ("put", path_item.put.as_ref()),
@@ -1520,28 +1921,40 @@ fn convert_path_item(
         ("head", path_item.head.as_ref()),
         ("options", path_item.options.as_ref()),
         ("trace", path_item.trace.as_ref()),
-    ];
-
-    for (method_name, operation_opt) in methods {
-        if let Some(operation) = operation_opt {
-            // Extract tag first so it can be used for collision resolution in naming
-            let tag = operation
-                .tags
-                .first()
-                .cloned()
-                .unwrap_or_else(|| "default".to_string());
-
-            // Set current tag in context for schema naming fallback
-            ctx.current_operation_tag = Some(tag.clone());
-
-            let op = convert_operation(ctx, path, method_name, operation, global_security);
+    ]
+    .into_iter()
+    .filter_map(|(method_name, operation)| operation.map(|op| (method_name, op)))
+}
 
-            // Clear tag after operation is converted
-            ctx.current_operation_tag = None;
+/// Convert OpenAPI 3.1 webhooks. The webhook name takes the place of the path.
+fn convert_webhooks(
+    ctx: &mut BuildContext,
+    webhooks: &BTreeMap<String, oas3::spec::PathItem>,
+    security: &[oas3::spec::SecurityRequirement],
+) -> Vec<Operation> {
+    let global_security = if security.is_empty() {
+        None
+    } else {
+        Some(security)
+    };
 
-            services_map.entry(tag).or_default().push(op);
+    let mut operations = Vec::new();
+    for (name, path_item) in webhooks {
+        for (method_name, operation) in path_item_operations(path_item) {
+            let depth = ctx.enter_structural(&["webhooks"]);
+            ctx.enter(&[name, method_name]);
+            operations.push(convert_operation(
+                ctx,
+                name,
+                method_name,
+                operation,
+                &path_item.parameters,
+                global_security,
+            ));
+            ctx.leave(depth);
         }
     }
+    operations
 }
 
 /// Convert an OpenAPI operation to our Operation type
@@ -1550,6 +1963,7 @@ fn convert_operation(
     path: &str,
     method_name: &str,
     operation: &oas3::spec::Operation,
+    shared_parameters: &[oas3::spec::ObjectOrReference<oas3::spec::Parameter>],
     global_security: Option<&[oas3::spec::SecurityRequirement]>,
 ) -> Operation {
     let operation_id = operation
@@ -1575,14 +1989,18 @@ fn convert_operation(
         _ => HttpMethod::Get,
     };
 
-    let docs = Docs {
+    let mut docs = Docs {
         summary: operation.summary.clone(),
         description: operation.description.clone(),
         deprecated: operation.deprecated.unwrap_or(false),
         since: None,
         examples: Vec::new(),
         external_urls: Vec::new(),
+        sunset: None,
+        replacement: None,
+        translations: i18n::translations(&operation.extensions),
     };
+    apply_deprecation_extensions(&mut docs, &operation.extensions);
 
     // Convert parameters
     let mut path_params = Vec::new();
@@ -1590,24 +2008,24 @@ fn convert_operation(
     let mut headers = Vec::new();
     let mut cookies = Vec::new();
 
-    for param_ref in &operation.parameters {
-        // Resolve parameter if it's a reference
-        if let Ok(param) = param_ref.resolve(ctx.spec) {
-            convert_parameter(
-                ctx,
-                &param,
-                &mut path_params,
-                &mut query,
-                &mut headers,
-                &mut cookies,
-            );
-        }
+    for param in resolve_operation_parameters(ctx, shared_parameters, &operation.parameters) {
+        convert_parameter(
+            ctx,
+            &param,
+            &mut path_params,
+            &mut query,
+            &mut headers,
+            &mut cookies,
+        );
     }
 
     // Convert request body
     let (body, consumes) = if let Some(request_body_ref) = &operation.request_body {
         if let Ok(request_body) = request_body_ref.resolve(ctx.spec) {
-            convert_request_body(ctx, &request_body)
+            let depth = ctx.enter(&["requestBody"]);
+            let converted = convert_request_body(ctx, &request_body);
+            ctx.leave(depth);
+            converted
         } else {
             (None, Vec::new())
         }
@@ -1616,10 +2034,10 @@ fn convert_operation(
     };
 
     // Convert responses
-    let (success, produces) = if let Some(responses) = &operation.responses {
+    let (success, alt_success, produces) = if let Some(responses) = &operation.responses {
         convert_responses(ctx, responses)
     } else {
-        (None, Vec::new())
+        (None, Vec::new(), Vec::new())
     };
 
     // Convert error responses
@@ -1656,11 +2074,11 @@ fn convert_operation(
     Operation {
         id,
         name,
+        deprecated: docs.deprecated,
         docs,
-        deprecated: operation.deprecated.unwrap_or(false),
         http,
         success,
-        alt_success: Vec::new(),
+        alt_success,
         errors,
         auth,
         pagination: None,
@@ -1669,9 +2087,150 @@ fn convert_operation(
             HttpMethod::Get | HttpMethod::Put | HttpMethod::Delete
         ),
         retryable_statuses: Default::default(),
+        owners: owners_from_extensions(&operation.extensions),
+        feature_flag: operation
+            .extensions
+            .get("feature-flag")
+            .and_then(JsonValue::as_str)
+            .map(|flag| flag.trim().to_string())
+            .filter(|flag| !flag.is_empty()),
+        cache: cache_hint(ctx.spec, method, operation),
+        renamed_from: renamed_from(&operation.extensions),
+        tags: operation.tags.clone(),
+    }
+}
+
+/// Former `operationId`s from `x-renamed-from` (a single id or a list).
+fn renamed_from(extensions: &BTreeMap<String, JsonValue>) -> Vec<CanonicalName> {
+    let ids: Vec<&str> = match extensions.get("renamed-from") {
+        Some(JsonValue::String(id)) => vec![id],
+        Some(JsonValue::Array(ids)) => ids.iter().filter_map(JsonValue::as_str).collect(),
+        _ => Vec::new(),
+    };
+    ids.into_iter()
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(CanonicalName::from_string)
+        .collect()
+}
+
+/// Caching for a GET operation. `x-cacheable` (`true`, `false` or a TTL in
+/// seconds) takes precedence; otherwise a `Cache-Control` header documented on
+/// a 2xx response makes it cacheable, with `max-age` read from the header's
+/// example or schema default. `no-store`, `no-cache` and `max-age=0` opt out.
+fn cache_hint(
+    spec: &oas3::Spec,
+    method: HttpMethod,
+    operation: &oas3::spec::Operation,
+) -> Option<CacheHint> {
+    if method != HttpMethod::Get {
+        return None;
+    }
+
+    match operation.extensions.get("cacheable") {
+        Some(JsonValue::Bool(false)) => return None,
+        Some(JsonValue::Bool(true)) => return Some(CacheHint { max_age: None }),
+        Some(JsonValue::Number(seconds)) => {
+            return seconds
+                .as_u64()
+                .filter(|seconds| *seconds > 0)
+                .map(|seconds| CacheHint {
+                    max_age: Some(seconds),
+                });
+        }
+        _ => {}
+    }
+
+    let responses = operation.responses.as_ref()?;
+    let header = responses
+        .iter()
+        .filter(|(status, _)| status.starts_with('2'))
+        .filter_map(|(_, response)| response.resolve(spec).ok())
+        .find_map(|response| {
+            response
+                .headers
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case("cache-control"))
+                .and_then(|(_, header)| header.resolve(spec).ok())
+        })?;
+
+    let directives = header
+        .example
+        .clone()
+        .or_else(|| {
+            header
+                .schema
+                .as_ref()
+                .and_then(|schema| schema.resolve(spec).ok())
+                .and_then(|schema| schema.default.or(schema.example))
+        })
+        .and_then(|value| value.as_str().map(str::to_ascii_lowercase))
+        .unwrap_or_default();
+
+    let mut max_age = None;
+    for directive in directives.split(',').map(str::trim) {
+        match directive.split_once('=') {
+            Some(("max-age", seconds)) => max_age = seconds.trim().parse().ok(),
+            None if directive == "no-store" || directive == "no-cache" => return None,
+            _ => {}
+        }
+    }
+    if max_age == Some(0) {
+        return None;
+    }
+    Some(CacheHint { max_age })
+}
+
+/// Read `x-owner` (a single owner or a list) as CODEOWNERS owners.
+/// Bare names get an `@` prefix; emails and `@user`/`@org/team` are kept.
+fn owners_from_extensions(extensions: &BTreeMap<String, JsonValue>) -> Vec<String> {
+    let normalize = |owner: &str| {
+        let owner = owner.trim();
+        if owner.contains('@') {
+            owner.to_string()
+        } else {
+            format!("@{}", owner)
+        }
+    };
+
+    match extensions.get("owner") {
+        Some(JsonValue::String(owner)) => vec![normalize(owner)],
+        Some(JsonValue::Array(owners)) => owners
+            .iter()
+            .filter_map(|owner| owner.as_str())
+            .map(normalize)
+            .collect(),
+        _ => Vec::new(),
     }
 }
 
+/// Resolve the parameters that apply to an operation
+///
+/// Path-item level parameters apply to every operation under the path unless the
+/// operation redefines a parameter with the same name and location. References
+/// (e.g. `#/components/parameters/PageSize`) are resolved against the spec.
+fn resolve_operation_parameters(
+    ctx: &BuildContext,
+    shared: &[oas3::spec::ObjectOrReference<oas3::spec::Parameter>],
+    own: &[oas3::spec::ObjectOrReference<oas3::spec::Parameter>],
+) -> Vec<oas3::spec::Parameter> {
+    let own: Vec<oas3::spec::Parameter> = own
+        .iter()
+        .filter_map(|param_ref| param_ref.resolve(ctx.spec).ok())
+        .collect();
+
+    let mut params: Vec<oas3::spec::Parameter> = shared
+        .iter()
+        .filter_map(|param_ref| param_ref.resolve(ctx.spec).ok())
+        .filter(|shared| {
+            !own.iter()
+                .any(|param| param.name == shared.name && param.location == shared.location)
+        })
+        .collect();
+    params.extend(own);
+    params
+}
+
 /// Convert OpenAPI security requirements to AuthUse
 fn convert_security_requirements(
     security: Option<&[oas3::spec::SecurityRequirement]>,
@@ -1705,37 +2264,20 @@ fn convert_parameter(
     cookies: &mut Vec<CookieParam>,
 ) {
     let name = CanonicalName::from_string(&param.name);
-    let docs = Docs {
+    let mut docs = Docs {
         summary: None,
         description: param.description.clone(),
         deprecated: param.deprecated.unwrap_or(false),
         since: None,
         examples: Vec::new(),
         external_urls: Vec::new(),
+        sunset: None,
+        replacement: None,
+        translations: i18n::translations(&param.extensions),
     };
+    apply_deprecation_extensions(&mut docs, &param.extensions);
 
-    // Get type from schema
-    let ty = if let Some(schema_ref) = &param.schema {
-        if let Ok(schema) = schema_ref.resolve(ctx.spec) {
-            convert_object_schema_to_type_ref(ctx, &schema)
-        } else {
-            TypeRef {
-                target: StableId::new("string"),
-                optional: false,
-                nullable: false,
-                by_ref: false,
-                modifiers: Vec::new(),
-            }
-        }
-    } else {
-        TypeRef {
-            target: StableId::new("string"),
-            optional: false,
-            nullable: false,
-            by_ref: false,
-            modifiers: Vec::new(),
-        }
-    };
+    let (ty, content_type) = convert_parameter_type(ctx, param);
 
     let required = param.required.unwrap_or(false);
 
@@ -1766,6 +2308,7 @@ fn convert_parameter(
                 ty,
                 required,
                 default: None,
+                content_type,
             });
         }
         oas3::spec::ParameterIn::Cookie => {
@@ -1776,11 +2319,61 @@ fn convert_parameter(
                 ty,
                 required,
                 default: None,
+                content_type,
             });
         }
     }
 }
 
+/// Get a parameter's type from its `schema`, or from the single `content` entry
+/// for parameters such as JSON-encoded headers.
+///
+/// References are kept so named types (enums, objects) survive, and arrays carry
+/// their List modifier. Returns the media type for `content`-style parameters.
+fn convert_parameter_type(
+    ctx: &mut BuildContext,
+    param: &oas3::spec::Parameter,
+) -> (TypeRef, Option<String>) {
+    if let Some(schema_ref) = &param.schema {
+        let ty = convert_schema_ref_to_type_ref_with_hint(ctx, schema_ref, Some(&param.name));
+        return (ty, None);
+    }
+
+    if let Some((content_type, media_type)) = param
+        .content
+        .as_ref()
+        .and_then(|content| content.iter().next())
+    {
+        let ty = match &media_type.schema {
+            Some(schema_ref) => {
+                convert_schema_ref_to_type_ref_with_hint(ctx, schema_ref, Some(&param.name))
+            }
+            None => {
+                ctx.fallback(FallbackReason::Untyped);
+                TypeRef {
+                    target: StableId::Primitive(Primitive::Any),
+                    optional: false,
+                    nullable: false,
+                    by_ref: false,
+                    modifiers: Vec::new(),
+                }
+            }
+        };
+        return (ty, Some(content_type.clone()));
+    }
+
+    (
+        TypeRef {
+            target: StableId::Primitive(Primitive::String),
+            optional: false,
+            nullable: false,
+            by_ref: false,
+            modifiers: Vec::new(),
+        },
+        None,
+    )
+}
+
 /// Extract multipart encoding information from schema
 fn extract_multipart_encoding(
     ctx: &mut BuildContext,
@@ -1829,6 +2422,7 @@ fn convert_request_body(
 
         if let Some(schema_ref) = &media_type.schema {
             // Check if this is an inline schema that should be hoisted
+            let depth = ctx.enter_structural(&["content", content_type, "schema"]);
             let ty = match schema_ref {
                 oas3::spec::ObjectOrReference::Ref { .. } => {
                     // Reference - use normal conversion
@@ -1863,6 +2457,7 @@ fn convert_request_body(
                     }
                 }
             };
+            ctx.leave(depth);
 
             // Extract encoding for multipart requests
             let encoding = if content_type.starts_with("multipart/") {
@@ -1892,12 +2487,15 @@ fn convert_request_body(
     (body, consumes)
 }
 
-/// Convert responses to success payload
+/// Convert responses to the success payload of the first 2xx response,
+/// plus the later 2xx responses with a different body as alternatives.
 fn convert_responses(
     ctx: &mut BuildContext,
     responses: &BTreeMap<String, oas3::spec::ObjectOrReference<oas3::spec::Response>>,
-) -> (Option<Payload>, Vec<String>) {
+) -> (Option<Payload>, Vec<Payload>, Vec<String>) {
     let mut produces = Vec::new();
+    let mut success: Option<Payload> = None;
+    let mut alt_success: Vec<Payload> = Vec::new();
 
     // Look for 2xx success responses
     for (status_code, response_ref) in responses {
@@ -1906,91 +2504,133 @@ fn convert_responses(
         {
             // Resolve the response
             if let Ok(response) = response_ref.resolve(ctx.spec) {
-                // Collect ALL content types for produces
-                for content_type in response.content.keys() {
-                    produces.push(content_type.clone());
+                // Collect ALL content types of the primary response for produces
+                if success.is_none() {
+                    produces.extend(response.content.keys().cloned());
                 }
 
-                // Get first content type for the main payload
-                if let Some((content_type, media_type)) = response.content.iter().next()
-                    && let Some(schema_ref) = &media_type.schema
-                {
-                    // Check if this is an inline schema that should be hoisted
-                    let ty = match schema_ref {
-                        oas3::spec::ObjectOrReference::Ref { .. } => {
-                            // Reference - use normal conversion
-                            convert_schema_ref_to_type_ref(ctx, schema_ref)
+                // Later responses name their hoisted body after the status code
+                let hint = success.as_ref().map(|_| format!("Response{}", code));
+                let payload = convert_success_response(ctx, status_code, code, &response, hint);
+                match &success {
+                    None => success = Some(payload),
+                    Some(primary) => {
+                        if primary.ty != payload.ty
+                            && alt_success.iter().all(|alt| alt.ty != payload.ty)
+                        {
+                            alt_success.push(payload);
                         }
-                        oas3::spec::ObjectOrReference::Object(inline_schema) => {
-                            // Inline schema - check if we should hoist it
-                            if should_hoist_schema(inline_schema) {
-                                // Hoist inline schema
-                                let type_name = generate_inline_type_name(
-                                    ctx,
-                                    ctx.current_operation_id.as_deref(),
-                                    "Response",
-                                    None,
-                                );
-                                let type_id = hoist_inline_schema_with_parent(
-                                    ctx,
-                                    type_name.clone(),
-                                    inline_schema,
-                                    Some(&type_name),
-                                );
-                                TypeRef {
-                                    target: type_id,
-                                    optional: false,
-                                    nullable: inline_schema.is_nullable().unwrap_or(false),
-                                    by_ref: false,
-                                    modifiers: Vec::new(),
-                                }
-                            } else {
-                                // Simple inline schema - use normal conversion
-                                convert_schema_ref_to_type_ref(ctx, schema_ref)
-                            }
-                        }
-                    };
-
-                    let payload = Payload {
-                        status: StatusSpec::Code(code),
-                        content_type: Some(content_type.clone()),
-                        ty: Some(ty),
-                        headers: Vec::new(), // TODO: convert response headers
-                        docs: Docs {
-                            summary: response.description.clone(),
-                            description: None,
-                            deprecated: false,
-                            since: None,
-                            examples: Vec::new(),
-                            external_urls: Vec::new(),
-                        },
-                    };
-
-                    return (Some(payload), produces);
+                    }
                 }
+            }
+        }
+    }
 
-                // Response with no content
-                let payload = Payload {
-                    status: StatusSpec::Code(code),
-                    content_type: None,
-                    ty: None,
-                    headers: Vec::new(),
-                    docs: Docs {
-                        summary: response.description.clone(),
-                        description: None,
-                        deprecated: false,
-                        since: None,
-                        examples: Vec::new(),
-                        external_urls: Vec::new(),
-                    },
-                };
+    (success, alt_success, produces)
+}
+
+/// Convert one 2xx response, using its first content type for the payload.
+fn convert_success_response(
+    ctx: &mut BuildContext,
+    status_code: &str,
+    code: u16,
+    response: &oas3::spec::Response,
+    hint: Option<String>,
+) -> Payload {
+    let docs = Docs {
+        summary: response.description.clone(),
+        description: None,
+        deprecated: false,
+        since: None,
+        examples: Vec::new(),
+        external_urls: Vec::new(),
+        sunset: None,
+        replacement: None,
+        translations: BTreeMap::new(),
+    };
 
-                return (Some(payload), produces);
+    // Get first content type for the main payload
+    if let Some((content_type, media_type)) = response.content.iter().next()
+        && let Some(schema_ref) = &media_type.schema
+    {
+        // Check if this is an inline schema that should be hoisted
+        let depth = ctx.enter(&["responses", status_code]);
+        ctx.enter_structural(&["content", content_type, "schema"]);
+        let ty = match schema_ref {
+            oas3::spec::ObjectOrReference::Ref { .. } => {
+                // Reference - use normal conversion
+                convert_schema_ref_to_type_ref(ctx, schema_ref)
             }
-        }
+            oas3::spec::ObjectOrReference::Object(inline_schema) => {
+                // Inline schema - check if we should hoist it
+                if should_hoist_schema(inline_schema) {
+                    // Hoist inline schema
+                    let type_name = generate_inline_type_name(
+                        ctx,
+                        ctx.current_operation_id.as_deref(),
+                        "Response",
+                        hint.as_deref(),
+                    );
+                    let type_id = hoist_inline_schema_with_parent(
+                        ctx,
+                        type_name.clone(),
+                        inline_schema,
+                        Some(&type_name),
+                    );
+                    TypeRef {
+                        target: type_id,
+                        optional: false,
+                        nullable: inline_schema.is_nullable().unwrap_or(false),
+                        by_ref: false,
+                        modifiers: Vec::new(),
+                    }
+                } else {
+                    // Simple inline schema - use normal conversion
+                    convert_schema_ref_to_type_ref(ctx, schema_ref)
+                }
+            }
+        };
+        ctx.leave(depth);
+
+        return Payload {
+            status: StatusSpec::Code(code),
+            content_type: Some(content_type.clone()),
+            ty: Some(ty),
+            headers: Vec::new(), // TODO: convert response headers
+            docs,
+        };
     }
 
-    (None, produces)
+    // Response with no content
+    Payload {
+        status: StatusSpec::Code(code),
+        content_type: None,
+        ty: None,
+        headers: Vec::new(),
+        docs,
+    }
+}
+
+/// Name of the error variant for an HTTP status `code`, e.g. `NotFound`
+/// for 404.
+pub fn error_variant_name(code: u16) -> String {
+    match code {
+        400 => "BadRequest",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "NotFound",
+        405 => "MethodNotAllowed",
+        409 => "Conflict",
+        422 => "UnprocessableEntity",
+        429 => "TooManyRequests",
+        500 => "InternalServerError",
+        502 => "BadGateway",
+        503 => "ServiceUnavailable",
+        504 => "GatewayTimeout",
+        // For other codes, generate a generic name
+        _ => return format!("Status{}", code),
+    }
+    .to_string()
 }
 
 /// Convert error responses (4xx, 5xx) to ErrorUse
@@ -2017,25 +2657,8 @@ fn convert_error_responses(
 
         // Resolve the response
         if let Ok(response) = response_ref.resolve(ctx.spec) {
-            // Generate a name for this error variant based on status code
-            let variant_name = match code {
-                400 => "BadRequest",
-                401 => "Unauthorized",
-                403 => "Forbidden",
-                404 => "NotFound",
-                405 => "MethodNotAllowed",
-                409 => "Conflict",
-                422 => "UnprocessableEntity",
-                429 => "TooManyRequests",
-                500 => "InternalServerError",
-                502 => "BadGateway",
-                503 => "ServiceUnavailable",
-                504 => "GatewayTimeout",
-                _ => {
-                    // For other codes, generate a generic name
-                    &format!("Status{}", code)
-                }
-            };
+            let variant_name = error_variant_name(code);
+            let variant_name = variant_name.as_str();
 
             let mut content_type = None;
             let mut ty = None;
@@ -2046,6 +2669,8 @@ fn convert_error_responses(
 
                 if let Some(schema_ref) = &media_type.schema {
                     // Check if this is an inline schema that should be hoisted
+                    let depth = ctx.enter(&["responses", status_code]);
+                    ctx.enter_structural(&["content", ct, "schema"]);
                     ty = Some(match schema_ref {
                         oas3::spec::ObjectOrReference::Ref { .. } => {
                             // Reference - use normal conversion
@@ -2080,6 +2705,7 @@ fn convert_error_responses(
                             }
                         }
                     });
+                    ctx.leave(depth);
                 }
             }
 
@@ -2095,6 +2721,9 @@ fn convert_error_responses(
                     since: None,
                     examples: Vec::new(),
                     external_urls: Vec::new(),
+                    sunset: None,
+                    replacement: None,
+                    translations: BTreeMap::new(),
                 },
             };
 
@@ -2149,6 +2778,11 @@ mod tests {
         assert_eq!(gen_ir.api.package_name.canonical, "Test API");
         assert_eq!(gen_ir.api.package_name.snake, "test_api");
         assert_eq!(gen_ir.api.package_name.pascal, "TestApi");
+
+        // The spec hash follows the document
+        assert_eq!(gen_ir.api.spec_hash.len(), 16);
+        let edited = parse(&json.replace("A test API", "An API")).unwrap();
+        assert_ne!(GenIr::from(edited).api.spec_hash, gen_ir.api.spec_hash);
     }
 
     #[test]
@@ -2940,6 +3574,76 @@ mod tests {
         assert_eq!(response_type, response_type_id);
     }
 
+    #[test]
+    fn test_alt_success_responses() {
+        let yaml = r##"
+openapi: 3.1.0
+info: { title: Pets, version: "1" }
+paths:
+  /pets:
+    put:
+      operationId: upsertPet
+      responses:
+        "200":
+          description: Updated
+          content:
+            application/json:
+              schema: { $ref: "#/components/schemas/Pet" }
+        "201":
+          description: Created
+          content:
+            application/json:
+              schema:
+                type: object
+                properties:
+                  id: { type: string }
+        "202":
+          description: Also updated
+          content:
+            application/json:
+              schema: { $ref: "#/components/schemas/Pet" }
+        "204": { description: Unchanged }
+components:
+  schemas:
+    Pet:
+      type: object
+      properties:
+        name: { type: string }
+"##;
+
+        let gen_ir = GenIr::from(parse(yaml).unwrap());
+        let op = &gen_ir.services[0].operations[0];
+        let status = |payload: &Payload| match payload.status {
+            StatusSpec::Code(code) => code,
+            _ => panic!("expected a status code"),
+        };
+        assert_eq!(status(op.success.as_ref().unwrap()), 200);
+
+        // 202 has the same body as 200, so it isn't an alternative
+        let alternatives: Vec<(u16, Option<StableId>)> = op
+            .alt_success
+            .iter()
+            .map(|payload| {
+                (
+                    status(payload),
+                    payload.ty.as_ref().map(|ty| ty.target.clone()),
+                )
+            })
+            .collect();
+        assert_eq!(
+            alternatives,
+            [
+                (201, Some(StableId::new("UpsertPetResponse201"))),
+                (204, None),
+            ]
+        );
+        assert!(
+            gen_ir
+                .types
+                .contains_key(&StableId::new("UpsertPetResponse201"))
+        );
+    }
+
     #[test]
     fn test_inline_schema_hoisting_nested_properties() {
         // Test that nested inline object properties are hoisted
@@ -3508,22 +4212,22 @@ type Pet = {
         let pseudo_code = generate_pseudo_code(&gen_ir);
 
         // Check against the ENTIRE pseudo-code output
-        // Inline schemas with "title" should be hoisted to named types
-        let expected = r##"type InlineTypeObject = {
+        // Inline schemas are hoisted to types named after their location
+        let expected = r##"type Response = union<OneOf> {
+  Success: ResponseOneOf0
+  Error: ResponseOneOf1
+}
+
+type ResponseOneOf0 = {
   data?: Primitive_String
   [key: string]: any
 }
 
-type InlineTypeObject2 = {
+type ResponseOneOf1 = {
   error?: Primitive_String
   [key: string]: any
 }
 
-type Response = union<OneOf> {
-  Success: InlineTypeObject
-  Error: InlineTypeObject2
-}
-
 "##;
         assert_eq!(
             pseudo_code.trim(),
@@ -3532,6 +4236,60 @@ type Response = union<OneOf> {
         );
     }
 
+    #[test]
+    fn test_inline_names_stable_across_unrelated_edits() {
+        // Adding an unrelated inline schema earlier in the document must not
+        // rename existing hoisted types
+        let spec = |extra: &str| {
+            format!(
+                r##"{{
+            "openapi": "3.0.0",
+            "info": {{ "title": "Test API", "version": "1.0.0" }},
+            "paths": {{}},
+            "components": {{
+                "schemas": {{
+                    {extra}
+                    "Shape": {{
+                        "oneOf": [
+                            {{ "type": "object", "properties": {{ "radius": {{ "type": "number" }} }} }},
+                            {{ "type": "object", "properties": {{ "side": {{ "type": "number" }} }} }}
+                        ]
+                    }}
+                }}
+            }}
+        }}"##
+            )
+        };
+
+        let before = GenIr::from(parse(&spec("")).unwrap());
+        let after = GenIr::from(
+            parse(&spec(
+                r#""Animal": {
+                        "oneOf": [
+                            { "type": "object", "properties": { "legs": { "type": "integer" } } }
+                        ]
+                    },"#,
+            ))
+            .unwrap(),
+        );
+
+        for name in ["ShapeOneOf0", "ShapeOneOf1"] {
+            let id = StableId::new(name);
+            assert!(before.types.contains_key(&id), "{name} missing before edit");
+            assert_eq!(
+                format!("{:?}", before.types[&id].kind),
+                format!("{:?}", after.types[&id].kind)
+            );
+        }
+        assert!(after.types.contains_key(&StableId::new("AnimalOneOf0")));
+        assert_eq!(
+            before.types[&StableId::new("ShapeOneOf1")]
+                .origin
+                .as_deref(),
+            Some("#/components/schemas/Shape/oneOf/1")
+        );
+    }
+
     #[test]
     fn test_allof_multiple_objects() {
         // Test allOf with multiple object schemas merging their properties
@@ -4356,4 +5114,627 @@ type Extended = {
             response_types
         );
     }
+
+    #[test]
+    fn test_component_parameter_and_body_refs() {
+        let json = r##"{
+            "openapi": "3.0.0",
+            "info": { "title": "Test API", "version": "1.0.0" },
+            "paths": {
+                "/pets/{petId}": {
+                    "parameters": [
+                        { "$ref": "#/components/parameters/PetId" },
+                        { "$ref": "#/components/parameters/Limit" }
+                    ],
+                    "put": {
+                        "operationId": "updatePet",
+                        "parameters": [
+                            { "name": "limit", "in": "query", "required": true, "schema": { "type": "integer" } }
+                        ],
+                        "requestBody": { "$ref": "#/components/requestBodies/PetBody" },
+                        "responses": {
+                            "200": { "$ref": "#/components/responses/PetResponse" }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "parameters": {
+                    "PetId": { "name": "petId", "in": "path", "required": true, "schema": { "type": "string" } },
+                    "Limit": { "name": "limit", "in": "query", "schema": { "type": "integer" } }
+                },
+                "requestBodies": {
+                    "PetBody": {
+                        "content": { "application/json": { "schema": { "$ref": "#/components/schemas/Pet" } } }
+                    }
+                },
+                "responses": {
+                    "PetResponse": {
+                        "description": "The pet",
+                        "content": { "application/json": { "schema": { "$ref": "#/components/schemas/Pet" } } }
+                    }
+                },
+                "schemas": {
+                    "Pet": { "type": "object", "properties": { "name": { "type": "string" } } }
+                }
+            }
+        }"##;
+
+        let gen_ir = GenIr::from(parse(json).unwrap());
+        let op = &gen_ir.services[0].operations[0];
+
+        // Path-level parameters apply, operation-level ones override them
+        assert_eq!(op.http.path_params.len(), 1);
+        assert_eq!(op.http.path_params[0].wire, "petId");
+        assert_eq!(op.http.query.len(), 1);
+        assert_eq!(op.http.query[0].wire, "limit");
+        assert!(op.http.query[0].required);
+
+        let body = op.http.body.as_ref().expect("request body ref resolved");
+        assert_eq!(body.variants[0].ty.target, StableId::new("Pet"));
+        let success = op.success.as_ref().expect("response ref resolved");
+        assert_eq!(
+            success.ty.as_ref().map(|ty| &ty.target),
+            Some(&StableId::new("Pet"))
+        );
+    }
+
+    #[test]
+    fn test_header_and_cookie_params_typed() {
+        let json = r##"{
+            "openapi": "3.0.0",
+            "info": { "title": "Test API", "version": "1.0.0" },
+            "paths": {
+                "/search": {
+                    "get": {
+                        "operationId": "search",
+                        "parameters": [
+                            { "name": "X-Filter", "in": "header", "required": true,
+                              "content": { "application/json": { "schema": { "$ref": "#/components/schemas/Filter" } } } },
+                            { "name": "X-Tags", "in": "header",
+                              "schema": { "type": "array", "items": { "type": "string" } } },
+                            { "name": "X-Priority", "in": "header",
+                              "schema": { "$ref": "#/components/schemas/Priority" } },
+                            { "name": "session", "in": "cookie", "schema": { "type": "integer", "format": "int64" } }
+                        ],
+                        "responses": { "204": { "description": "No content" } }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "Filter": { "type": "object", "properties": { "q": { "type": "string" } } },
+                    "Priority": { "type": "string", "enum": ["low", "high"] }
+                }
+            }
+        }"##;
+
+        let gen_ir = GenIr::from(parse(json).unwrap());
+        let headers = &gen_ir.services[0].operations[0].http.headers;
+        let cookies = &gen_ir.services[0].operations[0].http.cookies;
+
+        assert_eq!(headers[0].wire, "X-Filter");
+        assert_eq!(headers[0].ty.target, StableId::new("Filter"));
+        assert_eq!(headers[0].content_type.as_deref(), Some("application/json"));
+
+        assert_eq!(headers[1].ty.target, StableId::Primitive(Primitive::String));
+        assert!(matches!(
+            headers[1].ty.modifiers.as_slice(),
+            [TypeMod::List]
+        ));
+        assert_eq!(headers[1].content_type, None);
+
+        assert_eq!(headers[2].ty.target, StableId::new("Priority"));
+
+        assert_eq!(cookies[0].ty.target, StableId::Primitive(Primitive::I64));
+    }
+
+    #[test]
+    fn test_deprecation_extensions() {
+        let json = r##"{
+            "openapi": "3.0.0",
+            "info": { "title": "Test API", "version": "1.0.0" },
+            "paths": {
+                "/pets": {
+                    "get": {
+                        "operationId": "listPets",
+                        "x-deprecated-since": "v2",
+                        "x-sunset": "2025-06-30",
+                        "x-replacement-operation": "list_pets_v2",
+                        "responses": { "204": { "description": "No content" } }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "Pet": {
+                        "type": "object",
+                        "properties": {
+                            "tag": { "type": "string", "deprecated": true, "x-sunset": "2025-01-01" }
+                        }
+                    }
+                }
+            }
+        }"##;
+
+        let gen_ir = GenIr::from(parse(json).unwrap());
+
+        let op = &gen_ir.services[0].operations[0];
+        assert!(op.deprecated);
+        assert_eq!(op.docs.since.as_deref(), Some("v2"));
+        assert_eq!(op.docs.sunset.as_deref(), Some("2025-06-30"));
+        assert_eq!(
+            op.docs.replacement.as_ref().map(|name| name.camel.as_str()),
+            Some("listPetsV2")
+        );
+
+        let TypeKind::Struct { fields, .. } = &gen_ir.types[&StableId::new("Pet")].kind else {
+            panic!("expected struct");
+        };
+        assert!(fields[0].deprecated);
+        assert_eq!(fields[0].docs.sunset.as_deref(), Some("2025-01-01"));
+        assert!(fields[0].docs.replacement.is_none());
+    }
+
+    #[test]
+    fn test_not_and_boolean_schemas_are_opaque() {
+        let json = r##"{
+            "openapi": "3.1.0",
+            "info": { "title": "Test API", "version": "1.0.0" },
+            "paths": {},
+            "components": {
+                "schemas": {
+                    "NotString": { "not": { "type": "string" } },
+                    "Anything": true,
+                    "Flag": { "anyOf": [true, { "type": "string" }] },
+                    "Holder": {
+                        "type": "object",
+                        "properties": {
+                            "filter": { "not": { "type": "null" } },
+                            "extra": true
+                        }
+                    }
+                }
+            }
+        }"##;
+
+        let gen_ir = GenIr::from(parse(json).unwrap());
+
+        let TypeKind::Opaque { reason, schema } = &gen_ir.types[&StableId::new("NotString")].kind
+        else {
+            panic!("expected opaque type");
+        };
+        assert_eq!(*reason, OpaqueReason::Not);
+        assert_eq!(schema, &serde_json::json!({ "not": { "type": "string" } }));
+
+        let TypeKind::Opaque { reason, schema } = &gen_ir.types[&StableId::new("Flag")].kind else {
+            panic!("expected opaque type");
+        };
+        assert_eq!(*reason, OpaqueReason::BooleanSchema);
+        assert_eq!(schema["anyOf"][0], serde_json::json!(true));
+
+        assert!(matches!(
+            gen_ir.types[&StableId::new("Anything")].kind,
+            TypeKind::Alias {
+                aliased: AliasTarget::Primitive(Primitive::Any)
+            }
+        ));
+
+        let TypeKind::Struct { fields, .. } = &gen_ir.types[&StableId::new("Holder")].kind else {
+            panic!("expected struct");
+        };
+        let extra = fields.iter().find(|f| f.name.canonical == "extra").unwrap();
+        assert_eq!(extra.ty.target, StableId::Primitive(Primitive::Any));
+        let filter = fields
+            .iter()
+            .find(|f| f.name.canonical == "filter")
+            .unwrap();
+        assert!(matches!(
+            gen_ir.types[&filter.ty.target].kind,
+            TypeKind::Opaque {
+                reason: OpaqueReason::Not,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_openapi_3_1_documents() {
+        let json = r##"{
+            "openapi": "3.1.0",
+            "jsonSchemaDialect": "https://spec.openapis.org/oas/3.1/dialect/base",
+            "info": { "title": "Test API", "version": "1.0.0" },
+            "webhooks": {
+                "petAdopted": {
+                    "post": {
+                        "operationId": "onPetAdopted",
+                        "requestBody": {
+                            "content": {
+                                "application/json": {
+                                    "schema": { "$ref": "#/components/schemas/Pet" }
+                                }
+                            }
+                        },
+                        "responses": { "200": { "description": "Acknowledged" } }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "Pet": {
+                        "type": "object",
+                        "properties": {
+                            "name": { "type": ["string", "null"] },
+                            "age": { "type": "integer", "exclusiveMinimum": 0 }
+                        }
+                    }
+                }
+            }
+        }"##;
+
+        let gen_ir = GenIr::from(parse(json).unwrap());
+
+        assert!(gen_ir.services.is_empty());
+        assert_eq!(gen_ir.webhooks.len(), 1);
+        let webhook = &gen_ir.webhooks[0];
+        assert_eq!(webhook.name.camel, "onPetAdopted");
+        assert_eq!(webhook.http.path_template, "petAdopted");
+        let body = webhook.http.body.as_ref().unwrap();
+        assert_eq!(body.variants[0].ty.target, StableId::new("Pet"));
+
+        let TypeKind::Struct { fields, .. } = &gen_ir.types[&StableId::new("Pet")].kind else {
+            panic!("expected struct");
+        };
+        let name = fields.iter().find(|f| f.name.canonical == "name").unwrap();
+        assert_eq!(name.ty.target, StableId::Primitive(Primitive::String));
+        assert!(name.ty.nullable);
+    }
+
+    #[test]
+    fn test_openapi_3_0_keywords_are_upgraded() {
+        let json = r##"{
+            "openapi": "3.0.3",
+            "info": { "title": "Test API", "version": "1.0.0" },
+            "paths": {},
+            "components": {
+                "schemas": {
+                    "Pet": {
+                        "type": "object",
+                        "properties": {
+                            "nickname": { "type": "string", "nullable": true },
+                            "age": { "type": "integer", "minimum": 0, "exclusiveMinimum": true }
+                        }
+                    }
+                }
+            }
+        }"##;
+
+        let gen_ir = GenIr::from(parse(json).unwrap());
+
+        let TypeKind::Struct { fields, .. } = &gen_ir.types[&StableId::new("Pet")].kind else {
+            panic!("expected struct");
+        };
+        let nickname = fields
+            .iter()
+            .find(|f| f.name.canonical == "nickname")
+            .unwrap();
+        assert_eq!(nickname.ty.target, StableId::Primitive(Primitive::String));
+        assert!(nickname.ty.nullable);
+    }
+
+    #[test]
+    fn test_owner_extensions() {
+        let json = r##"{
+            "openapi": "3.0.0",
+            "info": { "title": "Test API", "version": "1.0.0" },
+            "tags": [{ "name": "pets", "x-owner": "acme/pets" }],
+            "paths": {
+                "/pets": {
+                    "get": {
+                        "operationId": "listPets",
+                        "tags": ["pets"],
+                        "responses": { "204": { "description": "No content" } }
+                    },
+                    "delete": {
+                        "operationId": "deletePets",
+                        "tags": ["pets"],
+                        "x-owner": ["@acme/pets", "security@acme.dev"],
+                        "responses": { "204": { "description": "No content" } }
+                    }
+                }
+            }
+        }"##;
+
+        let gen_ir = GenIr::from(parse(json).unwrap());
+
+        let service = &gen_ir.services[0];
+        assert_eq!(service.owners, vec!["@acme/pets"]);
+        let delete = service
+            .operations
+            .iter()
+            .find(|op| op.name.camel == "deletePets")
+            .unwrap();
+        assert_eq!(delete.owners, vec!["@acme/pets", "security@acme.dev"]);
+        assert_eq!(
+            service.all_owners(),
+            vec!["@acme/pets", "security@acme.dev"]
+        );
+    }
+
+    #[test]
+    fn test_feature_flag_extension() {
+        let json = r##"{
+            "openapi": "3.1.0",
+            "info": { "title": "Test API", "version": "1.0.0" },
+            "paths": {
+                "/checkout": {
+                    "post": {
+                        "operationId": "createCheckout",
+                        "x-feature-flag": "newCheckout",
+                        "responses": { "204": { "description": "No content" } }
+                    },
+                    "get": {
+                        "operationId": "getCheckout",
+                        "responses": { "204": { "description": "No content" } }
+                    }
+                }
+            }
+        }"##;
+
+        let gen_ir = GenIr::from(parse(json).unwrap());
+
+        let operations = &gen_ir.services[0].operations;
+        let flag_of = |name: &str| {
+            operations
+                .iter()
+                .find(|op| op.name.camel == name)
+                .unwrap()
+                .feature_flag
+                .clone()
+        };
+        assert_eq!(flag_of("createCheckout").as_deref(), Some("newCheckout"));
+        assert_eq!(flag_of("getCheckout"), None);
+        assert_eq!(
+            gen_ir.feature_flags().into_iter().collect::<Vec<_>>(),
+            vec!["newCheckout"]
+        );
+    }
+
+    #[test]
+    fn test_cacheable_operations() {
+        let json = r##"{
+            "openapi": "3.1.0",
+            "info": { "title": "Test API", "version": "1.0.0" },
+            "paths": {
+                "/pets": {
+                    "get": {
+                        "operationId": "listPets",
+                        "responses": {
+                            "200": {
+                                "description": "OK",
+                                "headers": {
+                                    "Cache-Control": {
+                                        "schema": { "type": "string", "example": "public, max-age=300" }
+                                    }
+                                }
+                            }
+                        }
+                    },
+                    "post": {
+                        "operationId": "createPet",
+                        "x-cacheable": true,
+                        "responses": { "204": { "description": "No content" } }
+                    }
+                },
+                "/pets/{id}": {
+                    "get": {
+                        "operationId": "getPet",
+                        "x-cacheable": 60,
+                        "responses": { "204": { "description": "No content" } }
+                    }
+                },
+                "/stats": {
+                    "get": {
+                        "operationId": "getStats",
+                        "responses": {
+                            "200": {
+                                "description": "OK",
+                                "headers": {
+                                    "cache-control": { "example": "no-store" }
+                                }
+                            }
+                        }
+                    }
+                },
+                "/health": {
+                    "get": {
+                        "operationId": "getHealth",
+                        "responses": { "204": { "description": "No content" } }
+                    }
+                }
+            }
+        }"##;
+
+        let gen_ir = GenIr::from(parse(json).unwrap());
+
+        let cache_of = |name: &str| {
+            gen_ir
+                .services
+                .iter()
+                .flat_map(|service| &service.operations)
+                .find(|op| op.name.camel == name)
+                .unwrap()
+                .cache
+                .clone()
+        };
+        assert_eq!(cache_of("listPets"), Some(CacheHint { max_age: Some(300) }));
+        assert_eq!(cache_of("getPet"), Some(CacheHint { max_age: Some(60) }));
+        assert_eq!(cache_of("createPet"), None);
+        assert_eq!(cache_of("getStats"), None);
+        assert_eq!(cache_of("getHealth"), None);
+        assert!(gen_ir.has_cacheable_operations());
+    }
+
+    #[test]
+    fn test_sensitive_fields() {
+        let json = r##"{
+            "openapi": "3.1.0",
+            "info": { "title": "Test API", "version": "1.0.0" },
+            "paths": {},
+            "components": {
+                "schemas": {
+                    "Login": {
+                        "type": "object",
+                        "properties": {
+                            "username": { "type": "string" },
+                            "password": { "type": "string", "format": "password" },
+                            "ssn": { "type": "string", "x-sensitive": true }
+                        }
+                    }
+                }
+            }
+        }"##;
+
+        let gen_ir = GenIr::from(parse(json).unwrap());
+
+        let TypeKind::Struct { fields, .. } = &gen_ir.types[&StableId::new("Login")].kind else {
+            panic!("expected struct");
+        };
+        let sensitive: Vec<&str> = fields
+            .iter()
+            .filter(|f| f.sensitive)
+            .map(|f| f.wire_name.as_str())
+            .collect();
+        assert_eq!(sensitive, vec!["password", "ssn"]);
+    }
+
+    #[test]
+    fn test_coerce_number_extension() {
+        let json = r##"{
+            "openapi": "3.1.0",
+            "info": { "title": "Test API", "version": "1.0.0" },
+            "paths": {},
+            "components": {
+                "schemas": {
+                    "Quote": {
+                        "type": "object",
+                        "properties": {
+                            "price": { "type": "number", "x-coerce-number": true },
+                            "volume": { "type": "integer" }
+                        }
+                    }
+                }
+            }
+        }"##;
+
+        let gen_ir = GenIr::from(parse(json).unwrap());
+
+        let TypeKind::Struct { fields, .. } = &gen_ir.types[&StableId::new("Quote")].kind else {
+            panic!("expected struct");
+        };
+        let coerced: Vec<&str> = fields
+            .iter()
+            .filter(|f| f.coerce_number)
+            .map(|f| f.wire_name.as_str())
+            .collect();
+        assert_eq!(coerced, vec!["price"]);
+    }
+
+    #[test]
+    fn test_time_format_extensions() {
+        let json = r##"{
+            "openapi": "3.1.0",
+            "info": { "title": "Test API", "version": "1.0.0" },
+            "paths": {},
+            "components": {
+                "schemas": {
+                    "Event": {
+                        "type": "object",
+                        "properties": {
+                            "at": { "type": "string", "format": "date-time" },
+                            "createdAt": { "type": "integer", "x-time-format": "unix" },
+                            "day": { "type": "string", "format": "date", "x-format-pattern": "%d/%m/%Y" },
+                            "count": { "type": "integer" }
+                        }
+                    }
+                }
+            }
+        }"##;
+
+        let gen_ir = GenIr::from(parse(json).unwrap());
+
+        let TypeKind::Struct { fields, .. } = &gen_ir.types[&StableId::new("Event")].kind else {
+            panic!("expected struct");
+        };
+        let field = |name: &str| fields.iter().find(|f| f.wire_name == name).unwrap();
+
+        assert_eq!(field("at").time_format, None);
+        assert_eq!(
+            field("createdAt").time_format,
+            Some(TimeFormat::UnixSeconds)
+        );
+        assert!(matches!(
+            field("createdAt").ty.target,
+            StableId::Primitive(Primitive::DateTime)
+        ));
+        assert_eq!(
+            field("day").time_format,
+            Some(TimeFormat::Pattern("%d/%m/%Y".to_string()))
+        );
+        assert!(matches!(
+            field("count").ty.target,
+            StableId::Primitive(Primitive::I64 | Primitive::I32)
+        ));
+    }
+
+    #[test]
+    fn test_fallbacks() {
+        let json = r##"{
+            "openapi": "3.1.0",
+            "info": { "title": "Test API", "version": "1.0.0" },
+            "paths": {},
+            "components": {
+                "schemas": {
+                    "Pet": {
+                        "type": "object",
+                        "properties": {
+                            "name": { "type": "string" },
+                            "extra": {},
+                            "tags": { "type": "array" },
+                            "size": { "type": ["string", "integer"] },
+                            "owner": { "$ref": "#/components/schemas/Owner" }
+                        }
+                    }
+                }
+            }
+        }"##;
+
+        let gen_ir = GenIr::from(parse(json).unwrap());
+
+        let fallback = |pointer: &str| {
+            gen_ir
+                .fallbacks
+                .iter()
+                .find(|f| f.pointer == pointer)
+                .map(|f| f.reason)
+        };
+        let base = "#/components/schemas/Pet/properties";
+        assert_eq!(fallback(&format!("{base}/name")), None);
+        assert_eq!(
+            fallback(&format!("{base}/extra")),
+            Some(FallbackReason::Untyped)
+        );
+        assert_eq!(
+            fallback(&format!("{base}/tags")),
+            Some(FallbackReason::MissingItems)
+        );
+        assert_eq!(
+            fallback(&format!("{base}/size")),
+            Some(FallbackReason::MixedTypes)
+        );
+        assert_eq!(
+            fallback(&format!("{base}/owner")),
+            Some(FallbackReason::UnresolvedRef)
+        );
+    }
 }
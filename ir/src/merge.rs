@@ -0,0 +1,610 @@
+//! Building one [`GenIr`] from several OpenAPI documents.
+//!
+//! Platforms split into one spec per service can still get a single SDK.
+//! The documents are merged before conversion, so the rest of the pipeline
+//! sees an ordinary spec:
+//!
+//! - components with the same name and the same definition are shared;
+//!   differing ones are prefixed with their document's title
+//!   (`Error` becomes `BillingError` and `UsersError`), `$ref`s and
+//!   discriminator mappings included. Components that only differ through
+//!   the refs renamed this way are renamed as well, and a prefixed name
+//!   that is already taken gets a number (`UsersError2`)
+//! - operationIds used by more than one document are prefixed the same way
+//!   (`listItems` becomes `UsersListItems`), links included
+//! - paths, webhooks, tags and servers are unioned; on a clash the earlier
+//!   document wins
+//! - security schemes are unioned, and each document's default `security`
+//!   is copied onto its own operations so it doesn't leak into the others
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde_json::{Map, Value};
+
+use crate::gen_ir::{CanonicalName, GenIr};
+
+const METHODS: &[&str] = &[
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// Keywords whose value maps user-chosen names to objects, so its keys are
+/// never keywords themselves.
+const NAME_MAPS: &[&str] = &[
+    "properties",
+    "patternProperties",
+    "dependentSchemas",
+    "$defs",
+    "definitions",
+    "paths",
+    "webhooks",
+    "schemas",
+    "responses",
+    "parameters",
+    "examples",
+    "requestBodies",
+    "headers",
+    "securitySchemes",
+    "links",
+    "callbacks",
+    "pathItems",
+    "content",
+    "encoding",
+    "variables",
+];
+
+/// Keywords whose value is literal data rather than part of the document.
+const LITERALS: &[&str] = &["example", "default", "const", "enum", "value"];
+
+impl GenIr {
+    /// Merge several specs into one IR. API metadata (title, version) comes
+    /// from the first spec; an empty list is an error.
+    pub fn from_specs(specs: Vec<oas3::Spec>) -> Result<Self, parser::error::ParserError> {
+        let documents = specs
+            .into_iter()
+            .map(serde_json::to_value)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(GenIr::from(parser::parse_value(merge(documents))?))
+    }
+}
+
+fn merge(mut documents: Vec<Value>) -> Value {
+    let prefixes = prefixes(&documents);
+    // Renaming a component changes the refs to it, which can make components
+    // that were equal differ, so repeat until no shared name is defined
+    // differently
+    loop {
+        let collisions = colliding_components(&documents);
+        if collisions.is_empty() {
+            break;
+        }
+        for (kind, name) in collisions {
+            for index in 0..documents.len() {
+                if documents[index]
+                    .pointer(&component_pointer(&kind, &name))
+                    .is_none()
+                {
+                    continue;
+                }
+                let renamed = free_name(&format!("{}{}", prefixes[index], name), |candidate| {
+                    documents.iter().any(|document| {
+                        document
+                            .pointer(&component_pointer(&kind, candidate))
+                            .is_some()
+                    })
+                });
+                rename_component(&mut documents[index], &kind, &name, &renamed);
+            }
+        }
+    }
+    rename_shared_operation_ids(&mut documents, &prefixes);
+    for document in &mut documents {
+        push_down_security(document);
+    }
+
+    let mut documents = documents.into_iter();
+    // parse_value rejects null, so no specs is reported as a parse error
+    let Some(mut merged) = documents.next() else {
+        return Value::Null;
+    };
+    for document in documents {
+        for key in ["paths", "webhooks"] {
+            if let Some(Value::Object(paths)) = document.get(key) {
+                let target = object_entry(&mut merged, key);
+                for (path, item) in paths {
+                    match target.get_mut(path) {
+                        Some(Value::Object(existing)) => {
+                            if let Value::Object(item) = item {
+                                for (method, operation) in item {
+                                    existing
+                                        .entry(method.clone())
+                                        .or_insert_with(|| operation.clone());
+                                }
+                            }
+                        }
+                        _ => {
+                            target.insert(path.clone(), item.clone());
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(Value::Object(components)) = document.get("components") {
+            let target = object_entry(&mut merged, "components");
+            for (kind, entries) in components {
+                let Value::Object(entries) = entries else {
+                    continue;
+                };
+                let Value::Object(existing) = target
+                    .entry(kind.clone())
+                    .or_insert_with(|| Value::Object(Map::new()))
+                else {
+                    continue;
+                };
+                for (name, entry) in entries {
+                    existing
+                        .entry(name.clone())
+                        .or_insert_with(|| entry.clone());
+                }
+            }
+        }
+
+        for (key, identity) in [("servers", "url"), ("tags", "name")] {
+            if let Some(Value::Array(items)) = document.get(key) {
+                let Value::Object(root) = &mut merged else {
+                    break;
+                };
+                let Value::Array(existing) =
+                    root.entry(key).or_insert_with(|| Value::Array(Vec::new()))
+                else {
+                    continue;
+                };
+                for item in items {
+                    if !existing
+                        .iter()
+                        .any(|other| other.get(identity) == item.get(identity))
+                    {
+                        existing.push(item.clone());
+                    }
+                }
+            }
+        }
+    }
+    merged
+}
+
+/// Prefix used to namespace each document's colliding components: its
+/// title in PascalCase, or `Spec<n>` when titles are missing or repeat.
+fn prefixes(documents: &[Value]) -> Vec<String> {
+    let mut seen = BTreeSet::new();
+    documents
+        .iter()
+        .enumerate()
+        .map(|(index, document)| {
+            let title = document
+                .pointer("/info/title")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            let prefix = CanonicalName::from_string(title).pascal;
+            if prefix.is_empty() || !seen.insert(prefix.clone()) {
+                format!("Spec{}", index + 1)
+            } else {
+                prefix
+            }
+        })
+        .collect()
+}
+
+/// `(kind, name)` of components defined differently by two documents.
+/// Security schemes are referenced by name rather than `$ref`, so they are
+/// never renamed.
+fn colliding_components(documents: &[Value]) -> Vec<(String, String)> {
+    let mut definitions: BTreeMap<(String, String), Vec<&Value>> = BTreeMap::new();
+    for document in documents {
+        let Some(Value::Object(components)) = document.get("components") else {
+            continue;
+        };
+        for (kind, entries) in components {
+            if kind == "securitySchemes" {
+                continue;
+            }
+            if let Value::Object(entries) = entries {
+                for (name, entry) in entries {
+                    definitions
+                        .entry((kind.clone(), name.clone()))
+                        .or_default()
+                        .push(entry);
+                }
+            }
+        }
+    }
+    definitions
+        .into_iter()
+        .filter(|(_, entries)| entries.iter().any(|entry| *entry != entries[0]))
+        .map(|(key, _)| key)
+        .collect()
+}
+
+/// JSON pointer to a component.
+fn component_pointer(kind: &str, name: &str) -> String {
+    format!(
+        "/components/{}/{}",
+        kind,
+        name.replace('~', "~0").replace('/', "~1")
+    )
+}
+
+/// `candidate`, or the first of `candidate2`, `candidate3`, … that isn't
+/// `taken`.
+fn free_name(candidate: &str, taken: impl Fn(&str) -> bool) -> String {
+    if !taken(candidate) {
+        return candidate.to_string();
+    }
+    (2..)
+        .map(|n| format!("{}{}", candidate, n))
+        .find(|name| !taken(name))
+        .expect("some numbered name is free")
+}
+
+fn rename_component(document: &mut Value, kind: &str, from: &str, to: &str) {
+    let Some(Value::Object(entries)) = document.pointer_mut(&format!("/components/{}", kind))
+    else {
+        return;
+    };
+    let Some(entry) = entries.remove(from) else {
+        return;
+    };
+    entries.insert(to.to_string(), entry);
+    rewrite_refs(document, kind, from, to);
+}
+
+/// Point `$ref`s and discriminator mappings that target the component
+/// `kind`/`from`, or something inside it, at `to`.
+fn rewrite_refs(document: &mut Value, kind: &str, from: &str, to: &str) {
+    let old = format!("#/components/{}/{}", kind, from);
+    let new = format!("#/components/{}/{}", kind, to);
+    let rewrite = |target: &mut String| {
+        if *target == old {
+            *target = new.clone();
+        } else if let Some(rest) = target.strip_prefix(&old)
+            && rest.starts_with('/')
+        {
+            *target = format!("{}{}", new, rest);
+        }
+    };
+    visit_objects(document, &mut |object| {
+        if let Some(Value::String(target)) = object.get_mut("$ref") {
+            rewrite(target);
+        }
+        if let Some(Value::Object(mapping)) = object.get_mut("mapping") {
+            for target in mapping.values_mut() {
+                let Value::String(target) = target else {
+                    continue;
+                };
+                // Mappings may name a schema instead of referencing it
+                if kind == "schemas" && *target == from {
+                    *target = to.to_string();
+                } else {
+                    rewrite(target);
+                }
+            }
+        }
+    });
+}
+
+/// Prefix operationIds that more than one document uses with the owning
+/// document's prefix, so each operation keeps a method of its own.
+fn rename_shared_operation_ids(documents: &mut [Value], prefixes: &[String]) {
+    let mut owners: BTreeMap<String, BTreeSet<usize>> = BTreeMap::new();
+    for (index, document) in documents.iter_mut().enumerate() {
+        visit_operations(document, &mut |operation| {
+            if let Some(Value::String(id)) = operation.get("operationId") {
+                owners.entry(id.clone()).or_default().insert(index);
+            }
+        });
+    }
+    let mut taken = owners.keys().cloned().collect::<BTreeSet<_>>();
+    for (id, owners) in &owners {
+        if owners.len() < 2 {
+            continue;
+        }
+        for &index in owners {
+            let mut chars = id.chars();
+            let capitalized = chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default();
+            let renamed = free_name(&format!("{}{}", prefixes[index], capitalized), |name| {
+                taken.contains(name)
+            });
+            taken.insert(renamed.clone());
+            // Operations and the links that name them
+            visit_objects(&mut documents[index], &mut |object| {
+                if let Some(Value::String(target)) = object.get_mut("operationId")
+                    && *target == *id
+                {
+                    *target = renamed.clone();
+                }
+            });
+        }
+    }
+}
+
+/// Call `f` on every operation in `paths` and `webhooks`.
+fn visit_operations(document: &mut Value, f: &mut dyn FnMut(&mut Map<String, Value>)) {
+    for key in ["paths", "webhooks"] {
+        let Some(Value::Object(paths)) = document.get_mut(key) else {
+            continue;
+        };
+        for item in paths.values_mut() {
+            for method in METHODS {
+                if let Some(Value::Object(operation)) = item.get_mut(*method) {
+                    f(operation);
+                }
+            }
+        }
+    }
+}
+
+/// Call `f` on every object that is part of the document's structure,
+/// skipping literal data such as examples and defaults, which may look
+/// like refs without being ones.
+fn visit_objects(value: &mut Value, f: &mut dyn FnMut(&mut Map<String, Value>)) {
+    visit(value, false, f);
+
+    fn visit(value: &mut Value, names: bool, f: &mut dyn FnMut(&mut Map<String, Value>)) {
+        match value {
+            Value::Array(items) => {
+                for item in items {
+                    visit(item, false, f);
+                }
+            }
+            Value::Object(map) => {
+                if !names {
+                    f(map);
+                }
+                for (key, value) in map.iter_mut() {
+                    if names {
+                        visit(value, false, f);
+                    } else if LITERALS.contains(&key.as_str())
+                        // 3.1 schemas list examples; media types name them
+                        || (key == "examples" && value.is_array())
+                    {
+                        continue;
+                    } else {
+                        visit(value, NAME_MAPS.contains(&key.as_str()), f);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Move the document-wide `security` onto operations that don't set their own.
+fn push_down_security(document: &mut Value) {
+    let Some(root) = document.as_object_mut() else {
+        return;
+    };
+    let Some(security) = root.remove("security") else {
+        return;
+    };
+    for key in ["paths", "webhooks"] {
+        let Some(Value::Object(paths)) = root.get_mut(key) else {
+            continue;
+        };
+        for item in paths.values_mut() {
+            for method in METHODS {
+                if let Some(Value::Object(operation)) = item.get_mut(*method) {
+                    operation
+                        .entry("security")
+                        .or_insert_with(|| security.clone());
+                }
+            }
+        }
+    }
+}
+
+fn object_entry<'a>(document: &'a mut Value, key: &str) -> &'a mut Map<String, Value> {
+    let root = document
+        .as_object_mut()
+        .expect("an OpenAPI document is an object");
+    let entry = root.entry(key).or_insert_with(|| Value::Object(Map::new()));
+    if !entry.is_object() {
+        *entry = Value::Object(Map::new());
+    }
+    entry.as_object_mut().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(json: &str) -> oas3::Spec {
+        parser::parse(json).unwrap()
+    }
+
+    #[test]
+    fn test_from_specs() {
+        let users = spec(
+            r##"{
+                "openapi": "3.1.0",
+                "info": { "title": "Users", "version": "1.0.0" },
+                "servers": [{ "url": "https://users.example.com" }],
+                "security": [{ "bearer": [] }],
+                "paths": {
+                    "/users": {
+                        "get": {
+                            "operationId": "listUsers",
+                            "responses": {
+                                "400": {
+                                    "description": "bad",
+                                    "content": { "application/json": { "schema": { "$ref": "#/components/schemas/Error" } } }
+                                }
+                            }
+                        }
+                    }
+                },
+                "components": {
+                    "schemas": {
+                        "Error": { "type": "object", "properties": { "message": { "type": "string" } } },
+                        "Page": { "type": "object", "properties": { "next": { "type": "string" } } }
+                    },
+                    "securitySchemes": { "bearer": { "type": "http", "scheme": "bearer" } }
+                }
+            }"##,
+        );
+        let billing = spec(
+            r##"{
+                "openapi": "3.1.0",
+                "info": { "title": "Billing", "version": "2.0.0" },
+                "servers": [{ "url": "https://billing.example.com" }],
+                "paths": {
+                    "/invoices": {
+                        "get": {
+                            "operationId": "listInvoices",
+                            "responses": {
+                                "400": {
+                                    "description": "bad",
+                                    "content": { "application/json": { "schema": { "$ref": "#/components/schemas/Error" } } }
+                                }
+                            }
+                        }
+                    }
+                },
+                "components": {
+                    "schemas": {
+                        "Error": { "type": "object", "properties": { "code": { "type": "integer" } } },
+                        "Page": { "type": "object", "properties": { "next": { "type": "string" } } }
+                    },
+                    "securitySchemes": { "apiKey": { "type": "apiKey", "in": "header", "name": "X-Key" } }
+                }
+            }"##,
+        );
+
+        let ir = GenIr::from_specs(vec![users, billing]).unwrap();
+        assert_eq!(ir.api.title, "Users");
+
+        let type_names = ir
+            .types
+            .values()
+            .map(|decl| decl.name.canonical.as_str())
+            .collect::<BTreeSet<_>>();
+        assert!(type_names.contains("UsersError"));
+        assert!(type_names.contains("BillingError"));
+        assert!(type_names.contains("Page"));
+        assert!(!type_names.contains("Error"));
+
+        assert_eq!(ir.operation_count(), 2);
+        assert_eq!(ir.auth_schemes.len(), 2);
+        let servers = ir
+            .server_sets
+            .iter()
+            .flat_map(|set| set.urls.iter().map(|url| url.template.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            servers,
+            ["https://users.example.com", "https://billing.example.com"]
+        );
+    }
+
+    #[test]
+    fn test_renames_follow_refs_and_avoid_taken_names() {
+        let page = serde_json::json!({
+            "properties": { "error": { "$ref": "#/components/schemas/Error" } },
+            "example": { "$ref": "#/components/schemas/Error" }
+        });
+        let users = serde_json::json!({
+            "info": { "title": "Users" },
+            "paths": {
+                "/users": {
+                    "get": {
+                        "operationId": "list",
+                        "responses": {
+                            "200": { "description": "ok", "links": { "next": { "operationId": "list" } } }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "Error": { "properties": { "message": { "type": "string" } } },
+                    "UsersError": { "type": "string" },
+                    "Page": page
+                }
+            }
+        });
+        let billing = serde_json::json!({
+            "info": { "title": "Billing" },
+            "paths": { "/invoices": { "get": { "operationId": "list" } } },
+            "components": {
+                "schemas": {
+                    "Error": { "properties": { "code": { "type": "integer" } } },
+                    "Page": page
+                }
+            }
+        });
+
+        let merged = merge(vec![users, billing]);
+        let schemas = merged
+            .pointer("/components/schemas")
+            .and_then(Value::as_object)
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect::<BTreeSet<_>>();
+        // `Page` only differs once `Error` is renamed; `UsersError` was taken
+        assert_eq!(
+            schemas,
+            BTreeSet::from([
+                "BillingError",
+                "BillingPage",
+                "UsersError",
+                "UsersError2",
+                "UsersPage"
+            ])
+        );
+        assert_eq!(
+            merged.pointer("/components/schemas/UsersPage/properties/error/$ref"),
+            Some(&serde_json::json!("#/components/schemas/UsersError2"))
+        );
+        // Examples are data, not refs
+        assert_eq!(
+            merged.pointer("/components/schemas/UsersPage/example/$ref"),
+            Some(&serde_json::json!("#/components/schemas/Error"))
+        );
+
+        assert_eq!(
+            merged.pointer("/paths/~1users/get/operationId"),
+            Some(&serde_json::json!("UsersList"))
+        );
+        assert_eq!(
+            merged.pointer("/paths/~1users/get/responses/200/links/next/operationId"),
+            Some(&serde_json::json!("UsersList"))
+        );
+        assert_eq!(
+            merged.pointer("/paths/~1invoices/get/operationId"),
+            Some(&serde_json::json!("BillingList"))
+        );
+    }
+
+    #[test]
+    fn test_security_stays_with_its_document() {
+        let mut document = serde_json::json!({
+            "security": [{ "bearer": [] }],
+            "paths": {
+                "/a": { "get": {}, "post": { "security": [] } }
+            }
+        });
+        push_down_security(&mut document);
+        assert!(document.get("security").is_none());
+        assert_eq!(
+            document.pointer("/paths/~1a/get/security"),
+            Some(&serde_json::json!([{ "bearer": [] }]))
+        );
+        assert_eq!(
+            document.pointer("/paths/~1a/post/security"),
+            Some(&serde_json::json!([]))
+        );
+    }
+}
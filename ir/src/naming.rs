@@ -0,0 +1,390 @@
+//! Naming policy shared by the IR builder and generators.
+//!
+//! `CanonicalName::from_string` only does case conversion. A [`NamingPolicy`]
+//! layers org-wide type prefixes/suffixes and reserved-word escaping for the
+//! target language on top of it.
+
+use std::collections::BTreeSet;
+
+use serde::Serialize;
+
+use crate::gen_ir::{CanonicalName, GenIr, Map, StableId, TypeKind};
+
+/// Keywords that cannot be used as bare identifiers in Rust.
+pub const RUST_KEYWORDS: &[&str] = &[
+    "as", "break", "const", "continue", "crate", "else", "enum", "extern", "false", "fn", "for",
+    "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return",
+    "self", "Self", "static", "struct", "super", "trait", "true", "type", "unsafe", "use", "where",
+    "while", "async", "await", "dyn", "abstract", "become", "box", "do", "final", "macro",
+    "override", "priv", "typeof", "unsized", "virtual", "yield", "try", "_",
+];
+
+/// Rust keywords that cannot be written as raw identifiers (`r#self` is invalid).
+const RUST_NON_RAW_KEYWORDS: &[&str] = &["crate", "self", "Self", "super", "_"];
+
+/// Reserved words that cannot be used as bare identifiers in TypeScript.
+pub const TYPESCRIPT_KEYWORDS: &[&str] = &[
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "import",
+    "in",
+    "instanceof",
+    "new",
+    "null",
+    "return",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "var",
+    "void",
+    "while",
+    "with",
+    "as",
+    "implements",
+    "interface",
+    "let",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "static",
+    "yield",
+    "await",
+    "type",
+];
+
+/// Reserved words of a target language ("rust", "rust-axum", "typescript").
+/// Unknown languages have none.
+pub fn language_keywords(language: &str) -> &'static [&'static str] {
+    match language {
+        "rust" | "rust-axum" => RUST_KEYWORDS,
+        "typescript" | "ts" => TYPESCRIPT_KEYWORDS,
+        _ => &[],
+    }
+}
+
+/// How declared names are adjusted before they reach templates.
+#[derive(Debug, Clone, Serialize)]
+pub struct NamingPolicy {
+    /// Prepended to every declared type name (e.g. "Acme").
+    pub type_prefix: String,
+    /// Appended to every declared type name (e.g. "Dto").
+    pub type_suffix: String,
+    /// Extra words treated as reserved in addition to the target language's keywords.
+    pub reserved_words: BTreeSet<String>,
+    /// Appended to reserved identifiers when the language has no raw identifier syntax.
+    pub escape_suffix: String,
+}
+
+impl Default for NamingPolicy {
+    fn default() -> Self {
+        Self {
+            type_prefix: String::new(),
+            type_suffix: String::new(),
+            reserved_words: BTreeSet::new(),
+            escape_suffix: "_".to_string(),
+        }
+    }
+}
+
+impl NamingPolicy {
+    /// Whether this policy changes declared type names at all.
+    pub fn renames_types(&self) -> bool {
+        !self.type_prefix.is_empty() || !self.type_suffix.is_empty()
+    }
+
+    /// Canonical name of a declared type with the prefix/suffix applied.
+    pub fn type_name(&self, raw: &str) -> CanonicalName {
+        if !self.renames_types() {
+            return CanonicalName::from_string(raw);
+        }
+        let pascal = CanonicalName::from_string(raw).pascal;
+        CanonicalName::from_string(format!(
+            "{}{}{}",
+            self.type_prefix, pascal, self.type_suffix
+        ))
+    }
+
+    /// Whether `ident` is reserved for `language` under this policy.
+    pub fn is_reserved(&self, ident: &str, language: &str) -> bool {
+        language_keywords(language).contains(&ident) || self.reserved_words.contains(ident)
+    }
+
+    /// Escape `ident` if it is reserved for `language`.
+    ///
+    /// Rust keywords become raw identifiers (`r#type`) where allowed; everything
+    /// else gets `escape_suffix` appended (`default` -> `default_`).
+    pub fn escape(&self, ident: &str, language: &str) -> String {
+        if !self.is_reserved(ident, language) {
+            return ident.to_string();
+        }
+        let is_rust = matches!(language, "rust" | "rust-axum");
+        if is_rust && RUST_KEYWORDS.contains(&ident) && !RUST_NON_RAW_KEYWORDS.contains(&ident) {
+            format!("r#{}", ident)
+        } else {
+            format!("{}{}", ident, self.escape_suffix)
+        }
+    }
+}
+
+/// Two declared types that a [`NamingPolicy`] gives the same name, such as
+/// `pet_status` and `PetStatus` under a type prefix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NameCollision {
+    /// The name both types would get.
+    pub name: StableId,
+    /// The types that would share it, in declaration-map order.
+    pub types: (StableId, StableId),
+}
+
+impl std::fmt::Display for NameCollision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "types `{}` and `{}` would both be named `{}`",
+            self.types.0, self.types.1, self.name
+        )
+    }
+}
+
+impl std::error::Error for NameCollision {}
+
+impl GenIr {
+    /// Apply `policy` to declared type names. Ids and every reference to them are
+    /// renamed together so the IR stays consistent.
+    ///
+    /// Fails, changing nothing, if two types would end up with the same name.
+    pub fn apply_naming(&mut self, policy: &NamingPolicy) -> Result<(), NameCollision> {
+        if !policy.renames_types() {
+            return Ok(());
+        }
+
+        let mut names: Map<StableId, (StableId, CanonicalName)> = Map::new();
+        for id in self.types.keys() {
+            let (new_id, name) = match id {
+                StableId::Named(raw) => {
+                    let name = policy.type_name(raw);
+                    (StableId::new(name.pascal.clone()), name)
+                }
+                StableId::Primitive(_) => (id.clone(), self.types[id].name.clone()),
+            };
+            if let Some((first, _)) = names.get(&new_id) {
+                return Err(NameCollision {
+                    name: new_id,
+                    types: (first.clone(), id.clone()),
+                });
+            }
+            names.insert(new_id, (id.clone(), name));
+        }
+
+        let mut renames: Map<StableId, StableId> = Map::new();
+        let mut types = std::mem::take(&mut self.types);
+        for (new_id, (id, name)) in names {
+            let mut decl = types.remove(&id).expect("named above");
+            decl.name = name;
+            decl.id = new_id.clone();
+            if new_id != id {
+                renames.insert(id, new_id.clone());
+            }
+            self.types.insert(new_id, decl);
+        }
+        self.retarget_types(&renames);
+        Ok(())
+    }
+
+    /// Rename the named type `from` to `to`, updating every reference to
+    /// it. Returns `false`, changing nothing, if there is no type `from`.
+    pub fn rename_type(&mut self, from: &str, to: &str) -> bool {
+        let from = StableId::new(from);
+        let Some(mut decl) = self.types.remove(&from) else {
+            return false;
+        };
+        decl.name = CanonicalName::from_string(to);
+        decl.id = StableId::new(decl.name.pascal.clone());
+        let renames = Map::from([(from, decl.id.clone())]);
+        self.types.insert(decl.id.clone(), decl);
+        self.retarget_types(&renames);
+        true
+    }
+
+    /// Point discriminator mappings and type references at renamed types.
+    fn retarget_types(&mut self, renames: &Map<StableId, StableId>) {
+        for decl in self.types.values_mut() {
+            if let TypeKind::Struct {
+                discriminator: Some(discriminator),
+                ..
+            } = &mut decl.kind
+            {
+                for target in discriminator.mapping.values_mut() {
+                    if let Some(renamed) = renames.get(target) {
+                        *target = renamed.clone();
+                    }
+                }
+            }
+        }
+
+        self.visit_type_refs_mut(|ty| {
+            if let Some(renamed) = renames.get(&ty.target) {
+                ty.target = renamed.clone();
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::parse;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_escape_reserved_words() {
+        let policy = NamingPolicy::default();
+        assert_eq!(policy.escape("type", "rust"), "r#type");
+        assert_eq!(policy.escape("async", "rust"), "r#async");
+        assert_eq!(policy.escape("self", "rust"), "self_");
+        assert_eq!(policy.escape("default", "typescript"), "default_");
+        assert_eq!(policy.escape("default", "rust"), "default");
+        assert_eq!(policy.escape("name", "typescript"), "name");
+
+        let policy = NamingPolicy {
+            reserved_words: BTreeSet::from(["client".to_string()]),
+            escape_suffix: "Value".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(policy.escape("client", "rust"), "clientValue");
+        assert_eq!(policy.escape("class", "typescript"), "classValue");
+    }
+
+    #[test]
+    fn test_apply_naming_renames_types_and_refs() {
+        let json = r##"{
+            "openapi": "3.0.0",
+            "info": { "title": "Test API", "version": "1.0.0" },
+            "paths": {},
+            "components": {
+                "schemas": {
+                    "Pet": {
+                        "type": "object",
+                        "properties": {
+                            "owner": { "$ref": "#/components/schemas/owner" }
+                        }
+                    },
+                    "owner": {
+                        "type": "object",
+                        "properties": { "name": { "type": "string" } }
+                    }
+                }
+            }
+        }"##;
+
+        let policy = NamingPolicy {
+            type_prefix: "Acme".to_string(),
+            type_suffix: "Dto".to_string(),
+            ..Default::default()
+        };
+        let ir = GenIr::from_spec_with_naming(parse(json).unwrap(), &policy).unwrap();
+
+        let ids: Vec<_> = ir.types.keys().cloned().collect();
+        assert_eq!(
+            ids,
+            vec![StableId::new("AcmeOwnerDto"), StableId::new("AcmePetDto")]
+        );
+
+        let pet = &ir.types[&StableId::new("AcmePetDto")];
+        assert_eq!(pet.name.pascal, "AcmePetDto");
+        let TypeKind::Struct { fields, .. } = &pet.kind else {
+            panic!("expected struct");
+        };
+        assert_eq!(fields[0].ty.target, StableId::new("AcmeOwnerDto"));
+    }
+
+    #[test]
+    fn test_apply_naming_rejects_collisions() {
+        let json = r##"{
+            "openapi": "3.0.0",
+            "info": { "title": "Test API", "version": "1.0.0" },
+            "paths": {},
+            "components": {
+                "schemas": {
+                    "PetStatus": { "type": "string", "enum": ["sold"] },
+                    "pet_status": { "type": "string", "enum": ["available"] }
+                }
+            }
+        }"##;
+
+        let policy = NamingPolicy {
+            type_prefix: "Acme".to_string(),
+            ..Default::default()
+        };
+        let mut ir = GenIr::from(parse(json).unwrap());
+        let before: Vec<_> = ir.types.keys().cloned().collect();
+
+        let collision = ir.apply_naming(&policy).unwrap_err();
+        assert_eq!(collision.name, StableId::new("AcmePetStatus"));
+        assert_eq!(
+            collision.to_string(),
+            "types `PetStatus` and `pet_status` would both be named `AcmePetStatus`"
+        );
+        let after: Vec<_> = ir.types.keys().cloned().collect();
+        assert_eq!(after, before);
+    }
+
+    #[test]
+    fn test_rename_type() {
+        let json = r##"{
+            "openapi": "3.0.0",
+            "info": { "title": "Test API", "version": "1.0.0" },
+            "paths": {},
+            "components": {
+                "schemas": {
+                    "Pet": {
+                        "type": "object",
+                        "properties": {
+                            "owner": { "$ref": "#/components/schemas/Owner" }
+                        }
+                    },
+                    "Owner": {
+                        "type": "object",
+                        "properties": { "name": { "type": "string" } }
+                    }
+                }
+            }
+        }"##;
+
+        let mut ir = GenIr::from(parse(json).unwrap());
+        assert!(ir.rename_type("Owner", "person"));
+        assert!(!ir.rename_type("Owner", "Human"));
+
+        let person = &ir.types[&StableId::new("Person")];
+        assert_eq!(person.id, StableId::new("Person"));
+        assert_eq!(person.name.pascal, "Person");
+        assert!(!ir.types.contains_key(&StableId::new("Owner")));
+        let TypeKind::Struct { fields, .. } = &ir.types[&StableId::new("Pet")].kind else {
+            panic!("expected struct");
+        };
+        assert_eq!(fields[0].ty.target, StableId::new("Person"));
+    }
+}
@@ -0,0 +1,851 @@
+//! Building a [`GenIr`] from a Postman collection (v2.0 or v2.1).
+//!
+//! Teams without a spec often have a collection. It is converted into an
+//! OpenAPI 3.1 document, best effort, which then goes through the ordinary
+//! pipeline:
+//!
+//! - every request becomes an operation; its name is the `operationId` and
+//!   the folder it is in its tag. Requests with the same method and path
+//!   (variants of one endpoint) become one operation: the first one's name
+//!   and body are kept, and parameters and responses are unioned
+//! - `:id` path segments become path parameters, enabled query entries and
+//!   headers optional parameters (once per name). `{{var}}`s of collection
+//!   variables in the path are filled in; other `{{var}}` segments become
+//!   path parameters
+//! - JSON bodies of requests and saved responses become schemas inferred from
+//!   the example; requests without a saved response get an empty `200`
+//! - `bearer`, `basic`, `apikey` and `oauth2` auth become security schemes,
+//!   inherited from the collection and folders like in Postman
+//! - collection variables fill in `{{var}}`s in hosts, giving the servers
+//!
+//! Types are only as good as the examples: fields missing from them are
+//! missing from the types, and nothing is `required`.
+
+use std::collections::BTreeMap;
+
+use serde_json::{Map, Value, json};
+
+use crate::gen_ir::{CanonicalName, GenIr};
+use parser::error::ParserError;
+use parser::validate::Violation;
+
+/// Headers that describe the body or carry credentials rather than being
+/// parameters of the operation.
+const SKIPPED_HEADERS: &[&str] = &["accept", "authorization", "content-length", "content-type"];
+
+impl GenIr {
+    /// Convert a Postman collection (JSON) into an IR. See [`postman`](crate::postman).
+    pub fn from_postman(input: &str) -> Result<Self, ParserError> {
+        let collection: Value = serde_json::from_str(input)?;
+        Ok(GenIr::from(parser::parse_value(to_openapi(&collection)?)?))
+    }
+}
+
+/// Convert a Postman collection into an OpenAPI 3.1 document.
+pub fn to_openapi(collection: &Value) -> Result<Value, ParserError> {
+    let Some(items) = collection.get("item").and_then(Value::as_array) else {
+        return Err(ParserError::Invalid(vec![Violation {
+            pointer: String::new(),
+            message: "not a Postman collection: `item` is missing".to_string(),
+        }]));
+    };
+    let info = collection.get("info");
+
+    let mut converter = Converter {
+        variables: variables(collection.get("variable")),
+        ..Converter::default()
+    };
+    let auth = collection.get("auth");
+    if let Some(scheme) = auth.and_then(|auth| converter.security_scheme(auth)) {
+        converter.security = Some(json!([{ scheme: [] }]));
+    }
+    converter.items(items, None, auth);
+
+    let mut document = json!({
+        "openapi": "3.1.0",
+        "info": {
+            "title": info.and_then(|info| info.get("name")).and_then(Value::as_str).unwrap_or("API"),
+            "version": converter.variables.get("version").map_or("1.0.0", String::as_str),
+        },
+        "paths": converter.paths,
+    });
+    if let Some(description) = info.and_then(|info| description(info.get("description"))) {
+        document["info"]["description"] = description.into();
+    }
+    if !converter.servers.is_empty() {
+        document["servers"] = converter
+            .servers
+            .iter()
+            .map(|url| json!({ "url": url }))
+            .collect();
+    }
+    if !converter.tags.is_empty() {
+        document["tags"] = converter.tags.into();
+    }
+    if !converter.schemes.is_empty() {
+        document["components"] = json!({ "securitySchemes": converter.schemes });
+    }
+    if let Some(security) = converter.security {
+        document["security"] = security;
+    }
+    Ok(document)
+}
+
+#[derive(Default)]
+struct Converter {
+    variables: BTreeMap<String, String>,
+    paths: Map<String, Value>,
+    servers: Vec<String>,
+    tags: Vec<Value>,
+    schemes: Map<String, Value>,
+    /// Default requirement, from the collection's auth
+    security: Option<Value>,
+    operation_ids: Vec<String>,
+}
+
+impl Converter {
+    /// Convert the requests in `items`, recursing into folders. `auth` is
+    /// the one inherited from the enclosing folder or collection.
+    fn items(&mut self, items: &[Value], tag: Option<&str>, auth: Option<&Value>) {
+        for item in items {
+            let name = item.get("name").and_then(Value::as_str).unwrap_or("");
+            // Auth of type `inherit` (or none) defers to the parent
+            let own_auth = item
+                .get("auth")
+                .filter(|auth| auth.get("type").and_then(Value::as_str) != Some("inherit"));
+            if let Some(children) = item.get("item").and_then(Value::as_array) {
+                // Folders with the same name share a tag
+                let index = match self.tags.iter().position(|tag| tag["name"] == name) {
+                    Some(index) => index,
+                    None => {
+                        self.tags.push(json!({ "name": name }));
+                        self.tags.len() - 1
+                    }
+                };
+                if let Some(description) = description(item.get("description"))
+                    && self.tags[index].get("description").is_none()
+                {
+                    self.tags[index]["description"] = description.into();
+                }
+                self.items(children, Some(name), own_auth.or(auth));
+            } else if let Some(request) = item.get("request") {
+                let request_auth = request
+                    .get("auth")
+                    .filter(|auth| auth.get("type").and_then(Value::as_str) != Some("inherit"));
+                self.request(
+                    name,
+                    request,
+                    item.get("response"),
+                    tag,
+                    request_auth.or(auth),
+                );
+            }
+        }
+    }
+
+    fn request(
+        &mut self,
+        name: &str,
+        request: &Value,
+        responses: Option<&Value>,
+        tag: Option<&str>,
+        auth: Option<&Value>,
+    ) {
+        // A request may be just its URL
+        let (method, url) = match request {
+            Value::String(url) => ("get".to_string(), Some(Value::String(url.clone()))),
+            _ => (
+                request
+                    .get("method")
+                    .and_then(Value::as_str)
+                    .unwrap_or("GET")
+                    .to_lowercase(),
+                request.get("url").cloned(),
+            ),
+        };
+        let url = url.map(|url| Url::parse(&url)).unwrap_or_default();
+
+        if let Some(origin) = self.origin(&url)
+            && !self.servers.contains(&origin)
+        {
+            self.servers.push(origin);
+        }
+
+        let mut parameters = Vec::new();
+        let mut path = String::new();
+        for segment in &url.path {
+            path.push('/');
+            // Collection variables are constants, not parameters
+            let segment = self.substitute(segment);
+            let segment = segment.trim_matches('/');
+            let parameter = segment
+                .strip_prefix(':')
+                .or_else(|| segment.strip_prefix("{{")?.strip_suffix("}}"));
+            match parameter {
+                Some(parameter) => {
+                    path.push_str(&format!("{{{}}}", parameter));
+                    let variable = url.variables.get(parameter);
+                    let mut param = json!({
+                        "name": parameter,
+                        "in": "path",
+                        "required": true,
+                        "schema": variable
+                            .and_then(|v| v.get("value"))
+                            .map_or_else(|| json!({ "type": "string" }), scalar_schema),
+                    });
+                    if let Some(description) =
+                        variable.and_then(|v| description(v.get("description")))
+                    {
+                        param["description"] = description.into();
+                    }
+                    push_parameter(&mut parameters, param);
+                }
+                None => path.push_str(segment),
+            }
+        }
+        if path.is_empty() {
+            path.push('/');
+        }
+        for (location, entries) in [
+            ("query", url.query.as_slice()),
+            (
+                "header",
+                request
+                    .get("header")
+                    .and_then(Value::as_array)
+                    .map_or(&[][..], Vec::as_slice),
+            ),
+        ] {
+            for entry in entries {
+                let Some(key) = entry.get("key").and_then(Value::as_str) else {
+                    continue;
+                };
+                if entry.get("disabled").and_then(Value::as_bool) == Some(true)
+                    || (location == "header"
+                        && SKIPPED_HEADERS.contains(&key.to_lowercase().as_str()))
+                {
+                    continue;
+                }
+                let mut param = json!({
+                    "name": key,
+                    "in": location,
+                    "required": false,
+                    "schema": entry.get("value").map_or_else(|| json!({ "type": "string" }), scalar_schema),
+                });
+                if let Some(description) = description(entry.get("description")) {
+                    param["description"] = description.into();
+                }
+                // Repeated keys (`?id=1&id=2`) are one parameter
+                push_parameter(&mut parameters, param);
+            }
+        }
+
+        let mut operation = json!({
+            "summary": name,
+            "responses": self.responses(responses),
+        });
+        if let Some(tag) = tag {
+            operation["tags"] = json!([tag]);
+        }
+        if let Some(description) = description(request.get("description")) {
+            operation["description"] = description.into();
+        }
+        if !parameters.is_empty() {
+            operation["parameters"] = parameters.into();
+        }
+        if let Some(body) = request.get("body").and_then(request_body) {
+            operation["requestBody"] = body;
+        }
+        // Only differences from the collection's default are written out
+        let security = auth.map(|auth| match self.security_scheme(auth) {
+            Some(scheme) => json!([{ scheme: [] }]),
+            None => json!([]),
+        });
+        if security != self.security {
+            operation["security"] = security.unwrap_or_else(|| json!([]));
+        }
+
+        if let Some(existing) = self
+            .paths
+            .get_mut(&path)
+            .and_then(|item| item.get_mut(method.as_str()))
+        {
+            merge_operation(existing, operation);
+            return;
+        }
+        operation["operationId"] = self.operation_id(name, &method, &path).into();
+        let item = self
+            .paths
+            .entry(path)
+            .or_insert_with(|| Value::Object(Map::new()));
+        item[method.as_str()] = operation;
+    }
+
+    /// `operationId` from the request name, made unique. Unnamed requests
+    /// are named after their method and path.
+    fn operation_id(&mut self, name: &str, method: &str, path: &str) -> String {
+        let base = if name.trim().is_empty() {
+            CanonicalName::from_string(format!("{} {}", method, path.replace(['{', '}'], ""))).camel
+        } else {
+            CanonicalName::from_string(name).camel
+        };
+        let mut id = base.clone();
+        let mut n = 2;
+        while self.operation_ids.contains(&id) {
+            id = format!("{}{}", base, n);
+            n += 1;
+        }
+        self.operation_ids.push(id.clone());
+        id
+    }
+
+    /// Responses from the saved examples, or an empty `200`.
+    fn responses(&self, examples: Option<&Value>) -> Value {
+        let mut responses = Map::new();
+        for example in examples.and_then(Value::as_array).into_iter().flatten() {
+            let code = example.get("code").and_then(Value::as_u64).unwrap_or(200);
+            let mut response = json!({
+                "description": example
+                    .get("status")
+                    .or_else(|| example.get("name"))
+                    .and_then(Value::as_str)
+                    .unwrap_or("Response"),
+            });
+            let json_body = example
+                .get("body")
+                .and_then(Value::as_str)
+                .and_then(|body| serde_json::from_str::<Value>(body).ok());
+            if let Some(body) = json_body {
+                response["content"] = json!({
+                    "application/json": { "schema": infer_schema(&body), "example": body }
+                });
+            }
+            // The first example of a status wins
+            responses.entry(code.to_string()).or_insert(response);
+        }
+        if responses.is_empty() {
+            responses.insert(
+                "200".to_string(),
+                json!({ "description": "Successful response" }),
+            );
+        }
+        Value::Object(responses)
+    }
+
+    /// Name of the security scheme for a Postman `auth` object, registering
+    /// it. `None` for `noauth` and unsupported types.
+    fn security_scheme(&mut self, auth: &Value) -> Option<String> {
+        let kind = auth.get("type").and_then(Value::as_str)?;
+        let params = auth.get(kind);
+        let (name, scheme) = match kind {
+            "bearer" => (
+                "bearerAuth".to_string(),
+                json!({ "type": "http", "scheme": "bearer" }),
+            ),
+            "basic" => (
+                "basicAuth".to_string(),
+                json!({ "type": "http", "scheme": "basic" }),
+            ),
+            "apikey" => {
+                let key = auth_param(params, "key").unwrap_or_else(|| "X-API-Key".to_string());
+                let location = match auth_param(params, "in").as_deref() {
+                    Some("query") => "query",
+                    _ => "header",
+                };
+                (
+                    format!("{}ApiKey", CanonicalName::from_string(&key).camel),
+                    json!({ "type": "apiKey", "in": location, "name": key }),
+                )
+            }
+            "oauth2" => {
+                let token_url = auth_param(params, "accessTokenUrl")
+                    .map(|url| self.substitute(&url))
+                    .unwrap_or_default();
+                let scopes: Map<String, Value> = auth_param(params, "scope")
+                    .unwrap_or_default()
+                    .split_whitespace()
+                    .map(|scope| (scope.to_string(), Value::String(String::new())))
+                    .collect();
+                let flows = match auth_param(params, "grant_type").as_deref() {
+                    Some("authorization_code") | Some("authorization_code_with_pkce") => json!({
+                        "authorizationCode": {
+                            "authorizationUrl": auth_param(params, "authUrl")
+                                .map(|url| self.substitute(&url))
+                                .unwrap_or_default(),
+                            "tokenUrl": token_url,
+                            "scopes": scopes,
+                        }
+                    }),
+                    _ => json!({
+                        "clientCredentials": { "tokenUrl": token_url, "scopes": scopes }
+                    }),
+                };
+                (
+                    "oauth2".to_string(),
+                    json!({ "type": "oauth2", "flows": flows }),
+                )
+            }
+            _ => return None,
+        };
+        self.schemes.entry(name.clone()).or_insert(scheme);
+        Some(name)
+    }
+
+    /// `protocol://host[:port]` of `url` with variables filled in, if it has
+    /// a host.
+    fn origin(&self, url: &Url) -> Option<String> {
+        let host = self.substitute(url.host.as_deref()?);
+        if host.contains("://") {
+            return Some(host.trim_end_matches('/').to_string());
+        }
+        let mut origin = format!("{}://{}", url.protocol.as_deref().unwrap_or("https"), host);
+        if let Some(port) = &url.port {
+            origin.push_str(&format!(":{}", port));
+        }
+        Some(origin)
+    }
+
+    /// Replace the `{{var}}`s of known collection variables.
+    fn substitute(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        for (key, value) in &self.variables {
+            text = text.replace(&format!("{{{{{}}}}}", key), value);
+        }
+        text
+    }
+}
+
+/// A request URL, from either the string or the object form.
+#[derive(Default)]
+struct Url {
+    protocol: Option<String>,
+    host: Option<String>,
+    port: Option<String>,
+    path: Vec<String>,
+    query: Vec<Value>,
+    /// Path variables (`:id`) by name
+    variables: BTreeMap<String, Value>,
+}
+
+impl Url {
+    fn parse(url: &Value) -> Self {
+        match url {
+            Value::String(raw) => Self::parse_raw(raw),
+            Value::Object(object) => {
+                // Collections exported without the parts only have `raw`
+                let mut parsed = object
+                    .get("raw")
+                    .and_then(Value::as_str)
+                    .map(Self::parse_raw)
+                    .unwrap_or_default();
+                if let Some(protocol) = object.get("protocol").and_then(Value::as_str) {
+                    parsed.protocol = Some(protocol.to_string());
+                }
+                match object.get("host") {
+                    Some(Value::Array(parts)) => {
+                        let parts: Vec<&str> = parts.iter().filter_map(Value::as_str).collect();
+                        parsed.host = Some(parts.join("."));
+                    }
+                    Some(Value::String(host)) => parsed.host = Some(host.clone()),
+                    _ => {}
+                }
+                if let Some(port) = object.get("port").and_then(Value::as_str) {
+                    parsed.port = Some(port.to_string());
+                }
+                match object.get("path") {
+                    Some(Value::Array(segments)) => {
+                        parsed.path = segments
+                            .iter()
+                            .filter_map(|segment| match segment {
+                                Value::String(segment) => Some(segment.clone()),
+                                // v2.0 path variables can be objects
+                                Value::Object(segment) => segment
+                                    .get("value")
+                                    .and_then(Value::as_str)
+                                    .map(|value| format!(":{}", value)),
+                                _ => None,
+                            })
+                            .filter(|segment| !segment.is_empty())
+                            .collect();
+                    }
+                    Some(Value::String(path)) => parsed.path = split_path(path),
+                    _ => {}
+                }
+                if let Some(query) = object.get("query").and_then(Value::as_array) {
+                    parsed.query = query.clone();
+                }
+                for variable in object
+                    .get("variable")
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten()
+                {
+                    let key = variable
+                        .get("key")
+                        .or_else(|| variable.get("id"))
+                        .and_then(Value::as_str);
+                    if let Some(key) = key {
+                        parsed.variables.insert(key.to_string(), variable.clone());
+                    }
+                }
+                parsed
+            }
+            _ => Self::default(),
+        }
+    }
+
+    /// Split `https://{{host}}/users/:id?limit=10` into its parts.
+    fn parse_raw(raw: &str) -> Self {
+        let (raw, query) = raw.split_once('?').unwrap_or((raw, ""));
+        let (protocol, rest) = match raw.split_once("://") {
+            Some((protocol, rest)) => (Some(protocol.to_string()), rest),
+            None => (None, raw),
+        };
+        let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) if port.chars().all(|c| c.is_ascii_digit()) => {
+                (host, Some(port.to_string()))
+            }
+            _ => (authority, None),
+        };
+        Self {
+            protocol,
+            host: (!host.is_empty()).then(|| host.to_string()),
+            port,
+            path: split_path(path),
+            query: query
+                .split('&')
+                .filter(|pair| !pair.is_empty())
+                .map(|pair| {
+                    let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                    json!({ "key": key, "value": value })
+                })
+                .collect(),
+            variables: BTreeMap::new(),
+        }
+    }
+}
+
+/// Add `param` unless a parameter with its name and location is there.
+fn push_parameter(parameters: &mut Vec<Value>, param: Value) {
+    if !parameters
+        .iter()
+        .any(|other| other["name"] == param["name"] && other["in"] == param["in"])
+    {
+        parameters.push(param);
+    }
+}
+
+/// Fold `other`, a request for the same method and path, into `existing`:
+/// its new parameters, responses for other statuses (or with a body where
+/// `existing` has none) and a body if `existing` has none.
+fn merge_operation(existing: &mut Value, other: Value) {
+    let Value::Object(mut other) = other else {
+        return;
+    };
+    if let Some(Value::Array(params)) = other.remove("parameters") {
+        let mut parameters = match existing.get_mut("parameters") {
+            Some(Value::Array(parameters)) => std::mem::take(parameters),
+            _ => Vec::new(),
+        };
+        for param in params {
+            push_parameter(&mut parameters, param);
+        }
+        existing["parameters"] = parameters.into();
+    }
+    if let (Some(Value::Object(responses)), Some(Value::Object(target))) =
+        (other.remove("responses"), existing.get_mut("responses"))
+    {
+        for (status, response) in responses {
+            let replace = match target.get(&status) {
+                None => true,
+                Some(current) => {
+                    current.get("content").is_none() && response.get("content").is_some()
+                }
+            };
+            if replace {
+                target.insert(status, response);
+            }
+        }
+    }
+    if existing.get("requestBody").is_none()
+        && let Some(body) = other.remove("requestBody")
+    {
+        existing["requestBody"] = body;
+    }
+}
+
+fn split_path(path: &str) -> Vec<String> {
+    path.split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Collection variables by key.
+fn variables(variables: Option<&Value>) -> BTreeMap<String, String> {
+    variables
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|variable| {
+            let key = variable
+                .get("key")
+                .or_else(|| variable.get("id"))?
+                .as_str()?;
+            let value = match variable.get("value")? {
+                Value::String(value) => value.clone(),
+                other => other.to_string(),
+            };
+            Some((key.to_string(), value))
+        })
+        .collect()
+}
+
+/// A description, which is either a string or `{ content, type }`.
+fn description(value: Option<&Value>) -> Option<String> {
+    match value? {
+        Value::String(text) => Some(text.clone()),
+        Value::Object(object) => object.get("content")?.as_str().map(str::to_string),
+        _ => None,
+    }
+    .filter(|text| !text.is_empty())
+}
+
+/// Value of an auth parameter: `[{ key, value }]` in v2.1, `{ key: value }`
+/// in v2.0.
+fn auth_param(params: Option<&Value>, key: &str) -> Option<String> {
+    let value = match params? {
+        Value::Array(entries) => entries
+            .iter()
+            .find(|entry| entry.get("key").and_then(Value::as_str) == Some(key))?
+            .get("value")?,
+        Value::Object(object) => object.get(key)?,
+        _ => return None,
+    };
+    value.as_str().map(str::to_string)
+}
+
+/// Request body for a Postman `body` object.
+fn request_body(body: &Value) -> Option<Value> {
+    let (content_type, schema, example) = match body.get("mode")?.as_str()? {
+        "raw" => {
+            let raw = body.get("raw")?.as_str()?;
+            match serde_json::from_str::<Value>(raw) {
+                Ok(example) => ("application/json", infer_schema(&example), Some(example)),
+                Err(_) => ("text/plain", json!({ "type": "string" }), None),
+            }
+        }
+        "graphql" => ("application/json", json!({ "type": "object" }), None),
+        mode @ ("urlencoded" | "formdata") => {
+            let mut properties = Map::new();
+            for field in body.get(mode)?.as_array()? {
+                let Some(key) = field.get("key").and_then(Value::as_str) else {
+                    continue;
+                };
+                if field.get("disabled").and_then(Value::as_bool) == Some(true) {
+                    continue;
+                }
+                let schema = if field.get("type").and_then(Value::as_str) == Some("file") {
+                    json!({ "type": "string", "format": "binary" })
+                } else {
+                    field
+                        .get("value")
+                        .map_or_else(|| json!({ "type": "string" }), scalar_schema)
+                };
+                properties.insert(key.to_string(), schema);
+            }
+            let content_type = if mode == "urlencoded" {
+                "application/x-www-form-urlencoded"
+            } else {
+                "multipart/form-data"
+            };
+            (
+                content_type,
+                json!({ "type": "object", "properties": properties }),
+                None,
+            )
+        }
+        "file" => (
+            "application/octet-stream",
+            json!({ "type": "string", "format": "binary" }),
+            None,
+        ),
+        _ => return None,
+    };
+    let mut media = json!({ "schema": schema });
+    if let Some(example) = example {
+        media["example"] = example;
+    }
+    Some(json!({ "content": { content_type: media } }))
+}
+
+/// Schema of a parameter from its example value, which is always a string
+/// in a collection: numbers and booleans are recognized.
+fn scalar_schema(value: &Value) -> Value {
+    match value {
+        Value::String(text) if text.parse::<i64>().is_ok() => json!({ "type": "integer" }),
+        Value::String(text) if text.parse::<f64>().is_ok() => json!({ "type": "number" }),
+        Value::String(text) if text == "true" || text == "false" => json!({ "type": "boolean" }),
+        other => infer_schema(other),
+    }
+}
+
+/// Schema describing an example value.
+pub fn infer_schema(value: &Value) -> Value {
+    match value {
+        Value::Null => json!({}),
+        Value::Bool(_) => json!({ "type": "boolean" }),
+        Value::Number(number) if number.is_f64() => json!({ "type": "number" }),
+        Value::Number(_) => json!({ "type": "integer" }),
+        Value::String(_) => json!({ "type": "string" }),
+        Value::Array(items) => json!({
+            "type": "array",
+            "items": items.first().map_or_else(|| json!({}), infer_schema),
+        }),
+        Value::Object(object) => {
+            let properties: Map<String, Value> = object
+                .iter()
+                .map(|(key, value)| (key.clone(), infer_schema(value)))
+                .collect();
+            json!({ "type": "object", "properties": properties })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collection() -> Value {
+        json!({
+            "info": {
+                "name": "Pet Store",
+                "schema": "https://schema.getpostman.com/json/collection/v2.1.0/collection.json"
+            },
+            "auth": { "type": "bearer", "bearer": [{ "key": "token", "value": "{{token}}" }] },
+            "variable": [{ "key": "baseUrl", "value": "https://api.example.com" }],
+            "item": [
+                {
+                    "name": "Pets",
+                    "item": [
+                        {
+                            "name": "Get pet",
+                            "request": {
+                                "method": "GET",
+                                "url": {
+                                    "raw": "{{baseUrl}}/pets/:petId?verbose=true",
+                                    "host": ["{{baseUrl}}"],
+                                    "path": ["pets", ":petId"],
+                                    "query": [{ "key": "verbose", "value": "true" }],
+                                    "variable": [{ "key": "petId", "value": "42" }]
+                                }
+                            },
+                            "response": [{
+                                "name": "Found",
+                                "code": 200,
+                                "body": "{\"id\": 42, \"name\": \"Rex\", \"tags\": [\"dog\"]}"
+                            }]
+                        },
+                        {
+                            "name": "Create pet",
+                            "request": {
+                                "method": "POST",
+                                "auth": { "type": "noauth" },
+                                "header": [{ "key": "Content-Type", "value": "application/json" }],
+                                "url": "{{baseUrl}}/pets",
+                                "body": { "mode": "raw", "raw": "{\"name\": \"Rex\"}" }
+                            }
+                        }
+                    ]
+                }
+            ]
+        })
+    }
+
+    #[test]
+    fn test_to_openapi() {
+        let document = to_openapi(&collection()).unwrap();
+        assert_eq!(
+            document["servers"],
+            json!([{ "url": "https://api.example.com" }])
+        );
+        assert_eq!(document["security"], json!([{ "bearerAuth": [] }]));
+
+        let get = &document["paths"]["/pets/{petId}"]["get"];
+        assert_eq!(get["operationId"], "getPet");
+        assert_eq!(get["tags"], json!(["Pets"]));
+        assert_eq!(get["parameters"][0]["schema"], json!({ "type": "integer" }));
+        assert_eq!(get["parameters"][1]["name"], "verbose");
+        assert_eq!(
+            get["responses"]["200"]["content"]["application/json"]["schema"]["properties"]["tags"],
+            json!({ "type": "array", "items": { "type": "string" } })
+        );
+        assert!(get.get("security").is_none());
+
+        let post = &document["paths"]["/pets"]["post"];
+        assert_eq!(post["security"], json!([]));
+        assert!(post.get("parameters").is_none());
+        assert_eq!(
+            post["requestBody"]["content"]["application/json"]["schema"]["properties"]["name"],
+            json!({ "type": "string" })
+        );
+
+        assert!(to_openapi(&json!({ "info": {} })).is_err());
+    }
+
+    #[test]
+    fn test_variants_folders_and_variables() {
+        let request = |query: &str, code: u64| {
+            json!({
+                "name": "List pets",
+                "request": { "method": "GET", "url": format!("{{{{baseUrl}}}}/{{{{apiVersion}}}}/pets{}", query) },
+                "response": [{ "name": "Listed", "code": code, "body": "{}" }]
+            })
+        };
+        let collection = json!({
+            "info": { "name": "Pets" },
+            "variable": [
+                { "key": "baseUrl", "value": "https://api.example.com" },
+                { "key": "apiVersion", "value": "v2" }
+            ],
+            "item": [
+                { "name": "Pets", "item": [request("?ids=1&ids=2", 200)] },
+                {
+                    "name": "Pets",
+                    "description": "All about pets",
+                    "item": [request("?limit=10", 404)]
+                }
+            ]
+        });
+        let document = to_openapi(&collection).unwrap();
+
+        // Folders with one name are one tag
+        assert_eq!(
+            document["tags"],
+            json!([{ "name": "Pets", "description": "All about pets" }])
+        );
+        // The collection variable is filled in rather than a parameter
+        let paths = document["paths"].as_object().unwrap();
+        assert_eq!(paths.keys().collect::<Vec<_>>(), ["/v2/pets"]);
+        // Both requests end up in one operation
+        let get = &paths["/v2/pets"]["get"];
+        assert_eq!(get["operationId"], "listPets");
+        let names = get["parameters"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|param| param["name"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["ids", "limit"]);
+        assert!(get["responses"].get("200").is_some());
+        assert!(get["responses"].get("404").is_some());
+    }
+
+    #[test]
+    fn test_from_postman() {
+        let ir = GenIr::from_postman(&collection().to_string()).unwrap();
+        assert_eq!(ir.api.title, "Pet Store");
+        assert_eq!(ir.operation_count(), 2);
+    }
+}
@@ -0,0 +1,304 @@
+//! Dependency graph over the declared types of a [`GenIr`].
+//!
+//! Generators that emit forward declarations, per-file imports or need to
+//! order declarations can use [`GenIr::type_graph`] instead of re-deriving
+//! references from every `TypeKind`.
+
+use std::collections::BTreeSet;
+
+use crate::gen_ir::{
+    Additional, AliasTarget, Composite, GenIr, Map, StableId, TypeDecl, TypeKind, TypeMod, TypeRef,
+};
+
+/// Dependency edges between named types plus a cycle-aware topological order.
+#[derive(Debug, Clone, Default)]
+pub struct TypeGraph {
+    /// type -> named types it references directly (only ids declared in `GenIr.types`)
+    pub edges: Map<StableId, BTreeSet<StableId>>,
+    /// Every declared type, dependencies before dependents.
+    /// Members of a cycle are adjacent and ordered by id.
+    pub order: Vec<StableId>,
+    /// Strongly connected groups that reference themselves (directly or transitively).
+    pub cycles: Vec<Vec<StableId>>,
+}
+
+impl TypeGraph {
+    /// Build the graph for all types in `ir`.
+    pub fn build(ir: &GenIr) -> Self {
+        let mut edges = Map::new();
+        for (id, decl) in &ir.types {
+            let mut deps = BTreeSet::new();
+            collect_decl_deps(decl, &mut deps);
+            deps.retain(|dep| ir.types.contains_key(dep));
+            edges.insert(id.clone(), deps);
+        }
+
+        let components = strongly_connected_components(&edges);
+
+        let mut order = Vec::with_capacity(edges.len());
+        let mut cycles = Vec::new();
+        for mut component in components {
+            component.sort();
+            let recursive = component.len() > 1
+                || edges
+                    .get(&component[0])
+                    .is_some_and(|deps| deps.contains(&component[0]));
+            if recursive {
+                cycles.push(component.clone());
+            }
+            order.extend(component);
+        }
+
+        Self {
+            edges,
+            order,
+            cycles,
+        }
+    }
+
+    /// Named types referenced directly by `id`.
+    pub fn dependencies(&self, id: &StableId) -> impl Iterator<Item = &StableId> {
+        self.edges.get(id).into_iter().flatten()
+    }
+
+    /// Named types that reference `id` directly.
+    pub fn dependents<'a>(&'a self, id: &'a StableId) -> impl Iterator<Item = &'a StableId> {
+        self.edges
+            .iter()
+            .filter(move |(_, deps)| deps.contains(id))
+            .map(|(from, _)| from)
+    }
+
+    /// Whether `id` is part of a reference cycle (including self-reference).
+    pub fn is_recursive(&self, id: &StableId) -> bool {
+        self.cycles.iter().any(|cycle| cycle.contains(id))
+    }
+}
+
+impl GenIr {
+    /// Dependency edges and topological ordering of the declared types.
+    pub fn type_graph(&self) -> TypeGraph {
+        TypeGraph::build(self)
+    }
+}
+
+fn collect_decl_deps(decl: &TypeDecl, out: &mut BTreeSet<StableId>) {
+    match &decl.kind {
+        TypeKind::Struct {
+            fields,
+            additional,
+            discriminator,
+        } => {
+            for field in fields {
+                collect_type_ref_deps(&field.ty, out);
+            }
+            if let Additional::Typed(ty) = additional {
+                collect_type_ref_deps(ty, out);
+            }
+            if let Some(discriminator) = discriminator {
+                out.extend(discriminator.mapping.values().cloned());
+            }
+        }
+        TypeKind::Union { variants, .. } => {
+            for variant in variants {
+                collect_type_ref_deps(&variant.ty, out);
+            }
+        }
+        TypeKind::Enum { .. } | TypeKind::Opaque { .. } => {}
+        TypeKind::Alias { aliased } => match aliased {
+            AliasTarget::Primitive(_) => {}
+            AliasTarget::Reference(ty) => collect_type_ref_deps(ty, out),
+            AliasTarget::Composite(Composite::List(ty)) => collect_type_ref_deps(ty, out),
+            AliasTarget::Composite(Composite::Map { value, .. }) => {
+                collect_type_ref_deps(value, out)
+            }
+            AliasTarget::Composite(Composite::Tuple(items)) => {
+                for ty in items {
+                    collect_type_ref_deps(ty, out);
+                }
+            }
+        },
+    }
+}
+
+fn collect_type_ref_deps(ty: &TypeRef, out: &mut BTreeSet<StableId>) {
+    if let StableId::Named(_) = &ty.target {
+        out.insert(ty.target.clone());
+    }
+    for modifier in &ty.modifiers {
+        if let TypeMod::Map(value) = modifier {
+            collect_type_ref_deps(value, out);
+        }
+    }
+}
+
+/// Tarjan's algorithm. Components are returned dependencies-first because an
+/// SCC is only emitted once everything reachable from it has been emitted.
+fn strongly_connected_components(edges: &Map<StableId, BTreeSet<StableId>>) -> Vec<Vec<StableId>> {
+    struct State<'a> {
+        edges: &'a Map<StableId, BTreeSet<StableId>>,
+        index: Map<&'a StableId, usize>,
+        lowlink: Map<&'a StableId, usize>,
+        on_stack: BTreeSet<&'a StableId>,
+        stack: Vec<&'a StableId>,
+        next_index: usize,
+        components: Vec<Vec<StableId>>,
+    }
+
+    fn visit<'a>(state: &mut State<'a>, node: &'a StableId) {
+        state.index.insert(node, state.next_index);
+        state.lowlink.insert(node, state.next_index);
+        state.next_index += 1;
+        state.stack.push(node);
+        state.on_stack.insert(node);
+
+        let edges = state.edges;
+        for dep in edges.get(node).into_iter().flatten() {
+            if !state.index.contains_key(dep) {
+                visit(state, dep);
+                let low = state.lowlink[node].min(state.lowlink[dep]);
+                state.lowlink.insert(node, low);
+            } else if state.on_stack.contains(dep) {
+                let low = state.lowlink[node].min(state.index[dep]);
+                state.lowlink.insert(node, low);
+            }
+        }
+
+        if state.lowlink[node] == state.index[node] {
+            let mut component = Vec::new();
+            while let Some(member) = state.stack.pop() {
+                state.on_stack.remove(member);
+                component.push(member.clone());
+                if member == node {
+                    break;
+                }
+            }
+            state.components.push(component);
+        }
+    }
+
+    let mut state = State {
+        edges,
+        index: Map::new(),
+        lowlink: Map::new(),
+        on_stack: BTreeSet::new(),
+        stack: Vec::new(),
+        next_index: 0,
+        components: Vec::new(),
+    };
+
+    for node in edges.keys() {
+        if !state.index.contains_key(node) {
+            visit(&mut state, node);
+        }
+    }
+
+    state.components
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::parse;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn position(graph: &TypeGraph, name: &str) -> usize {
+        graph
+            .order
+            .iter()
+            .position(|id| id == &StableId::new(name))
+            .unwrap_or_else(|| panic!("{name} missing from order"))
+    }
+
+    #[test]
+    fn test_type_graph_orders_dependencies_first() {
+        let json = r##"{
+            "openapi": "3.0.0",
+            "info": { "title": "Test API", "version": "1.0.0" },
+            "paths": {},
+            "components": {
+                "schemas": {
+                    "Owner": {
+                        "type": "object",
+                        "properties": {
+                            "pets": { "type": "array", "items": { "$ref": "#/components/schemas/Pet" } }
+                        }
+                    },
+                    "Pet": {
+                        "type": "object",
+                        "properties": {
+                            "kind": { "$ref": "#/components/schemas/Kind" },
+                            "name": { "type": "string" }
+                        }
+                    },
+                    "Kind": { "type": "string", "enum": ["cat", "dog"] }
+                }
+            }
+        }"##;
+
+        let ir = GenIr::from(parse(json).expect("Failed to parse OpenAPI"));
+        let graph = ir.type_graph();
+
+        assert_eq!(
+            graph
+                .dependencies(&StableId::new("Owner"))
+                .collect::<Vec<_>>(),
+            vec![&StableId::new("Pet")]
+        );
+        assert_eq!(
+            graph.dependents(&StableId::new("Kind")).collect::<Vec<_>>(),
+            vec![&StableId::new("Pet")]
+        );
+        assert!(position(&graph, "Kind") < position(&graph, "Pet"));
+        assert!(position(&graph, "Pet") < position(&graph, "Owner"));
+        assert_eq!(graph.order.len(), ir.types.len());
+        assert!(graph.cycles.is_empty());
+    }
+
+    #[test]
+    fn test_type_graph_reports_cycles() {
+        let json = r##"{
+            "openapi": "3.0.0",
+            "info": { "title": "Test API", "version": "1.0.0" },
+            "paths": {},
+            "components": {
+                "schemas": {
+                    "Node": {
+                        "type": "object",
+                        "properties": {
+                            "children": { "type": "array", "items": { "$ref": "#/components/schemas/Node" } }
+                        }
+                    },
+                    "A": {
+                        "type": "object",
+                        "properties": { "b": { "$ref": "#/components/schemas/B" } }
+                    },
+                    "B": {
+                        "type": "object",
+                        "properties": { "a": { "$ref": "#/components/schemas/A" } }
+                    },
+                    "Root": {
+                        "type": "object",
+                        "properties": { "a": { "$ref": "#/components/schemas/A" } }
+                    }
+                }
+            }
+        }"##;
+
+        let ir = GenIr::from(parse(json).expect("Failed to parse OpenAPI"));
+        let graph = ir.type_graph();
+
+        assert_eq!(
+            graph.cycles,
+            vec![
+                vec![StableId::new("A"), StableId::new("B")],
+                vec![StableId::new("Node")],
+            ]
+        );
+        assert!(graph.is_recursive(&StableId::new("Node")));
+        assert!(!graph.is_recursive(&StableId::new("Root")));
+        assert!(position(&graph, "A") < position(&graph, "Root"));
+        assert!(position(&graph, "B") < position(&graph, "Root"));
+    }
+}
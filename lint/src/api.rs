@@ -1,7 +1,14 @@
+use std::collections::BTreeMap;
+
 use crate::LintError;
-use crate::lint::{Diagnostic, Indexes, LintCtx, RuleId, RuleSet, resolve_diagnostics, run_rules};
-use crate::model::LineIndex;
-use crate::parse::SpanDbBuilder;
+use crate::config::{CoverageThresholds, Ignore, LintConfig, NamingConventions, RuleLevel};
+use crate::fix::{FixResult, apply_edits};
+use crate::lint::{
+    CategorySeverity, Diagnostic, Finding, Indexes, LintCtx, Rule, RuleId, RuleSet, Severity,
+    TextEdit, resolve_diagnostics, run_rules,
+};
+use crate::model::SourceMap;
+use crate::suppress::{IGNORE_EXTENSION, apply_inline_ignores, inline_ignores};
 
 /// Result of linting an OpenAPI specification
 #[derive(Debug)]
@@ -10,9 +17,16 @@ pub struct Validation {
     pub diagnostics: Vec<Diagnostic>,
     /// Whether the spec parsed successfully
     pub parsed_ok: bool,
+    /// Findings dropped by `x-lint-ignore`, counted per rule ID
+    pub suppressed: BTreeMap<String, usize>,
 }
 
 impl Validation {
+    /// Number of findings dropped by `x-lint-ignore`
+    pub fn suppressed_count(&self) -> usize {
+        self.suppressed.values().sum()
+    }
+
     /// Check if there are any errors
     pub fn has_errors(&self) -> bool {
         self.diagnostics
@@ -86,34 +100,325 @@ pub fn lint_all(spec: &str) -> Result<Validation, LintError> {
 
 /// Lint with a pre-built RuleSet
 pub fn lint_with_ruleset(spec: &str, rule_set: RuleSet) -> Result<Validation, LintError> {
-    // Build span database from YAML
-    let span_db = SpanDbBuilder::build(spec).map_err(|e| LintError::YamlParse(e.to_string()))?;
+    lint_with_config(spec, rule_set, &CategorySeverity::default())
+}
 
-    // Build line index for line/column conversion
-    let line_index = LineIndex::new(spec);
+/// Lint with a pre-built RuleSet and category severity overrides
+/// (e.g. escalate `security` to errors or turn `style` off).
+pub fn lint_with_config(
+    spec: &str,
+    rule_set: RuleSet,
+    category_severity: &CategorySeverity,
+) -> Result<Validation, LintError> {
+    Linter::new(rule_set)
+        .with_category_severity(category_severity.clone())
+        .lint(spec)
+}
 
-    // Parse as OpenAPI spec
-    let parsed_spec: oas3::Spec = serde_saphyr::from_str(spec).map_err(|e| {
-        // Try to get useful location info from the error
-        LintError::OpenApiParse(e.to_string())
-    })?;
+/// Runs the enabled built-in rules plus any custom [`Rule`]s over a spec.
+///
+/// # Example
+/// ```
+/// use lint::{Linter, RuleSet};
+///
+/// let spec = r#"
+/// openapi: "3.1.0"
+/// info:
+///   title: My API
+///   version: "1.0"
+/// paths: {}
+/// "#;
+///
+/// let linter = Linter::new(RuleSet::all());
+/// let result = linter.lint(spec).unwrap();
+/// for diag in &result.diagnostics {
+///     println!("{} at {}: {}", diag.rule.as_str(), diag.pointer, diag.message);
+/// }
+/// ```
+pub struct Linter {
+    rule_set: RuleSet,
+    rules: Vec<Box<dyn Rule>>,
+    category_severity: CategorySeverity,
+    /// Per-rule levels, applied on top of `rule_set` and category overrides
+    rule_levels: BTreeMap<String, RuleLevel>,
+    ignores: Vec<Ignore>,
+    naming: NamingConventions,
+    coverage: CoverageThresholds,
+}
 
-    // Build indexes
-    let indexes = Indexes::build(&parsed_spec);
+/// Runs the [core ruleset](RuleSet::core).
+impl Default for Linter {
+    fn default() -> Self {
+        Self::new(RuleSet::core())
+    }
+}
 
-    // Create lint context
-    let ctx = LintCtx::new(&parsed_spec, &indexes, &span_db);
+impl Linter {
+    /// A linter running the built-in rules enabled in `rule_set`
+    pub fn new(rule_set: RuleSet) -> Self {
+        Self {
+            rule_set,
+            rules: Vec::new(),
+            category_severity: CategorySeverity::default(),
+            rule_levels: BTreeMap::new(),
+            ignores: Vec::new(),
+            naming: NamingConventions::default(),
+            coverage: CoverageThresholds::default(),
+        }
+    }
+
+    /// A linter set up from an `oas-lint.toml`. Settings applied afterwards
+    /// (rulesets, levels, category overrides, ignores) take precedence.
+    pub fn from_config(config: &LintConfig) -> Result<Self, LintError> {
+        let name = config.ruleset.as_deref().unwrap_or("core");
+        let rule_set = RuleSet::named(name)
+            .ok_or_else(|| LintError::Config(format!("unknown ruleset `{}`", name)))?;
+        let mut linter = Self::new(rule_set).with_category_severity(config.category_severity()?);
+        linter.rule_levels = config.rules.clone();
+        linter.ignores = config.ignore.clone();
+        linter.naming = config.naming;
+        linter.coverage = config.coverage;
+        Ok(linter)
+    }
 
-    // Run rules and collect findings
-    let findings = run_rules(&ctx, rule_set);
+    /// Replace the built-in rules to start from. Per-rule levels still apply.
+    pub fn with_rule_set(mut self, rule_set: RuleSet) -> Self {
+        self.rule_set = rule_set;
+        self
+    }
+
+    /// Turn `rule` (built-in or custom) on or off, or set its severity
+    pub fn with_rule_level(mut self, rule: &str, level: RuleLevel) -> Self {
+        self.rule_levels.insert(rule.to_string(), level);
+        self
+    }
 
-    // Resolve to diagnostics with source locations
-    let diagnostics = resolve_diagnostics(findings, &span_db, &line_index);
+    /// Drop findings matching `ignore`
+    pub fn with_ignore(mut self, ignore: Ignore) -> Self {
+        self.ignores.push(ignore);
+        self
+    }
+
+    /// Casing `schema-naming-conventions` checks against
+    pub fn with_naming(mut self, naming: NamingConventions) -> Self {
+        self.naming = naming;
+        self
+    }
+
+    /// Minimums the docs-coverage rule checks against
+    pub fn with_coverage(mut self, coverage: CoverageThresholds) -> Self {
+        self.coverage = coverage;
+        self
+    }
+
+    /// Also run `rule`
+    pub fn with_rule(mut self, rule: impl Rule + 'static) -> Self {
+        self.add_rule(Box::new(rule));
+        self
+    }
+
+    /// Also run `rule`
+    pub fn add_rule(&mut self, rule: Box<dyn Rule>) -> &mut Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Also run `rules`, e.g. those of a [`SpectralRuleset`](crate::SpectralRuleset)
+    pub fn with_rules(mut self, rules: impl IntoIterator<Item = Box<dyn Rule>>) -> Self {
+        self.rules.extend(rules);
+        self
+    }
+
+    /// Also run the rules of a shared library exporting the versioned C
+    /// ABI of [`plugin`](crate::plugin), the same way
+    /// `GeneratorRegistry::load_plugin` loads generators. Fails with
+    /// [`LintError::Plugin`] if the plugin was built for another ABI
+    /// version. The library stays loaded as long as its rules.
+    /// Requires the "dynamic-plugins" feature.
+    #[cfg(feature = "dynamic-plugins")]
+    pub fn load_plugin(&mut self, path: &std::path::Path) -> Result<&mut Self, LintError> {
+        self.rules.extend(crate::plugin::load(path)?);
+        Ok(self)
+    }
+
+    /// Apply category severity overrides to built-in rule findings, on
+    /// top of any set before
+    pub fn with_category_severity(mut self, category_severity: CategorySeverity) -> Self {
+        self.category_severity.merge(&category_severity);
+        self
+    }
 
-    Ok(Validation {
-        diagnostics,
-        parsed_ok: true,
-    })
+    /// IDs of every rule this linter runs
+    pub fn rules(&self) -> impl Iterator<Item = RuleId> + '_ {
+        RuleId::all()
+            .iter()
+            .copied()
+            .chain(self.rules.iter().map(|rule| rule.id()))
+            .filter(|rule| self.effective_severity(*rule).is_some())
+    }
+
+    /// The configuration this linter runs with: every known rule with its
+    /// final level, and the ignores. Category overrides are folded into
+    /// the rule levels.
+    pub fn effective_config(&self) -> LintConfig {
+        let rules = RuleId::all()
+            .iter()
+            .copied()
+            .chain(self.rules.iter().map(|rule| rule.id()))
+            .map(|rule| {
+                let level = self
+                    .effective_severity(rule)
+                    .map_or(RuleLevel::Off, RuleLevel::Severity);
+                (rule.as_str().to_string(), level)
+            })
+            .collect();
+        LintConfig {
+            ruleset: Some("none".to_string()),
+            rules,
+            categories: BTreeMap::new(),
+            ignore: self.ignores.clone(),
+            naming: self.naming,
+            coverage: self.coverage,
+        }
+    }
+
+    /// Severity `rule` reports at, or `None` if it doesn't run.
+    fn effective_severity(&self, rule: RuleId) -> Option<Severity> {
+        let custom = self.rules.iter().find(|r| r.id() == rule);
+        let default = custom.map_or_else(|| rule.default_severity(), |r| r.severity());
+        let enabled = custom.is_some() || self.rule_set.is_enabled(rule);
+        match self.rule_levels.get(rule.as_str()) {
+            Some(RuleLevel::Off) => None,
+            Some(RuleLevel::Severity(severity)) => Some(*severity),
+            Some(RuleLevel::On) => self.category_severity.severity_for(rule, default),
+            None if enabled => self.category_severity.severity_for(rule, default),
+            None => None,
+        }
+    }
+
+    /// Built-in rules to run after per-rule levels.
+    fn effective_rule_set(&self) -> RuleSet {
+        let mut rule_set = self.rule_set;
+        for (name, level) in &self.rule_levels {
+            let Some(rule) = RuleId::parse(name) else {
+                continue;
+            };
+            match level {
+                RuleLevel::Off => rule_set.disable(rule),
+                RuleLevel::On | RuleLevel::Severity(_) => rule_set.enable(rule),
+            }
+        }
+        rule_set
+    }
+
+    /// Apply category overrides, per-rule levels and ignores.
+    fn configure(&self, findings: Vec<Finding>) -> Vec<Finding> {
+        self.category_severity
+            .apply(findings)
+            .into_iter()
+            .filter_map(|f| {
+                let f = match self.rule_levels.get(f.rule.as_str()) {
+                    Some(RuleLevel::Off) => return None,
+                    Some(RuleLevel::Severity(severity)) => f.with_severity(*severity),
+                    Some(RuleLevel::On) | None => f,
+                };
+                let ignored = self
+                    .ignores
+                    .iter()
+                    .any(|ignore| ignore.matches(f.rule.as_str(), &f.ptr));
+                (!ignored).then_some(f)
+            })
+            .collect()
+    }
+
+    /// Lint a YAML or JSON spec.
+    pub fn lint(&self, spec: &str) -> Result<Validation, LintError> {
+        // Map pointers to spans, lines and columns
+        let source_map = SourceMap::new(spec)?;
+
+        // Parse as OpenAPI spec
+        let parsed_spec: oas3::Spec = parser::yaml::from_str(spec).map_err(|e| {
+            // Try to get useful location info from the error
+            LintError::OpenApiParse(e.to_string())
+        })?;
+
+        // Build indexes
+        let indexes = Indexes::build(&parsed_spec);
+
+        // Create lint context
+        let ctx = LintCtx::new(&parsed_spec, &indexes, source_map.spans())
+            .with_source(spec)
+            .with_naming(self.naming)
+            .with_coverage(self.coverage);
+
+        // Run rules and collect findings
+        let mut findings = run_rules(&ctx, self.effective_rule_set());
+        for rule in &self.rules {
+            if !matches!(
+                self.rule_levels.get(rule.id().as_str()),
+                Some(RuleLevel::Off)
+            ) {
+                findings.extend(rule.check(&ctx));
+            }
+        }
+        let findings = self.configure(findings);
+
+        // Honor x-lint-ignore, reading the document only when it's used
+        let (findings, suppressed) = if spec.contains(IGNORE_EXTENSION) {
+            apply_inline_ignores(&inline_ignores(ctx.document()), findings)
+        } else {
+            (findings, BTreeMap::new())
+        };
+
+        // Resolve to diagnostics with source locations
+        let diagnostics = resolve_diagnostics(findings, &source_map);
+
+        Ok(Validation {
+            diagnostics,
+            parsed_ok: true,
+            suppressed,
+        })
+    }
+
+    /// Lint `spec` and apply the text edits of its diagnostics' fixes, in
+    /// diagnostic order. Everything the fixes don't touch, comments and
+    /// formatting included, is left as it was.
+    pub fn apply_fixes(&self, spec: &str) -> Result<FixResult, LintError> {
+        let validation = self.lint(spec)?;
+
+        let mut edits: Vec<TextEdit> = Vec::new();
+        let mut applied = Vec::new();
+        let mut skipped = Vec::new();
+        for diagnostic in validation.diagnostics {
+            if diagnostic.fixes.is_empty() {
+                if diagnostic.autofix.is_some() {
+                    skipped.push(diagnostic);
+                }
+                continue;
+            }
+            let conflicts = diagnostic.fixes.iter().enumerate().any(|(i, edit)| {
+                edits
+                    .iter()
+                    .chain(&diagnostic.fixes[..i])
+                    .any(|other| edit.overlaps(other))
+            });
+            if conflicts {
+                skipped.push(diagnostic);
+            } else {
+                edits.extend(diagnostic.fixes.iter().cloned());
+                applied.push(diagnostic);
+            }
+        }
+
+        let spec = apply_edits(spec, &edits);
+        // Fail rather than hand back a document a fix broke
+        SourceMap::new(&spec)?;
+        Ok(FixResult {
+            spec,
+            applied,
+            skipped,
+        })
+    }
 }
 
 /// Get all available rule IDs
@@ -220,4 +525,267 @@ paths: {}
         let result = lint_all(spec).unwrap();
         assert!(result.diagnostics.len() > 1);
     }
+
+    #[test]
+    fn test_lint_category_severity() {
+        let spec = r#"
+openapi: "3.1.0"
+info:
+  title: Test API
+  version: "1.0"
+paths:
+  /pets:
+    get:
+      responses:
+        "200":
+          description: OK
+"#;
+
+        let rules = RuleSet::from_slice(&["operation-id-required", "security-declared"]);
+
+        let result = lint_with_ruleset(spec, rules).unwrap();
+        assert!(!result.has_errors());
+        assert_eq!(result.diagnostics.len(), 2);
+
+        let overrides = CategorySeverity::parse("security:error,style:off").unwrap();
+        let result = lint_with_config(spec, rules, &overrides).unwrap();
+        assert_eq!(result.diagnostics.len(), 1);
+        assert_eq!(result.diagnostics[0].rule, RuleId::SecurityDeclared);
+        assert!(result.has_errors());
+    }
+
+    #[test]
+    fn test_ruleset_from_categories() {
+        let set = RuleSet::from_categories(&[crate::lint::RuleCategory::Security]);
+        assert!(set.is_enabled(RuleId::SecurityNoApiKeyInQuery));
+        assert!(set.is_enabled(RuleId::SecuritySchemesDefined));
+        assert!(!set.is_enabled(RuleId::PathNoVerbs));
+
+        assert!(CategorySeverity::parse("security:loud").is_err());
+        assert!(CategorySeverity::parse("naming:off").is_err());
+    }
+
+    #[test]
+    fn test_linter_custom_rule() {
+        use crate::lint::{Finding, Severity};
+        use crate::model::pointer;
+
+        struct PathsHaveSummary;
+
+        impl Rule for PathsHaveSummary {
+            fn id(&self) -> RuleId {
+                RuleId::Custom("path-summary-required")
+            }
+
+            fn severity(&self) -> Severity {
+                Severity::Error
+            }
+
+            fn check(&self, ctx: &LintCtx) -> Vec<Finding> {
+                let paths = ctx.spec.paths.iter().flatten();
+                paths
+                    .filter(|(_, item)| item.summary.is_none())
+                    .map(|(path, _)| {
+                        self.finding(pointer(["paths", path]), format!("{} has no summary", path))
+                    })
+                    .collect()
+            }
+        }
+
+        let spec = r#"
+openapi: "3.1.0"
+info:
+  title: Test API
+  version: "1.0"
+paths:
+  /pets/{id}:
+    get:
+      responses:
+        "200":
+          description: OK
+"#;
+
+        let linter = Linter::new(RuleSet::from_slice(&["operation-id-required"]))
+            .with_rule(PathsHaveSummary);
+        assert_eq!(
+            linter.rules().map(|r| r.as_str()).collect::<Vec<_>>(),
+            ["operation-id-required", "path-summary-required"]
+        );
+
+        let result = linter.lint(spec).unwrap();
+        assert_eq!(result.diagnostics.len(), 2);
+        let custom = result
+            .diagnostics
+            .iter()
+            .find(|d| d.rule == RuleId::Custom("path-summary-required"))
+            .unwrap();
+        assert_eq!(custom.severity, Severity::Error);
+        assert_eq!(custom.pointer, "/paths/~1pets~1{id}");
+        assert!(result.has_errors());
+    }
+
+    #[test]
+    fn test_core_ruleset() {
+        let spec = r#"
+openapi: "3.0.3"
+info:
+  title: Test API
+  version: "1.0"
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      parameters:
+        - name: limit
+          in: query
+          schema:
+            type: integer
+      responses:
+        "400":
+          description: Bad request
+components:
+  securitySchemes:
+    bearer:
+      type: http
+      scheme: bearer
+"#;
+
+        let result = Linter::default().lint(spec).unwrap();
+        let mut rules: Vec<_> = result.diagnostics.iter().map(|d| d.rule).collect();
+        rules.sort();
+        assert_eq!(
+            rules,
+            [
+                RuleId::ResponsesHave2xx,
+                RuleId::OperationTagsRequired,
+                RuleId::ParamDescriptionRequired,
+                RuleId::ComponentNoUnused,
+            ]
+        );
+        assert!(!RuleSet::core().is_enabled(RuleId::PathNoVerbs));
+        assert!(!RuleSet::core().is_enabled(RuleId::OpenApiVersion31));
+    }
+
+    #[test]
+    fn test_linter_from_config() {
+        let spec = r#"
+openapi: "3.1.0"
+info:
+  title: Test API
+  version: "1.0"
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      responses:
+        "200":
+          description: OK
+  /internal/jobs:
+    get:
+      responses:
+        "200":
+          description: OK
+"#;
+
+        let config = LintConfig::from_toml(
+            r#"
+ruleset = "core"
+
+[rules]
+operation-tags-required = "off"
+info-description-present = "error"
+
+[[ignore]]
+path = "/paths/~1internal*"
+"#,
+        )
+        .unwrap();
+        let result = Linter::from_config(&config).unwrap().lint(spec).unwrap();
+        let found: Vec<(RuleId, Severity)> = result
+            .diagnostics
+            .iter()
+            .map(|d| (d.rule, d.severity))
+            .collect();
+        assert_eq!(found, [(RuleId::InfoDescriptionPresent, Severity::Error)]);
+
+        // Programmatic settings win over the file
+        let linter = Linter::from_config(&config)
+            .unwrap()
+            .with_rule_level("info-description-present", RuleLevel::Off)
+            .with_rule_level("operation-tags-required", RuleLevel::On);
+        let result = linter.lint(spec).unwrap();
+        assert_eq!(result.diagnostics.len(), 1);
+        assert_eq!(result.diagnostics[0].rule, RuleId::OperationTagsRequired);
+
+        let effective = linter.effective_config();
+        assert_eq!(
+            effective.rules["operation-tags-required"],
+            RuleLevel::Severity(Severity::Warning)
+        );
+        assert_eq!(effective.rules["info-description-present"], RuleLevel::Off);
+        assert_eq!(effective.rules["path-no-verbs"], RuleLevel::Off);
+        let reloaded = Linter::from_config(&effective).unwrap();
+        assert_eq!(
+            reloaded.rules().collect::<Vec<_>>(),
+            linter.rules().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_apply_fixes() {
+        let spec = r#"openapi: "3.1.0"
+info:
+  title: Test API
+  version: "1.0"
+paths:
+  /pets/{petId}:
+    get:
+      # Fetch one pet
+      parameters:
+        - name: petId
+          in: path
+          required: true
+          schema:
+            type: string
+      responses:
+        2xx:
+          description: OK
+"#;
+        let linter = Linter::new(RuleSet::from_slice(&[
+            "operation-id-required",
+            "operation-tags-required",
+            "response-status-code-valid",
+        ]));
+        let fixed = linter.apply_fixes(spec).unwrap();
+        assert_eq!(fixed.applied.len(), 3);
+        assert!(fixed.skipped.is_empty());
+        // Only the fixed nodes change; the comment and layout stay
+        assert_eq!(
+            fixed.spec,
+            spec.replace(
+                "      parameters:",
+                "      operationId: getPetsByPetId\n      tags: [pets]\n      parameters:"
+            )
+            .replace("2xx:", "2XX:")
+        );
+
+        let relinted = linter.lint(&fixed.spec).unwrap();
+        assert!(
+            relinted.diagnostics.is_empty(),
+            "{:?}",
+            relinted.diagnostics
+        );
+        assert!(!linter.apply_fixes(&fixed.spec).unwrap().changed());
+
+        let json = crate::testutil::yaml_to_json(spec);
+        let fixed = linter.apply_fixes(&json).unwrap();
+        assert_eq!(fixed.applied.len(), 3);
+        assert!(
+            fixed
+                .spec
+                .contains("\"operationId\": \"getPetsByPetId\",\n")
+        );
+        assert!(fixed.spec.contains("\"2XX\": {"));
+        assert!(linter.lint(&fixed.spec).unwrap().diagnostics.is_empty());
+    }
 }
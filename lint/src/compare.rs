@@ -0,0 +1,607 @@
+//! Breaking changes between two versions of a spec.
+//!
+//! [`compare`] diffs an old and a new version and reports what would break
+//! existing clients, located in the new version:
+//!
+//! - removed paths and operations
+//! - removed properties, or renamed ones (a property removed and another
+//!   with the same schema added)
+//! - newly required request properties and parameters
+//! - enums that lost values, except in request parameters and bodies
+//! - schemas whose type changed
+//!
+//! Schemas are compared where both versions have them: component schemas by
+//! name, and parameter, request body and response schemas of operations
+//! present in both. `$ref`s are compared by target, not followed.
+
+use serde_json::{Map, Value};
+
+use crate::LintError;
+use crate::api::Validation;
+use crate::lint::{Finding, RuleId, Severity, resolve_diagnostics};
+use crate::model::{Direction, SourceMap, escape_pointer_segment};
+use crate::suppress::{apply_inline_ignores, inline_ignores};
+
+pub const PATH_REMOVED: RuleId = RuleId::Custom("breaking-path-removed");
+pub const OPERATION_REMOVED: RuleId = RuleId::Custom("breaking-operation-removed");
+pub const PROPERTY_REMOVED: RuleId = RuleId::Custom("breaking-property-removed");
+pub const PROPERTY_RENAMED: RuleId = RuleId::Custom("breaking-property-renamed");
+pub const REQUIRED_ADDED: RuleId = RuleId::Custom("breaking-required-added");
+pub const ENUM_NARROWED: RuleId = RuleId::Custom("breaking-enum-narrowed");
+pub const TYPE_CHANGED: RuleId = RuleId::Custom("breaking-type-changed");
+
+/// Every rule [`compare`] reports.
+pub const BREAKING_RULES: &[RuleId] = &[
+    PATH_REMOVED,
+    OPERATION_REMOVED,
+    PROPERTY_REMOVED,
+    PROPERTY_RENAMED,
+    REQUIRED_ADDED,
+    ENUM_NARROWED,
+    TYPE_CHANGED,
+];
+
+const METHODS: &[&str] = &[
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// Report breaking changes from `old` to `new` (YAML or JSON) as error
+/// diagnostics located in `new`. `x-lint-ignore` in `new` applies.
+pub fn compare(old: &str, new: &str) -> Result<Validation, LintError> {
+    let source_map = SourceMap::new(new)?;
+    let old_doc: Value = parser::yaml::from_str(old)
+        .map_err(|e| LintError::YamlParse(format!("old spec: {}", e)))?;
+    let new_doc: Value =
+        parser::yaml::from_str(new).map_err(|e| LintError::YamlParse(e.to_string()))?;
+
+    let mut comparer = Comparer {
+        old: &old_doc,
+        new: &new_doc,
+        findings: Vec::new(),
+    };
+    comparer.paths();
+    comparer.components();
+
+    let (findings, suppressed) = apply_inline_ignores(&inline_ignores(&new_doc), comparer.findings);
+    Ok(Validation {
+        diagnostics: resolve_diagnostics(findings, &source_map),
+        parsed_ok: true,
+        suppressed,
+    })
+}
+
+struct Comparer<'a> {
+    old: &'a Value,
+    new: &'a Value,
+    findings: Vec<Finding>,
+}
+
+impl<'a> Comparer<'a> {
+    fn report(&mut self, rule: RuleId, ptr: String, message: String) {
+        self.findings
+            .push(Finding::new(rule, ptr, message).with_severity(Severity::Error));
+    }
+
+    fn paths(&mut self) {
+        let (old, new) = (self.old, self.new);
+        let (Some(old_paths), new_paths) = (
+            old.get("paths").and_then(Value::as_object),
+            new.get("paths").and_then(Value::as_object),
+        ) else {
+            return;
+        };
+        for (path, old_item) in old_paths {
+            let ptr = format!("/paths/{}", escape_pointer_segment(path));
+            let Some(new_item) = new_paths.and_then(|paths| paths.get(path)) else {
+                self.report(PATH_REMOVED, ptr, format!("Path {} was removed", path));
+                continue;
+            };
+            for method in METHODS {
+                let Some(old_op) = old_item.get(*method) else {
+                    continue;
+                };
+                let op_ptr = format!("{}/{}", ptr, method);
+                match new_item.get(*method) {
+                    Some(new_op) => self.operation(old_op, new_op, &op_ptr),
+                    None => self.report(
+                        OPERATION_REMOVED,
+                        op_ptr,
+                        format!("Operation {} {} was removed", method.to_uppercase(), path),
+                    ),
+                }
+            }
+        }
+    }
+
+    fn operation(&mut self, old_op: &'a Value, new_op: &'a Value, ptr: &str) {
+        self.parameters(old_op, new_op, ptr);
+
+        if let (Some(old_body), Some(new_body)) = (
+            self.resolve(self.old, old_op.get("requestBody")),
+            self.resolve(self.new, new_op.get("requestBody")),
+        ) {
+            self.content(
+                old_body,
+                new_body,
+                &format!("{}/requestBody", ptr),
+                Direction::Request,
+            );
+        }
+
+        let (Some(old_responses), Some(new_responses)) = (
+            old_op.get("responses").and_then(Value::as_object),
+            new_op.get("responses").and_then(Value::as_object),
+        ) else {
+            return;
+        };
+        for (status, old_response) in old_responses {
+            if let (Some(old_response), Some(new_response)) = (
+                self.resolve(self.old, Some(old_response)),
+                self.resolve(self.new, new_responses.get(status)),
+            ) {
+                self.content(
+                    old_response,
+                    new_response,
+                    &format!("{}/responses/{}", ptr, escape_pointer_segment(status)),
+                    Direction::Response,
+                );
+            }
+        }
+    }
+
+    fn parameters(&mut self, old_op: &'a Value, new_op: &'a Value, ptr: &str) {
+        let old_params: Vec<&Value> = old_op
+            .get("parameters")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|param| self.resolve(self.old, Some(param)))
+            .collect();
+        let Some(new_params) = new_op.get("parameters").and_then(Value::as_array) else {
+            return;
+        };
+
+        for (i, new_param) in new_params.iter().enumerate() {
+            let Some(new_param) = self.resolve(self.new, Some(new_param)) else {
+                continue;
+            };
+            let param_ptr = format!("{}/parameters/{}", ptr, i);
+            let name = new_param.get("name").and_then(Value::as_str).unwrap_or("");
+            let location = new_param.get("in").and_then(Value::as_str).unwrap_or("");
+            let old_param = old_params.iter().copied().find(|param| {
+                param.get("name").and_then(Value::as_str) == Some(name)
+                    && param.get("in").and_then(Value::as_str) == Some(location)
+            });
+
+            let required = |param: &Value| param.get("required").and_then(Value::as_bool);
+            if required(new_param) == Some(true)
+                && old_param.is_none_or(|param| required(param) != Some(true))
+            {
+                self.report(
+                    REQUIRED_ADDED,
+                    format!("{}/required", param_ptr),
+                    format!("{} parameter '{}' is now required", location, name),
+                );
+            }
+
+            if let Some(old_param) = old_param
+                && let (Some(old_schema), Some(new_schema)) =
+                    (old_param.get("schema"), new_param.get("schema"))
+            {
+                self.schema(
+                    old_schema,
+                    new_schema,
+                    &format!("{}/schema", param_ptr),
+                    Some(Direction::Request),
+                );
+            }
+        }
+    }
+
+    /// Compare the schemas of media types both versions have.
+    fn content(&mut self, old: &Value, new: &Value, ptr: &str, direction: Direction) {
+        let (Some(old_content), Some(new_content)) = (
+            old.get("content").and_then(Value::as_object),
+            new.get("content").and_then(Value::as_object),
+        ) else {
+            return;
+        };
+        for (media_type, old_media) in old_content {
+            if let (Some(old_schema), Some(new_schema)) = (
+                old_media.get("schema"),
+                new_content.get(media_type).and_then(|m| m.get("schema")),
+            ) {
+                self.schema(
+                    old_schema,
+                    new_schema,
+                    &format!(
+                        "{}/content/{}/schema",
+                        ptr,
+                        escape_pointer_segment(media_type)
+                    ),
+                    Some(direction),
+                );
+            }
+        }
+    }
+
+    fn components(&mut self) {
+        let schemas = |doc: &'a Value| {
+            doc.pointer("/components/schemas")
+                .and_then(Value::as_object)
+        };
+        let (Some(old_schemas), Some(new_schemas)) = (schemas(self.old), schemas(self.new)) else {
+            return;
+        };
+        for (name, old_schema) in old_schemas {
+            if let Some(new_schema) = new_schemas.get(name) {
+                // Components may be used in either direction
+                self.schema(
+                    old_schema,
+                    new_schema,
+                    &format!("/components/schemas/{}", escape_pointer_segment(name)),
+                    None,
+                );
+            }
+        }
+    }
+
+    /// `direction` is `None` where a schema may be read and written.
+    fn schema(&mut self, old: &Value, new: &Value, ptr: &str, direction: Option<Direction>) {
+        let (Some(old), Some(new)) = (old.as_object(), new.as_object()) else {
+            return;
+        };
+
+        let old_ref = old.get("$ref").and_then(Value::as_str);
+        let new_ref = new.get("$ref").and_then(Value::as_str);
+        if old_ref.is_some() || new_ref.is_some() {
+            if old_ref != new_ref {
+                self.report(
+                    TYPE_CHANGED,
+                    ptr.to_string(),
+                    format!(
+                        "Schema changed from {} to {}",
+                        old_ref.unwrap_or("an inline schema"),
+                        new_ref.unwrap_or("an inline schema")
+                    ),
+                );
+            }
+            return;
+        }
+
+        let old_types = types(old);
+        let new_types = types(new);
+        if !old_types.is_empty()
+            && !new_types.is_empty()
+            && !old_types.iter().all(|ty| new_types.contains(ty))
+        {
+            self.report(
+                TYPE_CHANGED,
+                format!("{}/type", ptr),
+                format!(
+                    "Type changed from {} to {}",
+                    old_types.join(" | "),
+                    new_types.join(" | ")
+                ),
+            );
+        }
+
+        // Fewer accepted values only matter where the server produces them;
+        // components may be read, so they are checked too
+        if direction != Some(Direction::Request)
+            && let Some(new_enum) = new.get("enum").and_then(Value::as_array)
+        {
+            let removed: Vec<String> = match old.get("enum").and_then(Value::as_array) {
+                Some(old_enum) => old_enum
+                    .iter()
+                    .filter(|value| !new_enum.contains(value))
+                    .map(Value::to_string)
+                    .collect(),
+                None => vec!["any value not listed".to_string()],
+            };
+            if !removed.is_empty() {
+                self.report(
+                    ENUM_NARROWED,
+                    format!("{}/enum", ptr),
+                    format!("Enum no longer allows {}", removed.join(", ")),
+                );
+            }
+        }
+
+        self.properties(old, new, ptr, direction);
+
+        for keyword in ["items", "additionalProperties", "not"] {
+            if let (Some(old_child), Some(new_child)) = (old.get(keyword), new.get(keyword)) {
+                self.schema(
+                    old_child,
+                    new_child,
+                    &format!("{}/{}", ptr, keyword),
+                    direction,
+                );
+            }
+        }
+        for keyword in ["allOf", "oneOf", "anyOf", "prefixItems"] {
+            if let (Some(Value::Array(old_list)), Some(Value::Array(new_list))) =
+                (old.get(keyword), new.get(keyword))
+                && old_list.len() == new_list.len()
+            {
+                for (i, (old_child, new_child)) in old_list.iter().zip(new_list).enumerate() {
+                    self.schema(
+                        old_child,
+                        new_child,
+                        &format!("{}/{}/{}", ptr, keyword, i),
+                        direction,
+                    );
+                }
+            }
+        }
+    }
+
+    fn properties(
+        &mut self,
+        old: &Map<String, Value>,
+        new: &Map<String, Value>,
+        ptr: &str,
+        direction: Option<Direction>,
+    ) {
+        let empty = Map::new();
+        let old_props = old
+            .get("properties")
+            .and_then(Value::as_object)
+            .unwrap_or(&empty);
+        let new_props = new
+            .get("properties")
+            .and_then(Value::as_object)
+            .unwrap_or(&empty);
+
+        let mut added: Vec<&String> = new_props
+            .keys()
+            .filter(|name| !old_props.contains_key(*name))
+            .collect();
+        for (name, old_schema) in old_props {
+            match new_props.get(name) {
+                Some(new_schema) => self.schema(
+                    old_schema,
+                    new_schema,
+                    &format!("{}/properties/{}", ptr, escape_pointer_segment(name)),
+                    direction,
+                ),
+                None => {
+                    let renamed = added
+                        .iter()
+                        .position(|candidate| new_props.get(*candidate) == Some(old_schema));
+                    match renamed {
+                        Some(i) => {
+                            let new_name = added.remove(i);
+                            self.report(
+                                PROPERTY_RENAMED,
+                                format!("{}/properties/{}", ptr, escape_pointer_segment(new_name)),
+                                format!("Property '{}' was renamed to '{}'", name, new_name),
+                            );
+                        }
+                        None => self.report(
+                            PROPERTY_REMOVED,
+                            format!("{}/properties/{}", ptr, escape_pointer_segment(name)),
+                            format!("Property '{}' was removed", name),
+                        ),
+                    }
+                }
+            }
+        }
+
+        // Only clients sending the schema have to supply new required fields
+        if direction == Some(Direction::Response) {
+            return;
+        }
+        let required = |schema: &Map<String, Value>| -> Vec<String> {
+            schema
+                .get("required")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(|name| name.as_str().map(str::to_string))
+                .collect()
+        };
+        let old_required = required(old);
+        for name in required(new) {
+            if !old_required.contains(&name) {
+                self.report(
+                    REQUIRED_ADDED,
+                    format!("{}/required", ptr),
+                    format!("Property '{}' is now required", name),
+                );
+            }
+        }
+    }
+
+    /// Follow local `$ref`s to a parameter, request body or response.
+    fn resolve(&self, doc: &'a Value, value: Option<&'a Value>) -> Option<&'a Value> {
+        let mut value = value?;
+        // Bounded so reference cycles can't loop forever
+        for _ in 0..16 {
+            match value.get("$ref").and_then(Value::as_str) {
+                Some(reference) => value = doc.pointer(reference.strip_prefix('#')?)?,
+                None => return Some(value),
+            }
+        }
+        None
+    }
+}
+
+/// Types a schema allows, from `type` as a string or list.
+fn types(schema: &Map<String, Value>) -> Vec<&str> {
+    match schema.get("type") {
+        Some(Value::String(ty)) => vec![ty.as_str()],
+        Some(Value::Array(types)) => types.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OLD: &str = r#"openapi: "3.1.0"
+info:
+  title: Pets
+  version: "1.0"
+paths:
+  /pets:
+    get:
+      parameters:
+        - name: limit
+          in: query
+          schema:
+            type: integer
+      responses:
+        "200":
+          description: OK
+    delete:
+      responses:
+        "204":
+          description: Deleted
+  /owners:
+    get:
+      responses:
+        "200":
+          description: OK
+components:
+  schemas:
+    Pet:
+      type: object
+      required: [id]
+      properties:
+        id:
+          type: integer
+        name:
+          type: string
+        status:
+          type: string
+          enum: [available, sold, pending]
+"#;
+
+    const NEW: &str = r#"openapi: "3.1.0"
+info:
+  title: Pets
+  version: "2.0"
+paths:
+  /pets:
+    get:
+      parameters:
+        - name: limit
+          in: query
+          required: true
+          schema:
+            type: integer
+      responses:
+        "200":
+          description: OK
+components:
+  schemas:
+    Pet:
+      type: object
+      required: [id, title]
+      properties:
+        id:
+          type: string
+        title:
+          type: string
+        status:
+          type: string
+          enum: [available, sold]
+"#;
+
+    #[test]
+    fn test_compare() {
+        let result = compare(OLD, NEW).unwrap();
+        let mut found: Vec<(&str, &str)> = result
+            .diagnostics
+            .iter()
+            .map(|d| (d.rule.as_str(), d.pointer.as_str()))
+            .collect();
+        found.sort();
+        assert_eq!(
+            found,
+            [
+                (
+                    "breaking-enum-narrowed",
+                    "/components/schemas/Pet/properties/status/enum"
+                ),
+                ("breaking-operation-removed", "/paths/~1pets/delete"),
+                ("breaking-path-removed", "/paths/~1owners"),
+                (
+                    "breaking-property-renamed",
+                    "/components/schemas/Pet/properties/title"
+                ),
+                (
+                    "breaking-required-added",
+                    "/components/schemas/Pet/required"
+                ),
+                (
+                    "breaking-required-added",
+                    "/paths/~1pets/get/parameters/0/required"
+                ),
+                (
+                    "breaking-type-changed",
+                    "/components/schemas/Pet/properties/id/type"
+                ),
+            ]
+        );
+        assert!(result.has_errors());
+
+        let renamed = result
+            .diagnostics
+            .iter()
+            .find(|d| d.rule == PROPERTY_RENAMED)
+            .unwrap();
+        assert_eq!(renamed.message, "Property 'name' was renamed to 'title'");
+        let narrowed = result
+            .diagnostics
+            .iter()
+            .find(|d| d.rule == ENUM_NARROWED)
+            .unwrap();
+        assert_eq!(narrowed.message, "Enum no longer allows \"pending\"");
+
+        assert!(compare(NEW, NEW).unwrap().diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_enum_narrowed_by_direction() {
+        let spec = |values: &str| {
+            format!(
+                r#"
+openapi: "3.1.0"
+info: {{ title: Pets, version: "1" }}
+paths:
+  /pets:
+    get:
+      parameters:
+        - name: status
+          in: query
+          schema: {{ type: string, enum: [{values}] }}
+      requestBody:
+        content:
+          application/json:
+            schema: {{ type: string, enum: [{values}] }}
+      responses:
+        "200":
+          description: OK
+          content:
+            application/json:
+              schema: {{ type: string, enum: [{values}] }}
+"#
+            )
+        };
+        let result = compare(&spec("available, sold"), &spec("available")).unwrap();
+        let pointers: Vec<&str> = result
+            .diagnostics
+            .iter()
+            .filter(|d| d.rule == ENUM_NARROWED)
+            .map(|d| d.pointer.as_str())
+            .collect();
+        assert_eq!(
+            pointers,
+            ["/paths/~1pets/get/responses/200/content/application~1json/schema/enum"]
+        );
+    }
+}
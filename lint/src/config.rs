@@ -0,0 +1,550 @@
+//! Lint configuration files.
+//!
+//! An `oas-lint.toml` picks the starting ruleset, turns rules on or off,
+//! overrides severities per rule or category, and ignores findings under
+//! some parts of the spec:
+//!
+//! ```toml
+//! # "core" (default), "all", "none" or categories, comma-separated
+//! ruleset = "core"
+//!
+//! [rules]
+//! operation-tags-required = "off"
+//! info-description-present = "error"  # enables the rule too
+//! path-no-verbs = "on"
+//!
+//! [categories]
+//! security = "error"
+//!
+//! [[ignore]]
+//! path = "/paths/~1internal*"          # JSON pointer, `*` matches anything
+//! rules = ["param-description-required"]  # omit to ignore every rule
+//!
+//! # Casing checked by schema-naming-conventions
+//! [naming]
+//! schemas = "PascalCase"
+//! properties = "snake_case"              # default: camelCase or snake_case
+//! paths = "kebab-case"                   # default: unchecked
+//! enums = "SCREAMING_SNAKE_CASE"         # default: unchecked
+//!
+//! # Minimum percentages checked by docs-coverage
+//! [coverage]
+//! descriptions = 90
+//! examples = 50
+//! ```
+//!
+//! [`Linter::from_config`](crate::Linter::from_config) builds a linter from
+//! it; anything set on the linter afterwards takes precedence, and
+//! [`Linter::effective_config`](crate::Linter::effective_config) reports
+//! what will actually run.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::LintError;
+use crate::lint::{CategorySeverity, RuleCategory, Severity};
+
+/// Contents of an `oas-lint.toml`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LintConfig {
+    /// Rules to start from, see [`RuleSet::named`](crate::RuleSet::named)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ruleset: Option<String>,
+    /// Per-rule levels, keyed by rule ID (built-in or custom)
+    pub rules: BTreeMap<String, RuleLevel>,
+    /// Per-category severities, keyed by category name
+    pub categories: BTreeMap<String, RuleLevel>,
+    /// Findings to drop
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub ignore: Vec<Ignore>,
+    /// Casing checked by `schema-naming-conventions`
+    #[serde(skip_serializing_if = "NamingConventions::is_default")]
+    pub naming: NamingConventions,
+    /// Minimums checked by the docs-coverage rule
+    #[serde(skip_serializing_if = "CoverageThresholds::is_default")]
+    pub coverage: CoverageThresholds,
+}
+
+impl LintConfig {
+    /// Name of the config file looked up by [`discover`](Self::discover).
+    pub const FILE_NAME: &'static str = "oas-lint.toml";
+
+    /// Parse an `oas-lint.toml`.
+    pub fn from_toml(input: &str) -> Result<Self, LintError> {
+        let config: Self = toml::from_str(input).map_err(|e| LintError::Config(e.to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Read a config file.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, LintError> {
+        let path = path.as_ref();
+        let input = std::fs::read_to_string(path)
+            .map_err(|e| LintError::Config(format!("{}: {}", path.display(), e)))?;
+        Self::from_toml(&input).map_err(|e| match e {
+            LintError::Config(message) => {
+                LintError::Config(format!("{}: {}", path.display(), message))
+            }
+            e => e,
+        })
+    }
+
+    /// Find `oas-lint.toml` in `dir` or its closest ancestor that has one.
+    pub fn discover(dir: impl AsRef<Path>) -> Option<PathBuf> {
+        dir.as_ref()
+            .ancestors()
+            .map(|dir| dir.join(Self::FILE_NAME))
+            .find(|path| path.is_file())
+    }
+
+    /// Render as TOML, e.g. to report the effective configuration.
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).unwrap_or_default()
+    }
+
+    /// Layer `overrides` on top: its ruleset replaces this one's, its rule
+    /// and category levels win, its ignores are added, and its naming
+    /// conventions and coverage thresholds replace these unless left at the
+    /// defaults.
+    pub fn merge(mut self, overrides: LintConfig) -> Self {
+        if overrides.ruleset.is_some() {
+            self.ruleset = overrides.ruleset;
+        }
+        self.rules.extend(overrides.rules);
+        self.categories.extend(overrides.categories);
+        self.ignore.extend(overrides.ignore);
+        if !overrides.naming.is_default() {
+            self.naming = overrides.naming;
+        }
+        if !overrides.coverage.is_default() {
+            self.coverage = overrides.coverage;
+        }
+        self
+    }
+
+    /// Category overrides in the form the linter applies them.
+    pub fn category_severity(&self) -> Result<CategorySeverity, LintError> {
+        let mut category_severity = CategorySeverity::new();
+        for (name, level) in &self.categories {
+            let category = RuleCategory::parse(name)
+                .ok_or_else(|| LintError::Config(format!("unknown rule category `{}`", name)))?;
+            let severity = match level {
+                RuleLevel::Off => None,
+                RuleLevel::On => continue,
+                RuleLevel::Severity(severity) => Some(*severity),
+            };
+            category_severity.set(category, severity);
+        }
+        Ok(category_severity)
+    }
+
+    fn validate(&self) -> Result<(), LintError> {
+        if let Some(ruleset) = &self.ruleset
+            && crate::RuleSet::named(ruleset).is_none()
+        {
+            return Err(LintError::Config(format!("unknown ruleset `{}`", ruleset)));
+        }
+        // Unknown rule IDs are allowed: they may name custom rules
+        self.category_severity().map(|_| ())
+    }
+}
+
+/// Whether a rule runs, and at which severity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum RuleLevel {
+    Off,
+    /// Enabled at its default severity
+    On,
+    /// Enabled at this severity
+    Severity(Severity),
+}
+
+impl TryFrom<String> for RuleLevel {
+    type Error = String;
+
+    fn try_from(level: String) -> Result<Self, String> {
+        match level.as_str() {
+            "off" => Ok(RuleLevel::Off),
+            "on" => Ok(RuleLevel::On),
+            other => Severity::parse(other)
+                .map(RuleLevel::Severity)
+                .ok_or_else(|| format!("unknown level `{}`", other)),
+        }
+    }
+}
+
+impl From<RuleLevel> for String {
+    fn from(level: RuleLevel) -> String {
+        match level {
+            RuleLevel::Off => "off".to_string(),
+            RuleLevel::On => "on".to_string(),
+            RuleLevel::Severity(severity) => severity.as_str().to_string(),
+        }
+    }
+}
+
+/// Drop findings at or below `path`, optionally only for some rules.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Ignore {
+    /// JSON pointer; `*` matches any run of characters
+    pub path: String,
+    /// Rule IDs to ignore; empty means every rule
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<String>,
+}
+
+impl Ignore {
+    /// Whether a finding of `rule` at `pointer` is ignored.
+    pub fn matches(&self, rule: &str, pointer: &str) -> bool {
+        (self.rules.is_empty() || self.rules.iter().any(|r| r == rule))
+            && (wildcard_match(&self.path, pointer)
+                || wildcard_match(&format!("{}/*", self.path), pointer))
+    }
+}
+
+/// Casing of a name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Casing {
+    /// `petId`
+    Camel,
+    /// `PetId`
+    Pascal,
+    /// `pet_id`
+    Snake,
+    /// `PET_ID`
+    ScreamingSnake,
+    /// `pet-id`
+    Kebab,
+}
+
+impl Casing {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Casing::Camel => "camelCase",
+            Casing::Pascal => "PascalCase",
+            Casing::Snake => "snake_case",
+            Casing::ScreamingSnake => "SCREAMING_SNAKE_CASE",
+            Casing::Kebab => "kebab-case",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Casing> {
+        match s {
+            "camelCase" | "camel" => Some(Casing::Camel),
+            "PascalCase" | "pascal" => Some(Casing::Pascal),
+            "snake_case" | "snake" => Some(Casing::Snake),
+            "SCREAMING_SNAKE_CASE" | "screaming_snake" => Some(Casing::ScreamingSnake),
+            "kebab-case" | "kebab" => Some(Casing::Kebab),
+            _ => None,
+        }
+    }
+
+    /// Whether `name` is written in this casing. Digits may appear anywhere
+    /// but first; acronyms (`petID`) are allowed in camel and Pascal case.
+    pub fn matches(&self, name: &str) -> bool {
+        let mut chars = name.chars();
+        let Some(first) = chars.next() else {
+            return false;
+        };
+        let rest = chars.as_str();
+        match self {
+            Casing::Camel => {
+                first.is_ascii_lowercase() && rest.chars().all(|c| c.is_ascii_alphanumeric())
+            }
+            Casing::Pascal => {
+                first.is_ascii_uppercase() && rest.chars().all(|c| c.is_ascii_alphanumeric())
+            }
+            Casing::Snake => Self::separated(name, '_', |c| c.is_ascii_lowercase()),
+            Casing::ScreamingSnake => Self::separated(name, '_', |c| c.is_ascii_uppercase()),
+            Casing::Kebab => Self::separated(name, '-', |c| c.is_ascii_lowercase()),
+        }
+    }
+
+    /// Words of letters matching `letter` and digits, joined by single `separator`s.
+    fn separated(name: &str, separator: char, letter: impl Fn(char) -> bool) -> bool {
+        name.split(separator).all(|word| {
+            word.chars().next().is_some_and(&letter)
+                && word.chars().all(|c| letter(c) || c.is_ascii_digit())
+        })
+    }
+
+    /// `name` rewritten in this casing, e.g. for suggestions.
+    pub fn convert(&self, name: &str) -> String {
+        let words = split_words(name);
+        let capitalize = |word: &str| {
+            let mut chars = word.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first.to_ascii_uppercase().to_string() + &chars.as_str().to_ascii_lowercase()
+            })
+        };
+        match self {
+            Casing::Camel => words
+                .iter()
+                .enumerate()
+                .map(|(i, word)| {
+                    if i == 0 {
+                        word.to_ascii_lowercase()
+                    } else {
+                        capitalize(word)
+                    }
+                })
+                .collect(),
+            Casing::Pascal => words.iter().map(|word| capitalize(word)).collect(),
+            Casing::Snake => words
+                .iter()
+                .map(|word| word.to_ascii_lowercase())
+                .collect::<Vec<_>>()
+                .join("_"),
+            Casing::ScreamingSnake => words
+                .iter()
+                .map(|word| word.to_ascii_uppercase())
+                .collect::<Vec<_>>()
+                .join("_"),
+            Casing::Kebab => words
+                .iter()
+                .map(|word| word.to_ascii_lowercase())
+                .collect::<Vec<_>>()
+                .join("-"),
+        }
+    }
+}
+
+impl TryFrom<String> for Casing {
+    type Error = String;
+
+    fn try_from(casing: String) -> Result<Self, String> {
+        Casing::parse(&casing).ok_or_else(|| format!("unknown casing `{}`", casing))
+    }
+}
+
+impl From<Casing> for String {
+    fn from(casing: Casing) -> String {
+        casing.as_str().to_string()
+    }
+}
+
+/// Split on separators and lower-to-upper case changes; `HTTPServer`
+/// splits into `HTTP` and `Server`.
+fn split_words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    for part in name.split(|c: char| !c.is_ascii_alphanumeric()) {
+        let chars: Vec<char> = part.chars().collect();
+        let mut word = String::new();
+        for (i, &c) in chars.iter().enumerate() {
+            let boundary = i > 0
+                && c.is_ascii_uppercase()
+                && (chars[i - 1].is_ascii_lowercase()
+                    || chars[i - 1].is_ascii_digit()
+                    || chars
+                        .get(i + 1)
+                        .is_some_and(|next| next.is_ascii_lowercase())
+                        && chars[i - 1].is_ascii_uppercase());
+            if boundary && !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            word.push(c);
+        }
+        if !word.is_empty() {
+            words.push(word);
+        }
+    }
+    words
+}
+
+/// Casing `schema-naming-conventions` expects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NamingConventions {
+    /// Component schema names
+    pub schemas: Casing,
+    /// Property names of every schema; camelCase or snake_case when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub properties: Option<Casing>,
+    /// Literal path segments; unchecked when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paths: Option<Casing>,
+    /// String enum values; unchecked when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enums: Option<Casing>,
+}
+
+impl Default for NamingConventions {
+    fn default() -> Self {
+        Self {
+            schemas: Casing::Pascal,
+            properties: None,
+            paths: None,
+            enums: None,
+        }
+    }
+}
+
+impl NamingConventions {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Minimum coverage percentages the `docs-coverage` rule checks each kind
+/// (operations, schemas, fields) against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CoverageThresholds {
+    /// Minimum share of each kind with a description
+    pub descriptions: u8,
+    /// Minimum share of each kind with an example
+    pub examples: u8,
+}
+
+impl Default for CoverageThresholds {
+    fn default() -> Self {
+        Self {
+            descriptions: 80,
+            examples: 0,
+        }
+    }
+}
+
+impl CoverageThresholds {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// `*` matches any (possibly empty) run of characters.
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        let config = LintConfig::from_toml(
+            r#"
+ruleset = "core"
+
+[rules]
+operation-tags-required = "off"
+info-description-present = "error"
+path-no-verbs = "on"
+
+[categories]
+security = "warn"
+
+[[ignore]]
+path = "/paths/~1internal*"
+rules = ["param-description-required"]
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.rules["info-description-present"],
+            RuleLevel::Severity(Severity::Error)
+        );
+        assert_eq!(config.rules["operation-tags-required"], RuleLevel::Off);
+        assert_eq!(config.ignore.len(), 1);
+
+        let roundtrip = LintConfig::from_toml(&config.to_toml()).unwrap();
+        assert_eq!(roundtrip, config);
+
+        assert!(LintConfig::from_toml("ruleset = \"everything\"").is_err());
+        // The same names as `--rulesets`
+        assert!(LintConfig::from_toml("ruleset = \"recommended, strict, compat\"").is_ok());
+        assert!(LintConfig::from_toml("[categories]\nnaming = \"off\"").is_err());
+        assert!(LintConfig::from_toml("[rules]\npath-no-verbs = \"loud\"").is_err());
+        assert!(LintConfig::from_toml("severity = \"error\"").is_err());
+    }
+
+    #[test]
+    fn test_merge() {
+        let base = LintConfig::from_toml(
+            "ruleset = \"all\"\n[rules]\npath-no-verbs = \"error\"\nschema-no-any = \"off\"",
+        )
+        .unwrap();
+        let overrides = LintConfig::from_toml("[rules]\npath-no-verbs = \"off\"").unwrap();
+        let merged = base.merge(overrides);
+        assert_eq!(merged.ruleset.as_deref(), Some("all"));
+        assert_eq!(merged.rules["path-no-verbs"], RuleLevel::Off);
+        assert_eq!(merged.rules["schema-no-any"], RuleLevel::Off);
+    }
+
+    #[test]
+    fn test_casing() {
+        assert!(Casing::Camel.matches("petId"));
+        assert!(Casing::Camel.matches("petID"));
+        assert!(!Casing::Camel.matches("pet_id"));
+        assert!(Casing::Pascal.matches("HTTPError"));
+        assert!(Casing::Snake.matches("pet_id2"));
+        assert!(!Casing::Snake.matches("pet__id"));
+        assert!(!Casing::Snake.matches("_pet"));
+        assert!(Casing::ScreamingSnake.matches("PET_ID"));
+        assert!(Casing::Kebab.matches("vaccine-records"));
+        assert!(!Casing::Kebab.matches("vaccineRecords"));
+
+        assert_eq!(
+            Casing::Snake.convert("HTTPServerError"),
+            "http_server_error"
+        );
+        assert_eq!(Casing::Camel.convert("pet_id"), "petId");
+        assert_eq!(Casing::Pascal.convert("vaccine-records"), "VaccineRecords");
+        assert_eq!(Casing::Kebab.convert("vaccineRecords"), "vaccine-records");
+        assert_eq!(Casing::ScreamingSnake.convert("inStock"), "IN_STOCK");
+
+        let config = LintConfig::from_toml("[naming]\nproperties = \"snake_case\"").unwrap();
+        assert_eq!(config.naming.properties, Some(Casing::Snake));
+        assert_eq!(config.naming.schemas, Casing::Pascal);
+        assert!(LintConfig::from_toml("[naming]\nproperties = \"Title Case\"").is_err());
+    }
+
+    #[test]
+    fn test_coverage_thresholds() {
+        let config = LintConfig::from_toml("[coverage]\nexamples = 50").unwrap();
+        assert_eq!(config.coverage.descriptions, 80);
+        assert_eq!(config.coverage.examples, 50);
+        assert!(config.to_toml().contains("[coverage]"));
+        assert!(!LintConfig::default().to_toml().contains("[coverage]"));
+    }
+
+    #[test]
+    fn test_ignore_matches() {
+        let ignore = Ignore {
+            path: "/paths/~1internal*".to_string(),
+            rules: vec!["operation-tags-required".to_string()],
+        };
+        assert!(ignore.matches("operation-tags-required", "/paths/~1internal~1jobs/get"));
+        assert!(!ignore.matches("operation-id-required", "/paths/~1internal/get"));
+        assert!(!ignore.matches("operation-tags-required", "/paths/~1pets/get"));
+
+        let ignore = Ignore {
+            path: "/components/schemas/Legacy".to_string(),
+            rules: Vec::new(),
+        };
+        assert!(ignore.matches("schema-no-any", "/components/schemas/Legacy"));
+        assert!(ignore.matches("schema-no-any", "/components/schemas/Legacy/properties/a"));
+        assert!(!ignore.matches("schema-no-any", "/components/schemas/LegacyV2"));
+    }
+}
@@ -0,0 +1,214 @@
+//! Documentation coverage.
+//!
+//! [`DocsCoverage`] counts how many operations, component schemas and
+//! schema properties ("fields") carry a description and an example. The
+//! `docs-coverage` rule reports the kinds that fall below the minimums in
+//! [`CoverageThresholds`](crate::CoverageThresholds); callers can also
+//! render the numbers directly.
+
+use oas3::spec::{MediaType, ObjectOrReference, ObjectSchema, Operation};
+use serde::Serialize;
+
+use crate::LintError;
+use crate::model::{NodeCtx, SpecVisitor, walk_spec};
+
+/// How many items of one kind are documented.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct CoverageCount {
+    pub total: usize,
+    /// Items with a non-empty description (or summary, for operations)
+    pub described: usize,
+    /// Items with at least one example
+    pub with_examples: usize,
+}
+
+impl CoverageCount {
+    /// Share of items with a description, 0-100. 100 when there are none.
+    pub fn description_percent(&self) -> f64 {
+        percent(self.described, self.total)
+    }
+
+    /// Share of items with an example, 0-100. 100 when there are none.
+    pub fn example_percent(&self) -> f64 {
+        percent(self.with_examples, self.total)
+    }
+
+    fn add(&mut self, described: bool, with_examples: bool) {
+        self.total += 1;
+        self.described += described as usize;
+        self.with_examples += with_examples as usize;
+    }
+}
+
+/// Documentation coverage of a spec, gathered with a single
+/// [`walk_spec`] pass.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DocsCoverage {
+    /// Operations; examples count from parameters and request or response
+    /// bodies
+    pub operations: CoverageCount,
+    /// Schemas under `components/schemas`
+    pub schemas: CoverageCount,
+    /// Inline properties of any schema. `$ref` properties are documented
+    /// by their target and not counted.
+    pub fields: CoverageCount,
+}
+
+impl DocsCoverage {
+    pub fn collect(spec: &oas3::Spec) -> Self {
+        let mut coverage = DocsCoverage::default();
+        walk_spec(spec, &mut coverage);
+        coverage
+    }
+
+    /// Each kind with its plural name, e.g. for rendering a table.
+    pub fn kinds(&self) -> [(&'static str, CoverageCount); 3] {
+        [
+            ("operations", self.operations),
+            ("schemas", self.schemas),
+            ("fields", self.fields),
+        ]
+    }
+}
+
+/// Documentation coverage of a YAML or JSON spec.
+pub fn docs_coverage(spec: &str) -> Result<DocsCoverage, LintError> {
+    let spec: oas3::Spec =
+        parser::yaml::from_str(spec).map_err(|e| LintError::OpenApiParse(e.to_string()))?;
+    Ok(DocsCoverage::collect(&spec))
+}
+
+impl<'s> SpecVisitor<'s> for DocsCoverage {
+    fn visit_operation(&mut self, operation: &'s Operation, _ctx: &NodeCtx<'s>, _ptr: &str) {
+        let described = has_text(&operation.summary) || has_text(&operation.description);
+        self.operations
+            .add(described, operation_has_examples(operation));
+    }
+
+    fn visit_schema(&mut self, schema: &'s ObjectSchema, _ctx: &NodeCtx<'s>, ptr: &str) {
+        if is_component_schema(ptr) {
+            self.schemas
+                .add(has_text(&schema.description), schema_has_examples(schema));
+        }
+        for property in schema.properties.values() {
+            if let ObjectOrReference::Object(property) = property {
+                self.fields.add(
+                    has_text(&property.description),
+                    schema_has_examples(property),
+                );
+            }
+        }
+    }
+}
+
+fn percent(part: usize, total: usize) -> f64 {
+    if total == 0 {
+        100.0
+    } else {
+        part as f64 * 100.0 / total as f64
+    }
+}
+
+fn has_text(text: &Option<String>) -> bool {
+    text.as_deref().is_some_and(|text| !text.trim().is_empty())
+}
+
+/// `/components/schemas/{name}` itself, not a schema nested in it.
+fn is_component_schema(ptr: &str) -> bool {
+    ptr.strip_prefix("/components/schemas/")
+        .is_some_and(|name| !name.contains('/'))
+}
+
+fn schema_has_examples(schema: &ObjectSchema) -> bool {
+    schema.example.is_some() || !schema.examples.is_empty()
+}
+
+fn media_type_has_examples(media_type: &MediaType) -> bool {
+    media_type
+        .examples
+        .as_ref()
+        .is_some_and(|examples| !examples.is_empty())
+        || matches!(&media_type.schema, Some(ObjectOrReference::Object(schema)) if schema_has_examples(schema))
+}
+
+fn operation_has_examples(operation: &Operation) -> bool {
+    let parameters = operation.parameters.iter().any(|param| {
+        matches!(param, ObjectOrReference::Object(param)
+            if param.example.is_some() || !param.examples.is_empty())
+    });
+    let request = matches!(&operation.request_body, Some(ObjectOrReference::Object(body))
+        if body.content.values().any(media_type_has_examples));
+    let responses = operation.responses.iter().flatten().any(|(_, response)| {
+        matches!(response, ObjectOrReference::Object(response)
+            if response.content.values().any(media_type_has_examples))
+    });
+    parameters || request || responses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_docs_coverage() {
+        let coverage = docs_coverage(
+            r#"
+openapi: 3.1.0
+info: { title: Pets, version: "1" }
+paths:
+  /pets:
+    get:
+      summary: List pets
+      responses:
+        "200":
+          description: OK
+          content:
+            application/json:
+              example: [{ name: Rex }]
+    post:
+      responses:
+        "201":
+          description: Created
+components:
+  schemas:
+    Pet:
+      description: A pet
+      type: object
+      properties:
+        name:
+          type: string
+          description: The pet's name
+          example: Rex
+        owner:
+          $ref: '#/components/schemas/Owner'
+    Owner:
+      type: object
+      properties:
+        name:
+          type: string
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            coverage.operations,
+            CoverageCount {
+                total: 2,
+                described: 1,
+                with_examples: 1
+            }
+        );
+        assert_eq!(coverage.schemas.described, 1);
+        assert_eq!(coverage.schemas.total, 2);
+        assert_eq!(
+            coverage.fields,
+            CoverageCount {
+                total: 2,
+                described: 1,
+                with_examples: 1
+            }
+        );
+        assert_eq!(coverage.fields.description_percent(), 50.0);
+        assert_eq!(CoverageCount::default().example_percent(), 100.0);
+    }
+}
@@ -0,0 +1,389 @@
+//! Applying [`Fix`]es to a spec.
+//!
+//! Fixes are written as JSON Patch operations. [`text_edits`] turns them
+//! into edits of the source text located through its [`SpanDb`], so
+//! [`Linter::apply_fixes`](crate::Linter::apply_fixes) changes only the
+//! nodes a fix touches and keeps the rest of the file, comments and
+//! formatting included. [`apply_fix`] applies a fix to a parsed document
+//! instead.
+
+use serde_json::Value;
+
+use crate::lint::{Diagnostic, Fix, PatchOp, TextEdit};
+use crate::model::{ByteSpan, SourceMap, SpanDb};
+
+/// A spec rewritten by [`Linter::apply_fixes`](crate::Linter::apply_fixes).
+#[derive(Debug)]
+pub struct FixResult {
+    /// The fixed spec: the input with the fixes' edits applied
+    pub spec: String,
+    /// Diagnostics whose fixes were applied
+    pub applied: Vec<Diagnostic>,
+    /// Diagnostics with fixes that weren't applied: they overlap an
+    /// earlier fix, or can't be made as text edits (see [`text_edits`])
+    pub skipped: Vec<Diagnostic>,
+}
+
+impl FixResult {
+    /// Whether any fix changed the spec.
+    pub fn changed(&self) -> bool {
+        !self.applied.is_empty()
+    }
+}
+
+/// The edits of the text `map` was built from that make `fix`, or `None`
+/// if one of its operations can't be made without rewriting more than the
+/// node it touches. Supported: replacing a single-line value, adding a
+/// member to a mapping, and renaming a key within its mapping (a `move`
+/// between siblings). Nodes behind YAML aliases are never edited.
+pub fn text_edits(fix: &Fix, map: &SourceMap) -> Option<Vec<TextEdit>> {
+    let source = Source {
+        text: map.source(),
+        spans: map.spans(),
+        json: parser::fmt::SpecFormat::detect(map.source()) == parser::fmt::SpecFormat::Json,
+    };
+    fix.ops.iter().map(|op| source.edit(op)).collect()
+}
+
+/// Apply `edits` to `source`. Edits must not overlap; insertions at the
+/// same offset keep their order.
+pub fn apply_edits(source: &str, edits: &[TextEdit]) -> String {
+    let mut order: Vec<usize> = (0..edits.len()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse((edits[i].start_byte, i)));
+    let mut text = source.to_string();
+    for i in order {
+        let edit = &edits[i];
+        text.replace_range(
+            edit.start_byte as usize..edit.end_byte as usize,
+            &edit.replacement,
+        );
+    }
+    text
+}
+
+struct Source<'a> {
+    text: &'a str,
+    spans: &'a SpanDb,
+    json: bool,
+}
+
+impl Source<'_> {
+    fn edit(&self, op: &PatchOp) -> Option<TextEdit> {
+        match op {
+            PatchOp::Replace { path, value } => self.replace(path, value),
+            PatchOp::Add { path, value } if self.spans.value_spans.contains_key(path) => {
+                self.replace(path, value)
+            }
+            PatchOp::Add { path, value } => self.insert(path, value),
+            PatchOp::Move { from, path } => self.rename(from, path),
+            PatchOp::Remove { .. } => None,
+        }
+    }
+
+    fn replace(&self, path: &str, value: &Value) -> Option<TextEdit> {
+        if self.spans.alias_ptrs.contains(path) {
+            return None;
+        }
+        let (start, end) = self.token(*self.spans.value_spans.get(path)?)?;
+        // Block collections and multi-line scalars would need re-indenting
+        if self.text[start..end].contains('\n') {
+            return None;
+        }
+        Some(edit(start, end, self.value(value)))
+    }
+
+    /// Add `path` to its parent mapping, before the first member.
+    fn insert(&self, path: &str, value: &Value) -> Option<TextEdit> {
+        let (parent, key) = split(path).ok()?;
+        if self.spans.alias_ptrs.contains(parent) {
+            return None;
+        }
+        let member = format!("{}: {}", self.key(&key), self.value(value));
+        let first = self
+            .spans
+            .key_spans
+            .iter()
+            .filter(|(ptr, _)| is_child(ptr, parent))
+            .filter_map(|(_, span)| self.token(*span))
+            .map(|(start, _)| start)
+            .min();
+
+        let Some(start) = first else {
+            // Only an empty flow mapping has no members
+            let (start, end) = self.token(*self.spans.value_spans.get(parent)?)?;
+            return (self.text[start..end].trim() == "{}")
+                .then(|| edit(start, end, format!("{{{}}}", member)));
+        };
+        let before = self.text[..start].trim_end();
+        let replacement = if before.ends_with('{') {
+            // Flow mapping or JSON object: keep the members' line layout
+            match self.text[before.len()..start].rfind('\n') {
+                Some(i) => format!("{},{}", member, &self.text[before.len() + i..start]),
+                None => format!("{}, ", member),
+            }
+        } else {
+            // Block mapping: a line of its own at the members' indentation,
+            // which follows a `- ` when the mapping is a sequence item
+            let line_start = self.text[..start].rfind('\n').map_or(0, |i| i + 1);
+            let indent: String = self.text[line_start..start]
+                .chars()
+                .map(|c| if c.is_whitespace() { c } else { ' ' })
+                .collect();
+            format!("{}\n{}", member, indent)
+        };
+        Some(edit(start, start, replacement))
+    }
+
+    /// Rename the key of `from` to the last token of `path`.
+    fn rename(&self, from: &str, path: &str) -> Option<TextEdit> {
+        let (from_parent, _) = split(from).ok()?;
+        let (parent, key) = split(path).ok()?;
+        if from_parent != parent
+            || self.spans.key_spans.contains_key(path)
+            || self.spans.alias_ptrs.contains(parent)
+        {
+            return None;
+        }
+        let (start, end) = self.token(*self.spans.key_spans.get(from)?)?;
+        let replacement = match self.text[start..].chars().next() {
+            Some('"') => serde_json::to_string(&key).ok()?,
+            Some('\'') => format!("'{}'", key.replace('\'', "''")),
+            _ => self.key(&key),
+        };
+        Some(edit(start, end, replacement))
+    }
+
+    /// Byte range of the scalar or collection at `span`, including the
+    /// quotes of a quoted scalar.
+    fn token(&self, span: ByteSpan) -> Option<(usize, usize)> {
+        let (start, end) = (span.start as usize, span.end as usize);
+        self.text.get(start..end)?;
+        let bytes = self.text.as_bytes();
+        match (start.checked_sub(1).map(|i| bytes[i]), bytes.get(end)) {
+            (Some(open @ (b'"' | b'\'')), Some(close)) if open == *close => {
+                Some((start - 1, end + 1))
+            }
+            _ => Some((start, end)),
+        }
+    }
+
+    fn key(&self, key: &str) -> String {
+        if self.json || !is_plain(key) {
+            Value::from(key).to_string()
+        } else {
+            key.to_string()
+        }
+    }
+
+    /// `value` in flow style, which is valid in both YAML and JSON.
+    fn value(&self, value: &Value) -> String {
+        match value {
+            _ if self.json => value.to_string(),
+            Value::String(s) => self.key(s),
+            Value::Array(items) => {
+                let items: Vec<String> = items.iter().map(|item| self.value(item)).collect();
+                format!("[{}]", items.join(", "))
+            }
+            Value::Object(members) => {
+                let members: Vec<String> = members
+                    .iter()
+                    .map(|(key, value)| format!("{}: {}", self.key(key), self.value(value)))
+                    .collect();
+                format!("{{{}}}", members.join(", "))
+            }
+            _ => value.to_string(),
+        }
+    }
+}
+
+fn edit(start: usize, end: usize, replacement: String) -> TextEdit {
+    TextEdit {
+        start_byte: start as u32,
+        end_byte: end as u32,
+        replacement,
+    }
+}
+
+/// Whether `ptr` is a member of the mapping at `parent`.
+fn is_child(ptr: &str, parent: &str) -> bool {
+    ptr.strip_prefix(parent)
+        .and_then(|rest| rest.strip_prefix('/'))
+        .is_some_and(|rest| !rest.contains('/'))
+}
+
+/// Whether `s` reads back as the same string when written as a plain YAML
+/// scalar.
+fn is_plain(s: &str) -> bool {
+    const KEYWORDS: &[&str] = &["true", "false", "null", "yes", "no", "on", "off", "y", "n"];
+
+    s.starts_with(|c: char| c.is_ascii_alphanumeric() || c == '_')
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '/'))
+        && !KEYWORDS.contains(&s.to_ascii_lowercase().as_str())
+        && s.parse::<f64>().is_err()
+}
+
+/// Apply all of `fix`'s operations to a parsed document, or none of them.
+pub fn apply_fix(document: &mut Value, fix: &Fix) -> Result<(), String> {
+    let mut patched = document.clone();
+    for op in &fix.ops {
+        apply_op(&mut patched, op)?;
+    }
+    *document = patched;
+    Ok(())
+}
+
+fn apply_op(document: &mut Value, op: &PatchOp) -> Result<(), String> {
+    match op {
+        PatchOp::Add { path, value } => add(document, path, value.clone()),
+        PatchOp::Replace { path, value } => {
+            let target = document
+                .pointer_mut(path)
+                .ok_or_else(|| format!("{} does not exist", path))?;
+            *target = value.clone();
+            Ok(())
+        }
+        PatchOp::Remove { path } => remove(document, path).map(|_| ()),
+        PatchOp::Move { from, path } => {
+            let value = remove(document, from)?;
+            add(document, path, value)
+        }
+    }
+}
+
+/// Split a pointer into its parent and unescaped last token.
+fn split(path: &str) -> Result<(&str, String), String> {
+    let (parent, token) = path
+        .rsplit_once('/')
+        .ok_or_else(|| format!("invalid JSON pointer `{}`", path))?;
+    Ok((parent, token.replace("~1", "/").replace("~0", "~")))
+}
+
+fn add(document: &mut Value, path: &str, value: Value) -> Result<(), String> {
+    let (parent, token) = split(path)?;
+    match document.pointer_mut(parent) {
+        Some(Value::Object(map)) => {
+            map.insert(token, value);
+            Ok(())
+        }
+        Some(Value::Array(items)) => {
+            let index = if token == "-" {
+                items.len()
+            } else {
+                token
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|index| *index <= items.len())
+                    .ok_or_else(|| format!("invalid array index in {}", path))?
+            };
+            items.insert(index, value);
+            Ok(())
+        }
+        _ => Err(format!("{} does not exist", parent)),
+    }
+}
+
+fn remove(document: &mut Value, path: &str) -> Result<Value, String> {
+    let (parent, token) = split(path)?;
+    let removed = match document.pointer_mut(parent) {
+        Some(Value::Object(map)) => map.remove(&token),
+        Some(Value::Array(items)) => token
+            .parse::<usize>()
+            .ok()
+            .filter(|index| *index < items.len())
+            .map(|index| items.remove(index)),
+        _ => None,
+    };
+    removed.ok_or_else(|| format!("{} does not exist", path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_text_edits() {
+        let source = "a: {b: 1} # flow\nc:\n  - name: x\n    in: query\n'd': 1\n";
+        let map = SourceMap::new(source).unwrap();
+        let fix = Fix::new(
+            "edit",
+            vec![
+                PatchOp::Add {
+                    path: "/a/z".to_string(),
+                    value: json!(true),
+                },
+                PatchOp::Add {
+                    path: "/c/0/required".to_string(),
+                    value: json!("yes"),
+                },
+                PatchOp::Replace {
+                    path: "/c/0/in".to_string(),
+                    value: json!("path"),
+                },
+                PatchOp::Move {
+                    from: "/d".to_string(),
+                    path: "/e".to_string(),
+                },
+            ],
+        );
+        let edits = text_edits(&fix, &map).unwrap();
+        assert_eq!(
+            apply_edits(source, &edits),
+            "a: {z: true, b: 1} # flow\nc:\n  - required: \"yes\"\n    name: x\n    in: path\n'e': 1\n"
+        );
+
+        // Moving a node to another mapping would rewrite both
+        let fix = Fix::new(
+            "move",
+            vec![PatchOp::Move {
+                from: "/d".to_string(),
+                path: "/a/d".to_string(),
+            }],
+        );
+        assert!(text_edits(&fix, &map).is_none());
+    }
+
+    #[test]
+    fn test_apply_fix() {
+        let mut document = json!({ "responses": { "2xx": { "description": "OK" } }, "tags": [] });
+        let fix = Fix::new(
+            "normalize",
+            vec![
+                PatchOp::Move {
+                    from: "/responses/2xx".to_string(),
+                    path: "/responses/2XX".to_string(),
+                },
+                PatchOp::Add {
+                    path: "/tags/-".to_string(),
+                    value: json!("pets"),
+                },
+                PatchOp::Replace {
+                    path: "/responses/2XX/description".to_string(),
+                    value: json!("Success"),
+                },
+            ],
+        );
+        apply_fix(&mut document, &fix).unwrap();
+        assert_eq!(
+            document,
+            json!({ "responses": { "2XX": { "description": "Success" } }, "tags": ["pets"] })
+        );
+
+        // A failing operation leaves the document untouched
+        let fix = Fix::new(
+            "broken",
+            vec![
+                PatchOp::Remove {
+                    path: "/tags/0".to_string(),
+                },
+                PatchOp::Remove {
+                    path: "/missing".to_string(),
+                },
+            ],
+        );
+        let before = document.clone();
+        assert!(apply_fix(&mut document, &fix).is_err());
+        assert_eq!(document, before);
+    }
+}
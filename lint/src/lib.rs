@@ -39,17 +39,48 @@
 //! // Or run specific rules
 //! let result = lint(spec, &["operation-id-required", "responses-exist"]).unwrap();
 //! ```
+//!
+//! Custom rules implement [`Rule`] and run through a [`Linter`] next to
+//! the built-in ones. Existing Spectral rulesets load with
+//! [`SpectralRuleset`]. Projects configure rules, severities and ignores
+//! in an `oas-lint.toml` read by [`LintConfig`]. Results are rendered by a
+//! [`Reporter`]; [`ReportFormat`] picks a built-in one by name. [`compare`]
+//! reports breaking changes between two versions of a spec.
 
 mod api;
+mod compare;
+mod config;
+mod coverage;
+mod fix;
 mod lint;
 mod model;
 mod parse;
+pub mod plugin;
+mod report;
 mod rules;
+pub mod sarif;
+mod spectral;
+mod suppress;
 mod testutil;
 
-pub use api::{Validation, available_rules, lint, lint_all, lint_with_ruleset};
-pub use lint::{Diagnostic, Finding, FixPlan, RuleId, RuleSet, Severity, TextEdit};
-pub use model::{ByteSpan, LineCol, Range};
+pub use api::{
+    Linter, Validation, available_rules, lint, lint_all, lint_with_config, lint_with_ruleset,
+};
+pub use compare::{BREAKING_RULES, compare};
+pub use config::{Casing, CoverageThresholds, Ignore, LintConfig, NamingConventions, RuleLevel};
+pub use coverage::{CoverageCount, DocsCoverage, docs_coverage};
+pub use fix::{FixResult, apply_edits, apply_fix, text_edits};
+pub use lint::{
+    CategorySeverity, Diagnostic, Finding, Fix, FixPlan, LintCtx, PatchOp, Rule, RuleCategory,
+    RuleId, RuleSet, Severity, TextEdit,
+};
+pub use model::{
+    ByteSpan, Direction, LineCol, Location, MAX_SNIPPET_LINES, NodeCtx, Range, Section, SourceMap,
+    SpecStats, SpecVisitor, escape_pointer_segment, pointer, walk_spec,
+};
+pub use report::{JsonReporter, PrettyReporter, ReportFormat, Reporter, SarifReporter, SpecFile};
+pub use spectral::SpectralRuleset;
+pub use suppress::{IGNORE_EXTENSION, InlineIgnore, inline_ignores};
 
 use thiserror::Error;
 
@@ -63,6 +94,18 @@ pub enum LintError {
     /// Failed to parse as valid OpenAPI
     #[error("OpenAPI parse error: {0}")]
     OpenApiParse(String),
+
+    /// Invalid lint configuration
+    #[error("Config error: {0}")]
+    Config(String),
+
+    /// Failed to load a ruleset
+    #[error("Ruleset error: {0}")]
+    Ruleset(String),
+
+    /// Failed to load a rule plugin
+    #[error("Plugin load error: {0}")]
+    Plugin(String),
 }
 
 #[cfg(test)]
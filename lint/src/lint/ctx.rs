@@ -3,7 +3,8 @@ use std::sync::OnceLock;
 use rustc_hash::FxHashSet;
 
 use super::Indexes;
-use crate::model::SpanDb;
+use crate::config::{CoverageThresholds, NamingConventions};
+use crate::model::{NodeCtx, SpanDb, SpecVisitor, walk_spec};
 
 /// Context passed to lint rules, providing read-only access to
 /// the parsed spec and shared indexes.
@@ -16,6 +17,14 @@ pub struct LintCtx<'a> {
     pub span_db: &'a SpanDb,
     /// Lazily-computed set of all $ref values used in the spec
     used_refs: OnceLock<FxHashSet<String>>,
+    /// The text the spec was parsed from, if known
+    source: Option<&'a str>,
+    /// Lazily-computed JSON form of the spec
+    document: OnceLock<serde_json::Value>,
+    /// Casing `schema-naming-conventions` expects
+    naming: NamingConventions,
+    /// Minimums the docs-coverage rule checks
+    coverage: CoverageThresholds,
 }
 
 impl<'a> LintCtx<'a> {
@@ -25,156 +34,72 @@ impl<'a> LintCtx<'a> {
             indexes,
             span_db,
             used_refs: OnceLock::new(),
+            source: None,
+            document: OnceLock::new(),
+            naming: NamingConventions::default(),
+            coverage: CoverageThresholds::default(),
         }
     }
 
-    /// Returns the set of all $ref values used in the spec.
-    /// Lazily computed on first access.
-    pub fn used_refs(&self) -> &FxHashSet<String> {
-        self.used_refs
-            .get_or_init(|| collect_all_refs_from_spec(self.spec))
+    /// Read [`document`](Self::document) from the text the spec was parsed
+    /// from instead of serializing the spec.
+    pub fn with_source(mut self, source: &'a str) -> Self {
+        self.source = Some(source);
+        self
     }
-}
-
-/// Collect all $ref paths used in the specification by traversing the parsed spec
-fn collect_all_refs_from_spec(spec: &oas3::Spec) -> FxHashSet<String> {
-    let mut refs = FxHashSet::default();
 
-    // Helper to collect a ref from ObjectOrReference
-    fn collect_ref<T>(oor: &oas3::spec::ObjectOrReference<T>, out: &mut FxHashSet<String>) {
-        if let oas3::spec::ObjectOrReference::Ref { ref_path, .. } = oor {
-            out.insert(ref_path.clone());
-        }
+    /// Check names against `naming` instead of the default conventions.
+    pub fn with_naming(mut self, naming: NamingConventions) -> Self {
+        self.naming = naming;
+        self
     }
 
-    // Traverse paths and operations
-    if let Some(paths) = &spec.paths {
-        for path_item in paths.values() {
-            // Check all operations
-            for operation in [
-                &path_item.get,
-                &path_item.post,
-                &path_item.put,
-                &path_item.patch,
-                &path_item.delete,
-                &path_item.options,
-                &path_item.head,
-                &path_item.trace,
-            ]
-            .iter()
-            .filter_map(|op| op.as_ref())
-            {
-                // Request body
-                if let Some(rb) = &operation.request_body {
-                    collect_ref(rb, &mut refs);
-                    if let oas3::spec::ObjectOrReference::Object(rb_obj) = rb {
-                        for media_type in rb_obj.content.values() {
-                            if let Some(schema) = &media_type.schema {
-                                collect_schema_refs(schema, &mut refs);
-                            }
-                        }
-                    }
-                }
-
-                // Parameters
-                for param in &operation.parameters {
-                    collect_ref(param, &mut refs);
-                    if let oas3::spec::ObjectOrReference::Object(param_obj) = param
-                        && let Some(schema) = &param_obj.schema
-                    {
-                        collect_schema_refs(schema, &mut refs);
-                    }
-                }
-
-                // Responses
-                if let Some(responses) = &operation.responses {
-                    for response in responses.values() {
-                        collect_ref(response, &mut refs);
-                        if let oas3::spec::ObjectOrReference::Object(response_obj) = response {
-                            for media_type in response_obj.content.values() {
-                                if let Some(schema) = &media_type.schema {
-                                    collect_schema_refs(schema, &mut refs);
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-
-            // Path-level parameters
-            for param in &path_item.parameters {
-                collect_ref(param, &mut refs);
-            }
-        }
+    /// Casing `schema-naming-conventions` expects.
+    pub fn naming(&self) -> &NamingConventions {
+        &self.naming
     }
 
-    // Traverse components
-    if let Some(components) = &spec.components {
-        // Schemas can reference other schemas
-        for schema in components.schemas.values() {
-            collect_schema_refs(schema, &mut refs);
-        }
-
-        // Parameters can reference schemas
-        for param in components.parameters.values() {
-            if let oas3::spec::ObjectOrReference::Object(param_obj) = param
-                && let Some(schema) = &param_obj.schema
-            {
-                collect_schema_refs(schema, &mut refs);
-            }
-        }
+    /// Check docs coverage against `coverage` instead of the defaults.
+    pub fn with_coverage(mut self, coverage: CoverageThresholds) -> Self {
+        self.coverage = coverage;
+        self
+    }
 
-        // Responses can reference schemas
-        for response in components.responses.values() {
-            if let oas3::spec::ObjectOrReference::Object(response_obj) = response {
-                for media_type in response_obj.content.values() {
-                    if let Some(schema) = &media_type.schema {
-                        collect_schema_refs(schema, &mut refs);
-                    }
-                }
-            }
-        }
+    /// Minimums the docs-coverage rule checks.
+    pub fn coverage(&self) -> &CoverageThresholds {
+        &self.coverage
+    }
 
-        // Request bodies can reference schemas
-        for request_body in components.request_bodies.values() {
-            if let oas3::spec::ObjectOrReference::Object(rb_obj) = request_body {
-                for media_type in rb_obj.content.values() {
-                    if let Some(schema) = &media_type.schema {
-                        collect_schema_refs(schema, &mut refs);
-                    }
-                }
-            }
-        }
+    /// Returns the set of all $ref values used in the spec.
+    /// Lazily computed on first access.
+    pub fn used_refs(&self) -> &FxHashSet<String> {
+        self.used_refs
+            .get_or_init(|| collect_all_refs_from_spec(self.spec))
     }
 
-    refs
+    /// The spec as JSON, for rules that address it by path rather than
+    /// through the typed model. Lazily computed on first access, from the
+    /// source text when it is known.
+    pub fn document(&self) -> &serde_json::Value {
+        self.document.get_or_init(|| {
+            self.source
+                .and_then(|source| parser::yaml::from_str(source).ok())
+                .unwrap_or_else(|| serde_json::to_value(self.spec).unwrap_or_default())
+        })
+    }
 }
 
-/// Recursively collect schema references
-fn collect_schema_refs(
-    schema: &oas3::spec::ObjectOrReference<oas3::spec::ObjectSchema>,
-    refs: &mut FxHashSet<String>,
-) {
-    match schema {
-        oas3::spec::ObjectOrReference::Ref { ref_path, .. } => {
-            refs.insert(ref_path.clone());
-        }
-        oas3::spec::ObjectOrReference::Object(obj) => {
-            // Properties
-            for prop_schema in obj.properties.values() {
-                collect_schema_refs(prop_schema, refs);
-            }
+/// Collect all $ref paths used in the specification by traversing the parsed spec
+fn collect_all_refs_from_spec(spec: &oas3::Spec) -> FxHashSet<String> {
+    struct RefCollector(FxHashSet<String>);
 
-            // allOf, oneOf, anyOf
-            for sub_schema in &obj.all_of {
-                collect_schema_refs(sub_schema, refs);
-            }
-            for sub_schema in &obj.one_of {
-                collect_schema_refs(sub_schema, refs);
-            }
-            for sub_schema in &obj.any_of {
-                collect_schema_refs(sub_schema, refs);
-            }
+    impl<'s> SpecVisitor<'s> for RefCollector {
+        fn visit_ref(&mut self, ref_path: &'s str, _ctx: &NodeCtx<'s>, _ptr: &str) {
+            self.0.insert(ref_path.to_string());
         }
     }
+
+    let mut collector = RefCollector(FxHashSet::default());
+    walk_spec(spec, &mut collector);
+    collector.0
 }
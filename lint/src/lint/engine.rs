@@ -1,10 +1,15 @@
+use std::collections::BTreeSet;
+use std::sync::{Mutex, PoisonError};
+
+use serde::Serialize;
+use serde_json::Value;
+
 use super::LintCtx;
-use crate::model::{ByteSpan, LineIndex, Range, SpanDb};
+use crate::model::{ByteSpan, Range, SourceMap};
 use crate::rules;
 
 /// Unique identifier for each lint rule
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
-#[repr(u8)]
 pub enum RuleId {
     // === Baseline validation (OAS-VAL) ===
     /// OpenAPI version must be 3.1.x
@@ -19,6 +24,8 @@ pub enum RuleId {
     InfoDescriptionPresent,
     /// contact and license should be present for public APIs
     InfoContactLicensePresent,
+    /// Description and example coverage meets the configured minimums
+    DocsCoverage,
     /// servers must use HTTPS in production
     ServersHttpsRequired,
     /// paths must not be empty
@@ -57,6 +64,8 @@ pub enum RuleId {
     OperationMethodSemantics,
     /// deprecated: true must include replacement note
     OperationDeprecatedNote,
+    /// Operations should be grouped under at least one tag
+    OperationTagsRequired,
 
     // === Parameters (PAR) ===
     /// Parameter names should be consistent case
@@ -67,6 +76,8 @@ pub enum RuleId {
     ParamEnumDocumented,
     /// Date/time formats must be explicit
     ParamDateTimeFormat,
+    /// Parameters should have a description
+    ParamDescriptionRequired,
 
     // === Request bodies (REQ) ===
     /// requestBody must define content with at least one media type
@@ -87,6 +98,8 @@ pub enum RuleId {
     Response204NoBody,
     /// Standard error model should be consistent
     ResponseErrorModelConsistent,
+    /// Response keys must be status codes, ranges (`2XX`) or `default`
+    ResponseStatusCodeValid,
 
     // === Schema quality (SCH) ===
     /// Every schema should have type
@@ -105,12 +118,17 @@ pub enum RuleId {
     SchemaNamingConventions,
     /// No "any" schemas unless explicitly approved
     SchemaNoAny,
+    /// Examples must match their schema
+    ExampleMatchesSchema,
 
     // === Components (CMP) ===
     /// Reuse shared schemas via components/schemas
     ComponentReuseSchemas,
-    /// No unused components
+    /// No components unreachable from a path or webhook, and no security
+    /// schemes without a requirement
     ComponentNoUnused,
+    /// Local `$ref`s must point at an existing node
+    ComponentRefDangling,
 
     // === Security (SEC) ===
     /// Define components.securitySchemes for every referenced scheme
@@ -121,6 +139,19 @@ pub enum RuleId {
     SecurityNoApiKeyInQuery,
     /// OAuth2 flows should be complete with scopes
     SecurityOAuth2Complete,
+
+    // === Generator compatibility (GEN) ===
+    /// Operations with inline body schemas should have an operationId
+    GenInlineBodyOperationId,
+    /// anyOf unions generate as exclusive unions
+    GenAnyOfUnion,
+    /// Responses should have a single content type
+    GenMultipleResponseContentTypes,
+    /// Parameters should be declared on operations, not path items
+    GenPathLevelParameters,
+
+    /// A rule supplied by the caller through the [`Rule`](super::Rule) trait
+    Custom(&'static str),
 }
 
 impl RuleId {
@@ -134,6 +165,7 @@ impl RuleId {
             RuleId::InfoVersionRequired => "info-version-required",
             RuleId::InfoDescriptionPresent => "info-description-present",
             RuleId::InfoContactLicensePresent => "info-contact-license-present",
+            RuleId::DocsCoverage => "docs-coverage",
             RuleId::ServersHttpsRequired => "servers-https-required",
             RuleId::PathsNotEmpty => "paths-not-empty",
             RuleId::ServerUrlNonEmpty => "server-url-non-empty",
@@ -153,11 +185,13 @@ impl RuleId {
             RuleId::ResponsesHave2xx => "responses-have-2xx",
             RuleId::OperationMethodSemantics => "operation-method-semantics",
             RuleId::OperationDeprecatedNote => "operation-deprecated-note",
+            RuleId::OperationTagsRequired => "operation-tags-required",
             // Parameters
             RuleId::ParamNamingConsistent => "param-naming-consistent",
             RuleId::ParamSchemaRequired => "param-schema-required",
             RuleId::ParamEnumDocumented => "param-enum-documented",
             RuleId::ParamDateTimeFormat => "param-date-time-format",
+            RuleId::ParamDescriptionRequired => "param-description-required",
             // Request bodies
             RuleId::RequestBodyContentRequired => "request-body-content-required",
             RuleId::RequestBodyJsonMediaType => "request-body-json-media-type",
@@ -168,6 +202,7 @@ impl RuleId {
             RuleId::ResponseSchemaRequired => "response-schema-required",
             RuleId::Response204NoBody => "response-204-no-body",
             RuleId::ResponseErrorModelConsistent => "response-error-model-consistent",
+            RuleId::ResponseStatusCodeValid => "response-status-code-valid",
             // Schemas
             RuleId::SchemaTypeRequired => "schema-type-required",
             RuleId::SchemaObjectPropertiesRequired => "schema-object-properties-required",
@@ -177,17 +212,40 @@ impl RuleId {
             RuleId::SchemaReadWriteOnlyCorrect => "schema-read-write-only-correct",
             RuleId::SchemaNamingConventions => "schema-naming-conventions",
             RuleId::SchemaNoAny => "schema-no-any",
+            RuleId::ExampleMatchesSchema => "example-matches-schema",
             // Components
             RuleId::ComponentReuseSchemas => "component-reuse-schemas",
             RuleId::ComponentNoUnused => "component-no-unused",
+            RuleId::ComponentRefDangling => "component-ref-dangling",
             // Security
             RuleId::SecuritySchemesDefined => "security-schemes-defined",
             RuleId::SecurityDeclared => "security-declared",
             RuleId::SecurityNoApiKeyInQuery => "security-no-api-key-in-query",
             RuleId::SecurityOAuth2Complete => "security-oauth2-complete",
+            // Generator compatibility
+            RuleId::GenInlineBodyOperationId => "gen-inline-body-operation-id",
+            RuleId::GenAnyOfUnion => "gen-any-of-union",
+            RuleId::GenMultipleResponseContentTypes => "gen-multiple-response-content-types",
+            RuleId::GenPathLevelParameters => "gen-path-level-parameters",
+            RuleId::Custom(id) => id,
         }
     }
 
+    /// A custom rule ID for a name only known at runtime, such as a rule
+    /// loaded from a ruleset file. Each distinct name is allocated once and
+    /// kept for the rest of the program.
+    pub fn custom(name: &str) -> RuleId {
+        static NAMES: Mutex<BTreeSet<&'static str>> = Mutex::new(BTreeSet::new());
+
+        let mut names = NAMES.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(name) = names.get(name) {
+            return RuleId::Custom(name);
+        }
+        let name: &'static str = Box::leak(name.into());
+        names.insert(name);
+        RuleId::Custom(name)
+    }
+
     /// Parse a rule ID from string
     pub fn parse(s: &str) -> Option<RuleId> {
         match s {
@@ -198,6 +256,7 @@ impl RuleId {
             "info-version-required" => Some(RuleId::InfoVersionRequired),
             "info-description-present" => Some(RuleId::InfoDescriptionPresent),
             "info-contact-license-present" => Some(RuleId::InfoContactLicensePresent),
+            "docs-coverage" => Some(RuleId::DocsCoverage),
             "servers-https-required" => Some(RuleId::ServersHttpsRequired),
             "paths-not-empty" => Some(RuleId::PathsNotEmpty),
             "server-url-non-empty" => Some(RuleId::ServerUrlNonEmpty),
@@ -217,11 +276,13 @@ impl RuleId {
             "responses-have-2xx" => Some(RuleId::ResponsesHave2xx),
             "operation-method-semantics" => Some(RuleId::OperationMethodSemantics),
             "operation-deprecated-note" => Some(RuleId::OperationDeprecatedNote),
+            "operation-tags-required" => Some(RuleId::OperationTagsRequired),
             // Parameters
             "param-naming-consistent" => Some(RuleId::ParamNamingConsistent),
             "param-schema-required" => Some(RuleId::ParamSchemaRequired),
             "param-enum-documented" => Some(RuleId::ParamEnumDocumented),
             "param-date-time-format" => Some(RuleId::ParamDateTimeFormat),
+            "param-description-required" => Some(RuleId::ParamDescriptionRequired),
             // Request bodies
             "request-body-content-required" => Some(RuleId::RequestBodyContentRequired),
             "request-body-json-media-type" => Some(RuleId::RequestBodyJsonMediaType),
@@ -232,6 +293,7 @@ impl RuleId {
             "response-schema-required" => Some(RuleId::ResponseSchemaRequired),
             "response-204-no-body" => Some(RuleId::Response204NoBody),
             "response-error-model-consistent" => Some(RuleId::ResponseErrorModelConsistent),
+            "response-status-code-valid" => Some(RuleId::ResponseStatusCodeValid),
             // Schemas
             "schema-type-required" => Some(RuleId::SchemaTypeRequired),
             "schema-object-properties-required" => Some(RuleId::SchemaObjectPropertiesRequired),
@@ -243,19 +305,26 @@ impl RuleId {
             "schema-read-write-only-correct" => Some(RuleId::SchemaReadWriteOnlyCorrect),
             "schema-naming-conventions" => Some(RuleId::SchemaNamingConventions),
             "schema-no-any" => Some(RuleId::SchemaNoAny),
+            "example-matches-schema" => Some(RuleId::ExampleMatchesSchema),
             // Components
             "component-reuse-schemas" => Some(RuleId::ComponentReuseSchemas),
             "component-no-unused" => Some(RuleId::ComponentNoUnused),
+            "component-ref-dangling" => Some(RuleId::ComponentRefDangling),
             // Security
             "security-schemes-defined" => Some(RuleId::SecuritySchemesDefined),
             "security-declared" => Some(RuleId::SecurityDeclared),
             "security-no-api-key-in-query" => Some(RuleId::SecurityNoApiKeyInQuery),
             "security-oauth2-complete" => Some(RuleId::SecurityOAuth2Complete),
+            // Generator compatibility
+            "gen-inline-body-operation-id" => Some(RuleId::GenInlineBodyOperationId),
+            "gen-any-of-union" => Some(RuleId::GenAnyOfUnion),
+            "gen-multiple-response-content-types" => Some(RuleId::GenMultipleResponseContentTypes),
+            "gen-path-level-parameters" => Some(RuleId::GenPathLevelParameters),
             _ => None,
         }
     }
 
-    /// Get all available rule IDs
+    /// Get all built-in rule IDs
     pub fn all() -> &'static [RuleId] {
         &[
             // Baseline validation
@@ -265,6 +334,7 @@ impl RuleId {
             RuleId::InfoVersionRequired,
             RuleId::InfoDescriptionPresent,
             RuleId::InfoContactLicensePresent,
+            RuleId::DocsCoverage,
             RuleId::ServersHttpsRequired,
             RuleId::PathsNotEmpty,
             RuleId::ServerUrlNonEmpty,
@@ -284,11 +354,13 @@ impl RuleId {
             RuleId::ResponsesHave2xx,
             RuleId::OperationMethodSemantics,
             RuleId::OperationDeprecatedNote,
+            RuleId::OperationTagsRequired,
             // Parameters
             RuleId::ParamNamingConsistent,
             RuleId::ParamSchemaRequired,
             RuleId::ParamEnumDocumented,
             RuleId::ParamDateTimeFormat,
+            RuleId::ParamDescriptionRequired,
             // Request bodies
             RuleId::RequestBodyContentRequired,
             RuleId::RequestBodyJsonMediaType,
@@ -299,6 +371,7 @@ impl RuleId {
             RuleId::ResponseSchemaRequired,
             RuleId::Response204NoBody,
             RuleId::ResponseErrorModelConsistent,
+            RuleId::ResponseStatusCodeValid,
             // Schemas
             RuleId::SchemaTypeRequired,
             RuleId::SchemaObjectPropertiesRequired,
@@ -308,14 +381,21 @@ impl RuleId {
             RuleId::SchemaReadWriteOnlyCorrect,
             RuleId::SchemaNamingConventions,
             RuleId::SchemaNoAny,
+            RuleId::ExampleMatchesSchema,
             // Components
             RuleId::ComponentReuseSchemas,
             RuleId::ComponentNoUnused,
+            RuleId::ComponentRefDangling,
             // Security
             RuleId::SecuritySchemesDefined,
             RuleId::SecurityDeclared,
             RuleId::SecurityNoApiKeyInQuery,
             RuleId::SecurityOAuth2Complete,
+            // Generator compatibility
+            RuleId::GenInlineBodyOperationId,
+            RuleId::GenAnyOfUnion,
+            RuleId::GenMultipleResponseContentTypes,
+            RuleId::GenPathLevelParameters,
         ]
     }
 
@@ -329,6 +409,7 @@ impl RuleId {
             RuleId::InfoVersionRequired => Severity::Error,
             RuleId::InfoDescriptionPresent => Severity::Warning,
             RuleId::InfoContactLicensePresent => Severity::Warning,
+            RuleId::DocsCoverage => Severity::Warning,
             RuleId::ServersHttpsRequired => Severity::Error,
             RuleId::PathsNotEmpty => Severity::Warning,
             RuleId::ServerUrlNonEmpty => Severity::Warning,
@@ -348,11 +429,13 @@ impl RuleId {
             RuleId::ResponsesHave2xx => Severity::Warning,
             RuleId::OperationMethodSemantics => Severity::Warning,
             RuleId::OperationDeprecatedNote => Severity::Warning,
+            RuleId::OperationTagsRequired => Severity::Warning,
             // Parameters
             RuleId::ParamNamingConsistent => Severity::Warning,
             RuleId::ParamSchemaRequired => Severity::Error,
             RuleId::ParamEnumDocumented => Severity::Warning,
             RuleId::ParamDateTimeFormat => Severity::Warning,
+            RuleId::ParamDescriptionRequired => Severity::Warning,
             // Request bodies
             RuleId::RequestBodyContentRequired => Severity::Error,
             RuleId::RequestBodyJsonMediaType => Severity::Warning,
@@ -363,6 +446,7 @@ impl RuleId {
             RuleId::ResponseSchemaRequired => Severity::Error,
             RuleId::Response204NoBody => Severity::Warning,
             RuleId::ResponseErrorModelConsistent => Severity::Warning,
+            RuleId::ResponseStatusCodeValid => Severity::Error,
             // Schemas
             RuleId::SchemaTypeRequired => Severity::Warning,
             RuleId::SchemaObjectPropertiesRequired => Severity::Warning,
@@ -372,14 +456,89 @@ impl RuleId {
             RuleId::SchemaReadWriteOnlyCorrect => Severity::Warning,
             RuleId::SchemaNamingConventions => Severity::Warning,
             RuleId::SchemaNoAny => Severity::Warning,
+            RuleId::ExampleMatchesSchema => Severity::Error,
             // Components
             RuleId::ComponentReuseSchemas => Severity::Warning,
             RuleId::ComponentNoUnused => Severity::Info,
+            RuleId::ComponentRefDangling => Severity::Error,
             // Security
             RuleId::SecuritySchemesDefined => Severity::Error,
             RuleId::SecurityDeclared => Severity::Warning,
             RuleId::SecurityNoApiKeyInQuery => Severity::Error,
             RuleId::SecurityOAuth2Complete => Severity::Error,
+            // Generator compatibility - output degrades, the spec is fine
+            RuleId::GenInlineBodyOperationId
+            | RuleId::GenAnyOfUnion
+            | RuleId::GenMultipleResponseContentTypes
+            | RuleId::GenPathLevelParameters => Severity::Warning,
+            RuleId::Custom(_) => Severity::Warning,
+        }
+    }
+
+    /// Categories this rule belongs to (used for `--only` filters and category severity overrides)
+    pub fn categories(&self) -> &'static [RuleCategory] {
+        match self {
+            RuleId::OpenApiVersion31
+            | RuleId::InfoTitleRequired
+            | RuleId::InfoVersionRequired
+            | RuleId::PathsNotEmpty
+            | RuleId::ServerUrlNonEmpty
+            | RuleId::PathParamsDeclared
+            | RuleId::PathParamsRequired
+            | RuleId::PathParamsUnused
+            | RuleId::PathTemplatingAmbiguous
+            | RuleId::OperationIdUnique
+            | RuleId::ResponsesExist
+            | RuleId::ResponsesHave2xx
+            | RuleId::OperationMethodSemantics
+            | RuleId::ParamSchemaRequired
+            | RuleId::ParamDateTimeFormat
+            | RuleId::RequestBodyContentRequired
+            | RuleId::RequestBodySchemaRequired
+            | RuleId::ResponseSchemaRequired
+            | RuleId::Response204NoBody
+            | RuleId::ResponseStatusCodeValid
+            | RuleId::SchemaTypeRequired
+            | RuleId::SchemaObjectPropertiesRequired
+            | RuleId::SchemaAdditionalPropertiesExplicit
+            | RuleId::SchemaEnumValid
+            | RuleId::SchemaArrayItemsRequired
+            | RuleId::SchemaReadWriteOnlyCorrect
+            | RuleId::SchemaNoAny => &[RuleCategory::Correctness],
+            RuleId::InfoDescriptionPresent
+            | RuleId::InfoContactLicensePresent
+            | RuleId::DocsCoverage
+            | RuleId::OperationSummaryRequired
+            | RuleId::OperationDeprecatedNote
+            | RuleId::ParamEnumDocumented
+            | RuleId::ParamDescriptionRequired
+            | RuleId::RequestBodyExamplesPresent
+            | RuleId::ResponseDescriptionRequired => &[RuleCategory::Documentation],
+            RuleId::ServersHttpsRequired
+            | RuleId::SecurityDeclared
+            | RuleId::SecurityNoApiKeyInQuery
+            | RuleId::SecurityOAuth2Complete => &[RuleCategory::Security],
+            RuleId::TagsDeclared
+            | RuleId::PathStyleNormalized
+            | RuleId::PathNoVerbs
+            | RuleId::OperationIdRequired
+            | RuleId::OperationTagsRequired
+            | RuleId::ParamNamingConsistent
+            | RuleId::RequestBodyJsonMediaType
+            | RuleId::ResponseErrorModelConsistent
+            | RuleId::SchemaNamingConventions
+            | RuleId::ComponentReuseSchemas
+            | RuleId::ComponentNoUnused => &[RuleCategory::Style],
+            RuleId::ComponentRefDangling => &[RuleCategory::Correctness],
+            RuleId::SecuritySchemesDefined => &[RuleCategory::Correctness, RuleCategory::Security],
+            RuleId::ExampleMatchesSchema => {
+                &[RuleCategory::Correctness, RuleCategory::Documentation]
+            }
+            RuleId::GenInlineBodyOperationId
+            | RuleId::GenAnyOfUnion
+            | RuleId::GenMultipleResponseContentTypes
+            | RuleId::GenPathLevelParameters => &[RuleCategory::Compatibility],
+            RuleId::Custom(_) => &[],
         }
     }
 }
@@ -393,6 +552,157 @@ pub enum Severity {
     Hint,
 }
 
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info => "info",
+            Severity::Hint => "hint",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Severity> {
+        match s {
+            "error" => Some(Severity::Error),
+            "warning" | "warn" => Some(Severity::Warning),
+            "info" => Some(Severity::Info),
+            "hint" => Some(Severity::Hint),
+            _ => None,
+        }
+    }
+}
+
+/// Broad grouping of rules so teams can run or tune focused subsets
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub enum RuleCategory {
+    /// The spec is wrong or ambiguous for tooling
+    Correctness,
+    /// Transport and auth hygiene
+    Security,
+    /// Naming and design consistency
+    Style,
+    /// Descriptions, summaries and examples
+    Documentation,
+    /// Constructs the bundled generators handle poorly
+    Compatibility,
+}
+
+impl RuleCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RuleCategory::Correctness => "correctness",
+            RuleCategory::Security => "security",
+            RuleCategory::Style => "style",
+            RuleCategory::Documentation => "documentation",
+            RuleCategory::Compatibility => "compatibility",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<RuleCategory> {
+        match s {
+            "correctness" => Some(RuleCategory::Correctness),
+            "security" => Some(RuleCategory::Security),
+            "style" => Some(RuleCategory::Style),
+            "documentation" | "docs" => Some(RuleCategory::Documentation),
+            "compatibility" | "compat" => Some(RuleCategory::Compatibility),
+            _ => None,
+        }
+    }
+
+    pub fn all() -> &'static [RuleCategory] {
+        &[
+            RuleCategory::Correctness,
+            RuleCategory::Security,
+            RuleCategory::Style,
+            RuleCategory::Documentation,
+            RuleCategory::Compatibility,
+        ]
+    }
+}
+
+/// Category-level severity overrides, e.g. `security: error`, `style: off`.
+///
+/// Overrides are applied in insertion order, so for rules in several
+/// categories the last matching override wins.
+#[derive(Clone, Debug, Default)]
+pub struct CategorySeverity {
+    /// `None` turns the category off
+    overrides: Vec<(RuleCategory, Option<Severity>)>,
+}
+
+impl CategorySeverity {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the severity for every rule in `category` (`None` = off).
+    pub fn set(&mut self, category: RuleCategory, severity: Option<Severity>) -> &mut Self {
+        self.overrides.retain(|(c, _)| *c != category);
+        self.overrides.push((category, severity));
+        self
+    }
+
+    /// Parse a comma-separated list like `security:error,style:off`.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let mut config = Self::new();
+        for entry in s.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (category, level) = entry
+                .split_once(':')
+                .or_else(|| entry.split_once('='))
+                .ok_or_else(|| format!("Expected 'category:level', got '{}'", entry))?;
+            let category = RuleCategory::parse(category.trim())
+                .ok_or_else(|| format!("Unknown rule category: '{}'", category.trim()))?;
+            let level = match level.trim() {
+                "off" => None,
+                other => Some(
+                    Severity::parse(other)
+                        .ok_or_else(|| format!("Unknown severity level: '{}'", other))?,
+                ),
+            };
+            config.set(category, level);
+        }
+        Ok(config)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.overrides.is_empty()
+    }
+
+    /// Apply `other`'s overrides on top of these.
+    pub fn merge(&mut self, other: &CategorySeverity) -> &mut Self {
+        for (category, severity) in &other.overrides {
+            self.set(*category, *severity);
+        }
+        self
+    }
+
+    /// Effective severity of `rule`, or `None` if its category is turned off.
+    pub fn severity_for(&self, rule: RuleId, default: Severity) -> Option<Severity> {
+        let mut severity = Some(default);
+        for (category, level) in &self.overrides {
+            if rule.categories().contains(category) {
+                severity = *level;
+            }
+        }
+        severity
+    }
+
+    /// Rewrite finding severities and drop findings from disabled categories.
+    pub fn apply(&self, findings: Vec<Finding>) -> Vec<Finding> {
+        if self.is_empty() {
+            return findings;
+        }
+        findings
+            .into_iter()
+            .filter_map(|f| {
+                let severity = self.severity_for(f.rule, f.severity)?;
+                Some(f.with_severity(severity))
+            })
+            .collect()
+    }
+}
+
 /// A semantic finding from a lint rule (before span resolution)
 #[derive(Debug, Clone)]
 pub struct Finding {
@@ -401,6 +711,7 @@ pub struct Finding {
     pub message: String,
     pub ptr: String,
     pub fix: Option<FixPlan>,
+    pub autofix: Option<Fix>,
 }
 
 impl Finding {
@@ -411,6 +722,7 @@ impl Finding {
             message: message.into(),
             ptr: ptr.into(),
             fix: None,
+            autofix: None,
         }
     }
 
@@ -423,6 +735,11 @@ impl Finding {
         self.fix = Some(fix);
         self
     }
+
+    pub fn with_autofix(mut self, autofix: Fix) -> Self {
+        self.autofix = Some(autofix);
+        self
+    }
 }
 
 /// A planned fix for a finding
@@ -440,15 +757,60 @@ pub struct TextEdit {
     pub replacement: String,
 }
 
+impl TextEdit {
+    /// Whether the edits can't both be applied: their ranges intersect, or
+    /// one inserts inside the range the other replaces.
+    pub fn overlaps(&self, other: &TextEdit) -> bool {
+        self.start_byte < other.end_byte && other.start_byte < self.end_byte
+    }
+}
+
+/// A structural fix for a finding, applied by
+/// [`Linter::apply_fixes`](crate::Linter::apply_fixes)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fix {
+    pub description: String,
+    /// JSON Patch operations against the spec document, applied in order
+    pub ops: Vec<PatchOp>,
+}
+
+impl Fix {
+    pub fn new(description: impl Into<String>, ops: Vec<PatchOp>) -> Self {
+        Self {
+            description: description.into(),
+            ops,
+        }
+    }
+}
+
+/// A JSON Patch (RFC 6902) operation; paths are JSON pointers
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOp {
+    Add { path: String, value: Value },
+    Replace { path: String, value: Value },
+    Remove { path: String },
+    Move { from: String, path: String },
+}
+
 /// A final diagnostic with resolved source location
 #[derive(Debug, Clone)]
 pub struct Diagnostic {
     pub rule: RuleId,
     pub severity: Severity,
     pub message: String,
+    /// JSON pointer to the offending node
+    pub pointer: String,
     pub range: Range,
     pub byte_span: ByteSpan,
+    /// The source lines `range` covers, for showing the finding without
+    /// opening the file
+    pub snippet: String,
+    /// Edits of the source that fix the finding: the rule's own, or its
+    /// `autofix` located in the source (see [`text_edits`](crate::text_edits))
     pub fixes: Vec<TextEdit>,
+    /// Structural fix, if the rule can repair the finding mechanically
+    pub autofix: Option<Fix>,
     /// True if the location is approximate due to YAML alias
     pub is_alias_location: bool,
 }
@@ -458,6 +820,9 @@ pub struct Diagnostic {
 pub struct RuleSet(u128);
 
 impl RuleSet {
+    /// Names [`named`](Self::named) accepts besides the categories.
+    pub const PRESETS: &'static [&'static str] = &["core", "recommended", "strict", "all", "none"];
+
     pub fn new() -> Self {
         Self(0)
     }
@@ -471,15 +836,123 @@ impl RuleSet {
     }
 
     pub fn enable(&mut self, rule: RuleId) {
-        self.0 |= 1 << (rule as u8);
+        self.0 |= Self::bit(rule);
     }
 
     pub fn disable(&mut self, rule: RuleId) {
-        self.0 &= !(1 << (rule as u8));
+        self.0 &= !Self::bit(rule);
     }
 
     pub fn is_enabled(&self, rule: RuleId) -> bool {
-        (self.0 & (1 << (rule as u8))) != 0
+        (self.0 & Self::bit(rule)) != 0
+    }
+
+    /// Position of a built-in rule in the set; custom rules have none.
+    fn bit(rule: RuleId) -> u128 {
+        RuleId::all()
+            .iter()
+            .position(|r| *r == rule)
+            .map_or(0, |i| 1 << i)
+    }
+
+    /// The default ruleset: hard correctness plus the handful of checks
+    /// (operationIds, tags, success responses, parameter docs, security
+    /// schemes) that catch most problems in generated SDKs. It leaves out
+    /// `openapi-version-31`, since the generators read 3.0 specs too.
+    pub fn core() -> Self {
+        let mut set = Self::new();
+        for rule in [
+            RuleId::InfoTitleRequired,
+            RuleId::InfoVersionRequired,
+            RuleId::PathParamsDeclared,
+            RuleId::PathParamsRequired,
+            RuleId::OperationIdRequired,
+            RuleId::OperationIdUnique,
+            RuleId::OperationTagsRequired,
+            RuleId::ResponsesExist,
+            RuleId::ResponsesHave2xx,
+            RuleId::ParamDescriptionRequired,
+            RuleId::ResponseDescriptionRequired,
+            RuleId::ResponseStatusCodeValid,
+            RuleId::ComponentRefDangling,
+            RuleId::SecuritySchemesDefined,
+            RuleId::ComponentNoUnused,
+        ] {
+            set.enable(rule);
+        }
+        set
+    }
+
+    /// Rules in any of the given categories.
+    pub fn from_categories(categories: &[RuleCategory]) -> Self {
+        let mut set = Self::new();
+        for rule in RuleId::all() {
+            if rule.categories().iter().any(|c| categories.contains(c)) {
+                set.enable(*rule);
+            }
+        }
+        set
+    }
+
+    /// Rules for good API design: document metadata, operationIds,
+    /// responses and path parameters.
+    pub fn recommended() -> Self {
+        let mut set = Self::new();
+        for rule in [
+            RuleId::OpenApiVersion31,
+            RuleId::InfoTitleRequired,
+            RuleId::InfoVersionRequired,
+            RuleId::InfoDescriptionPresent,
+            RuleId::PathsNotEmpty,
+            RuleId::OperationIdRequired,
+            RuleId::OperationIdUnique,
+            RuleId::ResponsesExist,
+            RuleId::ResponseDescriptionRequired,
+            RuleId::PathParamsDeclared,
+            RuleId::PathParamsRequired,
+        ] {
+            set.enable(rule);
+        }
+        set
+    }
+
+    /// Every rule that defaults to error severity.
+    pub fn strict() -> Self {
+        let mut set = Self::new();
+        for rule in RuleId::all() {
+            if rule.default_severity() == Severity::Error {
+                set.enable(*rule);
+            }
+        }
+        set
+    }
+
+    /// A ruleset by name: one of [`PRESETS`](Self::PRESETS), a category, or
+    /// several of those separated by commas. Used for both `--rulesets` and
+    /// `ruleset` in `oas-lint.toml`.
+    pub fn named(name: &str) -> Option<Self> {
+        let mut set = Self::new();
+        for name in name.split(',').map(str::trim) {
+            set = set.union(match name {
+                "core" => Self::core(),
+                "recommended" => Self::recommended(),
+                "strict" => Self::strict(),
+                "all" => Self::all(),
+                "none" => Self::new(),
+                category => Self::from_categories(&[RuleCategory::parse(category)?]),
+            });
+        }
+        Some(set)
+    }
+
+    /// Rules enabled in either set.
+    pub fn union(self, other: RuleSet) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    /// Keep only the rules that are enabled in both sets.
+    pub fn intersect(self, other: RuleSet) -> Self {
+        Self(self.0 & other.0)
     }
 
     pub fn from_slice(rules: &[&str]) -> Self {
@@ -517,6 +990,7 @@ fn run_rule(rule: RuleId, ctx: &LintCtx, out: &mut Vec<Finding>) {
         RuleId::InfoVersionRequired => rules::info_version_required(ctx, out),
         RuleId::InfoDescriptionPresent => rules::info_description_present(ctx, out),
         RuleId::InfoContactLicensePresent => rules::info_contact_license_present(ctx, out),
+        RuleId::DocsCoverage => rules::docs_coverage(ctx, out),
         RuleId::ServersHttpsRequired => rules::servers_https_required(ctx, out),
         RuleId::PathsNotEmpty => rules::paths_not_empty(ctx, out),
         RuleId::ServerUrlNonEmpty => rules::server_url_non_empty(ctx, out),
@@ -536,11 +1010,13 @@ fn run_rule(rule: RuleId, ctx: &LintCtx, out: &mut Vec<Finding>) {
         RuleId::ResponsesHave2xx => rules::responses_have_2xx(ctx, out),
         RuleId::OperationMethodSemantics => rules::operation_method_semantics(ctx, out),
         RuleId::OperationDeprecatedNote => rules::operation_deprecated_note(ctx, out),
+        RuleId::OperationTagsRequired => rules::operation_tags_required(ctx, out),
         // Parameters
         RuleId::ParamNamingConsistent => rules::param_naming_consistent(ctx, out),
         RuleId::ParamSchemaRequired => rules::param_schema_required(ctx, out),
         RuleId::ParamEnumDocumented => rules::param_enum_documented(ctx, out),
         RuleId::ParamDateTimeFormat => rules::param_date_time_format(ctx, out),
+        RuleId::ParamDescriptionRequired => rules::param_description_required(ctx, out),
         // Request bodies
         RuleId::RequestBodyContentRequired => rules::request_body_content_required(ctx, out),
         RuleId::RequestBodyJsonMediaType => rules::request_body_json_media_type(ctx, out),
@@ -551,6 +1027,7 @@ fn run_rule(rule: RuleId, ctx: &LintCtx, out: &mut Vec<Finding>) {
         RuleId::ResponseSchemaRequired => rules::response_schema_required(ctx, out),
         RuleId::Response204NoBody => rules::response_204_no_body(ctx, out),
         RuleId::ResponseErrorModelConsistent => rules::response_error_model_consistent(ctx, out),
+        RuleId::ResponseStatusCodeValid => rules::response_status_code_valid(ctx, out),
         // Schemas
         RuleId::SchemaTypeRequired => rules::schema_type_required(ctx, out),
         RuleId::SchemaObjectPropertiesRequired => {
@@ -564,37 +1041,55 @@ fn run_rule(rule: RuleId, ctx: &LintCtx, out: &mut Vec<Finding>) {
         RuleId::SchemaReadWriteOnlyCorrect => rules::schema_read_write_only_correct(ctx, out),
         RuleId::SchemaNamingConventions => rules::schema_naming_conventions(ctx, out),
         RuleId::SchemaNoAny => rules::schema_no_any(ctx, out),
+        RuleId::ExampleMatchesSchema => rules::example_matches_schema(ctx, out),
+        // Naming
         // Components
         RuleId::ComponentReuseSchemas => rules::component_reuse_schemas(ctx, out),
         RuleId::ComponentNoUnused => rules::component_no_unused(ctx, out),
+        RuleId::ComponentRefDangling => rules::component_ref_dangling(ctx, out),
         // Security
         RuleId::SecuritySchemesDefined => rules::security_schemes_defined(ctx, out),
         RuleId::SecurityDeclared => rules::security_declared(ctx, out),
         RuleId::SecurityNoApiKeyInQuery => rules::security_no_api_key_in_query(ctx, out),
         RuleId::SecurityOAuth2Complete => rules::security_oauth2_complete(ctx, out),
+        // Generator compatibility
+        RuleId::GenInlineBodyOperationId => rules::gen_inline_body_operation_id(ctx, out),
+        RuleId::GenAnyOfUnion => rules::gen_any_of_union(ctx, out),
+        RuleId::GenMultipleResponseContentTypes => {
+            rules::gen_multiple_response_content_types(ctx, out)
+        }
+        RuleId::GenPathLevelParameters => rules::gen_path_level_parameters(ctx, out),
+        // Custom rules are run by the `Linter` that owns them
+        RuleId::Custom(_) => {}
     }
 }
 
 /// Resolve findings to diagnostics with source locations
-pub fn resolve_diagnostics(
-    findings: Vec<Finding>,
-    span_db: &SpanDb,
-    line_index: &LineIndex,
-) -> Vec<Diagnostic> {
+pub fn resolve_diagnostics(findings: Vec<Finding>, source_map: &SourceMap) -> Vec<Diagnostic> {
     let mut diagnostics: Vec<Diagnostic> = findings
         .into_iter()
         .filter_map(|f| {
-            let (byte_span, is_alias) = span_db.resolve_value_span(&f.ptr)?;
-            let range = line_index.range(byte_span);
+            let location = source_map.locate(&f.ptr)?;
 
             Some(Diagnostic {
                 rule: f.rule,
                 severity: f.severity,
                 message: f.message,
-                range,
-                byte_span,
-                fixes: f.fix.map(|fp| fp.edits).unwrap_or_default(),
-                is_alias_location: is_alias,
+                pointer: f.ptr,
+                range: location.range,
+                byte_span: location.byte_span,
+                snippet: source_map.snippet(location.range),
+                fixes: f
+                    .fix
+                    .map(|fp| fp.edits)
+                    .or_else(|| {
+                        f.autofix
+                            .as_ref()
+                            .and_then(|fix| crate::fix::text_edits(fix, source_map))
+                    })
+                    .unwrap_or_default(),
+                autofix: f.autofix,
+                is_alias_location: location.is_alias,
             })
         })
         .collect();
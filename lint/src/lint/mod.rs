@@ -1,7 +1,9 @@
 mod ctx;
 mod engine;
 mod indexes;
+mod rule;
 
 pub use ctx::*;
 pub use engine::*;
 pub use indexes::*;
+pub use rule::*;
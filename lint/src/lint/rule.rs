@@ -0,0 +1,66 @@
+use super::{Finding, LintCtx, RuleId, Severity};
+
+/// A lint rule supplied by the caller, run by a [`Linter`](crate::Linter)
+/// alongside the built-in rules.
+///
+/// Rules report [`Finding`]s against JSON pointers (see
+/// [`pointer`](crate::pointer)); the linter resolves them to source
+/// locations and applies severity overrides.
+///
+/// ```
+/// use lint::{Finding, LintCtx, Rule, RuleId, Severity, pointer};
+///
+/// struct TitleCase;
+///
+/// impl Rule for TitleCase {
+///     fn id(&self) -> RuleId {
+///         RuleId::Custom("info-title-case")
+///     }
+///
+///     fn severity(&self) -> Severity {
+///         Severity::Error
+///     }
+///
+///     fn check(&self, ctx: &LintCtx) -> Vec<Finding> {
+///         let title = &ctx.spec.info.title;
+///         if title.starts_with(char::is_lowercase) {
+///             vec![self.finding(pointer(["info", "title"]), "Title should be capitalized")]
+///         } else {
+///             Vec::new()
+///         }
+///     }
+/// }
+/// ```
+pub trait Rule: Send + Sync {
+    /// Identifier reported on every finding, usually `RuleId::Custom`
+    fn id(&self) -> RuleId;
+
+    /// Severity of the rule's findings before overrides
+    fn severity(&self) -> Severity {
+        self.id().default_severity()
+    }
+
+    /// Check the spec (`ctx.spec`) and report findings
+    fn check(&self, ctx: &LintCtx) -> Vec<Finding>;
+
+    /// A finding for this rule at `ptr`, with the rule's severity
+    fn finding(&self, ptr: impl Into<String>, message: impl Into<String>) -> Finding
+    where
+        Self: Sized,
+    {
+        Finding::new(self.id(), ptr, message).with_severity(self.severity())
+    }
+}
+
+/// Built-in rules are rules too, so they can be mixed with custom ones.
+impl Rule for RuleId {
+    fn id(&self) -> RuleId {
+        *self
+    }
+
+    fn check(&self, ctx: &LintCtx) -> Vec<Finding> {
+        let mut set = super::RuleSet::new();
+        set.enable(*self);
+        super::run_rules(ctx, set)
+    }
+}
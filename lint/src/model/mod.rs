@@ -1,5 +1,9 @@
 mod pointers;
+mod source_map;
 mod spans;
+mod visit;
 
 pub use pointers::*;
+pub use source_map::*;
 pub use spans::*;
+pub use visit::*;
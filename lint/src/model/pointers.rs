@@ -5,6 +5,19 @@ pub fn escape_pointer_segment(segment: &str) -> String {
     segment.replace('~', "~0").replace('/', "~1")
 }
 
+/// Build a JSON Pointer from unescaped segments,
+/// e.g. `["paths", "/pets", "get"]` → `/paths/~1pets/get`
+pub fn pointer<I>(segments: I) -> String
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    segments
+        .into_iter()
+        .map(|segment| format!("/{}", escape_pointer_segment(segment.as_ref())))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -16,4 +29,13 @@ mod tests {
         assert_eq!(escape_pointer_segment("a~b"), "a~0b");
         assert_eq!(escape_pointer_segment("a/b~c"), "a~1b~0c");
     }
+
+    #[test]
+    fn test_pointer() {
+        assert_eq!(
+            pointer(["paths", "/pets/{id}", "get"]),
+            "/paths/~1pets~1{id}/get"
+        );
+        assert_eq!(pointer(Vec::<String>::new()), "");
+    }
 }
@@ -0,0 +1,106 @@
+use crate::LintError;
+use crate::parse::SpanDbBuilder;
+
+use super::{ByteSpan, LineIndex, Range, SpanDb};
+
+/// Most source lines kept in a snippet
+pub const MAX_SNIPPET_LINES: usize = 5;
+
+/// Where a JSON Pointer's value sits in the source text
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Location {
+    pub byte_span: ByteSpan,
+    pub range: Range,
+    /// True if the location is approximate due to YAML alias
+    pub is_alias: bool,
+}
+
+/// Maps JSON Pointers into the original YAML or JSON text: byte offsets,
+/// 0-based lines and columns, and the source lines around them.
+#[derive(Debug)]
+pub struct SourceMap<'a> {
+    source: &'a str,
+    spans: SpanDb,
+    lines: LineIndex,
+}
+
+impl<'a> SourceMap<'a> {
+    /// Index `source`, failing if it is not well-formed YAML (or JSON)
+    pub fn new(source: &'a str) -> Result<Self, LintError> {
+        let spans =
+            SpanDbBuilder::build(source).map_err(|e| LintError::YamlParse(e.to_string()))?;
+        Ok(Self {
+            source,
+            spans,
+            lines: LineIndex::new(source),
+        })
+    }
+
+    pub fn source(&self) -> &'a str {
+        self.source
+    }
+
+    pub fn spans(&self) -> &SpanDb {
+        &self.spans
+    }
+
+    /// Locate the value at `pointer`, falling back to its nearest ancestor
+    /// when the pointer itself is not in the text (e.g. a missing field)
+    pub fn locate(&self, pointer: &str) -> Option<Location> {
+        let (byte_span, is_alias) = self.spans.resolve_value_span(pointer)?;
+        Some(Location {
+            byte_span,
+            range: self.lines.range(byte_span),
+            is_alias,
+        })
+    }
+
+    /// The full source lines `range` covers, at most [`MAX_SNIPPET_LINES`]
+    pub fn snippet(&self, range: Range) -> String {
+        let first = range.start.line;
+        // A block node's span ends at the start of the line after it
+        let last = if range.end.col == 0 && range.end.line > first {
+            range.end.line - 1
+        } else {
+            range.end.line.max(first)
+        };
+        let last = last.min(first + MAX_SNIPPET_LINES as u32 - 1);
+        (first..=last)
+            .map_while(|line| self.lines.line_span(line))
+            .map(|span| self.source[span.start as usize..span.end as usize].trim_end_matches('\r'))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::LineCol;
+
+    const SPEC: &str = "openapi: 3.1.0\ninfo:\n  title: Pets\n  version: '1'\npaths: {}\n";
+
+    #[test]
+    fn test_source_map() {
+        let map = SourceMap::new(SPEC).unwrap();
+
+        let title = map.locate("/info/title").unwrap();
+        assert_eq!(title.range.start, LineCol::new(2, 9));
+        assert_eq!(
+            &SPEC[title.byte_span.start as usize..title.byte_span.end as usize],
+            "Pets"
+        );
+        assert_eq!(map.snippet(title.range), "  title: Pets");
+
+        // Missing nodes resolve to their parent
+        let missing = map.locate("/info/description").unwrap();
+        assert_eq!(missing.range.start.line, 2);
+        assert!(
+            map.snippet(missing.range)
+                .starts_with("  title: Pets\n  version: '1'")
+        );
+        assert!(!map.snippet(missing.range).contains("paths"));
+
+        assert!(SourceMap::new("a: [").is_err());
+    }
+}
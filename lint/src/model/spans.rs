@@ -97,6 +97,8 @@ impl SpanDb {
 pub struct LineIndex {
     /// Byte offset of the start of each line
     line_starts: Vec<u32>,
+    /// Length of the source in bytes
+    len: u32,
 }
 
 impl LineIndex {
@@ -108,7 +110,26 @@ impl LineIndex {
                 line_starts.push((i + 1) as u32);
             }
         }
-        Self { line_starts }
+        Self {
+            line_starts,
+            len: source.len() as u32,
+        }
+    }
+
+    /// Byte span of `line` (0-based) without its `\n`, or `None` past the
+    /// last line. Like [`str::lines`], a final line break doesn't start an
+    /// empty line.
+    pub fn line_span(&self, line: u32) -> Option<ByteSpan> {
+        let line = line as usize;
+        let start = *self.line_starts.get(line)?;
+        if start >= self.len {
+            return None;
+        }
+        let end = self
+            .line_starts
+            .get(line + 1)
+            .map_or(self.len, |next| next - 1);
+        Some(ByteSpan::new(start, end))
     }
 
     /// Convert byte offset to line/column (0-based)
@@ -140,6 +161,11 @@ mod tests {
         assert_eq!(index.line_col(5), LineCol::new(0, 5)); // '\n'
         assert_eq!(index.line_col(6), LineCol::new(1, 0)); // 'w'
         assert_eq!(index.line_col(12), LineCol::new(2, 0)); // 'f'
+
+        assert_eq!(index.line_span(1), Some(ByteSpan::new(6, 11)));
+        assert_eq!(index.line_span(2), Some(ByteSpan::new(12, 15)));
+        assert_eq!(index.line_span(3), None);
+        assert_eq!(LineIndex::new("a\n").line_span(1), None);
     }
 
     #[test]
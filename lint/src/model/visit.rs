@@ -0,0 +1,567 @@
+//! Typed visitor over a parsed OpenAPI document.
+//!
+//! [`walk_spec`] performs a single deterministic traversal and calls back into a
+//! [`SpecVisitor`] with the node, the JSON pointer to it and a [`NodeCtx`]
+//! inherited from its ancestors. Rules, ref collection and stats share it
+//! instead of each re-walking the spec.
+
+use oas3::spec::{
+    Header, MediaType, ObjectOrReference, ObjectSchema, Operation, Parameter, PathItem,
+    RequestBody, Response, Schema,
+};
+
+use super::escape_pointer_segment;
+
+/// Which side of an exchange a node belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Request,
+    Response,
+}
+
+/// Top-level section of the document a node was reached from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Section {
+    #[default]
+    Paths,
+    Webhooks,
+    Components,
+}
+
+/// Context inherited from a node's ancestors.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NodeCtx<'s> {
+    /// Path template when under `/paths` (or the webhook name under `/webhooks`)
+    pub path: Option<&'s str>,
+    /// Lowercase HTTP method when under an operation
+    pub method: Option<&'static str>,
+    /// The enclosing operation
+    pub operation: Option<&'s Operation>,
+    /// Section of the document the node is declared in
+    pub section: Section,
+    /// Request or response side, when known
+    pub direction: Option<Direction>,
+    /// Schema nesting depth (0 for a root schema)
+    pub depth: usize,
+}
+
+impl NodeCtx<'_> {
+    /// Whether the node belongs to a path item or operation under `/paths`.
+    pub fn in_paths(&self) -> bool {
+        self.path.is_some() && self.section == Section::Paths
+    }
+}
+
+/// Callbacks invoked by [`walk_spec`]. All methods default to doing nothing.
+#[allow(unused_variables)]
+pub trait SpecVisitor<'s> {
+    fn visit_path_item(&mut self, path: &'s str, item: &'s PathItem, ptr: &str) {}
+
+    fn visit_operation(&mut self, operation: &'s Operation, ctx: &NodeCtx<'s>, ptr: &str) {}
+
+    fn visit_parameter(&mut self, param: &'s Parameter, ctx: &NodeCtx<'s>, ptr: &str) {}
+
+    fn visit_request_body(&mut self, body: &'s RequestBody, ctx: &NodeCtx<'s>, ptr: &str) {}
+
+    fn visit_response(
+        &mut self,
+        status: &'s str,
+        response: &'s Response,
+        ctx: &NodeCtx<'s>,
+        ptr: &str,
+    ) {
+    }
+
+    fn visit_header(&mut self, name: &'s str, header: &'s Header, ctx: &NodeCtx<'s>, ptr: &str) {}
+
+    fn visit_schema(&mut self, schema: &'s ObjectSchema, ctx: &NodeCtx<'s>, ptr: &str) {}
+
+    /// Called for every `$ref` encountered, wherever it appears.
+    fn visit_ref(&mut self, ref_path: &'s str, ctx: &NodeCtx<'s>, ptr: &str) {}
+}
+
+/// Walk the whole document: paths, webhooks, then components.
+pub fn walk_spec<'s>(spec: &'s oas3::Spec, visitor: &mut impl SpecVisitor<'s>) {
+    if let Some(paths) = &spec.paths {
+        for (path, item) in paths {
+            let ptr = format!("/paths/{}", escape_pointer_segment(path));
+            walk_path_item(path, item, &ptr, Section::Paths, visitor);
+        }
+    }
+
+    for (name, item) in &spec.webhooks {
+        let ptr = format!("/webhooks/{}", escape_pointer_segment(name));
+        walk_path_item(name, item, &ptr, Section::Webhooks, visitor);
+    }
+
+    let Some(components) = &spec.components else {
+        return;
+    };
+    let ctx = NodeCtx {
+        section: Section::Components,
+        ..Default::default()
+    };
+
+    for (name, schema) in &components.schemas {
+        let ptr = format!("/components/schemas/{}", escape_pointer_segment(name));
+        walk_schema_ref(schema, &ctx, &ptr, visitor);
+    }
+    for (name, param) in &components.parameters {
+        let ptr = format!("/components/parameters/{}", escape_pointer_segment(name));
+        walk_parameter_ref(param, &ctx, &ptr, visitor);
+    }
+    for (name, body) in &components.request_bodies {
+        let ptr = format!("/components/requestBodies/{}", escape_pointer_segment(name));
+        let ctx = NodeCtx {
+            direction: Some(Direction::Request),
+            ..ctx
+        };
+        walk_request_body_ref(body, &ctx, &ptr, visitor);
+    }
+    for (name, response) in &components.responses {
+        let ptr = format!("/components/responses/{}", escape_pointer_segment(name));
+        let ctx = NodeCtx {
+            direction: Some(Direction::Response),
+            ..ctx
+        };
+        walk_response_ref(name, response, &ctx, &ptr, visitor);
+    }
+    for (name, header) in &components.headers {
+        let ptr = format!("/components/headers/{}", escape_pointer_segment(name));
+        walk_header_ref(name, header, &ctx, &ptr, visitor);
+    }
+    for (name, item) in &components.path_items {
+        let ptr = format!("/components/pathItems/{}", escape_pointer_segment(name));
+        match item {
+            ObjectOrReference::Ref { ref_path, .. } => visitor.visit_ref(ref_path, &ctx, &ptr),
+            ObjectOrReference::Object(item) => {
+                walk_path_item(name, item, &ptr, Section::Components, visitor)
+            }
+        }
+    }
+}
+
+/// Operations of a path item in a stable method order.
+pub fn path_item_operations(item: &PathItem) -> impl Iterator<Item = (&'static str, &Operation)> {
+    [
+        ("get", item.get.as_ref()),
+        ("put", item.put.as_ref()),
+        ("post", item.post.as_ref()),
+        ("delete", item.delete.as_ref()),
+        ("options", item.options.as_ref()),
+        ("head", item.head.as_ref()),
+        ("patch", item.patch.as_ref()),
+        ("trace", item.trace.as_ref()),
+    ]
+    .into_iter()
+    .filter_map(|(method, op)| op.map(|op| (method, op)))
+}
+
+fn walk_path_item<'s>(
+    path: &'s str,
+    item: &'s PathItem,
+    ptr: &str,
+    section: Section,
+    visitor: &mut impl SpecVisitor<'s>,
+) {
+    visitor.visit_path_item(path, item, ptr);
+
+    let ctx = NodeCtx {
+        path: Some(path),
+        section,
+        direction: Some(Direction::Request),
+        ..Default::default()
+    };
+    for (i, param) in item.parameters.iter().enumerate() {
+        walk_parameter_ref(param, &ctx, &format!("{}/parameters/{}", ptr, i), visitor);
+    }
+
+    for (method, operation) in path_item_operations(item) {
+        let op_ptr = format!("{}/{}", ptr, method);
+        let ctx = NodeCtx {
+            method: Some(method),
+            operation: Some(operation),
+            direction: None,
+            ..ctx
+        };
+        walk_operation(operation, &ctx, &op_ptr, visitor);
+    }
+}
+
+fn walk_operation<'s>(
+    operation: &'s Operation,
+    ctx: &NodeCtx<'s>,
+    ptr: &str,
+    visitor: &mut impl SpecVisitor<'s>,
+) {
+    visitor.visit_operation(operation, ctx, ptr);
+
+    let request = NodeCtx {
+        direction: Some(Direction::Request),
+        ..*ctx
+    };
+    for (i, param) in operation.parameters.iter().enumerate() {
+        walk_parameter_ref(
+            param,
+            &request,
+            &format!("{}/parameters/{}", ptr, i),
+            visitor,
+        );
+    }
+    if let Some(body) = &operation.request_body {
+        walk_request_body_ref(body, &request, &format!("{}/requestBody", ptr), visitor);
+    }
+
+    if let Some(responses) = &operation.responses {
+        let response_ctx = NodeCtx {
+            direction: Some(Direction::Response),
+            ..*ctx
+        };
+        for (status, response) in responses {
+            let response_ptr = format!("{}/responses/{}", ptr, escape_pointer_segment(status));
+            walk_response_ref(status, response, &response_ctx, &response_ptr, visitor);
+        }
+    }
+}
+
+fn walk_parameter_ref<'s>(
+    param: &'s ObjectOrReference<Parameter>,
+    ctx: &NodeCtx<'s>,
+    ptr: &str,
+    visitor: &mut impl SpecVisitor<'s>,
+) {
+    match param {
+        ObjectOrReference::Ref { ref_path, .. } => visitor.visit_ref(ref_path, ctx, ptr),
+        ObjectOrReference::Object(param) => {
+            visitor.visit_parameter(param, ctx, ptr);
+            if let Some(schema) = &param.schema {
+                walk_schema_ref(schema, ctx, &format!("{}/schema", ptr), visitor);
+            }
+            if let Some(content) = &param.content {
+                walk_content(content, ctx, ptr, visitor);
+            }
+        }
+    }
+}
+
+fn walk_request_body_ref<'s>(
+    body: &'s ObjectOrReference<RequestBody>,
+    ctx: &NodeCtx<'s>,
+    ptr: &str,
+    visitor: &mut impl SpecVisitor<'s>,
+) {
+    match body {
+        ObjectOrReference::Ref { ref_path, .. } => visitor.visit_ref(ref_path, ctx, ptr),
+        ObjectOrReference::Object(body) => {
+            visitor.visit_request_body(body, ctx, ptr);
+            walk_content(&body.content, ctx, ptr, visitor);
+        }
+    }
+}
+
+fn walk_response_ref<'s>(
+    status: &'s str,
+    response: &'s ObjectOrReference<Response>,
+    ctx: &NodeCtx<'s>,
+    ptr: &str,
+    visitor: &mut impl SpecVisitor<'s>,
+) {
+    match response {
+        ObjectOrReference::Ref { ref_path, .. } => visitor.visit_ref(ref_path, ctx, ptr),
+        ObjectOrReference::Object(response) => {
+            visitor.visit_response(status, response, ctx, ptr);
+            for (name, header) in &response.headers {
+                let header_ptr = format!("{}/headers/{}", ptr, escape_pointer_segment(name));
+                walk_header_ref(name, header, ctx, &header_ptr, visitor);
+            }
+            walk_content(&response.content, ctx, ptr, visitor);
+        }
+    }
+}
+
+fn walk_header_ref<'s>(
+    name: &'s str,
+    header: &'s ObjectOrReference<Header>,
+    ctx: &NodeCtx<'s>,
+    ptr: &str,
+    visitor: &mut impl SpecVisitor<'s>,
+) {
+    match header {
+        ObjectOrReference::Ref { ref_path, .. } => visitor.visit_ref(ref_path, ctx, ptr),
+        ObjectOrReference::Object(header) => {
+            visitor.visit_header(name, header, ctx, ptr);
+            if let Some(schema) = &header.schema {
+                walk_schema_ref(schema, ctx, &format!("{}/schema", ptr), visitor);
+            }
+            if let Some(content) = &header.content {
+                walk_content(content, ctx, ptr, visitor);
+            }
+        }
+    }
+}
+
+fn walk_content<'s>(
+    content: &'s std::collections::BTreeMap<String, MediaType>,
+    ctx: &NodeCtx<'s>,
+    ptr: &str,
+    visitor: &mut impl SpecVisitor<'s>,
+) {
+    for (media_type, media) in content {
+        if let Some(schema) = &media.schema {
+            let schema_ptr = format!(
+                "{}/content/{}/schema",
+                ptr,
+                escape_pointer_segment(media_type)
+            );
+            walk_schema_ref(schema, ctx, &schema_ptr, visitor);
+        }
+    }
+}
+
+fn walk_schema_ref<'s>(
+    schema: &'s ObjectOrReference<ObjectSchema>,
+    ctx: &NodeCtx<'s>,
+    ptr: &str,
+    visitor: &mut impl SpecVisitor<'s>,
+) {
+    match schema {
+        ObjectOrReference::Ref { ref_path, .. } => visitor.visit_ref(ref_path, ctx, ptr),
+        ObjectOrReference::Object(schema) => walk_schema(schema, ctx, ptr, visitor),
+    }
+}
+
+fn walk_boxed_schema<'s>(
+    schema: &'s Schema,
+    ctx: &NodeCtx<'s>,
+    ptr: &str,
+    visitor: &mut impl SpecVisitor<'s>,
+) {
+    if let Schema::Object(schema) = schema {
+        walk_schema_ref(schema, ctx, ptr, visitor);
+    }
+}
+
+fn walk_schema<'s>(
+    schema: &'s ObjectSchema,
+    ctx: &NodeCtx<'s>,
+    ptr: &str,
+    visitor: &mut impl SpecVisitor<'s>,
+) {
+    visitor.visit_schema(schema, ctx, ptr);
+
+    let child = NodeCtx {
+        depth: ctx.depth + 1,
+        ..*ctx
+    };
+
+    for (name, property) in &schema.properties {
+        let property_ptr = format!("{}/properties/{}", ptr, escape_pointer_segment(name));
+        walk_schema_ref(property, &child, &property_ptr, visitor);
+    }
+    if let Some(items) = &schema.items {
+        walk_boxed_schema(items, &child, &format!("{}/items", ptr), visitor);
+    }
+    for (i, item) in schema.prefix_items.iter().enumerate() {
+        walk_schema_ref(item, &child, &format!("{}/prefixItems/{}", ptr, i), visitor);
+    }
+    if let Some(additional) = &schema.additional_properties {
+        walk_boxed_schema(
+            additional,
+            &child,
+            &format!("{}/additionalProperties", ptr),
+            visitor,
+        );
+    }
+    for (keyword, subschemas) in [
+        ("allOf", &schema.all_of),
+        ("oneOf", &schema.one_of),
+        ("anyOf", &schema.any_of),
+    ] {
+        for (i, subschema) in subschemas.iter().enumerate() {
+            walk_schema_ref(
+                subschema,
+                &child,
+                &format!("{}/{}/{}", ptr, keyword, i),
+                visitor,
+            );
+        }
+    }
+}
+
+/// Document-wide counts gathered with a single [`walk_spec`] pass.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpecStats {
+    pub paths: usize,
+    pub operations: usize,
+    pub parameters: usize,
+    pub request_bodies: usize,
+    pub responses: usize,
+    pub schemas: usize,
+    pub refs: usize,
+    /// Deepest schema nesting level seen
+    pub max_schema_depth: usize,
+}
+
+impl SpecStats {
+    pub fn collect(spec: &oas3::Spec) -> Self {
+        let mut stats = SpecStats::default();
+        walk_spec(spec, &mut stats);
+        stats
+    }
+}
+
+impl<'s> SpecVisitor<'s> for SpecStats {
+    fn visit_path_item(&mut self, _path: &'s str, _item: &'s PathItem, _ptr: &str) {
+        self.paths += 1;
+    }
+
+    fn visit_operation(&mut self, _operation: &'s Operation, _ctx: &NodeCtx<'s>, _ptr: &str) {
+        self.operations += 1;
+    }
+
+    fn visit_parameter(&mut self, _param: &'s Parameter, _ctx: &NodeCtx<'s>, _ptr: &str) {
+        self.parameters += 1;
+    }
+
+    fn visit_request_body(&mut self, _body: &'s RequestBody, _ctx: &NodeCtx<'s>, _ptr: &str) {
+        self.request_bodies += 1;
+    }
+
+    fn visit_response(
+        &mut self,
+        _status: &'s str,
+        _response: &'s Response,
+        _ctx: &NodeCtx<'s>,
+        _ptr: &str,
+    ) {
+        self.responses += 1;
+    }
+
+    fn visit_schema(&mut self, _schema: &'s ObjectSchema, ctx: &NodeCtx<'s>, _ptr: &str) {
+        self.schemas += 1;
+        self.max_schema_depth = self.max_schema_depth.max(ctx.depth);
+    }
+
+    fn visit_ref(&mut self, _ref_path: &'s str, _ctx: &NodeCtx<'s>, _ptr: &str) {
+        self.refs += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: &str = r#"
+openapi: 3.1.0
+info:
+  title: Test API
+  version: 1.0.0
+paths:
+  /pets/{petId}:
+    parameters:
+      - name: petId
+        in: path
+        required: true
+        schema:
+          type: string
+    get:
+      operationId: getPet
+      parameters:
+        - name: fields
+          in: query
+          schema:
+            type: array
+            items:
+              type: string
+      responses:
+        '200':
+          description: OK
+          content:
+            application/json:
+              schema:
+                $ref: '#/components/schemas/Pet'
+components:
+  schemas:
+    Pet:
+      type: object
+      properties:
+        name:
+          type: string
+        tags:
+          type: array
+          items:
+            $ref: '#/components/schemas/Tag'
+    Tag:
+      type: string
+"#;
+
+    fn parse(yaml: &str) -> oas3::Spec {
+        serde_saphyr::from_str(yaml).unwrap()
+    }
+
+    #[derive(Default)]
+    struct Recorder {
+        params: Vec<(String, Option<&'static str>)>,
+        refs: Vec<(String, String)>,
+    }
+
+    impl<'s> SpecVisitor<'s> for Recorder {
+        fn visit_parameter(&mut self, _param: &'s Parameter, ctx: &NodeCtx<'s>, ptr: &str) {
+            self.params.push((ptr.to_string(), ctx.method));
+        }
+
+        fn visit_ref(&mut self, ref_path: &'s str, _ctx: &NodeCtx<'s>, ptr: &str) {
+            self.refs.push((ref_path.to_string(), ptr.to_string()));
+        }
+    }
+
+    #[test]
+    fn test_walk_spec_pointers_and_context() {
+        let spec = parse(SPEC);
+        let mut recorder = Recorder::default();
+        walk_spec(&spec, &mut recorder);
+
+        assert_eq!(
+            recorder.params,
+            vec![
+                ("/paths/~1pets~1{petId}/parameters/0".to_string(), None),
+                (
+                    "/paths/~1pets~1{petId}/get/parameters/0".to_string(),
+                    Some("get")
+                ),
+            ]
+        );
+        assert_eq!(
+            recorder.refs,
+            vec![
+                (
+                    "#/components/schemas/Pet".to_string(),
+                    "/paths/~1pets~1{petId}/get/responses/200/content/application~1json/schema"
+                        .to_string()
+                ),
+                (
+                    "#/components/schemas/Tag".to_string(),
+                    "/components/schemas/Pet/properties/tags/items".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_spec_stats() {
+        let stats = SpecStats::collect(&parse(SPEC));
+        assert_eq!(
+            stats,
+            SpecStats {
+                paths: 1,
+                operations: 1,
+                parameters: 2,
+                request_bodies: 0,
+                responses: 1,
+                schemas: 7,
+                refs: 2,
+                max_schema_depth: 1,
+            }
+        );
+    }
+}
@@ -0,0 +1,374 @@
+//! The interface between a [`Linter`](crate::Linter) and rules built as
+//! shared library plugins.
+//!
+//! Rust has no stable ABI, so a plugin can't hand the linter a
+//! `Box<dyn Rule>`: the vtable layout differs between compiler versions.
+//! Like generator plugins, rule plugins exchange JSON through a
+//! `#[repr(C)]` [`RulePluginVTable`] of `extern "C"` functions, versioned by
+//! [`RULE_PLUGIN_ABI_VERSION`]. The library exports two symbols:
+//!
+//! ```ignore
+//! #[unsafe(no_mangle)]
+//! pub extern "C" fn oas_lint_plugin_abi_version() -> u32 {
+//!     lint::plugin::RULE_PLUGIN_ABI_VERSION
+//! }
+//!
+//! #[unsafe(no_mangle)]
+//! pub extern "C" fn oas_lint_plugin_vtable() -> *const RulePluginVTable {
+//!     static VTABLE: RulePluginVTable = RulePluginVTable {
+//!         abi_version: RULE_PLUGIN_ABI_VERSION,
+//!         rules,
+//!         check,
+//!         free,
+//!     };
+//!     &VTABLE
+//! }
+//! ```
+//!
+//! The linter checks the version before touching the vtable, so a plugin
+//! built for another version fails to load with [`LintError::Plugin`]
+//! instead of crashing. The messages are:
+//!
+//! - `rules()`: `[{"id": "rate-limit-responses", "severity": "warning"}]`
+//!   (`severity` optional, `warning` by default)
+//! - `check` input: `{"abi_version": 1, "rule": "rate-limit-responses", "spec": <document>}`
+//! - `check` output: `{"findings": [{"pointer": "/paths/~1pets", "message": "...", "severity": "error"}]}`
+//!   (`severity` optional, the rule's by default), or `{"error": "message"}`
+
+use std::sync::Arc;
+
+use serde::Deserialize;
+
+use crate::LintError;
+use crate::lint::{Finding, LintCtx, Rule, RuleId, Severity};
+
+/// Version of [`RulePluginVTable`] and the JSON messages. Bumped on any
+/// incompatible change.
+pub const RULE_PLUGIN_ABI_VERSION: u32 = 1;
+
+/// Symbol of `extern "C" fn() -> u32` returning the plugin's
+/// [`RULE_PLUGIN_ABI_VERSION`].
+pub const ABI_VERSION_SYMBOL: &[u8] = b"oas_lint_plugin_abi_version";
+
+/// Symbol of `extern "C" fn() -> *const RulePluginVTable`.
+pub const VTABLE_SYMBOL: &[u8] = b"oas_lint_plugin_vtable";
+
+/// Bytes allocated by the plugin, handed to the linter and given back to
+/// [`RulePluginVTable::free`].
+#[repr(C)]
+#[derive(Debug)]
+pub struct PluginBuffer {
+    pub ptr: *mut u8,
+    pub len: usize,
+    pub capacity: usize,
+}
+
+impl PluginBuffer {
+    /// Hand `bytes` over to the linter. For plugins written in Rust.
+    pub fn from_vec(bytes: Vec<u8>) -> Self {
+        let mut bytes = std::mem::ManuallyDrop::new(bytes);
+        Self {
+            ptr: bytes.as_mut_ptr(),
+            len: bytes.len(),
+            capacity: bytes.capacity(),
+        }
+    }
+
+    /// Take back a buffer made by [`from_vec`](Self::from_vec), e.g. in
+    /// the plugin's [`free`](RulePluginVTable::free).
+    ///
+    /// # Safety
+    ///
+    /// `self` must come from [`from_vec`](Self::from_vec) in the same
+    /// binary and not have been freed.
+    pub unsafe fn into_vec(self) -> Vec<u8> {
+        unsafe { Vec::from_raw_parts(self.ptr, self.len, self.capacity) }
+    }
+
+    /// The bytes, without taking ownership.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to `len` readable bytes until the buffer is freed.
+    pub unsafe fn as_slice(&self) -> &[u8] {
+        if self.ptr.is_null() {
+            return &[];
+        }
+        unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+    }
+}
+
+/// Functions of a rule plugin. Every function may be called from any
+/// thread.
+#[repr(C)]
+pub struct RulePluginVTable {
+    /// [`RULE_PLUGIN_ABI_VERSION`] the plugin was built for.
+    pub abi_version: u32,
+    /// The plugin's rules, as JSON.
+    pub rules: unsafe extern "C" fn() -> PluginBuffer,
+    /// Check one rule against the JSON input of `len` bytes at `input`,
+    /// returning the JSON output.
+    pub check: unsafe extern "C" fn(input: *const u8, len: usize) -> PluginBuffer,
+    /// Free a buffer returned by `rules` or `check`.
+    pub free: unsafe extern "C" fn(buffer: PluginBuffer),
+}
+
+/// A loaded plugin, shared by its rules.
+struct Plugin {
+    name: String,
+    vtable: &'static RulePluginVTable,
+    // Last, so the vtable's functions outlive every use
+    #[cfg(feature = "dynamic-plugins")]
+    _library: Option<libloading::Library>,
+}
+
+// The ABI requires plugin functions to be callable from any thread
+unsafe impl Send for Plugin {}
+unsafe impl Sync for Plugin {}
+
+impl Plugin {
+    /// Call `f` and copy the buffer it returns, then give it back to be freed.
+    fn call(&self, f: impl FnOnce(&RulePluginVTable) -> PluginBuffer) -> Vec<u8> {
+        let buffer = f(self.vtable);
+        let bytes = unsafe { buffer.as_slice() }.to_vec();
+        unsafe { (self.vtable.free)(buffer) };
+        bytes
+    }
+}
+
+/// A rule of a plugin.
+struct PluginRule {
+    id: RuleId,
+    severity: Severity,
+    plugin: Arc<Plugin>,
+}
+
+impl Rule for PluginRule {
+    fn id(&self) -> RuleId {
+        self.id
+    }
+
+    fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    /// A plugin that fails reports one error finding at the root instead.
+    fn check(&self, ctx: &LintCtx) -> Vec<Finding> {
+        let failed = |message: String| {
+            vec![
+                Finding::new(
+                    self.id,
+                    "",
+                    format!("plugin {} failed: {}", self.plugin.name, message),
+                )
+                .with_severity(Severity::Error),
+            ]
+        };
+        let input = serde_json::to_vec(&serde_json::json!({
+            "abi_version": RULE_PLUGIN_ABI_VERSION,
+            "rule": self.id.as_str(),
+            "spec": ctx.document(),
+        }))
+        .expect("the plugin input serializes to JSON");
+        let output = self
+            .plugin
+            .call(|vtable| unsafe { (vtable.check)(input.as_ptr(), input.len()) });
+        let output: CheckOutput = match serde_json::from_slice(&output) {
+            Ok(output) => output,
+            Err(e) => return failed(format!("invalid output: {}", e)),
+        };
+        if let Some(message) = output.error {
+            return failed(message);
+        }
+        output
+            .findings
+            .into_iter()
+            .map(|finding| {
+                let severity = finding
+                    .severity
+                    .as_deref()
+                    .and_then(Severity::parse)
+                    .unwrap_or(self.severity);
+                Finding::new(self.id, finding.pointer, finding.message).with_severity(severity)
+            })
+            .collect()
+    }
+}
+
+#[derive(Deserialize)]
+struct RuleInfo {
+    id: String,
+    severity: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CheckOutput {
+    #[serde(default)]
+    findings: Vec<OutputFinding>,
+    error: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct OutputFinding {
+    pointer: String,
+    message: String,
+    severity: Option<String>,
+}
+
+/// The rules of the plugin at `path`, checking that it was built for
+/// [`RULE_PLUGIN_ABI_VERSION`] before calling anything else.
+#[cfg(feature = "dynamic-plugins")]
+pub(crate) fn load(path: &std::path::Path) -> Result<Vec<Box<dyn Rule>>, LintError> {
+    use libloading::Library;
+
+    let error = |message: String| LintError::Plugin(format!("{}: {}", path.display(), message));
+    // Loading runs the library's initializers; plugins are trusted code
+    let library = unsafe { Library::new(path) }.map_err(|e| error(e.to_string()))?;
+
+    let version = unsafe {
+        let abi_version = library
+            .get::<unsafe extern "C" fn() -> u32>(ABI_VERSION_SYMBOL)
+            .map_err(|_| {
+                error(
+                    "not a lint plugin, or built for the unversioned interface: it doesn't export `oas_lint_plugin_abi_version`"
+                        .to_string(),
+                )
+            })?;
+        abi_version()
+    };
+    check_version("plugin", version).map_err(error)?;
+
+    // 'static as far as the rules are concerned: the library stays loaded
+    // as long as they do
+    let vtable: &'static RulePluginVTable = unsafe {
+        let vtable = library
+            .get::<unsafe extern "C" fn() -> *const RulePluginVTable>(VTABLE_SYMBOL)
+            .map_err(|e| error(e.to_string()))?;
+        vtable().as_ref()
+    }
+    .ok_or_else(|| error("plugin returned no vtable".to_string()))?;
+
+    rules(Plugin {
+        name: path.display().to_string(),
+        vtable,
+        _library: Some(library),
+    })
+    .map_err(error)
+}
+
+/// The rules of the plugin behind `vtable`, e.g. one linked into the
+/// program. Fails if it was built for another ABI version.
+pub fn from_vtable(
+    name: &str,
+    vtable: &'static RulePluginVTable,
+) -> Result<Vec<Box<dyn Rule>>, LintError> {
+    rules(Plugin {
+        name: name.to_string(),
+        vtable,
+        #[cfg(feature = "dynamic-plugins")]
+        _library: None,
+    })
+    .map_err(|message| LintError::Plugin(format!("{}: {}", name, message)))
+}
+
+fn check_version(what: &str, version: u32) -> Result<(), String> {
+    if version == RULE_PLUGIN_ABI_VERSION {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} uses ABI version {}, expected {}",
+            what, version, RULE_PLUGIN_ABI_VERSION
+        ))
+    }
+}
+
+fn rules(plugin: Plugin) -> Result<Vec<Box<dyn Rule>>, String> {
+    check_version("plugin vtable", plugin.vtable.abi_version)?;
+    let infos = plugin.call(|vtable| unsafe { (vtable.rules)() });
+    let infos: Vec<RuleInfo> =
+        serde_json::from_slice(&infos).map_err(|e| format!("invalid rule list: {}", e))?;
+    let plugin = Arc::new(plugin);
+    infos
+        .into_iter()
+        .map(|info| {
+            let severity = match info.severity.as_deref() {
+                None => Severity::Warning,
+                Some(severity) => Severity::parse(severity)
+                    .ok_or_else(|| format!("rule {}: unknown severity `{}`", info.id, severity))?,
+            };
+            Ok(Box::new(PluginRule {
+                id: RuleId::custom(&info.id),
+                severity,
+                plugin: plugin.clone(),
+            }) as Box<dyn Rule>)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Linter;
+    use crate::lint::RuleSet;
+
+    unsafe extern "C" fn rules() -> PluginBuffer {
+        PluginBuffer::from_vec(br#"[{"id":"info-contact-required","severity":"error"}]"#.to_vec())
+    }
+
+    unsafe extern "C" fn check(input: *const u8, len: usize) -> PluginBuffer {
+        let input: serde_json::Value =
+            serde_json::from_slice(unsafe { std::slice::from_raw_parts(input, len) }).unwrap();
+        let output = if input["spec"]["info"].get("contact").is_some() {
+            serde_json::json!({ "findings": [] })
+        } else {
+            serde_json::json!({
+                "findings": [{ "pointer": "/info", "message": "Add a contact" }]
+            })
+        };
+        PluginBuffer::from_vec(serde_json::to_vec(&output).unwrap())
+    }
+
+    unsafe extern "C" fn free(buffer: PluginBuffer) {
+        drop(unsafe { buffer.into_vec() });
+    }
+
+    static VTABLE: RulePluginVTable = RulePluginVTable {
+        abi_version: RULE_PLUGIN_ABI_VERSION,
+        rules,
+        check,
+        free,
+    };
+
+    static OUTDATED: RulePluginVTable = RulePluginVTable {
+        abi_version: RULE_PLUGIN_ABI_VERSION + 1,
+        rules,
+        check,
+        free,
+    };
+
+    #[test]
+    fn test_rule_plugin() {
+        let spec = "openapi: 3.1.0\ninfo:\n  title: Pets\n  version: '1'\npaths: {}\n";
+        let linter =
+            Linter::new(RuleSet::new()).with_rules(from_vtable("contact-rules", &VTABLE).unwrap());
+        let result = linter.lint(spec).unwrap();
+        assert_eq!(result.diagnostics.len(), 1);
+        let diagnostic = &result.diagnostics[0];
+        assert_eq!(diagnostic.rule.as_str(), "info-contact-required");
+        assert_eq!(diagnostic.severity, Severity::Error);
+        assert_eq!(diagnostic.pointer, "/info");
+        assert_eq!(diagnostic.range.start.line, 2);
+
+        let Err(LintError::Plugin(message)) = from_vtable("contact-rules", &OUTDATED) else {
+            panic!("expected a version mismatch");
+        };
+        assert_eq!(
+            message,
+            format!(
+                "contact-rules: plugin vtable uses ABI version {}, expected {}",
+                RULE_PLUGIN_ABI_VERSION + 1,
+                RULE_PLUGIN_ABI_VERSION
+            )
+        );
+    }
+}
@@ -0,0 +1,380 @@
+//! Rendering lint results.
+//!
+//! A [`Reporter`] writes a [`Validation`] for one spec file. The built-in
+//! ones are picked by name with [`ReportFormat`]:
+//!
+//! - `pretty`: rustc-style diagnostics with the offending source line, for
+//!   terminals
+//! - `json`: one object per diagnostic, for scripts and editors
+//! - `sarif`: SARIF 2.1.0, for code-scanning UIs (see [`crate::sarif`])
+
+use std::io::{self, Write};
+
+use serde_json::{Value, json};
+
+use crate::api::Validation;
+use crate::lint::{Diagnostic, Severity};
+
+/// The spec file a [`Validation`] was produced from.
+#[derive(Debug, Clone, Copy)]
+pub struct SpecFile<'a> {
+    /// Path or URI as it should be shown to the user
+    pub path: &'a str,
+    /// The text that was linted
+    pub content: &'a str,
+}
+
+/// Renders lint results.
+pub trait Reporter {
+    fn report(
+        &self,
+        validation: &Validation,
+        spec: SpecFile<'_>,
+        out: &mut dyn Write,
+    ) -> io::Result<()>;
+
+    /// [`report`](Self::report) into a string.
+    fn render(&self, validation: &Validation, spec: SpecFile<'_>) -> String {
+        let mut out = Vec::new();
+        // Writing to a Vec can't fail
+        let _ = self.report(validation, spec, &mut out);
+        String::from_utf8_lossy(&out).into_owned()
+    }
+}
+
+/// The built-in reporters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReportFormat {
+    #[default]
+    Pretty,
+    Json,
+    Sarif,
+}
+
+impl ReportFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReportFormat::Pretty => "pretty",
+            ReportFormat::Json => "json",
+            ReportFormat::Sarif => "sarif",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<ReportFormat> {
+        match s {
+            "pretty" => Some(ReportFormat::Pretty),
+            "json" => Some(ReportFormat::Json),
+            "sarif" => Some(ReportFormat::Sarif),
+            _ => None,
+        }
+    }
+
+    /// The reporter for this format. Pretty output is colored.
+    pub fn reporter(&self) -> Box<dyn Reporter> {
+        match self {
+            ReportFormat::Pretty => Box::new(PrettyReporter::new()),
+            ReportFormat::Json => Box::new(JsonReporter),
+            ReportFormat::Sarif => Box::new(SarifReporter),
+        }
+    }
+}
+
+/// rustc-style diagnostics: severity, message and rule ID, the location,
+/// and the source line with the offending span underlined.
+#[derive(Debug, Clone)]
+pub struct PrettyReporter {
+    color: bool,
+}
+
+impl PrettyReporter {
+    /// A reporter using ANSI colors.
+    pub fn new() -> Self {
+        Self { color: true }
+    }
+
+    /// Turn ANSI colors on or off, e.g. off when not writing to a terminal.
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    fn paint(&self, style: &str, text: &str) -> String {
+        if self.color {
+            format!("\x1b[{}m{}\x1b[0m", style, text)
+        } else {
+            text.to_string()
+        }
+    }
+
+    fn severity_style(severity: Severity) -> &'static str {
+        match severity {
+            Severity::Error => "1;31",
+            Severity::Warning => "1;33",
+            Severity::Info => "1;36",
+            Severity::Hint => "1;34",
+        }
+    }
+
+    fn diagnostic(
+        &self,
+        diag: &Diagnostic,
+        spec: SpecFile<'_>,
+        out: &mut dyn Write,
+    ) -> io::Result<()> {
+        writeln!(
+            out,
+            "{}: {}{}",
+            self.paint(Self::severity_style(diag.severity), diag.severity.as_str()),
+            diag.message,
+            self.paint("1", &format!("[{}]", diag.rule.as_str()))
+        )?;
+
+        let line_num = diag.range.start.line + 1;
+        let col_num = diag.range.start.col + 1;
+        writeln!(
+            out,
+            "  {} {}:{}:{}",
+            self.paint("1;36", "-->"),
+            spec.path,
+            line_num,
+            col_num
+        )?;
+        if !diag.pointer.is_empty() {
+            writeln!(out, "  {} {}", self.paint("1;36", "at"), diag.pointer)?;
+        }
+
+        if let Some(line_text) = spec.content.lines().nth(diag.range.start.line as usize) {
+            let gutter_width = line_num.to_string().len();
+            let gutter = " ".repeat(gutter_width);
+            let bar = self.paint("1;36", "|");
+
+            writeln!(out, "{} {}", gutter, bar)?;
+            writeln!(
+                out,
+                "{} {}",
+                self.paint(
+                    "1;36",
+                    &format!("{:>width$} |", line_num, width = gutter_width)
+                ),
+                line_text
+            )?;
+
+            let spaces = " ".repeat(diag.range.start.col as usize);
+            let carets = if diag.range.end.line == diag.range.start.line {
+                let len = diag
+                    .range
+                    .end
+                    .col
+                    .saturating_sub(diag.range.start.col)
+                    .max(1);
+                "^".repeat(len as usize)
+            } else {
+                "^".to_string()
+            };
+            writeln!(
+                out,
+                "{} {} {}{}",
+                gutter,
+                bar,
+                spaces,
+                self.paint(Self::severity_style(diag.severity), &carets)
+            )?;
+            writeln!(out, "{} {}", gutter, bar)?;
+        }
+
+        writeln!(out)
+    }
+}
+
+impl Default for PrettyReporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Reporter for PrettyReporter {
+    fn report(
+        &self,
+        validation: &Validation,
+        spec: SpecFile<'_>,
+        out: &mut dyn Write,
+    ) -> io::Result<()> {
+        if validation.diagnostics.is_empty() {
+            return writeln!(out, "✅ No issues found");
+        }
+
+        for diag in &validation.diagnostics {
+            self.diagnostic(diag, spec, out)?;
+        }
+
+        let mut summary = Vec::new();
+        for severity in [Severity::Error, Severity::Warning] {
+            let count = validation
+                .diagnostics
+                .iter()
+                .filter(|d| d.severity == severity)
+                .count();
+            if count > 0 {
+                summary.push(self.paint(
+                    Self::severity_style(severity),
+                    &format!(
+                        "{} {}{}",
+                        count,
+                        severity.as_str(),
+                        if count == 1 { "" } else { "s" }
+                    ),
+                ));
+            }
+        }
+        if !summary.is_empty() {
+            writeln!(out, "{}", summary.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// A JSON object with the file, whether it parsed, and the diagnostics.
+/// Lines and columns are 1-based.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonReporter;
+
+impl JsonReporter {
+    /// The report as a JSON value.
+    pub fn to_json(validation: &Validation, spec: SpecFile<'_>) -> Value {
+        let diagnostics: Vec<Value> = validation
+            .diagnostics
+            .iter()
+            .map(|diag| {
+                json!({
+                    "rule": diag.rule.as_str(),
+                    "severity": diag.severity.as_str(),
+                    "message": diag.message,
+                    "pointer": diag.pointer,
+                    "start": { "line": diag.range.start.line + 1, "column": diag.range.start.col + 1 },
+                    "end": { "line": diag.range.end.line + 1, "column": diag.range.end.col + 1 },
+                    "offset": diag.byte_span.start,
+                    "snippet": diag.snippet,
+                })
+            })
+            .collect();
+        json!({
+            "path": spec.path,
+            "parsed": validation.parsed_ok,
+            "diagnostics": diagnostics
+        })
+    }
+}
+
+impl Reporter for JsonReporter {
+    fn report(
+        &self,
+        validation: &Validation,
+        spec: SpecFile<'_>,
+        out: &mut dyn Write,
+    ) -> io::Result<()> {
+        serde_json::to_writer_pretty(&mut *out, &Self::to_json(validation, spec))?;
+        writeln!(out)
+    }
+}
+
+/// SARIF 2.1.0, with the spec's path as the artifact.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SarifReporter;
+
+impl Reporter for SarifReporter {
+    fn report(
+        &self,
+        validation: &Validation,
+        spec: SpecFile<'_>,
+        out: &mut dyn Write,
+    ) -> io::Result<()> {
+        writeln!(
+            out,
+            "{}",
+            crate::sarif::to_sarif_string(validation, Some(spec.path))
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::lint;
+
+    const SPEC: &str = r#"openapi: "3.1.0"
+info:
+  title: Test API
+  version: "1.0"
+paths:
+  /pets:
+    get:
+      responses:
+        "200":
+          description: OK
+"#;
+
+    fn spec() -> SpecFile<'static> {
+        SpecFile {
+            path: "openapi.yaml",
+            content: SPEC,
+        }
+    }
+
+    #[test]
+    fn test_pretty_reporter() {
+        let result = lint(SPEC, &["operation-id-required"]).unwrap();
+        let output = PrettyReporter::new()
+            .with_color(false)
+            .render(&result, spec());
+        assert!(output.starts_with("warning: "));
+        assert!(output.contains("[operation-id-required]"));
+        let line = result.diagnostics[0].range.start.line + 1;
+        assert!(output.contains(&format!("--> openapi.yaml:{}:", line)));
+        assert!(output.contains("at /paths/~1pets/get/operationId"));
+        assert!(output.contains(&format!("{} | ", line)));
+        assert!(output.ends_with("1 warning\n"));
+        assert!(!output.contains('\x1b'));
+
+        let colored = PrettyReporter::new().render(&result, spec());
+        assert!(colored.contains("\x1b[1;33mwarning\x1b[0m"));
+
+        let clean = lint(SPEC, &["paths-not-empty"]).unwrap();
+        assert_eq!(
+            PrettyReporter::new().render(&clean, spec()),
+            "✅ No issues found\n"
+        );
+    }
+
+    #[test]
+    fn test_json_reporter() {
+        let result = lint(SPEC, &["operation-id-required"]).unwrap();
+        let output = ReportFormat::Json.reporter().render(&result, spec());
+        let value: Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(value["path"], "openapi.yaml");
+        assert_eq!(value["parsed"], true);
+        let diagnostic = &value["diagnostics"][0];
+        assert_eq!(diagnostic["rule"], "operation-id-required");
+        assert_eq!(diagnostic["severity"], "warning");
+        assert_eq!(diagnostic["pointer"], "/paths/~1pets/get/operationId");
+        assert_eq!(
+            diagnostic["start"]["line"],
+            result.diagnostics[0].range.start.line + 1
+        );
+        // The missing operationId is reported on its operation
+        let snippet = diagnostic["snippet"].as_str().unwrap();
+        assert!(snippet.starts_with("      responses:\n"));
+        assert!(snippet.ends_with("description: OK"));
+    }
+
+    #[test]
+    fn test_report_format_parse() {
+        for format in [
+            ReportFormat::Pretty,
+            ReportFormat::Json,
+            ReportFormat::Sarif,
+        ] {
+            assert_eq!(ReportFormat::parse(format.as_str()), Some(format));
+        }
+        assert_eq!(ReportFormat::parse("xml"), None);
+    }
+}
@@ -0,0 +1,204 @@
+use oas3::spec::{ObjectOrReference, ObjectSchema, Operation, PathItem, Response};
+
+use crate::{
+    lint::{Finding, LintCtx, RuleId},
+    model::{NodeCtx, SpecVisitor, walk_spec},
+};
+
+/// Operations with inline object bodies need an operationId to name the
+/// generated request and response types
+pub fn gen_inline_body_operation_id(ctx: &LintCtx, out: &mut Vec<Finding>) {
+    run(ctx, RuleId::GenInlineBodyOperationId, out);
+}
+
+/// anyOf becomes a plain union, losing the "several at once" semantics
+pub fn gen_any_of_union(ctx: &LintCtx, out: &mut Vec<Finding>) {
+    run(ctx, RuleId::GenAnyOfUnion, out);
+}
+
+/// Generated clients decode a single content type per response
+pub fn gen_multiple_response_content_types(ctx: &LintCtx, out: &mut Vec<Finding>) {
+    run(ctx, RuleId::GenMultipleResponseContentTypes, out);
+}
+
+/// Path-level parameters are merged into every operation of the path
+pub fn gen_path_level_parameters(ctx: &LintCtx, out: &mut Vec<Finding>) {
+    run(ctx, RuleId::GenPathLevelParameters, out);
+}
+
+fn run(ctx: &LintCtx, rule: RuleId, out: &mut Vec<Finding>) {
+    walk_spec(ctx.spec, &mut Compat { rule, out });
+}
+
+/// Reports the constructs of one generator-compatibility rule
+struct Compat<'a> {
+    rule: RuleId,
+    out: &'a mut Vec<Finding>,
+}
+
+impl<'s> SpecVisitor<'s> for Compat<'_> {
+    fn visit_path_item(&mut self, path: &'s str, item: &'s PathItem, ptr: &str) {
+        if self.rule == RuleId::GenPathLevelParameters && !item.parameters.is_empty() {
+            self.out.push(Finding::new(
+                self.rule,
+                format!("{}/parameters", ptr),
+                format!(
+                    "Path {} declares parameters for all its operations; generators copy them into each operation, so declare them per operation to control the generated signatures",
+                    path
+                ),
+            ));
+        }
+    }
+
+    fn visit_operation(&mut self, operation: &'s Operation, ctx: &NodeCtx<'s>, ptr: &str) {
+        if self.rule != RuleId::GenInlineBodyOperationId || operation.operation_id.is_some() {
+            return;
+        }
+        let request = matches!(&operation.request_body, Some(ObjectOrReference::Object(body))
+            if body.content.values().any(|media| is_inline_object(&media.schema)));
+        let response = operation.responses.iter().flatten().any(|(_, response)| {
+            matches!(response, ObjectOrReference::Object(response)
+                if response.content.values().any(|media| is_inline_object(&media.schema)))
+        });
+        if request || response {
+            self.out.push(Finding::new(
+                self.rule,
+                format!("{}/operationId", ptr),
+                format!(
+                    "{} {} has inline body schemas but no operationId; generated type names will be derived from the path",
+                    ctx.method.unwrap_or_default().to_uppercase(),
+                    ctx.path.unwrap_or_default()
+                ),
+            ));
+        }
+    }
+
+    fn visit_response(
+        &mut self,
+        status: &'s str,
+        response: &'s Response,
+        _ctx: &NodeCtx<'s>,
+        ptr: &str,
+    ) {
+        if self.rule == RuleId::GenMultipleResponseContentTypes && response.content.len() > 1 {
+            let media_types: Vec<&str> = response.content.keys().map(String::as_str).collect();
+            self.out.push(Finding::new(
+                self.rule,
+                format!("{}/content", ptr),
+                format!(
+                    "Response {} has {} content types ({}); generated clients only decode one of them",
+                    status,
+                    media_types.len(),
+                    media_types.join(", ")
+                ),
+            ));
+        }
+    }
+
+    fn visit_schema(&mut self, schema: &'s ObjectSchema, _ctx: &NodeCtx<'s>, ptr: &str) {
+        if self.rule == RuleId::GenAnyOfUnion && !schema.any_of.is_empty() {
+            self.out.push(Finding::new(
+                self.rule,
+                format!("{}/anyOf", ptr),
+                "anyOf is generated as a union of exactly one variant; use oneOf, or allOf for values matching several schemas",
+            ));
+        }
+    }
+}
+
+/// An inline schema the generators have to name: an object or a composition
+fn is_inline_object(schema: &Option<ObjectOrReference<ObjectSchema>>) -> bool {
+    matches!(schema, Some(ObjectOrReference::Object(schema))
+        if !schema.properties.is_empty()
+            || !schema.all_of.is_empty()
+            || !schema.one_of.is_empty()
+            || !schema.any_of.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lint::RuleId;
+    use crate::{Linter, RuleSet};
+
+    const SPEC: &str = r#"
+openapi: 3.1.0
+info: { title: Pets, version: "1" }
+paths:
+  /pets/{petId}:
+    parameters:
+      - name: petId
+        in: path
+        required: true
+        schema: { type: string }
+    put:
+      requestBody:
+        content:
+          application/json:
+            schema:
+              type: object
+              properties:
+                name: { type: string }
+      responses:
+        "200":
+          description: OK
+          content:
+            application/json:
+              schema: { $ref: '#/components/schemas/Pet' }
+            application/xml:
+              schema: { $ref: '#/components/schemas/Pet' }
+    get:
+      operationId: getPet
+      responses:
+        "200":
+          description: OK
+          content:
+            application/json:
+              schema:
+                type: object
+                properties:
+                  name: { type: string }
+components:
+  schemas:
+    Pet:
+      type: object
+      properties:
+        tag:
+          anyOf:
+            - type: string
+            - type: integer
+"#;
+
+    #[test]
+    fn test_compatibility_rules() {
+        let result = Linter::new(RuleSet::named("compatibility").unwrap())
+            .lint(SPEC)
+            .unwrap();
+        let found: Vec<(&str, &str)> = result
+            .diagnostics
+            .iter()
+            .map(|d| (d.rule.as_str(), d.pointer.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (
+                    "gen-path-level-parameters",
+                    "/paths/~1pets~1{petId}/parameters"
+                ),
+                (
+                    "gen-inline-body-operation-id",
+                    "/paths/~1pets~1{petId}/put/operationId"
+                ),
+                (
+                    "gen-multiple-response-content-types",
+                    "/paths/~1pets~1{petId}/put/responses/200/content"
+                ),
+                (
+                    "gen-any-of-union",
+                    "/components/schemas/Pet/properties/tag/anyOf"
+                ),
+            ]
+        );
+        assert!(!RuleSet::core().is_enabled(RuleId::GenAnyOfUnion));
+    }
+}
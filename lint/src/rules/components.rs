@@ -1,8 +1,11 @@
+use std::collections::{BTreeMap, BTreeSet};
+
 use oas3::spec::{ObjectOrReference, ObjectSchema};
 
+use super::security::referenced_schemes;
 use crate::{
     lint::{Finding, LintCtx, RuleId, Severity},
-    model::escape_pointer_segment,
+    model::{NodeCtx, SpecVisitor, escape_pointer_segment, walk_spec},
 };
 
 /// Reuse shared schemas via components/schemas (warn about complex inline schemas)
@@ -63,73 +66,154 @@ pub fn component_reuse_schemas(ctx: &LintCtx, out: &mut Vec<Finding>) {
     }
 }
 
-/// No unused components
+/// No unused components: those that no path or webhook reaches, directly or
+/// through other components. Security schemes are used when a security
+/// requirement names them.
 pub fn component_no_unused(ctx: &LintCtx, out: &mut Vec<Finding>) {
     let Some(components) = &ctx.spec.components else {
         return;
     };
 
-    let used_refs = ctx.used_refs();
+    let refs = collect_refs(ctx);
+    // Component pointer -> components it references, and the reverse
+    let mut edges: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    let mut referrers: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    let mut reachable: BTreeSet<&str> = BTreeSet::new();
+    let mut queue: Vec<&str> = Vec::new();
+    for (ref_path, ptr) in &refs {
+        let Some(target) = ref_path.strip_prefix('#').and_then(component_of) else {
+            continue;
+        };
+        match component_of(ptr) {
+            Some(owner) => {
+                edges.entry(owner).or_default().push(target);
+                if owner != target {
+                    referrers.entry(target).or_default().insert(owner);
+                }
+            }
+            None => {
+                if reachable.insert(target) {
+                    queue.push(target);
+                }
+            }
+        }
+    }
+    while let Some(component) = queue.pop() {
+        for &target in edges.get(component).into_iter().flatten() {
+            if reachable.insert(target) {
+                queue.push(target);
+            }
+        }
+    }
 
-    // Check for unused schemas
-    for name in components.schemas.keys() {
-        let ref_path = format!("#/components/schemas/{}", name);
-        if !used_refs.contains(&ref_path) {
+    let kinds = [
+        (
+            "schemas",
+            "Schema",
+            components.schemas.keys().collect::<Vec<_>>(),
+        ),
+        (
+            "parameters",
+            "Parameter",
+            components.parameters.keys().collect(),
+        ),
+        (
+            "responses",
+            "Response",
+            components.responses.keys().collect(),
+        ),
+        (
+            "requestBodies",
+            "RequestBody",
+            components.request_bodies.keys().collect(),
+        ),
+        ("headers", "Header", components.headers.keys().collect()),
+    ];
+    for (kind, label, names) in kinds {
+        for name in names {
+            let ptr = format!("/components/{}/{}", kind, escape_pointer_segment(name));
+            if reachable.contains(ptr.as_str()) {
+                continue;
+            }
+            let message = match referrers.get(ptr.as_str()) {
+                Some(from) => format!(
+                    "{} '{}' is only referenced from components no path uses: {}",
+                    label,
+                    name,
+                    from.iter().copied().collect::<Vec<_>>().join(", ")
+                ),
+                None => format!("{} '{}' is defined but never referenced", label, name),
+            };
             out.push(
-                Finding::new(
-                    RuleId::ComponentNoUnused,
-                    format!("/components/schemas/{}", name),
-                    format!("Schema '{}' is defined but never referenced", name),
-                )
-                .with_severity(Severity::Info),
+                Finding::new(RuleId::ComponentNoUnused, ptr, message).with_severity(Severity::Info),
             );
         }
     }
 
-    // Check for unused parameters
-    for name in components.parameters.keys() {
-        let ref_path = format!("#/components/parameters/{}", name);
-        if !used_refs.contains(&ref_path) {
+    let schemes = referenced_schemes(ctx.spec);
+    for name in components.security_schemes.keys() {
+        if !schemes.contains(name) {
+            // Unused schemes hint at operations missing their security
             out.push(
                 Finding::new(
                     RuleId::ComponentNoUnused,
-                    format!("/components/parameters/{}", name),
-                    format!("Parameter '{}' is defined but never referenced", name),
+                    format!(
+                        "/components/securitySchemes/{}",
+                        escape_pointer_segment(name)
+                    ),
+                    format!(
+                        "Security scheme '{}' is defined but never used by a security requirement",
+                        name
+                    ),
                 )
-                .with_severity(Severity::Info),
+                .with_severity(Severity::Warning),
             );
         }
     }
+}
 
-    // Check for unused responses
-    for name in components.responses.keys() {
-        let ref_path = format!("#/components/responses/{}", name);
-        if !used_refs.contains(&ref_path) {
-            out.push(
-                Finding::new(
-                    RuleId::ComponentNoUnused,
-                    format!("/components/responses/{}", name),
-                    format!("Response '{}' is defined but never referenced", name),
-                )
-                .with_severity(Severity::Info),
-            );
+/// Local `$ref`s must point at an existing node
+pub fn component_ref_dangling(ctx: &LintCtx, out: &mut Vec<Finding>) {
+    for (ref_path, ptr) in collect_refs(ctx) {
+        // External refs are bundled (and reported) by the parser
+        let Some(target) = ref_path.strip_prefix('#') else {
+            continue;
+        };
+        if ctx.document().pointer(target).is_none() {
+            out.push(Finding::new(
+                RuleId::ComponentRefDangling,
+                ptr.clone(),
+                format!(
+                    "$ref '{}' at {} points at nothing: {} does not exist",
+                    ref_path, ptr, target
+                ),
+            ));
         }
     }
+}
 
-    // Check for unused request bodies
-    for name in components.request_bodies.keys() {
-        let ref_path = format!("#/components/requestBodies/{}", name);
-        if !used_refs.contains(&ref_path) {
-            out.push(
-                Finding::new(
-                    RuleId::ComponentNoUnused,
-                    format!("/components/requestBodies/{}", name),
-                    format!("RequestBody '{}' is defined but never referenced", name),
-                )
-                .with_severity(Severity::Info),
-            );
+/// Every `$ref` in the document with the pointer of the node holding it.
+fn collect_refs<'s>(ctx: &LintCtx<'s>) -> Vec<(&'s str, String)> {
+    struct Refs<'s>(Vec<(&'s str, String)>);
+
+    impl<'s> SpecVisitor<'s> for Refs<'s> {
+        fn visit_ref(&mut self, ref_path: &'s str, _ctx: &NodeCtx<'s>, ptr: &str) {
+            self.0.push((ref_path, ptr.to_string()));
         }
     }
+
+    let mut refs = Refs(Vec::new());
+    walk_spec(ctx.spec, &mut refs);
+    refs.0
+}
+
+/// The `/components/{kind}/{name}` prefix of `ptr`, if it points into one.
+fn component_of(ptr: &str) -> Option<&str> {
+    let rest = ptr.strip_prefix("/components/")?;
+    let mut segments = rest.splitn(3, '/');
+    let kind = segments.next()?;
+    let name = segments.next()?;
+    Some(&ptr[.."/components/".len() + kind.len() + 1 + name.len()])
 }
 
 /// Check if a schema is complex enough to warrant moving to components
@@ -687,4 +771,94 @@ components:
             "Schema 'UnusedSchema' is defined but never referenced"
         );
     }
+
+    #[test]
+    fn test_component_no_unused_reachability_and_dangling_refs() {
+        let yaml = r#"
+openapi: 3.1.0
+info:
+  title: Test API
+  version: 1.0.0
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      parameters:
+        - $ref: '#/components/parameters/Limit'
+      responses:
+        '200':
+          description: Success
+          content:
+            application/json:
+              schema:
+                $ref: '#/components/schemas/Pet'
+components:
+  schemas:
+    Pet:
+      type: object
+      properties:
+        owner:
+          $ref: '#/components/schemas/Owner'
+    Owner:
+      type: object
+    Legacy:
+      type: object
+      properties:
+        pet:
+          $ref: '#/components/schemas/LegacyPet'
+    LegacyPet:
+      type: object
+      properties:
+        tag:
+          $ref: '#/components/schemas/Tag'
+  securitySchemes:
+    apiKey:
+      type: apiKey
+      in: header
+      name: X-Key
+"#;
+        let validation = lint_with_ruleset(
+            yaml,
+            RuleSet::from_slice(&[
+                RuleId::ComponentNoUnused.as_str(),
+                RuleId::ComponentRefDangling.as_str(),
+            ]),
+        )
+        .unwrap();
+        let found: Vec<(&str, &str, &str)> = validation
+            .diagnostics
+            .iter()
+            .map(|d| (d.rule.as_str(), d.pointer.as_str(), d.message.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (
+                    "component-ref-dangling",
+                    "/paths/~1pets/get/parameters/0",
+                    "$ref '#/components/parameters/Limit' at /paths/~1pets/get/parameters/0 points at nothing: /components/parameters/Limit does not exist"
+                ),
+                (
+                    "component-no-unused",
+                    "/components/schemas/Legacy",
+                    "Schema 'Legacy' is defined but never referenced"
+                ),
+                (
+                    "component-no-unused",
+                    "/components/schemas/LegacyPet",
+                    "Schema 'LegacyPet' is only referenced from components no path uses: /components/schemas/Legacy"
+                ),
+                (
+                    "component-ref-dangling",
+                    "/components/schemas/LegacyPet/properties/tag",
+                    "$ref '#/components/schemas/Tag' at /components/schemas/LegacyPet/properties/tag points at nothing: /components/schemas/Tag does not exist"
+                ),
+                (
+                    "component-no-unused",
+                    "/components/securitySchemes/apiKey",
+                    "Security scheme 'apiKey' is defined but never used by a security requirement"
+                ),
+            ]
+        );
+    }
 }
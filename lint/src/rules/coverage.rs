@@ -0,0 +1,106 @@
+use crate::{
+    coverage::DocsCoverage,
+    lint::{Finding, LintCtx, RuleId},
+};
+
+/// Operations, component schemas and fields meet the configured
+/// description and example coverage
+pub fn docs_coverage(ctx: &LintCtx, out: &mut Vec<Finding>) {
+    let thresholds = ctx.coverage();
+    let coverage = DocsCoverage::collect(ctx.spec);
+    for (kind, count) in coverage.kinds() {
+        if count.total == 0 {
+            continue;
+        }
+        let ptr = match kind {
+            "operations" => "/paths",
+            "schemas" => "/components/schemas",
+            _ => "",
+        };
+        let checks = [
+            (
+                "a description",
+                count.described,
+                count.description_percent(),
+                thresholds.descriptions,
+            ),
+            (
+                "an example",
+                count.with_examples,
+                count.example_percent(),
+                thresholds.examples,
+            ),
+        ];
+        for (what, covered, percent, minimum) in checks {
+            if percent < f64::from(minimum) {
+                out.push(Finding::new(
+                    RuleId::DocsCoverage,
+                    ptr,
+                    format!(
+                        "{} of {} {} ({:.0}%) have {}, below the {}% minimum",
+                        covered, count.total, kind, percent, what, minimum
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::CoverageThresholds;
+    use crate::lint::RuleId;
+    use crate::{Linter, RuleSet};
+
+    const SPEC: &str = r#"
+openapi: 3.1.0
+info: { title: Pets, version: "1" }
+paths:
+  /pets:
+    get:
+      summary: List pets
+      responses:
+        "200":
+          description: OK
+    post:
+      responses:
+        "201":
+          description: Created
+components:
+  schemas:
+    Pet:
+      description: A pet
+      type: object
+      properties:
+        name:
+          type: string
+          description: The pet's name
+"#;
+
+    #[test]
+    fn test_docs_coverage() {
+        let linter = Linter::new(RuleSet::from_slice(&[RuleId::DocsCoverage.as_str()]));
+        let messages: Vec<String> = linter
+            .lint(SPEC)
+            .unwrap()
+            .diagnostics
+            .into_iter()
+            .map(|d| d.message)
+            .collect();
+        assert_eq!(
+            messages,
+            ["1 of 2 operations (50%) have a description, below the 80% minimum"]
+        );
+
+        let linter = linter.with_coverage(CoverageThresholds {
+            descriptions: 50,
+            examples: 100,
+        });
+        let result = linter.lint(SPEC).unwrap();
+        assert_eq!(result.diagnostics.len(), 3);
+        assert!(result.diagnostics.iter().all(|d| {
+            d.message
+                .contains("have an example, below the 100% minimum")
+        }));
+    }
+}
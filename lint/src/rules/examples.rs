@@ -0,0 +1,488 @@
+use oas3::spec::{Header, ObjectSchema, Parameter, RequestBody, Response};
+use serde_json::{Map, Value};
+
+use crate::{
+    lint::{Finding, LintCtx, RuleId},
+    model::{NodeCtx, SpecVisitor, escape_pointer_segment, walk_spec},
+};
+
+/// `$ref`s followed per example before giving up, so cycles terminate
+const MAX_REF_DEPTH: usize = 32;
+
+/// `example` and `examples` values must match their schema's types,
+/// required properties and enums
+pub fn example_matches_schema(ctx: &LintCtx, out: &mut Vec<Finding>) {
+    let mut holders = Holders::default();
+    walk_spec(ctx.spec, &mut holders);
+
+    let document = ctx.document();
+    for ptr in holders.schemas {
+        let Some(schema) = document.pointer(&ptr) else {
+            continue;
+        };
+        if let Some(example) = schema.get("example") {
+            check(document, schema, example, &format!("{}/example", ptr), out);
+        }
+        if let Some(Value::Array(examples)) = schema.get("examples") {
+            for (i, example) in examples.iter().enumerate() {
+                check(
+                    document,
+                    schema,
+                    example,
+                    &format!("{}/examples/{}", ptr, i),
+                    out,
+                );
+            }
+        }
+    }
+    for ptr in holders.media {
+        let Some(Value::Object(node)) = document.pointer(&ptr) else {
+            continue;
+        };
+        check_media(document, node, &ptr, out);
+        // Parameters and headers may describe themselves with `content`
+        if let Some(Value::Object(content)) = node.get("content") {
+            for (media_type, media) in content {
+                if let Value::Object(media) = media {
+                    let media_ptr =
+                        format!("{}/content/{}", ptr, escape_pointer_segment(media_type));
+                    check_media(document, media, &media_ptr, out);
+                }
+            }
+        }
+    }
+}
+
+/// Pointers to the nodes that can carry examples.
+#[derive(Default)]
+struct Holders {
+    /// Schemas, with `example` and a JSON Schema `examples` array
+    schemas: Vec<String>,
+    /// Parameters, headers, request bodies and responses, with a `schema`
+    /// and `example` or an `examples` map of Example Objects
+    media: Vec<String>,
+}
+
+impl<'s> SpecVisitor<'s> for Holders {
+    fn visit_parameter(&mut self, _param: &'s Parameter, _ctx: &NodeCtx<'s>, ptr: &str) {
+        self.media.push(ptr.to_string());
+    }
+
+    fn visit_request_body(&mut self, _body: &'s RequestBody, _ctx: &NodeCtx<'s>, ptr: &str) {
+        self.media.push(ptr.to_string());
+    }
+
+    fn visit_response(
+        &mut self,
+        _status: &'s str,
+        _response: &'s Response,
+        _ctx: &NodeCtx<'s>,
+        ptr: &str,
+    ) {
+        self.media.push(ptr.to_string());
+    }
+
+    fn visit_header(&mut self, _name: &'s str, _header: &'s Header, _ctx: &NodeCtx<'s>, ptr: &str) {
+        self.media.push(ptr.to_string());
+    }
+
+    fn visit_schema(&mut self, _schema: &'s ObjectSchema, _ctx: &NodeCtx<'s>, ptr: &str) {
+        self.schemas.push(ptr.to_string());
+    }
+}
+
+/// Check the `example` or `examples` of a node holding a `schema`.
+fn check_media(document: &Value, node: &Map<String, Value>, ptr: &str, out: &mut Vec<Finding>) {
+    let Some(schema) = node.get("schema") else {
+        return;
+    };
+    if let Some(example) = node.get("example") {
+        check(document, schema, example, &format!("{}/example", ptr), out);
+    }
+    let Some(Value::Object(examples)) = node.get("examples") else {
+        return;
+    };
+    for (name, example) in examples {
+        let example_ptr = format!("{}/examples/{}", ptr, escape_pointer_segment(name));
+        // Examples shared through components are reported where they're declared
+        let (example, example_ptr) = match example.get("$ref").and_then(Value::as_str) {
+            Some(ref_path) => match ref_path
+                .strip_prefix('#')
+                .and_then(|target| Some((document.pointer(target)?, target.to_string())))
+            {
+                Some(resolved) => resolved,
+                None => continue,
+            },
+            None => (example, example_ptr),
+        };
+        if let Some(value) = example.get("value") {
+            check(
+                document,
+                schema,
+                value,
+                &format!("{}/value", example_ptr),
+                out,
+            );
+        }
+    }
+}
+
+/// Report each way `example` at `ptr` fails to match `schema`.
+fn check(document: &Value, schema: &Value, example: &Value, ptr: &str, out: &mut Vec<Finding>) {
+    let mut mismatches = Vec::new();
+    validate(document, schema, example, "", 0, &mut mismatches);
+    for (path, message) in mismatches {
+        let message = if path.is_empty() {
+            format!("Example does not match its schema: {}", message)
+        } else {
+            format!("Example does not match its schema at {}: {}", path, message)
+        };
+        out.push(Finding::new(
+            RuleId::ExampleMatchesSchema,
+            format!("{}{}", ptr, path),
+            message,
+        ));
+    }
+}
+
+/// Collect `(pointer within the value, message)` for each mismatch. Only
+/// types, required properties, enums and `const` are checked, through
+/// properties, items, `$ref`s and compositions; other keywords pass. A
+/// `oneOf` must match exactly one schema, or the one its discriminator
+/// selects.
+fn validate(
+    document: &Value,
+    schema: &Value,
+    value: &Value,
+    path: &str,
+    depth: usize,
+    out: &mut Vec<(String, String)>,
+) {
+    let Value::Object(schema) = schema else {
+        // `true`, `false` and malformed schemas aren't checked
+        return;
+    };
+
+    if let Some(ref_path) = schema.get("$ref").and_then(Value::as_str) {
+        if depth < MAX_REF_DEPTH
+            && let Some(target) = ref_path
+                .strip_prefix('#')
+                .and_then(|target| document.pointer(target))
+        {
+            validate(document, target, value, path, depth + 1, out);
+        }
+        return;
+    }
+
+    if let Some(types) = schema.get("type") {
+        let nullable = schema.get("nullable") == Some(&Value::Bool(true));
+        let allowed: Vec<&str> = match types {
+            Value::String(ty) => vec![ty.as_str()],
+            Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        let matches = allowed.iter().any(|ty| has_type(value, ty)) || (nullable && value.is_null());
+        if !allowed.is_empty() && !matches {
+            out.push((
+                path.to_string(),
+                format!(
+                    "expected {}, found {}",
+                    allowed.join(" or "),
+                    type_name(value)
+                ),
+            ));
+            // Nested keywords would only repeat the mismatch
+            return;
+        }
+    }
+
+    if let Some(Value::Array(values)) = schema.get("enum")
+        && !values.contains(value)
+    {
+        out.push((
+            path.to_string(),
+            format!("{} is not one of the enum values", value),
+        ));
+    }
+    if let Some(expected) = schema.get("const")
+        && expected != value
+    {
+        out.push((path.to_string(), format!("{} is not {}", value, expected)));
+    }
+
+    if let Value::Object(object) = value {
+        if let Some(Value::Array(required)) = schema.get("required") {
+            for name in required.iter().filter_map(Value::as_str) {
+                if !object.contains_key(name) {
+                    out.push((
+                        path.to_string(),
+                        format!("missing required property '{}'", name),
+                    ));
+                }
+            }
+        }
+        if let Some(Value::Object(properties)) = schema.get("properties") {
+            for (name, property) in properties {
+                if let Some(field) = object.get(name) {
+                    let field_path = format!("{}/{}", path, escape_pointer_segment(name));
+                    validate(document, property, field, &field_path, depth, out);
+                }
+            }
+        }
+    }
+
+    if let (Value::Array(items), Some(item_schema)) = (value, schema.get("items")) {
+        for (i, item) in items.iter().enumerate() {
+            validate(
+                document,
+                item_schema,
+                item,
+                &format!("{}/{}", path, i),
+                depth,
+                out,
+            );
+        }
+    }
+
+    if let Some(Value::Array(all_of)) = schema.get("allOf") {
+        for part in all_of {
+            validate(document, part, value, path, depth, out);
+        }
+    }
+    let matches = |variant: &Value| {
+        let mut mismatches = Vec::new();
+        validate(document, variant, value, path, depth, &mut mismatches);
+        mismatches.is_empty()
+    };
+    if let Some(Value::Array(variants)) = schema.get("anyOf")
+        && !variants.is_empty()
+        && !variants.iter().any(&matches)
+    {
+        out.push((
+            path.to_string(),
+            "matches none of the anyOf schemas".to_string(),
+        ));
+    }
+    if let Some(Value::Array(variants)) = schema.get("oneOf")
+        && !variants.is_empty()
+    {
+        // The discriminator picks the one schema the value has to match
+        if let Some(variant) = discriminated(schema, variants, value) {
+            validate(document, variant, value, path, depth, out);
+            return;
+        }
+        match variants.iter().filter(|variant| matches(variant)).count() {
+            1 => {}
+            0 => out.push((
+                path.to_string(),
+                "matches none of the oneOf schemas".to_string(),
+            )),
+            n => out.push((
+                path.to_string(),
+                format!("matches {} of the oneOf schemas, expected exactly one", n),
+            )),
+        }
+    }
+}
+
+/// The `oneOf` variant the schema's discriminator selects for `value`: the
+/// `mapping` entry for its discriminator property, or the `$ref` named
+/// after it.
+fn discriminated<'v>(
+    schema: &Map<String, Value>,
+    variants: &'v [Value],
+    value: &Value,
+) -> Option<&'v Value> {
+    let discriminator = schema.get("discriminator")?;
+    let property = discriminator.get("propertyName")?.as_str()?;
+    let tag = value.get(property)?.as_str()?;
+    let target = discriminator
+        .get("mapping")
+        .and_then(|mapping| mapping.get(tag))
+        .and_then(Value::as_str);
+    variants.iter().find(|variant| {
+        let Some(ref_path) = variant.get("$ref").and_then(Value::as_str) else {
+            return false;
+        };
+        match target {
+            Some(target) => ref_path == target,
+            None => ref_path.rsplit('/').next() == Some(tag),
+        }
+    })
+}
+
+fn has_type(value: &Value, ty: &str) -> bool {
+    match ty {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => {
+            value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|n| n.fract() == 0.0)
+        }
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        // Unknown types are left to schema-type-required and friends
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lint::RuleId;
+    use crate::{Linter, RuleSet};
+
+    const SPEC: &str = r#"
+openapi: 3.1.0
+info: { title: Pets, version: "1" }
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      parameters:
+        - name: limit
+          in: query
+          schema: { type: integer }
+          example: ten
+      responses:
+        "200":
+          description: OK
+          content:
+            application/json:
+              schema:
+                type: array
+                items: { $ref: '#/components/schemas/Pet' }
+              examples:
+                rex:
+                  value: [{ name: Rex, status: sold }, { status: available }]
+                good:
+                  $ref: '#/components/examples/Good'
+components:
+  examples:
+    Good:
+      value: [{ name: Rex }]
+  schemas:
+    Pet:
+      type: object
+      required: [name]
+      properties:
+        name: { type: string, example: Rex }
+        status:
+          type: string
+          enum: [available, pending]
+        age:
+          type: integer
+          example: 2.5
+"#;
+
+    #[test]
+    fn test_example_matches_schema() {
+        let result = Linter::new(RuleSet::from_slice(
+            &[RuleId::ExampleMatchesSchema.as_str()],
+        ))
+        .lint(SPEC)
+        .unwrap();
+        let found: Vec<(&str, &str)> = result
+            .diagnostics
+            .iter()
+            .map(|d| (d.pointer.as_str(), d.message.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (
+                    "/paths/~1pets/get/parameters/0/example",
+                    "Example does not match its schema: expected integer, found string"
+                ),
+                (
+                    "/paths/~1pets/get/responses/200/content/application~1json/examples/rex/value/0/status",
+                    "Example does not match its schema at /0/status: \"sold\" is not one of the enum values"
+                ),
+                (
+                    "/paths/~1pets/get/responses/200/content/application~1json/examples/rex/value/1",
+                    "Example does not match its schema at /1: missing required property 'name'"
+                ),
+                (
+                    "/components/schemas/Pet/properties/age/example",
+                    "Example does not match its schema: expected integer, found number"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_example_one_of_matches_exactly_one() {
+        let spec = r#"
+openapi: 3.1.0
+info: { title: Pets, version: "1" }
+paths: {}
+components:
+  schemas:
+    Id:
+      oneOf:
+        - type: integer
+        - type: number
+      examples: [1, 2.5, "x"]
+    Cat:
+      type: object
+      required: [kind]
+      properties:
+        kind: { type: string }
+        indoor: { type: boolean }
+    Dog:
+      type: object
+      required: [kind]
+      properties:
+        kind: { type: string }
+        barks: { type: boolean }
+    Pet:
+      oneOf:
+        - $ref: '#/components/schemas/Cat'
+        - $ref: '#/components/schemas/Dog'
+      discriminator:
+        propertyName: kind
+      examples:
+        - { kind: Cat, indoor: true }
+        - { kind: Dog, barks: "loud" }
+"#;
+        let result = Linter::new(RuleSet::from_slice(
+            &[RuleId::ExampleMatchesSchema.as_str()],
+        ))
+        .lint(spec)
+        .unwrap();
+        let found: Vec<(&str, &str)> = result
+            .diagnostics
+            .iter()
+            .map(|d| (d.pointer.as_str(), d.message.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (
+                    "/components/schemas/Id/examples/0",
+                    "Example does not match its schema: matches 2 of the oneOf schemas, expected exactly one"
+                ),
+                (
+                    "/components/schemas/Id/examples/2",
+                    "Example does not match its schema: matches none of the oneOf schemas"
+                ),
+                (
+                    "/components/schemas/Pet/examples/1/barks",
+                    "Example does not match its schema at /barks: expected boolean, found string"
+                ),
+            ]
+        );
+    }
+}
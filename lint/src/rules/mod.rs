@@ -1,4 +1,7 @@
+mod compat;
 mod components;
+mod coverage;
+mod examples;
 mod info;
 mod operation_id;
 mod operations;
@@ -13,7 +16,10 @@ mod servers;
 mod tags;
 mod version;
 
+pub use compat::*;
 pub use components::*;
+pub use coverage::*;
+pub use examples::*;
 pub use info::*;
 pub use operation_id::*;
 pub use operations::*;
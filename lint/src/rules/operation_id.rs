@@ -1,23 +1,67 @@
+use std::collections::HashSet;
+
+use serde_json::Value;
+
 use crate::{
-    lint::{Finding, LintCtx, RuleId},
+    lint::{Finding, Fix, LintCtx, PatchOp, RuleId},
     model::escape_pointer_segment,
 };
 
 /// operationId must exist for all operations
 pub fn operation_id_required(ctx: &LintCtx, out: &mut Vec<Finding>) {
+    let mut taken: HashSet<String> = ctx.indexes.operation_ids.keys().cloned().collect();
     for op in &ctx.indexes.ops {
         if op.operation_id.is_none() {
-            out.push(Finding::new(
-                RuleId::OperationIdRequired,
-                format!("{}/operationId", op.ptr_base),
-                format!(
-                    "Operation {} {} is missing operationId",
-                    op.method.to_uppercase(),
-                    op.path
-                ),
-            ));
+            let ptr = format!("{}/operationId", op.ptr_base);
+            let operation_id = unique_operation_id(op.method, &op.path, &mut taken);
+            out.push(
+                Finding::new(
+                    RuleId::OperationIdRequired,
+                    ptr.clone(),
+                    format!(
+                        "Operation {} {} is missing operationId",
+                        op.method.to_uppercase(),
+                        op.path
+                    ),
+                )
+                .with_autofix(Fix::new(
+                    format!("Set operationId to '{}'", operation_id),
+                    vec![PatchOp::Add {
+                        path: ptr,
+                        value: Value::String(operation_id),
+                    }],
+                )),
+            );
+        }
+    }
+}
+
+/// `getPetsByPetId` for `GET /pets/{petId}`, numbered if already taken.
+fn unique_operation_id(method: &str, path: &str, taken: &mut HashSet<String>) -> String {
+    let mut base = method.to_string();
+    for segment in path.split('/').filter(|s| !s.is_empty()) {
+        let (prefix, name) = match segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+            Some(param) => ("By", param),
+            None => ("", segment),
+        };
+        base.push_str(prefix);
+        for word in name.split(|c: char| !c.is_ascii_alphanumeric()) {
+            let mut chars = word.chars();
+            if let Some(first) = chars.next() {
+                base.push(first.to_ascii_uppercase());
+                base.push_str(chars.as_str());
+            }
         }
     }
+
+    let mut operation_id = base.clone();
+    let mut n = 2;
+    while taken.contains(&operation_id) {
+        operation_id = format!("{}{}", base, n);
+        n += 1;
+    }
+    taken.insert(operation_id.clone());
+    operation_id
 }
 
 /// operationId must be unique across all operations
@@ -51,6 +95,7 @@ pub fn operation_id_unique(ctx: &LintCtx, out: &mut Vec<Finding>) {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
     use crate::lint::RuleId;
     use crate::testutil::yaml_to_json;
     use crate::{RuleSet, lint_with_ruleset};
@@ -124,6 +169,13 @@ paths:
             validation.diagnostics[0].message,
             "Operation POST /pets is missing operationId"
         );
+        assert_eq!(
+            validation.diagnostics[0].autofix.as_ref().unwrap().ops,
+            [crate::PatchOp::Add {
+                path: "/paths/~1pets/post/operationId".to_string(),
+                value: "postPets".into(),
+            }]
+        );
 
         // Should also fail with JSON input
         let json = yaml_to_json(yaml);
@@ -139,6 +191,18 @@ paths:
         );
     }
 
+    #[test]
+    fn test_generated_operation_ids() {
+        let mut taken = HashSet::from(["getPets".to_string()]);
+        assert_eq!(
+            unique_operation_id("get", "/pets/{pet_id}/vaccine-records", &mut taken),
+            "getPetsByPetIdVaccineRecords"
+        );
+        assert_eq!(unique_operation_id("get", "/pets", &mut taken), "getPets2");
+        assert_eq!(unique_operation_id("get", "/pets", &mut taken), "getPets3");
+        assert_eq!(unique_operation_id("get", "/", &mut taken), "get");
+    }
+
     #[test]
     fn test_unique_operation_ids() {
         let yaml = r#"
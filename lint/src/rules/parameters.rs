@@ -1,39 +1,16 @@
-use oas3::spec::{ObjectOrReference, ParameterIn};
+use oas3::spec::{ObjectOrReference, Parameter, ParameterIn};
 
 use crate::{
     lint::{Finding, LintCtx, RuleId},
-    model::escape_pointer_segment,
+    model::{NodeCtx, SpecVisitor, walk_spec},
 };
 
 /// Parameter names should be consistent case (camelCase for query, kebab-case for headers)
 pub fn param_naming_consistent(ctx: &LintCtx, out: &mut Vec<Finding>) {
-    let Some(paths) = &ctx.spec.paths else {
-        return;
-    };
-
-    for (path, path_item) in paths {
-        let path_ptr = format!("/paths/{}", escape_pointer_segment(path));
-
-        // Check path-level parameters
-        for (i, param) in path_item.parameters.iter().enumerate() {
-            if let ObjectOrReference::Object(p) = param {
-                check_param_naming(p, &format!("{}/parameters/{}", path_ptr, i), out);
-            }
-        }
-
-        // Check operation-level parameters
-        for (method, operation) in iter_operations(path_item) {
-            let op_ptr = format!("{}/{}", path_ptr, method);
-            for (i, param) in operation.parameters.iter().enumerate() {
-                if let ObjectOrReference::Object(p) = param {
-                    check_param_naming(p, &format!("{}/parameters/{}", op_ptr, i), out);
-                }
-            }
-        }
-    }
+    for_each_path_parameter(ctx, |param, ptr| check_param_naming(param, ptr, out));
 }
 
-fn check_param_naming(param: &oas3::spec::Parameter, ptr: &str, out: &mut Vec<Finding>) {
+fn check_param_naming(param: &Parameter, ptr: &str, out: &mut Vec<Finding>) {
     let name = &param.name;
 
     match param.location {
@@ -71,77 +48,23 @@ fn check_param_naming(param: &oas3::spec::Parameter, ptr: &str, out: &mut Vec<Fi
 
 /// Query parameters must declare schema and type
 pub fn param_schema_required(ctx: &LintCtx, out: &mut Vec<Finding>) {
-    let Some(paths) = &ctx.spec.paths else {
-        return;
-    };
-
-    for (path, path_item) in paths {
-        let path_ptr = format!("/paths/{}", escape_pointer_segment(path));
-
-        // Check path-level parameters
-        for (i, param) in path_item.parameters.iter().enumerate() {
-            if let ObjectOrReference::Object(p) = param
-                && p.location == ParameterIn::Query
-                && p.schema.is_none()
-                && p.content.is_none()
-            {
-                out.push(Finding::new(
-                    RuleId::ParamSchemaRequired,
-                    format!("{}/parameters/{}", path_ptr, i),
-                    format!("Query parameter '{}' must declare a schema", p.name),
-                ));
-            }
-        }
-
-        // Check operation-level parameters
-        for (method, operation) in iter_operations(path_item) {
-            let op_ptr = format!("{}/{}", path_ptr, method);
-            for (i, param) in operation.parameters.iter().enumerate() {
-                if let ObjectOrReference::Object(p) = param
-                    && p.location == ParameterIn::Query
-                    && p.schema.is_none()
-                    && p.content.is_none()
-                {
-                    out.push(Finding::new(
-                        RuleId::ParamSchemaRequired,
-                        format!("{}/parameters/{}", op_ptr, i),
-                        format!("Query parameter '{}' must declare a schema", p.name),
-                    ));
-                }
-            }
+    for_each_path_parameter(ctx, |p, ptr| {
+        if p.location == ParameterIn::Query && p.schema.is_none() && p.content.is_none() {
+            out.push(Finding::new(
+                RuleId::ParamSchemaRequired,
+                ptr,
+                format!("Query parameter '{}' must declare a schema", p.name),
+            ));
         }
-    }
+    });
 }
 
 /// Enum parameters should have documented values
 pub fn param_enum_documented(ctx: &LintCtx, out: &mut Vec<Finding>) {
-    let Some(paths) = &ctx.spec.paths else {
-        return;
-    };
-
-    for (path, path_item) in paths {
-        let path_ptr = format!("/paths/{}", escape_pointer_segment(path));
-
-        // Check path-level parameters
-        for (i, param) in path_item.parameters.iter().enumerate() {
-            if let ObjectOrReference::Object(p) = param {
-                check_param_enum(p, &format!("{}/parameters/{}", path_ptr, i), out);
-            }
-        }
-
-        // Check operation-level parameters
-        for (method, operation) in iter_operations(path_item) {
-            let op_ptr = format!("{}/{}", path_ptr, method);
-            for (i, param) in operation.parameters.iter().enumerate() {
-                if let ObjectOrReference::Object(p) = param {
-                    check_param_enum(p, &format!("{}/parameters/{}", op_ptr, i), out);
-                }
-            }
-        }
-    }
+    for_each_path_parameter(ctx, |param, ptr| check_param_enum(param, ptr, out));
 }
 
-fn check_param_enum(param: &oas3::spec::Parameter, ptr: &str, out: &mut Vec<Finding>) {
+fn check_param_enum(param: &Parameter, ptr: &str, out: &mut Vec<Finding>) {
     if let Some(ObjectOrReference::Object(schema)) = &param.schema
         && !schema.enum_values.is_empty()
         && param.description.is_none()
@@ -159,33 +82,10 @@ fn check_param_enum(param: &oas3::spec::Parameter, ptr: &str, out: &mut Vec<Find
 
 /// Date/time formats must be explicit (format: date or date-time)
 pub fn param_date_time_format(ctx: &LintCtx, out: &mut Vec<Finding>) {
-    let Some(paths) = &ctx.spec.paths else {
-        return;
-    };
-
-    for (path, path_item) in paths {
-        let path_ptr = format!("/paths/{}", escape_pointer_segment(path));
-
-        // Check path-level parameters
-        for (i, param) in path_item.parameters.iter().enumerate() {
-            if let ObjectOrReference::Object(p) = param {
-                check_param_datetime(p, &format!("{}/parameters/{}", path_ptr, i), out);
-            }
-        }
-
-        // Check operation-level parameters
-        for (method, operation) in iter_operations(path_item) {
-            let op_ptr = format!("{}/{}", path_ptr, method);
-            for (i, param) in operation.parameters.iter().enumerate() {
-                if let ObjectOrReference::Object(p) = param {
-                    check_param_datetime(p, &format!("{}/parameters/{}", op_ptr, i), out);
-                }
-            }
-        }
-    }
+    for_each_path_parameter(ctx, |param, ptr| check_param_datetime(param, ptr, out));
 }
 
-fn check_param_datetime(param: &oas3::spec::Parameter, ptr: &str, out: &mut Vec<Finding>) {
+fn check_param_datetime(param: &Parameter, ptr: &str, out: &mut Vec<Finding>) {
     let name_lower = param.name.to_lowercase();
 
     // Check if name suggests a date/time field
@@ -235,23 +135,36 @@ fn is_standard_header(name: &str) -> bool {
     )
 }
 
-fn iter_operations(
-    path_item: &oas3::spec::PathItem,
-) -> impl Iterator<Item = (&'static str, &oas3::spec::Operation)> {
-    let methods: [(&'static str, Option<&oas3::spec::Operation>); 8] = [
-        ("get", path_item.get.as_ref()),
-        ("put", path_item.put.as_ref()),
-        ("post", path_item.post.as_ref()),
-        ("delete", path_item.delete.as_ref()),
-        ("options", path_item.options.as_ref()),
-        ("head", path_item.head.as_ref()),
-        ("patch", path_item.patch.as_ref()),
-        ("trace", path_item.trace.as_ref()),
-    ];
-
-    methods
-        .into_iter()
-        .filter_map(|(method, op)| op.map(|o| (method, o)))
+/// Parameters should have a description
+pub fn param_description_required(ctx: &LintCtx, out: &mut Vec<Finding>) {
+    for_each_path_parameter(ctx, |param, ptr| {
+        let documented = param
+            .description
+            .as_deref()
+            .is_some_and(|d| !d.trim().is_empty());
+        if !documented {
+            out.push(Finding::new(
+                RuleId::ParamDescriptionRequired,
+                ptr,
+                format!("Parameter '{}' has no description", param.name),
+            ));
+        }
+    });
+}
+
+/// Call `f` with every inline parameter declared on a path item or operation under `/paths`.
+fn for_each_path_parameter<'s>(ctx: &LintCtx<'s>, f: impl FnMut(&'s Parameter, &str)) {
+    struct Params<F>(F);
+
+    impl<'s, F: FnMut(&'s Parameter, &str)> SpecVisitor<'s> for Params<F> {
+        fn visit_parameter(&mut self, param: &'s Parameter, node: &NodeCtx<'s>, ptr: &str) {
+            if node.in_paths() {
+                (self.0)(param, ptr);
+            }
+        }
+    }
+
+    walk_spec(ctx.spec, &mut Params(f));
 }
 
 #[cfg(test)]
@@ -606,4 +519,46 @@ paths:
             "Parameter 'createdAt' appears to be a date/time; use format: date or date-time"
         );
     }
+
+    #[test]
+    fn test_param_description_required() {
+        let yaml = r#"
+openapi: 3.1.0
+info:
+  title: Test API
+  version: 1.0.0
+paths:
+  /pets/{petId}:
+    parameters:
+      - name: petId
+        in: path
+        required: true
+        description: The pet to fetch
+        schema:
+          type: string
+    get:
+      operationId: getPet
+      parameters:
+        - name: fields
+          in: query
+          description: "  "
+          schema:
+            type: string
+      responses:
+        '200':
+          description: Success
+"#;
+        let rules = RuleSet::from_slice(&[RuleId::ParamDescriptionRequired.as_str()]);
+        let validation = lint_with_ruleset(yaml, rules).unwrap();
+
+        assert_eq!(validation.diagnostics.len(), 1);
+        assert_eq!(
+            validation.diagnostics[0].message,
+            "Parameter 'fields' has no description"
+        );
+
+        let json = yaml_to_json(yaml);
+        let validation = lint_with_ruleset(&json, rules).unwrap();
+        assert_eq!(validation.diagnostics.len(), 1);
+    }
 }
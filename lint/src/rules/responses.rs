@@ -1,7 +1,7 @@
 use oas3::spec::ObjectOrReference;
 
 use crate::{
-    lint::{Finding, LintCtx, RuleId, Severity},
+    lint::{Finding, Fix, LintCtx, PatchOp, RuleId, Severity},
     model::escape_pointer_segment,
 };
 
@@ -198,6 +198,78 @@ pub fn response_error_model_consistent(ctx: &LintCtx, out: &mut Vec<Finding>) {
     }
 }
 
+/// Response keys must be status codes, ranges (`2XX`) or `default`
+pub fn response_status_code_valid(ctx: &LintCtx, out: &mut Vec<Finding>) {
+    let Some(paths) = &ctx.spec.paths else {
+        return;
+    };
+
+    for (path, path_item) in paths {
+        let path_ptr = format!("/paths/{}", escape_pointer_segment(path));
+
+        for (method, operation) in iter_operations(path_item) {
+            let Some(responses) = &operation.responses else {
+                continue;
+            };
+            let responses_ptr = format!("{}/{}/responses", path_ptr, method);
+
+            for status_code in responses.keys() {
+                if is_valid_status_code(status_code) {
+                    continue;
+                }
+                let ptr = format!("{}/{}", responses_ptr, escape_pointer_segment(status_code));
+                let mut finding = Finding::new(
+                    RuleId::ResponseStatusCodeValid,
+                    ptr.clone(),
+                    format!(
+                        "Response key '{}' for {} {} is not a status code, range (e.g. 4XX) or 'default'",
+                        status_code,
+                        method.to_uppercase(),
+                        path
+                    ),
+                );
+
+                // Fixable when only case or whitespace is off and the
+                // normalized key isn't already used
+                let normalized = normalize_status_code(status_code);
+                if is_valid_status_code(&normalized) && !responses.contains_key(&normalized) {
+                    finding = finding.with_autofix(Fix::new(
+                        format!("Rename response '{}' to '{}'", status_code, normalized),
+                        vec![PatchOp::Move {
+                            from: ptr,
+                            path: format!(
+                                "{}/{}",
+                                responses_ptr,
+                                escape_pointer_segment(&normalized)
+                            ),
+                        }],
+                    ));
+                }
+                out.push(finding);
+            }
+        }
+    }
+}
+
+fn is_valid_status_code(key: &str) -> bool {
+    match key.as_bytes() {
+        b"default" => true,
+        [b'1'..=b'5', b'X', b'X'] => true,
+        [b'1'..=b'5', tens, units] => tens.is_ascii_digit() && units.is_ascii_digit(),
+        _ => false,
+    }
+}
+
+/// `2xx` -> `2XX`, `Default` -> `default`, surrounding whitespace dropped.
+fn normalize_status_code(key: &str) -> String {
+    let key = key.trim();
+    if key.eq_ignore_ascii_case("default") {
+        "default".to_string()
+    } else {
+        key.to_ascii_uppercase()
+    }
+}
+
 fn iter_operations(
     path_item: &oas3::spec::PathItem,
 ) -> impl Iterator<Item = (&'static str, &oas3::spec::Operation)> {
@@ -408,4 +480,57 @@ paths:
         .unwrap();
         assert!(validation.diagnostics.is_empty());
     }
+
+    #[test]
+    fn test_response_status_code_valid() {
+        let yaml = r#"
+openapi: 3.1.0
+info:
+  title: Test API
+  version: 1.0.0
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      responses:
+        '200':
+          description: OK
+        4xx:
+          description: Client error
+        Default:
+          description: Anything else
+        '600':
+          description: Nope
+"#;
+        let validation = lint_with_ruleset(
+            yaml,
+            RuleSet::from_slice(&[RuleId::ResponseStatusCodeValid.as_str()]),
+        )
+        .unwrap();
+
+        let found: Vec<(&str, Option<&str>)> = validation
+            .diagnostics
+            .iter()
+            .map(|d| {
+                (
+                    d.pointer.as_str(),
+                    d.autofix.as_ref().map(|fix| fix.description.as_str()),
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (
+                    "/paths/~1pets/get/responses/4xx",
+                    Some("Rename response '4xx' to '4XX'")
+                ),
+                (
+                    "/paths/~1pets/get/responses/Default",
+                    Some("Rename response 'Default' to 'default'")
+                ),
+                ("/paths/~1pets/get/responses/600", None),
+            ]
+        );
+    }
 }
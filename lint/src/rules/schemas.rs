@@ -1,7 +1,9 @@
 use oas3::spec::{ObjectOrReference, ObjectSchema};
 use rustc_hash::FxHashSet;
 
+use crate::config::Casing;
 use crate::lint::{Finding, LintCtx, RuleId};
+use crate::model::{NodeCtx, SpecVisitor, escape_pointer_segment, walk_spec};
 
 /// Every schema should have type (unless it's a $ref, oneOf/allOf/anyOf, or boolean schema)
 pub fn schema_type_required(ctx: &LintCtx, out: &mut Vec<Finding>) {
@@ -227,36 +229,99 @@ fn check_read_write_only(schema: &ObjectSchema, ptr: &str, out: &mut Vec<Finding
     }
 }
 
-/// Schema/property names should follow conventions (PascalCase for schemas, camelCase for properties)
+/// Schema/property names should follow conventions (PascalCase for schemas, camelCase or
+/// snake_case for properties). `[naming]` in the lint config changes the casings and opts
+/// into checking literal path segments and string enum values.
 pub fn schema_naming_conventions(ctx: &LintCtx, out: &mut Vec<Finding>) {
+    let naming = ctx.naming();
+
     if let Some(components) = &ctx.spec.components {
-        for (name, schema) in &components.schemas {
-            // Check schema name is PascalCase
-            if !is_pascal_case(name) {
+        for name in components.schemas.keys() {
+            if !naming.schemas.matches(name) {
                 out.push(Finding::new(
                     RuleId::SchemaNamingConventions,
-                    format!("/components/schemas/{}", name),
-                    format!("Schema name '{}' should be PascalCase", name),
+                    format!("/components/schemas/{}", escape_pointer_segment(name)),
+                    format!(
+                        "Schema name '{}' should be {}",
+                        name,
+                        naming.schemas.as_str()
+                    ),
                 ));
             }
+        }
+    }
 
-            // Check property names
-            if let ObjectOrReference::Object(s) = schema {
-                for prop_name in s.properties.keys() {
-                    if !is_camel_case(prop_name) && !is_snake_case(prop_name) {
-                        out.push(Finding::new(
-                            RuleId::SchemaNamingConventions,
-                            format!("/components/schemas/{}/properties/{}", name, prop_name),
-                            format!(
-                                "Property name '{}' should be camelCase or snake_case",
-                                prop_name
-                            ),
-                        ));
-                    }
+    for_each_schema(ctx, |schema, ptr| {
+        for prop_name in schema.properties.keys() {
+            let (ok, expected) = match naming.properties {
+                Some(casing) => (casing.matches(prop_name), casing.as_str()),
+                None => (
+                    is_camel_case(prop_name) || is_snake_case(prop_name),
+                    "camelCase or snake_case",
+                ),
+            };
+            if !ok {
+                out.push(Finding::new(
+                    RuleId::SchemaNamingConventions,
+                    format!("{}/properties/{}", ptr, escape_pointer_segment(prop_name)),
+                    format!("Property name '{}' should be {}", prop_name, expected),
+                ));
+            }
+        }
+
+        if let Some(casing) = naming.enums {
+            for (i, value) in schema.enum_values.iter().enumerate() {
+                if let Some(value) = value.as_str()
+                    && !casing.matches(value)
+                {
+                    out.push(Finding::new(
+                        RuleId::SchemaNamingConventions,
+                        format!("{}/enum/{}", ptr, i),
+                        format!("Enum value '{}' should be {}", value, casing.as_str()),
+                    ));
                 }
             }
         }
+    });
+
+    if let (Some(casing), Some(paths)) = (naming.paths, &ctx.spec.paths) {
+        for path in paths.keys() {
+            if let Some(segment) = offending_path_segment(path, casing) {
+                out.push(Finding::new(
+                    RuleId::SchemaNamingConventions,
+                    format!("/paths/{}", escape_pointer_segment(path)),
+                    format!(
+                        "Path {} segment '{}' should be {}",
+                        path,
+                        segment,
+                        casing.as_str()
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+/// First literal segment of `path` not in `casing`. Templated segments
+/// (`{petId}`) and file extensions (`openapi.json`) are skipped.
+fn offending_path_segment(path: &str, casing: Casing) -> Option<&str> {
+    path.split('/')
+        .filter(|segment| !segment.is_empty() && !segment.contains('{'))
+        .map(|segment| segment.split('.').next().unwrap_or(segment))
+        .find(|segment| !casing.matches(segment))
+}
+
+/// Call `f` with every schema in the document and its pointer.
+fn for_each_schema<'s>(ctx: &LintCtx<'s>, f: impl FnMut(&'s ObjectSchema, &str)) {
+    struct Schemas<F>(F);
+
+    impl<'s, F: FnMut(&'s ObjectSchema, &str)> SpecVisitor<'s> for Schemas<F> {
+        fn visit_schema(&mut self, schema: &'s ObjectSchema, _ctx: &NodeCtx<'s>, ptr: &str) {
+            (self.0)(schema, ptr);
+        }
     }
+
+    walk_spec(ctx.spec, &mut Schemas(f));
 }
 
 /// No "any" schemas (empty {} or just additionalProperties: true)
@@ -292,14 +357,6 @@ fn check_any_schema(schema: &ObjectSchema, ptr: &str, out: &mut Vec<Finding>) {
     }
 }
 
-fn is_pascal_case(s: &str) -> bool {
-    if s.is_empty() {
-        return false;
-    }
-    let first = s.chars().next().unwrap();
-    first.is_uppercase() && !s.contains('_') && !s.contains('-')
-}
-
 fn is_camel_case(s: &str) -> bool {
     if s.is_empty() {
         return false;
@@ -318,9 +375,10 @@ fn is_snake_case(s: &str) -> bool {
 
 #[cfg(test)]
 mod tests {
+    use crate::config::{Casing, NamingConventions};
     use crate::lint::RuleId;
     use crate::testutil::yaml_to_json;
-    use crate::{RuleSet, lint_with_ruleset};
+    use crate::{Linter, RuleSet, lint_with_ruleset};
 
     #[test]
     fn test_schema_type_required_present() {
@@ -551,6 +609,80 @@ components:
         );
     }
 
+    #[test]
+    fn test_schema_naming_conventions_configured() {
+        let yaml = r#"
+openapi: 3.1.0
+info:
+  title: Test API
+  version: 1.0.0
+paths:
+  /vaccineRecords/{petId}:
+    get:
+      operationId: getRecords
+      responses:
+        '200':
+          description: OK
+  /openapi.json:
+    get:
+      operationId: getSpec
+      responses:
+        '200':
+          description: OK
+components:
+  schemas:
+    PetRecord:
+      type: object
+      properties:
+        petId:
+          type: string
+        owner_name:
+          type: string
+        status:
+          type: string
+          enum: [in_stock, SoldOut]
+"#;
+        let linter = Linter::new(RuleSet::from_slice(&[
+            RuleId::SchemaNamingConventions.as_str()
+        ]));
+        // Paths and enum values are only checked when configured
+        assert!(linter.lint(yaml).unwrap().diagnostics.is_empty());
+
+        let linter = linter.with_naming(NamingConventions {
+            properties: Some(Casing::Camel),
+            paths: Some(Casing::Kebab),
+            enums: Some(Casing::ScreamingSnake),
+            ..NamingConventions::default()
+        });
+        let result = linter.lint(yaml).unwrap();
+        let found: Vec<(&str, &str)> = result
+            .diagnostics
+            .iter()
+            .map(|d| (d.pointer.as_str(), d.message.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (
+                    "/paths/~1vaccineRecords~1{petId}",
+                    "Path /vaccineRecords/{petId} segment 'vaccineRecords' should be kebab-case"
+                ),
+                (
+                    "/components/schemas/PetRecord/properties/owner_name",
+                    "Property name 'owner_name' should be camelCase"
+                ),
+                (
+                    "/components/schemas/PetRecord/properties/status/enum/0",
+                    "Enum value 'in_stock' should be SCREAMING_SNAKE_CASE"
+                ),
+                (
+                    "/components/schemas/PetRecord/properties/status/enum/1",
+                    "Enum value 'SoldOut' should be SCREAMING_SNAKE_CASE"
+                ),
+            ]
+        );
+    }
+
     #[test]
     fn test_schema_object_properties_required_good() {
         let yaml = r#"
@@ -16,17 +16,34 @@ pub fn security_schemes_defined(ctx: &LintCtx, out: &mut Vec<Finding>) {
         .map(|c| c.security_schemes.keys().cloned().collect())
         .unwrap_or_default();
 
+    // Report undefined schemes
+    for scheme_name in referenced_schemes(ctx.spec) {
+        if !defined_schemes.contains(&scheme_name) {
+            out.push(Finding::new(
+                RuleId::SecuritySchemesDefined,
+                "/security",
+                format!(
+                    "Security scheme '{}' is referenced but not defined in components/securitySchemes",
+                    scheme_name
+                ),
+            ));
+        }
+    }
+}
+
+/// Names of every scheme used by a global or operation security requirement
+pub(super) fn referenced_schemes(spec: &oas3::Spec) -> FxHashSet<String> {
     let mut referenced_schemes: FxHashSet<String> = FxHashSet::default();
 
     // Check global security requirements
-    for requirement in &ctx.spec.security {
+    for requirement in &spec.security {
         for (scheme_name, _) in requirement.0.iter() {
             referenced_schemes.insert(scheme_name.clone());
         }
     }
 
     // Check operation-level security requirements
-    if let Some(paths) = &ctx.spec.paths {
+    if let Some(paths) = &spec.paths {
         for path_item in paths.values() {
             for (_, operation) in iter_operations(path_item) {
                 for requirement in &operation.security {
@@ -38,19 +55,7 @@ pub fn security_schemes_defined(ctx: &LintCtx, out: &mut Vec<Finding>) {
         }
     }
 
-    // Report undefined schemes
-    for scheme_name in referenced_schemes {
-        if !defined_schemes.contains(&scheme_name) {
-            out.push(Finding::new(
-                RuleId::SecuritySchemesDefined,
-                "/security",
-                format!(
-                    "Security scheme '{}' is referenced but not defined in components/securitySchemes",
-                    scheme_name
-                ),
-            ));
-        }
-    }
+    referenced_schemes
 }
 
 /// Every operation should declare security (or explicitly opt out with empty array)
@@ -220,7 +225,7 @@ fn iter_operations(
 
 #[cfg(test)]
 mod tests {
-    use crate::lint::RuleId;
+    use crate::lint::{RuleId, Severity};
     use crate::testutil::yaml_to_json;
     use crate::{RuleSet, lint_with_ruleset};
 
@@ -784,4 +789,51 @@ components:
             "OAuth2 security scheme 'oauth2' must define at least one flow"
         );
     }
+
+    #[test]
+    fn test_security_schemes_unused() {
+        let yaml = r#"
+openapi: 3.1.0
+info:
+  title: Test API
+  version: 1.0.0
+security:
+  - bearer: []
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      security:
+        - apiKey: []
+      responses:
+        '200':
+          description: Success
+components:
+  securitySchemes:
+    bearer:
+      type: http
+      scheme: bearer
+    apiKey:
+      type: apiKey
+      in: header
+      name: X-API-Key
+    legacy:
+      type: http
+      scheme: basic
+"#;
+        let rules = RuleSet::from_slice(&[RuleId::ComponentNoUnused.as_str()]);
+        let validation = lint_with_ruleset(yaml, rules).unwrap();
+
+        assert_eq!(validation.diagnostics.len(), 1);
+        assert_eq!(validation.diagnostics[0].rule, RuleId::ComponentNoUnused);
+        assert_eq!(validation.diagnostics[0].severity, Severity::Warning);
+        assert_eq!(
+            validation.diagnostics[0].pointer,
+            "/components/securitySchemes/legacy"
+        );
+
+        let json = yaml_to_json(yaml);
+        let validation = lint_with_ruleset(&json, rules).unwrap();
+        assert_eq!(validation.diagnostics.len(), 1);
+    }
 }
@@ -1,4 +1,6 @@
-use crate::lint::{Finding, LintCtx, RuleId};
+use serde_json::json;
+
+use crate::lint::{Finding, Fix, LintCtx, PatchOp, RuleId};
 
 /// Tags used by operations should be declared at top level
 pub fn tags_declared(ctx: &LintCtx, out: &mut Vec<Finding>) {
@@ -19,6 +21,40 @@ pub fn tags_declared(ctx: &LintCtx, out: &mut Vec<Finding>) {
     }
 }
 
+/// Operations should be grouped under at least one tag
+pub fn operation_tags_required(ctx: &LintCtx, out: &mut Vec<Finding>) {
+    for op in &ctx.indexes.ops {
+        if op.tags.is_empty() {
+            let tag = default_tag(&op.path);
+            out.push(
+                Finding::new(
+                    RuleId::OperationTagsRequired,
+                    op.ptr_base.clone(),
+                    format!(
+                        "Operation {} {} has no tags",
+                        op.method.to_uppercase(),
+                        op.path
+                    ),
+                )
+                .with_autofix(Fix::new(
+                    format!("Tag the operation '{}'", tag),
+                    vec![PatchOp::Add {
+                        path: format!("{}/tags", op.ptr_base),
+                        value: json!([tag]),
+                    }],
+                )),
+            );
+        }
+    }
+}
+
+/// The first literal path segment (`pets` for `/pets/{id}`), or `default`.
+fn default_tag(path: &str) -> &str {
+    path.split('/')
+        .find(|segment| !segment.is_empty() && !segment.starts_with('{'))
+        .unwrap_or("default")
+}
+
 #[cfg(test)]
 mod tests {
     use crate::lint::RuleId;
@@ -170,4 +206,52 @@ paths:
                 .unwrap();
         assert!(validation.diagnostics.is_empty());
     }
+
+    #[test]
+    fn test_operation_tags_required() {
+        let yaml = r#"
+openapi: 3.1.0
+info:
+  title: Test API
+  version: 1.0.0
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      tags:
+        - pets
+      responses:
+        '200':
+          description: Success
+    post:
+      operationId: createPet
+      responses:
+        '201':
+          description: Created
+"#;
+        let rules = RuleSet::from_slice(&[RuleId::OperationTagsRequired.as_str()]);
+        let validation = lint_with_ruleset(yaml, rules).unwrap();
+
+        assert_eq!(validation.diagnostics.len(), 1);
+        assert_eq!(
+            validation.diagnostics[0].rule,
+            RuleId::OperationTagsRequired
+        );
+        assert_eq!(validation.diagnostics[0].pointer, "/paths/~1pets/post");
+        assert_eq!(
+            validation.diagnostics[0].message,
+            "Operation POST /pets has no tags"
+        );
+        assert_eq!(
+            validation.diagnostics[0].autofix.as_ref().unwrap().ops,
+            [crate::PatchOp::Add {
+                path: "/paths/~1pets/post/tags".to_string(),
+                value: serde_json::json!(["pets"]),
+            }]
+        );
+
+        let json = yaml_to_json(yaml);
+        let validation = lint_with_ruleset(&json, rules).unwrap();
+        assert_eq!(validation.diagnostics.len(), 1);
+    }
 }
@@ -0,0 +1,176 @@
+//! SARIF 2.1.0 output, for code-scanning UIs (GitHub code scanning, Azure
+//! DevOps, IDE SARIF viewers).
+//!
+//! Every result carries its JSON pointer as a logical location. When the
+//! spec's file is known, results also get a physical location: the file and
+//! the line/column range the pointer resolved to in the original text.
+
+use serde_json::{Value, json};
+
+use crate::api::Validation;
+use crate::lint::{Diagnostic, RuleId, Severity};
+
+/// Schema URI written to `$schema`.
+pub const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Render `validation` as a SARIF log with one run.
+///
+/// `artifact_uri` is the spec's path or URI as it should appear in the
+/// code-scanning UI (usually relative to the repository root); without it
+/// results only have logical (JSON pointer) locations.
+pub fn to_sarif(validation: &Validation, artifact_uri: Option<&str>) -> Value {
+    let mut rules: Vec<RuleId> = Vec::new();
+    let results: Vec<Value> = validation
+        .diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let index = match rules.iter().position(|rule| *rule == diagnostic.rule) {
+                Some(index) => index,
+                None => {
+                    rules.push(diagnostic.rule);
+                    rules.len() - 1
+                }
+            };
+            result(diagnostic, index, artifact_uri)
+        })
+        .collect();
+
+    let rules: Vec<Value> = rules
+        .iter()
+        .map(|rule| {
+            json!({
+                "id": rule.as_str(),
+                "defaultConfiguration": { "level": level(rule.default_severity()) },
+                "properties": {
+                    "tags": rule.categories().iter().map(|c| c.as_str()).collect::<Vec<_>>()
+                }
+            })
+        })
+        .collect();
+
+    let mut run = json!({
+        "tool": {
+            "driver": {
+                "name": "oas-gen",
+                "version": env!("CARGO_PKG_VERSION"),
+                "rules": rules
+            }
+        },
+        "results": results
+    });
+    if let Some(uri) = artifact_uri {
+        run["artifacts"] = json!([{ "location": { "uri": uri } }]);
+    }
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [run]
+    })
+}
+
+/// [`to_sarif`], pretty-printed.
+pub fn to_sarif_string(validation: &Validation, artifact_uri: Option<&str>) -> String {
+    serde_json::to_string_pretty(&to_sarif(validation, artifact_uri)).unwrap_or_default()
+}
+
+fn result(diagnostic: &Diagnostic, rule_index: usize, artifact_uri: Option<&str>) -> Value {
+    let mut location = json!({
+        "logicalLocations": [{
+            "fullyQualifiedName": diagnostic.pointer,
+            "kind": "member"
+        }]
+    });
+    if let Some(uri) = artifact_uri {
+        // SARIF lines and columns are 1-based; ours are 0-based
+        let range = diagnostic.range;
+        location["physicalLocation"] = json!({
+            "artifactLocation": { "uri": uri, "index": 0 },
+            "region": {
+                "startLine": range.start.line + 1,
+                "startColumn": range.start.col + 1,
+                "endLine": range.end.line + 1,
+                "endColumn": range.end.col + 1,
+                "byteOffset": diagnostic.byte_span.start,
+                "byteLength": diagnostic.byte_span.end.saturating_sub(diagnostic.byte_span.start),
+                "snippet": { "text": diagnostic.snippet }
+            }
+        });
+    }
+
+    json!({
+        "ruleId": diagnostic.rule.as_str(),
+        "ruleIndex": rule_index,
+        "level": level(diagnostic.severity),
+        "message": { "text": diagnostic.message },
+        "locations": [location]
+    })
+}
+
+/// SARIF has no separate hint level.
+fn level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Info | Severity::Hint => "note",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::lint;
+
+    #[test]
+    fn test_to_sarif() {
+        let spec = r#"openapi: "3.1.0"
+info:
+  title: Test API
+  version: "1.0"
+paths:
+  /pets:
+    get:
+      responses:
+        "200":
+          description: OK
+  /owners:
+    get:
+      responses:
+        "200":
+          description: OK
+"#;
+        let result = lint(spec, &["operation-id-required", "paths-not-empty"]).unwrap();
+        let sarif = to_sarif(&result, Some("openapi.yaml"));
+
+        assert_eq!(sarif["version"], "2.1.0");
+        let run = &sarif["runs"][0];
+        assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), 1);
+        assert_eq!(
+            run["tool"]["driver"]["rules"][0]["id"],
+            "operation-id-required"
+        );
+
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0]["ruleIndex"], 0);
+        assert_eq!(results[0]["level"], "warning");
+        let location = &results[0]["locations"][0];
+        assert_eq!(
+            location["logicalLocations"][0]["fullyQualifiedName"],
+            "/paths/~1pets/get/operationId"
+        );
+        assert_eq!(
+            location["physicalLocation"]["artifactLocation"]["uri"],
+            "openapi.yaml"
+        );
+        assert_eq!(
+            location["physicalLocation"]["region"]["startLine"],
+            result.diagnostics[0].range.start.line + 1
+        );
+
+        let sarif = to_sarif(&result, None);
+        let location = &sarif["runs"][0]["results"][0]["locations"][0];
+        assert!(location.get("physicalLocation").is_none());
+        assert!(sarif["runs"][0].get("artifacts").is_none());
+    }
+}
@@ -0,0 +1,719 @@
+//! Spectral-compatible rulesets.
+//!
+//! Teams that already describe their API style in a `.spectral.yaml` can
+//! load it with [`SpectralRuleset::from_yaml`] and run its rules through a
+//! [`Linter`](crate::Linter) next to the built-in ones:
+//!
+//! ```yaml
+//! rules:
+//!   info-contact:
+//!     description: Info must have a contact
+//!     severity: error
+//!     given: $.info
+//!     then:
+//!       field: contact
+//!       function: truthy
+//!   path-kebab-case:
+//!     message: "{{property}} is not kebab-case"
+//!     given: $.paths
+//!     then:
+//!       field: "@key"
+//!       function: pattern
+//!       functionOptions:
+//!         match: "^(/[a-z0-9-{}]+)+$"
+//! ```
+//!
+//! Supported: `given` as a JSONPath (`$`, `.key`, `['key']`, `[n]`, `*`,
+//! `..key`) or a list of them; `then` as one check or a list, with an
+//! optional `field` (`@key` checks the matched key); the `truthy`, `falsy`,
+//! `defined`, `undefined`, `pattern`, `enumeration` and `length` functions;
+//! severities `error`, `warn`, `info`, `hint` (or 0-3) and `off`. `extends`,
+//! `formats`, `overrides` and custom functions are ignored.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::LintError;
+use crate::lint::{Finding, LintCtx, Rule, RuleId, Severity};
+use crate::model::pointer;
+
+/// Rules read from a Spectral ruleset.
+#[derive(Debug, Default)]
+pub struct SpectralRuleset {
+    rules: Vec<SpectralRule>,
+}
+
+impl SpectralRuleset {
+    /// Parse a `.spectral.yaml` (or `.spectral.json`) ruleset.
+    ///
+    /// Rules turned `off` are skipped. Rule names become
+    /// [`RuleId::custom`] IDs.
+    pub fn from_yaml(input: &str) -> Result<Self, LintError> {
+        let document: RulesetDocument =
+            parser::yaml::from_str(input).map_err(|e| LintError::Ruleset(e.to_string()))?;
+
+        let mut rules = Vec::new();
+        for (name, definition) in document.rules {
+            // `rule-name: off` or a bare severity only tunes a rule from
+            // `extends`, which isn't loaded
+            if !definition.is_object() {
+                continue;
+            }
+            let definition: RuleDefinition =
+                serde_json::from_value(definition).map_err(|e| rule_error(&name, e.to_string()))?;
+            let Some(severity) = parse_severity(definition.severity.as_ref(), &name)? else {
+                continue;
+            };
+            let given: Vec<JsonPath> = definition
+                .given
+                .into_vec()
+                .iter()
+                .map(|path| JsonPath::parse(path).map_err(|e| rule_error(&name, e)))
+                .collect::<Result<_, _>>()?;
+            let then: Vec<Check> = definition
+                .then
+                .into_vec()
+                .into_iter()
+                .map(|then| Check::new(then).map_err(|e| rule_error(&name, e)))
+                .collect::<Result<_, _>>()?;
+
+            rules.push(SpectralRule {
+                id: RuleId::custom(&name),
+                description: definition.description,
+                message: definition.message,
+                severity,
+                given,
+                then,
+            });
+        }
+        Ok(Self { rules })
+    }
+
+    /// Read a ruleset file.
+    pub fn from_path(path: impl AsRef<std::path::Path>) -> Result<Self, LintError> {
+        let path = path.as_ref();
+        let input = std::fs::read_to_string(path)
+            .map_err(|e| LintError::Ruleset(format!("{}: {}", path.display(), e)))?;
+        Self::from_yaml(&input)
+    }
+
+    /// IDs of the loaded rules
+    pub fn rule_ids(&self) -> impl Iterator<Item = RuleId> + '_ {
+        self.rules.iter().map(|rule| rule.id)
+    }
+
+    /// The loaded rules, ready for [`Linter::with_rules`](crate::Linter::with_rules)
+    pub fn into_rules(self) -> impl Iterator<Item = Box<dyn Rule>> {
+        self.rules
+            .into_iter()
+            .map(|rule| Box::new(rule) as Box<dyn Rule>)
+    }
+}
+
+fn rule_error(name: &str, message: String) -> LintError {
+    LintError::Ruleset(format!("rule `{}`: {}", name, message))
+}
+
+/// `None` when the rule is turned off.
+fn parse_severity(severity: Option<&Value>, name: &str) -> Result<Option<Severity>, LintError> {
+    let severity = match severity {
+        None => Severity::Warning,
+        Some(Value::Bool(false)) => return Ok(None),
+        Some(Value::String(s)) if s == "off" => return Ok(None),
+        Some(Value::String(s)) => Severity::parse(s)
+            .ok_or_else(|| rule_error(name, format!("unknown severity `{}`", s)))?,
+        Some(Value::Number(n)) => match n.as_i64() {
+            Some(-1) => return Ok(None),
+            Some(0) => Severity::Error,
+            Some(1) => Severity::Warning,
+            Some(2) => Severity::Info,
+            Some(3) => Severity::Hint,
+            _ => return Err(rule_error(name, format!("unknown severity `{}`", n))),
+        },
+        Some(other) => return Err(rule_error(name, format!("unknown severity `{}`", other))),
+    };
+    Ok(Some(severity))
+}
+
+#[derive(Deserialize)]
+struct RulesetDocument {
+    #[serde(default)]
+    rules: BTreeMap<String, Value>,
+}
+
+#[derive(Deserialize)]
+struct RuleDefinition {
+    description: Option<String>,
+    message: Option<String>,
+    severity: Option<Value>,
+    given: OneOrMany<String>,
+    then: OneOrMany<ThenDefinition>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OneOrMany<T> {
+    One(T),
+    Many(Vec<T>),
+}
+
+impl<T> OneOrMany<T> {
+    fn into_vec(self) -> Vec<T> {
+        match self {
+            OneOrMany::One(one) => vec![one],
+            OneOrMany::Many(many) => many,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ThenDefinition {
+    field: Option<String>,
+    function: String,
+    #[serde(default)]
+    function_options: Value,
+}
+
+/// One rule from the ruleset.
+#[derive(Debug)]
+struct SpectralRule {
+    id: RuleId,
+    description: Option<String>,
+    message: Option<String>,
+    severity: Severity,
+    given: Vec<JsonPath>,
+    then: Vec<Check>,
+}
+
+impl Rule for SpectralRule {
+    fn id(&self) -> RuleId {
+        self.id
+    }
+
+    fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    fn check(&self, ctx: &LintCtx) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for path in &self.given {
+            for (segments, node) in path.select(ctx.document()) {
+                for check in &self.then {
+                    for (target_path, target) in check.targets(&segments, node) {
+                        let Some(error) = check.function.run(target.as_deref()) else {
+                            continue;
+                        };
+                        // A missing field is reported on the node that lacks it
+                        let ptr = if target.is_some() {
+                            pointer(&target_path)
+                        } else {
+                            pointer(&segments)
+                        };
+                        let message = self.message(&error, &target_path, target.as_deref(), &ptr);
+                        findings.push(self.finding(ptr, message));
+                    }
+                }
+            }
+        }
+        findings
+    }
+}
+
+impl SpectralRule {
+    /// Fill in `message` (or the description, or the function's own error)
+    /// the way Spectral does.
+    fn message(&self, error: &str, path: &[String], value: Option<&Value>, ptr: &str) -> String {
+        let template = self
+            .message
+            .as_deref()
+            .or(self.description.as_deref())
+            .unwrap_or("{{error}}");
+        let property = path.last().map(String::as_str).unwrap_or("");
+        let value = match value {
+            Some(Value::String(s)) => s.clone(),
+            Some(other) => other.to_string(),
+            None => String::new(),
+        };
+        template
+            .replace("{{error}}", error)
+            .replace("{{property}}", property)
+            .replace("{{value}}", &value)
+            .replace("{{path}}", ptr)
+            .replace("{{description}}", self.description.as_deref().unwrap_or(""))
+    }
+}
+
+/// One `then` entry.
+#[derive(Debug)]
+struct Check {
+    field: Option<String>,
+    function: Function,
+}
+
+impl Check {
+    fn new(then: ThenDefinition) -> Result<Self, String> {
+        Ok(Self {
+            field: then.field,
+            function: Function::new(&then.function, &then.function_options)?,
+        })
+    }
+
+    /// The values the function runs on, with their paths. `@key` checks
+    /// every key of the matched object.
+    fn targets<'a>(
+        &self,
+        segments: &[String],
+        node: &'a Value,
+    ) -> Vec<(Vec<String>, Option<Cow<'a, Value>>)> {
+        match self.field.as_deref() {
+            None => vec![(segments.to_vec(), Some(Cow::Borrowed(node)))],
+            Some("@key") => node
+                .as_object()
+                .into_iter()
+                .flatten()
+                .map(|(key, _)| {
+                    let key_value = Cow::Owned(Value::String(key.clone()));
+                    (extend(segments, key), Some(key_value))
+                })
+                .collect(),
+            Some(field) => {
+                let mut path = segments.to_vec();
+                let mut value = Some(node);
+                for part in field.split('.') {
+                    path.push(part.to_string());
+                    value = value.and_then(|v| child(v, part));
+                }
+                vec![(path, value.map(Cow::Borrowed))]
+            }
+        }
+    }
+}
+
+fn child<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    match value {
+        Value::Object(map) => map.get(key),
+        Value::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get(i)),
+        _ => None,
+    }
+}
+
+/// Built-in Spectral functions.
+#[derive(Debug)]
+enum Function {
+    Truthy,
+    Falsy,
+    Defined,
+    Undefined,
+    Pattern {
+        matches: Option<regex::Regex>,
+        not_match: Option<regex::Regex>,
+    },
+    Enumeration(Vec<Value>),
+    Length {
+        min: Option<f64>,
+        max: Option<f64>,
+    },
+}
+
+impl Function {
+    fn new(name: &str, options: &Value) -> Result<Self, String> {
+        let option = |key: &str| options.get(key);
+        Ok(match name {
+            "truthy" => Function::Truthy,
+            "falsy" => Function::Falsy,
+            "defined" => Function::Defined,
+            "undefined" => Function::Undefined,
+            "pattern" => {
+                let regex = |key: &str| {
+                    option(key)
+                        .and_then(Value::as_str)
+                        .map(spectral_regex)
+                        .transpose()
+                };
+                let (matches, not_match) = (regex("match")?, regex("notMatch")?);
+                if matches.is_none() && not_match.is_none() {
+                    return Err("`pattern` needs a `match` or `notMatch` option".to_string());
+                }
+                Function::Pattern { matches, not_match }
+            }
+            "enumeration" => match option("values") {
+                Some(Value::Array(values)) => Function::Enumeration(values.clone()),
+                _ => return Err("`enumeration` needs a `values` list".to_string()),
+            },
+            "length" => {
+                let min = option("min").and_then(Value::as_f64);
+                let max = option("max").and_then(Value::as_f64);
+                if min.is_none() && max.is_none() {
+                    return Err("`length` needs a `min` or `max` option".to_string());
+                }
+                Function::Length { min, max }
+            }
+            other => return Err(format!("unsupported function `{}`", other)),
+        })
+    }
+
+    /// The error message when `value` fails the check.
+    fn run(&self, value: Option<&Value>) -> Option<String> {
+        match self {
+            Function::Truthy if !value.is_some_and(truthy) => Some("must be truthy".to_string()),
+            Function::Falsy if value.is_some_and(truthy) => Some("must be falsy".to_string()),
+            Function::Defined if value.is_none() => Some("must be defined".to_string()),
+            Function::Undefined if value.is_some() => Some("must be undefined".to_string()),
+            Function::Pattern { matches, not_match } => {
+                let text = value?.as_str()?;
+                if let Some(regex) = matches
+                    && !regex.is_match(text)
+                {
+                    return Some(format!("must match the pattern `{}`", regex.as_str()));
+                }
+                if let Some(regex) = not_match
+                    && regex.is_match(text)
+                {
+                    return Some(format!("must not match the pattern `{}`", regex.as_str()));
+                }
+                None
+            }
+            Function::Enumeration(values) => {
+                let value = value?;
+                (!values.contains(value)).then(|| {
+                    let allowed: Vec<String> = values.iter().map(Value::to_string).collect();
+                    format!("must be one of: {}", allowed.join(", "))
+                })
+            }
+            Function::Length { min, max } => {
+                let length = match value? {
+                    Value::String(s) => s.chars().count() as f64,
+                    Value::Array(items) => items.len() as f64,
+                    Value::Object(map) => map.len() as f64,
+                    Value::Number(n) => n.as_f64()?,
+                    _ => return None,
+                };
+                if let Some(min) = min
+                    && length < *min
+                {
+                    Some(format!("must not be shorter than {}", min))
+                } else if let Some(max) = max
+                    && length > *max
+                {
+                    Some(format!("must not be longer than {}", max))
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+/// JavaScript truthiness, which Spectral's `truthy` follows.
+fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64().is_some_and(|n| n != 0.0),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(_) | Value::Object(_) => true,
+    }
+}
+
+/// Spectral accepts both bare patterns and `/pattern/flags`.
+fn spectral_regex(pattern: &str) -> Result<regex::Regex, String> {
+    let pattern = match pattern.strip_prefix('/').and_then(|p| p.rsplit_once('/')) {
+        Some((body, flags)) if flags.chars().all(|c| "imsu".contains(c)) => {
+            let flags: String = flags.chars().filter(|c| "ims".contains(*c)).collect();
+            if flags.is_empty() {
+                body.to_string()
+            } else {
+                format!("(?{}){}", flags, body)
+            }
+        }
+        _ => pattern.to_string(),
+    };
+    regex::Regex::new(&pattern).map_err(|e| e.to_string())
+}
+
+/// The JSONPath subset used by typical Spectral rulesets.
+#[derive(Debug, Clone, PartialEq)]
+struct JsonPath(Vec<Step>);
+
+#[derive(Debug, Clone, PartialEq)]
+enum Step {
+    Key(String),
+    Index(usize),
+    Wildcard,
+    /// `..key` or `..*`: any descendant
+    Descendant(Option<String>),
+}
+
+impl JsonPath {
+    fn parse(path: &str) -> Result<Self, String> {
+        let unsupported = || format!("unsupported JSONPath `{}`", path);
+        let mut rest = path
+            .trim()
+            .strip_prefix('$')
+            .ok_or_else(|| format!("JSONPath `{}` must start with `$`", path))?;
+        let mut steps = Vec::new();
+
+        let name_end = |s: &str| s.find(['.', '[']).unwrap_or(s.len());
+        while !rest.is_empty() {
+            if let Some(after) = rest.strip_prefix("..") {
+                let end = name_end(after);
+                let name = &after[..end];
+                if name.is_empty() {
+                    return Err(unsupported());
+                }
+                steps.push(Step::Descendant((name != "*").then(|| name.to_string())));
+                rest = &after[end..];
+            } else if let Some(after) = rest.strip_prefix('.') {
+                let end = name_end(after);
+                let name = &after[..end];
+                steps.push(match name {
+                    "" => return Err(unsupported()),
+                    "*" => Step::Wildcard,
+                    name => Step::Key(name.to_string()),
+                });
+                rest = &after[end..];
+            } else if let Some(after) = rest.strip_prefix('[') {
+                let end = after.find(']').ok_or_else(unsupported)?;
+                let inner = after[..end].trim();
+                steps.push(if inner == "*" {
+                    Step::Wildcard
+                } else if let Ok(index) = inner.parse() {
+                    Step::Index(index)
+                } else if let Some(key) = inner
+                    .strip_prefix('\'')
+                    .and_then(|k| k.strip_suffix('\''))
+                    .or_else(|| inner.strip_prefix('"').and_then(|k| k.strip_suffix('"')))
+                {
+                    Step::Key(key.to_string())
+                } else {
+                    // Filters (`?(...)`), slices and unions
+                    return Err(unsupported());
+                });
+                rest = &after[end + 1..];
+            } else {
+                return Err(unsupported());
+            }
+        }
+        Ok(Self(steps))
+    }
+
+    /// Every node matching the path, with its path segments.
+    fn select<'a>(&self, root: &'a Value) -> Vec<(Vec<String>, &'a Value)> {
+        let mut nodes = vec![(Vec::new(), root)];
+        for step in &self.0 {
+            let mut next = Vec::new();
+            for (path, node) in nodes {
+                match step {
+                    Step::Key(key) => {
+                        if let Some(value) = node.as_object().and_then(|map| map.get(key)) {
+                            next.push((extend(&path, key), value));
+                        }
+                    }
+                    Step::Index(index) => {
+                        if let Some(value) = node.as_array().and_then(|items| items.get(*index)) {
+                            next.push((extend(&path, &index.to_string()), value));
+                        }
+                    }
+                    Step::Wildcard => children(&path, node, &mut next),
+                    Step::Descendant(key) => descendants(&path, node, key.as_deref(), &mut next),
+                }
+            }
+            nodes = next;
+        }
+        nodes
+    }
+}
+
+fn extend(path: &[String], segment: &str) -> Vec<String> {
+    let mut path = path.to_vec();
+    path.push(segment.to_string());
+    path
+}
+
+fn children<'a>(path: &[String], node: &'a Value, out: &mut Vec<(Vec<String>, &'a Value)>) {
+    match node {
+        Value::Object(map) => {
+            out.extend(map.iter().map(|(key, value)| (extend(path, key), value)));
+        }
+        Value::Array(items) => out.extend(
+            items
+                .iter()
+                .enumerate()
+                .map(|(i, value)| (extend(path, &i.to_string()), value)),
+        ),
+        _ => {}
+    }
+}
+
+fn descendants<'a>(
+    path: &[String],
+    node: &'a Value,
+    key: Option<&str>,
+    out: &mut Vec<(Vec<String>, &'a Value)>,
+) {
+    let mut direct = Vec::new();
+    children(path, node, &mut direct);
+    for (child_path, child) in direct {
+        if key.is_none_or(|key| child_path.last().is_some_and(|last| last == key)) {
+            out.push((child_path.clone(), child));
+        }
+        descendants(&child_path, child, key, out);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Linter, RuleSet};
+
+    const RULESET: &str = r#"
+extends: spectral:oas
+rules:
+  operation-description: off
+  info-contact:
+    description: Info must have a contact
+    severity: error
+    given: $.info
+    then:
+      field: contact
+      function: truthy
+  path-kebab-case:
+    message: "{{property}} is not kebab-case"
+    given: $.paths
+    then:
+      field: "@key"
+      function: pattern
+      functionOptions:
+        match: "/^(\\/[a-z0-9{}-]+)+$/"
+  operation-summary-length:
+    severity: 2
+    given: "$.paths[*][*].summary"
+    then:
+      function: length
+      functionOptions: { max: 20 }
+  response-codes:
+    given: "$..responses"
+    then:
+      field: "@key"
+      function: enumeration
+      functionOptions: { values: ["200", "404"] }
+"#;
+
+    #[test]
+    fn test_jsonpath() {
+        let doc = serde_json::json!({
+            "paths": {
+                "/a": { "get": { "summary": "A" } },
+                "/b": { "post": { "summary": "B" } }
+            }
+        });
+        let path = JsonPath::parse("$.paths[*][*].summary").unwrap();
+        let found: Vec<String> = path
+            .select(&doc)
+            .into_iter()
+            .map(|(segments, _)| pointer(&segments))
+            .collect();
+        assert_eq!(found, ["/paths/~1a/get/summary", "/paths/~1b/post/summary"]);
+
+        let path = JsonPath::parse("$..summary").unwrap();
+        assert_eq!(path.select(&doc).len(), 2);
+        let path = JsonPath::parse("$.paths['/a'].get").unwrap();
+        assert_eq!(path.select(&doc).len(), 1);
+
+        assert!(JsonPath::parse("$.paths[?(@.get)]").is_err());
+        assert!(JsonPath::parse("paths").is_err());
+    }
+
+    #[test]
+    fn test_spectral_ruleset() {
+        let ruleset = SpectralRuleset::from_yaml(RULESET).unwrap();
+        let ids: Vec<&str> = ruleset.rule_ids().map(|id| id.as_str()).collect();
+        assert_eq!(
+            ids,
+            [
+                "info-contact",
+                "operation-summary-length",
+                "path-kebab-case",
+                "response-codes"
+            ]
+        );
+
+        let spec = r#"
+openapi: "3.1.0"
+info:
+  title: Test API
+  version: "1.0"
+paths:
+  /pet_owners:
+    get:
+      operationId: listOwners
+      summary: List every single pet owner
+      responses:
+        "200":
+          description: OK
+        "500":
+          description: Oops
+"#;
+        let linter = Linter::new(RuleSet::new()).with_rules(ruleset.into_rules());
+        let result = linter.lint(spec).unwrap();
+
+        let found: Vec<(&str, Severity, &str)> = result
+            .diagnostics
+            .iter()
+            .map(|d| (d.rule.as_str(), d.severity, d.pointer.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("info-contact", Severity::Error, "/info"),
+                ("path-kebab-case", Severity::Warning, "/paths/~1pet_owners"),
+                (
+                    "operation-summary-length",
+                    Severity::Info,
+                    "/paths/~1pet_owners/get/summary"
+                ),
+                (
+                    "response-codes",
+                    Severity::Warning,
+                    "/paths/~1pet_owners/get/responses/500"
+                ),
+            ]
+        );
+        assert_eq!(result.diagnostics[0].message, "Info must have a contact");
+        assert_eq!(
+            result.diagnostics[1].message,
+            "/pet_owners is not kebab-case"
+        );
+        assert_eq!(result.diagnostics[2].message, "must not be longer than 20");
+
+        // Loading the ruleset again reuses the interned names
+        let reloaded = SpectralRuleset::from_yaml(RULESET).unwrap();
+        let RuleId::Custom(first) = reloaded.rule_ids().next().unwrap() else {
+            panic!("spectral rules are custom");
+        };
+        let RuleId::Custom(again) = RuleId::custom("info-contact") else {
+            unreachable!()
+        };
+        assert!(std::ptr::eq(first, again));
+    }
+
+    #[test]
+    fn test_invalid_ruleset() {
+        let unknown_function = r#"
+rules:
+  custom:
+    given: $.info
+    then: { function: myFunction }
+"#;
+        let err = SpectralRuleset::from_yaml(unknown_function).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("unsupported function `myFunction`")
+        );
+    }
+}
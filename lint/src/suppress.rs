@@ -0,0 +1,186 @@
+//! Inline suppressions.
+//!
+//! Any node can carry `x-lint-ignore` to drop findings for itself and
+//! everything below it:
+//!
+//! ```yaml
+//! /legacy/search:
+//!   x-lint-ignore: [path-no-verbs, operation-tags-required]
+//!   get: ...
+//! ```
+//!
+//! The value is a rule ID, a list of them, or `"*"` (or `true`) for every
+//! rule. Suppressed findings are counted in
+//! [`Validation::suppressed`](crate::Validation::suppressed).
+
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use crate::lint::Finding;
+use crate::model::escape_pointer_segment;
+
+/// The extension holding inline suppressions.
+pub const IGNORE_EXTENSION: &str = "x-lint-ignore";
+
+/// An `x-lint-ignore` found in a spec.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InlineIgnore {
+    /// Pointer to the node carrying the extension
+    pub pointer: String,
+    /// Rule IDs to ignore; empty means every rule
+    pub rules: Vec<String>,
+}
+
+impl InlineIgnore {
+    /// Whether a finding of `rule` at `pointer` is suppressed.
+    pub fn matches(&self, rule: &str, pointer: &str) -> bool {
+        (self.rules.is_empty() || self.rules.iter().any(|r| r == rule))
+            && pointer
+                .strip_prefix(self.pointer.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+    }
+}
+
+/// Every `x-lint-ignore` in `document`, outermost first.
+pub fn inline_ignores(document: &Value) -> Vec<InlineIgnore> {
+    let mut ignores = Vec::new();
+    collect(document, &mut String::new(), &mut ignores);
+    ignores
+}
+
+fn collect(node: &Value, ptr: &mut String, out: &mut Vec<InlineIgnore>) {
+    let len = ptr.len();
+    match node {
+        Value::Object(map) => {
+            if let Some(value) = map.get(IGNORE_EXTENSION) {
+                let rules = match value {
+                    Value::String(rule) if rule == "*" => Some(Vec::new()),
+                    Value::String(rule) => Some(vec![rule.clone()]),
+                    Value::Bool(true) => Some(Vec::new()),
+                    Value::Array(rules) => {
+                        let rules: Vec<String> = rules
+                            .iter()
+                            .filter_map(|rule| rule.as_str().map(str::to_string))
+                            .collect();
+                        if rules.iter().any(|rule| rule == "*") {
+                            Some(Vec::new())
+                        } else {
+                            Some(rules).filter(|rules| !rules.is_empty())
+                        }
+                    }
+                    _ => None,
+                };
+                if let Some(rules) = rules {
+                    out.push(InlineIgnore {
+                        pointer: ptr.clone(),
+                        rules,
+                    });
+                }
+            }
+            for (key, child) in map {
+                ptr.push('/');
+                ptr.push_str(&escape_pointer_segment(key));
+                collect(child, ptr, out);
+                ptr.truncate(len);
+            }
+        }
+        Value::Array(items) => {
+            for (i, child) in items.iter().enumerate() {
+                ptr.push('/');
+                ptr.push_str(&i.to_string());
+                collect(child, ptr, out);
+                ptr.truncate(len);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Drop findings under a matching `x-lint-ignore`, returning the rest and
+/// how many were dropped per rule.
+pub(crate) fn apply_inline_ignores(
+    ignores: &[InlineIgnore],
+    findings: Vec<Finding>,
+) -> (Vec<Finding>, BTreeMap<String, usize>) {
+    let mut suppressed = BTreeMap::new();
+    let findings = findings
+        .into_iter()
+        .filter(|f| {
+            let rule = f.rule.as_str();
+            let ignored = ignores.iter().any(|ignore| ignore.matches(rule, &f.ptr));
+            if ignored {
+                *suppressed.entry(rule.to_string()).or_insert(0) += 1;
+            }
+            !ignored
+        })
+        .collect();
+    (findings, suppressed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lint::RuleId;
+    use crate::{Linter, RuleSet};
+    use serde_json::json;
+
+    #[test]
+    fn test_inline_ignores() {
+        let document = json!({
+            "paths": {
+                "/a/b": { "x-lint-ignore": "path-no-verbs", "get": {} },
+                "/c": { "get": { "x-lint-ignore": ["*"] } },
+                "/d": { "x-lint-ignore": 3 }
+            }
+        });
+        let ignores = inline_ignores(&document);
+        assert_eq!(
+            ignores,
+            [
+                InlineIgnore {
+                    pointer: "/paths/~1a~1b".to_string(),
+                    rules: vec!["path-no-verbs".to_string()],
+                },
+                InlineIgnore {
+                    pointer: "/paths/~1c/get".to_string(),
+                    rules: vec![],
+                },
+            ]
+        );
+        assert!(ignores[0].matches("path-no-verbs", "/paths/~1a~1b/get"));
+        assert!(!ignores[0].matches("path-no-verbs", "/paths/~1a~1bc"));
+        assert!(!ignores[0].matches("operation-id-required", "/paths/~1a~1b/get"));
+        assert!(ignores[1].matches("operation-id-required", "/paths/~1c/get"));
+    }
+
+    #[test]
+    fn test_lint_with_inline_ignores() {
+        let spec = r#"
+openapi: 3.1.0
+info: { title: Pets, version: "1" }
+paths:
+  /pets:
+    x-lint-ignore: [operation-id-required]
+    get:
+      responses:
+        "200":
+          description: OK
+  /owners:
+    get:
+      responses:
+        "200":
+          description: OK
+"#;
+        let result = Linter::new(RuleSet::from_slice(&[RuleId::OperationIdRequired.as_str()]))
+            .lint(spec)
+            .unwrap();
+        assert_eq!(result.diagnostics.len(), 1);
+        assert_eq!(
+            result.diagnostics[0].pointer,
+            "/paths/~1owners/get/operationId"
+        );
+        assert_eq!(result.suppressed["operation-id-required"], 1);
+        assert_eq!(result.suppressed_count(), 1);
+    }
+}
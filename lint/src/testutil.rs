@@ -1,5 +1,5 @@
 #[cfg(test)]
 pub(crate) fn yaml_to_json(yaml: &str) -> String {
-    let value: serde_json::Value = serde_saphyr::from_str(yaml).unwrap();
+    let value: serde_json::Value = parser::yaml::from_str(yaml).unwrap();
     serde_json::to_string_pretty(&value).unwrap()
 }
@@ -0,0 +1,328 @@
+//! Generate SDKs and servers from OpenAPI specifications.
+//!
+//! This crate is the stable entry point to the pipeline:
+//!
+//! ```text
+//! OpenAPI Spec → parser → GenIr → GeneratorRegistry → VirtualFS → Disk
+//! ```
+//!
+//! Depend on it instead of `parser`, `ir`, `codegen` and `generate`
+//! separately. Everything exported at the crate root follows semver; the
+//! underlying crates are re-exported under [`pipeline`] for advanced use and
+//! may change between minor versions.
+//!
+//! Fields are added to [`Config`] and [`GenIr`] in minor versions, so build
+//! them with `Config::default()` and its `with_*` methods, and with
+//! `GenIr::from(spec)` or [`GenIr::new`], rather than struct literals.
+//! [`Error`] may gain variants the same way.
+//!
+//! ```no_run
+//! use oas_gen::{Config, ServiceStyle, generate_from_path};
+//!
+//! let config = Config::default().with_service_style(ServiceStyle::ByTag);
+//! let files = generate_from_path("openapi.yaml", "typescript", &config)?;
+//! files.write_to_disk(std::path::Path::new("./generated"))?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! With the `async` feature, [`generate_from_url`] fetches the spec over
+//! HTTP without blocking the runtime, for services that build SDKs on
+//! demand.
+
+use std::fmt;
+use std::path::Path;
+
+pub use codegen::{
+    CallbackSink, Config, DiskSink, FileSink, GENERATOR_VERSION, GenIr, Generator, GeneratorInfo,
+    LintThreshold, NamingPolicy, ProjectConfig, ServiceStyle, VirtualFS,
+};
+pub use generate::{GeneratorRegistry, SpecCache};
+pub use parser::error::ParserError;
+pub use parser::source::{FileSource, SpecSource, StringSource, ValueSource};
+
+/// The crates behind the facade. Not covered by this crate's semver
+/// guarantees.
+pub mod pipeline {
+    pub use codegen;
+    pub use generate;
+    pub use ir;
+    pub use parser;
+}
+
+/// Result type for the pipeline.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Errors from any stage of the pipeline.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// The spec could not be loaded or parsed.
+    Parse(ParserError),
+    /// Code generation failed, including an unknown language.
+    Generate(codegen::Error),
+    /// The spec at `url` could not be downloaded.
+    Fetch {
+        url: String,
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse(e) => write!(f, "Failed to load spec: {}", e),
+            Error::Generate(e) => write!(f, "{}", e),
+            Error::Fetch { url, source } => write!(f, "Failed to fetch {}: {}", url, source),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Parse(e) => Some(e),
+            Error::Generate(e) => Some(e),
+            Error::Fetch { source, .. } => Some(&**source),
+        }
+    }
+}
+
+impl From<ParserError> for Error {
+    fn from(e: ParserError) -> Self {
+        Error::Parse(e)
+    }
+}
+
+impl From<codegen::Error> for Error {
+    fn from(e: codegen::Error) -> Self {
+        Error::Generate(e)
+    }
+}
+
+/// Load the spec at `spec` (bundling any files it references) and generate
+/// `language` with the built-in generators.
+///
+/// With [`Config::lint_threshold`] set, the file is linted first with the
+/// nearest `oas-lint.toml`, and findings point into it.
+pub fn generate_from_path(
+    spec: impl AsRef<Path>,
+    language: &str,
+    config: &Config,
+) -> Result<VirtualFS> {
+    generate_from_source(&FileSource::new(spec.as_ref()), language, config)
+}
+
+/// Like [`generate_from_path`], for a spec held in memory. Lints with the
+/// `oas-lint.toml` nearest to the current directory.
+pub fn generate_from_str(spec: &str, language: &str, config: &Config) -> Result<VirtualFS> {
+    generate_from_source(&StringSource::new("<string>", spec), language, config)
+}
+
+/// Like [`generate_from_path`], loading the spec from any [`SpecSource`].
+///
+/// With [`Config::lint_threshold`] set, the source's
+/// [`text`](SpecSource::text) is linted. Sources without one are linted in
+/// their loaded document's canonical YAML form, which findings then point
+/// into.
+pub fn generate_from_source(
+    source: &dyn SpecSource,
+    language: &str,
+    config: &Config,
+) -> Result<VirtualFS> {
+    if config.lint_threshold.is_some() {
+        let text = match source.text()? {
+            Some(text) => text,
+            None => parser::fmt::format_value(&source.document()?, parser::fmt::SpecFormat::Yaml)?,
+        };
+        generate::lint_spec(&text, source.file(), config)?;
+    }
+    generate_unlinted(source, language, config)
+}
+
+/// Fetch the spec at `url` and generate `language` with the built-in
+/// generators. Requires the `async` feature and a tokio runtime.
+///
+/// Fetching, parsing and generation run on tokio's blocking pool, so the
+/// runtime's workers stay free while large specs download and generate.
+/// Relative refs resolve against `url` and are fetched too, as long as they
+/// stay on its host. The sync functions above remain the way to generate
+/// outside a runtime.
+#[cfg(feature = "async")]
+pub async fn generate_from_url(url: &str, language: &str, config: &Config) -> Result<VirtualFS> {
+    use parser::fetch::{HttpFetcher, RemoteRefs};
+    use parser::source::UrlSource;
+
+    let (url, language, config) = (url.to_string(), language.to_string(), config.clone());
+    tokio::task::spawn_blocking(move || {
+        let fetch_error = |source: Box<dyn std::error::Error + Send + Sync>| Error::Fetch {
+            url: url.clone(),
+            source,
+        };
+        let text = ureq::AgentBuilder::new()
+            .timeout(std::time::Duration::from_secs(30))
+            .build()
+            .get(&url)
+            .call()
+            .map_err(|e| fetch_error(e.into()))?
+            .into_string()
+            .map_err(|e| fetch_error(e.into()))?;
+
+        let host: String = url.split_inclusive('/').take(3).collect();
+        let remote = RemoteRefs::new(HttpFetcher::new()).allow(host);
+        let fetcher = HttpFetcher::new();
+        let source = UrlSource::new(url.as_str(), &fetcher)
+            .with_content(text)
+            .with_remote_refs(&remote);
+        generate_from_source(&source, &language, &config)
+    })
+    .await
+    .map_err(|e| {
+        Error::Generate(codegen::Error::Custom(format!(
+            "generation panicked: {}",
+            e
+        )))
+    })?
+}
+
+fn generate_unlinted(
+    source: &dyn SpecSource,
+    language: &str,
+    config: &Config,
+) -> Result<VirtualFS> {
+    let config: &Config = &generate::linted(config);
+    let spec = generate::apply_overlays(source.load()?, config)?;
+    let config: &Config = &generate::overlaid(config);
+    Ok(GeneratorRegistry::with_defaults().generate(language, &GenIr::from(spec), config)?)
+}
+
+/// Languages the built-in generators support.
+pub fn languages() -> Vec<String> {
+    GeneratorRegistry::with_defaults()
+        .languages()
+        .into_iter()
+        .map(str::to_string)
+        .collect()
+}
+
+/// Name, version, description and options of the built-in generators.
+pub fn generators() -> Vec<GeneratorInfo> {
+    GeneratorRegistry::with_defaults().list()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: &str = r#"
+openapi: 3.1.0
+info: { title: Pets, version: "1" }
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      responses:
+        "200":
+          description: OK
+"#;
+
+    #[test]
+    fn test_generate_from_str() {
+        let vfs = generate_from_str(SPEC, "typescript", &Config::default()).unwrap();
+        assert!(!vfs.is_empty());
+
+        let err = generate_from_str(SPEC, "cobol", &Config::default()).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Generate(codegen::Error::GeneratorNotFound(_))
+        ));
+
+        let err = generate_from_str("openapi: [", "typescript", &Config::default()).unwrap_err();
+        assert!(matches!(err, Error::Parse(_)));
+
+        // The spec has no operation tags, a warning
+        let config = Config::default().with_lint_threshold(LintThreshold::Warning);
+        let err = generate_from_str(SPEC, "typescript", &config).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Generate(codegen::Error::LintFailed(_))
+        ));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_generate_from_url() {
+        use std::io::{Read, Write};
+
+        const ROOT: &str = r##"
+openapi: 3.1.0
+info: { title: Pets, version: "1" }
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      responses:
+        "200":
+          description: OK
+          content:
+            application/json:
+              schema: { $ref: "schemas/pet.yaml#/Pet" }
+"##;
+        const PET: &str = "Pet:\n  type: object\n  properties:\n    name: { type: string }\n";
+
+        // Serves the spec and the schema it references, then refuses
+        // connections
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/v1/openapi.yaml", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let mut requested = Vec::new();
+            for _ in 0..2 {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = [0; 1024];
+                let read = stream.read(&mut request).unwrap();
+                let request = String::from_utf8_lossy(&request[..read]).to_string();
+                let path = request.split(' ').nth(1).unwrap().to_string();
+                let body = if path == "/v1/schemas/pet.yaml" {
+                    PET
+                } else {
+                    ROOT
+                };
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                )
+                .unwrap();
+                requested.push(path);
+            }
+            requested
+        });
+
+        let vfs = generate_from_url(&url, "typescript", &Config::default())
+            .await
+            .unwrap();
+        // The schema from the second document is generated
+        let pet = vfs
+            .files()
+            .any(|(_, content)| String::from_utf8_lossy(content).contains("export interface Pet"));
+        assert!(pet);
+        assert_eq!(
+            server.join().unwrap(),
+            ["/v1/openapi.yaml", "/v1/schemas/pet.yaml"]
+        );
+
+        let err = generate_from_url(&url, "typescript", &Config::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(&err, Error::Fetch { url: fetched, .. } if *fetched == url));
+        assert!(std::error::Error::source(&err).is_some());
+    }
+
+    #[test]
+    fn test_generate_from_path() {
+        let spec = concat!(env!("CARGO_MANIFEST_DIR"), "/../examples/petstore.json");
+        let vfs = generate_from_path(spec, "typescript", &Config::default()).unwrap();
+        assert!(!vfs.is_empty());
+        assert!(languages().contains(&"typescript".to_string()));
+    }
+}
@@ -0,0 +1,782 @@
+//! Bundling of specs split across several local files.
+//!
+//! Any `$ref` whose part before `#` is non-empty (`./schemas/pet.yaml`,
+//! `common.yaml#/components/parameters/Limit`) is loaded relative to the file
+//! that contains it. Refs found inside a loaded file are resolved relative to
+//! that file in turn, so nested directories work as expected.
+//!
+//! - Schemas are hoisted into `components/schemas` and the ref is rewritten to
+//!   an internal pointer. The component is named after the last pointer
+//!   segment, or the file stem when the ref points at a whole file; clashes get
+//!   a numeric suffix.
+//! - Everything else (path items, parameters, responses, ...) is inlined.
+//! - Refs back into the root document become internal pointers.
+//!
+//! Remote (`http://`, `https://`) refs, and relative refs inside remote
+//! documents, are loaded through [`RemoteRefs`] by [`bundle_with`]. [`bundle`]
+//! leaves them untouched.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use serde_json::{Map, Value};
+
+use crate::error::{ParserError, Result};
+use crate::fetch::{RemoteRefs, join_url};
+
+/// Keywords holding a map of name -> schema.
+const SCHEMA_MAP_KEYWORDS: &[&str] = &[
+    "properties",
+    "patternProperties",
+    "$defs",
+    "dependentSchemas",
+];
+
+/// Keywords holding a list of schemas.
+const SCHEMA_LIST_KEYWORDS: &[&str] = &["allOf", "anyOf", "oneOf", "prefixItems"];
+
+/// Keywords holding a single schema.
+const SCHEMA_KEYWORDS: &[&str] = &[
+    "items",
+    "additionalProperties",
+    "not",
+    "contains",
+    "if",
+    "then",
+    "else",
+    "propertyNames",
+    "unevaluatedItems",
+    "unevaluatedProperties",
+];
+
+/// Load the document at `root` and every local file it references into a
+/// single document whose refs are all internal.
+pub fn bundle(root: impl AsRef<Path>) -> Result<Value> {
+    bundle_from(root.as_ref(), None, None)
+}
+
+/// Like [`bundle`], also loading remote refs through `remote`.
+pub fn bundle_with(root: impl AsRef<Path>, remote: &RemoteRefs) -> Result<Value> {
+    bundle_from(root.as_ref(), None, Some(remote))
+}
+
+/// Bundle `document`, fetched from `url`, loading the documents it
+/// references through `remote`. Relative refs resolve against `url`.
+pub(crate) fn bundle_url(url: &str, document: Value, remote: &RemoteRefs) -> Result<Value> {
+    bundle_document(document, Location::Url(url.to_string()), Some(remote))
+}
+
+/// Bundle the document at `root`, taking its content from `content` when
+/// it was already read.
+pub(crate) fn bundle_from(
+    root: &Path,
+    content: Option<&str>,
+    remote: Option<&RemoteRefs>,
+) -> Result<Value> {
+    let root = Location::File(canonical_path(root, None)?);
+    let document = match content {
+        Some(content) => crate::diagnostic::parse_document(content)?,
+        None => Bundler::new(root.clone(), remote).read_document(&root)?,
+    };
+    bundle_document(document, root, remote)
+}
+
+fn bundle_document(
+    mut document: Value,
+    root: Location,
+    remote: Option<&RemoteRefs>,
+) -> Result<Value> {
+    let mut bundler = Bundler::new(root.clone(), remote);
+    bundler.register_root_schemas(&document)?;
+    bundler.walk_document(&mut document, &root)?;
+    bundler.merge_into(&mut document);
+    Ok(document)
+}
+
+/// Replace every internal `$ref` in `document` with a copy of what it points
+/// to. Refs that would expand forever (a schema containing itself) and refs to
+/// missing targets are kept as they are. Keys next to a `$ref` (allowed in 3.1,
+/// e.g. `description`) override the target's.
+pub fn dereference(document: &mut Value) {
+    let source = document.clone();
+    inline_refs(document, &source, &mut Vec::new());
+}
+
+fn inline_refs(value: &mut Value, source: &Value, expanding: &mut Vec<String>) {
+    if let Value::Object(map) = value
+        && let Some(pointer) = ref_of_map(map).and_then(|r| r.strip_prefix('#'))
+    {
+        let pointer = pointer.to_string();
+        if expanding.contains(&pointer) {
+            return;
+        }
+        let Some(mut target) = source.pointer(&pointer).cloned() else {
+            return;
+        };
+
+        expanding.push(pointer);
+        inline_refs(&mut target, source, expanding);
+        if let Value::Object(target) = &mut target {
+            for (key, sibling) in map.iter_mut().filter(|(key, _)| *key != "$ref") {
+                inline_refs(sibling, source, expanding);
+                target.insert(key.clone(), sibling.take());
+            }
+        }
+        expanding.pop();
+
+        *value = target;
+        return;
+    }
+
+    match value {
+        Value::Object(map) => {
+            for child in map.values_mut() {
+                inline_refs(child, source, expanding);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                inline_refs(item, source, expanding);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// A document taking part in the bundle.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Location {
+    File(PathBuf),
+    Url(String),
+}
+
+impl Location {
+    /// File stem or last URL path segment without its extension.
+    fn stem(&self) -> Option<&str> {
+        match self {
+            Location::File(path) => path.file_stem().and_then(|stem| stem.to_str()),
+            Location::Url(url) => url
+                .split(['?', '#'])
+                .next()
+                .and_then(|path| path.rsplit('/').next())
+                .map(|name| name.split('.').next().unwrap_or(name))
+                .filter(|stem| !stem.is_empty()),
+        }
+    }
+}
+
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Location::File(path) => write!(f, "{}", path.display()),
+            Location::Url(url) => write!(f, "{}", url),
+        }
+    }
+}
+
+/// A location a `$ref` points at.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Target {
+    location: Location,
+    /// JSON pointer into the document; empty for the whole document.
+    pointer: String,
+}
+
+impl std::fmt::Display for Target {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}#{}", self.location, self.pointer)
+    }
+}
+
+struct Bundler<'a> {
+    root: Location,
+    remote: Option<&'a RemoteRefs>,
+    documents: HashMap<Location, Value>,
+    /// Component names of schemas already hoisted (or being hoisted).
+    schema_names: HashMap<Target, String>,
+    /// Names taken from root schemas that are only a ref to another file.
+    reserved_names: HashMap<Target, String>,
+    used_names: HashSet<String>,
+    schemas: BTreeMap<String, Value>,
+    /// Non-schema refs currently being inlined, to report cycles.
+    inlining: Vec<Target>,
+}
+
+impl<'a> Bundler<'a> {
+    fn new(root: Location, remote: Option<&'a RemoteRefs>) -> Self {
+        Self {
+            root,
+            remote,
+            documents: HashMap::new(),
+            schema_names: HashMap::new(),
+            reserved_names: HashMap::new(),
+            used_names: HashSet::new(),
+            schemas: BTreeMap::new(),
+            inlining: Vec::new(),
+        }
+    }
+
+    /// Reserve the root's schema names. A root schema that is only a ref to
+    /// another file (`Pet: { $ref: ./pet.yaml }`) keeps its name for that file.
+    fn register_root_schemas(&mut self, document: &Value) -> Result<()> {
+        let Some(Value::Object(schemas)) = document.pointer("/components/schemas") else {
+            return Ok(());
+        };
+        let root = self.root.clone();
+        for (name, schema) in schemas {
+            self.used_names.insert(name.clone());
+            if let Some(reference) = ref_of(schema)
+                && let Some(target) = self.target(&root, reference)?
+            {
+                self.reserved_names
+                    .entry(target)
+                    .or_insert_with(|| name.clone());
+            }
+        }
+        Ok(())
+    }
+
+    /// Walk non-schema objects, inlining external refs and looking for places
+    /// that hold schemas.
+    fn walk_document(&mut self, value: &mut Value, location: &Location) -> Result<()> {
+        match value {
+            Value::Object(map) => {
+                if let Some(reference) = ref_of_map(map) {
+                    let reference = reference.to_string();
+                    if let Some(target) = self.target(location, &reference)? {
+                        if target.location == self.root {
+                            map.insert("$ref".to_string(), internal_ref(&target.pointer));
+                            return Ok(());
+                        }
+                        *value = self.inline(target)?;
+                        return Ok(());
+                    }
+                }
+                for (key, child) in map.iter_mut() {
+                    match key.as_str() {
+                        // Example payloads are arbitrary JSON, not specs
+                        "example" | "examples" => {}
+                        key if key.starts_with("x-") => {}
+                        "schema" => self.walk_schema(child, location)?,
+                        "schemas" => {
+                            if let Value::Object(schemas) = child {
+                                for schema in schemas.values_mut() {
+                                    self.walk_schema(schema, location)?;
+                                }
+                            }
+                        }
+                        _ => self.walk_document(child, location)?,
+                    }
+                }
+            }
+            Value::Array(items) => {
+                for item in items {
+                    self.walk_document(item, location)?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn inline(&mut self, target: Target) -> Result<Value> {
+        if self.inlining.contains(&target) {
+            let start = self.inlining.iter().position(|t| *t == target).unwrap_or(0);
+            let chain = self.inlining[start..]
+                .iter()
+                .chain(std::iter::once(&target))
+                .map(Target::to_string)
+                .collect();
+            return Err(ParserError::CircularRef(chain));
+        }
+
+        let mut value = self.resolve(&target)?;
+        self.inlining.push(target.clone());
+        self.walk_document(&mut value, &target.location)?;
+        self.inlining.pop();
+        Ok(value)
+    }
+
+    fn walk_schema(&mut self, schema: &mut Value, location: &Location) -> Result<()> {
+        let Value::Object(map) = schema else {
+            return Ok(());
+        };
+
+        if let Some(reference) = ref_of_map(map) {
+            let reference = reference.to_string();
+            if let Some(target) = self.target(location, &reference)? {
+                let pointer = if target.location == self.root {
+                    target.pointer
+                } else {
+                    format!("/components/schemas/{}", self.hoist(target)?)
+                };
+                map.insert("$ref".to_string(), internal_ref(&pointer));
+            }
+        }
+
+        for (key, child) in map.iter_mut() {
+            let key = key.as_str();
+            if SCHEMA_MAP_KEYWORDS.contains(&key) {
+                if let Value::Object(schemas) = child {
+                    for schema in schemas.values_mut() {
+                        self.walk_schema(schema, location)?;
+                    }
+                }
+            } else if SCHEMA_LIST_KEYWORDS.contains(&key) {
+                if let Value::Array(schemas) = child {
+                    for schema in schemas {
+                        self.walk_schema(schema, location)?;
+                    }
+                }
+            } else if SCHEMA_KEYWORDS.contains(&key) {
+                self.walk_schema(child, location)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Copy the schema at `target` into the bundle, returning its component
+    /// name. The name is registered before walking the schema so recursive
+    /// schemas refer back to themselves.
+    fn hoist(&mut self, target: Target) -> Result<String> {
+        if let Some(name) = self.schema_names.get(&target) {
+            return Ok(name.clone());
+        }
+
+        let name = match self.reserved_names.remove(&target) {
+            Some(name) => name,
+            None => self.unique_name(&schema_name(&target)),
+        };
+        self.schema_names.insert(target.clone(), name.clone());
+
+        let mut schema = self.resolve(&target)?;
+        self.walk_schema(&mut schema, &target.location)?;
+        self.schemas.insert(name.clone(), schema);
+        Ok(name)
+    }
+
+    fn unique_name(&mut self, base: &str) -> String {
+        let mut name = base.to_string();
+        let mut suffix = 2;
+        while self.used_names.contains(&name) {
+            name = format!("{}{}", base, suffix);
+            suffix += 1;
+        }
+        self.used_names.insert(name.clone());
+        name
+    }
+
+    /// Where `reference`, found in the document at `location`, points. `None`
+    /// for refs that stay as they are: internal refs of the root, and remote
+    /// refs when no [`RemoteRefs`] is configured.
+    fn target(&self, location: &Location, reference: &str) -> Result<Option<Target>> {
+        let (path, pointer) = reference.split_once('#').unwrap_or((reference, ""));
+        let location = match location {
+            _ if path.is_empty() => {
+                if *location == self.root {
+                    return Ok(None);
+                }
+                location.clone()
+            }
+            Location::Url(base) => Location::Url(join_url(base, path)),
+            Location::File(_) if is_url(path) => {
+                if self.remote.is_none() {
+                    return Ok(None);
+                }
+                Location::Url(path.to_string())
+            }
+            Location::File(file) => {
+                let base_dir = file.parent().unwrap_or_else(|| Path::new("."));
+                Location::File(canonical_path(&base_dir.join(path), Some(file))?)
+            }
+        };
+
+        Ok(Some(Target {
+            location,
+            pointer: pointer.to_string(),
+        }))
+    }
+
+    fn resolve(&mut self, target: &Target) -> Result<Value> {
+        if !self.documents.contains_key(&target.location) {
+            let document = self.read_document(&target.location)?;
+            self.documents.insert(target.location.clone(), document);
+        }
+        self.documents[&target.location]
+            .pointer(&target.pointer)
+            .cloned()
+            .ok_or_else(|| ParserError::UnresolvedRef(format!("{} does not exist", target)))
+    }
+
+    fn read_document(&self, location: &Location) -> Result<Value> {
+        let content = match (location, self.remote) {
+            (Location::File(path), _) => std::fs::read_to_string(path)?,
+            (Location::Url(url), Some(remote)) => remote.load(url)?,
+            (Location::Url(url), None) => {
+                return Err(ParserError::UnresolvedRef(format!(
+                    "{} is remote and remote refs are not enabled",
+                    url
+                )));
+            }
+        };
+        crate::diagnostic::parse_document(&content)
+    }
+
+    /// Add the hoisted schemas to the root document. A root schema that was
+    /// just a ref to a file is replaced by that file's content.
+    fn merge_into(self, document: &mut Value) {
+        if self.schemas.is_empty() {
+            return;
+        }
+        let Value::Object(root) = document else {
+            return;
+        };
+        let components = root
+            .entry("components")
+            .or_insert_with(|| Value::Object(Map::new()));
+        let Value::Object(components) = components else {
+            return;
+        };
+        let schemas = components
+            .entry("schemas")
+            .or_insert_with(|| Value::Object(Map::new()));
+        if let Value::Object(schemas) = schemas {
+            schemas.extend(self.schemas);
+        }
+    }
+}
+
+fn ref_of(value: &Value) -> Option<&str> {
+    match value {
+        Value::Object(map) => ref_of_map(map),
+        _ => None,
+    }
+}
+
+fn ref_of_map(map: &Map<String, Value>) -> Option<&str> {
+    map.get("$ref").and_then(Value::as_str)
+}
+
+fn internal_ref(pointer: &str) -> Value {
+    Value::String(format!("#{}", pointer))
+}
+
+/// Component name for a hoisted schema: the last pointer segment, or the file
+/// stem for a whole-file ref.
+fn schema_name(target: &Target) -> String {
+    let segment = target
+        .pointer
+        .rsplit('/')
+        .next()
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| segment.replace("~1", "/").replace("~0", "~"));
+    segment.unwrap_or_else(|| target.location.stem().unwrap_or("Schema").to_string())
+}
+
+fn canonical_path(path: &Path, referenced_from: Option<&Path>) -> Result<PathBuf> {
+    path.canonicalize().map_err(|e| match referenced_from {
+        Some(from) => ParserError::UnresolvedRef(format!(
+            "cannot read {} (referenced from {}): {}",
+            path.display(),
+            from.display(),
+            e
+        )),
+        None => ParserError::Io(e),
+    })
+}
+
+fn is_url(reference: &str) -> bool {
+    reference.starts_with("http://") || reference.starts_with("https://")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn write_files(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("oas-gen-bundle-{}-{}", name, std::process::id()));
+        for (path, content) in files {
+            let path = dir.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_bundle_split_spec() {
+        let dir = write_files(
+            "split",
+            &[
+                (
+                    "openapi.yaml",
+                    r##"
+openapi: 3.1.0
+info: { title: Pets, version: 1.0.0 }
+paths:
+  /pets:
+    $ref: paths/pets.yaml
+components:
+  schemas:
+    Pet:
+      $ref: ./schemas/pet.yaml
+    Error:
+      type: object
+"##,
+                ),
+                (
+                    "paths/pets.yaml",
+                    r##"
+get:
+  parameters:
+    - $ref: "../common.yaml#/components/parameters/Limit"
+  responses:
+    "200":
+      description: ok
+      content:
+        application/json:
+          schema:
+            type: array
+            items:
+              $ref: ../schemas/pet.yaml
+    default:
+      description: error
+      content:
+        application/json:
+          schema:
+            $ref: "../openapi.yaml#/components/schemas/Error"
+"##,
+                ),
+                (
+                    "common.yaml",
+                    r##"
+components:
+  parameters:
+    Limit:
+      name: limit
+      in: query
+      schema:
+        type: integer
+"##,
+                ),
+                (
+                    "schemas/pet.yaml",
+                    r##"
+type: object
+properties:
+  tag:
+    $ref: tag.yaml
+  children:
+    type: array
+    items:
+      $ref: "#"
+"##,
+                ),
+                ("schemas/tag.yaml", "type: string\n"),
+            ],
+        );
+
+        let document = bundle(dir.join("openapi.yaml")).unwrap();
+        let get = &document["paths"]["/pets"]["get"];
+        assert_eq!(get["parameters"][0]["name"], "limit");
+
+        let responses = &get["responses"];
+        assert_eq!(
+            responses["200"]["content"]["application/json"]["schema"]["items"],
+            json!({ "$ref": "#/components/schemas/Pet" })
+        );
+        assert_eq!(
+            responses["default"]["content"]["application/json"]["schema"],
+            json!({ "$ref": "#/components/schemas/Error" })
+        );
+
+        let schemas = &document["components"]["schemas"];
+        assert_eq!(
+            schemas["Pet"]["properties"]["tag"],
+            json!({ "$ref": "#/components/schemas/tag" })
+        );
+        assert_eq!(
+            schemas["Pet"]["properties"]["children"]["items"],
+            json!({ "$ref": "#/components/schemas/Pet" })
+        );
+        assert_eq!(schemas["tag"], json!({ "type": "string" }));
+
+        let spec = crate::parse_value(document).unwrap();
+        assert_eq!(spec.info.title, "Pets");
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_bundle_name_clash_and_errors() {
+        let dir = write_files(
+            "errors",
+            &[
+                (
+                    "openapi.json",
+                    r##"{
+                        "openapi": "3.1.0",
+                        "info": { "title": "T", "version": "1" },
+                        "paths": {},
+                        "components": {
+                            "schemas": {
+                                "User": { "type": "object" },
+                                "Account": {
+                                    "properties": { "user": { "$ref": "./models.json#/User" } }
+                                }
+                            }
+                        }
+                    }"##,
+                ),
+                ("models.json", r#"{ "User": { "type": "string" } }"#),
+                (
+                    "missing.yaml",
+                    "openapi: 3.1.0\npaths:\n  /a:\n    $ref: ./nope.yaml\n",
+                ),
+                (
+                    "cycle.yaml",
+                    "openapi: 3.1.0\npaths:\n  /a:\n    $ref: ./loop.yaml\n",
+                ),
+                ("loop.yaml", "get:\n  $ref: ./loop.yaml\n"),
+            ],
+        );
+
+        let document = bundle(dir.join("openapi.json")).unwrap();
+        let schemas = &document["components"]["schemas"];
+        assert_eq!(
+            schemas["Account"]["properties"]["user"],
+            json!({ "$ref": "#/components/schemas/User2" })
+        );
+        assert_eq!(schemas["User2"], json!({ "type": "string" }));
+
+        assert!(matches!(
+            bundle(dir.join("missing.yaml")),
+            Err(ParserError::UnresolvedRef(_))
+        ));
+        let Err(ParserError::CircularRef(chain)) = bundle(dir.join("cycle.yaml")) else {
+            panic!("expected a circular reference error");
+        };
+        assert_eq!(chain.len(), 2);
+        assert!(
+            chain
+                .iter()
+                .all(|location| location.ends_with("loop.yaml#"))
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_dereference() {
+        let mut document = json!({
+            "paths": {
+                "/pets": {
+                    "get": {
+                        "responses": {
+                            "200": { "$ref": "#/components/responses/Pets" }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "responses": {
+                    "Pets": {
+                        "description": "Pets",
+                        "content": {
+                            "application/json": {
+                                "schema": {
+                                    "$ref": "#/components/schemas/Pet",
+                                    "description": "A pet"
+                                }
+                            }
+                        }
+                    }
+                },
+                "schemas": {
+                    "Pet": {
+                        "type": "object",
+                        "properties": {
+                            "parent": { "$ref": "#/components/schemas/Pet" },
+                            "owner": { "$ref": "#/components/schemas/Missing" }
+                        }
+                    }
+                }
+            }
+        });
+
+        dereference(&mut document);
+
+        let schema = &document["paths"]["/pets"]["get"]["responses"]["200"]["content"]["application/json"]
+            ["schema"];
+        assert_eq!(schema["type"], json!("object"));
+        assert_eq!(schema["description"], json!("A pet"));
+        // Recursive and dangling refs are kept
+        assert_eq!(
+            schema["properties"]["parent"],
+            json!({ "$ref": "#/components/schemas/Pet" })
+        );
+        assert_eq!(
+            schema["properties"]["owner"],
+            json!({ "$ref": "#/components/schemas/Missing" })
+        );
+    }
+
+    #[test]
+    fn test_bundle_remote_refs() {
+        let dir = write_files(
+            "remote",
+            &[(
+                "openapi.yaml",
+                r##"
+openapi: 3.1.0
+info: { title: Pets, version: 1.0.0 }
+paths: {}
+components:
+  schemas:
+    Listing:
+      properties:
+        pet:
+          $ref: https://schemas.acme.dev/pets/pet.yaml
+"##,
+            )],
+        );
+        let root = dir.join("openapi.yaml");
+
+        let remote = RemoteRefs::new(|url: &str| -> Result<String> {
+            match url {
+                "https://schemas.acme.dev/pets/pet.yaml" => {
+                    Ok("properties:\n  tag:\n    $ref: ../common/tag.yaml\n".to_string())
+                }
+                "https://schemas.acme.dev/common/tag.yaml" => Ok("type: string\n".to_string()),
+                _ => Err(ParserError::UnresolvedRef(url.to_string())),
+            }
+        })
+        .allow("https://schemas.acme.dev/");
+        let document = bundle_with(&root, &remote).unwrap();
+        let schemas = &document["components"]["schemas"];
+        assert_eq!(
+            schemas["Listing"]["properties"]["pet"],
+            json!({ "$ref": "#/components/schemas/pet" })
+        );
+        assert_eq!(
+            schemas["pet"]["properties"]["tag"],
+            json!({ "$ref": "#/components/schemas/tag" })
+        );
+        assert_eq!(schemas["tag"], json!({ "type": "string" }));
+
+        // Without remote refs enabled the URL is kept as is
+        let document = bundle(&root).unwrap();
+        assert_eq!(
+            document["components"]["schemas"]["Listing"]["properties"]["pet"]["$ref"],
+            "https://schemas.acme.dev/pets/pet.yaml"
+        );
+
+        let denied = RemoteRefs::new(|_: &str| -> Result<String> { Ok(String::new()) })
+            .allow("https://internal.acme.dev/");
+        assert!(bundle_with(&root, &denied).is_err());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
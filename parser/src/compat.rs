@@ -0,0 +1,265 @@
+//! Normalizes documents into the shape the `oas3` model can hold.
+//!
+//! `oas3` models OpenAPI 3.1. Before a document is deserialized, 3.0 schema
+//! keywords are upgraded to their 3.1 equivalents so the IR builder only has
+//! to understand one dialect:
+//!
+//! - `type: T, nullable: true` becomes `type: [T, "null"]`
+//! - `maximum: n, exclusiveMaximum: true` becomes `exclusiveMaximum: n`
+//!   (likewise for `minimum`)
+//!
+//! `oas3` also drops the `not` keyword and rejects boolean schemas where it
+//! expects a schema object (properties, `allOf`/`anyOf`/`oneOf` members, ...).
+//! These are moved into extensions so later stages can see them:
+//!
+//! - `not: <schema>` becomes `x-oas-gen-not: <schema>`
+//! - a boolean schema `b` becomes `{ "x-oas-gen-boolean-schema": b }`
+//!
+//! In 3.1 documents, `$dynamicRef`s are first replaced with equivalent
+//! `$ref`s (see [`crate::dynamic_ref`]).
+//!
+//! [`restore`] reverses the extension rewrite on a serialized schema.
+
+use serde_json::{Map, Value};
+
+use crate::version::SpecVersion;
+
+/// Extension holding the original `not` subschema (without the `x-` prefix,
+/// as `oas3` exposes extension names).
+pub const NOT_EXTENSION: &str = "oas-gen-not";
+
+/// Extension marking a schema object that stands in for a boolean schema.
+pub const BOOLEAN_SCHEMA_EXTENSION: &str = "oas-gen-boolean-schema";
+
+/// Keywords holding a map of name -> schema.
+const SCHEMA_MAP_KEYWORDS: &[&str] = &["properties", "patternProperties", "$defs"];
+
+/// Keywords holding a list of schemas.
+const SCHEMA_LIST_KEYWORDS: &[&str] = &["allOf", "anyOf", "oneOf", "prefixItems"];
+
+/// Keywords holding a single schema that `oas3` also accepts as a boolean.
+const SCHEMA_KEYWORDS: &[&str] = &["items", "additionalProperties"];
+
+/// Normalize a whole OpenAPI document of the given version.
+pub fn normalize(document: &mut Value, version: SpecVersion) {
+    if version == SpecVersion::V3_1 {
+        crate::dynamic_ref::resolve(document);
+    }
+    walk_document(document, version);
+}
+
+/// Undo [`normalize`] on a single schema, e.g. to show its original JSON.
+pub fn restore(schema: &mut Value) {
+    match schema {
+        Value::Object(map) => {
+            if map.len() == 1
+                && let Some(Value::Bool(b)) = map.get(&extension_key(BOOLEAN_SCHEMA_EXTENSION))
+            {
+                *schema = Value::Bool(*b);
+                return;
+            }
+            if let Some(not) = map.remove(&extension_key(NOT_EXTENSION)) {
+                map.insert("not".to_string(), not);
+            }
+            for value in map.values_mut() {
+                restore(value);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                restore(item);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn extension_key(name: &str) -> String {
+    format!("x-{}", name)
+}
+
+/// Walk non-schema objects looking for places that hold schemas.
+fn walk_document(value: &mut Value, version: SpecVersion) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                match key.as_str() {
+                    // Example payloads are arbitrary JSON, not schemas
+                    "example" | "examples" => {}
+                    key if key.starts_with("x-") => {}
+                    "schema" => normalize_schema_slot(child, version),
+                    "schemas" => {
+                        if let Value::Object(schemas) = child {
+                            for schema in schemas.values_mut() {
+                                normalize_schema_slot(schema, version);
+                            }
+                        }
+                    }
+                    _ => walk_document(child, version),
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                walk_document(item, version);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Normalize a schema in a position where `oas3` requires an object.
+fn normalize_schema_slot(schema: &mut Value, version: SpecVersion) {
+    if let Value::Bool(b) = schema {
+        let mut marker = Map::new();
+        marker.insert(extension_key(BOOLEAN_SCHEMA_EXTENSION), Value::Bool(*b));
+        *schema = Value::Object(marker);
+    } else {
+        normalize_schema(schema, version);
+    }
+}
+
+fn normalize_schema(schema: &mut Value, version: SpecVersion) {
+    let Value::Object(map) = schema else {
+        return;
+    };
+
+    if version == SpecVersion::V3_0 {
+        upgrade_schema_keywords(map);
+    }
+
+    if let Some(not) = map.remove("not") {
+        map.insert(extension_key(NOT_EXTENSION), not);
+    }
+
+    for (key, child) in map.iter_mut() {
+        let key = key.as_str();
+        if SCHEMA_MAP_KEYWORDS.contains(&key) {
+            if let Value::Object(schemas) = child {
+                for schema in schemas.values_mut() {
+                    normalize_schema_slot(schema, version);
+                }
+            }
+        } else if SCHEMA_LIST_KEYWORDS.contains(&key) {
+            if let Value::Array(schemas) = child {
+                for schema in schemas {
+                    normalize_schema_slot(schema, version);
+                }
+            }
+        } else if SCHEMA_KEYWORDS.contains(&key) {
+            normalize_schema(child, version);
+        }
+    }
+}
+
+/// Rewrite OpenAPI 3.0 schema keywords to their 3.1 form.
+fn upgrade_schema_keywords(map: &mut Map<String, Value>) {
+    if let Some(nullable) = map.remove("nullable")
+        && nullable == Value::Bool(true)
+    {
+        match map.get_mut("type") {
+            Some(ty @ Value::String(_)) => {
+                *ty = Value::Array(vec![ty.take(), Value::from("null")]);
+            }
+            Some(Value::Array(types)) if !types.contains(&Value::from("null")) => {
+                types.push(Value::from("null"));
+            }
+            _ => {}
+        }
+    }
+
+    for (exclusive, bound) in [
+        ("exclusiveMaximum", "maximum"),
+        ("exclusiveMinimum", "minimum"),
+    ] {
+        if let Some(Value::Bool(is_exclusive)) = map.get(exclusive).cloned() {
+            map.remove(exclusive);
+            if is_exclusive && let Some(limit) = map.remove(bound) {
+                map.insert(exclusive.to_string(), limit);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_normalize_and_restore_round_trip() {
+        let original = json!({
+            "openapi": "3.1.0",
+            "components": {
+                "schemas": {
+                    "NotString": { "not": { "type": "string" } },
+                    "Flag": { "anyOf": [true, { "type": "string" }] },
+                    "Never": false
+                }
+            }
+        });
+
+        let mut document = original.clone();
+        normalize(&mut document, SpecVersion::V3_1);
+
+        let schemas = &document["components"]["schemas"];
+        assert_eq!(
+            schemas["NotString"],
+            json!({ "x-oas-gen-not": { "type": "string" } })
+        );
+        assert_eq!(
+            schemas["Flag"]["anyOf"][0],
+            json!({ "x-oas-gen-boolean-schema": true })
+        );
+        assert_eq!(
+            schemas["Never"],
+            json!({ "x-oas-gen-boolean-schema": false })
+        );
+
+        restore(&mut document);
+        assert_eq!(document, original);
+    }
+
+    #[test]
+    fn test_normalize_leaves_property_named_not() {
+        let mut document = json!({
+            "components": {
+                "schemas": {
+                    "Filter": { "properties": { "not": { "type": "boolean" } } }
+                }
+            }
+        });
+        let original = document.clone();
+
+        normalize(&mut document, SpecVersion::V3_1);
+        assert_eq!(document, original);
+    }
+
+    #[test]
+    fn test_upgrade_3_0_keywords() {
+        let mut document = json!({
+            "components": {
+                "schemas": {
+                    "Age": {
+                        "type": "integer",
+                        "nullable": true,
+                        "minimum": 0,
+                        "exclusiveMinimum": true,
+                        "maximum": 150,
+                        "exclusiveMaximum": false
+                    }
+                }
+            }
+        });
+
+        normalize(&mut document, SpecVersion::V3_0);
+        assert_eq!(
+            document["components"]["schemas"]["Age"],
+            json!({
+                "type": ["integer", "null"],
+                "exclusiveMinimum": 0,
+                "maximum": 150
+            })
+        );
+    }
+}
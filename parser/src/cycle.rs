@@ -0,0 +1,140 @@
+//! Detection of `$ref` chains that lead back to themselves.
+//!
+//! A component that is only a `$ref` to another (`A: { $ref: B }`) is an
+//! alias. When aliases form a loop (`A -> B -> A`) there is nothing to
+//! resolve them to, and `oas3` follows them until the stack overflows.
+//! [`check`] finds such loops first and reports the whole chain.
+//!
+//! Recursive schemas (`Node: { properties: { next: { $ref: Node } } }`)
+//! are fine: the `$ref` points at a schema with content of its own.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde_json::Value;
+
+use crate::diagnostic::escape;
+use crate::error::{ParserError, Result};
+
+/// Fields holding user data, which may contain anything.
+const DATA_FIELDS: &[&str] = &["example", "examples", "default", "enum", "const"];
+
+/// Fail with [`ParserError::CircularRef`] if following internal `$ref`s
+/// from some object in `document` never reaches anything but another
+/// `$ref`.
+pub fn check(document: &Value) -> Result<()> {
+    let mut refs = BTreeMap::new();
+    collect(document, "", &mut refs);
+
+    let mut checked = BTreeSet::new();
+    for start in refs.keys() {
+        let mut chain: Vec<&str> = Vec::new();
+        let mut current = start.as_str();
+        while !checked.contains(current) {
+            if let Some(position) = chain.iter().position(|pointer| *pointer == current) {
+                let mut cycle: Vec<String> = chain[position..]
+                    .iter()
+                    .map(|pointer| format!("#{}", pointer))
+                    .collect();
+                cycle.push(format!("#{}", current));
+                return Err(ParserError::CircularRef(cycle));
+            }
+            chain.push(current);
+            // Targets that aren't `$ref`s themselves end the chain
+            match refs.get(current) {
+                Some(target) if refs.contains_key(target.as_str()) => current = target,
+                _ => break,
+            }
+        }
+        checked.extend(chain);
+    }
+    Ok(())
+}
+
+/// Map the pointer of every object with an internal `$ref` to its target.
+fn collect(value: &Value, pointer: &str, refs: &mut BTreeMap<String, String>) {
+    match value {
+        Value::Object(map) => {
+            if let Some(Value::String(reference)) = map.get("$ref")
+                && let Some(target) = reference.strip_prefix('#')
+            {
+                refs.insert(pointer.to_string(), target.to_string());
+            }
+            for (key, child) in map {
+                if DATA_FIELDS.contains(&key.as_str()) || key.starts_with("x-") {
+                    continue;
+                }
+                collect(child, &format!("{}/{}", pointer, escape(key)), refs);
+            }
+        }
+        Value::Array(items) => {
+            for (index, item) in items.iter().enumerate() {
+                collect(item, &format!("{}/{}", pointer, index), refs);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_alias_cycle() {
+        let document = json!({
+            "components": {
+                "schemas": {
+                    "Pet": { "$ref": "#/components/schemas/Animal" },
+                    "Animal": { "$ref": "#/components/schemas/Creature" },
+                    "Creature": { "$ref": "#/components/schemas/Animal" }
+                }
+            },
+            "paths": {
+                "/pets": {
+                    "get": {
+                        "responses": {
+                            "200": {
+                                "description": "ok",
+                                "content": {
+                                    "application/json": {
+                                        "schema": { "$ref": "#/components/schemas/Pet" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        });
+
+        let Err(ParserError::CircularRef(chain)) = check(&document) else {
+            panic!("expected a circular reference");
+        };
+        assert_eq!(
+            chain,
+            [
+                "#/components/schemas/Animal",
+                "#/components/schemas/Creature",
+                "#/components/schemas/Animal",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_recursive_schema_is_not_a_cycle() {
+        let document = json!({
+            "components": {
+                "schemas": {
+                    "Node": {
+                        "type": "object",
+                        "properties": { "next": { "$ref": "#/components/schemas/Node" } }
+                    },
+                    "List": { "$ref": "#/components/schemas/Node" },
+                    "Self": { "$ref": "#/components/schemas/Missing" }
+                }
+            }
+        });
+        check(&document).unwrap();
+    }
+}
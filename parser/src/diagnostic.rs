@@ -0,0 +1,685 @@
+//! Structured parse errors.
+//!
+//! A [`Diagnostic`] says which format a document was read as, what went wrong,
+//! where (line/column), and the JSON pointer of the nearest enclosing value,
+//! e.g. `YAML error at line 12, column 9 (/paths/~1pets/get): ...`.
+
+use std::io::{BufRead, BufReader, Read};
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::error::{ParserError, Result};
+
+/// Format a document was read as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceFormat {
+    Json,
+    Yaml,
+}
+
+impl SourceFormat {
+    /// JSON if the document starts with `{`, YAML otherwise. JSON is valid
+    /// YAML, but the JSON parser reports much better errors for it.
+    pub fn detect(input: &str) -> Self {
+        if input
+            .trim_start_matches('\u{feff}')
+            .trim_start()
+            .starts_with('{')
+        {
+            SourceFormat::Json
+        } else {
+            SourceFormat::Yaml
+        }
+    }
+}
+
+impl From<crate::fmt::SpecFormat> for SourceFormat {
+    fn from(format: crate::fmt::SpecFormat) -> Self {
+        match format {
+            crate::fmt::SpecFormat::Json => SourceFormat::Json,
+            crate::fmt::SpecFormat::Yaml => SourceFormat::Yaml,
+        }
+    }
+}
+
+impl std::fmt::Display for SourceFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SourceFormat::Json => write!(f, "JSON"),
+            SourceFormat::Yaml => write!(f, "YAML"),
+        }
+    }
+}
+
+/// A document that could not be parsed or is not a valid OpenAPI spec.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// Format the source was read as; `None` when only the parsed value was
+    /// available (e.g. a bundled spec).
+    pub format: Option<SourceFormat>,
+    pub message: String,
+    /// 1-based line.
+    pub line: Option<usize>,
+    /// 1-based column.
+    pub column: Option<usize>,
+    /// JSON pointer of the nearest value containing the error.
+    pub pointer: Option<String>,
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.format {
+            Some(format) => write!(f, "{} error", format)?,
+            None => write!(f, "Invalid spec")?,
+        }
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(f, " at line {}, column {}", line, column)?,
+            (Some(line), None) => write!(f, " at line {}", line)?,
+            _ => {}
+        }
+        if let Some(pointer) = &self.pointer {
+            let pointer = if pointer.is_empty() { "/" } else { pointer };
+            write!(f, " ({})", pointer)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+/// Parse `input` as JSON or YAML (see [`SourceFormat::detect`]).
+pub fn parse_document(input: &str) -> Result<Value> {
+    parse_document_as(input, SourceFormat::detect(input))
+}
+
+/// Parse `input` as `format`, e.g. the one its `Content-Type` names.
+pub fn parse_document_as(input: &str, format: SourceFormat) -> Result<Value> {
+    let (message, location) = match format {
+        SourceFormat::Json => match serde_json::from_str(input) {
+            Ok(value) => return Ok(value),
+            Err(e) => json_error(e),
+        },
+        SourceFormat::Yaml => {
+            match serde_saphyr::from_str_with_options(input, crate::yaml::options()) {
+                Ok(value) => return Ok(value),
+                Err(e) => yaml_error(e),
+            }
+        }
+    };
+
+    let pointer = location.map(|(line, column)| match format {
+        SourceFormat::Json => json_pointer_at(input, offset_of(input, line, column)),
+        SourceFormat::Yaml => yaml_pointer_at(input, line),
+    });
+    Err(syntax_error(format, message, location, pointer))
+}
+
+/// Read a JSON or YAML document from `reader` without buffering it as a
+/// string. Syntax errors have a line/column but no pointer.
+pub fn read_document(reader: impl Read) -> Result<Value> {
+    let mut reader = BufReader::new(reader);
+    let format = SourceFormat::detect(&String::from_utf8_lossy(reader.fill_buf()?));
+    let (message, location) = match format {
+        SourceFormat::Json => match serde_json::from_reader(reader) {
+            Ok(value) => return Ok(value),
+            Err(e) if e.is_io() => return Err(ParserError::Json(e)),
+            Err(e) => json_error(e),
+        },
+        SourceFormat::Yaml => {
+            match serde_saphyr::from_reader_with_options(reader, crate::yaml::options()) {
+                Ok(value) => return Ok(value),
+                Err(e) => yaml_error(e),
+            }
+        }
+    };
+    Err(syntax_error(format, message, location, None))
+}
+
+type Location = Option<(usize, usize)>;
+
+fn json_error(e: serde_json::Error) -> (String, Location) {
+    let location = (e.line() > 0).then(|| (e.line(), e.column()));
+    (e.to_string(), location)
+}
+
+fn yaml_error(e: serde_saphyr::Error) -> (String, Location) {
+    let location = e
+        .location()
+        .map(|l| (l.line() as usize, l.column() as usize));
+    (e.to_string(), location)
+}
+
+fn syntax_error(
+    format: SourceFormat,
+    message: String,
+    location: Location,
+    pointer: Option<String>,
+) -> ParserError {
+    let (line, column) = location.unzip();
+    ParserError::Parse(Diagnostic {
+        format: Some(format),
+        message: strip_location(&message),
+        line,
+        column,
+        pointer,
+    })
+}
+
+/// Deserialize a spec, reporting the pointer of the offending value.
+pub(crate) fn deserialize_spec(value: &Value) -> Result<oas3::Spec> {
+    oas3::Spec::deserialize(value).map_err(|e| {
+        let mut pointer = String::new();
+        narrow(value, Part::Spec, &mut pointer);
+        ParserError::Parse(Diagnostic {
+            format: None,
+            message: e.to_string(),
+            line: None,
+            column: None,
+            pointer: Some(pointer),
+        })
+    })
+}
+
+/// Deserialize a spec, consuming `value` so its strings move into the spec
+/// instead of being copied. Errors have no pointer.
+pub(crate) fn deserialize_spec_owned(value: Value) -> Result<oas3::Spec> {
+    oas3::Spec::deserialize(value).map_err(|e| {
+        ParserError::Parse(Diagnostic {
+            format: None,
+            message: e.to_string(),
+            line: None,
+            column: None,
+            pointer: None,
+        })
+    })
+}
+
+/// Fill in the format and line/column of a diagnostic that only has a pointer
+/// by finding that pointer in `input`.
+pub(crate) fn locate(error: ParserError, input: &str) -> ParserError {
+    let ParserError::Parse(mut diagnostic) = error else {
+        return error;
+    };
+    if diagnostic.format.is_some() {
+        return ParserError::Parse(diagnostic);
+    }
+
+    let format = SourceFormat::detect(input);
+    diagnostic.format = Some(format);
+    if let Some(pointer) = &diagnostic.pointer {
+        let found = match format {
+            SourceFormat::Json => find_json_pointer(input, pointer),
+            SourceFormat::Yaml => find_yaml_pointer(input, pointer),
+        };
+        if let Some((line, column)) = found {
+            diagnostic.line = Some(line);
+            diagnostic.column = Some(column);
+        }
+    }
+    ParserError::Parse(diagnostic)
+}
+
+/// serde_json and serde-saphyr append the position to their messages; it is
+/// reported separately here.
+fn strip_location(message: &str) -> String {
+    match message.rfind(" at line ") {
+        Some(index) => message[..index].to_string(),
+        None => message.to_string(),
+    }
+}
+
+/// Parts of a spec that can be deserialized on their own. oas3 flattens
+/// extensions into every object, which hides the path of a serde error, so
+/// the failing part is found by checking them one by one.
+#[derive(Clone, Copy)]
+enum Part {
+    Spec,
+    Info,
+    Server,
+    Tag,
+    PathItem,
+    Operation,
+    Parameter,
+    RequestBody,
+    Response,
+    Components,
+    SecurityScheme,
+    Schema,
+    SchemaOrBool,
+}
+
+impl Part {
+    fn is_valid(self, value: &Value) -> bool {
+        use oas3::spec::*;
+
+        fn ok<'de, T: serde::Deserialize<'de>>(value: &'de Value) -> bool {
+            T::deserialize(value).is_ok()
+        }
+
+        match self {
+            Part::Spec => ok::<Spec>(value),
+            Part::Info => ok::<Info>(value),
+            Part::Server => ok::<Server>(value),
+            Part::Tag => ok::<Tag>(value),
+            Part::PathItem => ok::<PathItem>(value),
+            Part::Operation => ok::<Operation>(value),
+            Part::Parameter => ok::<ObjectOrReference<Parameter>>(value),
+            Part::RequestBody => ok::<ObjectOrReference<RequestBody>>(value),
+            Part::Response => ok::<ObjectOrReference<Response>>(value),
+            Part::Components => ok::<Components>(value),
+            Part::SecurityScheme => ok::<ObjectOrReference<SecurityScheme>>(value),
+            Part::Schema => ok::<ObjectOrReference<ObjectSchema>>(value),
+            Part::SchemaOrBool => ok::<Schema>(value),
+        }
+    }
+
+    /// Members checked before blaming the part itself: `(key, every entry of
+    /// it rather than the member itself, part)`.
+    fn children(self) -> &'static [(&'static str, bool, Part)] {
+        const SCHEMA: &[(&str, bool, Part)] = &[
+            ("properties", true, Part::Schema),
+            ("items", false, Part::SchemaOrBool),
+            ("additionalProperties", false, Part::SchemaOrBool),
+            ("allOf", true, Part::Schema),
+            ("oneOf", true, Part::Schema),
+            ("anyOf", true, Part::Schema),
+        ];
+
+        match self {
+            Part::Spec => &[
+                ("info", false, Part::Info),
+                ("servers", true, Part::Server),
+                ("tags", true, Part::Tag),
+                ("paths", true, Part::PathItem),
+                ("webhooks", true, Part::PathItem),
+                ("components", false, Part::Components),
+            ],
+            Part::PathItem => &[
+                ("get", false, Part::Operation),
+                ("put", false, Part::Operation),
+                ("post", false, Part::Operation),
+                ("delete", false, Part::Operation),
+                ("options", false, Part::Operation),
+                ("head", false, Part::Operation),
+                ("patch", false, Part::Operation),
+                ("trace", false, Part::Operation),
+                ("parameters", true, Part::Parameter),
+                ("servers", true, Part::Server),
+            ],
+            Part::Operation => &[
+                ("parameters", true, Part::Parameter),
+                ("requestBody", false, Part::RequestBody),
+                ("responses", true, Part::Response),
+                ("servers", true, Part::Server),
+            ],
+            Part::Components => &[
+                ("schemas", true, Part::Schema),
+                ("responses", true, Part::Response),
+                ("parameters", true, Part::Parameter),
+                ("requestBodies", true, Part::RequestBody),
+                ("securitySchemes", true, Part::SecurityScheme),
+                ("pathItems", true, Part::PathItem),
+            ],
+            Part::Schema | Part::SchemaOrBool => SCHEMA,
+            _ => &[],
+        }
+    }
+}
+
+/// Append to `pointer` the path of the innermost invalid part of `value`.
+fn narrow(value: &Value, part: Part, pointer: &mut String) {
+    for &(key, each, child) in part.children() {
+        let Some(member) = value.get(key) else {
+            continue;
+        };
+        let entries: Vec<(String, &Value)> = match (each, member) {
+            (false, _) => vec![(String::new(), member)],
+            (true, Value::Object(map)) => map.iter().map(|(k, v)| (escape(k), v)).collect(),
+            (true, Value::Array(items)) => items
+                .iter()
+                .enumerate()
+                .map(|(i, v)| (i.to_string(), v))
+                .collect(),
+            (true, _) => Vec::new(),
+        };
+        for (entry, entry_value) in entries {
+            if !child.is_valid(entry_value) {
+                pointer.push('/');
+                pointer.push_str(&escape(key));
+                if each {
+                    pointer.push('/');
+                    pointer.push_str(&entry);
+                }
+                return narrow(entry_value, child, pointer);
+            }
+        }
+    }
+
+    // No invalid part below: blame the member without which this one is valid
+    if let Value::Object(map) = value {
+        for key in map.keys() {
+            let mut rest = map.clone();
+            rest.remove(key);
+            if part.is_valid(&Value::Object(rest)) {
+                pointer.push('/');
+                pointer.push_str(&escape(key));
+                return;
+            }
+        }
+    }
+}
+
+pub(crate) fn escape(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+fn pointer(path: &[String]) -> String {
+    path.iter()
+        .map(|segment| format!("/{}", escape(segment)))
+        .collect()
+}
+
+/// Byte offset of a 1-based line/column.
+fn offset_of(input: &str, line: usize, column: usize) -> usize {
+    let line_start: usize = input
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1))
+        .map(str::len)
+        .sum();
+    (line_start + column.saturating_sub(1)).min(input.len())
+}
+
+/// 1-based line/column of a byte offset.
+fn line_column(input: &str, offset: usize) -> (usize, usize) {
+    let before = &input[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = before.len() - before.rfind('\n').map_or(0, |i| i + 1) + 1;
+    (line, column)
+}
+
+/// Walk a JSON document, calling `visit(offset, path)` at every key and at the
+/// start of every value. Tolerates malformed input.
+fn walk_json(input: &str, mut visit: impl FnMut(usize, &[String]) -> bool) {
+    enum Frame {
+        Object { key: Option<String> },
+        Array { index: usize },
+    }
+
+    let mut stack: Vec<Frame> = Vec::new();
+    let mut path: Vec<String> = Vec::new();
+    let mut expect_key = false;
+    let mut key_offset = 0;
+    let mut chars = input.char_indices().peekable();
+
+    while let Some((offset, c)) = chars.next() {
+        match c {
+            '"' => {
+                let mut string = String::new();
+                while let Some((_, c)) = chars.next() {
+                    match c {
+                        '\\' => {
+                            if let Some((_, escaped)) = chars.next() {
+                                string.push(escaped);
+                            }
+                        }
+                        '"' => break,
+                        c => string.push(c),
+                    }
+                }
+                if expect_key {
+                    if let Some(Frame::Object { key }) = stack.last_mut() {
+                        *key = Some(string);
+                    }
+                    key_offset = offset;
+                    expect_key = false;
+                } else if !visit(offset, &path) {
+                    return;
+                }
+            }
+            ':' => {
+                if let Some(Frame::Object { key: Some(key) }) = stack.last() {
+                    path.push(key.clone());
+                    if !visit(key_offset, &path) {
+                        return;
+                    }
+                }
+            }
+            ',' => match stack.last_mut() {
+                Some(Frame::Object { key }) => {
+                    if key.take().is_some() {
+                        path.pop();
+                    }
+                    expect_key = true;
+                }
+                Some(Frame::Array { index }) => {
+                    *index += 1;
+                    path.pop();
+                    path.push(index.to_string());
+                }
+                None => {}
+            },
+            '{' | '[' => {
+                if !visit(offset, &path) {
+                    return;
+                }
+                if c == '{' {
+                    stack.push(Frame::Object { key: None });
+                    expect_key = true;
+                } else {
+                    stack.push(Frame::Array { index: 0 });
+                    path.push("0".to_string());
+                }
+            }
+            '}' | ']' => {
+                match stack.pop() {
+                    Some(Frame::Object { key: Some(_) }) | Some(Frame::Array { .. }) => {
+                        path.pop();
+                    }
+                    _ => {}
+                }
+                expect_key = false;
+                // the container itself was the value of its parent's key
+                if let Some(Frame::Object { key }) = stack.last_mut()
+                    && key.take().is_some()
+                {
+                    path.pop();
+                }
+            }
+            c if c.is_whitespace() => {}
+            _ => {
+                // bare scalar: number, true, false, null
+                if !visit(offset, &path) {
+                    return;
+                }
+                while let Some((_, c)) = chars.peek() {
+                    if matches!(c, ',' | '}' | ']') || c.is_whitespace() {
+                        break;
+                    }
+                    chars.next();
+                }
+            }
+        }
+    }
+}
+
+fn json_pointer_at(input: &str, offset: usize) -> String {
+    let mut nearest = Vec::new();
+    walk_json(input, |at, path| {
+        if at > offset {
+            return false;
+        }
+        nearest = path.to_vec();
+        true
+    });
+    pointer(&nearest)
+}
+
+fn find_json_pointer(input: &str, target: &str) -> Option<(usize, usize)> {
+    let mut found = None;
+    walk_json(input, |at, path| {
+        if pointer(path) == target {
+            found = Some(line_column(input, at));
+            return false;
+        }
+        true
+    });
+    found
+}
+
+/// Walk block-style YAML line by line, calling `visit(line, column, path)` for
+/// every key and sequence item. Flow collections are treated as scalars.
+fn walk_yaml(input: &str, mut visit: impl FnMut(usize, usize, &[String]) -> bool) {
+    // (indent, segment, is sequence item)
+    let mut stack: Vec<(usize, String, bool)> = Vec::new();
+    let mut block_scalar: Option<usize> = None;
+
+    for (number, line) in input.lines().enumerate() {
+        let content = line.trim_start();
+        let mut indent = line.len() - content.len();
+        if content.is_empty() || content.starts_with('#') {
+            continue;
+        }
+        if let Some(block_indent) = block_scalar {
+            if indent > block_indent {
+                continue;
+            }
+            block_scalar = None;
+        }
+
+        let mut rest = content;
+        while rest == "-" || rest.starts_with("- ") {
+            stack.retain(|(i, _, item)| *i < indent || (*i == indent && *item));
+            match stack.last_mut() {
+                Some((i, index, true)) if *i == indent => {
+                    *index = (index.parse::<usize>().unwrap_or(0) + 1).to_string();
+                }
+                _ => stack.push((indent, "0".to_string(), true)),
+            }
+            let path: Vec<String> = stack.iter().map(|(_, s, _)| s.clone()).collect();
+            if !visit(number + 1, indent + 1, &path) {
+                return;
+            }
+            let after = rest[1..].trim_start();
+            indent += rest.len() - after.len();
+            rest = after;
+        }
+
+        let Some((key, value)) = split_key(rest) else {
+            continue;
+        };
+        stack.retain(|(i, _, _)| *i < indent);
+        stack.push((indent, key, false));
+        let path: Vec<String> = stack.iter().map(|(_, s, _)| s.clone()).collect();
+        if !visit(number + 1, indent + 1, &path) {
+            return;
+        }
+        if value.starts_with('|') || value.starts_with('>') {
+            block_scalar = Some(indent);
+        }
+    }
+}
+
+/// `key: value` → (key, value)
+fn split_key(line: &str) -> Option<(String, &str)> {
+    let (key, rest) = match line.chars().next()? {
+        quote @ ('"' | '\'') => {
+            let end = line[1..].find(quote)? + 1;
+            (line[1..end].to_string(), &line[end + 1..])
+        }
+        '{' | '[' => return None,
+        _ => {
+            let end = line
+                .find(": ")
+                .or_else(|| line.ends_with(':').then(|| line.len() - 1))?;
+            (line[..end].trim_end().to_string(), &line[end..])
+        }
+    };
+    let value = rest.trim_start().strip_prefix(':')?;
+    Some((key, value.trim_start()))
+}
+
+fn yaml_pointer_at(input: &str, line: usize) -> String {
+    let mut nearest = Vec::new();
+    walk_yaml(input, |at, _, path| {
+        if at > line {
+            return false;
+        }
+        nearest = path.to_vec();
+        true
+    });
+    pointer(&nearest)
+}
+
+fn find_yaml_pointer(input: &str, target: &str) -> Option<(usize, usize)> {
+    let mut found = None;
+    walk_yaml(input, |line, column, path| {
+        if pointer(path) == target {
+            found = Some((line, column));
+            return false;
+        }
+        true
+    });
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diagnostic(result: Result<impl std::fmt::Debug>) -> Diagnostic {
+        match result {
+            Err(ParserError::Parse(diagnostic)) => diagnostic,
+            other => panic!("expected a parse diagnostic, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_json_syntax_error() {
+        let input =
+            "{\n  \"openapi\": \"3.1.0\",\n  \"paths\": {\n    \"/pets\": { \"get\": ] }\n  }\n}";
+        let diagnostic = diagnostic(parse_document(input));
+
+        assert_eq!(diagnostic.format, Some(SourceFormat::Json));
+        assert_eq!(diagnostic.line, Some(4));
+        assert_eq!(diagnostic.pointer.as_deref(), Some("/paths/~1pets/get"));
+        assert!(!diagnostic.message.contains("at line"));
+    }
+
+    #[test]
+    fn test_yaml_syntax_error() {
+        let input = "openapi: 3.1.0\npaths:\n  /pets:\n    get:\n      summary: \"unterminated\n";
+        let diagnostic = diagnostic(parse_document(input));
+
+        assert_eq!(diagnostic.format, Some(SourceFormat::Yaml));
+        assert!(diagnostic.line.is_some());
+        assert!(
+            diagnostic
+                .pointer
+                .as_deref()
+                .is_some_and(|p| p.starts_with("/paths/~1pets/get"))
+        );
+    }
+
+    #[test]
+    fn test_invalid_spec_is_located() {
+        let input = "openapi: 3.1.0\ninfo:\n  title: Test\n  version: 1.0.0\npaths:\n  /pets:\n    get:\n      responses: 12\n";
+        let diagnostic = diagnostic(crate::parse(input));
+
+        assert_eq!(diagnostic.format, Some(SourceFormat::Yaml));
+        assert_eq!(
+            diagnostic.pointer.as_deref(),
+            Some("/paths/~1pets/get/responses")
+        );
+        assert_eq!((diagnostic.line, diagnostic.column), (Some(8), Some(7)));
+    }
+
+    #[test]
+    fn test_find_pointer() {
+        let json = "{\"tags\": [{\"name\": \"a\"}, {\"name\": \"b\"}]}";
+        assert_eq!(find_json_pointer(json, "/tags/1/name"), Some((1, 27)));
+
+        let yaml = "tags:\n  - name: a\n  - name: b\n    description: |\n      x: y\nother: 1\n";
+        assert_eq!(find_yaml_pointer(yaml, "/tags/1/name"), Some((3, 5)));
+        assert_eq!(find_yaml_pointer(yaml, "/other"), Some((6, 1)));
+        assert_eq!(find_yaml_pointer(yaml, "/tags/1/description/x"), None);
+    }
+}
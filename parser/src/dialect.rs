@@ -0,0 +1,303 @@
+//! JSON Schema dialects of OpenAPI 3.1 documents.
+//!
+//! A 3.1 document's schemas default to the OpenAPI base dialect (JSON Schema
+//! 2020-12 plus the OpenAPI vocabulary). `jsonSchemaDialect` changes the
+//! default for the whole document and `$schema` changes it for one schema
+//! and everything below it.
+//!
+//! The IR builder understands 2020-12 keywords, so schemas in older drafts
+//! are rewritten into them before deserializing:
+//!
+//! - `items: [A, B]` becomes `prefixItems: [A, B]`, and `additionalItems: S`
+//!   next to it becomes `items: S`
+//! - draft-07 `dependencies` is split into `dependentRequired` (lists of
+//!   names) and `dependentSchemas` (schemas)
+//!
+//! Any other dialect is rejected with [`ParserError::UnsupportedDialect`]
+//! rather than generating code from keywords whose meaning isn't known.
+
+use serde_json::{Map, Value};
+
+use crate::error::{ParserError, Result};
+
+/// A supported JSON Schema dialect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    /// `https://spec.openapis.org/oas/3.1/dialect/base` (and dated versions)
+    OasBase,
+    Draft2020_12,
+    Draft2019_09,
+    Draft07,
+}
+
+impl Dialect {
+    /// URIs of the supported dialects, for error messages.
+    pub const SUPPORTED: &'static [&'static str] = &[
+        "https://spec.openapis.org/oas/3.1/dialect/base",
+        "https://json-schema.org/draft/2020-12/schema",
+        "https://json-schema.org/draft/2019-09/schema",
+        "http://json-schema.org/draft-07/schema#",
+    ];
+
+    /// The dialect identified by a `jsonSchemaDialect` or `$schema` URI.
+    /// `http` and `https` and a trailing empty fragment are accepted.
+    pub fn from_uri(uri: &str) -> Option<Self> {
+        let uri = uri.trim().trim_end_matches('#');
+        let uri = uri
+            .strip_prefix("https://")
+            .or_else(|| uri.strip_prefix("http://"))?;
+        if uri.starts_with("spec.openapis.org/oas/3.1/dialect/") {
+            return Some(Dialect::OasBase);
+        }
+        match uri {
+            "json-schema.org/draft/2020-12/schema" => Some(Dialect::Draft2020_12),
+            "json-schema.org/draft/2019-09/schema" => Some(Dialect::Draft2019_09),
+            "json-schema.org/draft-07/schema" => Some(Dialect::Draft07),
+            _ => None,
+        }
+    }
+
+    fn parse(uri: &str) -> Result<Self> {
+        Self::from_uri(uri).ok_or_else(|| ParserError::UnsupportedDialect(uri.to_string()))
+    }
+
+    /// The document's default dialect, from `jsonSchemaDialect`.
+    pub fn detect(document: &Value) -> Result<Self> {
+        match document.get("jsonSchemaDialect") {
+            None => Ok(Dialect::OasBase),
+            Some(Value::String(uri)) => Self::parse(uri),
+            Some(other) => Err(ParserError::UnsupportedDialect(other.to_string())),
+        }
+    }
+
+    /// Whether schemas in this dialect use pre-2020-12 keywords.
+    fn is_legacy(self) -> bool {
+        matches!(self, Dialect::Draft2019_09 | Dialect::Draft07)
+    }
+}
+
+/// Keywords holding a map of name -> schema.
+const SCHEMA_MAP_KEYWORDS: &[&str] = &[
+    "properties",
+    "patternProperties",
+    "$defs",
+    "definitions",
+    "dependentSchemas",
+];
+
+/// Keywords holding a list of schemas.
+const SCHEMA_LIST_KEYWORDS: &[&str] = &["allOf", "anyOf", "oneOf", "prefixItems"];
+
+/// Keywords holding a single schema.
+const SCHEMA_KEYWORDS: &[&str] = &[
+    "items",
+    "additionalItems",
+    "additionalProperties",
+    "unevaluatedItems",
+    "unevaluatedProperties",
+    "propertyNames",
+    "contains",
+    "not",
+    "if",
+    "then",
+    "else",
+];
+
+/// Check the dialects a 3.1 document uses and rewrite schemas in older
+/// drafts into 2020-12 keywords.
+pub fn normalize(document: &mut Value) -> Result<()> {
+    let dialect = Dialect::detect(document)?;
+    walk_document(document, dialect)
+}
+
+/// Walk non-schema objects looking for places that hold schemas.
+fn walk_document(value: &mut Value, dialect: Dialect) -> Result<()> {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                match key.as_str() {
+                    // Example payloads are arbitrary JSON, not schemas
+                    "example" | "examples" => {}
+                    key if key.starts_with("x-") => {}
+                    "schema" => walk_schema(child, dialect)?,
+                    "schemas" => {
+                        if let Value::Object(schemas) = child {
+                            for schema in schemas.values_mut() {
+                                walk_schema(schema, dialect)?;
+                            }
+                        }
+                    }
+                    _ => walk_document(child, dialect)?,
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                walk_document(item, dialect)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn walk_schema(schema: &mut Value, dialect: Dialect) -> Result<()> {
+    let Value::Object(map) = schema else {
+        return Ok(());
+    };
+    let dialect = match map.get("$schema") {
+        Some(Value::String(uri)) => Dialect::parse(uri)?,
+        _ => dialect,
+    };
+    if dialect.is_legacy() {
+        upgrade_legacy_keywords(map, dialect);
+    }
+
+    for (key, child) in map.iter_mut() {
+        let key = key.as_str();
+        if SCHEMA_MAP_KEYWORDS.contains(&key) {
+            if let Value::Object(schemas) = child {
+                for schema in schemas.values_mut() {
+                    walk_schema(schema, dialect)?;
+                }
+            }
+        } else if SCHEMA_LIST_KEYWORDS.contains(&key) {
+            if let Value::Array(schemas) = child {
+                for schema in schemas {
+                    walk_schema(schema, dialect)?;
+                }
+            }
+        } else if SCHEMA_KEYWORDS.contains(&key) {
+            walk_schema(child, dialect)?;
+        }
+    }
+    Ok(())
+}
+
+fn upgrade_legacy_keywords(map: &mut Map<String, Value>, dialect: Dialect) {
+    if matches!(map.get("items"), Some(Value::Array(_))) {
+        let tuple = map.remove("items").unwrap_or_default();
+        map.insert("prefixItems".to_string(), tuple);
+        if let Some(rest) = map.remove("additionalItems") {
+            map.insert("items".to_string(), rest);
+        }
+    }
+
+    if dialect == Dialect::Draft07
+        && let Some(Value::Object(dependencies)) = map.remove("dependencies")
+    {
+        let (required, schemas): (Map<_, _>, Map<_, _>) = dependencies
+            .into_iter()
+            .partition(|(_, dependency)| dependency.is_array());
+        for (keyword, entries) in [
+            ("dependentRequired", required),
+            ("dependentSchemas", schemas),
+        ] {
+            if !entries.is_empty() {
+                map.insert(keyword.to_string(), Value::Object(entries));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_dialect_from_uri() {
+        assert_eq!(
+            Dialect::from_uri("https://spec.openapis.org/oas/3.1/dialect/2024-10-25"),
+            Some(Dialect::OasBase)
+        );
+        assert_eq!(
+            Dialect::from_uri("https://json-schema.org/draft-07/schema#"),
+            Some(Dialect::Draft07)
+        );
+        for uri in Dialect::SUPPORTED {
+            assert!(Dialect::from_uri(uri).is_some(), "{}", uri);
+        }
+        assert_eq!(
+            Dialect::from_uri("http://json-schema.org/draft-04/schema#"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_normalize_legacy_schemas() {
+        let mut document = json!({
+            "openapi": "3.1.0",
+            "jsonSchemaDialect": "http://json-schema.org/draft-07/schema#",
+            "components": {
+                "schemas": {
+                    "Point": {
+                        "type": "array",
+                        "items": [{ "type": "number" }, { "type": "number" }],
+                        "additionalItems": false
+                    },
+                    "Card": {
+                        "type": "object",
+                        "properties": {
+                            "dependencies": { "type": "object", "dependencies": {} }
+                        },
+                        "dependencies": {
+                            "number": ["expiry"],
+                            "billing": { "required": ["address"] }
+                        }
+                    },
+                    "Modern": {
+                        "$schema": "https://json-schema.org/draft/2020-12/schema",
+                        "type": "array",
+                        "items": { "type": "string" }
+                    }
+                }
+            }
+        });
+        normalize(&mut document).unwrap();
+
+        let schemas = &document["components"]["schemas"];
+        assert_eq!(
+            schemas["Point"],
+            json!({
+                "type": "array",
+                "prefixItems": [{ "type": "number" }, { "type": "number" }],
+                "items": false
+            })
+        );
+        assert_eq!(
+            schemas["Card"]["dependentRequired"],
+            json!({ "number": ["expiry"] })
+        );
+        assert_eq!(
+            schemas["Card"]["dependentSchemas"],
+            json!({ "billing": { "required": ["address"] } })
+        );
+        assert_eq!(
+            schemas["Card"]["properties"]["dependencies"],
+            json!({ "type": "object" })
+        );
+        assert_eq!(schemas["Modern"]["items"], json!({ "type": "string" }));
+    }
+
+    #[test]
+    fn test_unsupported_dialect() {
+        let mut document = json!({
+            "openapi": "3.1.0",
+            "jsonSchemaDialect": "https://example.com/my-dialect",
+        });
+        let err = normalize(&mut document).unwrap_err();
+        assert!(matches!(err, ParserError::UnsupportedDialect(_)));
+        assert!(err.to_string().contains("https://example.com/my-dialect"));
+
+        let mut document = json!({
+            "openapi": "3.1.0",
+            "components": {
+                "schemas": {
+                    "Old": { "$schema": "http://json-schema.org/draft-04/schema#" }
+                }
+            }
+        });
+        assert!(normalize(&mut document).is_err());
+    }
+}
@@ -0,0 +1,233 @@
+//! Static resolution of JSON Schema 2020-12 dynamic references.
+//!
+//! `oas3` doesn't know `$dynamicRef`, so a schema using one would silently
+//! become an unconstrained schema. Before deserialization each `$dynamicRef`
+//! is replaced with the `$ref` it is equivalent to when no outer schema
+//! overrides the anchor, which is how the specs FastAPI and similar tools
+//! emit use it (recursive trees, paginated lists):
+//!
+//! - a JSON pointer (`#/$defs/node`) becomes a `$ref` to the same pointer
+//! - `#name` becomes a `$ref` to the schema declaring `$dynamicAnchor: name`
+//!   (or `$anchor: name`). An anchor in the same schema resource is
+//!   preferred: the nearest enclosing schema with an `$id`, or else the
+//!   enclosing component schema. Otherwise the anchor must be unique in the
+//!   document.
+//!
+//! Anything else (a missing or ambiguous anchor, an external URL) falls back
+//! to an unconstrained schema. The original reference is kept in the
+//! `x-oas-gen-dynamic-ref` extension and a warning is returned.
+
+use serde_json::Value;
+
+use crate::diagnostic::escape;
+use crate::repair::Repair;
+
+/// Extension holding a `$dynamicRef` that could not be resolved statically
+/// (without the `x-` prefix, as `oas3` exposes extension names).
+pub const DYNAMIC_REF_EXTENSION: &str = "oas-gen-dynamic-ref";
+
+/// Fields holding user data, which may contain anything.
+const DATA_FIELDS: &[&str] = &["example", "examples", "default", "enum", "const"];
+
+struct Anchor {
+    name: String,
+    pointer: String,
+    scope: String,
+}
+
+struct DynamicRef {
+    pointer: String,
+    target: String,
+    scope: String,
+}
+
+/// Replace every `$dynamicRef` in `document` with its static equivalent,
+/// returning a warning for each one that fell back to an unconstrained
+/// schema.
+pub fn resolve(document: &mut Value) -> Vec<Repair> {
+    let mut anchors = Vec::new();
+    let mut refs = Vec::new();
+    collect(document, "", "", &mut anchors, &mut refs);
+
+    let mut warnings = Vec::new();
+    for dynamic_ref in refs {
+        let Some(Value::Object(schema)) = document.pointer_mut(&dynamic_ref.pointer) else {
+            continue;
+        };
+        schema.remove("$dynamicRef");
+        match static_target(&dynamic_ref, &anchors) {
+            Ok(target) => {
+                schema.insert("$ref".into(), Value::String(target));
+            }
+            Err(reason) => {
+                schema.insert(
+                    format!("x-{}", DYNAMIC_REF_EXTENSION),
+                    Value::String(dynamic_ref.target.clone()),
+                );
+                warnings.push(Repair {
+                    pointer: dynamic_ref.pointer,
+                    message: format!(
+                        "`$dynamicRef: {}` {}; treated as an unconstrained schema",
+                        dynamic_ref.target, reason
+                    ),
+                });
+            }
+        }
+    }
+    warnings
+}
+
+fn static_target(
+    dynamic_ref: &DynamicRef,
+    anchors: &[Anchor],
+) -> std::result::Result<String, &'static str> {
+    let Some(fragment) = dynamic_ref.target.strip_prefix('#') else {
+        return Err("points outside the document");
+    };
+    if fragment.is_empty() || fragment.starts_with('/') {
+        return Ok(dynamic_ref.target.clone());
+    }
+
+    let named = anchors
+        .iter()
+        .filter(|anchor| anchor.name == fragment)
+        .collect::<Vec<_>>();
+    let in_scope = named
+        .iter()
+        .filter(|anchor| anchor.scope == dynamic_ref.scope)
+        .collect::<Vec<_>>();
+    let candidates = if in_scope.is_empty() {
+        named.iter().collect()
+    } else {
+        in_scope
+    };
+    match candidates.as_slice() {
+        [anchor] => Ok(format!("#{}", anchor.pointer)),
+        [] => Err("has no matching anchor"),
+        _ => Err("matches several anchors"),
+    }
+}
+
+/// Find anchors and dynamic references. `scope` is the pointer of the
+/// enclosing schema resource.
+fn collect(
+    value: &Value,
+    pointer: &str,
+    scope: &str,
+    anchors: &mut Vec<Anchor>,
+    refs: &mut Vec<DynamicRef>,
+) {
+    match value {
+        Value::Object(map) => {
+            let scope = if map.contains_key("$id") || is_component_schema(pointer) {
+                pointer
+            } else {
+                scope
+            };
+            for keyword in ["$dynamicAnchor", "$anchor"] {
+                if let Some(Value::String(name)) = map.get(keyword) {
+                    anchors.push(Anchor {
+                        name: name.clone(),
+                        pointer: pointer.to_string(),
+                        scope: scope.to_string(),
+                    });
+                }
+            }
+            if let Some(Value::String(target)) = map.get("$dynamicRef") {
+                refs.push(DynamicRef {
+                    pointer: pointer.to_string(),
+                    target: target.clone(),
+                    scope: scope.to_string(),
+                });
+            }
+            for (key, child) in map {
+                if DATA_FIELDS.contains(&key.as_str()) || key.starts_with("x-") {
+                    continue;
+                }
+                let child_pointer = format!("{}/{}", pointer, escape(key));
+                collect(child, &child_pointer, scope, anchors, refs);
+            }
+        }
+        Value::Array(items) => {
+            for (index, item) in items.iter().enumerate() {
+                collect(
+                    item,
+                    &format!("{}/{}", pointer, index),
+                    scope,
+                    anchors,
+                    refs,
+                );
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_component_schema(pointer: &str) -> bool {
+    pointer
+        .strip_prefix("/components/schemas/")
+        .is_some_and(|name| !name.contains('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_resolve_dynamic_refs() {
+        let mut document = json!({
+            "openapi": "3.1.0",
+            "info": { "title": "Trees", "version": "1" },
+            "components": {
+                "schemas": {
+                    "Tree": {
+                        "$dynamicAnchor": "node",
+                        "type": "object",
+                        "properties": {
+                            "children": { "type": "array", "items": { "$dynamicRef": "#node" } },
+                            "meta": { "$dynamicRef": "#/components/schemas/Meta" }
+                        }
+                    },
+                    "Meta": { "type": "object" },
+                    "Menu": {
+                        "type": "object",
+                        "properties": {
+                            "root": { "$dynamicRef": "#node" },
+                            "item": { "$dynamicRef": "#item" }
+                        }
+                    },
+                    "Graph": {
+                        "$dynamicAnchor": "node",
+                        "type": "object"
+                    }
+                }
+            }
+        });
+
+        let warnings = resolve(&mut document);
+        let schemas = &document["components"]["schemas"];
+        assert_eq!(
+            schemas["Tree"]["properties"]["children"]["items"],
+            json!({ "$ref": "#/components/schemas/Tree" })
+        );
+        assert_eq!(
+            schemas["Tree"]["properties"]["meta"],
+            json!({ "$ref": "#/components/schemas/Meta" })
+        );
+        assert_eq!(
+            schemas["Menu"]["properties"]["root"],
+            json!({ "x-oas-gen-dynamic-ref": "#node" })
+        );
+
+        let messages = warnings.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            [
+                "#/components/schemas/Menu/properties/item: `$dynamicRef: #item` has no matching anchor; treated as an unconstrained schema",
+                "#/components/schemas/Menu/properties/root: `$dynamicRef: #node` matches several anchors; treated as an unconstrained schema",
+            ]
+        );
+        crate::parse_value(document).unwrap();
+    }
+}
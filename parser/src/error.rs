@@ -1,8 +1,29 @@
+use crate::diagnostic::Diagnostic;
+use crate::validate::Violation;
+
 #[derive(Debug)]
 pub enum ParserError {
     Io(std::io::Error),
     Json(serde_json::Error),
     Yaml(serde_saphyr::Error),
+    YamlEmit(serde_saphyr::ser_error::Error),
+    UnsupportedVersion(String),
+    /// A `jsonSchemaDialect` or `$schema` naming a dialect we can't read.
+    UnsupportedDialect(String),
+    UnresolvedRef(String),
+    /// A chain of `$ref`s leading back to where it started, as the pointers
+    /// (`#/components/schemas/A`) or locations (`pets.yaml#/Pet`) followed.
+    CircularRef(Vec<String>),
+    /// A document that is not valid JSON/YAML or not a valid spec.
+    Parse(Diagnostic),
+    /// A document breaking the OpenAPI meta-schema (strict mode).
+    Invalid(Vec<Violation>),
+    /// An error loading the named spec source (see
+    /// [`SpecSource`](crate::source::SpecSource)).
+    Source {
+        name: String,
+        error: Box<ParserError>,
+    },
 }
 
 impl From<std::io::Error> for ParserError {
@@ -23,12 +44,26 @@ impl From<serde_saphyr::Error> for ParserError {
     }
 }
 
+impl From<serde_saphyr::ser_error::Error> for ParserError {
+    fn from(error: serde_saphyr::ser_error::Error) -> Self {
+        ParserError::YamlEmit(error)
+    }
+}
+
 impl std::error::Error for ParserError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
             ParserError::Io(e) => Some(e),
             ParserError::Json(e) => Some(e),
             ParserError::Yaml(e) => Some(e),
+            ParserError::YamlEmit(e) => Some(e),
+            ParserError::Source { error, .. } => Some(error.as_ref()),
+            ParserError::UnsupportedVersion(_)
+            | ParserError::UnsupportedDialect(_)
+            | ParserError::UnresolvedRef(_)
+            | ParserError::CircularRef(_)
+            | ParserError::Parse(_)
+            | ParserError::Invalid(_) => None,
         }
     }
 }
@@ -39,6 +74,55 @@ impl std::fmt::Display for ParserError {
             ParserError::Io(e) => write!(f, "IO error: {}", e),
             ParserError::Json(e) => write!(f, "JSON error: {}", e),
             ParserError::Yaml(e) => write!(f, "YAML error: {}", e),
+            ParserError::YamlEmit(e) => write!(f, "YAML serialization error: {}", e),
+            ParserError::UnsupportedVersion(v) => write!(f, "Unsupported OpenAPI version: {}", v),
+            ParserError::UnsupportedDialect(d) => write!(
+                f,
+                "Unsupported JSON Schema dialect: {} (supported: {})",
+                d,
+                crate::dialect::Dialect::SUPPORTED.join(", ")
+            ),
+            ParserError::UnresolvedRef(r) => write!(f, "Unresolved $ref: {}", r),
+            ParserError::CircularRef(chain) => write!(f, "Circular $ref: {}", chain.join(" -> ")),
+            ParserError::Parse(d) => write!(f, "{}", d),
+            ParserError::Invalid(violations) => {
+                write!(f, "Spec breaks the OpenAPI schema:")?;
+                for violation in violations {
+                    write!(f, "\n  {}", violation)?;
+                }
+                Ok(())
+            }
+            ParserError::Source { name, error } => write!(f, "{}: {}", name, error),
+        }
+    }
+}
+
+impl ParserError {
+    /// Attach the name of the source being loaded. Errors that already name
+    /// one (e.g. from a wrapped source) keep theirs.
+    pub fn in_source(self, name: impl Into<String>) -> Self {
+        match self {
+            source @ ParserError::Source { .. } => source,
+            error => ParserError::Source {
+                name: name.into(),
+                error: Box::new(error),
+            },
+        }
+    }
+
+    /// Name of the source the error came from, if known.
+    pub fn source_name(&self) -> Option<&str> {
+        match self {
+            ParserError::Source { name, .. } => Some(name),
+            _ => None,
+        }
+    }
+
+    /// The error without its source context, to match on its kind.
+    pub fn inner(&self) -> &ParserError {
+        match self {
+            ParserError::Source { error, .. } => error.inner(),
+            error => error,
         }
     }
 }
@@ -0,0 +1,140 @@
+//! Fields the OpenAPI model doesn't keep.
+//!
+//! [`oas3::Spec`] drops unknown fields, and drops `x-` extensions on some
+//! objects (request bodies, encodings, security schemes, discriminators).
+//! [`Extras`] keeps every `x-` extension and unknown field of the document,
+//! keyed by the JSON pointer of the object they appear on, so later stages
+//! can pass them through: `/info`, `/paths/~1pets/get`,
+//! `/paths/~1pets/get/responses/200`,
+//! `/components/schemas/Pet/properties/name`, ...
+//!
+//! Pointers are into the document as written, before 3.0 keywords are
+//! upgraded. Extras of a `$ref` target are found under the target's pointer,
+//! not the referencing one.
+
+use std::collections::BTreeMap;
+
+use serde_json::{Map, Value};
+
+/// `x-` extensions and unknown fields, by the pointer of their object.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Extras {
+    objects: BTreeMap<String, Map<String, Value>>,
+}
+
+impl Extras {
+    /// Collect the extras of `document`. Documents without a supported
+    /// `openapi` version have none.
+    pub fn collect(document: &Value) -> Self {
+        crate::validate::extras(document)
+    }
+
+    /// Extras of the object at `pointer`, with their keys as written
+    /// (extensions keep their `x-` prefix).
+    pub fn get(&self, pointer: &str) -> Option<&Map<String, Value>> {
+        self.objects.get(pointer)
+    }
+
+    /// One extra field of the object at `pointer`.
+    pub fn field(&self, pointer: &str, key: &str) -> Option<&Value> {
+        self.get(pointer)?.get(key)
+    }
+
+    /// Objects with extras and their fields, in pointer order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Map<String, Value>)> {
+        self.objects
+            .iter()
+            .map(|(pointer, fields)| (pointer.as_str(), fields))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+
+    pub(crate) fn insert(&mut self, pointer: &str, key: &str, value: &Value) {
+        self.objects
+            .entry(pointer.to_string())
+            .or_default()
+            .insert(key.to_string(), value.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_collect_extras() {
+        let document = json!({
+            "openapi": "3.1.0",
+            "info": { "title": "Pets", "version": "1", "x-logo": "pets.png" },
+            "paths": {
+                "x-internal": true,
+                "/pets": {
+                    "get": {
+                        "x-rate-limit": 10,
+                        "operationID": "typo",
+                        "responses": {
+                            "200": { "description": "ok", "x-cache": "public" }
+                        }
+                    },
+                    "post": {
+                        "requestBody": {
+                            "x-body-name": "pet",
+                            "content": {
+                                "application/json": {
+                                    "schema": { "$ref": "#/components/schemas/Pet" }
+                                }
+                            }
+                        },
+                        "responses": { "201": { "description": "created" } }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "Pet": {
+                        "type": "object",
+                        "x-table": "pets",
+                        "properties": {
+                            "name": { "type": "string", "x-sort": 1, "sortable": true },
+                            "tags": { "type": "array", "items": { "type": "string", "x-max": 3 } }
+                        }
+                    }
+                }
+            }
+        });
+
+        let extras = Extras::collect(&document);
+        let pointers = extras
+            .iter()
+            .map(|(pointer, fields)| {
+                let keys = fields.keys().cloned().collect::<Vec<_>>().join(",");
+                format!("{} {}", pointer, keys)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            pointers,
+            [
+                "/components/schemas/Pet x-table",
+                "/components/schemas/Pet/properties/name sortable,x-sort",
+                "/components/schemas/Pet/properties/tags/items x-max",
+                "/info x-logo",
+                "/paths x-internal",
+                "/paths/~1pets/get operationID,x-rate-limit",
+                "/paths/~1pets/get/responses/200 x-cache",
+                "/paths/~1pets/post/requestBody x-body-name",
+            ]
+        );
+        assert_eq!(
+            extras.field("/paths/~1pets/get", "x-rate-limit"),
+            Some(&json!(10))
+        );
+    }
+
+    #[test]
+    fn test_unsupported_version_has_no_extras() {
+        assert!(Extras::collect(&json!({ "swagger": "2.0", "x-a": 1 })).is_empty());
+    }
+}
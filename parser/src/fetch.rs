@@ -0,0 +1,379 @@
+//! Fetching of remote (`http://`, `https://`) `$ref` targets.
+//!
+//! [`RemoteRefs`] decides whether a URL may be loaded at all (allow-list,
+//! offline mode), serves it from an on-disk cache when possible, and otherwise
+//! asks a [`Fetcher`] for it. The bundler uses it for every remote ref; see
+//! [`crate::bundle::bundle_with`].
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::error::{ParserError, Result};
+
+/// Loads the raw content of a URL.
+pub trait Fetcher {
+    fn fetch(&self, url: &str) -> Result<String>;
+
+    /// Load the content along with its `Content-Type`, if the fetcher knows it.
+    fn fetch_with_type(&self, url: &str) -> Result<(String, Option<String>)> {
+        Ok((self.fetch(url)?, None))
+    }
+}
+
+impl<F> Fetcher for F
+where
+    F: Fn(&str) -> Result<String>,
+{
+    fn fetch(&self, url: &str) -> Result<String> {
+        self(url)
+    }
+}
+
+/// Blocking HTTP(S) fetcher.
+#[cfg(feature = "http")]
+pub struct HttpFetcher {
+    agent: ureq::Agent,
+}
+
+#[cfg(feature = "http")]
+impl HttpFetcher {
+    pub fn new() -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new()
+                .timeout(std::time::Duration::from_secs(30))
+                .build(),
+        }
+    }
+}
+
+#[cfg(feature = "http")]
+impl Default for HttpFetcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "http")]
+impl Fetcher for HttpFetcher {
+    fn fetch(&self, url: &str) -> Result<String> {
+        Ok(self.fetch_with_type(url)?.0)
+    }
+
+    fn fetch_with_type(&self, url: &str) -> Result<(String, Option<String>)> {
+        let response =
+            self.agent.get(url).call().map_err(|e| {
+                ParserError::UnresolvedRef(format!("failed to fetch {}: {}", url, e))
+            })?;
+        let content_type = response.header("content-type").map(str::to_string);
+        Ok((response.into_string()?, content_type))
+    }
+}
+
+/// How long a cached document is reused before it is fetched again.
+pub const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Policy for loading remote refs.
+pub struct RemoteRefs {
+    fetcher: Option<Box<dyn Fetcher>>,
+    allow: Vec<Option<UrlParts>>,
+    allow_any: bool,
+    cache_dir: Option<PathBuf>,
+    cache_ttl: Duration,
+}
+
+impl RemoteRefs {
+    /// Fetch remote refs with `fetcher`. No URL is allowed until
+    /// [`allow`](Self::allow) or [`allow_any`](Self::allow_any) is called.
+    pub fn new(fetcher: impl Fetcher + 'static) -> Self {
+        Self {
+            fetcher: Some(Box::new(fetcher)),
+            allow: Vec::new(),
+            allow_any: false,
+            cache_dir: None,
+            cache_ttl: DEFAULT_CACHE_TTL,
+        }
+    }
+
+    /// Only serve remote refs from the cache, however old; never fetch.
+    pub fn offline(cache_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            fetcher: None,
+            allow: Vec::new(),
+            allow_any: true,
+            cache_dir: Some(cache_dir.into()),
+            cache_ttl: DEFAULT_CACHE_TTL,
+        }
+    }
+
+    /// Allow URLs under `prefix` (e.g. `https://schemas.acme.dev/v1/`): same
+    /// scheme and host, and a path starting with the prefix's path segments,
+    /// so `/v1` doesn't admit `/v10`. A prefix that isn't an `http(s)://`
+    /// URL admits nothing.
+    pub fn allow(mut self, prefix: impl AsRef<str>) -> Self {
+        self.allow.push(UrlParts::parse(prefix.as_ref()));
+        self
+    }
+
+    /// Allow every URL.
+    pub fn allow_any(mut self) -> Self {
+        self.allow_any = true;
+        self
+    }
+
+    /// Keep fetched documents in `dir` and reuse them on later runs.
+    pub fn with_cache_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = Some(dir.into());
+        self
+    }
+
+    /// Refetch cached documents older than `ttl` (default
+    /// [`DEFAULT_CACHE_TTL`]). Offline mode serves them regardless.
+    pub fn with_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = ttl;
+        self
+    }
+
+    /// Whether `url` passes the allow-list.
+    pub fn is_allowed(&self, url: &str) -> bool {
+        if self.allow_any {
+            return true;
+        }
+        let Some(url) = UrlParts::parse(url) else {
+            return false;
+        };
+        self.allow
+            .iter()
+            .flatten()
+            .any(|prefix| prefix.contains(&url))
+    }
+
+    /// Load `url` from the cache, or fetch (and cache) it.
+    pub fn load(&self, url: &str) -> Result<String> {
+        if !self.is_allowed(url) {
+            return Err(ParserError::UnresolvedRef(format!(
+                "{} is not in the remote $ref allow-list",
+                url
+            )));
+        }
+
+        let cache_path = self.cache_path(url);
+        if let Some(path) = &cache_path
+            && let Some(content) = self.read_cache(path)
+        {
+            return Ok(content);
+        }
+
+        let Some(fetcher) = &self.fetcher else {
+            return Err(ParserError::UnresolvedRef(format!(
+                "{} is not cached and remote fetching is disabled (offline)",
+                url
+            )));
+        };
+        let content = fetcher.fetch(url)?;
+
+        if let Some(path) = &cache_path {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            std::fs::write(path, &content)?;
+        }
+        Ok(content)
+    }
+
+    /// The cached content at `path`, unless it has expired.
+    fn read_cache(&self, path: &Path) -> Option<String> {
+        let fresh = self.fetcher.is_none()
+            || std::fs::metadata(path)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .is_some_and(|age| age <= self.cache_ttl);
+        if !fresh {
+            return None;
+        }
+        std::fs::read_to_string(path).ok()
+    }
+
+    fn cache_path(&self, url: &str) -> Option<PathBuf> {
+        self.cache_dir
+            .as_ref()
+            .map(|dir| dir.join(format!("{:016x}", fnv1a(url.as_bytes()))))
+    }
+}
+
+/// Scheme, host and path segments of an `http(s)://` URL, as compared by the
+/// allow-list.
+#[derive(Debug, PartialEq)]
+struct UrlParts {
+    scheme: String,
+    host: String,
+    segments: Vec<String>,
+}
+
+impl UrlParts {
+    fn parse(url: &str) -> Option<Self> {
+        let (scheme, rest) = url.split_once("://")?;
+        let scheme = scheme.to_ascii_lowercase();
+        if scheme != "http" && scheme != "https" {
+            return None;
+        }
+
+        let (authority, path) = rest.split_at(rest.find(['/', '?', '#']).unwrap_or(rest.len()));
+        // Credentials aren't part of the host: `https://acme.dev@evil.dev/`
+        let host = authority
+            .rsplit_once('@')
+            .map_or(authority, |(_, host)| host)
+            .to_ascii_lowercase();
+        if host.is_empty() {
+            return None;
+        }
+
+        let path = path.split(['?', '#']).next().unwrap_or("");
+        let mut segments = Vec::new();
+        for segment in path.split('/') {
+            match segment.replace("%2e", ".").replace("%2E", ".").as_str() {
+                "" | "." => {}
+                ".." => {
+                    segments.pop();
+                }
+                segment => segments.push(segment.to_string()),
+            }
+        }
+
+        Some(Self {
+            scheme,
+            host,
+            segments,
+        })
+    }
+
+    /// Whether `url` lies under this prefix.
+    fn contains(&self, url: &UrlParts) -> bool {
+        self.scheme == url.scheme
+            && self.host == url.host
+            && url.segments.starts_with(&self.segments)
+    }
+}
+
+/// Stable 64-bit FNV-1a hash, used for cache file names so they survive
+/// compiler upgrades (unlike `DefaultHasher`). `ir::gen_ir::spec_hash`
+/// formats it for spec and generated file hashes.
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Resolve `reference` against the URL of the document containing it.
+pub(crate) fn join_url(base: &str, reference: &str) -> String {
+    if reference.starts_with("http://") || reference.starts_with("https://") {
+        return reference.to_string();
+    }
+
+    let (scheme, rest) = base.split_once("://").unwrap_or(("https", base));
+    let (host, base_path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+
+    let mut segments: Vec<&str> = if reference.starts_with('/') {
+        Vec::new()
+    } else {
+        let base_path = base_path.split(['?', '#']).next().unwrap_or("");
+        let dir = &base_path[..base_path.rfind('/').unwrap_or(0)];
+        dir.split('/')
+            .filter(|segment| !segment.is_empty())
+            .collect()
+    };
+    for segment in reference.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+
+    format!("{}://{}/{}", scheme, host, segments.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_join_url() {
+        let base = "https://schemas.acme.dev/v1/pets/pet.yaml";
+        assert_eq!(
+            join_url(base, "tag.yaml"),
+            "https://schemas.acme.dev/v1/pets/tag.yaml"
+        );
+        assert_eq!(
+            join_url(base, "../common/error.yaml"),
+            "https://schemas.acme.dev/v1/common/error.yaml"
+        );
+        assert_eq!(
+            join_url(base, "/v2/pet.yaml"),
+            "https://schemas.acme.dev/v2/pet.yaml"
+        );
+        assert_eq!(
+            join_url(base, "http://other.dev/x.json"),
+            "http://other.dev/x.json"
+        );
+    }
+
+    #[test]
+    fn test_remote_refs_allow_list_cache_and_offline() {
+        let cache_dir =
+            std::env::temp_dir().join(format!("oas-gen-ref-cache-{}", std::process::id()));
+        let calls = Rc::new(Cell::new(0));
+        let counter = calls.clone();
+        let remote = RemoteRefs::new(move |url: &str| -> Result<String> {
+            counter.set(counter.get() + 1);
+            Ok(format!("url: {}", url))
+        })
+        .allow("https://schemas.acme.dev/")
+        .with_cache_dir(&cache_dir);
+
+        let url = "https://schemas.acme.dev/pet.yaml";
+        assert_eq!(remote.load(url).unwrap(), format!("url: {}", url));
+        assert_eq!(remote.load(url).unwrap(), format!("url: {}", url));
+        assert_eq!(calls.get(), 1);
+        assert!(remote.load("https://evil.dev/pet.yaml").is_err());
+        assert!(
+            RemoteRefs::new(|_: &str| -> Result<String> { Ok(String::new()) })
+                .load(url)
+                .is_err()
+        );
+
+        let offline = RemoteRefs::offline(&cache_dir);
+        assert_eq!(offline.load(url).unwrap(), format!("url: {}", url));
+        assert!(offline.load("https://schemas.acme.dev/tag.yaml").is_err());
+
+        let expired = RemoteRefs::new(|_: &str| -> Result<String> { Ok("fresh".to_string()) })
+            .allow_any()
+            .with_cache_dir(&cache_dir)
+            .with_cache_ttl(Duration::ZERO);
+        std::thread::sleep(Duration::from_millis(10));
+        assert_eq!(expired.load(url).unwrap(), "fresh");
+
+        std::fs::remove_dir_all(cache_dir).unwrap();
+    }
+
+    #[test]
+    fn test_allow_list_compares_scheme_host_and_segments() {
+        let remote = RemoteRefs::new(|_: &str| -> Result<String> { Ok(String::new()) })
+            .allow("https://schemas.acme.dev/v1")
+            .allow("not a url");
+
+        assert!(remote.is_allowed("https://schemas.acme.dev/v1/pet.yaml"));
+        assert!(remote.is_allowed("HTTPS://Schemas.Acme.dev/v1/pet.yaml"));
+        assert!(!remote.is_allowed("http://schemas.acme.dev/v1/pet.yaml"));
+        assert!(!remote.is_allowed("https://schemas.acme.dev/v10/pet.yaml"));
+        assert!(!remote.is_allowed("https://schemas.acme.dev.evil.com/v1/pet.yaml"));
+        assert!(!remote.is_allowed("https://schemas.acme.dev@evil.com/v1/pet.yaml"));
+        assert!(!remote.is_allowed("https://schemas.acme.dev/v1/../admin.yaml"));
+        assert!(!remote.is_allowed("https://schemas.acme.dev/v1/%2e%2e/admin.yaml"));
+        assert!(!remote.is_allowed("not a url"));
+    }
+}
@@ -0,0 +1,471 @@
+//! Canonical formatting of OpenAPI documents.
+//!
+//! Rewrites a spec with a stable key order so that spec diffs only show
+//! meaningful changes:
+//!
+//! - top-level keys follow the OpenAPI document order (`openapi`, `info`,
+//!   `servers`, `tags`, `paths`, ..., `components`)
+//! - well-known objects (info, path items, operations, schemas, ...) use the
+//!   order from the specification
+//! - maps keyed by user names (paths, components, properties, responses) are
+//!   sorted alphabetically
+//! - unknown keys follow alphabetically and `x-*` extensions come last
+//!
+//! Array order is never changed. The output keeps the input format (JSON stays
+//! JSON, YAML stays YAML). Comments cannot be carried over because the document
+//! is round-tripped through a value tree, and aliases are expanded in place of
+//! their anchors; [`dropped_by_format`] tells whether a YAML input has either.
+
+use serde::ser::{Serialize, SerializeMap, SerializeSeq, Serializer};
+use serde_json::Value;
+
+use crate::error::Result;
+use crate::resolve::parse_as_json;
+
+/// Serialization format of a spec document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpecFormat {
+    Json,
+    Yaml,
+}
+
+impl SpecFormat {
+    /// Guess the format from the document content.
+    pub fn detect(input: &str) -> Self {
+        match input.trim_start().chars().next() {
+            Some('{') | Some('[') => SpecFormat::Json,
+            _ => SpecFormat::Yaml,
+        }
+    }
+}
+
+/// Format `input` canonically, keeping its original format.
+pub fn format(input: &str) -> Result<String> {
+    format_as(input, SpecFormat::detect(input))
+}
+
+/// Format `input` canonically and emit it as `format`.
+pub fn format_as(input: &str, format: SpecFormat) -> Result<String> {
+    let value = parse_as_json(input)?;
+    format_value(&value, format)
+}
+
+/// Emit an already parsed document canonically.
+pub fn format_value(value: &Value, format: SpecFormat) -> Result<String> {
+    let canonical = Canonical {
+        value,
+        context: Context::Root,
+    };
+    let mut output = match format {
+        SpecFormat::Json => serde_json::to_string_pretty(&canonical)?,
+        SpecFormat::Yaml => serde_saphyr::to_string(&canonical)?,
+    };
+    if !output.ends_with('\n') {
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+/// Whether `input` is already canonically formatted.
+pub fn is_formatted(input: &str) -> Result<bool> {
+    Ok(format(input)? == input)
+}
+
+/// What [`format`] would lose from `input`: `"comments"` and `"anchors"`
+/// for a YAML document that has them, nothing for JSON. Block scalars and
+/// quoted strings are skipped, so a `#` or `&` inside a value doesn't count.
+pub fn dropped_by_format(input: &str) -> Vec<&'static str> {
+    if SpecFormat::detect(input) == SpecFormat::Json {
+        return Vec::new();
+    }
+    let (mut comments, mut anchors) = (false, false);
+    // Indentation of the key whose block scalar the following lines belong to
+    let mut block_scalar: Option<usize> = None;
+    for line in input.lines() {
+        let indent = line.len() - line.trim_start().len();
+        if let Some(parent) = block_scalar {
+            if line.trim().is_empty() || indent > parent {
+                continue;
+            }
+            block_scalar = None;
+        }
+        let (mut single, mut double, mut escaped) = (false, false, false);
+        let mut previous = ' ';
+        let mut content_end = line.len();
+        for (i, c) in line.char_indices() {
+            if double {
+                match c {
+                    _ if escaped => escaped = false,
+                    '\\' => escaped = true,
+                    '"' => double = false,
+                    _ => {}
+                }
+            } else if single {
+                // `''` closes and reopens, which leaves it open
+                single = c != '\'';
+            } else {
+                match c {
+                    '#' if previous.is_whitespace() => {
+                        comments = true;
+                        content_end = i;
+                        break;
+                    }
+                    '"' => double = true,
+                    '\'' => single = true,
+                    '&' | '*' if previous.is_whitespace() || "[{,".contains(previous) => {
+                        let next = line[i + 1..].chars().next();
+                        anchors |= next.is_some_and(|next| !next.is_whitespace());
+                    }
+                    _ => {}
+                }
+            }
+            previous = c;
+        }
+        let content = line[..content_end].trim_end();
+        let header = content.rsplit(' ').next().unwrap_or_default();
+        if (header.starts_with('|') || header.starts_with('>'))
+            && header[1..]
+                .chars()
+                .all(|c| matches!(c, '-' | '+' | '1'..='9'))
+        {
+            block_scalar = Some(indent);
+        }
+    }
+    [("comments", comments), ("anchors", anchors)]
+        .into_iter()
+        .filter_map(|(what, found)| found.then_some(what))
+        .collect()
+}
+
+/// What kind of OpenAPI object a JSON value is, as far as ordering is concerned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Context {
+    Root,
+    Info,
+    Paths,
+    PathItem,
+    Operation,
+    Components,
+    /// Map of name -> schema (`components/schemas`, `properties`)
+    SchemaMap,
+    /// Array of schemas (`allOf`, `oneOf`, `anyOf`, `prefixItems`)
+    SchemaList,
+    Schema,
+    Other,
+}
+
+const ROOT_ORDER: &[&str] = &[
+    "openapi",
+    "info",
+    "jsonSchemaDialect",
+    "servers",
+    "security",
+    "tags",
+    "paths",
+    "webhooks",
+    "components",
+    "externalDocs",
+];
+
+const INFO_ORDER: &[&str] = &[
+    "title",
+    "summary",
+    "description",
+    "termsOfService",
+    "contact",
+    "license",
+    "version",
+];
+
+const PATH_ITEM_ORDER: &[&str] = &[
+    "$ref",
+    "summary",
+    "description",
+    "servers",
+    "parameters",
+    "get",
+    "put",
+    "post",
+    "delete",
+    "options",
+    "head",
+    "patch",
+    "trace",
+];
+
+const OPERATION_ORDER: &[&str] = &[
+    "tags",
+    "summary",
+    "description",
+    "externalDocs",
+    "operationId",
+    "parameters",
+    "requestBody",
+    "responses",
+    "callbacks",
+    "deprecated",
+    "security",
+    "servers",
+];
+
+const COMPONENTS_ORDER: &[&str] = &[
+    "schemas",
+    "responses",
+    "parameters",
+    "examples",
+    "requestBodies",
+    "headers",
+    "securitySchemes",
+    "links",
+    "callbacks",
+    "pathItems",
+];
+
+const SCHEMA_ORDER: &[&str] = &[
+    "$ref",
+    "title",
+    "description",
+    "type",
+    "format",
+    "enum",
+    "const",
+    "default",
+    "nullable",
+    "readOnly",
+    "writeOnly",
+    "deprecated",
+    "discriminator",
+    "required",
+    "properties",
+    "additionalProperties",
+    "items",
+    "prefixItems",
+    "allOf",
+    "oneOf",
+    "anyOf",
+    "not",
+    "example",
+    "examples",
+];
+
+const OTHER_ORDER: &[&str] = &["$ref", "name", "in", "summary", "description", "required"];
+
+const HTTP_METHODS: &[&str] = &[
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+impl Context {
+    /// Keys that come first, in this order. Everything else is alphabetical.
+    fn key_order(self) -> &'static [&'static str] {
+        match self {
+            Context::Root => ROOT_ORDER,
+            Context::Info => INFO_ORDER,
+            Context::PathItem => PATH_ITEM_ORDER,
+            Context::Operation => OPERATION_ORDER,
+            Context::Components => COMPONENTS_ORDER,
+            Context::Schema => SCHEMA_ORDER,
+            Context::Other => OTHER_ORDER,
+            Context::Paths | Context::SchemaMap | Context::SchemaList => &[],
+        }
+    }
+
+    /// Sort key: known keys by position, then plain keys, then `x-*` extensions.
+    fn rank<'k>(self, key: &'k str) -> (u8, usize, &'k str) {
+        if let Some(position) = self.key_order().iter().position(|known| *known == key) {
+            (0, position, key)
+        } else if key.starts_with("x-") {
+            (2, 0, key)
+        } else {
+            (1, 0, key)
+        }
+    }
+
+    /// Context of the value stored under `key` in an object of this context.
+    fn child(self, key: &str) -> Context {
+        if key.starts_with("x-") {
+            return Context::Other;
+        }
+        match (self, key) {
+            (Context::Root, "info") => Context::Info,
+            (Context::Root, "paths" | "webhooks") => Context::Paths,
+            (Context::Root, "components") => Context::Components,
+            (Context::Paths, _) => Context::PathItem,
+            (Context::PathItem, method) if HTTP_METHODS.contains(&method) => Context::Operation,
+            (Context::Components, "schemas") => Context::SchemaMap,
+            (Context::Components, "pathItems") => Context::Paths,
+            (Context::SchemaMap, _) => Context::Schema,
+            (Context::Schema, "properties" | "patternProperties" | "$defs") => Context::SchemaMap,
+            (Context::Schema, "items" | "additionalProperties" | "not") => Context::Schema,
+            (Context::Schema, "allOf" | "oneOf" | "anyOf" | "prefixItems") => Context::SchemaList,
+            (Context::Schema, _) => Context::Other,
+            (_, "schema") => Context::Schema,
+            _ => Context::Other,
+        }
+    }
+
+    /// Context of the elements of an array stored in this context.
+    fn element(self) -> Context {
+        match self {
+            Context::SchemaList => Context::Schema,
+            _ => Context::Other,
+        }
+    }
+}
+
+/// Serializes a JSON value with canonical key order.
+struct Canonical<'a> {
+    value: &'a Value,
+    context: Context,
+}
+
+impl Serialize for Canonical<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        match self.value {
+            Value::Object(map) => {
+                let mut entries: Vec<_> = map.iter().collect();
+                entries.sort_by(|(a, _), (b, _)| self.context.rank(a).cmp(&self.context.rank(b)));
+
+                let mut out = serializer.serialize_map(Some(entries.len()))?;
+                for (key, value) in entries {
+                    out.serialize_entry(
+                        key,
+                        &Canonical {
+                            value,
+                            context: self.context.child(key),
+                        },
+                    )?;
+                }
+                out.end()
+            }
+            Value::Array(items) => {
+                let context = self.context.element();
+                let mut out = serializer.serialize_seq(Some(items.len()))?;
+                for value in items {
+                    out.serialize_element(&Canonical { value, context })?;
+                }
+                out.end()
+            }
+            other => other.serialize(serializer),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_orders_keys_canonically() {
+        let input = r#"{
+            "x-generator": "hand",
+            "components": {
+                "schemas": {
+                    "Pet": {
+                        "properties": { "name": { "type": "string" }, "id": { "type": "integer" } },
+                        "type": "object",
+                        "required": ["name", "id"]
+                    }
+                }
+            },
+            "paths": {
+                "/pets": {
+                    "post": { "responses": {}, "operationId": "createPet" },
+                    "get": { "responses": {}, "operationId": "listPets", "summary": "List" }
+                },
+                "/owners": {}
+            },
+            "info": { "version": "1.0.0", "title": "Pets" },
+            "openapi": "3.1.0"
+        }"#;
+
+        let formatted = format(input).unwrap();
+        let expected = r#"{
+  "openapi": "3.1.0",
+  "info": {
+    "title": "Pets",
+    "version": "1.0.0"
+  },
+  "paths": {
+    "/owners": {},
+    "/pets": {
+      "get": {
+        "summary": "List",
+        "operationId": "listPets",
+        "responses": {}
+      },
+      "post": {
+        "operationId": "createPet",
+        "responses": {}
+      }
+    }
+  },
+  "components": {
+    "schemas": {
+      "Pet": {
+        "type": "object",
+        "required": [
+          "name",
+          "id"
+        ],
+        "properties": {
+          "id": {
+            "type": "integer"
+          },
+          "name": {
+            "type": "string"
+          }
+        }
+      }
+    }
+  },
+  "x-generator": "hand"
+}
+"#;
+        assert_eq!(formatted, expected);
+        assert!(is_formatted(&formatted).unwrap());
+    }
+
+    #[test]
+    fn test_format_keeps_yaml() {
+        let input = "paths: {}\ninfo:\n  version: 1.0.0\n  title: Pets\nopenapi: 3.0.3\n";
+
+        let formatted = format(input).unwrap();
+        assert_eq!(SpecFormat::detect(&formatted), SpecFormat::Yaml);
+        assert!(formatted.starts_with("openapi:"));
+        assert!(formatted.find("title").unwrap() < formatted.find("version").unwrap());
+        assert!(formatted.find("info").unwrap() < formatted.find("paths").unwrap());
+        assert_eq!(format(&formatted).unwrap(), formatted);
+        crate::parse(&formatted).expect("formatted YAML should still parse");
+        assert!(dropped_by_format(input).is_empty());
+    }
+
+    #[test]
+    fn test_dropped_by_format() {
+        let input = "\
+# Pets API
+openapi: 3.0.3
+info:
+  title: 'Pets # and more'
+  version: \"1 #2\" # the version
+  description: |
+    # Heading, not a comment
+    Cats & dogs
+paths: {}
+";
+        assert_eq!(dropped_by_format(input), vec!["comments"]);
+
+        let input = "\
+openapi: 3.0.3
+info: { title: Pets, version: '1' }
+paths: {}
+components:
+  schemas:
+    Name: &name { type: string }
+    Pet:
+      properties:
+        name: *name
+";
+        assert_eq!(dropped_by_format(input), vec!["anchors"]);
+        assert!(dropped_by_format("{\"openapi\": \"3.0.3\"} ").is_empty());
+    }
+}
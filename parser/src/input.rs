@@ -0,0 +1,169 @@
+//! Reading a spec from a path, stdin or a URL.
+//!
+//! [`read_source`] takes what a user would type: a file path, `-` for
+//! stdin, or an `http://`/`https://` URL, so CI pipelines can pipe a spec
+//! straight into generation. The format comes from the response's
+//! `Content-Type`, then the extension, then the content itself. The spec is
+//! returned as a [`StringSource`] named after where it was read from.
+
+use std::io::Read;
+use std::path::Path;
+
+use crate::error::Result;
+use crate::fetch::Fetcher;
+use crate::fmt::SpecFormat;
+use crate::source::StringSource;
+
+/// Where a spec was read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Location {
+    File(std::path::PathBuf),
+    Stdin,
+    Url(String),
+}
+
+impl Location {
+    /// Classify what the user passed: `-`, a URL, or else a path.
+    pub fn parse(location: &str) -> Self {
+        if location == "-" {
+            Location::Stdin
+        } else if location.starts_with("http://") || location.starts_with("https://") {
+            Location::Url(location.to_string())
+        } else {
+            Location::File(location.into())
+        }
+    }
+}
+
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Location::File(path) => write!(f, "{}", path.display()),
+            Location::Stdin => write!(f, "<stdin>"),
+            Location::Url(url) => write!(f, "{}", url),
+        }
+    }
+}
+
+/// Read a spec from a path, `-` (stdin) or a URL. URLs are fetched with
+/// [`HttpFetcher`](crate::fetch::HttpFetcher) when the `http` feature is on.
+pub fn read_source(location: &str) -> Result<StringSource> {
+    #[cfg(feature = "http")]
+    {
+        read_source_with(location, &crate::fetch::HttpFetcher::new())
+    }
+    #[cfg(not(feature = "http"))]
+    {
+        let no_http = |url: &str| -> Result<String> {
+            Err(crate::error::ParserError::UnresolvedRef(format!(
+                "cannot read {}: parser was built without the `http` feature",
+                url
+            )))
+        };
+        read_source_with(location, &no_http)
+    }
+}
+
+/// Like [`read_source`], fetching URLs with `fetcher`.
+pub fn read_source_with(location: &str, fetcher: &dyn Fetcher) -> Result<StringSource> {
+    read_location(Location::parse(location), fetcher, std::io::stdin())
+}
+
+fn read_location(
+    location: Location,
+    fetcher: &dyn Fetcher,
+    mut stdin: impl Read,
+) -> Result<StringSource> {
+    let (content, content_type) = match &location {
+        Location::File(path) => (std::fs::read_to_string(path)?, None),
+        Location::Stdin => {
+            let mut content = String::new();
+            stdin.read_to_string(&mut content)?;
+            (content, None)
+        }
+        Location::Url(url) => fetcher.fetch_with_type(url)?,
+    };
+    let format = content_type
+        .as_deref()
+        .and_then(format_of_content_type)
+        .or_else(|| format_of_name(&location));
+    let source = StringSource::new(location.to_string(), content);
+    Ok(match format {
+        Some(format) => source.with_format(format),
+        None => source,
+    })
+}
+
+/// `application/json`, `application/vnd.oai.openapi+json` and the like are
+/// JSON; anything mentioning YAML is YAML. Generic types (`text/plain`,
+/// `application/octet-stream`) say nothing.
+fn format_of_content_type(content_type: &str) -> Option<SpecFormat> {
+    let mime = content_type.split(';').next()?.trim().to_ascii_lowercase();
+    if mime.ends_with("/json") || mime.ends_with("+json") {
+        Some(SpecFormat::Json)
+    } else if mime.contains("yaml") || mime.contains("yml") {
+        Some(SpecFormat::Yaml)
+    } else {
+        None
+    }
+}
+
+fn format_of_name(location: &Location) -> Option<SpecFormat> {
+    let name = match location {
+        Location::File(path) => path.to_str()?,
+        Location::Url(url) => url.split(['?', '#']).next()?,
+        Location::Stdin => return None,
+    };
+    match Path::new(name).extension()?.to_str()? {
+        "json" => Some(SpecFormat::Json),
+        "yaml" | "yml" => Some(SpecFormat::Yaml),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::SpecSource;
+
+    struct Served(&'static str, Option<&'static str>);
+
+    impl Fetcher for Served {
+        fn fetch(&self, _url: &str) -> Result<String> {
+            Ok(self.0.to_string())
+        }
+
+        fn fetch_with_type(&self, _url: &str) -> Result<(String, Option<String>)> {
+            Ok((self.0.to_string(), self.1.map(str::to_string)))
+        }
+    }
+
+    #[test]
+    fn test_read_location() {
+        let spec = "openapi: 3.1.0\ninfo: { title: Pets, version: '1' }\npaths: {}\n";
+
+        let input = read_location(Location::Stdin, &Served("", None), spec.as_bytes()).unwrap();
+        assert_eq!(input.format(), SpecFormat::Yaml);
+        assert_eq!(input.name(), "<stdin>");
+        assert_eq!(input.load().unwrap().info.title, "Pets");
+
+        // Content-Type wins over the extension and the content
+        let url = Location::parse("https://api.example.com/openapi.json");
+        let input = read_location(url, &Served(spec, Some("application/yaml")), &b""[..]).unwrap();
+        assert_eq!(input.format(), SpecFormat::Yaml);
+
+        let url = Location::parse("https://api.example.com/openapi.json?v=2");
+        let input = read_location(url, &Served(spec, Some("text/plain")), &b""[..]).unwrap();
+        assert_eq!(input.format(), SpecFormat::Json);
+
+        // and decides how the content is parsed: a YAML flow mapping looks
+        // like JSON, but isn't
+        let flow = "{openapi: 3.1.0, info: {title: Pets, version: '1'}, paths: {}}";
+        let url = Location::parse("https://api.example.com/openapi");
+        let input = read_location(url, &Served(flow, Some("application/yaml")), &b""[..]).unwrap();
+        assert_eq!(input.load().unwrap().info.title, "Pets");
+        let url = Location::parse("https://api.example.com/openapi");
+        let input = read_location(url, &Served(flow, None), &b""[..]).unwrap();
+        assert!(input.load().is_err());
+    }
+}
@@ -1,7 +1,23 @@
 use std::path::PathBuf;
 
+pub mod bundle;
+pub mod compat;
+pub mod cycle;
+pub mod diagnostic;
+pub mod dialect;
+pub mod dynamic_ref;
 pub mod error;
+pub mod extras;
+pub mod fetch;
+pub mod fmt;
+pub mod input;
+pub mod options;
+pub mod repair;
 pub mod resolve;
+pub mod source;
+pub mod validate;
+pub mod version;
+pub mod yaml;
 
 pub fn read(path: impl Into<PathBuf>) -> Result<String, error::ParserError> {
     let path = path.into();
@@ -9,15 +25,131 @@ pub fn read(path: impl Into<PathBuf>) -> Result<String, error::ParserError> {
     Ok(string)
 }
 
+/// Read a spec from a path, `-` for stdin, or an `http(s)://` URL, detecting
+/// its format. See [`input`].
+pub fn read_source(location: &str) -> Result<source::StringSource, error::ParserError> {
+    input::read_source(location)
+}
+
+/// Detect the format and OpenAPI version of a document without parsing it
+/// as a spec, to route it or report an unsupported version early. See
+/// [`version::SpecInfo::detect`].
+pub fn detect(input: &str) -> Result<version::SpecInfo, error::ParserError> {
+    version::SpecInfo::detect(input)
+}
+
+/// Parse a JSON or YAML spec. Errors are [`error::ParserError::Parse`] with
+/// the line/column and JSON pointer of the problem where known.
 pub fn parse(input: &str) -> Result<oas3::Spec, error::ParserError> {
-    // Use strict_booleans to avoid YAML 1.1 quirks where y/n/yes/no/on/off
-    // are interpreted as booleans instead of strings
-    let options = serde_saphyr::Options {
-        strict_booleans: true,
-        ..Default::default()
+    parse_as(input, diagnostic::SourceFormat::detect(input))
+}
+
+/// Like [`parse`], reading `input` as `format`.
+pub(crate) fn parse_as(
+    input: &str,
+    format: diagnostic::SourceFormat,
+) -> Result<oas3::Spec, error::ParserError> {
+    deserialize_document(input, format, diagnostic::parse_document_as(input, format)?)
+}
+
+/// Like [`parse`], also returning the `x-` extensions and unknown fields the
+/// spec model drops. See [`extras`].
+pub fn parse_with_extras(input: &str) -> Result<(oas3::Spec, extras::Extras), error::ParserError> {
+    parse_with_extras_as(input, diagnostic::SourceFormat::detect(input))
+}
+
+/// Like [`parse_with_extras`], reading `input` as `format`.
+pub(crate) fn parse_with_extras_as(
+    input: &str,
+    format: diagnostic::SourceFormat,
+) -> Result<(oas3::Spec, extras::Extras), error::ParserError> {
+    let value = diagnostic::parse_document_as(input, format)?;
+    let extras = extras::Extras::collect(&value);
+    Ok((deserialize_document(input, format, value)?, extras))
+}
+
+/// Like [`parse`], checking the document in strict or lenient mode (see
+/// [`options`]). Lenient mode returns the problems it tolerated as warnings.
+pub fn parse_with(
+    input: &str,
+    options: &options::ParseOptions,
+) -> Result<options::Parsed, error::ParserError> {
+    parse_with_as(input, options, diagnostic::SourceFormat::detect(input))
+}
+
+/// Like [`parse_with`], reading `input` as `format`.
+pub(crate) fn parse_with_as(
+    input: &str,
+    options: &options::ParseOptions,
+    format: diagnostic::SourceFormat,
+) -> Result<options::Parsed, error::ParserError> {
+    let mut value = diagnostic::parse_document_as(input, format)?;
+    let warnings = options::prepare(&mut value, options)?;
+    Ok(options::Parsed {
+        spec: deserialize_document(input, format, value)?,
+        warnings,
+    })
+}
+
+/// Deserialize `value`, which was parsed from `input` as `format`.
+fn deserialize_document(
+    input: &str,
+    format: diagnostic::SourceFormat,
+    mut value: serde_json::Value,
+) -> Result<oas3::Spec, error::ParserError> {
+    normalize(&mut value)?;
+    diagnostic::deserialize_spec_owned(value).or_else(|_| {
+        // The document was consumed; parse it again to find what's wrong
+        let value = diagnostic::parse_document_as(input, format)?;
+        parse_value(value).map_err(|e| diagnostic::locate(e, input))
+    })
+}
+
+/// Parse a spec from `reader` without reading it into a `String` first, for
+/// very large specs. The intermediate document is consumed while building the
+/// spec, so strings are moved rather than copied. In exchange, errors carry
+/// less context than [`parse`]: no pointer, and no line/column for a document
+/// that is valid JSON/YAML but not a valid spec.
+pub fn parse_from_reader(reader: impl std::io::Read) -> Result<oas3::Spec, error::ParserError> {
+    let mut value = diagnostic::read_document(reader)?;
+    normalize(&mut value)?;
+    diagnostic::deserialize_spec_owned(value)
+}
+
+/// Write the spec of `source` as one self-contained document, for
+/// publishing alongside generated SDKs: external refs are brought in as the
+/// source does (a [`source::FileSource`] bundles files), internal refs are
+/// inlined (see [`bundle::dereference`]) and keys are put in canonical order
+/// (see [`fmt`]). Fails if the result is not a valid spec.
+pub fn bundle_to_string(
+    source: &dyn source::SpecSource,
+    format: fmt::SpecFormat,
+) -> Result<String, error::ParserError> {
+    let bundled = || {
+        let mut document = source.document()?;
+        bundle::dereference(&mut document);
+        parse_value(document.clone())?;
+        fmt::format_value(&document, format)
     };
-    let document: oas3::Spec = serde_saphyr::from_str_with_options(input, options)?;
-    Ok(document)
+    bundled().map_err(|e| e.in_source(source.name()))
+}
+
+/// Deserialize an already loaded document.
+pub fn parse_value(mut value: serde_json::Value) -> Result<oas3::Spec, error::ParserError> {
+    normalize(&mut value)?;
+    diagnostic::deserialize_spec(&value)
+}
+
+/// Upgrade 3.0 and older JSON Schema draft keywords and keep `not` and
+/// boolean schemas, which oas3 would otherwise drop or reject. Fails on
+/// unsupported dialects and on `$ref` cycles oas3 would follow forever.
+fn normalize(value: &mut serde_json::Value) -> Result<(), error::ParserError> {
+    let version = version::SpecVersion::detect(value)?;
+    if version == version::SpecVersion::V3_1 {
+        dialect::normalize(value)?;
+    }
+    compat::normalize(value, version);
+    cycle::check(value)
 }
 
 #[cfg(test)]
@@ -32,4 +164,59 @@ mod tests {
         assert_eq!(document.info.version, "2.0.0");
         assert_eq!(document.paths.iter().len(), 1);
     }
+
+    #[test]
+    fn test_bundle_to_string() {
+        let dir = std::env::temp_dir().join(format!("oas-gen-bundle-out-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("openapi.yaml"),
+            r#"openapi: 3.1.0
+info: { title: Pets, version: "1" }
+paths:
+  /pets:
+    get:
+      responses:
+        "200":
+          description: ok
+          content:
+            application/json:
+              schema: { $ref: "./pet.yaml" }
+"#,
+        )
+        .unwrap();
+        std::fs::write(dir.join("pet.yaml"), "type: object\n").unwrap();
+
+        let source = source::FileSource::new(dir.join("openapi.yaml"));
+        let output = bundle_to_string(&source, fmt::SpecFormat::Json).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(
+            value["paths"]["/pets"]["get"]["responses"]["200"]["content"]["application/json"]["schema"],
+            serde_json::json!({ "type": "object" })
+        );
+        assert!(output.starts_with("{\n  \"openapi\""));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_parse_from_reader() {
+        let file = std::fs::File::open("test-data/unkey.yml").expect("Failed to open file");
+        let document = parse_from_reader(file).expect("Failed to parse file");
+        assert_eq!(document.info.title, "Unkey API");
+
+        let json = br#"{"openapi": "3.1.0", "info": {"title": "JSON", "version": "1"}}"#;
+        let document = parse_from_reader(&json[..]).unwrap();
+        assert_eq!(document.info.title, "JSON");
+
+        let broken = b"{\n  \"openapi\": \"3.1.0\",\n  \"info\": ]\n}";
+        match parse_from_reader(&broken[..]) {
+            Err(error::ParserError::Parse(found)) => {
+                assert_eq!(found.format, Some(diagnostic::SourceFormat::Json));
+                assert_eq!(found.line, Some(3));
+                assert_eq!(found.pointer, None);
+            }
+            other => panic!("expected a diagnostic, got {:?}", other.map(|_| ())),
+        }
+    }
 }
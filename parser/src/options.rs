@@ -0,0 +1,180 @@
+//! Strict and lenient parsing.
+//!
+//! [`ParseOptions`] picks how a document that breaks the OpenAPI meta-schema
+//! is handled:
+//!
+//! - strict: any [`Violation`] (unknown keys, missing required fields,
+//!   malformed status codes, ...) fails with [`ParserError::Invalid`]
+//! - lenient (the default): parsing goes on as far as the spec model
+//!   allows. With `validate`, violations are returned as warnings; with
+//!   `repair`, common mistakes are also fixed first (see [`crate::repair`]).
+//!   Validation walks the whole document, so it is off unless asked for.
+//!
+//! In both modes `$dynamicRef`s that can't be resolved statically are
+//! reported as warnings (see [`crate::dynamic_ref`]).
+
+use serde_json::Value;
+
+use crate::error::{ParserError, Result};
+use crate::repair::Repair;
+use crate::validate::Violation;
+use crate::version::SpecVersion;
+
+/// How to parse a document.
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    /// Fail on any meta-schema violation.
+    pub strict: bool,
+    /// In lenient mode, repair common mistakes before parsing.
+    pub repair: bool,
+    /// In lenient mode, return meta-schema violations as warnings. Strict
+    /// mode always validates.
+    pub validate: bool,
+}
+
+impl ParseOptions {
+    /// Options that reject any meta-schema violation.
+    pub fn strict() -> Self {
+        Self {
+            strict: true,
+            repair: false,
+            validate: true,
+        }
+    }
+
+    /// Lenient options that also repair common mistakes and report the
+    /// violations left.
+    pub fn repairing() -> Self {
+        Self {
+            strict: false,
+            repair: true,
+            validate: true,
+        }
+    }
+}
+
+/// Something wrong with a document that didn't stop it from parsing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Warning {
+    /// A meta-schema violation tolerated in lenient mode.
+    Violation(Violation),
+    /// A change made so the document could be parsed.
+    Repair(Repair),
+}
+
+impl std::fmt::Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Warning::Violation(violation) => write!(f, "{}", violation),
+            Warning::Repair(repair) => write!(f, "repaired {}", repair),
+        }
+    }
+}
+
+/// A parsed spec and the warnings collected while parsing it.
+#[derive(Debug, Clone)]
+pub struct Parsed {
+    pub spec: oas3::Spec,
+    pub warnings: Vec<Warning>,
+}
+
+/// Check and prepare `document` for deserialization according to
+/// `options`, returning the warnings found.
+pub(crate) fn prepare(document: &mut Value, options: &ParseOptions) -> Result<Vec<Warning>> {
+    let mut warnings = Vec::new();
+    if options.strict {
+        let violations = crate::validate::validate(document);
+        if !violations.is_empty() {
+            return Err(ParserError::Invalid(violations));
+        }
+    } else {
+        if options.repair {
+            warnings.extend(
+                crate::repair::repair(document)
+                    .into_iter()
+                    .map(Warning::Repair),
+            );
+        }
+        if options.validate {
+            warnings.extend(
+                crate::validate::validate(document)
+                    .into_iter()
+                    .map(Warning::Violation),
+            );
+        }
+    }
+
+    if SpecVersion::detect(document)? == SpecVersion::V3_1 {
+        warnings.extend(
+            crate::dynamic_ref::resolve(document)
+                .into_iter()
+                .map(Warning::Repair),
+        );
+    }
+    Ok(warnings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: &str = r#"
+openapi: 3.1.0
+info: { title: Pets, version: 1 }
+paths:
+  /pets:
+    get:
+      operationID: listPets
+      responses:
+        "200": { description: ok }
+"#;
+
+    #[test]
+    fn test_strict_rejects_violations() {
+        match crate::parse_with(SPEC, &ParseOptions::strict()) {
+            Err(ParserError::Invalid(violations)) => assert_eq!(
+                violations
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>(),
+                [
+                    "#/info/version: expected a string",
+                    "#/paths/~1pets/get/operationID: unknown field `operationID`",
+                ]
+            ),
+            other => panic!("expected violations, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn test_lenient_returns_warnings() {
+        let parsed = crate::parse_with(SPEC, &ParseOptions::repairing()).unwrap();
+        assert_eq!(parsed.spec.info.version, "1");
+        assert_eq!(
+            parsed
+                .warnings
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            [
+                "repaired #/info/version: converted 1 to a string",
+                "#/paths/~1pets/get/operationID: unknown field `operationID`",
+            ]
+        );
+
+        // Validation only runs when asked for
+        let options = ParseOptions {
+            repair: true,
+            ..ParseOptions::default()
+        };
+        let parsed = crate::parse_with(SPEC, &options).unwrap();
+        assert_eq!(
+            parsed
+                .warnings
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            ["repaired #/info/version: converted 1 to a string"]
+        );
+    }
+}
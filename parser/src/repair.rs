@@ -0,0 +1,363 @@
+//! Lenient loading of slightly invalid specs.
+//!
+//! Third-party specs often break the spec in small ways that make
+//! deserialization fail: a numeric `openapi` or `info.version`, an operation
+//! without `responses`, a response without `description`, `required: "true"`
+//! as a string, or `required: true` on a schema property. [`repair`] fixes
+//! these in place and records each fix, so generation can go ahead with a
+//! warning instead of an error.
+
+use serde_json::{Map, Value};
+
+use crate::diagnostic::escape;
+
+/// A fix made to the document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Repair {
+    /// JSON pointer to the repaired value (`""` for the root).
+    pub pointer: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for Repair {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{}: {}", self.pointer, self.message)
+    }
+}
+
+const METHODS: &[&str] = &[
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// Fields that hold a boolean wherever they appear.
+const BOOLEAN_FIELDS: &[&str] = &[
+    "required",
+    "deprecated",
+    "nullable",
+    "readOnly",
+    "writeOnly",
+    "allowEmptyValue",
+    "explode",
+    "allowReserved",
+    "uniqueItems",
+];
+
+/// Fields holding user data, which is never repaired.
+const DATA_FIELDS: &[&str] = &["example", "examples", "default", "enum", "const"];
+
+/// Fields whose keys are names chosen by the spec author rather than
+/// OpenAPI keywords.
+const NAMED_MAPS: &[&str] = &[
+    "paths",
+    "webhooks",
+    "properties",
+    "patternProperties",
+    "$defs",
+    "definitions",
+    "schemas",
+    "responses",
+    "parameters",
+    "requestBodies",
+    "headers",
+    "securitySchemes",
+    "links",
+    "callbacks",
+    "pathItems",
+    "content",
+    "encoding",
+    "variables",
+];
+
+/// Repair common invalid constructs in `document`, returning what was
+/// changed. Nothing is changed in a valid document.
+pub fn repair(document: &mut Value) -> Vec<Repair> {
+    let mut repairs = Vec::new();
+    let Some(root) = document.as_object_mut() else {
+        return repairs;
+    };
+
+    if let Some(version) = root.get_mut("openapi")
+        && let Value::Number(number) = version
+    {
+        let mut fixed = number.to_string();
+        while fixed.matches('.').count() < 2 {
+            fixed.push_str(".0");
+        }
+        repairs.push(Repair {
+            pointer: "/openapi".into(),
+            message: format!("converted {} to \"{}\"", number, fixed),
+        });
+        *version = Value::String(fixed);
+    }
+
+    let info = root
+        .entry("info")
+        .or_insert_with(|| Value::Object(Map::new()));
+    if let Some(info) = info.as_object_mut() {
+        for field in ["title", "version"] {
+            let pointer = format!("/info/{}", field);
+            match info.get(field) {
+                None => {
+                    info.insert(field.into(), Value::String(String::new()));
+                    repairs.push(Repair {
+                        pointer,
+                        message: format!("added missing `{}`", field),
+                    });
+                }
+                Some(value @ (Value::Number(_) | Value::Bool(_))) => {
+                    let fixed = value.to_string();
+                    repairs.push(Repair {
+                        pointer,
+                        message: format!("converted {} to a string", fixed),
+                    });
+                    info.insert(field.into(), Value::String(fixed));
+                }
+                Some(_) => {}
+            }
+        }
+    }
+
+    for key in ["paths", "webhooks"] {
+        if let Some(Value::Object(items)) = root.get_mut(key) {
+            for (path, item) in items {
+                let pointer = format!("/{}/{}", key, escape(path));
+                repair_path_item(item, &pointer, &mut repairs);
+            }
+        }
+    }
+
+    walk(document, "", false, &mut repairs);
+    repairs
+}
+
+fn repair_path_item(item: &mut Value, pointer: &str, repairs: &mut Vec<Repair>) {
+    for method in METHODS {
+        let Some(Value::Object(operation)) = item.get_mut(*method) else {
+            continue;
+        };
+        let pointer = format!("{}/{}", pointer, method);
+        let has_responses = operation
+            .get("responses")
+            .and_then(Value::as_object)
+            .is_some_and(|responses| !responses.is_empty());
+        if !has_responses {
+            let mut responses = Map::new();
+            responses.insert("default".into(), serde_json::json!({ "description": "" }));
+            operation.insert("responses".into(), Value::Object(responses));
+            repairs.push(Repair {
+                pointer: format!("{}/responses", pointer),
+                message: "added a default response to an operation without responses".into(),
+            });
+            continue;
+        }
+
+        if let Some(Value::Object(responses)) = operation.get_mut("responses") {
+            for (status, response) in responses {
+                if let Value::Object(response) = response
+                    && !response.contains_key("$ref")
+                    && !response.contains_key("description")
+                {
+                    response.insert("description".into(), Value::String(String::new()));
+                    repairs.push(Repair {
+                        pointer: format!("{}/responses/{}/description", pointer, escape(status)),
+                        message: "added missing `description`".into(),
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Fix boolean fields given as strings, and `required` flags on schema
+/// properties, anywhere in the document. `named` is set for maps whose keys
+/// are names rather than keywords.
+fn walk(value: &mut Value, pointer: &str, named: bool, repairs: &mut Vec<Repair>) {
+    match value {
+        Value::Array(items) => {
+            for (index, item) in items.iter_mut().enumerate() {
+                walk(item, &format!("{}/{}", pointer, index), false, repairs);
+            }
+        }
+        Value::Object(map) if named => {
+            for (key, child) in map {
+                walk(
+                    child,
+                    &format!("{}/{}", pointer, escape(key)),
+                    false,
+                    repairs,
+                );
+            }
+        }
+        Value::Object(map) => {
+            lift_property_required(map, pointer, repairs);
+            for (key, child) in map {
+                if DATA_FIELDS.contains(&key.as_str()) || key.starts_with("x-") {
+                    continue;
+                }
+                let child_pointer = format!("{}/{}", pointer, escape(key));
+                if BOOLEAN_FIELDS.contains(&key.as_str())
+                    && let Value::String(text) = child
+                {
+                    let fixed = match text.trim().to_ascii_lowercase().as_str() {
+                        "true" | "yes" => Some(true),
+                        "false" | "no" => Some(false),
+                        _ => None,
+                    };
+                    if let Some(fixed) = fixed {
+                        repairs.push(Repair {
+                            pointer: child_pointer,
+                            message: format!("converted \"{}\" to {}", text, fixed),
+                        });
+                        *child = Value::Bool(fixed);
+                    }
+                    continue;
+                }
+                walk(
+                    child,
+                    &child_pointer,
+                    NAMED_MAPS.contains(&key.as_str()),
+                    repairs,
+                );
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Move `required: true` from properties into the schema's `required` list.
+fn lift_property_required(
+    schema: &mut Map<String, Value>,
+    pointer: &str,
+    repairs: &mut Vec<Repair>,
+) {
+    let Some(Value::Object(properties)) = schema.get_mut("properties") else {
+        return;
+    };
+    let mut lifted = Vec::new();
+    for (name, property) in properties.iter_mut() {
+        let Some(property) = property.as_object_mut() else {
+            continue;
+        };
+        let flag = match property.get("required") {
+            Some(Value::Bool(flag)) => *flag,
+            Some(Value::String(text)) if text.eq_ignore_ascii_case("true") => true,
+            Some(Value::String(text)) if text.eq_ignore_ascii_case("false") => false,
+            _ => continue,
+        };
+        property.remove("required");
+        repairs.push(Repair {
+            pointer: format!("{}/properties/{}/required", pointer, escape(name)),
+            message: if flag {
+                "moved `required: true` to the schema's `required` list".into()
+            } else {
+                "removed `required: false`".into()
+            },
+        });
+        if flag {
+            lifted.push(Value::String(name.clone()));
+        }
+    }
+    if lifted.is_empty() {
+        return;
+    }
+
+    let required = schema
+        .entry("required")
+        .or_insert_with(|| Value::Array(Vec::new()));
+    if !required.is_array() {
+        *required = Value::Array(Vec::new());
+    }
+    if let Value::Array(required) = required {
+        for name in lifted {
+            if !required.contains(&name) {
+                required.push(name);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_repair() {
+        let mut document = json!({
+            "openapi": 3.0,
+            "info": { "title": "Pets", "version": 1 },
+            "paths": {
+                "/pets/{id}": {
+                    "get": {
+                        "parameters": [
+                            { "name": "id", "in": "path", "required": "true", "schema": { "type": "string" } }
+                        ]
+                    },
+                    "delete": {
+                        "responses": { "204": {} }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "Pet": {
+                        "type": "object",
+                        "example": { "required": "true" },
+                        "properties": {
+                            "name": { "type": "string", "required": true },
+                            "tag": { "type": "string", "required": "false" }
+                        }
+                    }
+                }
+            }
+        });
+
+        let repairs = repair(&mut document);
+        let pointers = repairs
+            .iter()
+            .map(|repair| repair.pointer.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            pointers,
+            [
+                "/openapi",
+                "/info/version",
+                "/paths/~1pets~1{id}/get/responses",
+                "/paths/~1pets~1{id}/delete/responses/204/description",
+                "/components/schemas/Pet/properties/name/required",
+                "/components/schemas/Pet/properties/tag/required",
+                "/paths/~1pets~1{id}/get/parameters/0/required",
+            ]
+        );
+
+        assert_eq!(document["openapi"], "3.0.0");
+        assert_eq!(document["info"]["version"], "1");
+        assert_eq!(
+            document["paths"]["/pets/{id}"]["get"]["parameters"][0]["required"],
+            true
+        );
+        assert_eq!(
+            document["components"]["schemas"]["Pet"]["required"],
+            json!(["name"])
+        );
+        assert_eq!(
+            document["components"]["schemas"]["Pet"]["example"],
+            json!({ "required": "true" })
+        );
+        crate::parse_value(document).unwrap();
+    }
+
+    #[test]
+    fn test_valid_document_is_untouched() {
+        let mut document = json!({
+            "openapi": "3.1.0",
+            "info": { "title": "Pets", "version": "1" },
+            "paths": {
+                "/pets": { "get": { "responses": { "200": { "description": "ok" } } } }
+            }
+        });
+        let original = document.clone();
+        assert!(repair(&mut document).is_empty());
+        assert_eq!(document, original);
+    }
+}
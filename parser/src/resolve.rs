@@ -50,15 +50,14 @@ pub fn resolve(spec_path: impl AsRef<Path>) -> Result<String> {
 }
 
 /// Parse YAML or JSON content as a JSON value
-fn parse_as_json(content: &str) -> Result<Value> {
+pub(crate) fn parse_as_json(content: &str) -> Result<Value> {
     // Try JSON first
     if let Ok(value) = serde_json::from_str(content) {
         return Ok(value);
     }
 
     // Fall back to YAML
-    let value: Value = serde_saphyr::from_str(content)?;
-    Ok(value)
+    crate::yaml::from_str(content)
 }
 
 /// Recursively resolve all $ref fields in the value
@@ -68,16 +67,26 @@ fn resolve_refs(
     file_cache: &mut HashMap<PathBuf, Value>,
     collected_schemas: &mut HashMap<String, Value>,
 ) -> Result<()> {
-    resolve_refs_with_context(value, base_dir, file_cache, collected_schemas, &[])
+    resolve_refs_with_context(
+        value,
+        base_dir,
+        file_cache,
+        collected_schemas,
+        &[],
+        &mut Vec::new(),
+    )
 }
 
-/// Recursively resolve all $ref fields in the value with context tracking
+/// Recursively resolve all $ref fields in the value with context tracking.
+/// `inlining` holds the `file#pointer` targets being inlined, to report
+/// cycles.
 fn resolve_refs_with_context(
     value: &mut Value,
     base_dir: &Path,
     file_cache: &mut HashMap<PathBuf, Value>,
     collected_schemas: &mut HashMap<String, Value>,
     path: &[&str],
+    inlining: &mut Vec<String>,
 ) -> Result<()> {
     match value {
         Value::Object(map) => {
@@ -135,14 +144,24 @@ fn resolve_refs_with_context(
                             ))
                         })?;
 
+                        let target = format!("{}#{}", canonical_path.display(), json_pointer);
+                        if let Some(start) = inlining.iter().position(|t| *t == target) {
+                            let mut chain = inlining[start..].to_vec();
+                            chain.push(target);
+                            return Err(ParserError::CircularRef(chain));
+                        }
+
                         // Recursively resolve refs in the inlined content
+                        inlining.push(target);
                         resolve_refs_with_context(
                             &mut referenced_value,
                             new_base_dir,
                             file_cache,
                             collected_schemas,
                             path,
+                            inlining,
                         )?;
+                        inlining.pop();
 
                         // Replace the entire object with the referenced value
                         *value = referenced_value;
@@ -162,6 +181,7 @@ fn resolve_refs_with_context(
                         file_cache,
                         collected_schemas,
                         &new_path,
+                        inlining,
                     )?;
                 }
             }
@@ -169,7 +189,14 @@ fn resolve_refs_with_context(
         Value::Array(arr) => {
             // Recursively process all items in the array
             for item in arr.iter_mut() {
-                resolve_refs_with_context(item, base_dir, file_cache, collected_schemas, path)?;
+                resolve_refs_with_context(
+                    item,
+                    base_dir,
+                    file_cache,
+                    collected_schemas,
+                    path,
+                    inlining,
+                )?;
             }
         }
         _ => {
@@ -0,0 +1,488 @@
+//! Where a spec is loaded from.
+//!
+//! Every way of getting a spec into the generator goes through a
+//! [`SpecSource`], so fixes to loading land in one place. Multi-file specs
+//! can be read by two backends: the bundler ([`crate::bundle`]), which is the
+//! default and also handles remote refs, and the older string-based resolver
+//! ([`crate::resolve`]), kept for the CLI's `--resolve` flag. Which one runs
+//! is a detail of [`FileSource`].
+//!
+//! Specs that don't live in a file can be loaded from a string
+//! ([`StringSource`], also what [`crate::read_source`] returns for stdin and
+//! URLs), an already parsed document ([`ValueSource`]) or a URL
+//! ([`UrlSource`]). Embedders keeping specs in a database or object storage
+//! implement [`SpecSource`] themselves: a name and a [`Value`] are all it
+//! takes. Errors from the provided `load*` methods name the source (see
+//! [`ParserError::in_source`]).
+
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::error::{ParserError, Result};
+use crate::extras::Extras;
+use crate::fetch::{Fetcher, RemoteRefs};
+use crate::fmt::SpecFormat;
+use crate::options::{ParseOptions, Parsed};
+
+/// Something a spec can be loaded from.
+pub trait SpecSource {
+    /// Human-readable name of the source, e.g. the file path, for messages.
+    fn name(&self) -> String;
+
+    /// Read the document, with external refs brought into it.
+    fn document(&self) -> Result<Value>;
+
+    /// The spec as written, for tools that report positions in it such as
+    /// the linter. `None` for sources without a single text form.
+    fn text(&self) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    /// The file the spec is read from, if any, e.g. to find config files
+    /// next to it.
+    fn file(&self) -> Option<&Path> {
+        None
+    }
+
+    /// Read and parse the spec.
+    fn load(&self) -> Result<oas3::Spec> {
+        self.document()
+            .and_then(crate::parse_value)
+            .map_err(|e| e.in_source(self.name()))
+    }
+
+    /// Read and parse the spec in strict or lenient mode (see
+    /// [`crate::options`]).
+    fn load_with_options(&self, options: &ParseOptions) -> Result<Parsed> {
+        let parsed = || -> Result<Parsed> {
+            let mut document = self.document()?;
+            let warnings = crate::options::prepare(&mut document, options)?;
+            Ok(Parsed {
+                spec: crate::parse_value(document)?,
+                warnings,
+            })
+        };
+        parsed().map_err(|e| e.in_source(self.name()))
+    }
+
+    /// Read and parse the spec, also returning the fields the spec model
+    /// drops. See [`crate::extras`].
+    fn load_with_extras(&self) -> Result<(oas3::Spec, Extras)> {
+        let loaded = || -> Result<(oas3::Spec, Extras)> {
+            let document = self.document()?;
+            let extras = Extras::collect(&document);
+            Ok((crate::parse_value(document)?, extras))
+        };
+        loaded().map_err(|e| e.in_source(self.name()))
+    }
+}
+
+impl<S: SpecSource + ?Sized> SpecSource for &S {
+    fn name(&self) -> String {
+        (**self).name()
+    }
+
+    fn document(&self) -> Result<Value> {
+        (**self).document()
+    }
+
+    fn text(&self) -> Result<Option<String>> {
+        (**self).text()
+    }
+
+    fn file(&self) -> Option<&Path> {
+        (**self).file()
+    }
+
+    fn load(&self) -> Result<oas3::Spec> {
+        (**self).load()
+    }
+
+    fn load_with_options(&self, options: &ParseOptions) -> Result<Parsed> {
+        (**self).load_with_options(options)
+    }
+
+    fn load_with_extras(&self) -> Result<(oas3::Spec, Extras)> {
+        (**self).load_with_extras()
+    }
+}
+
+/// A spec file on disk, possibly referencing other files and URLs.
+pub struct FileSource<'a> {
+    path: PathBuf,
+    content: Option<String>,
+    backend: Backend<'a>,
+}
+
+enum Backend<'a> {
+    Bundle { remote: Option<&'a RemoteRefs> },
+    Resolve,
+}
+
+impl<'a> FileSource<'a> {
+    /// Load `path` with the bundler. Remote refs are left untouched.
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            content: None,
+            backend: Backend::Bundle { remote: None },
+        }
+    }
+
+    /// Use `content`, already read from the path, instead of reading the
+    /// file again. Files it references are still read from disk. Ignored by
+    /// the legacy resolver.
+    pub fn with_content(mut self, content: impl Into<String>) -> Self {
+        self.content = Some(content.into());
+        self
+    }
+
+    /// Also load remote refs through `remote`.
+    pub fn with_remote_refs(mut self, remote: &'a RemoteRefs) -> Self {
+        self.backend = Backend::Bundle {
+            remote: Some(remote),
+        };
+        self
+    }
+
+    /// Resolve file refs with [`crate::resolve::resolve`] instead of the
+    /// bundler. Remote refs are not supported.
+    pub fn with_legacy_resolver(mut self) -> Self {
+        self.backend = Backend::Resolve;
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl SpecSource for FileSource<'_> {
+    fn name(&self) -> String {
+        self.path.display().to_string()
+    }
+
+    fn document(&self) -> Result<Value> {
+        match self.backend {
+            Backend::Bundle { remote } => {
+                crate::bundle::bundle_from(&self.path, self.content.as_deref(), remote)
+            }
+            Backend::Resolve => Ok(serde_json::from_str(&crate::resolve::resolve(&self.path)?)?),
+        }
+    }
+
+    fn text(&self) -> Result<Option<String>> {
+        match &self.content {
+            Some(content) => Ok(Some(content.clone())),
+            None => Ok(Some(std::fs::read_to_string(&self.path)?)),
+        }
+    }
+
+    fn file(&self) -> Option<&Path> {
+        Some(&self.path)
+    }
+}
+
+/// A spec held in a string, e.g. read from a database, stdin or a URL.
+///
+/// Relative file refs can't be resolved without a directory; use
+/// [`FileSource`] for specs that have them.
+#[derive(Debug, Clone)]
+pub struct StringSource {
+    name: String,
+    content: String,
+    format: SpecFormat,
+}
+
+impl StringSource {
+    /// `name` identifies the spec in errors, e.g. `db://specs/42`. The
+    /// format is detected from the content.
+    pub fn new(name: impl Into<String>, content: impl Into<String>) -> Self {
+        let content = content.into();
+        Self {
+            name: name.into(),
+            format: SpecFormat::detect(&content),
+            content,
+        }
+    }
+
+    /// Parse the content as `format`, e.g. the one a `Content-Type` names,
+    /// instead of the detected one.
+    pub fn with_format(mut self, format: SpecFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+
+    pub fn format(&self) -> SpecFormat {
+        self.format
+    }
+}
+
+impl SpecSource for StringSource {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn document(&self) -> Result<Value> {
+        crate::diagnostic::parse_document_as(&self.content, self.format.into())
+    }
+
+    fn text(&self) -> Result<Option<String>> {
+        Ok(Some(self.content.clone()))
+    }
+
+    /// Parses from the text as [`format`](Self::format), so errors have
+    /// lines and columns.
+    fn load(&self) -> Result<oas3::Spec> {
+        crate::parse_as(&self.content, self.format.into()).map_err(|e| e.in_source(self.name()))
+    }
+
+    /// Parses from the text as [`format`](Self::format), so errors have
+    /// lines and columns.
+    fn load_with_options(&self, options: &ParseOptions) -> Result<Parsed> {
+        crate::parse_with_as(&self.content, options, self.format.into())
+            .map_err(|e| e.in_source(self.name()))
+    }
+
+    /// Parses from the text as [`format`](Self::format), so errors have
+    /// lines and columns.
+    fn load_with_extras(&self) -> Result<(oas3::Spec, Extras)> {
+        crate::parse_with_extras_as(&self.content, self.format.into())
+            .map_err(|e| e.in_source(self.name()))
+    }
+}
+
+/// An already parsed JSON/YAML document.
+#[derive(Debug, Clone)]
+pub struct ValueSource {
+    name: String,
+    document: Value,
+}
+
+impl ValueSource {
+    pub fn new(name: impl Into<String>, document: Value) -> Self {
+        Self {
+            name: name.into(),
+            document,
+        }
+    }
+}
+
+impl SpecSource for ValueSource {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn document(&self) -> Result<Value> {
+        Ok(self.document.clone())
+    }
+}
+
+/// A spec fetched from a URL with any [`Fetcher`], e.g. a signed
+/// object-storage URL. Refs to other documents are only followed with
+/// [`with_remote_refs`](Self::with_remote_refs).
+pub struct UrlSource<'a> {
+    url: String,
+    fetcher: &'a dyn Fetcher,
+    content: Option<String>,
+    format: Option<SpecFormat>,
+    remote: Option<&'a RemoteRefs>,
+}
+
+impl<'a> UrlSource<'a> {
+    pub fn new(url: impl Into<String>, fetcher: &'a dyn Fetcher) -> Self {
+        Self {
+            url: url.into(),
+            fetcher,
+            content: None,
+            format: None,
+            remote: None,
+        }
+    }
+
+    /// Use `content`, already fetched from the URL, instead of fetching it
+    /// again.
+    pub fn with_content(mut self, content: impl Into<String>) -> Self {
+        self.content = Some(content.into());
+        self
+    }
+
+    /// Parse the spec as `format`, e.g. the one its `Content-Type` names,
+    /// instead of the detected one.
+    pub fn with_format(mut self, format: SpecFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// Load the documents the spec references through `remote`, resolving
+    /// relative refs against the URL.
+    pub fn with_remote_refs(mut self, remote: &'a RemoteRefs) -> Self {
+        self.remote = Some(remote);
+        self
+    }
+
+    fn content(&self) -> Result<String> {
+        match &self.content {
+            Some(content) => Ok(content.clone()),
+            None => self.fetcher.fetch(&self.url),
+        }
+    }
+}
+
+impl SpecSource for UrlSource<'_> {
+    fn name(&self) -> String {
+        self.url.clone()
+    }
+
+    fn document(&self) -> Result<Value> {
+        let content = self.content()?;
+        let document = match self.format {
+            Some(format) => crate::diagnostic::parse_document_as(&content, format.into())?,
+            None => crate::diagnostic::parse_document(&content)?,
+        };
+        match self.remote {
+            Some(remote) => crate::bundle::bundle_url(&self.url, document, remote),
+            None => Ok(document),
+        }
+    }
+
+    /// The content given to [`with_content`](Self::with_content); `None`
+    /// rather than fetching the spec twice.
+    fn text(&self) -> Result<Option<String>> {
+        Ok(self.content.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backends_agree() {
+        let dir = std::env::temp_dir().join(format!("oas-gen-source-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("openapi.yaml"),
+            r#"openapi: 3.1.0
+info: { title: Pets, version: "1" }
+paths:
+  /pets:
+    get:
+      responses:
+        "200":
+          description: ok
+          content:
+            application/json:
+              schema: { $ref: "./pet.yaml#/Pet" }
+"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("pet.yaml"),
+            "Pet:\n  type: object\n  properties:\n    name: { type: string }\n",
+        )
+        .unwrap();
+
+        let bundled = FileSource::new(dir.join("openapi.yaml")).load().unwrap();
+        let resolved = FileSource::new(dir.join("openapi.yaml"))
+            .with_legacy_resolver()
+            .load()
+            .unwrap();
+        assert_eq!(bundled.info.title, resolved.info.title);
+        let schema_names = |spec: &oas3::Spec| {
+            spec.components
+                .as_ref()
+                .map(|components| components.schemas.keys().cloned().collect::<Vec<_>>())
+                .unwrap_or_default()
+        };
+        assert!(schema_names(&bundled).contains(&"Pet".to_string()));
+        assert!(schema_names(&resolved).contains(&"Pet".to_string()));
+
+        // Content that was already read is used instead of the file, and
+        // its refs still resolve against the file's directory
+        let content = std::fs::read_to_string(dir.join("openapi.yaml")).unwrap();
+        let preloaded = FileSource::new(dir.join("openapi.yaml"))
+            .with_content(content.replace("title: Pets", "title: Preloaded"))
+            .load()
+            .unwrap();
+        assert_eq!(preloaded.info.title, "Preloaded");
+        assert!(schema_names(&preloaded).contains(&"Pet".to_string()));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_in_memory_sources() {
+        let spec = "openapi: 3.1.0\ninfo: { title: Pets, version: '1' }\npaths: {}\n";
+
+        let source = StringSource::new("db://specs/42", spec);
+        assert_eq!(source.load().unwrap().info.title, "Pets");
+
+        let document = crate::diagnostic::parse_document(spec).unwrap();
+        let source = ValueSource::new("cache", document);
+        assert_eq!(source.load_with_extras().unwrap().0.info.title, "Pets");
+
+        let served = |_: &str| -> Result<String> { Ok(spec.to_string()) };
+        let source = UrlSource::new("s3://bucket/openapi.yaml", &served);
+        assert_eq!(source.load().unwrap().info.title, "Pets");
+
+        // Errors name the source and keep their location
+        let broken = StringSource::new("db://specs/43", "openapi: 3.1.0\ninfo: [\n");
+        let err = broken.load().unwrap_err();
+        assert_eq!(err.source_name(), Some("db://specs/43"));
+        assert!(matches!(err.inner(), ParserError::Parse(d) if d.line.is_some()));
+        assert!(err.to_string().starts_with("db://specs/43: "));
+
+        // Including errors in a well-formed document that isn't a valid spec
+        let invalid = StringSource::new("db://specs/44", "openapi: 3.1.0\ninfo: { title: 1 }\n");
+        let located =
+            |err: &ParserError| matches!(err.inner(), ParserError::Parse(d) if d.line.is_some());
+        assert!(located(
+            &invalid
+                .load_with_options(&ParseOptions::default())
+                .unwrap_err()
+        ));
+        assert!(located(&(&invalid).load_with_extras().unwrap_err()));
+
+        let err = UrlSource::new("s3://bucket/gone.yaml", &|url: &str| -> Result<String> {
+            Err(ParserError::UnresolvedRef(url.to_string()))
+        })
+        .load_with_options(&ParseOptions::default())
+        .unwrap_err();
+        assert_eq!(err.source_name(), Some("s3://bucket/gone.yaml"));
+    }
+
+    #[test]
+    fn test_url_source_resolves_refs_against_its_url() {
+        let spec = r##"openapi: 3.1.0
+info: { title: Pets, version: "1" }
+paths:
+  /pets:
+    get:
+      responses:
+        "200":
+          description: ok
+          content:
+            application/json:
+              schema: { $ref: "schemas/pet.yaml#/Pet" }
+"##;
+        let remote = RemoteRefs::new(|url: &str| -> Result<String> {
+            assert_eq!(url, "https://specs.acme.dev/v1/schemas/pet.yaml");
+            Ok("Pet:\n  type: object\n".to_string())
+        })
+        .allow("https://specs.acme.dev/");
+        let unreachable = |url: &str| -> Result<String> { panic!("fetched {}", url) };
+
+        let source = UrlSource::new("https://specs.acme.dev/v1/openapi.yaml", &unreachable)
+            .with_content(spec)
+            .with_remote_refs(&remote);
+        assert_eq!(source.text().unwrap().as_deref(), Some(spec));
+        let spec = source.load().unwrap();
+        assert!(spec.components.unwrap().schemas.contains_key("Pet"));
+    }
+}
@@ -0,0 +1,828 @@
+//! Structural validation against the OpenAPI 3.0/3.1 meta-schema.
+//!
+//! Deserializing into [`oas3::Spec`] is forgiving: unknown fields are dropped
+//! and many constraints are never checked. [`validate`] checks a document the
+//! way the official OpenAPI JSON Schemas do: required fields, value types,
+//! enumerated values, key patterns (paths, status codes, component names) and
+//! unknown fields, where only `x-` extensions are allowed. Schema objects are
+//! only checked to be schemas; their JSON Schema keywords are not validated.
+
+use serde_json::{Map, Value};
+
+use crate::error::Result;
+use crate::extras::Extras;
+use crate::version::SpecVersion;
+
+/// A place where the document breaks the meta-schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// JSON pointer to the offending value (`""` for the root).
+    pub pointer: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{}: {}", self.pointer, self.message)
+    }
+}
+
+/// Check `document` against the meta-schema of its OpenAPI version. An empty
+/// list means the document is structurally valid. Documents without a
+/// supported `openapi` version yield a single violation.
+pub fn validate(document: &Value) -> Vec<Violation> {
+    match walk(document) {
+        Ok(validator) => validator.violations,
+        Err(e) => vec![Violation {
+            pointer: String::new(),
+            message: e.to_string(),
+        }],
+    }
+}
+
+/// Walk `document` against the meta-schema, collecting violations and
+/// fields outside the OpenAPI vocabulary.
+fn walk(document: &Value) -> Result<Validator> {
+    let mut validator = Validator {
+        version: SpecVersion::detect(document)?,
+        violations: Vec::new(),
+        extras: Extras::default(),
+    };
+    validator.check(document, "", &Field::Obj(Kind::Root));
+    Ok(validator)
+}
+
+/// `x-` extensions and unknown fields of `document`. Empty when the version
+/// is unsupported.
+pub(crate) fn extras(document: &Value) -> Extras {
+    walk(document)
+        .map(|validator| validator.extras)
+        .unwrap_or_default()
+}
+
+/// Object types of the meta-schema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Root,
+    Info,
+    Contact,
+    License,
+    Server,
+    ServerVariable,
+    Components,
+    Paths,
+    PathItem,
+    Operation,
+    ExternalDocs,
+    Parameter,
+    Header,
+    RequestBody,
+    MediaType,
+    Encoding,
+    Responses,
+    Response,
+    Callback,
+    Example,
+    Link,
+    Tag,
+    SecurityScheme,
+    OAuthFlows,
+    OAuthFlow(&'static str),
+    SecurityRequirement,
+}
+
+/// Expected shape of a field value.
+#[derive(Debug, Clone, Copy)]
+enum Field {
+    Any,
+    Str,
+    Bool,
+    Enum(&'static [&'static str]),
+    StrList,
+    StrMap,
+    Schema,
+    SchemaMap,
+    Obj(Kind),
+    RefOr(Kind),
+    List(Kind),
+    RefOrList(Kind),
+    Map(Kind),
+    RefOrMap(Kind),
+}
+
+const PARAMETER_STYLES: &[&str] = &[
+    "matrix",
+    "label",
+    "form",
+    "simple",
+    "spaceDelimited",
+    "pipeDelimited",
+    "deepObject",
+];
+
+/// JSON Schema and OpenAPI schema keywords; anything else is an extra.
+const SCHEMA_KEYWORDS: &[&str] = &[
+    "$ref",
+    "$id",
+    "$schema",
+    "$anchor",
+    "$dynamicRef",
+    "$dynamicAnchor",
+    "$defs",
+    "$comment",
+    "$vocabulary",
+    "definitions",
+    "title",
+    "description",
+    "type",
+    "enum",
+    "const",
+    "default",
+    "examples",
+    "example",
+    "format",
+    "multipleOf",
+    "maximum",
+    "exclusiveMaximum",
+    "minimum",
+    "exclusiveMinimum",
+    "maxLength",
+    "minLength",
+    "pattern",
+    "maxItems",
+    "minItems",
+    "uniqueItems",
+    "maxContains",
+    "minContains",
+    "maxProperties",
+    "minProperties",
+    "required",
+    "dependentRequired",
+    "properties",
+    "patternProperties",
+    "additionalProperties",
+    "propertyNames",
+    "items",
+    "additionalItems",
+    "prefixItems",
+    "contains",
+    "allOf",
+    "anyOf",
+    "oneOf",
+    "not",
+    "if",
+    "then",
+    "else",
+    "dependentSchemas",
+    "unevaluatedItems",
+    "unevaluatedProperties",
+    "contentEncoding",
+    "contentMediaType",
+    "contentSchema",
+    "deprecated",
+    "readOnly",
+    "writeOnly",
+    "nullable",
+    "discriminator",
+    "xml",
+    "externalDocs",
+];
+
+const METHODS: &[&str] = &[
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+struct Validator {
+    version: SpecVersion,
+    violations: Vec<Violation>,
+    extras: Extras,
+}
+
+impl Validator {
+    fn report(&mut self, pointer: &str, message: impl Into<String>) {
+        self.violations.push(Violation {
+            pointer: pointer.to_string(),
+            message: message.into(),
+        });
+    }
+
+    fn check(&mut self, value: &Value, pointer: &str, field: &Field) {
+        match field {
+            Field::Any => {}
+            Field::Str => {
+                if !value.is_string() {
+                    self.report(pointer, "expected a string");
+                }
+            }
+            Field::Bool => {
+                if !value.is_boolean() {
+                    self.report(pointer, "expected a boolean");
+                }
+            }
+            Field::Enum(allowed) => match value.as_str() {
+                Some(s) if allowed.contains(&s) => {}
+                _ => self.report(pointer, format!("expected one of: {}", allowed.join(", "))),
+            },
+            Field::StrList => match value.as_array() {
+                Some(items) => {
+                    for (i, item) in items.iter().enumerate() {
+                        self.check(item, &format!("{}/{}", pointer, i), &Field::Str);
+                    }
+                }
+                None => self.report(pointer, "expected an array of strings"),
+            },
+            Field::StrMap => match value.as_object() {
+                Some(map) => {
+                    for (key, item) in map {
+                        self.check(item, &child(pointer, key), &Field::Str);
+                    }
+                }
+                None => self.report(pointer, "expected an object"),
+            },
+            Field::Schema => {
+                let boolean_allowed = self.version == SpecVersion::V3_1;
+                match value.as_object() {
+                    Some(map) => self.schema(map, pointer),
+                    None if boolean_allowed && value.is_boolean() => {}
+                    None => self.report(pointer, "expected a schema object"),
+                }
+            }
+            Field::SchemaMap => match value.as_object() {
+                Some(map) => {
+                    for (name, schema) in map {
+                        self.check(schema, &child(pointer, name), &Field::Schema);
+                    }
+                }
+                None => self.report(pointer, "expected an object"),
+            },
+            Field::Obj(kind) => match value.as_object() {
+                Some(map) => self.object(map, pointer, *kind),
+                None => self.report(pointer, "expected an object"),
+            },
+            Field::RefOr(kind) => match value.as_object() {
+                Some(map) if map.contains_key("$ref") => {
+                    self.check(&map["$ref"], &child(pointer, "$ref"), &Field::Str)
+                }
+                Some(map) => self.object(map, pointer, *kind),
+                None => self.report(pointer, "expected an object"),
+            },
+            Field::List(kind) | Field::RefOrList(kind) => match value.as_array() {
+                Some(items) => {
+                    let item_field = match field {
+                        Field::List(_) => Field::Obj(*kind),
+                        _ => Field::RefOr(*kind),
+                    };
+                    for (i, item) in items.iter().enumerate() {
+                        self.check(item, &format!("{}/{}", pointer, i), &item_field);
+                    }
+                }
+                None => self.report(pointer, "expected an array"),
+            },
+            Field::Map(kind) | Field::RefOrMap(kind) => match value.as_object() {
+                Some(map) => {
+                    let item_field = match field {
+                        Field::Map(_) => Field::Obj(*kind),
+                        _ => Field::RefOr(*kind),
+                    };
+                    for (key, item) in map {
+                        self.check(item, &child(pointer, key), &item_field);
+                    }
+                }
+                None => self.report(pointer, "expected an object"),
+            },
+        }
+    }
+
+    fn object(&mut self, map: &Map<String, Value>, pointer: &str, kind: Kind) {
+        match kind {
+            Kind::Paths => return self.paths(map, pointer),
+            Kind::Responses => return self.responses(map, pointer),
+            Kind::Callback => {
+                self.extensions(map, pointer);
+                for (expression, item) in map.iter().filter(|(key, _)| !key.starts_with("x-")) {
+                    self.check(
+                        item,
+                        &child(pointer, expression),
+                        &Field::RefOr(Kind::PathItem),
+                    );
+                }
+                return;
+            }
+            Kind::SecurityRequirement => {
+                for (name, scopes) in map {
+                    self.check(scopes, &child(pointer, name), &Field::StrList);
+                }
+                return;
+            }
+            _ => {}
+        }
+
+        let fields = self.fields(kind);
+        for (key, value) in map {
+            if key.starts_with("x-") {
+                self.extras.insert(pointer, key, value);
+                continue;
+            }
+            match fields.iter().find(|(name, _)| name == key) {
+                Some((_, field)) => self.check(value, &child(pointer, key), field),
+                None => {
+                    self.extras.insert(pointer, key, value);
+                    self.report(&child(pointer, key), format!("unknown field `{}`", key));
+                }
+            }
+        }
+        for required in self.required(kind, map) {
+            if !map.contains_key(required) {
+                self.report(pointer, format!("missing required field `{}`", required));
+            }
+        }
+        self.rules(kind, map, pointer);
+    }
+
+    fn paths(&mut self, map: &Map<String, Value>, pointer: &str) {
+        self.extensions(map, pointer);
+        for (path, item) in map {
+            if path.starts_with("x-") {
+                continue;
+            }
+            let item_pointer = child(pointer, path);
+            if !path.starts_with('/') {
+                self.report(&item_pointer, "path must start with `/`");
+            }
+            self.check(item, &item_pointer, &Field::Obj(Kind::PathItem));
+        }
+    }
+
+    fn responses(&mut self, map: &Map<String, Value>, pointer: &str) {
+        self.extensions(map, pointer);
+        let mut count = 0;
+        for (status, response) in map {
+            if status.starts_with("x-") {
+                continue;
+            }
+            count += 1;
+            let response_pointer = child(pointer, status);
+            if !is_status_key(status) {
+                self.report(
+                    &response_pointer,
+                    "response key must be `default`, a status code or a range like `4XX`",
+                );
+            }
+            self.check(response, &response_pointer, &Field::RefOr(Kind::Response));
+        }
+        if count == 0 && self.version == SpecVersion::V3_0 {
+            self.report(pointer, "at least one response is required");
+        }
+    }
+
+    /// Record the `x-` extensions of an object whose other keys are names.
+    fn extensions(&mut self, map: &Map<String, Value>, pointer: &str) {
+        for (key, value) in map.iter().filter(|(key, _)| key.starts_with("x-")) {
+            self.extras.insert(pointer, key, value);
+        }
+    }
+
+    /// Schemas are not validated, only searched for extras.
+    fn schema(&mut self, map: &Map<String, Value>, pointer: &str) {
+        for (key, value) in map {
+            if key.starts_with("x-") || !SCHEMA_KEYWORDS.contains(&key.as_str()) {
+                self.extras.insert(pointer, key, value);
+                continue;
+            }
+            let pointer = child(pointer, key);
+            match key.as_str() {
+                "properties" | "patternProperties" | "$defs" | "definitions"
+                | "dependentSchemas" => {
+                    if let Some(schemas) = value.as_object() {
+                        for (name, schema) in schemas {
+                            self.subschema(schema, &child(&pointer, name));
+                        }
+                    }
+                }
+                "allOf" | "anyOf" | "oneOf" | "prefixItems" => {
+                    if let Some(schemas) = value.as_array() {
+                        for (i, schema) in schemas.iter().enumerate() {
+                            self.subschema(schema, &format!("{}/{}", pointer, i));
+                        }
+                    }
+                }
+                "items"
+                | "additionalProperties"
+                | "not"
+                | "contains"
+                | "if"
+                | "then"
+                | "else"
+                | "propertyNames"
+                | "unevaluatedItems"
+                | "unevaluatedProperties"
+                | "contentSchema"
+                | "additionalItems" => self.subschema(value, &pointer),
+                _ => {}
+            }
+        }
+    }
+
+    fn subschema(&mut self, value: &Value, pointer: &str) {
+        if let Some(map) = value.as_object() {
+            self.schema(map, pointer);
+        }
+    }
+
+    fn required(&self, kind: Kind, map: &Map<String, Value>) -> Vec<&'static str> {
+        let v3_0 = self.version == SpecVersion::V3_0;
+        match kind {
+            Kind::Root if v3_0 => vec!["openapi", "info", "paths"],
+            Kind::Root => vec!["openapi", "info"],
+            Kind::Info => vec!["title", "version"],
+            Kind::License => vec!["name"],
+            Kind::Server => vec!["url"],
+            Kind::ServerVariable => vec!["default"],
+            Kind::Operation if v3_0 => vec!["responses"],
+            Kind::ExternalDocs => vec!["url"],
+            Kind::Parameter => vec!["name", "in"],
+            Kind::RequestBody => vec!["content"],
+            Kind::Response => vec!["description"],
+            Kind::Tag => vec!["name"],
+            Kind::SecurityScheme => match map.get("type").and_then(Value::as_str) {
+                Some("apiKey") => vec!["type", "name", "in"],
+                Some("http") => vec!["type", "scheme"],
+                Some("oauth2") => vec!["type", "flows"],
+                Some("openIdConnect") => vec!["type", "openIdConnectUrl"],
+                _ => vec!["type"],
+            },
+            Kind::OAuthFlow("implicit") => vec!["authorizationUrl", "scopes"],
+            Kind::OAuthFlow("authorizationCode") => {
+                vec!["authorizationUrl", "tokenUrl", "scopes"]
+            }
+            Kind::OAuthFlow(_) => vec!["tokenUrl", "scopes"],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Constraints between fields that a field table can't express.
+    fn rules(&mut self, kind: Kind, map: &Map<String, Value>, pointer: &str) {
+        match kind {
+            Kind::Components => {
+                for (group, components) in map.iter().filter(|(key, _)| !key.starts_with("x-")) {
+                    let names = components.as_object().into_iter().flat_map(|c| c.keys());
+                    for name in names.filter(|name| !is_component_name(name)) {
+                        self.report(
+                            &child(&child(pointer, group), name),
+                            "component names may only contain letters, digits, `.`, `-` and `_`",
+                        );
+                    }
+                }
+            }
+            Kind::Root if self.version == SpecVersion::V3_1 => {
+                if !["paths", "components", "webhooks"]
+                    .iter()
+                    .any(|key| map.contains_key(*key))
+                {
+                    self.report(
+                        pointer,
+                        "at least one of `paths`, `components` or `webhooks` is required",
+                    );
+                }
+            }
+            Kind::Parameter | Kind::Header => {
+                if map.contains_key("schema") == map.contains_key("content") {
+                    self.report(pointer, "exactly one of `schema` or `content` is required");
+                }
+                if kind == Kind::Parameter
+                    && map.get("in").and_then(Value::as_str) == Some("path")
+                    && map.get("required") != Some(&Value::Bool(true))
+                {
+                    self.report(pointer, "path parameters must be `required: true`");
+                }
+            }
+            Kind::License => {
+                if map.contains_key("identifier") && map.contains_key("url") {
+                    self.report(pointer, "`identifier` and `url` are mutually exclusive");
+                }
+            }
+            Kind::Example => {
+                if map.contains_key("value") && map.contains_key("externalValue") {
+                    self.report(
+                        pointer,
+                        "`value` and `externalValue` are mutually exclusive",
+                    );
+                }
+            }
+            Kind::Link => {
+                if map.contains_key("operationRef") && map.contains_key("operationId") {
+                    self.report(
+                        pointer,
+                        "`operationRef` and `operationId` are mutually exclusive",
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn fields(&self, kind: Kind) -> Vec<(&'static str, Field)> {
+        let v3_1 = self.version == SpecVersion::V3_1;
+        let mut fields = match kind {
+            Kind::Root => vec![
+                ("openapi", Field::Str),
+                ("info", Field::Obj(Kind::Info)),
+                ("servers", Field::List(Kind::Server)),
+                ("paths", Field::Obj(Kind::Paths)),
+                ("components", Field::Obj(Kind::Components)),
+                ("security", Field::List(Kind::SecurityRequirement)),
+                ("tags", Field::List(Kind::Tag)),
+                ("externalDocs", Field::Obj(Kind::ExternalDocs)),
+            ],
+            Kind::Info => vec![
+                ("title", Field::Str),
+                ("description", Field::Str),
+                ("termsOfService", Field::Str),
+                ("contact", Field::Obj(Kind::Contact)),
+                ("license", Field::Obj(Kind::License)),
+                ("version", Field::Str),
+            ],
+            Kind::Contact => vec![
+                ("name", Field::Str),
+                ("url", Field::Str),
+                ("email", Field::Str),
+            ],
+            Kind::License => vec![("name", Field::Str), ("url", Field::Str)],
+            Kind::Server => vec![
+                ("url", Field::Str),
+                ("description", Field::Str),
+                ("variables", Field::Map(Kind::ServerVariable)),
+            ],
+            Kind::ServerVariable => vec![
+                ("enum", Field::StrList),
+                ("default", Field::Str),
+                ("description", Field::Str),
+            ],
+            Kind::Components => vec![
+                ("schemas", Field::SchemaMap),
+                ("responses", Field::RefOrMap(Kind::Response)),
+                ("parameters", Field::RefOrMap(Kind::Parameter)),
+                ("examples", Field::RefOrMap(Kind::Example)),
+                ("requestBodies", Field::RefOrMap(Kind::RequestBody)),
+                ("headers", Field::RefOrMap(Kind::Header)),
+                ("securitySchemes", Field::RefOrMap(Kind::SecurityScheme)),
+                ("links", Field::RefOrMap(Kind::Link)),
+                ("callbacks", Field::RefOrMap(Kind::Callback)),
+            ],
+            Kind::PathItem => {
+                let mut fields = vec![
+                    ("$ref", Field::Str),
+                    ("summary", Field::Str),
+                    ("description", Field::Str),
+                    ("servers", Field::List(Kind::Server)),
+                    ("parameters", Field::RefOrList(Kind::Parameter)),
+                ];
+                fields.extend(METHODS.iter().map(|m| (*m, Field::Obj(Kind::Operation))));
+                fields
+            }
+            Kind::Operation => vec![
+                ("tags", Field::StrList),
+                ("summary", Field::Str),
+                ("description", Field::Str),
+                ("externalDocs", Field::Obj(Kind::ExternalDocs)),
+                ("operationId", Field::Str),
+                ("parameters", Field::RefOrList(Kind::Parameter)),
+                ("requestBody", Field::RefOr(Kind::RequestBody)),
+                ("responses", Field::Obj(Kind::Responses)),
+                ("callbacks", Field::RefOrMap(Kind::Callback)),
+                ("deprecated", Field::Bool),
+                ("security", Field::List(Kind::SecurityRequirement)),
+                ("servers", Field::List(Kind::Server)),
+            ],
+            Kind::ExternalDocs => vec![("description", Field::Str), ("url", Field::Str)],
+            Kind::Parameter | Kind::Header => {
+                let mut fields = vec![
+                    ("description", Field::Str),
+                    ("required", Field::Bool),
+                    ("deprecated", Field::Bool),
+                    ("allowEmptyValue", Field::Bool),
+                    ("style", Field::Enum(PARAMETER_STYLES)),
+                    ("explode", Field::Bool),
+                    ("allowReserved", Field::Bool),
+                    ("schema", Field::Schema),
+                    ("example", Field::Any),
+                    ("examples", Field::RefOrMap(Kind::Example)),
+                    ("content", Field::Map(Kind::MediaType)),
+                ];
+                if kind == Kind::Parameter {
+                    fields.push(("name", Field::Str));
+                    fields.push(("in", Field::Enum(&["query", "header", "path", "cookie"])));
+                }
+                fields
+            }
+            Kind::RequestBody => vec![
+                ("description", Field::Str),
+                ("content", Field::Map(Kind::MediaType)),
+                ("required", Field::Bool),
+            ],
+            Kind::MediaType => vec![
+                ("schema", Field::Schema),
+                ("example", Field::Any),
+                ("examples", Field::RefOrMap(Kind::Example)),
+                ("encoding", Field::Map(Kind::Encoding)),
+            ],
+            Kind::Encoding => vec![
+                ("contentType", Field::Str),
+                ("headers", Field::RefOrMap(Kind::Header)),
+                ("style", Field::Enum(PARAMETER_STYLES)),
+                ("explode", Field::Bool),
+                ("allowReserved", Field::Bool),
+            ],
+            Kind::Response => vec![
+                ("description", Field::Str),
+                ("headers", Field::RefOrMap(Kind::Header)),
+                ("content", Field::Map(Kind::MediaType)),
+                ("links", Field::RefOrMap(Kind::Link)),
+            ],
+            Kind::Example => vec![
+                ("summary", Field::Str),
+                ("description", Field::Str),
+                ("value", Field::Any),
+                ("externalValue", Field::Str),
+            ],
+            Kind::Link => vec![
+                ("operationRef", Field::Str),
+                ("operationId", Field::Str),
+                ("parameters", Field::Any),
+                ("requestBody", Field::Any),
+                ("description", Field::Str),
+                ("server", Field::Obj(Kind::Server)),
+            ],
+            Kind::Tag => vec![
+                ("name", Field::Str),
+                ("description", Field::Str),
+                ("externalDocs", Field::Obj(Kind::ExternalDocs)),
+            ],
+            Kind::SecurityScheme => vec![
+                (
+                    "type",
+                    Field::Enum(if v3_1 {
+                        &["apiKey", "http", "mutualTLS", "oauth2", "openIdConnect"]
+                    } else {
+                        &["apiKey", "http", "oauth2", "openIdConnect"]
+                    }),
+                ),
+                ("description", Field::Str),
+                ("name", Field::Str),
+                ("in", Field::Enum(&["query", "header", "cookie"])),
+                ("scheme", Field::Str),
+                ("bearerFormat", Field::Str),
+                ("flows", Field::Obj(Kind::OAuthFlows)),
+                ("openIdConnectUrl", Field::Str),
+            ],
+            Kind::OAuthFlows => vec![
+                ("implicit", Field::Obj(Kind::OAuthFlow("implicit"))),
+                ("password", Field::Obj(Kind::OAuthFlow("password"))),
+                (
+                    "clientCredentials",
+                    Field::Obj(Kind::OAuthFlow("clientCredentials")),
+                ),
+                (
+                    "authorizationCode",
+                    Field::Obj(Kind::OAuthFlow("authorizationCode")),
+                ),
+            ],
+            Kind::OAuthFlow(_) => vec![
+                ("authorizationUrl", Field::Str),
+                ("tokenUrl", Field::Str),
+                ("refreshUrl", Field::Str),
+                ("scopes", Field::StrMap),
+            ],
+            Kind::Paths | Kind::Responses | Kind::Callback | Kind::SecurityRequirement => {
+                Vec::new()
+            }
+        };
+
+        if v3_1 {
+            match kind {
+                Kind::Root => {
+                    fields.push(("jsonSchemaDialect", Field::Str));
+                    fields.push(("webhooks", Field::RefOrMap(Kind::PathItem)));
+                }
+                Kind::Info => fields.push(("summary", Field::Str)),
+                Kind::License => fields.push(("identifier", Field::Str)),
+                Kind::Components => fields.push(("pathItems", Field::RefOrMap(Kind::PathItem))),
+                _ => {}
+            }
+        }
+        fields
+    }
+}
+
+/// `default`, a status code (`200`) or a range (`4XX`).
+fn is_status_key(key: &str) -> bool {
+    if key == "default" {
+        return true;
+    }
+    let bytes = key.as_bytes();
+    bytes.len() == 3
+        && (b'1'..=b'5').contains(&bytes[0])
+        && (bytes[1..].iter().all(u8::is_ascii_digit) || &bytes[1..] == b"XX")
+}
+
+/// Matches `^[a-zA-Z0-9.\-_]+$`.
+fn is_component_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'))
+}
+
+fn child(pointer: &str, key: &str) -> String {
+    format!("{}/{}", pointer, key.replace('~', "~0").replace('/', "~1"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn violations(document: Value) -> Vec<String> {
+        validate(&document)
+            .into_iter()
+            .map(|violation| violation.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_valid_document() {
+        let document = json!({
+            "openapi": "3.1.0",
+            "info": { "title": "Pets", "version": "1", "x-logo": "pets.png" },
+            "paths": {
+                "/pets/{id}": {
+                    "parameters": [{ "$ref": "#/components/parameters/Id" }],
+                    "get": {
+                        "responses": {
+                            "200": { "description": "OK" },
+                            "4XX": { "$ref": "#/components/responses/Error" }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "parameters": {
+                    "Id": { "name": "id", "in": "path", "required": true, "schema": { "type": "integer" } }
+                },
+                "responses": { "Error": { "description": "Error" } },
+                "securitySchemes": {
+                    "oauth": {
+                        "type": "oauth2",
+                        "flows": { "clientCredentials": { "tokenUrl": "/token", "scopes": {} } }
+                    }
+                }
+            }
+        });
+        assert_eq!(violations(document), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_violations() {
+        let document = json!({
+            "openapi": "3.0.3",
+            "info": { "title": "Pets" },
+            "paths": {
+                "pets": {},
+                "/pets/{id}": {
+                    "get": {
+                        "operationID": "getPet",
+                        "parameters": [{ "name": "id", "in": "path", "schema": {} }],
+                        "responses": { "20X": { "description": "OK" } }
+                    },
+                    "post": {
+                        "responses": {}
+                    }
+                }
+            },
+            "components": {
+                "securitySchemes": { "key": { "type": "apiKey", "in": "body" } }
+            }
+        });
+        assert_eq!(
+            violations(document),
+            vec![
+                "#/components/securitySchemes/key/in: expected one of: query, header, cookie",
+                "#/components/securitySchemes/key: missing required field `name`",
+                "#/info: missing required field `version`",
+                "#/paths/~1pets~1{id}/get/operationID: unknown field `operationID`",
+                "#/paths/~1pets~1{id}/get/parameters/0: path parameters must be `required: true`",
+                "#/paths/~1pets~1{id}/get/responses/20X: response key must be `default`, a status code or a range like `4XX`",
+                "#/paths/~1pets~1{id}/post/responses: at least one response is required",
+                "#/paths/pets: path must start with `/`",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unsupported_version() {
+        assert_eq!(
+            violations(json!({ "swagger": "2.0" })),
+            vec!["#: Unsupported OpenAPI version: swagger 2.0"]
+        );
+    }
+}
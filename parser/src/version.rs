@@ -0,0 +1,237 @@
+//! OpenAPI version detection.
+
+use serde_json::Value;
+
+use crate::error::{ParserError, Result};
+use crate::fmt::SpecFormat;
+
+/// OpenAPI major/minor version of a document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SpecVersion {
+    /// Swagger 2.0 (`swagger: "2.0"`): recognized by [`SpecInfo::detect`],
+    /// but not parsed.
+    V2,
+    V3_0,
+    V3_1,
+}
+
+impl SpecVersion {
+    /// Parse the value of the `openapi` field (e.g. `"3.1.0"`).
+    pub fn from_version_str(version: &str) -> Option<Self> {
+        let mut parts = version.trim().split('.');
+        match (parts.next(), parts.next()) {
+            (Some("3"), Some("0")) => Some(SpecVersion::V3_0),
+            (Some("3"), Some("1")) => Some(SpecVersion::V3_1),
+            _ => None,
+        }
+    }
+
+    /// Read the version from a parsed document.
+    pub fn detect(document: &Value) -> Result<Self> {
+        if let Some(swagger) = document.get("swagger") {
+            return Err(ParserError::UnsupportedVersion(format!(
+                "swagger {}",
+                display_version(swagger)
+            )));
+        }
+
+        let version = document
+            .get("openapi")
+            .ok_or_else(|| ParserError::UnsupportedVersion("missing `openapi` field".into()))?;
+        version
+            .as_str()
+            .and_then(Self::from_version_str)
+            .ok_or_else(|| ParserError::UnsupportedVersion(display_version(version)))
+    }
+
+    /// `self`, or an error saying what to do with a document of a version
+    /// that can't be parsed.
+    pub fn supported(self) -> Result<Self> {
+        match self {
+            SpecVersion::V2 => Err(ParserError::UnsupportedVersion(
+                "swagger 2.0; convert it to OpenAPI 3 first (e.g. `npx swagger2openapi`)".into(),
+            )),
+            SpecVersion::V3_0 | SpecVersion::V3_1 => Ok(self),
+        }
+    }
+}
+
+/// Format and version of a document, found without parsing it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpecInfo {
+    pub format: SpecFormat,
+    pub version: SpecVersion,
+}
+
+impl SpecInfo {
+    /// Detect the format and version of `input`. The version is read from
+    /// the top-level `openapi`/`swagger` field with a line scan; only
+    /// documents where that fails (flow-style YAML, JSON with the field
+    /// sharing a line with others) are parsed.
+    pub fn detect(input: &str) -> Result<Self> {
+        let format = SpecFormat::detect(input);
+        let declared = match scan_version(input, format) {
+            Some(declared) => declared,
+            None => {
+                let document = crate::diagnostic::parse_document(input)?;
+                declared_version(&document)?
+            }
+        };
+        Ok(Self {
+            format,
+            version: declared.into_version()?,
+        })
+    }
+}
+
+/// The version field of a document and its value.
+struct Declared {
+    swagger: bool,
+    version: String,
+}
+
+impl Declared {
+    fn into_version(self) -> Result<SpecVersion> {
+        let mut parts = self.version.trim().split('.');
+        match (self.swagger, parts.next(), parts.next()) {
+            (true, Some("2"), Some("0")) => Ok(SpecVersion::V2),
+            (true, ..) => Err(ParserError::UnsupportedVersion(format!(
+                "swagger {}",
+                self.version
+            ))),
+            (false, ..) => SpecVersion::from_version_str(&self.version).ok_or_else(|| {
+                ParserError::UnsupportedVersion(format!(
+                    "{} (supported: 3.0.x and 3.1.x)",
+                    self.version
+                ))
+            }),
+        }
+    }
+}
+
+fn declared_version(document: &Value) -> Result<Declared> {
+    for (key, swagger) in [("openapi", false), ("swagger", true)] {
+        if let Some(version) = document.get(key) {
+            return Ok(Declared {
+                swagger,
+                version: display_version(version),
+            });
+        }
+    }
+    Err(ParserError::UnsupportedVersion(
+        "missing `openapi` field; is this an OpenAPI document?".into(),
+    ))
+}
+
+/// Find the version field of the top-level mapping on a line of its own:
+/// unindented in YAML, directly inside the outer object in JSON.
+fn scan_version(input: &str, format: SpecFormat) -> Option<Declared> {
+    // JSON nesting depth at the start of the current line
+    let mut depth = 0_usize;
+    input.lines().find_map(|line| {
+        let line = match format {
+            SpecFormat::Json => {
+                let top_level = depth == 1;
+                depth = depth_after(line, depth);
+                if !top_level {
+                    return None;
+                }
+                line.trim_start()
+            }
+            SpecFormat::Yaml => line,
+        };
+        ["openapi", "swagger"].into_iter().find_map(|key| {
+            let rest = [
+                format!("\"{}\"", key),
+                format!("'{}'", key),
+                key.to_string(),
+            ]
+            .into_iter()
+            .find_map(|quoted| line.strip_prefix(quoted.as_str()).map(str::to_string))?;
+            let value = rest.trim_start().strip_prefix(':')?;
+            let value = value.split(" #").next()?.trim().trim_end_matches(',');
+            let version = value.trim_matches(|c| c == '"' || c == '\'');
+            (!version.is_empty()).then(|| Declared {
+                swagger: key == "swagger",
+                version: version.to_string(),
+            })
+        })
+    })
+}
+
+/// JSON nesting depth after `line`, starting at `depth`. Strings can't
+/// span lines in JSON, so each line is scanned on its own.
+fn depth_after(line: &str, mut depth: usize) -> usize {
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in line.chars() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '{' | '[' if !in_string => depth += 1,
+            '}' | ']' if !in_string => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    depth
+}
+
+fn display_version(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_detect_version() {
+        assert_eq!(
+            SpecVersion::detect(&json!({ "openapi": "3.0.3" })).unwrap(),
+            SpecVersion::V3_0
+        );
+        assert_eq!(
+            SpecVersion::detect(&json!({ "openapi": "3.1.0" })).unwrap(),
+            SpecVersion::V3_1
+        );
+        assert!(SpecVersion::detect(&json!({ "openapi": "4.0.0" })).is_err());
+        assert!(SpecVersion::detect(&json!({ "swagger": "2.0" })).is_err());
+        assert!(SpecVersion::detect(&json!({})).is_err());
+    }
+
+    #[test]
+    fn test_spec_info() {
+        let detect = |input: &str| SpecInfo::detect(input).map(|info| (info.format, info.version));
+        assert_eq!(
+            detect("# Pets\nopenapi: 3.1.0\ninfo: {}").unwrap(),
+            (SpecFormat::Yaml, SpecVersion::V3_1)
+        );
+        assert_eq!(
+            detect("swagger: '2.0' # legacy\n").unwrap(),
+            (SpecFormat::Yaml, SpecVersion::V2)
+        );
+        assert_eq!(
+            detect("{\n  \"openapi\": \"3.0.3\",\n  \"info\": {}\n}").unwrap(),
+            (SpecFormat::Json, SpecVersion::V3_0)
+        );
+        // Not on a line of its own: falls back to parsing
+        assert_eq!(
+            detect(r#"{"info":{},"openapi":"3.1.1"}"#).unwrap(),
+            (SpecFormat::Json, SpecVersion::V3_1)
+        );
+        // Nested keys don't count, in YAML or JSON
+        assert!(detect("info:\n  openapi: 3.1.0\n").is_err());
+        assert_eq!(
+            detect("{\n  \"info\": {\n    \"openapi\": \"4.0.0\",\n    \"x\": \"{\"\n  },\n  \"openapi\": \"3.1.0\"\n}")
+                .unwrap(),
+            (SpecFormat::Json, SpecVersion::V3_1)
+        );
+        assert!(detect("openapi: 4.0.0").is_err());
+        assert!(SpecVersion::V2.supported().is_err());
+    }
+}
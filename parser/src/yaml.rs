@@ -0,0 +1,90 @@
+//! The YAML settings shared by every crate that reads specs.
+//!
+//! Anchors, aliases and `<<` merge keys are expanded while deserializing, so
+//! going through [`from_str`] (or [`options`]) is what makes the parser, the
+//! linter and the CLI see the same structure. Alias expansion is capped to
+//! reject "billion laughs" documents that stay small on disk but expand to
+//! millions of nodes.
+
+use serde::de::DeserializeOwned;
+
+use crate::error::Result;
+
+/// Most events replayed from aliases in one document. Real specs use a few
+/// hundred at most; a billion-laughs document needs millions.
+pub const MAX_ALIAS_EXPANSION: usize = 250_000;
+
+/// Deepest nesting of aliases within aliases.
+pub const MAX_ALIAS_DEPTH: usize = 32;
+
+/// Options for reading a spec.
+pub fn options() -> serde_saphyr::Options {
+    serde_saphyr::Options {
+        // Use strict_booleans to avoid YAML 1.1 quirks where y/n/yes/no/on/off
+        // are interpreted as booleans instead of strings
+        strict_booleans: true,
+        alias_limits: serde_saphyr::options::AliasLimits {
+            max_total_replayed_events: MAX_ALIAS_EXPANSION,
+            max_replay_stack_depth: MAX_ALIAS_DEPTH,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+/// Deserialize a YAML (or JSON) document with [`options`].
+pub fn from_str<T: DeserializeOwned>(input: &str) -> Result<T> {
+    Ok(serde_saphyr::from_str_with_options(input, options())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{Value, json};
+
+    #[test]
+    fn test_anchors_and_merge_keys() {
+        let input = r#"
+components:
+  responses:
+    Error: &error
+      description: Error
+      headers: &headers
+        X-Request-Id:
+          schema: { type: string }
+    NotFound:
+      <<: *error
+      description: Not found
+    Gone:
+      description: Gone
+      headers: *headers
+"#;
+        let value: Value = from_str(input).unwrap();
+        let responses = &value["components"]["responses"];
+
+        assert_eq!(responses["NotFound"]["description"], json!("Not found"));
+        assert_eq!(
+            responses["NotFound"]["headers"],
+            responses["Error"]["headers"]
+        );
+        assert_eq!(responses["Gone"]["headers"], responses["Error"]["headers"]);
+        assert!(responses["NotFound"].get("<<").is_none());
+    }
+
+    #[test]
+    fn test_billion_laughs_is_rejected() {
+        let mut input = String::from("a: &a [lol, lol, lol, lol, lol, lol, lol, lol, lol]\n");
+        let names = ["a", "b", "c", "d", "e", "f", "g"];
+        for pair in names.windows(2) {
+            let alias = format!("*{}", pair[0]);
+            input.push_str(&format!(
+                "{}: &{} [{}]\n",
+                pair[1],
+                pair[1],
+                vec![alias.as_str(); 9].join(", ")
+            ));
+        }
+
+        assert!(from_str::<Value>(&input).is_err());
+    }
+}
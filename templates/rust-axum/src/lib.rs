@@ -11,22 +11,36 @@
 mod service_codegen;
 
 use askama::Template;
-use codegen::{Config, Error, GenIr, Generator, Result, VirtualFS};
-use ir::gen_ir::{CanonicalName, Service, TypeDecl};
+use codegen::{
+    Config, Error, GenIr, Generator, NamingPolicy, Result, VirtualFS, codeowners,
+    diagnostic::ResultExt,
+    emit::{CodeBuilder, PrimitiveTypes, identifier, is_identifier},
+    overrides::{TemplateOverrides, render},
+    verify::{CommandVerifier, Verifier},
+};
+use ir::gen_ir::{CanonicalName, Service, TimeFormat, TypeDecl};
+use serde::Serialize;
 use service_codegen::ServiceModuleGenerator;
 use std::collections::{BTreeMap, BTreeSet};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Rust Axum server generator.
 pub struct RustAxumGenerator;
 
+fn is_date_field(field: &ir::gen_ir::Field) -> bool {
+    matches!(
+        field.ty.target,
+        ir::gen_ir::StableId::Primitive(ir::gen_ir::Primitive::Date)
+    )
+}
+
 impl RustAxumGenerator {
     pub fn new() -> Self {
         Self
     }
 
     /// Generate types in a single file with feature flags
-    fn generate_types(&self, ir: &GenIr, _config: &Config, vfs: &mut VirtualFS) -> Result<()> {
+    fn generate_types(&self, ir: &GenIr, config: &Config, vfs: &mut VirtualFS) -> Result<()> {
         let mut content = String::from("//! API types\n\nuse serde::{Deserialize, Serialize};\n\n");
 
         // Collect type IDs used in multipart request bodies (these will be generated in service modules)
@@ -58,6 +72,9 @@ impl RustAxumGenerator {
             type_features.insert(type_decl.name.pascal.clone(), features);
         }
 
+        // Each strftime pattern used by a date/time field gets a serde module
+        let time_patterns = Self::collect_time_patterns(ir, config);
+
         // Generate each type with its feature flags
         for type_decl in ir.types.values() {
             // Skip types that are used as multipart request bodies
@@ -81,85 +98,166 @@ impl RustAxumGenerator {
                 }
             }
 
-            let rendered = self.render_type(type_decl, ir)?;
+            let rendered = self
+                .render_type(type_decl, ir, config, &time_patterns)
+                .in_type(type_decl)?;
             content.push_str(&rendered);
             content.push_str("\n\n");
         }
 
+        for ((is_date, pattern), module) in &time_patterns {
+            content.push_str(&Self::render_time_pattern_module(module, pattern, *is_date));
+            content.push_str("\n\n");
+        }
+
+        let coerces_numbers = ir.types.values().any(|type_decl| match &type_decl.kind {
+            ir::gen_ir::TypeKind::Struct { fields, .. } => {
+                fields.iter().any(|f| config.coerces_number(f))
+            }
+            _ => false,
+        });
+        if coerces_numbers {
+            content.push_str(COERCE_NUMBER_MODULE);
+            content.push_str("\n\n");
+        }
+
+        // Each registered string format used by a field gets a regex static
+        for (format, rule) in Self::collect_formats(ir, config) {
+            content.push_str(&format!(
+                "#[allow(dead_code)]\nstatic {}: std::sync::LazyLock<regex::Regex> =\n    std::sync::LazyLock::new(|| regex::Regex::new({:?}).unwrap());\n\n",
+                Self::format_static(&format),
+                rule.pattern
+            ));
+        }
+
         vfs.add_file("src/types.rs", content);
         Ok(())
     }
 
-    /// Escape Rust keywords with r# prefix
-    fn escape_rust_keyword(name: &str) -> String {
-        match name {
-            "as" | "break" | "const" | "continue" | "crate" | "else" | "enum" | "extern"
-            | "false" | "fn" | "for" | "if" | "impl" | "in" | "let" | "loop" | "match" | "mod"
-            | "move" | "mut" | "pub" | "ref" | "return" | "self" | "Self" | "static" | "struct"
-            | "super" | "trait" | "true" | "type" | "unsafe" | "use" | "where" | "while"
-            | "async" | "await" | "dyn" | "abstract" | "become" | "box" | "do" | "final"
-            | "macro" | "override" | "priv" | "typeof" | "unsized" | "virtual" | "yield"
-            | "try" => format!("r#{}", name),
-            _ => name.to_string(),
+    /// Distinct (is date, pattern) pairs of `TimeFormat::Pattern` fields, mapped
+    /// to the name of the serde module generated for them.
+    fn collect_time_patterns(ir: &GenIr, config: &Config) -> BTreeMap<(bool, String), String> {
+        let mut patterns = BTreeSet::new();
+        for type_decl in ir.types.values() {
+            if let ir::gen_ir::TypeKind::Struct { fields, .. } = &type_decl.kind {
+                for field in fields {
+                    if let Some(TimeFormat::Pattern(pattern)) = config.time_formats.for_field(field)
+                    {
+                        patterns.insert((is_date_field(field), pattern.clone()));
+                    }
+                }
+            }
         }
+        patterns
+            .into_iter()
+            .enumerate()
+            .map(|(index, key)| (key, format!("time_format_{}", index)))
+            .collect()
     }
 
-    /// Check if a name is a valid Rust identifier
-    fn is_valid_rust_ident(name: &str) -> bool {
-        if name.is_empty() {
-            return false;
-        }
-        let first = name.chars().next().unwrap();
-        if !first.is_alphabetic() && first != '_' {
-            return false;
-        }
-        name.chars().all(|c| c.is_alphanumeric() || c == '_')
-    }
-
-    /// Make a valid Rust field name from an arbitrary string.
-    /// Returns (field_name, needs_rename) where needs_rename is true if the original
-    /// name differs from the generated field name.
-    fn make_valid_field_name(original: &str, snake: &str) -> (String, bool) {
-        // If the snake case version is valid, use it
-        if Self::is_valid_rust_ident(snake) {
-            let escaped = Self::escape_rust_keyword(snake);
-            // Check if we need rename (either the original differs from snake, or we escaped a keyword)
-            let needs_rename = original != snake || escaped != snake;
-            return (escaped, needs_rename);
-        }
+    /// `#[serde(with = ...)]` for a date/time field that isn't in jiff's
+    /// default RFC 3339 format.
+    fn time_format_attribute(
+        field: &ir::gen_ir::Field,
+        config: &Config,
+        time_patterns: &BTreeMap<(bool, String), String>,
+    ) -> Option<String> {
+        let is_date = is_date_field(field);
+        let module = match config.time_formats.for_field(field)? {
+            TimeFormat::Rfc3339 => return None,
+            // Epoch formats only apply to date-times
+            TimeFormat::UnixSeconds | TimeFormat::UnixMillis if is_date => return None,
+            TimeFormat::UnixSeconds => "jiff::fmt::serde::timestamp::second".to_string(),
+            TimeFormat::UnixMillis => "jiff::fmt::serde::timestamp::millisecond".to_string(),
+            TimeFormat::Pattern(pattern) => time_patterns.get(&(is_date, pattern.clone()))?.clone(),
+        };
+        Some(if field.ty.optional {
+            format!("    #[serde(default, with = \"{}::optional\")]\n", module)
+        } else {
+            format!("    #[serde(with = \"{}::required\")]\n", module)
+        })
+    }
 
-        // If it starts with a digit, prefix with underscore
-        if snake.chars().next().is_some_and(|c| c.is_ascii_digit()) {
-            let new_name = format!("_{}", snake);
-            return (new_name, true);
+    /// `deserialize_with` for a number that may arrive as a string.
+    fn coerce_number_attribute(field: &ir::gen_ir::Field, config: &Config) -> Option<String> {
+        if !config.coerces_number(field) {
+            return None;
         }
+        Some(if field.ty.optional {
+            "    #[serde(default, deserialize_with = \"coerce_number::optional\")]\n".to_string()
+        } else {
+            "    #[serde(deserialize_with = \"coerce_number::required\")]\n".to_string()
+        })
+    }
 
-        // Replace invalid characters with underscores
-        let valid: String = snake
-            .chars()
-            .map(|c| {
-                if c.is_alphanumeric() || c == '_' {
-                    c
-                } else {
-                    '_'
-                }
-            })
-            .collect();
-
-        // If it starts with underscore followed by digit or is all underscores, prefix with 'f'
-        if valid.is_empty()
-            || valid.chars().all(|c| c == '_')
-            || (valid.starts_with('_') && valid.chars().nth(1).is_some_and(|c| c.is_ascii_digit()))
-        {
-            let new_name = format!("f{}", valid);
-            return (new_name, true);
+    /// serde module (de)serializing dates or timestamps with a strftime
+    /// pattern, laid out like jiff's own `required`/`optional` modules.
+    fn render_time_pattern_module(module: &str, pattern: &str, is_date: bool) -> String {
+        let (ty, parse) = if is_date {
+            (
+                "jiff::civil::Date",
+                "jiff::civil::Date::strptime(PATTERN, value)",
+            )
+        } else {
+            // `Timestamp::strptime` requires an offset in the input
+            (
+                "jiff::Timestamp",
+                r#"let parsed = jiff::fmt::strtime::parse(PATTERN, value)?;
+        if parsed.offset().is_some() {
+            return parsed.to_timestamp();
         }
-
-        (valid.clone(), original != valid)
+        // No `%z` in the pattern: the value is UTC
+        Ok(parsed.to_datetime()?.to_zoned(jiff::tz::TimeZone::UTC)?.timestamp())"#,
+            )
+        };
+        format!(
+            r#"/// `{ty}` (de)serialized with the pattern `{pattern}`.
+#[allow(dead_code)]
+mod {module} {{
+    const PATTERN: &str = {pattern:?};
+
+    fn parse(value: &str) -> Result<{ty}, jiff::Error> {{
+        {parse}
+    }}
+
+    pub mod required {{
+        pub fn serialize<S: serde::Serializer>(value: &{ty}, serializer: S) -> Result<S::Ok, S::Error> {{
+            serializer.collect_str(&value.strftime(super::PATTERN))
+        }}
+
+        pub fn deserialize<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<{ty}, D::Error> {{
+            let value = <String as serde::Deserialize>::deserialize(deserializer)?;
+            super::parse(&value).map_err(serde::de::Error::custom)
+        }}
+    }}
+
+    pub mod optional {{
+        pub fn serialize<S: serde::Serializer>(value: &Option<{ty}>, serializer: S) -> Result<S::Ok, S::Error> {{
+            match value {{
+                Some(value) => super::required::serialize(value, serializer),
+                None => serializer.serialize_none(),
+            }}
+        }}
+
+        pub fn deserialize<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Option<{ty}>, D::Error> {{
+            let value = <Option<String> as serde::Deserialize>::deserialize(deserializer)?;
+            value
+                .map(|value| super::parse(&value).map_err(serde::de::Error::custom))
+                .transpose()
+        }}
+    }}
+}}"#
+        )
     }
 
     /// Render a type declaration
-    fn render_type(&self, type_decl: &TypeDecl, ir: &GenIr) -> Result<String> {
+    fn render_type(
+        &self,
+        type_decl: &TypeDecl,
+        ir: &GenIr,
+        config: &Config,
+        time_patterns: &BTreeMap<(bool, String), String>,
+    ) -> Result<String> {
         use ir::gen_ir::TypeKind;
 
         match &type_decl.kind {
@@ -167,31 +265,57 @@ impl RustAxumGenerator {
                 let fields_str: Vec<String> = fields
                     .iter()
                     .map(|f| {
-                        let (field_name, needs_rename) =
-                            Self::make_valid_field_name(&f.name.canonical, &f.name.snake);
+                        let field_name = identifier(&f.name.snake, "rust", &config.naming);
+                        let needs_rename = field_name != f.name.canonical;
                         let type_str = self.render_type_ref(&f.ty, ir);
+                        let serde_with = Self::time_format_attribute(f, config, time_patterns)
+                            .or_else(|| Self::coerce_number_attribute(f, config))
+                            .unwrap_or_default();
                         if needs_rename {
                             format!(
-                                "    #[serde(rename = \"{}\")]\n    pub {}: {},",
-                                f.name.canonical, field_name, type_str
+                                "{}    #[serde(rename = \"{}\")]\n    pub {}: {},",
+                                serde_with, f.name.canonical, field_name, type_str
                             )
                         } else {
-                            format!("    pub {}: {},", field_name, type_str)
+                            format!("{}    pub {}: {},", serde_with, field_name, type_str)
                         }
                     })
                     .collect();
 
-                Ok(format!(
-                    "#[derive(Debug, Clone, Serialize, Deserialize)]\npub struct {} {{\n{}\n}}",
+                // Sensitive fields get a hand-written Debug that redacts them
+                let has_sensitive = fields.iter().any(|f| f.sensitive);
+                let derives = if has_sensitive {
+                    "Clone, Serialize, Deserialize"
+                } else {
+                    "Debug, Clone, Serialize, Deserialize"
+                };
+                let mut rendered = format!(
+                    "#[derive({})]\npub struct {} {{\n{}\n}}",
+                    derives,
                     type_decl.name.pascal,
                     fields_str.join("\n")
-                ))
+                );
+                if has_sensitive {
+                    rendered.push_str("\n\n");
+                    rendered.push_str(&Self::render_redacted_debug(
+                        &type_decl.name.pascal,
+                        fields,
+                        config,
+                    ));
+                }
+                if let Some(validate) =
+                    Self::render_validate(&type_decl.name.pascal, fields, config)
+                {
+                    rendered.push_str("\n\n");
+                    rendered.push_str(&validate);
+                }
+                Ok(rendered)
             }
             TypeKind::Enum { values, .. } => {
                 let variants: Vec<String> = values
                     .iter()
                     .map(|v| {
-                        let variant_name = Self::escape_rust_keyword(&v.name.pascal);
+                        let variant_name = identifier(&v.name.pascal, "rust", &config.naming);
                         format!("    {},", variant_name)
                     })
                     .collect();
@@ -207,26 +331,61 @@ impl RustAxumGenerator {
                 Ok(format!("pub type {} = {};", type_decl.name.pascal, target))
             }
             TypeKind::Union { style, variants } => {
-                self.render_union(type_decl, style, variants, ir)
+                self.render_union(type_decl, style, variants, ir, &config.naming)
             }
+            TypeKind::Opaque { reason, .. } => Ok(format!(
+                "/// {}\npub type {} = serde_json::Value;",
+                reason.describe(),
+                type_decl.name.pascal
+            )),
         }
     }
 
+    /// `Debug` impl printing `[REDACTED]` for sensitive fields so secrets
+    /// don't end up in logs.
+    fn render_redacted_debug(name: &str, fields: &[ir::gen_ir::Field], config: &Config) -> String {
+        let mut code = CodeBuilder::rust();
+        code.braces(format!("impl std::fmt::Debug for {name}"), |code| {
+            code.braces(
+                "fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result",
+                |code| {
+                    code.line(format!("f.debug_struct(\"{name}\")"));
+                    code.indented(|code| {
+                        for f in fields {
+                            let field_name = identifier(&f.name.snake, "rust", &config.naming);
+                            if f.sensitive {
+                                code.line(format!(".field(\"{}\", &\"[REDACTED]\")", field_name));
+                            } else {
+                                code.line(format!(
+                                    ".field(\"{}\", &self.{})",
+                                    field_name, field_name
+                                ));
+                            }
+                        }
+                        code.line(".finish()");
+                    });
+                },
+            );
+        });
+        code.into_fragment()
+    }
+
     fn render_union(
         &self,
         type_decl: &TypeDecl,
         style: &ir::gen_ir::UnionStyle,
         variants: &[ir::gen_ir::Variant],
         ir: &GenIr,
+        naming: &NamingPolicy,
     ) -> Result<String> {
         use ir::gen_ir::UnionStyle;
 
         match style {
-            UnionStyle::AllOf => self.render_allof(type_decl, variants, ir),
-            UnionStyle::OneOf => self.render_oneof_anyof(type_decl, variants, ir, false),
-            UnionStyle::AnyOf => self.render_oneof_anyof(type_decl, variants, ir, false),
+            UnionStyle::AllOf => self.render_allof(type_decl, variants, ir, naming),
+            UnionStyle::OneOf => self.render_oneof_anyof(type_decl, variants, ir, naming),
+            UnionStyle::AnyOf => self.render_oneof_anyof(type_decl, variants, ir, naming),
             UnionStyle::Discriminated { tag } => {
-                self.render_discriminated(type_decl, variants, tag, ir)
+                self.render_discriminated(type_decl, variants, tag, ir, naming)
             }
         }
     }
@@ -236,12 +395,13 @@ impl RustAxumGenerator {
         type_decl: &TypeDecl,
         variants: &[ir::gen_ir::Variant],
         ir: &GenIr,
+        naming: &NamingPolicy,
     ) -> Result<String> {
         let mut fields = Vec::new();
 
         for variant in variants {
             let variant_type = self.render_type_ref(&variant.ty, ir);
-            let field_name = Self::escape_rust_keyword(&variant.name.snake);
+            let field_name = identifier(&variant.name.snake, "rust", naming);
             fields.push(format!(
                 "    #[serde(flatten)]\n    pub {}: {},",
                 field_name, variant_type
@@ -260,12 +420,12 @@ impl RustAxumGenerator {
         type_decl: &TypeDecl,
         variants: &[ir::gen_ir::Variant],
         ir: &GenIr,
-        _is_strict: bool,
+        naming: &NamingPolicy,
     ) -> Result<String> {
         let mut variant_lines = Vec::new();
 
         for variant in variants {
-            let variant_name = Self::escape_rust_keyword(&variant.name.pascal);
+            let variant_name = identifier(&variant.name.pascal, "rust", naming);
             let variant_type = self.render_type_ref(&variant.ty, ir);
             variant_lines.push(format!("    {}({}),", variant_name, variant_type));
         }
@@ -283,11 +443,12 @@ impl RustAxumGenerator {
         variants: &[ir::gen_ir::Variant],
         tag: &str,
         ir: &GenIr,
+        naming: &NamingPolicy,
     ) -> Result<String> {
         let mut variant_lines = Vec::new();
 
         for variant in variants {
-            let variant_name = Self::escape_rust_keyword(&variant.name.pascal);
+            let variant_name = identifier(&variant.name.pascal, "rust", naming);
             let variant_type = self.render_type_ref(&variant.ty, ir);
 
             if let Some(tag_value) = &variant.tag_value {
@@ -340,20 +501,7 @@ impl RustAxumGenerator {
 
     fn render_type_id(&self, id: &ir::gen_ir::StableId) -> String {
         match id {
-            ir::gen_ir::StableId::Primitive(p) => match p {
-                ir::gen_ir::Primitive::String => "String".to_string(),
-                ir::gen_ir::Primitive::Bool => "bool".to_string(),
-                ir::gen_ir::Primitive::I32 => "i32".to_string(),
-                ir::gen_ir::Primitive::I64 => "i64".to_string(),
-                ir::gen_ir::Primitive::F32 => "f32".to_string(),
-                ir::gen_ir::Primitive::F64 => "f64".to_string(),
-                ir::gen_ir::Primitive::Date => "jiff::civil::Date".to_string(),
-                ir::gen_ir::Primitive::DateTime => "jiff::Timestamp".to_string(),
-                ir::gen_ir::Primitive::Uuid => "uuid::Uuid".to_string(),
-                ir::gen_ir::Primitive::Bytes => "bytes::Bytes".to_string(),
-                ir::gen_ir::Primitive::Decimal => "rust_decimal::Decimal".to_string(),
-                ir::gen_ir::Primitive::Any => "serde_json::Value".to_string(),
-            },
+            ir::gen_ir::StableId::Primitive(p) => PrimitiveTypes::RUST.get(*p).to_string(),
             ir::gen_ir::StableId::Named(name) => {
                 // Use CanonicalName to ensure consistent pascal case (handles acronyms like FAQItem -> FaqItem)
                 CanonicalName::from_string(name).pascal
@@ -362,20 +510,9 @@ impl RustAxumGenerator {
     }
 
     fn render_alias_target(&self, target: &ir::gen_ir::AliasTarget, ir: &GenIr) -> String {
-        use ir::gen_ir::{AliasTarget, Composite, Primitive};
+        use ir::gen_ir::{AliasTarget, Composite};
         match target {
-            AliasTarget::Primitive(Primitive::String) => "String".to_string(),
-            AliasTarget::Primitive(Primitive::Bool) => "bool".to_string(),
-            AliasTarget::Primitive(Primitive::I32) => "i32".to_string(),
-            AliasTarget::Primitive(Primitive::I64) => "i64".to_string(),
-            AliasTarget::Primitive(Primitive::F32) => "f32".to_string(),
-            AliasTarget::Primitive(Primitive::F64) => "f64".to_string(),
-            AliasTarget::Primitive(Primitive::Date) => "jiff::civil::Date".to_string(),
-            AliasTarget::Primitive(Primitive::DateTime) => "jiff::Timestamp".to_string(),
-            AliasTarget::Primitive(Primitive::Uuid) => "uuid::Uuid".to_string(),
-            AliasTarget::Primitive(Primitive::Bytes) => "bytes::Bytes".to_string(),
-            AliasTarget::Primitive(Primitive::Decimal) => "rust_decimal::Decimal".to_string(),
-            AliasTarget::Primitive(Primitive::Any) => "serde_json::Value".to_string(),
+            AliasTarget::Primitive(p) => PrimitiveTypes::RUST.get(*p).to_string(),
             AliasTarget::Composite(Composite::List(inner)) => {
                 format!("Vec<{}>", self.render_type_ref(inner, ir))
             }
@@ -395,9 +532,15 @@ impl RustAxumGenerator {
     }
 
     /// Generate service modules (one per tag)
-    fn generate_services(&self, ir: &GenIr, _config: &Config, vfs: &mut VirtualFS) -> Result<()> {
+    fn generate_services(
+        &self,
+        ir: &GenIr,
+        config: &Config,
+        templates: &TemplateOverrides,
+        vfs: &mut VirtualFS,
+    ) -> Result<()> {
         for service in &ir.services {
-            self.generate_service_module(service, ir, vfs)?;
+            self.generate_service_module(service, ir, config, templates, vfs)?;
         }
 
         self.generate_services_mod(ir, vfs)?;
@@ -410,13 +553,23 @@ impl RustAxumGenerator {
         &self,
         service: &Service,
         ir: &GenIr,
+        config: &Config,
+        templates: &TemplateOverrides,
         vfs: &mut VirtualFS,
     ) -> Result<()> {
         let module_name = &service.name.snake;
 
-        let generator =
-            ServiceModuleGenerator::new(service, &ir.auth_schemes, &ir.api.package_name.snake);
-        let content = generator.generate();
+        let generator = ServiceModuleGenerator::new(
+            service,
+            &ir.auth_schemes,
+            &ir.api.package_name.snake,
+            &config.naming,
+        );
+        let mut content = generator.generate(templates)?;
+        let owners = service.all_owners();
+        if !owners.is_empty() {
+            content = format!("// Owners: {}\n\n{}", owners.join(" "), content);
+        }
 
         let file_path = PathBuf::from("src")
             .join("services")
@@ -426,29 +579,132 @@ impl RustAxumGenerator {
         Ok(())
     }
 
+    /// Generate a CODEOWNERS fragment for service modules with `x-owner` annotations.
+    fn generate_codeowners(&self, ir: &GenIr, vfs: &mut VirtualFS) -> Result<()> {
+        let entries: Vec<(PathBuf, Vec<String>)> = ir
+            .services
+            .iter()
+            .map(|service| {
+                let file = PathBuf::from("src")
+                    .join("services")
+                    .join(format!("{}.rs", service.name.snake));
+                (file, service.all_owners())
+            })
+            .collect();
+
+        if let Some(content) = codeowners(&entries) {
+            vfs.add_file("CODEOWNERS", content);
+        }
+
+        Ok(())
+    }
+
     /// Generate services/mod.rs
     fn generate_services_mod(&self, ir: &GenIr, vfs: &mut VirtualFS) -> Result<()> {
-        let mut mod_content = String::from("//! Service interfaces organized by tag\n\n");
+        let mut code = CodeBuilder::rust();
+        code.line("//! Service interfaces organized by tag").blank();
 
         for service in &ir.services {
-            let feature_name = service.name.snake.clone();
-
-            if service.name.canonical == "default" {
-                mod_content.push_str(&format!("pub mod {};\n", service.name.snake));
-            } else {
-                mod_content.push_str(&format!(
-                    "#[cfg(feature = \"{}\")]\npub mod {};\n",
-                    feature_name, service.name.snake
-                ));
+            if service.name.canonical != "default" {
+                code.line(format!("#[cfg(feature = \"{}\")]", service.name.snake));
             }
+            code.line(format!("pub mod {};", service.name.snake));
         }
 
-        vfs.add_file("src/services/mod.rs", mod_content);
+        vfs.add_file("src/services/mod.rs", code.finish());
         Ok(())
     }
 
+    /// String formats with a validation rule used by struct fields.
+    fn collect_formats<'a>(
+        ir: &GenIr,
+        config: &'a Config,
+    ) -> BTreeMap<String, &'a codegen::formats::FormatRule> {
+        let mut formats = BTreeMap::new();
+        for type_decl in ir.types.values() {
+            if let ir::gen_ir::TypeKind::Struct { fields, .. } = &type_decl.kind {
+                for field in fields {
+                    if let (Some(rule), Some(format)) =
+                        (config.formats.for_field(field), &field.format)
+                    {
+                        formats.insert(format.clone(), rule);
+                    }
+                }
+            }
+        }
+        formats
+    }
+
+    /// Name of the regex static checking `format`: `FORMAT_URI_REFERENCE`.
+    fn format_static(format: &str) -> String {
+        format!(
+            "FORMAT_{}",
+            CanonicalName::from_string(format).snake.to_uppercase()
+        )
+    }
+
+    /// `validate` method checking the fields with a registered string
+    /// format, if the struct has any.
+    fn render_validate(
+        name: &str,
+        fields: &[ir::gen_ir::Field],
+        config: &Config,
+    ) -> Option<String> {
+        let checks: Vec<(String, String)> = fields
+            .iter()
+            .filter_map(|f| {
+                let rule = config.formats.for_field(f)?;
+                let pattern = Self::format_static(f.format.as_deref()?);
+                let field_name = identifier(&f.name.snake, "rust", &config.naming);
+                let condition = if f.ty.optional {
+                    format!(
+                        "self.{}.as_deref().is_some_and(|value| !{}.is_match(value))",
+                        field_name, pattern
+                    )
+                } else {
+                    format!("!{}.is_match(&self.{})", pattern, field_name)
+                };
+                let message = format!("{}: expected {}", f.name.canonical, rule.description);
+                Some((condition, message))
+            })
+            .collect();
+        if checks.is_empty() {
+            return None;
+        }
+
+        let mut code = CodeBuilder::rust();
+        code.braces(format!("impl {name}"), |code| {
+            code.line("/// Check the fields that have a string format.");
+            code.braces(
+                "pub fn validate(&self) -> Result<(), Vec<String>>",
+                |code| {
+                    code.line("let mut errors = Vec::new();");
+                    for (condition, message) in &checks {
+                        code.braces(format!("if {}", condition), |code| {
+                            code.line(format!("errors.push({:?}.to_string());", message));
+                        });
+                    }
+                    code.block("if errors.is_empty() {", "} else {", |code| {
+                        code.line("Ok(())");
+                    });
+                    code.indented(|code| {
+                        code.line("Err(errors)");
+                    })
+                    .line("}");
+                },
+            );
+        });
+        Some(code.into_fragment())
+    }
+
     /// Generate Cargo.toml with feature flags
-    fn generate_cargo_toml(&self, ir: &GenIr, vfs: &mut VirtualFS) -> Result<()> {
+    fn generate_cargo_toml(
+        &self,
+        ir: &GenIr,
+        config: &Config,
+        templates: &TemplateOverrides,
+        vfs: &mut VirtualFS,
+    ) -> Result<()> {
         let mut tags: BTreeSet<String> = BTreeSet::new();
         for service in &ir.services {
             if service.name.canonical != "default" {
@@ -460,22 +716,22 @@ impl RustAxumGenerator {
             package_name: &ir.api.package_name.snake,
             version: &ir.api.version,
             tags: tags.iter().cloned().collect(),
+            uses_formats: !Self::collect_formats(ir, config).is_empty(),
         };
 
-        let content = data
-            .render()
-            .map_err(|e| Error::TemplateError(Box::new(e)))?;
+        let content = render(templates, "Cargo.toml.jinja", &data)?;
 
         vfs.add_file("Cargo.toml", content);
         Ok(())
     }
 
     /// Generate shared types / utilities module
-    fn generate_shared_module(&self, vfs: &mut VirtualFS) -> Result<()> {
-        let data = Shared;
-        let content = data
-            .render()
-            .map_err(|e| Error::TemplateError(Box::new(e)))?;
+    fn generate_shared_module(
+        &self,
+        templates: &TemplateOverrides,
+        vfs: &mut VirtualFS,
+    ) -> Result<()> {
+        let content = render(templates, "shared.rs.jinja", &Shared)?;
         vfs.add_file("src/shared.rs", content);
         Ok(())
     }
@@ -487,20 +743,47 @@ impl RustAxumGenerator {
         content.push_str("pub mod services;\n");
         content.push_str("pub mod shared;\n");
         content.push_str("pub mod multipart;\n");
+        content.push_str("pub mod meta;\n");
 
         vfs.add_file("src/lib.rs", content);
         Ok(())
     }
 
+    /// Generate meta.rs describing the contract the crate was built from
+    fn generate_meta_module(&self, ir: &GenIr, vfs: &mut VirtualFS) -> Result<()> {
+        let content = format!(
+            "//! The API contract this crate was generated from.\n\n\
+             /// `info.title` of the spec\n\
+             pub const TITLE: &str = {:?};\n\
+             /// `info.version` of the spec\n\
+             pub const VERSION: &str = {:?};\n\
+             /// Hash of the spec document the crate was generated from\n\
+             pub const CHECKSUM: &str = {:?};\n\
+             /// Tool that generated the crate\n\
+             pub const GENERATOR: &str = {:?};\n\
+             /// Number of operations in the crate\n\
+             pub const OPERATION_COUNT: usize = {};\n",
+            ir.api.title,
+            ir.api.version,
+            ir.api.spec_hash,
+            codegen::GENERATOR_VERSION,
+            ir.operation_count(),
+        );
+        vfs.add_file("src/meta.rs", content);
+        Ok(())
+    }
+
     /// Generate multipart utilities module
-    fn generate_multipart_module(&self, vfs: &mut VirtualFS) -> Result<()> {
-        #[derive(Template)]
+    fn generate_multipart_module(
+        &self,
+        templates: &TemplateOverrides,
+        vfs: &mut VirtualFS,
+    ) -> Result<()> {
+        #[derive(Template, Serialize)]
         #[template(path = "multipart.rs.jinja", escape = "none")]
         struct MultipartModule;
 
-        let content = MultipartModule
-            .render()
-            .map_err(|e| Error::TemplateError(Box::new(e)))?;
+        let content = render(templates, "multipart.rs.jinja", &MultipartModule)?;
         vfs.add_file("src/multipart.rs", content);
         Ok(())
     }
@@ -508,14 +791,27 @@ impl RustAxumGenerator {
 
 impl Generator for RustAxumGenerator {
     fn generate(&self, ir: &GenIr, config: &Config) -> Result<VirtualFS> {
+        // Module paths follow the crate's `mod` tree, which is fixed
+        let layout = config.layout_name()?;
+        if layout != "standard" {
+            return Err(Error::Custom(format!(
+                "the rust-axum generator only supports the standard layout, not `{}`",
+                layout
+            )));
+        }
+
+        let mut templates = TemplateOverrides::from_config(config)?;
+        service_codegen::add_filters(&mut templates, &config.naming);
         let mut vfs = VirtualFS::new();
 
         self.generate_types(ir, config, &mut vfs)?;
-        self.generate_services(ir, config, &mut vfs)?;
-        self.generate_cargo_toml(ir, &mut vfs)?;
-        self.generate_shared_module(&mut vfs)?;
-        self.generate_multipart_module(&mut vfs)?;
+        self.generate_services(ir, config, &templates, &mut vfs)?;
+        self.generate_cargo_toml(ir, config, &templates, &mut vfs)?;
+        self.generate_shared_module(&templates, &mut vfs)?;
+        self.generate_multipart_module(&templates, &mut vfs)?;
+        self.generate_meta_module(ir, &mut vfs)?;
         self.generate_lib_rs(&mut vfs)?;
+        self.generate_codeowners(ir, &mut vfs)?;
 
         Ok(vfs)
     }
@@ -524,6 +820,14 @@ impl Generator for RustAxumGenerator {
         "rust-axum"
     }
 
+    fn version(&self) -> &str {
+        env!("CARGO_PKG_VERSION")
+    }
+
+    fn description(&self) -> &str {
+        "Rust server interfaces for axum, with a feature flag per tag"
+    }
+
     fn validate(&self, ir: &GenIr) -> Result<()> {
         if ir.types.is_empty() && ir.services.is_empty() {
             return Err(Error::ValidationError(
@@ -532,6 +836,128 @@ impl Generator for RustAxumGenerator {
         }
         Ok(())
     }
+
+    fn options_schema(&self) -> Option<serde_json::Value> {
+        Some(serde_json::json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "title": "Rust (axum) generator options",
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "layout": {
+                    "type": "string",
+                    "enum": ["standard"],
+                    "default": "standard",
+                    "description": "Module paths follow the crate's `mod` tree, so only the standard layout is supported"
+                },
+                "coerce_numbers": {
+                    "type": "boolean",
+                    "default": false,
+                    "description": "Accept every numeric field as a string too"
+                }
+            }
+        }))
+    }
+
+    fn verifier(&self) -> Option<Box<dyn Verifier>> {
+        Some(Box::new(
+            CommandVerifier::new("cargo")
+                .with_name("cargo check")
+                .with_args(["check", "--quiet", "--message-format", "short"]),
+        ))
+    }
+
+    /// `src/lib.rs` with every module file inlined as `mod name { ... }`,
+    /// after `Cargo.toml` as a comment: the crate using the file needs its
+    /// dependencies, and its features to turn on modules behind them.
+    /// `crate::` paths become relative, so the file also works as a module.
+    fn merge_files(&self, vfs: &VirtualFS) -> Result<Option<String>> {
+        let mut merged = String::new();
+        if let Some(manifest) = vfs.get_file_str(Path::new("Cargo.toml")).transpose()? {
+            merged.push_str(
+                "// Cargo.toml of the generated crate, with the dependencies and features\n",
+            );
+            merged.push_str("// the crate using this file needs:\n//\n");
+            for line in manifest.lines() {
+                merged.push_str(format!("// {}", line).trim_end());
+                merged.push('\n');
+            }
+            merged.push('\n');
+        }
+        merged.push_str(&inline_modules(
+            vfs,
+            Path::new("src/lib.rs"),
+            Path::new("src"),
+            0,
+        )?);
+        Ok(Some(merged))
+    }
+}
+
+/// `file` with each `mod name;` replaced by the content of `dir/name.rs`
+/// (or `dir/name/mod.rs`), recursively. `file` is `depth` modules below
+/// the crate root.
+fn inline_modules(vfs: &VirtualFS, file: &Path, dir: &Path, depth: usize) -> Result<String> {
+    let content = vfs
+        .get_file_str(file)
+        .transpose()?
+        .ok_or_else(|| Error::Custom(format!("{} is missing", file.display())))?;
+    let mut merged = String::new();
+    for line in content.lines() {
+        let Some((declaration, name)) = module_declaration(line) else {
+            merged.push_str(&relative_paths(line, depth));
+            merged.push('\n');
+            continue;
+        };
+        let child = [
+            dir.join(format!("{}.rs", name)),
+            dir.join(name).join("mod.rs"),
+        ]
+        .into_iter()
+        .find(|path| vfs.get_file(path).is_some())
+        .ok_or_else(|| {
+            Error::Custom(format!("{}: no file for module `{}`", file.display(), name))
+        })?;
+        merged.push_str(&format!("{} {{\n", declaration));
+        merged.push_str(&inline_modules(vfs, &child, &dir.join(name), depth + 1)?);
+        merged.push_str("}\n");
+    }
+    Ok(merged)
+}
+
+/// `line` with its `crate::` paths relative to a module `depth` levels
+/// below the crate root (`self::` at the root, `super::` for each level).
+fn relative_paths(line: &str, depth: usize) -> String {
+    let root = match depth {
+        0 => "self::".to_string(),
+        depth => "super::".repeat(depth),
+    };
+    let mut relative = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(at) = rest.find("crate::") {
+        let (before, after) = rest.split_at(at);
+        relative.push_str(before);
+        // `$crate::` and names ending in `crate` stay
+        if before.ends_with(|c: char| c == '$' || c == '_' || c.is_alphanumeric()) {
+            relative.push_str("crate::");
+        } else {
+            relative.push_str(&root);
+        }
+        rest = &after["crate::".len()..];
+    }
+    relative.push_str(rest);
+    relative
+}
+
+/// Split `pub mod name;` into `pub mod name` and `name`.
+fn module_declaration(line: &str) -> Option<(&str, &str)> {
+    let declaration = line.trim_end().strip_suffix(';')?;
+    let (visibility, name) = declaration.split_once("mod ")?;
+    let visibility = visibility.trim();
+    let is_visibility =
+        visibility.is_empty() || visibility == "pub" || visibility.starts_with("pub(");
+    (is_visibility && is_identifier(name, "rust", &NamingPolicy::default()))
+        .then_some((declaration, name))
 }
 
 impl Default for RustAxumGenerator {
@@ -540,16 +966,59 @@ impl Default for RustAxumGenerator {
     }
 }
 
+/// serde `deserialize_with` helpers for numbers that may also arrive as
+/// strings (`x-coerce-number`). `str::parse` ignores the locale.
+const COERCE_NUMBER_MODULE: &str = r#"/// Numbers that may also arrive as strings (`"42.5"`).
+#[allow(dead_code)]
+mod coerce_number {
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum NumberOrString<T> {
+        Number(T),
+        String(String),
+    }
+
+    pub fn required<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+        T: Deserialize<'de> + std::str::FromStr,
+        T::Err: std::fmt::Display,
+    {
+        match NumberOrString::<T>::deserialize(deserializer)? {
+            NumberOrString::Number(value) => Ok(value),
+            NumberOrString::String(value) => value.trim().parse().map_err(serde::de::Error::custom),
+        }
+    }
+
+    pub fn optional<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+        T: Deserialize<'de> + std::str::FromStr,
+        T::Err: std::fmt::Display,
+    {
+        match Option::<NumberOrString<T>>::deserialize(deserializer)? {
+            None => Ok(None),
+            Some(NumberOrString::Number(value)) => Ok(Some(value)),
+            Some(NumberOrString::String(value)) => {
+                value.trim().parse().map(Some).map_err(serde::de::Error::custom)
+            }
+        }
+    }
+}"#;
+
 // Template data structures
 
-#[derive(Template)]
+#[derive(Template, Serialize)]
 #[template(path = "Cargo.toml.jinja", escape = "none")]
 struct CargoTomlData<'a> {
     package_name: &'a str,
     version: &'a str,
     tags: Vec<String>,
+    uses_formats: bool,
 }
 
-#[derive(Template)]
+#[derive(Template, Serialize)]
 #[template(path = "shared.rs.jinja", escape = "none")]
 struct Shared;
@@ -1,26 +1,28 @@
 //! Service module code generation
 
-use askama::Template;
-use ir::gen_ir::{AuthKind, AuthScheme, HttpMethod, Operation, Service};
-
-/// Escape Rust keywords with r# prefix
-fn escape_keyword(name: &str) -> String {
-    match name {
-        "as" | "break" | "const" | "continue" | "crate" | "else" | "enum" | "extern" | "false"
-        | "fn" | "for" | "if" | "impl" | "in" | "let" | "loop" | "match" | "mod" | "move"
-        | "mut" | "pub" | "ref" | "return" | "self" | "Self" | "static" | "struct" | "super"
-        | "trait" | "true" | "type" | "unsafe" | "use" | "where" | "while" | "async" | "await"
-        | "dyn" | "abstract" | "become" | "box" | "do" | "final" | "macro" | "override"
-        | "priv" | "typeof" | "unsized" | "virtual" | "yield" | "try" => format!("r#{}", name),
-        _ => name.to_string(),
-    }
-}
+use codegen::diagnostic::ResultExt;
+use codegen::emit::{PrimitiveTypes, identifier};
+use codegen::overrides::{TemplateOverrides, render};
+use codegen::{Error, Result};
+use ir::gen_ir::{
+    AuthKind, AuthScheme, CanonicalName, HttpMethod, Operation, Primitive, Service, StableId,
+    StatusSpec, TypeRef,
+};
+use ir::naming::NamingPolicy;
+use minijinja::Value;
+use serde::Serialize;
 
 mod filters {
     use ir::gen_ir::{CanonicalName, StableId};
+    use ir::naming::NamingPolicy;
 
-    pub fn escape_rust_keyword(name: &str, _: &dyn askama::Values) -> askama::Result<String> {
-        Ok(super::escape_keyword(name))
+    /// `name` as a valid Rust identifier, escaping the words `naming` reserves
+    pub fn escape_rust_keyword(
+        name: &str,
+        _: &dyn askama::Values,
+        naming: &NamingPolicy,
+    ) -> askama::Result<String> {
+        Ok(codegen::emit::identifier(name, "rust", naming))
     }
 
     /// Convert a string to PascalCase using CanonicalName
@@ -29,114 +31,175 @@ mod filters {
     }
 
     /// Render a type reference to its Rust type
-    /// Uses CanonicalName to ensure consistent pascal case naming
     pub fn render_type(type_id: &StableId, _: &dyn askama::Values) -> askama::Result<String> {
-        match type_id {
-            StableId::Primitive(p) => {
-                use ir::gen_ir::Primitive;
-                let rust_type = match p {
-                    Primitive::String => "String",
-                    Primitive::Bool => "bool",
-                    Primitive::I32 => "i32",
-                    Primitive::I64 => "i64",
-                    Primitive::F32 => "f32",
-                    Primitive::F64 => "f64",
-                    Primitive::Date => "jiff::civil::Date",
-                    Primitive::DateTime => "jiff::Timestamp",
-                    Primitive::Uuid => "uuid::Uuid",
-                    Primitive::Bytes => "bytes::Bytes",
-                    Primitive::Decimal => "rust_decimal::Decimal",
-                    Primitive::Any => "serde_json::Value",
-                };
-                Ok(rust_type.to_string())
-            }
-            StableId::Named(name) => {
-                // Use CanonicalName to get proper pascal case (handles acronyms like FAQItem -> FaqItem)
-                let pascal = CanonicalName::from_string(name).pascal;
-                Ok(format!("crate::types::{}", pascal))
-            }
-        }
+        Ok(super::render_type(type_id))
     }
 
     /// Convert HTTP status code to StatusCode expression (constant or unsafe constructor)
     pub fn status_code_const(code: &u16, _: &dyn askama::Values) -> askama::Result<String> {
-        let result = match *code {
-            100 => "StatusCode::CONTINUE",
-            101 => "StatusCode::SWITCHING_PROTOCOLS",
-            102 => "StatusCode::PROCESSING",
-            200 => "StatusCode::OK",
-            201 => "StatusCode::CREATED",
-            202 => "StatusCode::ACCEPTED",
-            203 => "StatusCode::NON_AUTHORITATIVE_INFORMATION",
-            204 => "StatusCode::NO_CONTENT",
-            205 => "StatusCode::RESET_CONTENT",
-            206 => "StatusCode::PARTIAL_CONTENT",
-            207 => "StatusCode::MULTI_STATUS",
-            208 => "StatusCode::ALREADY_REPORTED",
-            226 => "StatusCode::IM_USED",
-            300 => "StatusCode::MULTIPLE_CHOICES",
-            301 => "StatusCode::MOVED_PERMANENTLY",
-            302 => "StatusCode::FOUND",
-            303 => "StatusCode::SEE_OTHER",
-            304 => "StatusCode::NOT_MODIFIED",
-            305 => "StatusCode::USE_PROXY",
-            307 => "StatusCode::TEMPORARY_REDIRECT",
-            308 => "StatusCode::PERMANENT_REDIRECT",
-            400 => "StatusCode::BAD_REQUEST",
-            401 => "StatusCode::UNAUTHORIZED",
-            402 => "StatusCode::PAYMENT_REQUIRED",
-            403 => "StatusCode::FORBIDDEN",
-            404 => "StatusCode::NOT_FOUND",
-            405 => "StatusCode::METHOD_NOT_ALLOWED",
-            406 => "StatusCode::NOT_ACCEPTABLE",
-            407 => "StatusCode::PROXY_AUTHENTICATION_REQUIRED",
-            408 => "StatusCode::REQUEST_TIMEOUT",
-            409 => "StatusCode::CONFLICT",
-            410 => "StatusCode::GONE",
-            411 => "StatusCode::LENGTH_REQUIRED",
-            412 => "StatusCode::PRECONDITION_FAILED",
-            413 => "StatusCode::PAYLOAD_TOO_LARGE",
-            414 => "StatusCode::URI_TOO_LONG",
-            415 => "StatusCode::UNSUPPORTED_MEDIA_TYPE",
-            416 => "StatusCode::RANGE_NOT_SATISFIABLE",
-            417 => "StatusCode::EXPECTATION_FAILED",
-            418 => "StatusCode::IM_A_TEAPOT",
-            421 => "StatusCode::MISDIRECTED_REQUEST",
-            422 => "StatusCode::UNPROCESSABLE_ENTITY",
-            423 => "StatusCode::LOCKED",
-            424 => "StatusCode::FAILED_DEPENDENCY",
-            426 => "StatusCode::UPGRADE_REQUIRED",
-            428 => "StatusCode::PRECONDITION_REQUIRED",
-            429 => "StatusCode::TOO_MANY_REQUESTS",
-            431 => "StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE",
-            451 => "StatusCode::UNAVAILABLE_FOR_LEGAL_REASONS",
-            500 => "StatusCode::INTERNAL_SERVER_ERROR",
-            501 => "StatusCode::NOT_IMPLEMENTED",
-            502 => "StatusCode::BAD_GATEWAY",
-            503 => "StatusCode::SERVICE_UNAVAILABLE",
-            504 => "StatusCode::GATEWAY_TIMEOUT",
-            505 => "StatusCode::HTTP_VERSION_NOT_SUPPORTED",
-            506 => "StatusCode::VARIANT_ALSO_NEGOTIATES",
-            507 => "StatusCode::INSUFFICIENT_STORAGE",
-            508 => "StatusCode::LOOP_DETECTED",
-            510 => "StatusCode::NOT_EXTENDED",
-            511 => "StatusCode::NETWORK_AUTHENTICATION_REQUIRED",
-            0 => {
-                return Err(askama::Error::Custom(Box::new(std::io::Error::new(
-                    std::io::ErrorKind::InvalidInput,
-                    "Status code cannot be 0 (does not fit in NonZeroU16)".to_string(),
-                ))));
-            }
-            code => {
-                return Ok(format!("StatusCode::from_u16_unchecked({})", code));
-            }
-        };
-        Ok(result.to_string())
+        super::status_code_const(*code).map_err(|e| {
+            askama::Error::Custom(Box::new(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                e,
+            )))
+        })
+    }
+
+    /// Name of the primitive a type reference is, e.g. `Bytes`, or empty
+    /// for named types
+    pub fn primitive(type_id: &StableId, _: &dyn askama::Values) -> askama::Result<String> {
+        Ok(super::primitive_name(type_id))
     }
 }
 
+/// Render a type reference to its Rust type
+/// Uses CanonicalName to ensure consistent pascal case naming
+fn render_type(type_id: &StableId) -> String {
+    match type_id {
+        StableId::Primitive(p) => PrimitiveTypes::RUST.get(*p).to_string(),
+        StableId::Named(name) => {
+            // Use CanonicalName to get proper pascal case (handles acronyms like FAQItem -> FaqItem)
+            let pascal = CanonicalName::from_string(name).pascal;
+            format!("crate::types::{}", pascal)
+        }
+    }
+}
+
+/// Name of the primitive a type reference is, or empty for named types
+fn primitive_name(type_id: &StableId) -> String {
+    match type_id {
+        StableId::Primitive(p) => format!("{:?}", p),
+        StableId::Named(_) => String::new(),
+    }
+}
+
+const PRIMITIVES: [Primitive; 12] = [
+    Primitive::Any,
+    Primitive::Bool,
+    Primitive::I32,
+    Primitive::I64,
+    Primitive::F32,
+    Primitive::F64,
+    Primitive::String,
+    Primitive::Date,
+    Primitive::DateTime,
+    Primitive::Uuid,
+    Primitive::Bytes,
+    Primitive::Decimal,
+];
+
+/// A type reference as an override template sees it. `StableId` serializes
+/// untagged, so a schema named like a primitive (e.g. `Decimal`) reads as
+/// the primitive.
+fn stable_id(value: &Value) -> StableId {
+    let name = value.to_string();
+    PRIMITIVES
+        .into_iter()
+        .find(|p| format!("{:?}", p) == name)
+        .map_or(StableId::Named(name), StableId::Primitive)
+}
+
+/// Make the filters of `service_module.rs.jinja` available to an override
+/// of it.
+pub fn add_filters(templates: &mut TemplateOverrides, naming: &NamingPolicy) {
+    let naming = naming.clone();
+    templates.add_filter("escape_rust_keyword", move |name, _| {
+        Ok(Value::from(identifier(&name.to_string(), "rust", &naming)))
+    });
+    templates.add_filter("to_pascal_case", |name, _| {
+        Ok(Value::from(
+            CanonicalName::from_string(&name.to_string()).pascal,
+        ))
+    });
+    templates.add_filter("render_type", |type_id, _| {
+        Ok(Value::from(render_type(&stable_id(type_id))))
+    });
+    templates.add_filter("primitive", |type_id, _| {
+        Ok(Value::from(primitive_name(&stable_id(type_id))))
+    });
+    templates.add_filter("status_code_const", |code, _| {
+        code.as_i64()
+            .and_then(|code| u16::try_from(code).ok())
+            .ok_or("not a status code")
+            .and_then(status_code_const)
+            .map(Value::from)
+            .map_err(|e| minijinja::Error::new(minijinja::ErrorKind::InvalidOperation, e))
+    });
+}
+
+/// Convert HTTP status code to StatusCode expression (constant or unsafe constructor)
+fn status_code_const(code: u16) -> std::result::Result<String, &'static str> {
+    let result = match code {
+        100 => "StatusCode::CONTINUE",
+        101 => "StatusCode::SWITCHING_PROTOCOLS",
+        102 => "StatusCode::PROCESSING",
+        200 => "StatusCode::OK",
+        201 => "StatusCode::CREATED",
+        202 => "StatusCode::ACCEPTED",
+        203 => "StatusCode::NON_AUTHORITATIVE_INFORMATION",
+        204 => "StatusCode::NO_CONTENT",
+        205 => "StatusCode::RESET_CONTENT",
+        206 => "StatusCode::PARTIAL_CONTENT",
+        207 => "StatusCode::MULTI_STATUS",
+        208 => "StatusCode::ALREADY_REPORTED",
+        226 => "StatusCode::IM_USED",
+        300 => "StatusCode::MULTIPLE_CHOICES",
+        301 => "StatusCode::MOVED_PERMANENTLY",
+        302 => "StatusCode::FOUND",
+        303 => "StatusCode::SEE_OTHER",
+        304 => "StatusCode::NOT_MODIFIED",
+        305 => "StatusCode::USE_PROXY",
+        307 => "StatusCode::TEMPORARY_REDIRECT",
+        308 => "StatusCode::PERMANENT_REDIRECT",
+        400 => "StatusCode::BAD_REQUEST",
+        401 => "StatusCode::UNAUTHORIZED",
+        402 => "StatusCode::PAYMENT_REQUIRED",
+        403 => "StatusCode::FORBIDDEN",
+        404 => "StatusCode::NOT_FOUND",
+        405 => "StatusCode::METHOD_NOT_ALLOWED",
+        406 => "StatusCode::NOT_ACCEPTABLE",
+        407 => "StatusCode::PROXY_AUTHENTICATION_REQUIRED",
+        408 => "StatusCode::REQUEST_TIMEOUT",
+        409 => "StatusCode::CONFLICT",
+        410 => "StatusCode::GONE",
+        411 => "StatusCode::LENGTH_REQUIRED",
+        412 => "StatusCode::PRECONDITION_FAILED",
+        413 => "StatusCode::PAYLOAD_TOO_LARGE",
+        414 => "StatusCode::URI_TOO_LONG",
+        415 => "StatusCode::UNSUPPORTED_MEDIA_TYPE",
+        416 => "StatusCode::RANGE_NOT_SATISFIABLE",
+        417 => "StatusCode::EXPECTATION_FAILED",
+        418 => "StatusCode::IM_A_TEAPOT",
+        421 => "StatusCode::MISDIRECTED_REQUEST",
+        422 => "StatusCode::UNPROCESSABLE_ENTITY",
+        423 => "StatusCode::LOCKED",
+        424 => "StatusCode::FAILED_DEPENDENCY",
+        426 => "StatusCode::UPGRADE_REQUIRED",
+        428 => "StatusCode::PRECONDITION_REQUIRED",
+        429 => "StatusCode::TOO_MANY_REQUESTS",
+        431 => "StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE",
+        451 => "StatusCode::UNAVAILABLE_FOR_LEGAL_REASONS",
+        500 => "StatusCode::INTERNAL_SERVER_ERROR",
+        501 => "StatusCode::NOT_IMPLEMENTED",
+        502 => "StatusCode::BAD_GATEWAY",
+        503 => "StatusCode::SERVICE_UNAVAILABLE",
+        504 => "StatusCode::GATEWAY_TIMEOUT",
+        505 => "StatusCode::HTTP_VERSION_NOT_SUPPORTED",
+        506 => "StatusCode::VARIANT_ALSO_NEGOTIATES",
+        507 => "StatusCode::INSUFFICIENT_STORAGE",
+        508 => "StatusCode::LOOP_DETECTED",
+        510 => "StatusCode::NOT_EXTENDED",
+        511 => "StatusCode::NETWORK_AUTHENTICATION_REQUIRED",
+        0 => return Err("Status code cannot be 0 (does not fit in NonZeroU16)"),
+        code => {
+            return Ok(format!("StatusCode::from_u16_unchecked({})", code));
+        }
+    };
+    Ok(result.to_string())
+}
+
 /// Wrapper for operations with preprocessed data for templates
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 struct OperationTemplate<'a> {
     operation: &'a Operation,
     method_fn: &'static str,
@@ -144,9 +207,40 @@ struct OperationTemplate<'a> {
     response_content_type: ResponseContentType,
     /// For binary responses with multiple content types, this contains all supported types
     binary_content_types: Vec<String>,
+    /// Success responses when there are several (`alt_success`), each an
+    /// `<Op>Response` variant
+    success_variants: Vec<SuccessVariant<'a>>,
+}
+
+/// One status code of an operation's success response enum
+#[derive(Debug, Clone, Serialize)]
+struct SuccessVariant<'a> {
+    name: String,
+    code: u16,
+    ty: Option<&'a TypeRef>,
+}
+
+impl SuccessVariant<'_> {
+    /// Variant name for a status code: `Ok`, `Created`, ..., else `Status299`
+    fn name(code: u16) -> String {
+        match code {
+            200 => "Ok",
+            201 => "Created",
+            202 => "Accepted",
+            203 => "NonAuthoritativeInformation",
+            204 => "NoContent",
+            205 => "ResetContent",
+            206 => "PartialContent",
+            207 => "MultiStatus",
+            208 => "AlreadyReported",
+            226 => "ImUsed",
+            _ => return format!("Status{}", code),
+        }
+        .to_string()
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum RequestContentType {
     Json,
     Multipart,
@@ -154,7 +248,7 @@ pub enum RequestContentType {
     None,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum ResponseContentType {
     Json,
     /// Single binary content type (e.g., "application/octet-stream")
@@ -172,7 +266,23 @@ impl<'a> OperationTemplate<'a> {
         ct == "application/json" || ct.starts_with("application/json;") || ct.ends_with("+json")
     }
 
-    fn new(operation: &'a Operation) -> Self {
+    /// Fails if a success response has a status code axum can't represent.
+    fn new(operation: &'a Operation) -> Result<Self> {
+        let statuses = std::iter::once(&operation.success)
+            .flatten()
+            .chain(&operation.alt_success);
+        for payload in statuses {
+            if let StatusSpec::Code(code) = payload.status
+                && !(100..=999).contains(&code)
+            {
+                return Err(Error::ValidationError(format!(
+                    "status code {} is not a valid HTTP status",
+                    code
+                )))
+                .in_operation(operation);
+            }
+        }
+
         let method_fn = match operation.http.method {
             HttpMethod::Get => "get",
             HttpMethod::Post => "post",
@@ -232,23 +342,45 @@ impl<'a> OperationTemplate<'a> {
             ResponseContentType::None
         };
 
-        Self {
+        // Binary responses keep their own wrapper types
+        let success_variants = match (&operation.success, response_content_type) {
+            (Some(success), ResponseContentType::Json | ResponseContentType::None)
+                if !operation.alt_success.is_empty() =>
+            {
+                std::iter::once(success)
+                    .chain(&operation.alt_success)
+                    .filter_map(|payload| match payload.status {
+                        StatusSpec::Code(code) => Some(SuccessVariant {
+                            name: SuccessVariant::name(code),
+                            code,
+                            ty: payload.ty.as_ref(),
+                        }),
+                        _ => None,
+                    })
+                    .collect()
+            }
+            _ => Vec::new(),
+        };
+
+        Ok(Self {
             operation,
             method_fn,
             request_content_type,
             response_content_type,
             binary_content_types,
-        }
+            success_variants,
+        })
     }
 }
 
 /// Template data for service module generation
-#[derive(Template)]
+#[derive(askama::Template, Serialize)]
 #[template(path = "service_module.rs.jinja", escape = "none")]
 struct ServiceModuleTemplate<'a> {
     trait_name: &'a str,
     module_name: &'a str,
     package_name: &'a str,
+    naming: &'a NamingPolicy,
     has_bearer_auth: bool,
     has_api_key_auth: bool,
     operations: Vec<OperationTemplate<'a>>,
@@ -259,6 +391,7 @@ pub struct ServiceModuleGenerator<'a> {
     service: &'a Service,
     auth_schemes: &'a [AuthScheme],
     package_name: &'a str,
+    naming: &'a NamingPolicy,
 }
 
 impl<'a> ServiceModuleGenerator<'a> {
@@ -266,15 +399,17 @@ impl<'a> ServiceModuleGenerator<'a> {
         service: &'a Service,
         auth_schemes: &'a [AuthScheme],
         package_name: &'a str,
+        naming: &'a NamingPolicy,
     ) -> Self {
         Self {
             service,
             auth_schemes,
             package_name,
+            naming,
         }
     }
 
-    pub fn generate(&self) -> String {
+    pub fn generate(&self, templates: &TemplateOverrides) -> Result<String> {
         // Determine which auth wrappers to generate
         let mut has_bearer_auth = false;
         let mut has_api_key_auth = false;
@@ -297,7 +432,7 @@ impl<'a> ServiceModuleGenerator<'a> {
             .operations
             .iter()
             .map(OperationTemplate::new)
-            .collect();
+            .collect::<Result<_>>()?;
 
         // Collect unique HTTP methods used
         let mut methods: Vec<&'static str> = operations
@@ -312,14 +447,13 @@ impl<'a> ServiceModuleGenerator<'a> {
             trait_name: &self.service.name.pascal,
             module_name: &self.service.name.snake,
             package_name: self.package_name,
+            naming: self.naming,
             has_bearer_auth,
             has_api_key_auth,
             operations,
             methods,
         };
 
-        template
-            .render()
-            .unwrap_or_else(|e| panic!("Failed to render service module template: {}", e))
+        render(templates, "service_module.rs.jinja", &template)
     }
 }
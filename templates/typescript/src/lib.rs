@@ -4,10 +4,24 @@
 //! the hybrid approach: programmatic file structure with template-based rendering.
 
 use askama::Template;
-use codegen::{Config, Error, GenIr, Generator, Result, VirtualFS};
-use ir::gen_ir::{TypeDecl, TypeKind};
+use codegen::{
+    Config, Error, FileSink, GenIr, Generator, NamingPolicy, Result, TimeFormat, VirtualFS,
+    codeowners,
+    diagnostic::ResultExt,
+    emit::PrimitiveTypes,
+    layout::Layout,
+    overrides::{TemplateOverrides, render},
+    postprocess::{CommandProcessor, PostProcessor},
+    verify::{CommandVerifier, Verifier},
+};
+use ir::gen_ir::{StableId, TypeDecl, TypeKind};
+use serde::Serialize;
 use std::path::{Path, PathBuf};
 
+pub mod options;
+
+pub use options::{TypeFiles, TypeScriptOptions};
+
 /// TypeScript SDK generator.
 pub struct TypeScriptGenerator;
 
@@ -17,57 +31,536 @@ impl TypeScriptGenerator {
         Self
     }
 
-    /// Generate types into the virtual file system.
-    fn generate_types(&self, ir: &GenIr, _config: &Config, vfs: &mut VirtualFS) -> Result<()> {
-        let types_dir = PathBuf::from("src").join("types");
+    /// `<module>.ts`.
+    fn ts_file(module: &Path) -> PathBuf {
+        let mut file = module.as_os_str().to_owned();
+        file.push(".ts");
+        PathBuf::from(file)
+    }
 
-        // Group types into a single index file for simplicity
-        let mut type_declarations = Vec::new();
+    /// `module` with `/` separators and without a trailing `/index`, as
+    /// written in an import.
+    fn module_specifier(module: &Path) -> String {
+        let mut parts: Vec<String> = module
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect();
+        if parts.len() > 1 && parts.last().is_some_and(|last| last == "index") {
+            parts.pop();
+        }
+        parts.join("/")
+    }
 
+    /// Specifier importing the module `to` from the module `from`, e.g.
+    /// `../types` from `src/services/pets` to `src/types/index`.
+    fn import_path(from: &Path, to: &Path) -> String {
+        let from_dir: Vec<_> = from
+            .parent()
+            .map(|dir| dir.components().collect())
+            .unwrap_or_default();
+        let to_parts: Vec<_> = to.components().collect();
+        let common = from_dir
+            .iter()
+            .zip(&to_parts)
+            .take_while(|(a, b)| a == b)
+            .count();
+        let rest: PathBuf = to_parts[common..].iter().collect();
+        // `./index` stays as is: its directory is the importing one
+        let rest = if rest == Path::new("index") {
+            "index".to_string()
+        } else {
+            Self::module_specifier(&rest)
+        };
+        match from_dir.len() - common {
+            0 => format!("./{}", rest),
+            ups => format!("{}{}", "../".repeat(ups), rest),
+        }
+    }
+
+    /// Specifier importing the types module of the shared package
+    /// `package` from the module `from`: back to the package root, then
+    /// into the shared package's `types_module`, as it uses the same layout.
+    fn shared_types_module(package: &str, types_module: &Path, from: &Path) -> String {
+        let depth = from.parent().map_or(0, |dir| dir.components().count());
+        format!(
+            "{}{}/{}",
+            "../".repeat(depth),
+            package,
+            Self::module_specifier(types_module)
+        )
+    }
+
+    /// Modules declaring `types` with [`TypeFiles::PerType`], by id: next
+    /// to `types_module` if it is an `index`, else in a directory of its
+    /// name. A type whose module would be the index, a helper module beside
+    /// it or the module of an earlier type (`PetDto` and `PetDTO`) gets a
+    /// `-type` suffix, then a number.
+    fn type_modules<'a>(
+        types_module: &Path,
+        types: impl IntoIterator<Item = &'a TypeDecl>,
+    ) -> std::collections::BTreeMap<&'a StableId, PathBuf> {
+        let dir = match types_module.parent() {
+            Some(dir) if types_module.ends_with("index") => dir.to_path_buf(),
+            _ => types_module.to_path_buf(),
+        };
+        // Compared lowercased, for case-insensitive file systems
+        let mut taken: std::collections::BTreeSet<String> =
+            ["index", "errors", "datetime", "number"]
+                .into_iter()
+                .map(String::from)
+                .collect();
+        let mut modules = std::collections::BTreeMap::new();
+        for type_decl in types {
+            let kebab = &type_decl.name.kebab;
+            let mut name = kebab.clone();
+            let mut suffix = 1;
+            while taken.contains(&name.to_lowercase()) {
+                name = match suffix {
+                    1 => format!("{}-type", kebab),
+                    n => format!("{}-type-{}", kebab, n),
+                };
+                suffix += 1;
+            }
+            taken.insert(name.to_lowercase());
+            modules.insert(&type_decl.id, dir.join(name));
+        }
+        modules
+    }
+
+    /// Generate types into the file sink.
+    #[allow(clippy::too_many_arguments)]
+    fn generate_types(
+        &self,
+        ir: &GenIr,
+        config: &Config,
+        options: &TypeScriptOptions,
+        codecs: &Codecs,
+        layout: &dyn Layout,
+        templates: &TemplateOverrides,
+        sink: &mut dyn FileSink,
+    ) -> Result<()> {
+        let types_module = layout.types_module();
+        let types_dir = layout.types_dir();
+
+        // Types provided by a shared package (multi-version generation) are re-exported
+        let shared_package = config
+            .shared_types
+            .as_ref()
+            .map(|shared| shared.package.as_str());
+        let is_shared = |type_decl: &TypeDecl| {
+            config
+                .shared_types
+                .as_ref()
+                .is_some_and(|shared| shared.contains(type_decl))
+        };
+
+        let mut declarations = Vec::new();
+        let mut imported = Vec::new();
         for type_decl in ir.types.values() {
-            let rendered = self.render_type(type_decl, ir)?;
-            type_declarations.push(rendered);
+            if is_shared(type_decl) {
+                imported.extend(Self::type_exports(type_decl, codecs));
+                continue;
+            }
+            let mut rendered = self
+                .render_type(type_decl, ir, config, templates)
+                .in_type(type_decl)?;
+            let mut helpers = TypeHelpers::default();
+            if let Some(codec) = self
+                .render_json_codec(type_decl, config, codecs, templates)
+                .in_type(type_decl)?
+            {
+                rendered.push_str("\n\n");
+                rendered.push_str(&codec);
+                helpers.time = codec.contains("parseTime(");
+                helpers.number = codec.contains("coerceNumber(");
+            }
+            if let Some(validator) = self
+                .render_validator(type_decl, config, templates)
+                .in_type(type_decl)?
+            {
+                rendered.push_str("\n\n");
+                rendered.push_str(&validator);
+            }
+            declarations.push((type_decl, rendered, helpers));
         }
 
-        let types_content = format!(
-            "// Generated types from OpenAPI specification\n\n{}",
-            type_declarations.join("\n\n")
-        );
+        let uses_time = declarations.iter().any(|(_, _, helpers)| helpers.time);
+        let uses_number = declarations.iter().any(|(_, _, helpers)| helpers.number);
+        if uses_time {
+            let content = render(templates, "datetime.ts.jinja", &DateTimeTemplate)?;
+            sink.write_text(&Self::ts_file(&types_dir.join("datetime")), &content)?;
+        }
+        if uses_number {
+            let content = render(templates, "number.ts.jinja", &NumberTemplate)?;
+            sink.write_text(&Self::ts_file(&types_dir.join("number")), &content)?;
+        }
 
-        vfs.add_file(types_dir.join("index.ts"), types_content);
+        let mut types_content = String::from("// Generated types from OpenAPI specification\n\n");
+        match options.type_files {
+            TypeFiles::Single => {
+                let helpers = TypeHelpers {
+                    time: uses_time,
+                    number: uses_number,
+                };
+                types_content.push_str(&helpers.imports(&types_module, &types_dir));
+                if let Some(package) = shared_package
+                    && !imported.is_empty()
+                {
+                    let module = Self::shared_types_module(package, &types_module, &types_module);
+                    types_content.push_str(&format!(
+                        "import {{ {} }} from '{}';\nexport * from '{}';\n\n",
+                        imported.join(", "),
+                        module,
+                        module
+                    ));
+                }
+                let declarations: Vec<&str> = declarations
+                    .iter()
+                    .map(|(_, rendered, _)| rendered.as_str())
+                    .collect();
+                types_content.push_str(&declarations.join("\n\n"));
+            }
+            TypeFiles::PerType => {
+                // Each module imports what it references; the types module
+                // only re-exports them
+                let graph = ir.type_graph();
+                let modules = Self::type_modules(
+                    &types_module,
+                    declarations.iter().map(|(type_decl, _, _)| *type_decl),
+                );
+                for (type_decl, rendered, helpers) in &declarations {
+                    let module = &modules[&type_decl.id];
+                    let mut content = String::from("// Generated from OpenAPI specification\n\n");
+                    content.push_str(&helpers.imports(module, &types_dir));
+                    let (mut shared_types, mut shared_codecs) = (Vec::new(), Vec::new());
+                    let mut imports = String::new();
+                    for dependency in graph.dependencies(&type_decl.id) {
+                        let Some(dependency) = ir.types.get(dependency) else {
+                            continue;
+                        };
+                        if dependency.id == type_decl.id {
+                            continue;
+                        }
+                        let types = vec![dependency.name.pascal.clone()];
+                        // Codecs call the codecs of the types they contain
+                        let functions = match codecs.get(&dependency.id) {
+                            Some(name) if codecs.contains_key(&type_decl.id) => vec![
+                                Self::codec_function(name, true),
+                                Self::codec_function(name, false),
+                            ],
+                            _ => Vec::new(),
+                        };
+                        if is_shared(dependency) {
+                            shared_types.extend(types);
+                            shared_codecs.extend(functions);
+                            continue;
+                        }
+                        imports.push_str(&format!(
+                            "{} from '{}';\n",
+                            Self::import_statement(&types, &functions),
+                            Self::import_path(module, &modules[&dependency.id])
+                        ));
+                    }
+                    if let Some(package) = shared_package
+                        && !shared_types.is_empty()
+                    {
+                        imports.insert_str(
+                            0,
+                            &format!(
+                                "{} from '{}';\n",
+                                Self::import_statement(&shared_types, &shared_codecs),
+                                Self::shared_types_module(package, &types_module, module)
+                            ),
+                        );
+                    }
+                    if !imports.is_empty() {
+                        content.push_str(&imports);
+                        content.push('\n');
+                    }
+                    content.push_str(rendered);
+                    content.push('\n');
+                    sink.write_text(&Self::ts_file(module), &content)?;
+
+                    types_content.push_str(&format!(
+                        "export * from '{}';\n",
+                        Self::import_path(&types_module, module)
+                    ));
+                }
+                if let Some(package) = shared_package
+                    && !imported.is_empty()
+                {
+                    // Named, so they can't clash with this package's own types
+                    types_content.push_str(&format!(
+                        "export {{ {} }} from '{}';\n",
+                        imported.join(", "),
+                        Self::shared_types_module(package, &types_module, &types_module)
+                    ));
+                }
+            }
+        }
+
+        sink.write_text(&Self::ts_file(&types_module), &types_content)?;
 
         // Generate error classes
-        self.generate_errors(ir, vfs)?;
+        self.generate_errors(ir, layout, templates, sink)?;
 
         Ok(())
     }
 
     /// Generate base error classes for API error handling.
-    fn generate_errors(&self, _ir: &GenIr, vfs: &mut VirtualFS) -> Result<()> {
-        let types_dir = PathBuf::from("src").join("types");
-
-        let data = ErrorsTemplate;
-        let content = data
-            .render()
-            .map_err(|e| Error::TemplateError(Box::new(e)))?;
-        vfs.add_file(types_dir.join("errors.ts"), content);
+    fn generate_errors(
+        &self,
+        _ir: &GenIr,
+        layout: &dyn Layout,
+        templates: &TemplateOverrides,
+        sink: &mut dyn FileSink,
+    ) -> Result<()> {
+        let content = render(templates, "errors.ts.jinja", &ErrorsTemplate)?;
+        sink.write_text(&Self::ts_file(&layout.types_dir().join("errors")), &content)?;
 
         Ok(())
     }
 
+    /// `<name>FromJSON`/`<name>ToJSON` converting the fields of a struct
+    /// whose wire format differs from their TypeScript type: dates (see
+    /// `Config::time_formats`), numbers sent as strings (see
+    /// `Config::coerces_number`) and values of types with a codec themselves.
+    /// `None` if it has none.
+    fn render_json_codec(
+        &self,
+        type_decl: &TypeDecl,
+        config: &Config,
+        codecs: &Codecs,
+        templates: &TemplateOverrides,
+    ) -> Result<Option<String>> {
+        let TypeKind::Struct { fields, .. } = &type_decl.kind else {
+            return Ok(None);
+        };
+        let fields: Vec<CodecFieldData> = fields
+            .iter()
+            .filter_map(|f| Self::codec_field(f, config, codecs))
+            .collect();
+        if fields.is_empty() {
+            return Ok(None);
+        }
+
+        let data = JsonCodecTemplate {
+            name: &type_decl.name,
+            fields,
+        };
+        render(templates, "json_codec.ts.jinja", &data).map(Some)
+    }
+
+    /// How the JSON codec of a struct converts `field`, or `None` if its wire
+    /// format is its TypeScript type.
+    fn codec_field(
+        field: &ir::gen_ir::Field,
+        config: &Config,
+        codecs: &Codecs,
+    ) -> Option<CodecFieldData> {
+        let name = field.name.camel.clone();
+        if field.const_value.is_some() {
+            return None;
+        }
+        if let Some(format) = config.time_formats.for_field(field) {
+            let format = Self::render_time_format(field, format);
+            return Some(CodecFieldData {
+                decode: format!("parseTime(json.{}, {})", name, format),
+                encode: Some(format!("formatTime(value.{}, {})", name, format)),
+                name,
+            });
+        }
+        if config.coerces_number(field) {
+            return Some(CodecFieldData {
+                decode: format!("coerceNumber(json.{})", name),
+                encode: None,
+                name,
+            });
+        }
+        Some(CodecFieldData {
+            decode: Self::codec_call(&field.ty, codecs, &format!("json.{}", name), true)?,
+            encode: Self::codec_call(&field.ty, codecs, &format!("value.{}", name), false),
+            name,
+        })
+    }
+
+    /// `expr` converted by the `FromJSON` (`decode`) or `ToJSON` codec of the
+    /// type `ty` refers to, or `None` if that type has no codec.
+    fn codec_call(
+        ty: &ir::gen_ir::TypeRef,
+        codecs: &Codecs,
+        expr: &str,
+        decode: bool,
+    ) -> Option<String> {
+        let function = Self::codec_function(codecs.get(&ty.target)?, decode);
+        match ty.modifiers.as_slice() {
+            [] => Some(format!("{}({})", function, expr)),
+            [ir::gen_ir::TypeMod::List] if expr.contains(' ') => {
+                Some(format!("({}).map({})", expr, function))
+            }
+            [ir::gen_ir::TypeMod::List] => Some(format!("{}.map({})", expr, function)),
+            _ => None,
+        }
+    }
+
+    /// `petFromJSON` or `petToJSON`.
+    fn codec_function(name: &str, decode: bool) -> String {
+        format!("{}{}", name, if decode { "FromJSON" } else { "ToJSON" })
+    }
+
+    /// Struct types that need a JSON codec: those with a field whose wire
+    /// format differs from its TypeScript type, directly or through a field
+    /// of another such type.
+    fn codec_types(ir: &GenIr, config: &Config) -> Codecs {
+        let mut codecs = Codecs::new();
+        loop {
+            let found: Vec<(ir::gen_ir::StableId, String)> = ir
+                .types
+                .values()
+                .filter(|type_decl| !codecs.contains_key(&type_decl.id))
+                .filter(|type_decl| match &type_decl.kind {
+                    TypeKind::Struct { fields, .. } => fields
+                        .iter()
+                        .any(|f| Self::codec_field(f, config, &codecs).is_some()),
+                    _ => false,
+                })
+                .map(|type_decl| (type_decl.id.clone(), type_decl.name.camel.clone()))
+                .collect();
+            if found.is_empty() {
+                return codecs;
+            }
+            codecs.extend(found);
+        }
+    }
+
+    /// Names a type module exports for `type_decl`: the type, and its codec
+    /// functions if it has any.
+    fn type_exports(type_decl: &TypeDecl, codecs: &Codecs) -> Vec<String> {
+        let mut names = vec![type_decl.name.pascal.clone()];
+        if let Some(name) = codecs.get(&type_decl.id) {
+            names.push(Self::codec_function(name, true));
+            names.push(Self::codec_function(name, false));
+        }
+        names
+    }
+
+    /// `import type { Pet }`, or `import { type Pet, petFromJSON }` when
+    /// values are imported too.
+    fn import_statement(types: &[String], values: &[String]) -> String {
+        if values.is_empty() {
+            return format!("import type {{ {} }}", types.join(", "));
+        }
+        let names: Vec<String> = types
+            .iter()
+            .map(|name| format!("type {}", name))
+            .chain(values.iter().cloned())
+            .collect();
+        format!("import {{ {} }}", names.join(", "))
+    }
+
+    /// `validate<Type>` function checking the fields with a registered
+    /// string format, if there are any.
+    fn render_validator(
+        &self,
+        type_decl: &TypeDecl,
+        config: &Config,
+        templates: &TemplateOverrides,
+    ) -> Result<Option<String>> {
+        let TypeKind::Struct { fields, .. } = &type_decl.kind else {
+            return Ok(None);
+        };
+        let fields: Vec<ValidatorFieldData> = fields
+            .iter()
+            .filter_map(|f| {
+                let rule = config.formats.for_field(f)?;
+                Some(ValidatorFieldData {
+                    name: f.name.camel.clone(),
+                    pattern: Self::escape_regex_slashes(&rule.pattern),
+                    description: rule.description.replace('\\', "\\\\").replace('\'', "\\'"),
+                })
+            })
+            .collect();
+        if fields.is_empty() {
+            return Ok(None);
+        }
+
+        let data = ValidatorTemplate {
+            name: &type_decl.name,
+            fields,
+        };
+        render(templates, "validator.ts.jinja", &data).map(Some)
+    }
+
+    /// Copy of `ir` with declared type names that `naming` reserves escaped
+    /// (e.g. a reserved `Response` becomes `Response_`), or `None` if no name
+    /// is reserved. Ids are kept, so references resolve to the escaped name.
+    fn escape_type_names(ir: &GenIr, naming: &NamingPolicy) -> Option<GenIr> {
+        let reserved = |decl: &TypeDecl| naming.is_reserved(&decl.name.pascal, "typescript");
+        if !ir.types.values().any(reserved) {
+            return None;
+        }
+        let mut ir = ir.clone();
+        for decl in ir.types.values_mut() {
+            decl.name.pascal = naming.escape(&decl.name.pascal, "typescript");
+        }
+        Some(ir)
+    }
+
+    /// Escape the `/`s of `pattern` not already escaped, for a `/.../` literal.
+    fn escape_regex_slashes(pattern: &str) -> String {
+        let mut escaped = String::with_capacity(pattern.len());
+        let mut backslash = false;
+        for c in pattern.chars() {
+            if c == '/' && !backslash {
+                escaped.push('\\');
+            }
+            backslash = c == '\\' && !backslash;
+            escaped.push(c);
+        }
+        escaped
+    }
+
+    /// `TimeFormat` value from the generated `datetime.ts`.
+    fn render_time_format(field: &ir::gen_ir::Field, format: &TimeFormat) -> String {
+        let is_date = matches!(
+            field.ty.target,
+            ir::gen_ir::StableId::Primitive(ir::gen_ir::Primitive::Date)
+        );
+        match format {
+            TimeFormat::Rfc3339 if is_date => "'date'".to_string(),
+            TimeFormat::Rfc3339 => "'rfc3339'".to_string(),
+            TimeFormat::UnixSeconds => "'unix'".to_string(),
+            TimeFormat::UnixMillis => "'unix-ms'".to_string(),
+            TimeFormat::Pattern(pattern) => format!(
+                "{{ pattern: '{}' }}",
+                pattern.replace('\\', "\\\\").replace('\'', "\\'")
+            ),
+        }
+    }
+
     /// Render a single type declaration.
-    fn render_type(&self, type_decl: &TypeDecl, ir: &GenIr) -> Result<String> {
+    fn render_type(
+        &self,
+        type_decl: &TypeDecl,
+        ir: &GenIr,
+        config: &Config,
+        templates: &TemplateOverrides,
+    ) -> Result<String> {
         match &type_decl.kind {
             TypeKind::Struct { fields, .. } => {
                 let data = InterfaceTemplate {
                     name: &type_decl.name,
                     docs: &type_decl.docs,
+                    has_sensitive_fields: fields.iter().any(|f| f.sensitive),
                     fields: fields
                         .iter()
                         .map(|f| {
                             // If there's a const value, use it as a literal type
                             let type_str = if let Some(const_val) = &f.const_value {
                                 self.render_literal(const_val)
+                            } else if config.time_formats.for_field(f).is_some() {
+                                // Converted by the type's JSON codec
+                                if f.ty.nullable { "Date | null" } else { "Date" }.to_string()
                             } else {
                                 self.render_type_ref(&f.ty, ir)
                             };
@@ -77,11 +570,12 @@ impl TypeScriptGenerator {
                                 optional: f.ty.optional,
                                 type_str,
                                 docs: &f.docs,
+                                sensitive: f.sensitive,
                             }
                         })
                         .collect(),
                 };
-                data.render().map_err(|e| Error::TemplateError(Box::new(e)))
+                render(templates, "interface.ts.jinja", &data)
             }
             TypeKind::Enum { values, base: _ } => {
                 let data = EnumTemplate {
@@ -95,7 +589,7 @@ impl TypeScriptGenerator {
                         })
                         .collect(),
                 };
-                data.render().map_err(|e| Error::TemplateError(Box::new(e)))
+                render(templates, "enum.ts.jinja", &data)
             }
             TypeKind::Union { variants, .. } => {
                 // For simplicity, render unions as type aliases with union types
@@ -108,7 +602,7 @@ impl TypeScriptGenerator {
                     docs: &type_decl.docs,
                     target: type_names.join(" | "),
                 };
-                data.render().map_err(|e| Error::TemplateError(Box::new(e)))
+                render(templates, "type_alias.ts.jinja", &data)
             }
             TypeKind::Alias { aliased } => {
                 let target = self.render_alias_target(aliased, ir);
@@ -117,26 +611,33 @@ impl TypeScriptGenerator {
                     docs: &type_decl.docs,
                     target,
                 };
-                data.render().map_err(|e| Error::TemplateError(Box::new(e)))
+                render(templates, "type_alias.ts.jinja", &data)
+            }
+            TypeKind::Opaque { reason, .. } => {
+                // Note why the type is `unknown` in its doc comment
+                let mut docs = type_decl.docs.clone();
+                let note = reason.describe().to_string();
+                if docs.summary.is_none() {
+                    docs.summary = Some(note);
+                } else {
+                    docs.description = Some(match docs.description {
+                        Some(description) => format!("{}\n *\n * {}", description, note),
+                        None => note,
+                    });
+                }
+                let data = TypeAliasTemplate {
+                    name: &type_decl.name,
+                    docs: &docs,
+                    target: "unknown".to_string(),
+                };
+                render(templates, "type_alias.ts.jinja", &data)
             }
         }
     }
 
     /// Render a primitive type as TypeScript type.
     fn render_primitive(&self, primitive: ir::gen_ir::Primitive) -> String {
-        use ir::gen_ir::Primitive;
-        match primitive {
-            Primitive::Any => "any".to_string(),
-            Primitive::Bool => "boolean".to_string(),
-            Primitive::I32 | Primitive::I64 | Primitive::F32 | Primitive::F64 => {
-                "number".to_string()
-            }
-            Primitive::String => "string".to_string(),
-            Primitive::Date | Primitive::DateTime => "Date".to_string(),
-            Primitive::Uuid => "string".to_string(),
-            Primitive::Bytes => "Uint8Array".to_string(),
-            Primitive::Decimal => "number".to_string(),
-        }
+        PrimitiveTypes::TYPESCRIPT.get(primitive).to_string()
     }
 
     /// Render a literal as a TypeScript literal.
@@ -240,14 +741,80 @@ impl TypeScriptGenerator {
     }
 
     /// Generate service files.
-    fn generate_services(&self, ir: &GenIr, _config: &Config, vfs: &mut VirtualFS) -> Result<()> {
-        let services_dir = PathBuf::from("src").join("services");
+    fn generate_services(
+        &self,
+        ir: &GenIr,
+        options: &TypeScriptOptions,
+        codecs: &Codecs,
+        layout: &dyn Layout,
+        templates: &TemplateOverrides,
+        sink: &mut dyn FileSink,
+    ) -> Result<()> {
+        let runtime_dir = layout.runtime_dir();
+        let client_module = runtime_dir.join("client");
+        let errors_module = layout.types_dir().join("errors");
+
+        let has_cache = Self::uses_cache(ir, options);
+        if has_cache {
+            let content = render(templates, "cache.ts.jinja", &CacheTemplate)?;
+            sink.write_text(&runtime_dir.join("cache.ts"), &content)?;
+        }
+        let has_offline_queue = Self::uses_offline_queue(ir, options);
+        if has_offline_queue {
+            let content = render(templates, "offline.ts.jinja", &OfflineTemplate)?;
+            sink.write_text(&runtime_dir.join("offline.ts"), &content)?;
+        }
+        let has_dedupe = Self::uses_dedupe(ir, options);
+        if has_dedupe {
+            let content = render(templates, "inflight.ts.jinja", &InFlightTemplate)?;
+            sink.write_text(&runtime_dir.join("inflight.ts"), &content)?;
+        }
 
         // Generate individual service files
         for service in &ir.services {
-            let file_name = format!("{}.ts", service.name.snake);
-            let content = self.render_service(service, ir)?;
-            vfs.add_file(services_dir.join(file_name), content);
+            let module = layout.service_module(service);
+            let import = |to: &Path| Self::import_path(&module, to);
+            let imports = ServiceImports {
+                types: import(&layout.types_module()),
+                errors: import(&errors_module),
+                client: import(&client_module),
+                cache: import(&runtime_dir.join("cache")),
+                offline: import(&runtime_dir.join("offline")),
+                inflight: import(&runtime_dir.join("inflight")),
+            };
+            let (mut content, type_imports) = self.render_service(
+                service,
+                ir,
+                Runtime {
+                    cache: has_cache,
+                    offline_queue: has_offline_queue,
+                    dedupe: has_dedupe,
+                },
+                codecs,
+                &imports,
+                templates,
+            )?;
+            let owners = service.all_owners();
+            if !owners.is_empty() {
+                content = format!("// Owners: {}\n\n{}", owners.join(" "), content);
+            }
+            sink.write_text(&Self::ts_file(&module), &content)?;
+
+            if let Some(index) = layout.service_index(service) {
+                let mut content = format!(
+                    "export {{ {}Service }} from '{}';\n",
+                    service.name.pascal,
+                    Self::import_path(&index, &module)
+                );
+                if !type_imports.is_empty() {
+                    content.push_str(&format!(
+                        "export type {{ {} }} from '{}';\n",
+                        type_imports.join(", "),
+                        Self::import_path(&index, &layout.types_module())
+                    ));
+                }
+                sink.write_text(&Self::ts_file(&index), &content)?;
+            }
         }
 
         // Get default base URL from server sets
@@ -267,7 +834,7 @@ impl TypeScriptGenerator {
             .iter()
             .map(|s| ServiceImportData {
                 name: s.name.pascal.clone(),
-                file: s.name.snake.clone(),
+                file: Self::import_path(&client_module, &layout.service_module(s)),
             })
             .collect();
 
@@ -277,29 +844,133 @@ impl TypeScriptGenerator {
             auth_schemes: &ir.auth_schemes,
             default_base_url,
             service_imports,
+            has_feature_flags: !ir.feature_flags().is_empty(),
+            has_cache,
+            has_offline_queue,
+            has_dedupe,
+            raw_request: options.raw_request,
+            has_credential_headers: !ir.auth_schemes.is_empty()
+                && (options.raw_request || has_offline_queue),
+            credentials: ir.auth_schemes.iter().map(Self::auth_scheme_use).collect(),
+            errors_import: Self::import_path(&client_module, &errors_module),
         };
-        let content = data
-            .render()
-            .map_err(|e| Error::TemplateError(Box::new(e)))?;
-        vfs.add_file(services_dir.join("client.ts"), content);
+        let content = render(templates, "client.ts.jinja", &data)?;
+        sink.write_text(&Self::ts_file(&client_module), &content)?;
 
         Ok(())
     }
 
+    /// Whether the SDK gets a result cache: some GET operation is cacheable
+    /// and the `cache` lang option isn't `false`.
+    fn uses_cache(ir: &GenIr, options: &TypeScriptOptions) -> bool {
+        ir.has_cacheable_operations() && options.cache
+    }
+
+    /// Whether the SDK gets an offline queue: some operation is queueable and
+    /// the `offline_queue` lang option is `true`.
+    fn uses_offline_queue(ir: &GenIr, options: &TypeScriptOptions) -> bool {
+        ir.services
+            .iter()
+            .flat_map(|service| &service.operations)
+            .any(Self::is_queueable)
+            && options.offline_queue
+    }
+
+    /// Mutations that are safe to send again later: idempotent ones (PUT, DELETE).
+    fn is_queueable(op: &ir::gen_ir::Operation) -> bool {
+        op.idempotent && op.http.method != ir::gen_ir::HttpMethod::Get
+    }
+
+    /// Whether GET requests are coalesced while in flight: some operation is a
+    /// GET and the `dedupe_requests` lang option is `true`.
+    fn uses_dedupe(ir: &GenIr, options: &TypeScriptOptions) -> bool {
+        ir.services
+            .iter()
+            .flat_map(|service| &service.operations)
+            .any(|op| op.http.method == ir::gen_ir::HttpMethod::Get)
+            && options.dedupe_requests
+    }
+
+    /// How a request applies `scheme`'s credentials.
+    fn auth_scheme_use(scheme: &ir::gen_ir::AuthScheme) -> AuthSchemeUse {
+        let (kind, param_name) = match &scheme.kind {
+            ir::gen_ir::AuthKind::Http {
+                scheme: http_scheme,
+                ..
+            } => (format!("bearer_{}", http_scheme.to_ascii_lowercase()), None),
+            ir::gen_ir::AuthKind::ApiKey {
+                location,
+                param_name,
+            } => {
+                let kind = match location {
+                    ir::gen_ir::ApiKeyLocation::Header => "apikey_header",
+                    ir::gen_ir::ApiKeyLocation::Query => "apikey_query",
+                    ir::gen_ir::ApiKeyLocation::Cookie => "apikey_cookie",
+                };
+                (kind.to_string(), Some(param_name.clone()))
+            }
+            ir::gen_ir::AuthKind::OAuth2 { .. } => ("oauth2".to_string(), None),
+            ir::gen_ir::AuthKind::OpenIdConnect { .. } => ("openid".to_string(), None),
+        };
+        AuthSchemeUse {
+            name_camel: scheme.name.camel.clone(),
+            kind,
+            param_name,
+        }
+    }
+
     /// Render a single service file with all its operations.
     /// This generates a TypeScript service class with methods for each operation.
-    /// All custom types used in method signatures are collected and will be imported from '../types'.
-    fn render_service(&self, service: &ir::gen_ir::Service, ir: &GenIr) -> Result<String> {
+    /// All custom types used in method signatures are collected and imported
+    /// from the types module; they are returned along with the file.
+    fn render_service(
+        &self,
+        service: &ir::gen_ir::Service,
+        ir: &GenIr,
+        runtime: Runtime,
+        codecs: &Codecs,
+        imports: &ServiceImports,
+        templates: &TemplateOverrides,
+    ) -> Result<(String, Vec<String>)> {
         // Collect all custom types referenced by this service's operations
         // These will be imported at the top of the service file
         let mut type_imports = std::collections::HashSet::new();
+        let mut codec_imports = std::collections::BTreeSet::new();
 
-        let operations: Vec<OperationData> = service
+        let mut operations: Vec<OperationData> = service
             .operations
             .iter()
-            .map(|op| self.render_operation(op, ir, &mut type_imports))
+            .map(|op| {
+                let mut codec = |ty: &ir::gen_ir::TypeRef, expr: &str, decode: bool| {
+                    let call = Self::codec_call(ty, codecs, expr, decode)?;
+                    codec_imports.insert(Self::codec_function(&codecs[&ty.target], decode));
+                    Some(call)
+                };
+                self.render_operation(
+                    op,
+                    ir,
+                    &service.name,
+                    runtime,
+                    &mut type_imports,
+                    &mut codec,
+                )
+                .in_operation(op)
+            })
             .collect::<Result<Vec<_>>>()?;
 
+        // A former name taken by another method can't be kept as an alias,
+        // and a name claimed twice is only emitted for its first claimant
+        let mut members: std::collections::HashSet<String> = operations
+            .iter()
+            .flat_map(|op| {
+                std::iter::once(op.method_name.clone())
+                    .chain(op.cache.as_ref().map(|cache| cache.invalidate.clone()))
+            })
+            .collect();
+        for op in &mut operations {
+            op.aliases.retain(|alias| members.insert(alias.clone()));
+        }
+
         // Sort type imports for deterministic output
         let mut type_imports_vec: Vec<String> = type_imports.into_iter().collect();
         type_imports_vec.sort();
@@ -307,11 +978,57 @@ impl TypeScriptGenerator {
         let data = ServiceTemplate {
             name: &service.name,
             docs: &service.docs,
-            type_imports: type_imports_vec,
+            type_imports: type_imports_vec.clone(),
+            codec_imports: codec_imports.into_iter().collect(),
+            imports,
             operations,
             auth_schemes: &ir.auth_schemes,
+            has_feature_flags: !ir.feature_flags().is_empty(),
+            has_cache: runtime.cache,
+            has_offline_queue: runtime.offline_queue,
+            has_dedupe: runtime.dedupe,
         };
-        data.render().map_err(|e| Error::TemplateError(Box::new(e)))
+        let content = render(templates, "service.ts.jinja", &data)?;
+        Ok((content, type_imports_vec))
+    }
+
+    /// Text for the `@deprecated` JSDoc tag, e.g.
+    /// "Since v2. Removed after 2025-01-01. Use {@link listPetsV2} instead."
+    fn render_deprecation(docs: &ir::gen_ir::Docs) -> Option<String> {
+        if !docs.deprecated {
+            return None;
+        }
+        let mut parts = Vec::new();
+        if let Some(since) = &docs.since {
+            parts.push(format!("Since {}.", since));
+        }
+        if let Some(sunset) = &docs.sunset {
+            parts.push(format!("Removed after {}.", sunset));
+        }
+        if let Some(replacement) = &docs.replacement {
+            parts.push(format!("Use {{@link {}}} instead.", replacement.camel));
+        }
+        Some(parts.join(" "))
+    }
+
+    /// Expression serializing a header parameter value.
+    /// JSON `content` parameters are stringified, arrays use the `simple` style
+    /// (comma-separated), everything else goes through `String()`.
+    fn render_header_value(&self, param: &ir::gen_ir::HeaderParam) -> String {
+        let accessor = format!("params.{}", param.name.camel);
+        let is_json = param
+            .content_type
+            .as_deref()
+            .is_some_and(|ct| ct.contains("json"));
+        let is_list = matches!(param.ty.modifiers.last(), Some(ir::gen_ir::TypeMod::List));
+
+        if is_json {
+            format!("JSON.stringify({})", accessor)
+        } else if is_list {
+            format!("{}.map(String).join(',')", accessor)
+        } else {
+            format!("String({})", accessor)
+        }
     }
 
     /// Render operation data for template rendering.
@@ -322,7 +1039,10 @@ impl TypeScriptGenerator {
         &self,
         op: &ir::gen_ir::Operation,
         ir: &GenIr,
+        service_name: &ir::gen_ir::CanonicalName,
+        runtime: Runtime,
         type_imports: &mut std::collections::HashSet<String>,
+        codec: &mut dyn FnMut(&ir::gen_ir::TypeRef, &str, bool) -> Option<String>,
     ) -> Result<OperationData> {
         let mut params = Vec::new();
         let mut path_params = Vec::new();
@@ -342,6 +1062,7 @@ impl TypeScriptGenerator {
             });
             path_params.push(PathParamData {
                 name: param.name.camel.clone(),
+                type_str: type_str.clone(),
                 placeholder: format!("{{{}}}", param.wire),
             });
         }
@@ -375,13 +1096,19 @@ impl TypeScriptGenerator {
             header_params.push(HeaderParamData {
                 name: param.name.camel.clone(),
                 wire: param.wire.clone(),
+                optional: !param.required,
+                value: self.render_header_value(param),
             });
         }
 
         // Request body
+        let mut body_json = "params.body".to_string();
         if let Some(body) = &op.http.body
             && let Some(variant) = body.variants.first()
         {
+            if let Some(encoded) = codec(&variant.ty, "params.body", false) {
+                body_json = encoded;
+            }
             let type_str = self.render_type_ref(&variant.ty, ir);
             self.collect_type_imports(&variant.ty, ir, type_imports);
             params.push(ParamData {
@@ -392,9 +1119,32 @@ impl TypeScriptGenerator {
             });
         }
 
-        // Response type
+        // Response type, and how a response body is decoded
+        let response_decode = op
+            .success
+            .as_ref()
+            .filter(|_| op.alt_success.is_empty())
+            .and_then(|success| success.ty.as_ref())
+            .and_then(|ty| codec(ty, "await response.json()", true));
+        let success_decoders: Vec<StatusDecoderData> = op
+            .success
+            .iter()
+            .filter(|_| !op.alt_success.is_empty())
+            .chain(&op.alt_success)
+            .filter_map(|payload| {
+                let ir::gen_ir::StatusSpec::Code(status) = payload.status else {
+                    return None;
+                };
+                Some(StatusDecoderData {
+                    status,
+                    decode: codec(payload.ty.as_ref()?, "data", true)?,
+                })
+            })
+            .collect();
         let return_type = if let Some(success) = &op.success {
-            if let Some(ty) = &success.ty {
+            if !op.alt_success.is_empty() {
+                self.render_success_union(success, &op.alt_success, ir, type_imports)
+            } else if let Some(ty) = &success.ty {
                 self.collect_type_imports(ty, ir, type_imports);
                 self.render_type_ref(ty, ir)
             } else {
@@ -403,6 +1153,21 @@ impl TypeScriptGenerator {
         } else {
             "void".to_string()
         };
+        // Statuses of a success union without a body, to skip parsing
+        let success_union = op
+            .success
+            .as_ref()
+            .filter(|_| !op.alt_success.is_empty())
+            .map(|success| {
+                std::iter::once(success)
+                    .chain(&op.alt_success)
+                    .filter(|payload| payload.ty.is_none())
+                    .filter_map(|payload| match payload.status {
+                        ir::gen_ir::StatusSpec::Code(code) => Some(code),
+                        _ => None,
+                    })
+                    .collect::<Vec<u16>>()
+            });
 
         let http_method = match op.http.method {
             ir::gen_ir::HttpMethod::Get => "GET",
@@ -426,34 +1191,7 @@ impl TypeScriptGenerator {
                 ir.auth_schemes
                     .iter()
                     .find(|scheme| scheme.id == auth_use.scheme)
-                    .map(|scheme| {
-                        let (kind, param_name) = match &scheme.kind {
-                            ir::gen_ir::AuthKind::Http {
-                                scheme: http_scheme,
-                                ..
-                            } => (format!("bearer_{}", http_scheme), None),
-                            ir::gen_ir::AuthKind::ApiKey {
-                                location,
-                                param_name,
-                            } => {
-                                let kind = match location {
-                                    ir::gen_ir::ApiKeyLocation::Header => "apikey_header",
-                                    ir::gen_ir::ApiKeyLocation::Query => "apikey_query",
-                                    ir::gen_ir::ApiKeyLocation::Cookie => "apikey_cookie",
-                                };
-                                (kind.to_string(), Some(param_name.clone()))
-                            }
-                            ir::gen_ir::AuthKind::OAuth2 { .. } => ("oauth2".to_string(), None),
-                            ir::gen_ir::AuthKind::OpenIdConnect { .. } => {
-                                ("openid".to_string(), None)
-                            }
-                        };
-                        AuthSchemeUse {
-                            name_camel: scheme.name.camel.clone(),
-                            kind,
-                            param_name,
-                        }
-                    })
+                    .map(Self::auth_scheme_use)
             })
             .collect();
 
@@ -476,12 +1214,16 @@ impl TypeScriptGenerator {
                         } else {
                             (false, "void".to_string())
                         };
+                        let body_decode =
+                            v.ty.as_ref()
+                                .and_then(|ty| codec(ty, "await response.json()", true));
 
                         Some(ErrorVariantData {
                             class_name: format!("{}{}Error", op.name.pascal, v.name.pascal),
                             status_code,
                             has_body,
                             body_type,
+                            body_decode,
                         })
                     })
                     .collect()
@@ -490,17 +1232,52 @@ impl TypeScriptGenerator {
         };
         let has_errors = !error_variants.is_empty();
 
+        // Only results are cached, so operations without one never are
+        let cache = match &op.cache {
+            Some(hint) if runtime.cache && return_type != "void" && success_union.is_none() => {
+                Some(CacheData {
+                    key: format!("{}.{}", service_name.pascal, op.name.camel),
+                    ttl: hint.max_age,
+                    invalidate: format!("invalidate{}", op.name.pascal),
+                })
+            }
+            _ => None,
+        };
+
         Ok(OperationData {
             method_name: op.name.camel.clone(),
             docs: op.docs.clone(),
+            deprecation: Self::render_deprecation(&op.docs),
+            feature_flag: op.feature_flag.clone(),
+            aliases: op
+                .renamed_from
+                .iter()
+                .map(|name| name.camel.clone())
+                .filter(|name| *name != op.name.camel)
+                .collect(),
+            cache,
+            queueable: runtime.offline_queue && Self::is_queueable(op),
+            credential_headers: auth_schemes
+                .iter()
+                .filter_map(|auth| match auth.kind.as_str() {
+                    "bearer_bearer" | "bearer_basic" => Some("Authorization".to_string()),
+                    "apikey_header" => auth.param_name.clone(),
+                    _ => None,
+                })
+                .collect(),
+            dedupe: runtime.dedupe && op.http.method == ir::gen_ir::HttpMethod::Get,
             params,
             path_params,
             query_params,
             header_params,
             has_params,
             has_body,
+            body_json,
             has_return: return_type != "void",
             return_type,
+            response_decode,
+            success_union,
+            success_decoders,
             http_method: http_method.to_string(),
             path_template: op.http.path_template.clone(),
             auth_schemes,
@@ -509,6 +1286,35 @@ impl TypeScriptGenerator {
         })
     }
 
+    /// `{ status: 200; data: Pet } | { status: 201; data: PetCreated }` for an
+    /// operation with several success responses. Statuses without a body
+    /// have `data: undefined`.
+    fn render_success_union(
+        &self,
+        success: &ir::gen_ir::Payload,
+        alt_success: &[ir::gen_ir::Payload],
+        ir: &GenIr,
+        type_imports: &mut std::collections::HashSet<String>,
+    ) -> String {
+        std::iter::once(success)
+            .chain(alt_success)
+            .filter_map(|payload| {
+                let ir::gen_ir::StatusSpec::Code(code) = payload.status else {
+                    return None;
+                };
+                let data = match &payload.ty {
+                    Some(ty) => {
+                        self.collect_type_imports(ty, ir, type_imports);
+                        self.render_type_ref(ty, ir)
+                    }
+                    None => "undefined".to_string(),
+                };
+                Some(format!("{{ status: {}; data: {} }}", code, data))
+            })
+            .collect::<Vec<_>>()
+            .join(" | ")
+    }
+
     /// Collect type imports from a type reference.
     #[allow(clippy::only_used_in_recursion)]
     fn collect_type_imports(
@@ -531,7 +1337,7 @@ impl TypeScriptGenerator {
     }
 
     /// Generate package.json.
-    fn generate_package_json(&self, ir: &GenIr, vfs: &mut VirtualFS) -> Result<()> {
+    fn generate_package_json(&self, ir: &GenIr, sink: &mut dyn FileSink) -> Result<()> {
         let package_json = serde_json::json!({
             "name": ir.api.package_name.kebab.to_lowercase(),
             "version": ir.api.version,
@@ -548,26 +1354,50 @@ impl TypeScriptGenerator {
             }
         });
 
-        vfs.add_file(
-            "package.json",
-            serde_json::to_string_pretty(&package_json).unwrap(),
-        );
+        sink.write_text(
+            Path::new("package.json"),
+            &serde_json::to_string_pretty(&package_json).unwrap(),
+        )?;
+
+        Ok(())
+    }
+
+    /// Generate a CODEOWNERS fragment for service files with `x-owner` annotations.
+    fn generate_codeowners(
+        &self,
+        ir: &GenIr,
+        layout: &dyn Layout,
+        sink: &mut dyn FileSink,
+    ) -> Result<()> {
+        let entries: Vec<(PathBuf, Vec<String>)> = ir
+            .services
+            .iter()
+            .map(|service| {
+                let file = Self::ts_file(&layout.service_module(service));
+                (file, service.all_owners())
+            })
+            .collect();
+
+        if let Some(content) = codeowners(&entries) {
+            sink.write_text(Path::new("CODEOWNERS"), &content)?;
+        }
 
         Ok(())
     }
 
-    fn generate_gitignore(&self, vfs: &mut VirtualFS) -> Result<()> {
-        let gitignore = GitignoreTemplate;
-        let gitignore_content = gitignore
-            .render()
-            .map_err(|e| Error::TemplateError(Box::new(e)))?;
-        vfs.add_file(PathBuf::from(".gitignore"), gitignore_content);
+    fn generate_gitignore(
+        &self,
+        templates: &TemplateOverrides,
+        sink: &mut dyn FileSink,
+    ) -> Result<()> {
+        let gitignore_content = render(templates, ".gitignore.jinja", &GitignoreTemplate)?;
+        sink.write_text(Path::new(".gitignore"), &gitignore_content)?;
 
         Ok(())
     }
 
     /// Generate TypeScript configuration.
-    fn generate_tsconfig(&self, vfs: &mut VirtualFS) -> Result<()> {
+    fn generate_tsconfig(&self, sink: &mut dyn FileSink) -> Result<()> {
         let tsconfig = serde_json::json!({
             "compilerOptions": {
                 "target": "ES2022",
@@ -586,31 +1416,64 @@ impl TypeScriptGenerator {
             "types": ["@types/web"]
         });
 
-        vfs.add_file(
-            "tsconfig.json",
-            serde_json::to_string_pretty(&tsconfig).unwrap(),
-        );
+        sink.write_text(
+            Path::new("tsconfig.json"),
+            &serde_json::to_string_pretty(&tsconfig).unwrap(),
+        )?;
 
         Ok(())
     }
 
     /// Generate index.ts that exports everything.
-    fn generate_index(&self, ir: &GenIr, _config: &Config, vfs: &mut VirtualFS) -> Result<()> {
+    fn generate_index(
+        &self,
+        ir: &GenIr,
+        _config: &Config,
+        layout: &dyn Layout,
+        templates: &TemplateOverrides,
+        sink: &mut dyn FileSink,
+    ) -> Result<()> {
+        let index = PathBuf::from("src").join("index");
+        let import = |to: &Path| Self::import_path(&index, to);
         let services: Vec<ServiceExportData> = ir
             .services
             .iter()
             .map(|s| ServiceExportData {
                 name: s.name.pascal.clone(),
-                file: s.name.snake.clone(),
+                file: import(&layout.service_module(s)),
             })
             .collect();
 
-        let data = SdkExportTemplate { services };
-        let index_content = data
-            .render()
-            .map_err(|e| Error::TemplateError(Box::new(e)))?;
+        let data = SdkExportTemplate {
+            types: import(&layout.types_module()),
+            errors: import(&layout.types_dir().join("errors")),
+            client: import(&layout.runtime_dir().join("client")),
+            services,
+        };
+        let index_content = render(templates, "sdk_export.ts.jinja", &data)?;
 
-        vfs.add_file(PathBuf::from("src").join("index.ts"), index_content);
+        sink.write_text(&Self::ts_file(&index), &index_content)?;
+
+        Ok(())
+    }
+
+    /// Generate `src/meta.ts` describing the contract the SDK was built from.
+    fn generate_meta(
+        &self,
+        ir: &GenIr,
+        templates: &TemplateOverrides,
+        sink: &mut dyn FileSink,
+    ) -> Result<()> {
+        let literal = |value: &str| serde_json::Value::from(value).to_string();
+        let data = MetaTemplate {
+            title: literal(&ir.api.title),
+            version: literal(&ir.api.version),
+            checksum: literal(&ir.api.spec_hash),
+            generator: literal(codegen::GENERATOR_VERSION),
+            operation_count: ir.operation_count(),
+        };
+        let content = render(templates, "meta.ts.jinja", &data)?;
+        sink.write_text(&PathBuf::from("src").join("meta.ts"), &content)?;
 
         Ok(())
     }
@@ -619,27 +1482,69 @@ impl TypeScriptGenerator {
 impl Generator for TypeScriptGenerator {
     fn generate(&self, ir: &GenIr, config: &Config) -> Result<VirtualFS> {
         let mut vfs = VirtualFS::new();
+        self.generate_streaming(ir, config, &mut vfs)?;
+        Ok(vfs)
+    }
 
-        // Generate types
-        self.generate_types(ir, config, &mut vfs)?;
+    fn generate_streaming(
+        &self,
+        ir: &GenIr,
+        config: &Config,
+        sink: &mut dyn FileSink,
+    ) -> Result<()> {
+        let layout = config.layout()?;
+        let options: TypeScriptOptions = config.lang_options_as()?;
+        let templates = &TemplateOverrides::from_config(config)?;
+
+        let escaped;
+        let ir = match Self::escape_type_names(ir, &config.naming) {
+            Some(renamed) => {
+                escaped = renamed;
+                &escaped
+            }
+            None => ir,
+        };
 
-        // Generate services
-        self.generate_services(ir, config, &mut vfs)?;
+        let codecs = Self::codec_types(ir, config);
+
+        // Generate types
+        self.generate_types(
+            ir,
+            config,
+            &options,
+            &codecs,
+            layout.as_ref(),
+            templates,
+            sink,
+        )?;
+
+        // Generate services (one file per service, emitted as each is rendered)
+        self.generate_services(ir, &options, &codecs, layout.as_ref(), templates, sink)?;
 
         // Generate configuration files
-        self.generate_package_json(ir, &mut vfs)?;
-        self.generate_tsconfig(&mut vfs)?;
-        self.generate_gitignore(&mut vfs)?;
+        self.generate_package_json(ir, sink)?;
+        self.generate_tsconfig(sink)?;
+        self.generate_gitignore(templates, sink)?;
+        self.generate_codeowners(ir, layout.as_ref(), sink)?;
+        self.generate_meta(ir, templates, sink)?;
         // Generate index
-        self.generate_index(ir, config, &mut vfs)?;
+        self.generate_index(ir, config, layout.as_ref(), templates, sink)?;
 
-        Ok(vfs)
+        Ok(())
     }
 
     fn language(&self) -> &str {
         "typescript"
     }
 
+    fn version(&self) -> &str {
+        env!("CARGO_PKG_VERSION")
+    }
+
+    fn description(&self) -> &str {
+        "TypeScript client SDK using fetch"
+    }
+
     fn validate(&self, ir: &GenIr) -> Result<()> {
         if ir.types.is_empty() && ir.services.is_empty() {
             return Err(Error::ValidationError(
@@ -649,32 +1554,36 @@ impl Generator for TypeScriptGenerator {
         Ok(())
     }
 
-    fn after_write_to_disk(&self, output_dir: &Path, _vfs: &VirtualFS) -> Result<()> {
-        use std::process::Command;
-
-        let status = Command::new("npm")
-            .arg("install")
-            .current_dir(output_dir)
-            .status()
-            .map_err(|e| Error::Custom(format!("Failed to run npm install: {}", e)))?;
-
-        if !status.success() {
-            return Err(Error::Custom("npm install failed".to_string()));
-        }
-
-        // Run tsc
-        let status = Command::new("npm")
-            .arg("run")
-            .arg("build")
-            .current_dir(output_dir)
-            .status()
-            .map_err(|e| Error::Custom(format!("Failed to run tsc: {}", e)))?;
+    fn options_schema(&self) -> Option<serde_json::Value> {
+        Some(options::schema())
+    }
 
-        if !status.success() {
-            return Err(Error::Custom("npm run build failed".to_string()));
-        }
+    fn post_processors(&self) -> Vec<Box<dyn PostProcessor>> {
+        // Install dependencies, then type-check and compile with tsc
+        vec![
+            Box::new(
+                CommandProcessor::new("npm")
+                    .with_name("npm install")
+                    .with_args(["install"])
+                    .without_files(),
+            ),
+            Box::new(
+                CommandProcessor::new("npm")
+                    .with_name("npm run build")
+                    .with_args(["run", "build"])
+                    .without_files(),
+            ),
+        ]
+    }
 
-        Ok(())
+    fn verifier(&self) -> Option<Box<dyn Verifier>> {
+        // `--no` keeps npx from downloading tsc when it isn't installed
+        Some(Box::new(
+            CommandVerifier::new("npx")
+                .with_name("tsc")
+                .with_args(["--no", "tsc", "--noEmit", "--pretty", "false"])
+                .with_missing_marker("could not determine executable to run"),
+        ))
     }
 }
 
@@ -686,22 +1595,26 @@ impl Default for TypeScriptGenerator {
 
 // Template data structures
 
-#[derive(Template)]
+#[derive(Template, Serialize)]
 #[template(path = "interface.ts.jinja", escape = "none")]
 struct InterfaceTemplate<'a> {
     name: &'a ir::gen_ir::CanonicalName,
     docs: &'a ir::gen_ir::Docs,
     fields: Vec<FieldData<'a>>,
+    /// Emit a `<name>ToJSONSafe` helper redacting the sensitive fields
+    has_sensitive_fields: bool,
 }
 
+#[derive(Serialize)]
 struct FieldData<'a> {
     name: &'a str,
     optional: bool,
     type_str: String,
     docs: &'a ir::gen_ir::Docs,
+    sensitive: bool,
 }
 
-#[derive(Template)]
+#[derive(Template, Serialize)]
 #[template(path = "enum.ts.jinja", escape = "none")]
 struct EnumTemplate<'a> {
     name: &'a ir::gen_ir::CanonicalName,
@@ -709,12 +1622,13 @@ struct EnumTemplate<'a> {
     values: Vec<EnumValueData>,
 }
 
+#[derive(Serialize)]
 struct EnumValueData {
     name: String,
     value: String,
 }
 
-#[derive(Template)]
+#[derive(Template, Serialize)]
 #[template(path = "type_alias.ts.jinja", escape = "none")]
 struct TypeAliasTemplate<'a> {
     name: &'a ir::gen_ir::CanonicalName,
@@ -722,35 +1636,174 @@ struct TypeAliasTemplate<'a> {
     target: String,
 }
 
-#[derive(Template)]
+#[derive(Template, Serialize)]
 #[template(path = "errors.ts.jinja", escape = "none")]
 struct ErrorsTemplate;
 
-#[derive(Template)]
+#[derive(Template, Serialize)]
+#[template(path = "datetime.ts.jinja", escape = "none")]
+struct DateTimeTemplate;
+
+#[derive(Template, Serialize)]
+#[template(path = "number.ts.jinja", escape = "none")]
+struct NumberTemplate;
+
+/// Helper modules in the types directory that declarations call.
+#[derive(Debug, Clone, Copy, Default)]
+struct TypeHelpers {
+    /// `formatTime`/`parseTime` from `datetime`
+    time: bool,
+    /// `coerceNumber` from `number`
+    number: bool,
+}
+
+impl TypeHelpers {
+    /// Imports of the helpers from the module `from`, followed by a blank
+    /// line if there are any.
+    fn imports(self, from: &Path, types_dir: &Path) -> String {
+        let mut imports = String::new();
+        if self.time {
+            imports.push_str(&format!(
+                "import {{ formatTime, parseTime }} from '{}';\n",
+                TypeScriptGenerator::import_path(from, &types_dir.join("datetime"))
+            ));
+        }
+        if self.number {
+            imports.push_str(&format!(
+                "import {{ coerceNumber }} from '{}';\n",
+                TypeScriptGenerator::import_path(from, &types_dir.join("number"))
+            ));
+        }
+        if !imports.is_empty() {
+            imports.push('\n');
+        }
+        imports
+    }
+}
+
+#[derive(Template, Serialize)]
+#[template(path = "cache.ts.jinja", escape = "none")]
+struct CacheTemplate;
+
+#[derive(Template, Serialize)]
+#[template(path = "offline.ts.jinja", escape = "none")]
+struct OfflineTemplate;
+
+#[derive(Template, Serialize)]
+#[template(path = "inflight.ts.jinja", escape = "none")]
+struct InFlightTemplate;
+
+#[derive(Template, Serialize)]
+#[template(path = "json_codec.ts.jinja", escape = "none")]
+struct JsonCodecTemplate<'a> {
+    name: &'a ir::gen_ir::CanonicalName,
+    fields: Vec<CodecFieldData>,
+}
+
+/// Struct types with a JSON codec, mapped to their camelCase name.
+type Codecs = std::collections::BTreeMap<ir::gen_ir::StableId, String>;
+
+#[derive(Serialize)]
+struct CodecFieldData {
+    name: String,
+    /// Expression converting `json.<name>`
+    decode: String,
+    /// Expression converting `value.<name>` back, if it needs it
+    encode: Option<String>,
+}
+
+#[derive(Template, Serialize)]
+#[template(path = "validator.ts.jinja", escape = "none")]
+struct ValidatorTemplate<'a> {
+    name: &'a ir::gen_ir::CanonicalName,
+    fields: Vec<ValidatorFieldData>,
+}
+
+#[derive(Serialize)]
+struct ValidatorFieldData {
+    name: String,
+    /// Format pattern, escaped for a regex literal
+    pattern: String,
+    /// Escaped for a single-quoted string
+    description: String,
+}
+
+#[derive(Template, Serialize)]
 #[template(path = ".gitignore.jinja", escape = "none")]
 struct GitignoreTemplate;
 
-#[derive(Template)]
+#[derive(Template, Serialize)]
 #[template(path = "service.ts.jinja", escape = "none")]
 struct ServiceTemplate<'a> {
     name: &'a ir::gen_ir::CanonicalName,
     docs: &'a ir::gen_ir::Docs,
     type_imports: Vec<String>,
+    /// `FromJSON`/`ToJSON` functions of the types this service converts
+    codec_imports: Vec<String>,
+    imports: &'a ServiceImports,
     operations: Vec<OperationData>,
     auth_schemes: &'a [ir::gen_ir::AuthScheme],
+    /// Whether any operation in the API is feature-flagged; services then take
+    /// the client's `FeatureFlagProvider`
+    has_feature_flags: bool,
+    /// Whether the SDK caches results; services then take its `ResponseCache`
+    has_cache: bool,
+    /// Whether the SDK has an offline queue; services then take its `OfflineQueue`
+    has_offline_queue: bool,
+    /// Whether identical in-flight GETs are coalesced; services then take the
+    /// client's `InFlightRequests`
+    has_dedupe: bool,
+}
+
+/// Import specifiers of the modules a service file uses, relative to it.
+#[derive(Serialize)]
+struct ServiceImports {
+    types: String,
+    errors: String,
+    client: String,
+    cache: String,
+    offline: String,
+    inflight: String,
 }
 
+/// Optional runtime helpers emitted next to the client.
+#[derive(Clone, Copy)]
+struct Runtime {
+    cache: bool,
+    offline_queue: bool,
+    dedupe: bool,
+}
+
+#[derive(Serialize)]
 struct OperationData {
     method_name: String,
     docs: ir::gen_ir::Docs,
+    deprecation: Option<String>, // text following the @deprecated JSDoc tag
+    feature_flag: Option<String>, // `x-feature-flag` checked before the request
+    aliases: Vec<String>,        // former names (`x-renamed-from`), emitted as deprecated delegates
+    cache: Option<CacheData>,
+    queueable: bool, // queued on network errors when the SDK has an offline queue
+    /// Headers holding credentials, kept out of queued requests
+    credential_headers: Vec<String>,
+    dedupe: bool, // coalesced with identical in-flight GETs unless the call opts out
     params: Vec<ParamData>,
     path_params: Vec<PathParamData>,
     query_params: Vec<QueryParamData>,
     header_params: Vec<HeaderParamData>,
     has_params: bool,
     has_body: bool,
+    /// Request body as sent, e.g. `petToJSON(params.body)`
+    body_json: String,
     has_return: bool,
     return_type: String,
+    /// Decoded response body, e.g. `petFromJSON(await response.json())`, when
+    /// the result type has a JSON codec
+    response_decode: Option<String>,
+    /// Set when the operation returns a `{ status, data }` union: the
+    /// statuses without a body.
+    success_union: Option<Vec<u16>>,
+    /// Statuses of a success union whose `data` has a JSON codec
+    success_decoders: Vec<StatusDecoderData>,
     http_method: String,
     path_template: String,
     auth_schemes: Vec<AuthSchemeUse>,
@@ -758,19 +1811,38 @@ struct OperationData {
     has_errors: bool,
 }
 
+#[derive(Serialize)]
+struct CacheData {
+    key: String,        // e.g., "Pets.listPets", prefixing the path and query
+    ttl: Option<u64>,   // documented `max-age`, else the client's default TTL
+    invalidate: String, // e.g., "invalidateListPets"
+}
+
+#[derive(Serialize)]
 struct ErrorVariantData {
     class_name: String, // e.g., "ListPetsNotFoundError"
     status_code: u16,   // e.g., 404
     has_body: bool,     // whether error has a typed body
     body_type: String,  // e.g., "Error" or "void"
+    /// Decoded body when its type has a JSON codec
+    body_decode: Option<String>,
+}
+
+#[derive(Serialize)]
+struct StatusDecoderData {
+    status: u16,
+    /// Expression decoding `data`
+    decode: String,
 }
 
+#[derive(Serialize)]
 struct AuthSchemeUse {
     name_camel: String,
     kind: String, // "bearer", "apikey_header", "apikey_query"
     param_name: Option<String>,
 }
 
+#[derive(Serialize)]
 struct ParamData {
     name: String,
     type_str: String,
@@ -778,22 +1850,29 @@ struct ParamData {
     docs: Option<String>,
 }
 
+#[derive(Serialize)]
 struct PathParamData {
     name: String,
+    type_str: String,
     placeholder: String,
 }
 
+#[derive(Serialize)]
 struct QueryParamData {
     name: String,
     wire: String,
 }
 
+#[derive(Serialize)]
 struct HeaderParamData {
     name: String,
     wire: String,
+    optional: bool,
+    /// Expression that serializes the parameter to a header string
+    value: String,
 }
 
-#[derive(Template)]
+#[derive(Template, Serialize)]
 #[template(path = "client.ts.jinja", escape = "none")]
 struct ClientTemplate<'a> {
     api: &'a ir::gen_ir::ApiMeta,
@@ -801,21 +1880,52 @@ struct ClientTemplate<'a> {
     auth_schemes: &'a [ir::gen_ir::AuthScheme],
     default_base_url: String,
     service_imports: Vec<ServiceImportData>,
+    has_feature_flags: bool,
+    has_cache: bool,
+    has_offline_queue: bool,
+    has_dedupe: bool,
+    /// Generate the untyped `request` method (`raw_request` lang option)
+    raw_request: bool,
+    /// Generate `credentialHeaders`, used by `request` and offline replays
+    has_credential_headers: bool,
+    /// Every scheme the SDK is configured with
+    credentials: Vec<AuthSchemeUse>,
+    /// Import specifier of the error classes
+    errors_import: String,
 }
 
+#[derive(Serialize)]
 struct ServiceImportData {
     name: String,
+    /// Import specifier of the service module
     file: String,
 }
 
-#[derive(Template)]
+#[derive(Template, Serialize)]
+#[template(path = "meta.ts.jinja", escape = "none")]
+struct MetaTemplate {
+    // String fields are already JS string literals
+    title: String,
+    version: String,
+    checksum: String,
+    generator: String,
+    operation_count: usize,
+}
+
+#[derive(Template, Serialize)]
 #[template(path = "sdk_export.ts.jinja", escape = "none")]
 struct SdkExportTemplate {
+    // Import specifiers relative to `src/index`
+    types: String,
+    errors: String,
+    client: String,
     services: Vec<ServiceExportData>,
 }
 
+#[derive(Serialize)]
 struct ServiceExportData {
     name: String,
+    /// Import specifier of the service module
     file: String,
 }
 
@@ -840,6 +1950,9 @@ mod tests {
             since: None,
             examples: Vec::new(),
             external_urls: Vec::new(),
+            sunset: None,
+            replacement: None,
+            translations: BTreeMap::new(),
         };
 
         // Create a field with documentation
@@ -852,6 +1965,9 @@ mod tests {
                 since: None,
                 examples: Vec::new(),
                 external_urls: Vec::new(),
+                sunset: None,
+                replacement: None,
+                translations: BTreeMap::new(),
             },
             ty: TypeRef {
                 target: StableId::primitive(Primitive::String),
@@ -864,6 +1980,10 @@ mod tests {
             deprecated: false,
             const_value: None,
             wire_name: "testField".to_string(),
+            sensitive: false,
+            time_format: None,
+            coerce_number: false,
+            format: None,
         };
 
         let type_decl = TypeDecl {
@@ -885,15 +2005,25 @@ mod tests {
                 version: "1.0.0".to_string(),
                 package_name: CanonicalName::from_string("test-api"),
                 docs: Docs::default(),
+                spec_hash: String::new(),
             },
             types: BTreeMap::new(),
             services: Vec::new(),
             auth_schemes: Vec::new(),
             errors: Vec::new(),
             server_sets: Vec::new(),
+            webhooks: Vec::new(),
+            fallbacks: Vec::new(),
         };
 
-        let result = generator.render_type(&type_decl, &ir).unwrap();
+        let result = generator
+            .render_type(
+                &type_decl,
+                &ir,
+                &Config::default(),
+                &TemplateOverrides::default(),
+            )
+            .unwrap();
 
         // Verify the output contains JSDoc comments for the type
         assert!(result.contains("/**"), "Should contain JSDoc opening");
@@ -947,6 +2077,10 @@ mod tests {
             deprecated: false,
             const_value: None,
             wire_name: "simpleField".to_string(),
+            sensitive: false,
+            time_format: None,
+            coerce_number: false,
+            format: None,
         };
 
         let type_decl = TypeDecl {
@@ -968,15 +2102,25 @@ mod tests {
                 version: "1.0.0".to_string(),
                 package_name: CanonicalName::from_string("test-api"),
                 docs: Docs::default(),
+                spec_hash: String::new(),
             },
             types: BTreeMap::new(),
             services: Vec::new(),
             auth_schemes: Vec::new(),
             errors: Vec::new(),
             server_sets: Vec::new(),
+            webhooks: Vec::new(),
+            fallbacks: Vec::new(),
         };
 
-        let result = generator.render_type(&type_decl, &ir).unwrap();
+        let result = generator
+            .render_type(
+                &type_decl,
+                &ir,
+                &Config::default(),
+                &TemplateOverrides::default(),
+            )
+            .unwrap();
 
         // Should not have any JSDoc comments since there are no descriptions
         assert!(
@@ -1012,6 +2156,9 @@ mod tests {
                     since: None,
                     examples: Vec::new(),
                     external_urls: Vec::new(),
+                    sunset: None,
+                    replacement: None,
+                    translations: BTreeMap::new(),
                 },
                 ty: TypeRef {
                     target: StableId::primitive(Primitive::String),
@@ -1024,6 +2171,10 @@ mod tests {
                 deprecated: false,
                 const_value: Some(ir::gen_ir::Literal::String("pet".to_string())),
                 wire_name: "type".to_string(),
+                sensitive: false,
+                time_format: None,
+                coerce_number: false,
+                format: None,
             },
             Field {
                 name: CanonicalName::from_string("name"),
@@ -1034,6 +2185,9 @@ mod tests {
                     since: None,
                     examples: Vec::new(),
                     external_urls: Vec::new(),
+                    sunset: None,
+                    replacement: None,
+                    translations: BTreeMap::new(),
                 },
                 ty: TypeRef {
                     target: StableId::primitive(Primitive::String),
@@ -1046,6 +2200,10 @@ mod tests {
                 deprecated: false,
                 const_value: None,
                 wire_name: "name".to_string(),
+                sensitive: false,
+                time_format: None,
+                coerce_number: false,
+                format: None,
             },
             Field {
                 name: CanonicalName::from_string("active"),
@@ -1061,6 +2219,10 @@ mod tests {
                 deprecated: false,
                 const_value: Some(ir::gen_ir::Literal::Bool(true)),
                 wire_name: "active".to_string(),
+                sensitive: false,
+                time_format: None,
+                coerce_number: false,
+                format: None,
             },
         ];
 
@@ -1083,15 +2245,25 @@ mod tests {
                 version: "1.0.0".to_string(),
                 package_name: CanonicalName::from_string("test-api"),
                 docs: Docs::default(),
+                spec_hash: String::new(),
             },
             types: BTreeMap::new(),
             services: Vec::new(),
             auth_schemes: Vec::new(),
             errors: Vec::new(),
             server_sets: Vec::new(),
+            webhooks: Vec::new(),
+            fallbacks: Vec::new(),
         };
 
-        let result = generator.render_type(&type_decl, &ir).unwrap();
+        let result = generator
+            .render_type(
+                &type_decl,
+                &ir,
+                &Config::default(),
+                &TemplateOverrides::default(),
+            )
+            .unwrap();
 
         // Verify the interface structure
         assert!(
@@ -1118,4 +2290,270 @@ mod tests {
             result
         );
     }
+
+    #[test]
+    fn test_interface_with_sensitive_fields() {
+        let generator = TypeScriptGenerator::new();
+
+        let field = |name: &str, sensitive: bool| Field {
+            name: CanonicalName::from_string(name),
+            docs: Docs::default(),
+            ty: TypeRef {
+                target: StableId::primitive(Primitive::String),
+                optional: false,
+                nullable: false,
+                by_ref: false,
+                modifiers: Vec::new(),
+            },
+            default: None,
+            deprecated: false,
+            const_value: None,
+            wire_name: name.to_string(),
+            sensitive,
+            time_format: None,
+            coerce_number: false,
+            format: None,
+        };
+
+        let type_decl = TypeDecl {
+            id: StableId::new("Login"),
+            name: CanonicalName::from_string("Login"),
+            docs: Docs::default(),
+            kind: TypeKind::Struct {
+                fields: vec![field("username", false), field("password", true)],
+                additional: Additional::Forbidden,
+                discriminator: None,
+            },
+            origin: None,
+            tags: BTreeSet::new(),
+        };
+
+        let ir = GenIr {
+            api: ir::gen_ir::ApiMeta {
+                title: "Test API".to_string(),
+                version: "1.0.0".to_string(),
+                package_name: CanonicalName::from_string("test-api"),
+                docs: Docs::default(),
+                spec_hash: String::new(),
+            },
+            types: BTreeMap::new(),
+            services: Vec::new(),
+            auth_schemes: Vec::new(),
+            errors: Vec::new(),
+            server_sets: Vec::new(),
+            webhooks: Vec::new(),
+            fallbacks: Vec::new(),
+        };
+
+        let result = generator
+            .render_type(
+                &type_decl,
+                &ir,
+                &Config::default(),
+                &TemplateOverrides::default(),
+            )
+            .unwrap();
+
+        assert!(
+            result.contains(
+                "export function loginToJSONSafe(value: Login): Record<string, unknown> {"
+            )
+        );
+        assert!(
+            result.contains("password: value.password === undefined ? undefined : '[REDACTED]',")
+        );
+        assert!(!result.contains("username: value.username"));
+    }
+
+    #[test]
+    fn test_time_codec() {
+        let generator = TypeScriptGenerator::new();
+
+        let field = |name: &str, primitive: Primitive, time_format: Option<TimeFormat>| Field {
+            name: CanonicalName::from_string(name),
+            docs: Docs::default(),
+            ty: TypeRef {
+                target: StableId::primitive(primitive),
+                optional: true,
+                nullable: false,
+                by_ref: false,
+                modifiers: Vec::new(),
+            },
+            default: None,
+            deprecated: false,
+            const_value: None,
+            wire_name: name.to_string(),
+            sensitive: false,
+            time_format,
+            coerce_number: false,
+            format: None,
+        };
+
+        let type_decl = TypeDecl {
+            id: StableId::new("Event"),
+            name: CanonicalName::from_string("Event"),
+            docs: Docs::default(),
+            kind: TypeKind::Struct {
+                fields: vec![
+                    field("title", Primitive::String, None),
+                    field("at", Primitive::DateTime, None),
+                    field("created", Primitive::DateTime, Some(TimeFormat::UnixMillis)),
+                    field(
+                        "day",
+                        Primitive::Date,
+                        Some(TimeFormat::Pattern("%d/%m/%Y".to_string())),
+                    ),
+                ],
+                additional: Additional::Forbidden,
+                discriminator: None,
+            },
+            origin: None,
+            tags: BTreeSet::new(),
+        };
+
+        let mut config = Config::default();
+        config.time_formats.date_time = TimeFormat::UnixSeconds;
+        let result = generator
+            .render_json_codec(
+                &type_decl,
+                &config,
+                &Codecs::new(),
+                &TemplateOverrides::default(),
+            )
+            .unwrap()
+            .unwrap();
+
+        assert!(result.contains("export function eventFromJSON(json: any): Event {"));
+        assert!(result.contains("at: json.at == null ? json.at : parseTime(json.at, 'unix'),"));
+        assert!(result.contains("formatTime(value.created, 'unix-ms')"));
+        assert!(result.contains("parseTime(json.day, { pattern: '%d/%m/%Y' })"));
+
+        // The fields the codec converts are typed as what it decodes them to
+        let ir = GenIr {
+            api: ir::gen_ir::ApiMeta {
+                title: "Test API".to_string(),
+                version: "1.0.0".to_string(),
+                package_name: CanonicalName::from_string("test-api"),
+                docs: Docs::default(),
+                spec_hash: String::new(),
+            },
+            types: BTreeMap::new(),
+            services: Vec::new(),
+            auth_schemes: Vec::new(),
+            errors: Vec::new(),
+            server_sets: Vec::new(),
+            webhooks: Vec::new(),
+            fallbacks: Vec::new(),
+        };
+        let interface = generator
+            .render_type(&type_decl, &ir, &config, &TemplateOverrides::default())
+            .unwrap();
+        assert!(interface.contains("  at?: Date;"));
+        assert!(interface.contains("  day?: Date;"));
+        assert!(interface.contains("  title?: string;"));
+        assert!(!result.contains("title"));
+    }
+
+    #[test]
+    fn test_number_coercion_codec() {
+        let generator = TypeScriptGenerator::new();
+
+        let field = |name: &str, primitive: Primitive, coerce_number: bool| Field {
+            name: CanonicalName::from_string(name),
+            docs: Docs::default(),
+            ty: TypeRef {
+                target: StableId::primitive(primitive),
+                optional: false,
+                nullable: false,
+                by_ref: false,
+                modifiers: Vec::new(),
+            },
+            default: None,
+            deprecated: false,
+            const_value: None,
+            wire_name: name.to_string(),
+            sensitive: false,
+            time_format: None,
+            coerce_number,
+            format: None,
+        };
+
+        let type_decl = TypeDecl {
+            id: StableId::new("Quote"),
+            name: CanonicalName::from_string("Quote"),
+            docs: Docs::default(),
+            kind: TypeKind::Struct {
+                fields: vec![
+                    field("price", Primitive::F64, true),
+                    field("volume", Primitive::I64, false),
+                    field("symbol", Primitive::String, true),
+                ],
+                additional: Additional::Forbidden,
+                discriminator: None,
+            },
+            origin: None,
+            tags: BTreeSet::new(),
+        };
+
+        let config = Config::default();
+        let result = generator
+            .render_json_codec(
+                &type_decl,
+                &config,
+                &Codecs::new(),
+                &TemplateOverrides::default(),
+            )
+            .unwrap()
+            .unwrap();
+        assert!(
+            result.contains("price: json.price == null ? json.price : coerceNumber(json.price),")
+        );
+        assert!(!result.contains("volume"));
+        assert!(!result.contains("symbol"));
+        assert!(!result.contains("value.price"));
+
+        let mut config = Config::default();
+        config.lang_options.insert(
+            Config::COERCE_NUMBERS.to_string(),
+            serde_json::Value::Bool(true),
+        );
+        let result = generator
+            .render_json_codec(
+                &type_decl,
+                &config,
+                &Codecs::new(),
+                &TemplateOverrides::default(),
+            )
+            .unwrap()
+            .unwrap();
+        assert!(result.contains("coerceNumber(json.volume)"));
+        assert!(!result.contains("symbol"));
+    }
+
+    #[test]
+    fn test_options_schema() {
+        let generator = TypeScriptGenerator::new();
+        let schema = generator.options_schema().unwrap();
+        let mut config = Config::default();
+        config
+            .lang_options
+            .insert("layout".to_string(), serde_json::json!("flat"));
+        config
+            .lang_options
+            .insert("cache".to_string(), serde_json::Value::Bool(false));
+        assert!(codegen::options::validate("typescript", &schema, &config.lang_options).is_ok());
+        let options: TypeScriptOptions = config.lang_options_as().unwrap();
+        assert_eq!(options.layout, "flat");
+        assert!(!options.cache);
+        assert!(options.raw_request);
+
+        config
+            .lang_options
+            .insert("dedupe".to_string(), serde_json::Value::Bool(false));
+        let err = codegen::options::validate("typescript", &schema, &config.lang_options)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("unknown option `dedupe`"));
+        assert!(config.lang_options_as::<TypeScriptOptions>().is_err());
+    }
 }
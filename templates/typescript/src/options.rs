@@ -0,0 +1,99 @@
+//! `lang_options` understood by the TypeScript generator.
+
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+/// Options read from `Config::lang_options`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TypeScriptOptions {
+    /// Output layout, read through `Config::layout`
+    pub layout: String,
+    /// All types in the layout's types module, or a module per type
+    pub type_files: TypeFiles,
+    /// Accept every number as a string too, read through
+    /// `Config::coerces_number`
+    pub coerce_numbers: bool,
+    /// Expose `client.raw.request` for calls the SDK doesn't cover
+    pub raw_request: bool,
+    /// Cache results of cacheable GET operations
+    pub cache: bool,
+    /// Queue idempotent mutations while offline (off by default)
+    pub offline_queue: bool,
+    /// Coalesce identical GET requests in flight (off by default)
+    pub dedupe_requests: bool,
+}
+
+impl Default for TypeScriptOptions {
+    fn default() -> Self {
+        Self {
+            layout: "standard".to_string(),
+            type_files: TypeFiles::Single,
+            coerce_numbers: false,
+            raw_request: true,
+            cache: true,
+            offline_queue: false,
+            dedupe_requests: false,
+        }
+    }
+}
+
+/// Where [`TypeScriptGenerator`](crate::TypeScriptGenerator) declares types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TypeFiles {
+    /// Every type in the layout's types module.
+    Single,
+    /// One module per type, importing the types it references, with the
+    /// types module re-exporting them all.
+    PerType,
+}
+
+/// JSON Schema of [`TypeScriptOptions`].
+pub fn schema() -> Value {
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "TypeScript generator options",
+        "type": "object",
+        "additionalProperties": false,
+        "properties": {
+            "layout": {
+                "type": "string",
+                "enum": ["standard", "flat", "by_tag", "domain"],
+                "default": "standard",
+                "description": "Where generated files go"
+            },
+            "type_files": {
+                "type": "string",
+                "enum": ["single", "per_type"],
+                "default": "single",
+                "description": "Declare all types in one module, or each in its own re-exported by it"
+            },
+            "coerce_numbers": {
+                "type": "boolean",
+                "default": false,
+                "description": "Accept every numeric field as a string too"
+            },
+            "raw_request": {
+                "type": "boolean",
+                "default": true,
+                "description": "Expose client.raw.request for untyped calls"
+            },
+            "cache": {
+                "type": "boolean",
+                "default": true,
+                "description": "Cache results of cacheable GET operations"
+            },
+            "offline_queue": {
+                "type": "boolean",
+                "default": false,
+                "description": "Queue idempotent mutations while offline"
+            },
+            "dedupe_requests": {
+                "type": "boolean",
+                "default": false,
+                "description": "Coalesce identical GET requests in flight"
+            }
+        }
+    })
+}